@@ -259,6 +259,14 @@ where
 			.map_err(|_| error::ErrorKind::Input("Invalid pruning mode specified".to_owned()))?),
 	};
 
+	if let Some(database) = matches.value_of("database") {
+		config.database = database.parse().map_err(error::ErrorKind::Input)?;
+	}
+
+	if let Some(sync) = matches.value_of("sync") {
+		config.sync_mode = sync.parse().map_err(error::ErrorKind::Input)?;
+	}
+
 	let role =
 		if matches.is_present("light") {
 			config.execution_strategy = service::ExecutionStrategy::NativeWhenPossible;
@@ -19,7 +19,7 @@
 use std::sync::Arc;
 use parking_lot::RwLock;
 
-use kvdb::{KeyValueDB, DBTransaction};
+use kvdb::DBTransaction;
 
 use client::blockchain::Cache as BlockchainCache;
 use client::error::Result as ClientResult;
@@ -27,11 +27,12 @@ use codec::{Codec, Encode, Decode};
 use primitives::AuthorityId;
 use runtime_primitives::generic::BlockId;
 use runtime_primitives::traits::{Block as BlockT, As, NumberFor};
+use database::Database;
 use utils::{COLUMN_META, BlockKey, db_err, meta_keys, read_id, db_key_to_number, number_to_db_key};
 
 /// Database-backed cache of blockchain data.
 pub struct DbCache<Block: BlockT> {
-	db: Arc<KeyValueDB>,
+	db: Arc<Database>,
 	block_index_column: Option<u32>,
 	authorities_at: DbCacheList<Block, Vec<AuthorityId>>,
 }
@@ -43,7 +44,7 @@ impl<Block> DbCache<Block>
 {
 	/// Create new cache.
 	pub fn new(
-		db: Arc<KeyValueDB>,
+		db: Arc<Database>,
 		block_index_column: Option<u32>,
 		authorities_column: Option<u32>
 	) -> ClientResult<Self> {
@@ -88,7 +89,7 @@ impl<Block> BlockchainCache<Block> for DbCache<Block>
 /// best entry here means the entry that is valid for the best block (and
 /// probably for its ascendants).
 pub struct DbCacheList<Block: BlockT, T: Clone> {
-	db: Arc<KeyValueDB>,
+	db: Arc<Database>,
 	meta_key: &'static [u8],
 	column: Option<u32>,
 	/// Best entry at the moment. None means that cache has no entries at all.
@@ -123,7 +124,7 @@ impl<Block, T> DbCacheList<Block, T>
 		T: Clone + PartialEq + Codec,
 {
 	/// Creates new cache list.
-	fn new(db: Arc<KeyValueDB>, meta_key: &'static [u8], column: Option<u32>) -> ClientResult<Self> {
+	fn new(db: Arc<Database>, meta_key: &'static [u8], column: Option<u32>) -> ClientResult<Self> {
 		let best_entry = RwLock::new(db.get(COLUMN_META, meta_key)
 			.map_err(db_err)
 			.and_then(|block| match block {
@@ -282,7 +283,7 @@ impl<Block, T> DbCacheList<Block, T>
 
 /// Reads the entry at the block with given number.
 fn read_storage_entry<Block, T>(
-	db: &KeyValueDB,
+	db: &Database,
 	column: Option<u32>,
 	number: NumberFor<Block>
 ) -> ClientResult<Option<StorageEntry<NumberFor<Block>, T>>>
@@ -0,0 +1,51 @@
+// Copyright 2017 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The storage the full and light backends are built on.
+
+use std::io;
+
+use hashdb::DBValue;
+use kvdb::{KeyValueDB, DBTransaction};
+
+/// A column-oriented key-value database.
+///
+/// Columns are addressed with `Option<u32>`, `None` being the default column. Changes are only
+/// made through transactions, which are applied atomically.
+pub trait Database: Send + Sync {
+	/// Get the value stored under `key` in `col`.
+	fn get(&self, col: Option<u32>, key: &[u8]) -> io::Result<Option<DBValue>>;
+
+	/// Iterate over all the entries of `col`, in key order.
+	fn iter<'a>(&'a self, col: Option<u32>) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a>;
+
+	/// Apply all the changes of `transaction`, or none of them.
+	fn write(&self, transaction: DBTransaction) -> io::Result<()>;
+}
+
+impl<T: KeyValueDB> Database for T {
+	fn get(&self, col: Option<u32>, key: &[u8]) -> io::Result<Option<DBValue>> {
+		KeyValueDB::get(self, col, key)
+	}
+
+	fn iter<'a>(&'a self, col: Option<u32>) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		KeyValueDB::iter(self, col)
+	}
+
+	fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+		KeyValueDB::write(self, transaction)
+	}
+}
@@ -15,11 +15,17 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 // tag::description[]
-//! Client backend that uses RocksDB database as storage.
+//! Client backend that uses a key-value database as storage.
 // end::description[]
+//!
+//! The backends are built on the `Database` trait, and the database is selected with
+//! `DatabaseSettings::backend`. RocksDB is only available with the `rocksdb` feature, which is
+//! enabled by default; the append-only `LogDb` is always available.
 
 extern crate substrate_client as client;
+#[cfg(feature = "rocksdb")]
 extern crate kvdb_rocksdb;
+extern crate kvdb_memorydb;
 extern crate kvdb;
 extern crate hashdb;
 extern crate memorydb;
@@ -38,20 +44,23 @@ extern crate log;
 extern crate parity_codec_derive;
 
 #[cfg(test)]
-extern crate kvdb_memorydb;
+extern crate tempdir;
 
 pub mod light;
 
 mod cache;
+mod database;
+mod log_db;
 mod utils;
 
 use std::sync::Arc;
 use std::path::PathBuf;
 use std::io;
+use std::str::FromStr;
 
 use codec::{Decode, Encode};
 use hashdb::Hasher;
-use kvdb::{KeyValueDB, DBTransaction};
+use kvdb::DBTransaction;
 use memorydb::MemoryDB;
 use parking_lot::RwLock;
 use primitives::{H256, AuthorityId, Blake2Hasher, RlpCodec};
@@ -66,12 +75,50 @@ use utils::{Meta, db_err, meta_keys, number_to_db_key, db_key_to_number, open_da
 	read_db, read_id, read_meta};
 use state_db::StateDb;
 pub use state_db::PruningMode;
+pub use database::Database;
+pub use log_db::LogDb;
 
 const FINALIZATION_WINDOW: u64 = 32;
 
 /// DB-backed patricia trie state, transaction type is an overlay of changes to commit.
 pub type DbState = state_machine::TrieBackend<Blake2Hasher, RlpCodec>;
 
+/// The key-value database the client is stored in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseBackend {
+	/// RocksDB at `DatabaseSettings::path`.
+	RocksDb,
+	/// A `LogDb` at `DatabaseSettings::path`, which appends all changes to a single file.
+	LogDb,
+	/// An in-memory database, lost when the node stops.
+	Memory,
+}
+
+impl Default for DatabaseBackend {
+	#[cfg(feature = "rocksdb")]
+	fn default() -> Self {
+		DatabaseBackend::RocksDb
+	}
+
+	#[cfg(not(feature = "rocksdb"))]
+	fn default() -> Self {
+		DatabaseBackend::LogDb
+	}
+}
+
+impl FromStr for DatabaseBackend {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, String> {
+		match s {
+			"rocksdb" => Ok(DatabaseBackend::RocksDb),
+			"logdb" => Ok(DatabaseBackend::LogDb),
+			"memory" => Ok(DatabaseBackend::Memory),
+			_ => Err(format!("Unknown database backend `{}`; expected `rocksdb`, `logdb` or `memory`", s)),
+		}
+	}
+}
+
 /// Database settings.
 pub struct DatabaseSettings {
 	/// Cache size in bytes. If `None` default is used.
@@ -80,6 +127,8 @@ pub struct DatabaseSettings {
 	pub path: PathBuf,
 	/// Pruning mode.
 	pub pruning: PruningMode,
+	/// The database to use.
+	pub backend: DatabaseBackend,
 }
 
 /// Create an instance of db-backed client.
@@ -107,6 +156,7 @@ mod columns {
 	pub const HEADER: Option<u32> = Some(4);
 	pub const BODY: Option<u32> = Some(5);
 	pub const JUSTIFICATION: Option<u32> = Some(6);
+	pub const OFFCHAIN: Option<u32> = Some(7);
 }
 
 struct PendingBlock<Block: BlockT> {
@@ -117,7 +167,7 @@ struct PendingBlock<Block: BlockT> {
 }
 
 // wrapper that implements trait required for state_db
-struct StateMetaDb<'a>(&'a KeyValueDB);
+struct StateMetaDb<'a>(&'a Database);
 
 impl<'a> state_db::MetaDb for StateMetaDb<'a> {
 	type Error = io::Error;
@@ -129,12 +179,12 @@ impl<'a> state_db::MetaDb for StateMetaDb<'a> {
 
 /// Block database
 pub struct BlockchainDb<Block: BlockT> {
-	db: Arc<KeyValueDB>,
+	db: Arc<Database>,
 	meta: RwLock<Meta<<Block::Header as HeaderT>::Number, Block::Hash>>,
 }
 
 impl<Block: BlockT> BlockchainDb<Block> {
-	fn new(db: Arc<KeyValueDB>) -> Result<Self, client::error::Error> {
+	fn new(db: Arc<Database>) -> Result<Self, client::error::Error> {
 		let meta = read_meta::<Block>(&*db, columns::HEADER)?;
 		Ok(BlockchainDb {
 			db,
@@ -230,6 +280,7 @@ impl<Block: BlockT> client::blockchain::Backend<Block> for BlockchainDb<Block> {
 pub struct BlockImportOperation<Block: BlockT, H: Hasher> {
 	old_state: DbState,
 	updates: MemoryDB<H>,
+	offchain_changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 	pending_block: Option<PendingBlock<Block>>,
 }
 
@@ -269,10 +320,15 @@ where Block: BlockT,
 		self.updates = update;
 		Ok(())
 	}
+
+	fn update_offchain_storage<I: Iterator<Item=(Vec<u8>, Option<Vec<u8>>)>>(&mut self, changes: I) -> Result<(), client::error::Error> {
+		self.offchain_changes.extend(changes);
+		Ok(())
+	}
 }
 
 struct StorageDb<Block: BlockT> {
-	pub db: Arc<KeyValueDB>,
+	pub db: Arc<Database>,
 	pub state_db: StateDb<Block::Hash, H256>,
 }
 
@@ -306,7 +362,7 @@ impl<Block: BlockT> Backend<Block> {
 	pub fn new(config: DatabaseSettings, finalization_window: u64) -> Result<Self, client::error::Error> {
 		let db = open_database(&config, "full")?;
 
-		Backend::from_kvdb(db as Arc<_>, config.pruning, finalization_window)
+		Backend::from_database(db as Arc<_>, config.pruning, finalization_window)
 	}
 
 	#[cfg(test)]
@@ -315,10 +371,10 @@ impl<Block: BlockT> Backend<Block> {
 
 		let db = Arc::new(::kvdb_memorydb::create(NUM_COLUMNS));
 
-		Backend::from_kvdb(db as Arc<_>, PruningMode::keep_blocks(keep_blocks), 0).expect("failed to create test-db")
+		Backend::from_database(db as Arc<_>, PruningMode::keep_blocks(keep_blocks), 0).expect("failed to create test-db")
 	}
 
-	fn from_kvdb(db: Arc<KeyValueDB>, pruning: PruningMode, finalization_window: u64) -> Result<Self, client::error::Error> {
+	fn from_database(db: Arc<Database>, pruning: PruningMode, finalization_window: u64) -> Result<Self, client::error::Error> {
 		let blockchain = BlockchainDb::new(db.clone())?;
 		let map_e = |e: state_db::Error<io::Error>| ::client::error::Error::from(format!("State database error: {:?}", e));
 		let state_db: StateDb<Block::Hash, H256> = StateDb::new(pruning, &StateMetaDb(&*db)).map_err(map_e)?;
@@ -361,6 +417,7 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher, RlpCodec> for Backend<
 			pending_block: None,
 			old_state: state,
 			updates: MemoryDB::default(),
+			offchain_changes: Vec::new(),
 		})
 	}
 
@@ -379,6 +436,12 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher, RlpCodec> for Backend<
 				transaction.put(columns::JUSTIFICATION, &key, &justification.encode());
 			}
 			transaction.put(columns::BLOCK_INDEX, hash.as_ref(), &key);
+			for (offchain_key, value) in operation.offchain_changes.drain(..) {
+				match value {
+					Some(value) => transaction.put(columns::OFFCHAIN, &offchain_key, &value),
+					None => transaction.delete(columns::OFFCHAIN, &offchain_key),
+				}
+			}
 			if pending_block.is_best {
 				transaction.put(columns::META, meta_keys::BEST_BLOCK, &key);
 			}
@@ -469,10 +532,15 @@ impl<Block> client::backend::Backend<Block, Blake2Hasher, RlpCodec> for Backend<
 				let root = H256::from_slice(hdr.state_root().as_ref());
 				Ok(DbState::with_storage(self.storage.clone(), root))
 			},
+			Ok(Some(_)) => Err(client::error::ErrorKind::StatePruned(format!("{:?}", block)).into()),
 			Err(e) => Err(e),
-			_ => Err(client::error::ErrorKind::UnknownBlock(format!("{:?}", block)).into()),
+			Ok(None) => Err(client::error::ErrorKind::UnknownBlock(format!("{:?}", block)).into()),
 		}
 	}
+
+	fn offchain_storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>, client::error::Error> {
+		self.storage.db.get(columns::OFFCHAIN, key).map(|r| r.map(|v| v.to_vec())).map_err(db_err)
+	}
 }
 
 impl<Block> client::backend::LocalBackend<Block, Blake2Hasher, RlpCodec> for Backend<Block>
@@ -528,6 +596,104 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn state_at_pruned_block_is_distinct_error() {
+		let db = Backend::<Block>::new_test(1);
+		for i in 0..10 {
+			let id = if i == 0 {
+				BlockId::Hash(Default::default())
+			} else {
+				BlockId::Number(i - 1)
+			};
+
+			let mut op = db.begin_operation(id).unwrap();
+			let header = Header {
+				number: i,
+				parent_hash: if i == 0 {
+					Default::default()
+				} else {
+					db.blockchain.hash(i - 1).unwrap().unwrap()
+				},
+				state_root: Default::default(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			op.set_block_data(header, Some(vec![]), None, true).unwrap();
+			db.commit_operation(op).unwrap();
+		}
+
+		assert!(db.state_at(BlockId::Number(9)).is_ok());
+		match db.state_at(BlockId::Number(0)) {
+			Err(client::error::Error(client::error::ErrorKind::StatePruned(_), _)) => {},
+			_ => panic!("state of block 0 should have been pruned"),
+		}
+		match db.state_at(BlockId::Number(10)) {
+			Err(client::error::Error(client::error::ErrorKind::UnknownBlock(_), _)) => {},
+			_ => panic!("block 10 should be unknown"),
+		}
+	}
+
+	#[test]
+	fn offchain_changes_are_committed_with_block() {
+		let db = Backend::<Block>::new_test(1);
+		let mut op = db.begin_operation(BlockId::Hash(Default::default())).unwrap();
+		let header = Header {
+			number: 0,
+			parent_hash: Default::default(),
+			state_root: Default::default(),
+			digest: Default::default(),
+			extrinsics_root: Default::default(),
+		};
+		op.set_block_data(header, Some(vec![]), None, true).unwrap();
+		op.update_offchain_storage(vec![
+			(b"tx".to_vec(), Some(b"block".to_vec())),
+			(b"cleared".to_vec(), Some(b"value".to_vec())),
+			(b"cleared".to_vec(), None),
+		].into_iter()).unwrap();
+
+		assert!(db.offchain_storage(b"tx").unwrap().is_none());
+		db.commit_operation(op).unwrap();
+		assert_eq!(db.offchain_storage(b"tx").unwrap(), Some(b"block".to_vec()));
+		assert!(db.offchain_storage(b"cleared").unwrap().is_none());
+	}
+
+	#[test]
+	fn state_is_kept_in_log_db() {
+		let dir = ::tempdir::TempDir::new("client_db").unwrap();
+		let settings = || DatabaseSettings {
+			cache_size: None,
+			path: dir.path().to_owned(),
+			pruning: PruningMode::ArchiveAll,
+			backend: DatabaseBackend::LogDb,
+		};
+		let storage = vec![(vec![1, 3, 5], vec![2, 4, 6])];
+
+		{
+			let db = Backend::<Block>::new(settings(), 0).unwrap();
+			let mut op = db.begin_operation(BlockId::Hash(Default::default())).unwrap();
+			let mut header = Header {
+				number: 0,
+				parent_hash: Default::default(),
+				state_root: Default::default(),
+				digest: Default::default(),
+				extrinsics_root: Default::default(),
+			};
+			header.state_root = op.old_state.storage_root(storage
+				.iter()
+				.cloned()
+				.map(|(x, y)| (x, Some(y)))
+			).0.into();
+			op.reset_storage(storage.iter().cloned()).unwrap();
+			op.set_block_data(header, Some(vec![]), None, true).unwrap();
+			db.commit_operation(op).unwrap();
+		}
+
+		let db = Backend::<Block>::new(settings(), 0).unwrap();
+		assert_eq!(db.blockchain().info().unwrap().best_number, 0);
+		let state = db.state_at(BlockId::Number(0)).unwrap();
+		assert_eq!(state.storage(&[1, 3, 5]).unwrap(), Some(vec![2, 4, 6]));
+	}
+
 	#[test]
 	fn set_state_data() {
 		let db = Backend::<Block>::new_test(2);
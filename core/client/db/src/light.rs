@@ -19,7 +19,7 @@
 use std::sync::Arc;
 use parking_lot::RwLock;
 
-use kvdb::{KeyValueDB, DBTransaction};
+use kvdb::DBTransaction;
 
 use client::blockchain::{BlockStatus, Cache as BlockchainCache,
 	HeaderBackend as BlockchainHeaderBackend, Info as BlockchainInfo};
@@ -32,6 +32,7 @@ use runtime_primitives::generic::BlockId;
 use runtime_primitives::traits::{Block as BlockT, Header as HeaderT, Hash, HashFor,
 	Zero, One, As, NumberFor};
 use cache::DbCache;
+use database::Database;
 use utils::{meta_keys, Meta, db_err, number_to_db_key, db_key_to_number, open_database,
 	read_db, read_id, read_meta};
 use DatabaseSettings;
@@ -49,7 +50,7 @@ pub(crate) const AUTHORITIES_ENTRIES_TO_KEEP: u64 = cht::SIZE;
 
 /// Light blockchain storage. Stores most recent headers + CHTs for older headers.
 pub struct LightStorage<Block: BlockT> {
-	db: Arc<KeyValueDB>,
+	db: Arc<Database>,
 	meta: RwLock<Meta<<<Block as BlockT>::Header as HeaderT>::Number, Block::Hash>>,
 	cache: DbCache<Block>,
 }
@@ -70,7 +71,7 @@ impl<Block> LightStorage<Block>
 	pub fn new(config: DatabaseSettings) -> ClientResult<Self> {
 		let db = open_database(&config, "light")?;
 
-		Self::from_kvdb(db as Arc<_>)
+		Self::from_database(db as Arc<_>)
 	}
 
 	#[cfg(test)]
@@ -79,10 +80,10 @@ impl<Block> LightStorage<Block>
 
 		let db = Arc::new(::kvdb_memorydb::create(NUM_COLUMNS));
 
-		Self::from_kvdb(db as Arc<_>).expect("failed to create test-db")
+		Self::from_database(db as Arc<_>).expect("failed to create test-db")
 	}
 
-	fn from_kvdb(db: Arc<KeyValueDB>) -> ClientResult<Self> {
+	fn from_database(db: Arc<Database>) -> ClientResult<Self> {
 		let cache = DbCache::new(db.clone(), columns::BLOCK_INDEX, columns::AUTHORITIES)?;
 		let meta = RwLock::new(read_meta::<Block>(&*db, columns::HEADER)?);
 
@@ -94,7 +95,7 @@ impl<Block> LightStorage<Block>
 	}
 
 	#[cfg(test)]
-	pub(crate) fn db(&self) -> &Arc<KeyValueDB> {
+	pub(crate) fn db(&self) -> &Arc<Database> {
 		&self.db
 	}
 
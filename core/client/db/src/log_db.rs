@@ -0,0 +1,430 @@
+// Copyright 2017 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An append-only log database, suited to state data which is mostly inserted.
+//!
+//! Every transaction is appended to a single file as one record, and only the location of each
+//! value is kept in memory. Writes are therefore sequential and never wait for a compaction.
+//! The space taken by deleted and overwritten values is reclaimed when the database is opened,
+//! by rewriting the live entries once most of the log is garbage.
+//!
+//! A record is the length of its body (4 bytes, LE), the Blake2-256 hash of the body and the body
+//! itself: a list of insertions and deletions. A record which is cut short or doesn't match its
+//! hash was being written when the node stopped, so it is discarded along with the rest of the log.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use hashdb::DBValue;
+use kvdb::{DBOp, DBTransaction};
+use parking_lot::{Mutex, RwLock};
+use primitives::blake2_256;
+
+use database::Database;
+
+/// Name of the log in the database directory.
+const LOG_FILE: &str = "log.db";
+/// Size of a record header: the length of the body and its hash.
+const HEADER_SIZE: u64 = 4 + 32;
+/// Size of an operation without its key and value: the tag, the column and the lengths.
+const OP_OVERHEAD: u64 = 1 + 4 + 4 + 4;
+/// The log isn't rewritten on open unless it holds at least this much garbage.
+const MIN_GARBAGE: u64 = 64 * 1024 * 1024;
+/// Size of the records written when rewriting the log.
+const REWRITE_RECORD_SIZE: usize = 16 * 1024 * 1024;
+
+const INSERT: u8 = 0;
+const DELETE: u8 = 1;
+
+/// Where a value is in the log.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Location {
+	offset: u64,
+	len: u32,
+}
+
+/// Locations of the values of each column, the default column first.
+type Index = Vec<BTreeMap<Vec<u8>, Location>>;
+
+struct Log {
+	file: File,
+	/// End of the last complete record.
+	len: u64,
+}
+
+/// A database which appends all changes to a log.
+pub struct LogDb {
+	log: Mutex<Log>,
+	index: RwLock<Index>,
+}
+
+impl LogDb {
+	/// Open the database in the directory at `path`, creating it if needed.
+	pub fn open(path: &Path, num_columns: u32) -> io::Result<LogDb> {
+		fs::create_dir_all(path)?;
+		let log_path = path.join(LOG_FILE);
+		let (mut file, mut index, mut len) = load(&log_path, num_columns)?;
+
+		let live = live_size(&index);
+		if len - live >= MIN_GARBAGE && len - live > live {
+			info!("Rewriting {}, {} of its {} bytes are live", log_path.display(), live, len);
+			rewrite(&log_path, &mut file, &index)?;
+			let reloaded = load(&log_path, num_columns)?;
+			file = reloaded.0;
+			index = reloaded.1;
+			len = reloaded.2;
+		}
+
+		Ok(LogDb {
+			log: Mutex::new(Log { file, len }),
+			index: RwLock::new(index),
+		})
+	}
+
+	fn read(&self, location: Location) -> io::Result<Vec<u8>> {
+		read_value(&mut self.log.lock().file, location)
+	}
+}
+
+impl Database for LogDb {
+	fn get(&self, col: Option<u32>, key: &[u8]) -> io::Result<Option<DBValue>> {
+		let location = {
+			let index = self.index.read();
+			let column = column_index(col, index.len()).ok_or_else(|| unknown_column(col))?;
+			index[column].get(key).cloned()
+		};
+		match location {
+			Some(location) => self.read(location).map(|v| Some(DBValue::from_slice(&v))),
+			None => Ok(None),
+		}
+	}
+
+	fn iter<'a>(&'a self, col: Option<u32>) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+		let entries: Vec<_> = {
+			let index = self.index.read();
+			match column_index(col, index.len()) {
+				Some(column) => index[column].iter().map(|(k, l)| (k.clone(), *l)).collect(),
+				None => Vec::new(),
+			}
+		};
+		Box::new(entries.into_iter().filter_map(move |(key, location)| match self.read(location) {
+			Ok(value) => Some((key.into_boxed_slice(), value.into_boxed_slice())),
+			Err(e) => {
+				warn!("Error reading the database log: {}", e);
+				None
+			},
+		}))
+	}
+
+	fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+		if transaction.ops.is_empty() {
+			return Ok(());
+		}
+
+		let columns = self.index.read().len();
+		let mut body = Vec::new();
+		for op in &transaction.ops {
+			let column = column_index(op.col(), columns).ok_or_else(|| unknown_column(op.col()))?;
+			match *op {
+				DBOp::Insert { ref key, ref value, .. } => encode_insert(&mut body, column, key, value),
+				DBOp::Delete { ref key, .. } => encode_delete(&mut body, column, key),
+			}
+		}
+		if body.len() > u32::max_value() as usize {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "Transaction is too large"));
+		}
+
+		let mut log = self.log.lock();
+		let offset = log.len;
+		// anything after the last complete record is the remains of a failed write
+		log.file.seek(SeekFrom::Start(offset))?;
+		write_record(&mut log.file, &body)?;
+		log.file.sync_data()?;
+		log.len += HEADER_SIZE + body.len() as u64;
+
+		index_record(&mut self.index.write(), &body, offset + HEADER_SIZE)
+	}
+}
+
+/// Position of `col` in the index, `None` if there's no such column.
+fn column_index(col: Option<u32>, columns: usize) -> Option<usize> {
+	let column = col.map_or(0, |c| c as usize + 1);
+	if column < columns { Some(column) } else { None }
+}
+
+fn unknown_column(col: Option<u32>) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, format!("No column {:?} in the database", col))
+}
+
+fn corrupted() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, "Corrupted database log")
+}
+
+fn encode_bytes(body: &mut Vec<u8>, bytes: &[u8]) {
+	encode_u32(body, bytes.len() as u32);
+	body.extend_from_slice(bytes);
+}
+
+fn encode_u32(body: &mut Vec<u8>, n: u32) {
+	body.extend_from_slice(&[n as u8, (n >> 8) as u8, (n >> 16) as u8, (n >> 24) as u8]);
+}
+
+fn encode_insert(body: &mut Vec<u8>, column: usize, key: &[u8], value: &[u8]) {
+	body.push(INSERT);
+	encode_u32(body, column as u32);
+	encode_bytes(body, key);
+	encode_bytes(body, value);
+}
+
+fn encode_delete(body: &mut Vec<u8>, column: usize, key: &[u8]) {
+	body.push(DELETE);
+	encode_u32(body, column as u32);
+	encode_bytes(body, key);
+}
+
+fn decode_u32(bytes: &[u8]) -> u32 {
+	bytes[0] as u32 | (bytes[1] as u32) << 8 | (bytes[2] as u32) << 16 | (bytes[3] as u32) << 24
+}
+
+/// Take the next `len` bytes of `body` from `pos`.
+fn take<'a>(body: &'a [u8], pos: &mut usize, len: usize) -> io::Result<&'a [u8]> {
+	let end = match pos.checked_add(len) {
+		Some(end) if end <= body.len() => end,
+		_ => return Err(corrupted()),
+	};
+	let bytes = &body[*pos..end];
+	*pos = end;
+	Ok(bytes)
+}
+
+fn write_record<W: Write>(out: &mut W, body: &[u8]) -> io::Result<()> {
+	let mut header = Vec::with_capacity(HEADER_SIZE as usize);
+	encode_u32(&mut header, body.len() as u32);
+	header.extend_from_slice(&blake2_256(body));
+	out.write_all(&header)?;
+	out.write_all(body)
+}
+
+/// Apply the changes of a record `body`, which starts at `offset` in the log, to `index`.
+fn index_record(index: &mut Index, body: &[u8], offset: u64) -> io::Result<()> {
+	let mut pos = 0;
+	while pos < body.len() {
+		let tag = take(body, &mut pos, 1)?[0];
+		let column = decode_u32(take(body, &mut pos, 4)?) as usize;
+		if column >= index.len() {
+			return Err(corrupted());
+		}
+		let key_len = decode_u32(take(body, &mut pos, 4)?) as usize;
+		let key = take(body, &mut pos, key_len)?.to_vec();
+		match tag {
+			INSERT => {
+				let len = decode_u32(take(body, &mut pos, 4)?);
+				let location = Location { offset: offset + pos as u64, len };
+				take(body, &mut pos, len as usize)?;
+				index[column].insert(key, location);
+			},
+			DELETE => {
+				index[column].remove(&key);
+			},
+			_ => return Err(corrupted()),
+		}
+	}
+	Ok(())
+}
+
+/// Read the whole record from `reader` into `buf`, `false` if the log ends first.
+fn read_record_part<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<bool> {
+	match reader.read_exact(buf) {
+		Ok(()) => Ok(true),
+		Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+		Err(e) => Err(e),
+	}
+}
+
+/// Open the log at `log_path` and index it, dropping a torn record at its end.
+fn load(log_path: &Path, num_columns: u32) -> io::Result<(File, Index, u64)> {
+	let mut file = OpenOptions::new().read(true).write(true).create(true).open(log_path)?;
+	let file_len = file.metadata()?.len();
+	let mut index = vec![BTreeMap::new(); num_columns as usize + 1];
+	let mut len = 0;
+
+	{
+		let mut reader = BufReader::new(&mut file);
+		loop {
+			let mut header = [0u8; HEADER_SIZE as usize];
+			if !read_record_part(&mut reader, &mut header)? {
+				break;
+			}
+			let body_len = decode_u32(&header[..4]) as u64;
+			if len + HEADER_SIZE + body_len > file_len {
+				break;
+			}
+			let mut body = vec![0u8; body_len as usize];
+			if !read_record_part(&mut reader, &mut body)? || blake2_256(&body)[..] != header[4..] {
+				break;
+			}
+			index_record(&mut index, &body, len + HEADER_SIZE)?;
+			len += HEADER_SIZE + body_len;
+		}
+	}
+
+	if len != file_len {
+		warn!("Discarding an incomplete transaction at the end of {}", log_path.display());
+		file.set_len(len)?;
+		file.sync_all()?;
+	}
+
+	Ok((file, index, len))
+}
+
+/// Size the live entries of `index` take in the log.
+fn live_size(index: &Index) -> u64 {
+	index.iter()
+		.flat_map(|column| column.iter())
+		.map(|(key, location)| OP_OVERHEAD + key.len() as u64 + location.len as u64)
+		.sum()
+}
+
+fn read_value(file: &mut File, location: Location) -> io::Result<Vec<u8>> {
+	let mut value = vec![0u8; location.len as usize];
+	file.seek(SeekFrom::Start(location.offset))?;
+	file.read_exact(&mut value)?;
+	Ok(value)
+}
+
+/// Replace the log at `log_path` with one holding only the live entries of `index`.
+fn rewrite(log_path: &Path, file: &mut File, index: &Index) -> io::Result<()> {
+	let tmp_path = log_path.with_extension("tmp");
+	{
+		let mut out = BufWriter::new(File::create(&tmp_path)?);
+		let mut body = Vec::new();
+		for (column, entries) in index.iter().enumerate() {
+			for (key, location) in entries {
+				let value = read_value(file, *location)?;
+				encode_insert(&mut body, column, key, &value);
+				if body.len() >= REWRITE_RECORD_SIZE {
+					write_record(&mut out, &body)?;
+					body.clear();
+				}
+			}
+		}
+		if !body.is_empty() {
+			write_record(&mut out, &body)?;
+		}
+		out.flush()?;
+		out.get_ref().sync_all()?;
+	}
+	fs::rename(&tmp_path, log_path)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::OpenOptions;
+	use std::io::Write;
+	use tempdir::TempDir;
+	use super::*;
+
+	fn insert(col: Option<u32>, key: &[u8], value: &[u8]) -> DBTransaction {
+		let mut transaction = DBTransaction::new();
+		transaction.put(col, key, value);
+		transaction
+	}
+
+	#[test]
+	fn changes_are_read_back_after_reopening() {
+		let dir = TempDir::new("log_db").unwrap();
+		{
+			let db = LogDb::open(dir.path(), 2).unwrap();
+			db.write(insert(Some(0), b"a", b"1")).unwrap();
+			db.write(insert(Some(1), b"a", b"2")).unwrap();
+			db.write(insert(None, b"b", b"3")).unwrap();
+			let mut transaction = DBTransaction::new();
+			transaction.put(Some(0), b"c", b"4");
+			transaction.put(Some(0), b"a", b"5");
+			transaction.delete(None, b"b");
+			db.write(transaction).unwrap();
+
+			assert_eq!(db.get(Some(0), b"a").unwrap().unwrap(), &b"5"[..]);
+			assert_eq!(db.get(None, b"b").unwrap(), None);
+		}
+
+		let db = LogDb::open(dir.path(), 2).unwrap();
+		assert_eq!(db.get(Some(0), b"a").unwrap().unwrap(), &b"5"[..]);
+		assert_eq!(db.get(Some(1), b"a").unwrap().unwrap(), &b"2"[..]);
+		assert_eq!(db.get(None, b"b").unwrap(), None);
+		assert_eq!(db.iter(Some(0)).collect::<Vec<_>>(), vec![
+			(b"a".to_vec().into_boxed_slice(), b"5".to_vec().into_boxed_slice()),
+			(b"c".to_vec().into_boxed_slice(), b"4".to_vec().into_boxed_slice()),
+		]);
+	}
+
+	#[test]
+	fn unknown_columns_are_rejected() {
+		let dir = TempDir::new("log_db").unwrap();
+		let db = LogDb::open(dir.path(), 2).unwrap();
+		assert!(db.write(insert(Some(2), b"a", b"1")).is_err());
+		assert!(db.get(Some(2), b"a").is_err());
+		assert_eq!(db.iter(Some(2)).count(), 0);
+	}
+
+	#[test]
+	fn torn_record_is_discarded() {
+		let dir = TempDir::new("log_db").unwrap();
+		{
+			let db = LogDb::open(dir.path(), 1).unwrap();
+			db.write(insert(Some(0), b"a", b"1")).unwrap();
+		}
+		let complete_len = fs::metadata(dir.path().join(LOG_FILE)).unwrap().len();
+		{
+			let mut body = Vec::new();
+			encode_insert(&mut body, 1, b"b", b"2");
+			let mut record = Vec::new();
+			write_record(&mut record, &body).unwrap();
+			let mut file = OpenOptions::new().append(true).open(dir.path().join(LOG_FILE)).unwrap();
+			file.write_all(&record[..record.len() - 1]).unwrap();
+		}
+
+		let db = LogDb::open(dir.path(), 1).unwrap();
+		assert_eq!(db.get(Some(0), b"a").unwrap().unwrap(), &b"1"[..]);
+		assert_eq!(db.get(Some(0), b"b").unwrap(), None);
+		assert_eq!(fs::metadata(dir.path().join(LOG_FILE)).unwrap().len(), complete_len);
+
+		db.write(insert(Some(0), b"c", b"3")).unwrap();
+		drop(db);
+		let db = LogDb::open(dir.path(), 1).unwrap();
+		assert_eq!(db.get(Some(0), b"c").unwrap().unwrap(), &b"3"[..]);
+	}
+
+	#[test]
+	fn garbage_is_reclaimed_on_open() {
+		let dir = TempDir::new("log_db").unwrap();
+		let value = vec![42u8; 1024 * 1024];
+		{
+			let db = LogDb::open(dir.path(), 1).unwrap();
+			for _ in 0..(2 * MIN_GARBAGE / value.len() as u64) {
+				db.write(insert(Some(0), b"a", &value)).unwrap();
+			}
+			db.write(insert(Some(0), b"b", b"2")).unwrap();
+		}
+
+		let db = LogDb::open(dir.path(), 1).unwrap();
+		assert!(fs::metadata(dir.path().join(LOG_FILE)).unwrap().len() < 2 * value.len() as u64);
+		assert_eq!(db.get(Some(0), b"a").unwrap().unwrap(), &value[..]);
+		assert_eq!(db.get(Some(0), b"b").unwrap().unwrap(), &b"2"[..]);
+	}
+}
@@ -20,19 +20,20 @@
 use std::sync::Arc;
 use std::io;
 
-use kvdb::{KeyValueDB, DBTransaction};
-use kvdb_rocksdb::{Database, DatabaseConfig};
+use kvdb::DBTransaction;
 
 use client;
+use database::Database;
+use log_db::LogDb;
 use codec::Decode;
 use hashdb::DBValue;
 use runtime_primitives::generic::BlockId;
 use runtime_primitives::traits::{As, Block as BlockT, Header as HeaderT, Hash, HashFor, Zero};
-use DatabaseSettings;
+use {DatabaseBackend, DatabaseSettings};
 
 /// Number of columns in the db. Must be the same for both full && light dbs.
 /// Otherwise RocksDb will fail to open database && check its type.
-pub const NUM_COLUMNS: u32 = 7;
+pub const NUM_COLUMNS: u32 = 8;
 /// Meta column. The set of keys in the column is shared by full && light storages.
 pub const COLUMN_META: Option<u32> = Some(0);
 
@@ -89,12 +90,13 @@ pub fn db_err(err: io::Error) -> client::error::Error {
 	client::error::ErrorKind::Backend(err.description().into()).into()
 }
 
-/// Open RocksDB database.
-pub fn open_database(config: &DatabaseSettings, db_type: &str) -> client::error::Result<Arc<KeyValueDB>> {
-	let mut db_config = DatabaseConfig::with_columns(Some(NUM_COLUMNS));
-	db_config.memory_budget = config.cache_size;
-	let path = config.path.to_str().ok_or_else(|| client::error::ErrorKind::Backend("Invalid database path".into()))?;
-	let db = Database::open(&db_config, &path).map_err(db_err)?;
+/// Open the database selected by `config`.
+pub fn open_database(config: &DatabaseSettings, db_type: &str) -> client::error::Result<Arc<Database>> {
+	let db: Arc<Database> = match config.backend {
+		DatabaseBackend::RocksDb => open_rocksdb(config)?,
+		DatabaseBackend::LogDb => Arc::new(LogDb::open(&config.path, NUM_COLUMNS).map_err(db_err)?),
+		DatabaseBackend::Memory => Arc::new(::kvdb_memorydb::create(NUM_COLUMNS)),
+	};
 
 	// check database type
 	match db.get(COLUMN_META, meta_keys::TYPE).map_err(db_err)? {
@@ -111,11 +113,26 @@ pub fn open_database(config: &DatabaseSettings, db_type: &str) -> client::error:
 		},
 	}
 
-	Ok(Arc::new(db))
+	Ok(db)
+}
+
+#[cfg(feature = "rocksdb")]
+fn open_rocksdb(config: &DatabaseSettings) -> client::error::Result<Arc<Database>> {
+	use kvdb_rocksdb::{Database as RocksDb, DatabaseConfig};
+
+	let mut db_config = DatabaseConfig::with_columns(Some(NUM_COLUMNS));
+	db_config.memory_budget = config.cache_size;
+	let path = config.path.to_str().ok_or_else(|| client::error::ErrorKind::Backend("Invalid database path".into()))?;
+	Ok(Arc::new(RocksDb::open(&db_config, &path).map_err(db_err)?))
+}
+
+#[cfg(not(feature = "rocksdb"))]
+fn open_rocksdb(_config: &DatabaseSettings) -> client::error::Result<Arc<Database>> {
+	Err(client::error::ErrorKind::Backend("RocksDB support was not compiled in".into()).into())
 }
 
 /// Convert block id to block key, reading number from db if required.
-pub fn read_id<Block>(db: &KeyValueDB, col_index: Option<u32>, id: BlockId<Block>) -> Result<Option<BlockKey>, client::error::Error>
+pub fn read_id<Block>(db: &Database, col_index: Option<u32>, id: BlockId<Block>) -> Result<Option<BlockKey>, client::error::Error>
 	where
 		Block: BlockT,
 {
@@ -131,7 +148,7 @@ pub fn read_id<Block>(db: &KeyValueDB, col_index: Option<u32>, id: BlockId<Block
 }
 
 /// Read database column entry for the given block.
-pub fn read_db<Block>(db: &KeyValueDB, col_index: Option<u32>, col: Option<u32>, id: BlockId<Block>) -> client::error::Result<Option<DBValue>>
+pub fn read_db<Block>(db: &Database, col_index: Option<u32>, col: Option<u32>, id: BlockId<Block>) -> client::error::Result<Option<DBValue>>
 	where
 		Block: BlockT,
 {
@@ -142,7 +159,7 @@ pub fn read_db<Block>(db: &KeyValueDB, col_index: Option<u32>, col: Option<u32>,
 }
 
 /// Read meta from the database.
-pub fn read_meta<Block>(db: &KeyValueDB, col_header: Option<u32>) -> Result<Meta<<<Block as BlockT>::Header as HeaderT>::Number, Block::Hash>, client::error::Error>
+pub fn read_meta<Block>(db: &Database, col_header: Option<u32>) -> Result<Meta<<<Block as BlockT>::Header as HeaderT>::Number, Block::Hash>, client::error::Error>
 	where
 		Block: BlockT,
 {
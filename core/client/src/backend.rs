@@ -53,6 +53,8 @@ where
 	fn update_storage(&mut self, update: <Self::State as StateBackend<H, C>>::Transaction) -> error::Result<()>;
 	/// Inject storage data into the database replacing any existing data.
 	fn reset_storage<I: Iterator<Item=(Vec<u8>, Vec<u8>)>>(&mut self, iter: I) -> error::Result<()>;
+	/// Inject changes to the local offchain database made by the runtime while executing the block.
+	fn update_offchain_storage<I: Iterator<Item=(Vec<u8>, Option<Vec<u8>>)>>(&mut self, changes: I) -> error::Result<()>;
 }
 
 /// Client backend. Manages the data layer.
@@ -88,6 +90,8 @@ where
 	/// Attempts to revert the chain by `n` blocks. Returns the number of blocks that were
 	/// successfully reverted.
 	fn revert(&self, n: NumberFor<Block>) -> error::Result<NumberFor<Block>>;
+	/// Read an entry of the local offchain database.
+	fn offchain_storage(&self, key: &[u8]) -> error::Result<Option<Vec<u8>>>;
 }
 
 /// Mark for all Backend implementations, that are making use of state data, stored locally.
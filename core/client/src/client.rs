@@ -21,15 +21,15 @@ use futures::sync::mpsc;
 use parking_lot::{Mutex, RwLock};
 use primitives::AuthorityId;
 use runtime_primitives::{bft::Justification, generic::{BlockId, SignedBlock, Block as RuntimeBlock}};
-use runtime_primitives::traits::{Block as BlockT, Header as HeaderT, Zero, One, As, NumberFor};
+use runtime_primitives::traits::{Block as BlockT, Header as HeaderT, Hash as HashT, HashFor, Zero, One, As, NumberFor};
 use runtime_primitives::BuildStorage;
-use substrate_metadata::JsonMetadataDecodable;
+use substrate_metadata::RuntimeMetadataDecodable;
 use primitives::{Blake2Hasher, RlpCodec};
-use primitives::storage::{StorageKey, StorageData};
+use primitives::storage::{StorageKey, StorageData, well_known_keys};
 use codec::{Encode, Decode};
 use state_machine::{
 	Backend as StateBackend, CodeExecutor,
-	ExecutionStrategy, ExecutionManager, prove_read
+	ExecutionStrategy, ExecutionManager, prove_read, prove_range
 };
 
 use backend::{self, BlockImportOperation};
@@ -37,6 +37,7 @@ use blockchain::{self, Info as ChainInfo, Backend as ChainBackend, HeaderBackend
 use call_executor::{CallExecutor, LocalCallExecutor};
 use executor::{RuntimeVersion, RuntimeInfo};
 use notifications::{StorageNotifications, StorageEventStream};
+use events::{EventsDecoder, EventRecord};
 use {cht, error, in_mem, block_builder, bft, genesis};
 
 /// Type that implements `futures::Stream` of block import events.
@@ -225,9 +226,14 @@ impl<B, E, Block> Client<B, E, Block> where
 			.map(StorageData))
 	}
 
+	/// Return an entry of the local offchain database, as indexed by the runtime during block import.
+	pub fn offchain_storage(&self, key: &StorageKey) -> error::Result<Option<StorageData>> {
+		Ok(self.backend.offchain_storage(&key.0)?.map(StorageData))
+	}
+
 	/// Get the code at a given block.
 	pub fn code_at(&self, id: &BlockId<Block>) -> error::Result<Vec<u8>> {
-		Ok(self.storage(id, &StorageKey(b":code".to_vec()))?
+		Ok(self.storage(id, &StorageKey(well_known_keys::CODE.to_vec()))?
 			.expect("None is returned if there's no value stored for the given key; ':code' key is always defined; qed").0)
 	}
 
@@ -252,13 +258,20 @@ impl<B, E, Block> Client<B, E, Block> where
 		&self.executor
 	}
 
+	/// Returns the runtime metadata, from its `Metadata_metadata` API, or from `json_metadata` for
+	/// runtimes predating it.
+	pub fn metadata(&self, id: &BlockId<Block>) -> error::Result<RuntimeMetadataDecodable> {
+		self.executor.call(id, "Metadata_metadata", &[])
+			.or_else(|_| self.executor.call(id, "json_metadata", &[]))
+			.and_then(|r| RuntimeMetadataDecodable::decode(&mut &r.return_data[..])
+					  .ok_or("JSON Metadata decoding failed".into()))
+	}
+
 	/// Returns the runtime metadata as JSON.
 	pub fn json_metadata(&self, id: &BlockId<Block>) -> error::Result<String> {
-		self.executor.call(id, "json_metadata",&[])
-			.and_then(|r| Vec::<JsonMetadataDecodable>::decode(&mut &r.return_data[..])
-					  .ok_or("JSON Metadata decoding failed".into()))
+		self.metadata(id)
 			.and_then(|metadata| {
-				let mut json = metadata.into_iter().enumerate().fold(String::from("{"),
+				let mut json = metadata.into_json_metadata().into_iter().enumerate().fold(String::from("{"),
 					|mut json, (i, m)| {
 						if i > 0 {
 							json.push_str(",");
@@ -274,6 +287,15 @@ impl<B, E, Block> Client<B, E, Block> where
 			})
 	}
 
+	/// Returns the events deposited in a block, decoded with the types of its runtime metadata.
+	pub fn events(&self, id: &BlockId<Block>) -> error::Result<Vec<EventRecord>> {
+		let decoder = EventsDecoder::new(self.metadata(id)?).map_err(error::ErrorKind::EventsDecode)?;
+		match self.storage(id, &StorageKey(decoder.storage_key()))? {
+			Some(StorageData(events)) => decoder.decode(&events).map_err(|e| error::ErrorKind::EventsDecode(e).into()),
+			None => Ok(Vec::new()),
+		}
+	}
+
 	/// Reads storage value at a given block + key, returning read proof.
 	pub fn read_proof(&self, id: &BlockId<Block>, key: &[u8]) -> error::Result<Vec<Vec<u8>>> {
 		self.state_at(id)
@@ -282,6 +304,16 @@ impl<B, E, Block> Client<B, E, Block> where
 				.map_err(Into::into))
 	}
 
+	/// Reads a chunk of the state at a block: the entries with keys not smaller than `start`, in
+	/// ascending key order, stopping before their total size exceeds `max_bytes` (but always
+	/// including at least one entry). Also returns whether the chunk reaches the end of the state
+	/// and the proof of the chunk. Only the part of the trie covering the chunk is read.
+	pub fn state_range(&self, id: &BlockId<Block>, start: &[u8], max_bytes: usize) -> error::Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, Vec<Vec<u8>>)> {
+		self.state_at(id)
+			.and_then(|state| prove_range(state, start, max_bytes)
+				.map_err(Into::into))
+	}
+
 	/// Execute a call to a contract on top of state in a block of given hash
 	/// AND returning execution proof.
 	///
@@ -420,6 +452,65 @@ impl<B, E, Block> Client<B, E, Block> where
 		result
 	}
 
+	/// Import a block header without executing the block, e.g. while fast syncing. The state at
+	/// the block stays unknown, so it isn't made the best block, and the justification can't be
+	/// checked since the authorities are read from the state at the parent.
+	pub fn import_header(
+		&self,
+		origin: BlockOrigin,
+		header: <Block as BlockT>::Header,
+		justification: Justification<Block::Hash>,
+	) -> error::Result<ImportResult> {
+		self.import_without_execution(origin, header, justification, None)
+	}
+
+	/// Import a block with its whole state instead of executing it, e.g. when the state was
+	/// downloaded while fast syncing. The block becomes the best block. Fails unless the state
+	/// matches the state root of the header.
+	pub fn import_block_with_state(
+		&self,
+		origin: BlockOrigin,
+		header: <Block as BlockT>::Header,
+		justification: Justification<Block::Hash>,
+		state: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> error::Result<ImportResult> {
+		let root = HashFor::<Block>::trie_root(state.iter().map(|&(ref key, ref value)| (key, value)));
+		if &root != header.state_root() {
+			return Err(error::ErrorKind::InvalidState(format!("{}", header.hash())).into());
+		}
+		self.import_without_execution(origin, header, justification, Some(state))
+	}
+
+	fn import_without_execution(
+		&self,
+		origin: BlockOrigin,
+		header: <Block as BlockT>::Header,
+		justification: Justification<Block::Hash>,
+		state: Option<Vec<(Vec<u8>, Vec<u8>)>>,
+	) -> error::Result<ImportResult> {
+		match self.backend.blockchain().status(BlockId::Hash(*header.parent_hash()))? {
+			blockchain::BlockStatus::InChain => {},
+			blockchain::BlockStatus::Unknown => return Ok(ImportResult::UnknownParent),
+		}
+		let hash = header.hash();
+		let _import_lock = self.import_lock.lock();
+		match self.backend.blockchain().status(BlockId::Hash(hash))? {
+			blockchain::BlockStatus::InChain => return Ok(ImportResult::AlreadyInChain),
+			blockchain::BlockStatus::Unknown => {},
+		}
+
+		// the state of the parent may be unknown, so build on the empty state, like the genesis block
+		let mut transaction = self.backend.begin_operation(BlockId::Hash(Default::default()))?;
+		let is_new_best = state.is_some();
+		trace!("Imported {} without execution, (#{}), best={}, origin={:?}", hash, header.number(), is_new_best, origin);
+		transaction.set_block_data(header, None, Some(justification), is_new_best)?;
+		if let Some(state) = state {
+			transaction.reset_storage(state.into_iter())?;
+		}
+		self.backend.commit_operation(transaction)?;
+		Ok(ImportResult::Queued)
+	}
+
 	fn execute_and_import_block(
 		&self,
 		origin: BlockOrigin,
@@ -436,7 +527,7 @@ impl<B, E, Block> Client<B, E, Block> where
 		}
 
 		let mut transaction = self.backend.begin_operation(BlockId::Hash(parent_hash))?;
-		let (storage_update, storage_changes) = match transaction.state()? {
+		let (storage_update, storage_changes, offchain_changes) = match transaction.state()? {
 			Some(transaction_state) => {
 				let mut overlay = Default::default();
 				let mut r = self.executor.call_at_state(
@@ -464,9 +555,10 @@ impl<B, E, Block> Client<B, E, Block> where
 				);
 				let (_, storage_update) = r?;
 				overlay.commit_prospective();
-				(Some(storage_update), Some(overlay.into_committed()))
+				let offchain_changes = overlay.drain_offchain().collect::<Vec<_>>();
+				(Some(storage_update), Some(overlay.into_committed()), offchain_changes)
 			},
-			None => (None, None)
+			None => (None, None, Vec::new())
 		};
 
 		let is_new_best = header.number() == &(self.backend.blockchain().info()?.best_number + One::one());
@@ -477,6 +569,7 @@ impl<B, E, Block> Client<B, E, Block> where
 		if let Some(storage_update) = storage_update {
 			transaction.update_storage(storage_update)?;
 		}
+		transaction.update_offchain_storage(offchain_changes.into_iter())?;
 		self.backend.commit_operation(transaction)?;
 
 		if origin == BlockOrigin::NetworkBroadcast || origin == BlockOrigin::Own || origin == BlockOrigin::ConsensusBroadcast {
@@ -768,6 +861,41 @@ mod tests {
 		assert_eq!(client.body(&BlockId::Number(1)).unwrap().unwrap().len(), 1)
 	}
 
+	#[test]
+	fn import_block_with_state_works() {
+		let remote = test_client::new();
+		let mut builder = remote.new_block().unwrap();
+		builder.push_transfer(Transfer {
+			from: Keyring::Alice.to_raw_public().into(),
+			to: Keyring::Ferdie.to_raw_public().into(),
+			amount: 42,
+			nonce: 0,
+		}).unwrap();
+		remote.justify_and_import(BlockOrigin::Own, builder.bake().unwrap()).unwrap();
+		remote.justify_and_import(BlockOrigin::Own, remote.new_block().unwrap().bake().unwrap()).unwrap();
+
+		let header = |n: u64| remote.header(&BlockId::Number(n)).unwrap().unwrap();
+		let justification = |n: u64| remote.justification(&BlockId::Number(n)).unwrap().unwrap();
+		let mut state = remote.state_at(&BlockId::Number(2)).unwrap().pairs();
+
+		let client = test_client::new();
+		assert!(match client.import_header(BlockOrigin::NetworkInitialSync, header(2), justification(2)) {
+			Ok(ImportResult::UnknownParent) => true,
+			_ => false,
+		});
+		client.import_header(BlockOrigin::NetworkInitialSync, header(1), justification(1)).unwrap();
+		assert_eq!(client.info().unwrap().chain.best_number, 0);
+
+		state[0].1.push(0);
+		assert!(client.import_block_with_state(BlockOrigin::NetworkInitialSync, header(2), justification(2), state.clone()).is_err());
+		state[0].1.pop();
+		client.import_block_with_state(BlockOrigin::NetworkInitialSync, header(2), justification(2), state).unwrap();
+
+		assert_eq!(client.info().unwrap().chain.best_number, 2);
+		assert_eq!(client.call_api::<_, u64>("balance_of", &Keyring::Ferdie.to_raw_public()).unwrap(), 42);
+		assert!(client.body(&BlockId::Number(1)).unwrap().is_none());
+	}
+
 	#[test]
 	fn json_metadata() {
 		let client = test_client::new();
@@ -34,6 +34,12 @@ error_chain! {
 			display("UnknownBlock: {}", &*h),
 		}
 
+		/// The state of a known block has been pruned.
+		StatePruned(h: String) {
+			description("state pruned"),
+			display("State already discarded for {}", &*h),
+		}
+
 		/// Applying extrinsic error.
 		ApplyExtinsicFailed(e: ApplyError) {
 			description("Extrinsic error"),
@@ -88,6 +94,12 @@ error_chain! {
 			display("bad justification for header: {}", &*h),
 		}
 
+		/// State not matching the state root of a block.
+		InvalidState(h: String) {
+			description("state does not match the state root"),
+			display("State does not match the state root of block {}", &*h),
+		}
+
 		/// Not available on light client.
 		NotAvailableOnLightClient {
 			description("not available on light client"),
@@ -123,6 +135,12 @@ error_chain! {
 			description("Error decoding call result")
 			display("Error decoding call result of {}", method)
 		}
+
+		/// Error decoding the events of a block.
+		EventsDecode(e: String) {
+			description("Error decoding events"),
+			display("Error decoding events: {}", &*e),
+		}
 	}
 }
 
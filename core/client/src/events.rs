@@ -0,0 +1,409 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding the events of a block, the `Events` storage item of the system module, with the types
+//! the runtime metadata describes.
+//!
+//! The values are decoded into JSON: numbers up to 64 bits as numbers and larger ones as decimal
+//! strings, bytes as hex strings, structs with named fields as objects and the others as arrays,
+//! or as their single field. An enum variant without fields is its name, and one with fields is an
+//! object with the fields under the name.
+
+use std::collections::HashMap;
+
+use codec::Decode;
+use primitives::compact::Compact;
+use runtime_io::twox_128;
+use serde_json::{self, Map, Value};
+use substrate_metadata::{FieldDef, JsonMetadataDecodable, RuntimeMetadataDecodable, TypeDef, VariantDef};
+
+/// An event deposited in a block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRecord {
+	/// The phase of the block it was deposited in, e.g. `{ "ApplyExtrinsic": 1 }`.
+	pub phase: Value,
+	/// The module it was deposited by, as named in the outer event of the runtime, e.g. `balances`.
+	pub module: String,
+	/// The name of the event, e.g. `Transfer`.
+	pub name: String,
+	/// The parameters of the event, in order.
+	pub params: Vec<Value>,
+	/// The topics it is indexed under.
+	pub topics: Vec<Value>,
+}
+
+impl EventRecord {
+	/// The record as a JSON object with the fields of the struct.
+	pub fn into_json(self) -> Value {
+		let mut json = Map::new();
+		json.insert("phase".into(), self.phase);
+		json.insert("module".into(), Value::String(self.module));
+		json.insert("name".into(), Value::String(self.name));
+		json.insert("params".into(), Value::Array(self.params));
+		json.insert("topics".into(), Value::Array(self.topics));
+		Value::Object(json)
+	}
+}
+
+/// Decodes the events of blocks with the types of a runtime.
+pub struct EventsDecoder {
+	prefix: String,
+	records: String,
+	types: HashMap<String, TypeDef>,
+}
+
+impl EventsDecoder {
+	/// Find the type of `system::Events` in the metadata of a runtime. Fails for metadata predating
+	/// the `Types` of the runtime.
+	pub fn new(metadata: RuntimeMetadataDecodable) -> Result<Self, String> {
+		let mut types = None;
+		let mut events = None;
+		for metadata in metadata.into_json_metadata() {
+			match metadata {
+				JsonMetadataDecodable::Types { types: registered } => types = Some(registered),
+				JsonMetadataDecodable::FullModule { storage: Some(storage), types: written, .. } => {
+					let storage: Value = serde_json::from_str(&storage)
+						.map_err(|e| format!("Invalid storage metadata: {}", e))?;
+					if storage["prefix"] != "System" {
+						continue;
+					}
+					let ty = storage["items"]["Events"]["type"].as_str()
+						.ok_or_else(|| "The system module has no `Events` value".to_owned())?;
+					let name = written.into_iter().find(|(w, _)| w == ty).map(|(_, name)| name)
+						.ok_or_else(|| format!("No type registered for `{}`", ty))?;
+					events = Some(name);
+				},
+				_ => {},
+			}
+		}
+
+		let types: HashMap<_, _> = types.ok_or_else(|| "The metadata has no types".to_owned())?
+			.into_iter()
+			.collect();
+		let events = events.ok_or_else(|| "The metadata has no system module".to_owned())?;
+		let records = match types.get(&events) {
+			Some(TypeDef::Sequence(records)) => records.clone(),
+			_ => return Err(format!("`{}` is not a sequence of event records", events)),
+		};
+		Ok(EventsDecoder { prefix: "System".into(), records, types })
+	}
+
+	/// The storage key the events are stored under.
+	pub fn storage_key(&self) -> Vec<u8> {
+		let mut key = twox_128(self.prefix.as_bytes()).to_vec();
+		key.extend(&twox_128(b"Events")[..]);
+		key
+	}
+
+	/// Decode the value stored under `storage_key`.
+	pub fn decode(&self, mut data: &[u8]) -> Result<Vec<EventRecord>, String> {
+		let input = &mut data;
+		let len = u32::decode(input).ok_or_else(|| "Missing event count".to_owned())?;
+		let mut records = Vec::new();
+		for _ in 0..len {
+			records.push(self.decode_record(input)?);
+		}
+		if !input.is_empty() {
+			return Err("Trailing bytes after the events".into());
+		}
+		Ok(records)
+	}
+
+	fn decode_record(&self, input: &mut &[u8]) -> Result<EventRecord, String> {
+		let fields = match self.type_def(&self.records)? {
+			TypeDef::Struct(fields) => fields,
+			_ => return Err(format!("`{}` is not a struct", self.records)),
+		};
+		let mut record = EventRecord {
+			phase: Value::Null,
+			module: String::new(),
+			name: String::new(),
+			params: Vec::new(),
+			topics: Vec::new(),
+		};
+		for field in fields {
+			match field.name.as_ref().map(|name| &name[..]) {
+				Some("event") => {
+					let (module, fields) = self.decode_variant(&field.ty, input)?;
+					let event = match fields {
+						[event] => &event.ty,
+						_ => return Err(format!("The `{}` variant of `{}` is not an event", module.name, field.ty)),
+					};
+					let (name, fields) = self.decode_variant(event, input)?;
+					record.module = module.name.clone();
+					record.name = name.name.clone();
+					record.params = fields.iter().map(|field| self.decode_value(&field.ty, input)).collect::<Result<_, _>>()?;
+				},
+				Some("phase") => record.phase = self.decode_value(&field.ty, input)?,
+				Some("topics") => match self.decode_value(&field.ty, input)? {
+					Value::Array(topics) => record.topics = topics,
+					_ => return Err(format!("The topics of `{}` are not a sequence", self.records)),
+				},
+				_ => { self.decode_value(&field.ty, input)?; },
+			}
+		}
+		Ok(record)
+	}
+
+	fn type_def(&self, ty: &str) -> Result<&TypeDef, String> {
+		self.types.get(ty).ok_or_else(|| format!("Unknown type `{}`", ty))
+	}
+
+	fn decode_variant(&self, ty: &str, input: &mut &[u8]) -> Result<(&VariantDef, &[FieldDef]), String> {
+		let variants = match self.type_def(ty)? {
+			TypeDef::Enum(variants) => variants,
+			_ => return Err(format!("`{}` is not an enum", ty)),
+		};
+		let index = take(input, 1)?[0];
+		let variant = variants.iter().find(|variant| variant.index == index)
+			.ok_or_else(|| format!("`{}` has no variant {}", ty, index))?;
+		Ok((variant, &variant.fields[..]))
+	}
+
+	fn decode_value(&self, ty: &str, input: &mut &[u8]) -> Result<Value, String> {
+		Ok(match self.type_def(ty)? {
+			TypeDef::Primitive => decode_primitive(ty, input)?,
+			TypeDef::Struct(fields) => self.decode_fields(fields, input)?,
+			TypeDef::Enum(_) => {
+				let (variant, fields) = self.decode_variant(ty, input)?;
+				if fields.is_empty() {
+					Value::String(variant.name.clone())
+				} else {
+					let mut json = Map::new();
+					json.insert(variant.name.clone(), self.decode_fields(fields, input)?);
+					Value::Object(json)
+				}
+			},
+			TypeDef::Sequence(item) => {
+				let len = u32::decode(input).ok_or_else(|| format!("Missing length of `{}`", ty))? as usize;
+				// every item takes a byte at least, which bounds the items read from a bad length
+				if len > input.len() {
+					return Err(format!("`{}` is longer than its data", ty));
+				}
+				self.decode_items(item, len, input)?
+			},
+			TypeDef::Array(len, item) => self.decode_items(item, *len as usize, input)?,
+			TypeDef::Tuple(items) if items.is_empty() => Value::Null,
+			TypeDef::Tuple(items) => Value::Array(
+				items.iter().map(|item| self.decode_value(item, input)).collect::<Result<_, _>>()?
+			),
+			TypeDef::Compact(_) => {
+				let Compact(n) = <Compact<u128>>::decode(input).ok_or_else(|| format!("Invalid `{}`", ty))?;
+				large_number(n)
+			},
+		})
+	}
+
+	fn decode_fields(&self, fields: &[FieldDef], input: &mut &[u8]) -> Result<Value, String> {
+		if !fields.is_empty() && fields.iter().all(|field| field.name.is_some()) {
+			let mut json = Map::new();
+			for field in fields {
+				let name = field.name.clone().expect("all the fields are named; qed");
+				json.insert(name, self.decode_value(&field.ty, input)?);
+			}
+			return Ok(Value::Object(json));
+		}
+		let mut values = fields.iter().map(|field| self.decode_value(&field.ty, input)).collect::<Result<Vec<_>, _>>()?;
+		Ok(match values.len() {
+			1 => values.pop().expect("there is one value; qed"),
+			_ => Value::Array(values),
+		})
+	}
+
+	fn decode_items(&self, item: &str, len: usize, input: &mut &[u8]) -> Result<Value, String> {
+		if item == "u8" {
+			let bytes = take(input, len)?;
+			return Ok(Value::String(format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())));
+		}
+		let mut items = Vec::new();
+		for _ in 0..len {
+			items.push(self.decode_value(item, input)?);
+		}
+		Ok(Value::Array(items))
+	}
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+	if input.len() < len {
+		return Err("Unexpected end of the events".into());
+	}
+	let (taken, rest) = input.split_at(len);
+	*input = rest;
+	Ok(taken)
+}
+
+fn large_number(n: u128) -> Value {
+	if n >> 64 == 0 {
+		Value::from(n as u64)
+	} else {
+		Value::String(n.to_string())
+	}
+}
+
+fn decode_primitive(ty: &str, input: &mut &[u8]) -> Result<Value, String> {
+	let len = match ty {
+		"bool" | "u8" | "i8" => 1,
+		"u16" | "i16" => 2,
+		"u32" | "i32" => 4,
+		"u64" | "i64" => 8,
+		"u128" | "i128" => 16,
+		_ => return Err(format!("Unknown primitive type `{}`", ty)),
+	};
+	let bytes = take(input, len)?;
+	let n = bytes.iter().rev().fold(0u128, |n, b| (n << 8) | *b as u128);
+	Ok(match ty {
+		"bool" => match n {
+			0 => Value::Bool(false),
+			1 => Value::Bool(true),
+			_ => return Err("Invalid `bool`".into()),
+		},
+		"i8" | "i16" | "i32" | "i64" | "i128" => {
+			// sign-extend from the width of the type
+			let shift = 128 - 8 * len as u32;
+			let n = ((n << shift) as i128) >> shift;
+			if n == n as i64 as i128 {
+				Value::from(n as i64)
+			} else {
+				Value::String(n.to_string())
+			}
+		},
+		_ => large_number(n),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use substrate_metadata::{JsonMetadata, RuntimeMetadata};
+
+	fn field(name: Option<&str>, ty: &str) -> FieldDef {
+		FieldDef { name: name.map(Into::into), ty: ty.into() }
+	}
+
+	fn variant(name: &str, index: u8, fields: Vec<FieldDef>) -> VariantDef {
+		VariantDef { name: name.into(), index, fields }
+	}
+
+	fn metadata() -> RuntimeMetadataDecodable {
+		let types = vec![
+			("Vec<EventRecord<Event, H256>>".into(), TypeDef::Sequence("EventRecord<Event, H256>".into())),
+			("EventRecord<Event, H256>".into(), TypeDef::Struct(vec![
+				field(Some("phase"), "Phase"),
+				field(Some("event"), "Event"),
+				field(Some("topics"), "Vec<H256>"),
+			])),
+			("Phase".into(), TypeDef::Enum(vec![
+				variant("ApplyExtrinsic", 0, vec![field(None, "u32")]),
+				variant("Finalization", 1, vec![]),
+			])),
+			("u32".into(), TypeDef::Primitive),
+			("Event".into(), TypeDef::Enum(vec![
+				variant("system", 0, vec![field(None, "system::Event")]),
+				variant("balances", 1, vec![field(None, "balances::RawEvent<u64, u128>")]),
+			])),
+			("system::Event".into(), TypeDef::Enum(vec![variant("ExtrinsicSuccess", 0, vec![])])),
+			("balances::RawEvent<u64, u128>".into(), TypeDef::Enum(vec![
+				variant("NewAccount", 0, vec![field(None, "u64"), field(None, "u128")]),
+				variant("Transfer", 1, vec![field(None, "u64"), field(None, "u64"), field(None, "Compact<u128>")]),
+			])),
+			("u64".into(), TypeDef::Primitive),
+			("u128".into(), TypeDef::Primitive),
+			("Compact<u128>".into(), TypeDef::Compact("u128".into())),
+			("Vec<H256>".into(), TypeDef::Sequence("H256".into())),
+			("H256".into(), TypeDef::Array(32, "u8".into())),
+			("u8".into(), TypeDef::Primitive),
+		];
+		let metadata = RuntimeMetadata::V2(vec![
+			JsonMetadata::FullModule {
+				module: "{ }",
+				prefix: "system",
+				storage: Some(r#"{ "prefix": "System", "items": { "Events": { "type": "Vec<EventRecord<T::Event, T::Hash>>" } } }"#),
+				constants: vec![],
+				errors: "[ ]",
+				types: vec![("Vec<EventRecord<T::Event, T::Hash>>", "Vec<EventRecord<Event, H256>>".into())],
+			},
+			JsonMetadata::Types { types },
+		]);
+		RuntimeMetadataDecodable::decode(&mut &metadata.encode()[..]).unwrap()
+	}
+
+	fn events() -> Vec<u8> {
+		let mut events = 2u32.encode();
+		// ApplyExtrinsic(1), balances::Transfer(1, 2, 1 << 70), one topic
+		events.extend(vec![0, 1, 0, 0, 0, 1, 1]);
+		events.extend(1u64.encode());
+		events.extend(2u64.encode());
+		events.extend(Compact(1u128 << 70).encode());
+		events.extend(1u32.encode());
+		events.extend(vec![0xab; 32]);
+		// Finalization, system::ExtrinsicSuccess, no topics
+		events.extend(vec![1, 0, 0]);
+		events.extend(0u32.encode());
+		events
+	}
+
+	#[test]
+	fn events_are_decoded_with_the_types_of_the_metadata() {
+		let decoder = EventsDecoder::new(metadata()).unwrap();
+		let records = decoder.decode(&events()).unwrap();
+		assert_eq!(records, vec![
+			EventRecord {
+				phase: serde_json::from_str(r#"{ "ApplyExtrinsic": 1 }"#).unwrap(),
+				module: "balances".into(),
+				name: "Transfer".into(),
+				params: vec![Value::from(1u64), Value::from(2u64), Value::String((1u128 << 70).to_string())],
+				topics: vec![Value::String(format!("0x{}", "ab".repeat(32)))],
+			},
+			EventRecord {
+				phase: Value::String("Finalization".into()),
+				module: "system".into(),
+				name: "ExtrinsicSuccess".into(),
+				params: vec![],
+				topics: vec![],
+			},
+		]);
+		assert_eq!(
+			serde_json::to_string(&records[1].clone().into_json()).unwrap(),
+			r#"{"module":"system","name":"ExtrinsicSuccess","params":[],"phase":"Finalization","topics":[]}"#
+		);
+	}
+
+	#[test]
+	fn invalid_events_fail_to_decode() {
+		let decoder = EventsDecoder::new(metadata()).unwrap();
+		let events = events();
+		assert!(decoder.decode(&events[..events.len() - 1]).is_err());
+		assert!(decoder.decode(&[&events[..], &[0]].concat()).is_err());
+
+		// after the count and the phase
+		let mut unknown_module = events.clone();
+		unknown_module[4 + 5] = 2;
+		assert!(decoder.decode(&unknown_module).is_err());
+
+		// after the count, the phase, the event indices and the parameters
+		let mut long_topics = events.clone();
+		long_topics[4 + 5 + 2 + 16 + 10] = 0xff;
+		assert!(decoder.decode(&long_topics).is_err());
+	}
+
+	#[test]
+	fn metadata_without_types_is_refused() {
+		let metadata = RuntimeMetadata::V1(vec![JsonMetadata::HooksOrder { modules: &["System"] }]);
+		let metadata = RuntimeMetadataDecodable::decode(&mut &metadata.encode()[..]).unwrap();
+		assert!(EventsDecoder::new(metadata).is_err());
+	}
+}
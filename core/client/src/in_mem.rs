@@ -275,6 +275,7 @@ pub struct BlockImportOperation<Block: BlockT, H: Hasher, C: NodeCodec<H>> {
 	pending_authorities: Option<Vec<AuthorityId>>,
 	old_state: InMemory<H, C>,
 	new_state: Option<InMemory<H, C>>,
+	offchain_changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 }
 
 impl<Block, H, C> backend::BlockImportOperation<Block, H, C> for BlockImportOperation<Block, H, C>
@@ -318,6 +319,11 @@ where
 		self.new_state = Some(InMemory::from(iter.collect::<HashMap<_, _>>()));
 		Ok(())
 	}
+
+	fn update_offchain_storage<I: Iterator<Item=(Vec<u8>, Option<Vec<u8>>)>>(&mut self, changes: I) -> error::Result<()> {
+		self.offchain_changes.extend(changes);
+		Ok(())
+	}
 }
 
 /// In-memory backend. Keeps all states and blocks in memory. Useful for testing.
@@ -328,6 +334,7 @@ where
 	C: NodeCodec<H>
 {
 	states: RwLock<HashMap<Block::Hash, InMemory<H, C>>>,
+	offchain: RwLock<HashMap<Vec<u8>, Vec<u8>>>,
 	blockchain: Blockchain<Block>,
 }
 
@@ -341,6 +348,7 @@ where
 	pub fn new() -> Backend<Block, H, C> {
 		Backend {
 			states: RwLock::new(HashMap::new()),
+			offchain: RwLock::new(HashMap::new()),
 			blockchain: Blockchain::new(),
 		}
 	}
@@ -368,6 +376,7 @@ where
 			pending_authorities: None,
 			old_state: state,
 			new_state: None,
+			offchain_changes: Vec::new(),
 		})
 	}
 
@@ -385,6 +394,13 @@ where
 				self.blockchain.cache.insert(parent_hash, operation.pending_authorities);
 			}
 		}
+		let mut offchain = self.offchain.write();
+		for (key, value) in operation.offchain_changes {
+			match value {
+				Some(value) => { offchain.insert(key, value); }
+				None => { offchain.remove(&key); }
+			}
+		}
 		Ok(())
 	}
 
@@ -402,6 +418,10 @@ where
 	fn revert(&self, _n: NumberFor<Block>) -> error::Result<NumberFor<Block>> {
 		Ok(As::sa(0))
 	}
+
+	fn offchain_storage(&self, key: &[u8]) -> error::Result<Option<Vec<u8>>> {
+		Ok(self.offchain.read().get(key).cloned())
+	}
 }
 
 impl<Block, H, C> backend::LocalBackend<Block, H, C> for Backend<Block, H, C>
@@ -39,6 +39,7 @@ extern crate patricia_trie;
 extern crate hashdb;
 extern crate rlp;
 extern crate heapsize;
+extern crate serde_json;
 
 #[macro_use] extern crate error_chain;
 #[macro_use] extern crate log;
@@ -53,6 +54,7 @@ pub mod in_mem;
 pub mod genesis;
 pub mod block_builder;
 pub mod light;
+pub mod events;
 mod call_executor;
 mod client;
 mod notifications;
@@ -65,5 +67,6 @@ pub use client::{
 	Client, ClientInfo, ChainHead,
 	ImportResult, JustifiedHeader,
 };
+pub use events::EventRecord;
 pub use notifications::{StorageEventStream, StorageChangeSet};
 pub use state_machine::ExecutionStrategy;
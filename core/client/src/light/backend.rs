@@ -117,6 +117,11 @@ impl<S, F, Block, H, C> ClientBackend<Block, H, C> for Backend<S, F> where
 	fn revert(&self, _n: NumberFor<Block>) -> ClientResult<NumberFor<Block>> {
 		unimplemented!()
 	}
+
+	fn offchain_storage(&self, _key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+		// light clients don't execute blocks => there's nothing indexed
+		Ok(None)
+	}
 }
 
 impl<S, F, Block, H, C> RemoteBackend<Block, H, C> for Backend<S, F>
@@ -168,6 +173,11 @@ where
 		// we're not storing anything locally => ignore changes
 		Ok(())
 	}
+
+	fn update_offchain_storage<I: Iterator<Item=(Vec<u8>, Option<Vec<u8>>)>>(&mut self, _changes: I) -> ClientResult<()> {
+		// we're not storing anything locally => ignore changes
+		Ok(())
+	}
 }
 
 impl<Block, S, F, H, C> StateBackend<H, C> for OnDemandState<Block, S, F>
@@ -205,6 +215,10 @@ impl<Block, S, F, H, C> StateBackend<H, C> for OnDemandState<Block, S, F>
 		// whole state is not available on light node
 	}
 
+	fn next_storage_key(&self, _key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+		Err(ClientErrorKind::NotAvailableOnLightClient.into())
+	}
+
 	fn storage_root<I>(&self, _delta: I) -> (H::Out, Self::Transaction)
 		where I: IntoIterator<Item=(Vec<u8>, Option<Vec<u8>>)> {
 		(H::Out::default(), ())
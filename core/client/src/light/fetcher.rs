@@ -18,11 +18,12 @@
 
 use futures::IntoFuture;
 
-use primitives::H256;
+use primitives::{AuthorityId, H256};
 use hashdb::Hasher;
 use patricia_trie::NodeCodec;
 use rlp::Encodable;
 use heapsize::HeapSizeOf;
+use runtime_primitives::bft::Justification;
 use runtime_primitives::traits::{Block as BlockT, Header as HeaderT};
 use state_machine::{CodeExecutor, read_proof_check};
 use std::marker::PhantomData;
@@ -71,6 +72,19 @@ pub struct RemoteReadRequest<Header: HeaderT> {
 	pub retry_count: Option<usize>,
 }
 
+/// Remote block justification request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RemoteJustificationRequest<Header: HeaderT> {
+	/// Hash of the block to fetch the justification of.
+	pub block: Header::Hash,
+	/// Header of the block.
+	pub header: Header,
+	/// Authorities at the parent of the block, who must have signed the justification.
+	pub authorities: Vec<AuthorityId>,
+	/// Number of times to retry request. None means that default RETRY_COUNT is used.
+	pub retry_count: Option<usize>,
+}
+
 /// Light client data fetcher. Implementations of this trait must check if remote data
 /// is correct (see FetchedDataChecker) and return already checked data.
 pub trait Fetcher<Block: BlockT>: Send + Sync {
@@ -80,6 +94,8 @@ pub trait Fetcher<Block: BlockT>: Send + Sync {
 	type RemoteReadResult: IntoFuture<Item=Option<Vec<u8>>, Error=ClientError>;
 	/// Remote call result future.
 	type RemoteCallResult: IntoFuture<Item=CallResult, Error=ClientError>;
+	/// Remote justification future.
+	type RemoteJustificationResult: IntoFuture<Item=Justification<Block::Hash>, Error=ClientError>;
 
 	/// Fetch remote header.
 	fn remote_header(&self, request: RemoteHeaderRequest<Block::Header>) -> Self::RemoteHeaderResult;
@@ -87,6 +103,8 @@ pub trait Fetcher<Block: BlockT>: Send + Sync {
 	fn remote_read(&self, request: RemoteReadRequest<Block::Header>) -> Self::RemoteReadResult;
 	/// Fetch remote call result.
 	fn remote_call(&self, request: RemoteCallRequest<Block::Header>) -> Self::RemoteCallResult;
+	/// Fetch remote block justification.
+	fn remote_justification(&self, request: RemoteJustificationRequest<Block::Header>) -> Self::RemoteJustificationResult;
 }
 
 /// Light client remote data checker.
@@ -113,6 +131,12 @@ pub trait FetchChecker<Block: BlockT>: Send + Sync {
 		request: &RemoteCallRequest<Block::Header>,
 		remote_proof: Vec<Vec<u8>>
 	) -> ClientResult<CallResult>;
+	/// Check remote block justification.
+	fn check_justification(
+		&self,
+		request: &RemoteJustificationRequest<Block::Header>,
+		remote_justification: Option<Justification<Block::Hash>>
+	) -> ClientResult<Justification<Block::Hash>>;
 }
 
 /// Remote data checker.
@@ -173,6 +197,21 @@ impl<E, Block, H, C> FetchChecker<Block> for LightDataChecker<E, H, C>
 	) -> ClientResult<CallResult> {
 		check_execution_proof::<_, _, H, C>(&self.executor, request, remote_proof)
 	}
+
+	fn check_justification(
+		&self,
+		request: &RemoteJustificationRequest<Block::Header>,
+		remote_justification: Option<Justification<Block::Hash>>
+	) -> ClientResult<Justification<Block::Hash>> {
+		let bad_justification = || ClientError::from(ClientErrorKind::BadJustification(format!("{}", request.block)));
+		let remote_justification = remote_justification.ok_or_else(bad_justification)?;
+		if remote_justification.hash != request.block {
+			return Err(bad_justification());
+		}
+		::bft::check_justification::<Block>(&request.authorities, *request.header.parent_hash(), remote_justification.clone().into())
+			.map(|_| remote_justification)
+			.map_err(|_| bad_justification())
+	}
 }
 
 #[cfg(test)]
@@ -185,8 +224,9 @@ pub mod tests {
 	use test_client::{self, TestClient, runtime::{Hash, Block, Header}};
 	use test_client::client::BlockOrigin;
 	use in_mem::{Blockchain as InMemoryBlockchain};
+	use keyring::Keyring;
 	use light::fetcher::{Fetcher, FetchChecker, LightDataChecker,
-		RemoteCallRequest, RemoteHeaderRequest};
+		RemoteCallRequest, RemoteHeaderRequest, RemoteJustificationRequest};
 	use primitives::{Blake2Hasher, RlpCodec};
 	use runtime_primitives::generic::BlockId;
 	use state_machine::Backend;
@@ -198,6 +238,7 @@ pub mod tests {
 		type RemoteHeaderResult = FutureResult<Header, ClientError>;
 		type RemoteReadResult = FutureResult<Option<Vec<u8>>, ClientError>;
 		type RemoteCallResult = FutureResult<CallResult, ClientError>;
+		type RemoteJustificationResult = FutureResult<Justification<Hash>, ClientError>;
 
 		fn remote_header(&self, _request: RemoteHeaderRequest<Header>) -> Self::RemoteHeaderResult {
 			err("Not implemented on test node".into())
@@ -210,6 +251,10 @@ pub mod tests {
 		fn remote_call(&self, _request: RemoteCallRequest<Header>) -> Self::RemoteCallResult {
 			ok((*self.lock()).clone())
 		}
+
+		fn remote_justification(&self, _request: RemoteJustificationRequest<Header>) -> Self::RemoteJustificationResult {
+			err("Not implemented on test node".into())
+		}
 	}
 
 	fn prepare_for_read_proof_check() -> (
@@ -263,6 +308,30 @@ pub mod tests {
 		(local_checker, local_cht_root, remote_block_header, remote_header_proof)
 	}
 
+	fn prepare_for_justification_check() -> (
+		LightDataChecker<executor::NativeExecutor<test_client::LocalExecutor>, Blake2Hasher, RlpCodec>,
+		RemoteJustificationRequest<Header>, Justification<Hash>)
+	{
+		// prepare remote client
+		let remote_client = test_client::new();
+		let builder = remote_client.new_block().unwrap();
+		remote_client.justify_and_import(BlockOrigin::Own, builder.bake().unwrap()).unwrap();
+
+		// 'fetch' justification from remote node
+		let remote_block_id = BlockId::Number(1);
+		let remote_block_header = remote_client.header(&remote_block_id).unwrap().unwrap();
+		let remote_justification = remote_client.justification(&remote_block_id).unwrap().unwrap();
+
+		let request = RemoteJustificationRequest {
+			block: remote_block_header.hash(),
+			authorities: remote_client.authorities_at(&BlockId::Number(0)).unwrap(),
+			header: remote_block_header,
+			retry_count: None,
+		};
+		let local_checker = LightDataChecker::new(test_client::LocalExecutor::new());
+		(local_checker, request, remote_justification)
+	}
+
 	#[test]
 	fn storage_read_proof_is_generated_and_checked() {
 		let (local_checker, remote_block_header, remote_read_proof, authorities_len) = prepare_for_read_proof_check();
@@ -305,4 +374,28 @@ pub mod tests {
 			retry_count: None,
 		}, Some(remote_block_header.clone()), remote_header_proof).is_err());
 	}
+
+	#[test]
+	fn justification_is_fetched_and_checked() {
+		let (local_checker, request, remote_justification) = prepare_for_justification_check();
+		assert_eq!((&local_checker as &FetchChecker<Block>).check_justification(
+			&request,
+			Some(remote_justification.clone()),
+		).unwrap(), remote_justification);
+	}
+
+	#[test]
+	fn check_justification_fails_if_signed_by_other_authorities() {
+		let (local_checker, mut request, remote_justification) = prepare_for_justification_check();
+		request.authorities = vec![Keyring::Dave.into(), Keyring::Eve.into()];
+		assert!((&local_checker as &FetchChecker<Block>).check_justification(&request, Some(remote_justification)).is_err());
+	}
+
+	#[test]
+	fn check_justification_fails_if_missing_or_for_other_block() {
+		let (local_checker, mut request, remote_justification) = prepare_for_justification_check();
+		assert!((&local_checker as &FetchChecker<Block>).check_justification(&request, None).is_err());
+		request.block = Default::default();
+		assert!((&local_checker as &FetchChecker<Block>).check_justification(&request, Some(remote_justification)).is_err());
+	}
 }
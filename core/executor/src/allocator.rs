@@ -0,0 +1,274 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Freeing allocator for the runtime heap.
+//!
+//! The heap is a region of the Wasm linear memory placed after the initial memory pages.
+//! Chunks are handed out by a bump pointer, but each chunk belongs to a size class (a power
+//! of two between 8 bytes and 16 MiB) and, once freed, is put into the free list of its class
+//! so that later allocations of the same class can reuse it.
+//!
+//! Every chunk is preceded by an 8-byte header living in the linear memory itself. While the
+//! chunk is in use the header holds its size class, while it's free it holds the pointer to
+//! the next free chunk of the same class.
+//!
+//! As the runtime may overwrite the headers, whatever is read from them is checked against the
+//! heap before being used, and a forged header is reported as an error.
+
+use wasmi::MemoryRef;
+use wasm_utils::UserError;
+
+/// Minimal size (and alignment) of an allocation.
+const ALIGNMENT: u32 = 8;
+
+/// Size of the header preceding every chunk.
+const HEADER_SIZE: u32 = 8;
+
+/// Number of size classes: 8, 16, 32, ..., 2^24.
+const N: usize = 22;
+
+/// The biggest allocation the heap can serve.
+const MAX_POSSIBLE_ALLOCATION: u32 = 1 << 24;
+
+/// Marks the end of a free list.
+const NIL: u32 = u32::max_value();
+
+/// Header flag of an occupied chunk.
+const OCCUPIED: u32 = 1;
+
+/// Header flag of a free chunk.
+const FREE: u32 = 0;
+
+fn read_u32(memory: &MemoryRef, offset: u32) -> Result<u32, UserError> {
+	use byteorder::{LittleEndian, ByteOrder};
+	let bytes = memory.get(offset, 4).map_err(|_| UserError("Invalid attempt to read heap header"))?;
+	Ok(LittleEndian::read_u32(&bytes))
+}
+
+fn write_u32(memory: &MemoryRef, offset: u32, value: u32) -> Result<(), UserError> {
+	use byteorder::{LittleEndian, ByteOrder};
+	let mut bytes = [0u8; 4];
+	LittleEndian::write_u32(&mut bytes, value);
+	memory.set(offset, &bytes).map_err(|_| UserError("Invalid attempt to write heap header"))
+}
+
+/// Allocator that serves runtime allocations out of the Wasm linear memory and is able to
+/// reuse freed chunks.
+pub struct FreeingBumpHeap {
+	memory: MemoryRef,
+	/// Address of the first chunk header.
+	start: u32,
+	/// Address of the first byte which has never been handed out.
+	bumper: u32,
+	/// Address right after the last byte of the heap.
+	end: u32,
+	/// Heads of the free lists, one for each size class.
+	heads: [u32; N],
+	/// Bytes (including headers) currently in use.
+	used: u32,
+	/// The highest value `used` has ever reached.
+	peak: u32,
+}
+
+impl FreeingBumpHeap {
+	/// Create a heap covering `size` bytes of `memory`, starting at `start`.
+	pub fn new(memory: MemoryRef, start: u32, size: u32) -> Self {
+		let start_aligned = start.saturating_add(ALIGNMENT - 1) / ALIGNMENT * ALIGNMENT;
+		FreeingBumpHeap {
+			memory,
+			start: start_aligned,
+			bumper: start_aligned,
+			end: start.saturating_add(size),
+			heads: [NIL; N],
+			used: 0,
+			peak: 0,
+		}
+	}
+
+	/// Allocate a chunk of at least `size` bytes, returning its address.
+	pub fn allocate(&mut self, size: u32) -> Result<u32, UserError> {
+		if size > MAX_POSSIBLE_ALLOCATION {
+			return Err(UserError("Requested allocation size is too large"));
+		}
+
+		let item_size = ::std::cmp::max(size, ALIGNMENT).next_power_of_two();
+		let list_index = (item_size.trailing_zeros() - ALIGNMENT.trailing_zeros()) as usize;
+
+		let header_ptr = if self.heads[list_index] != NIL {
+			// reuse a previously freed chunk of the same size class.
+			let header_ptr = self.heads[list_index];
+			let next = read_u32(&self.memory, header_ptr)?;
+			// the link lives in the runtime's memory, which may have overwritten it.
+			if next != NIL && !self.is_handed_out(next, item_size) {
+				return Err(UserError("Invalid heap header"));
+			}
+			self.heads[list_index] = next;
+			header_ptr
+		} else {
+			let header_ptr = self.bumper;
+			if self.end.saturating_sub(header_ptr) < HEADER_SIZE + item_size {
+				return Err(UserError("Requested allocation size does not fit into remaining heap space"));
+			}
+			self.bumper = header_ptr + HEADER_SIZE + item_size;
+			header_ptr
+		};
+
+		write_u32(&self.memory, header_ptr, list_index as u32)?;
+		write_u32(&self.memory, header_ptr + 4, OCCUPIED)?;
+
+		self.used = self.used.checked_add(HEADER_SIZE + item_size)
+			.ok_or(UserError("Heap usage overflow"))?;
+		if self.used > self.peak {
+			self.peak = self.used;
+		}
+
+		Ok(header_ptr + HEADER_SIZE)
+	}
+
+	/// Whether a chunk of `item_size` bytes with its header at `header_ptr` is one which was
+	/// handed out by the bump pointer.
+	fn is_handed_out(&self, header_ptr: u32, item_size: u32) -> bool {
+		header_ptr >= self.start
+			&& (header_ptr - self.start) % ALIGNMENT == 0
+			&& header_ptr.checked_add(HEADER_SIZE + item_size).map_or(false, |end| end <= self.bumper)
+	}
+
+	/// Free the chunk at `ptr` which must have been returned by `allocate` before.
+	///
+	/// Freeing the null pointer is a no-op.
+	pub fn deallocate(&mut self, ptr: u32) -> Result<(), UserError> {
+		if ptr == 0 {
+			return Ok(());
+		}
+		if ptr < self.start + HEADER_SIZE || ptr >= self.bumper {
+			return Err(UserError("Invalid attempt to free memory outside of the heap"));
+		}
+
+		let header_ptr = ptr - HEADER_SIZE;
+		if read_u32(&self.memory, header_ptr + 4)? != OCCUPIED {
+			return Err(UserError("Invalid attempt to free memory which is not allocated"));
+		}
+		let list_index = read_u32(&self.memory, header_ptr)? as usize;
+		if list_index >= N || !self.is_handed_out(header_ptr, ALIGNMENT << list_index) {
+			return Err(UserError("Invalid heap header"));
+		}
+		let used = self.used.checked_sub(HEADER_SIZE + (ALIGNMENT << list_index))
+			.ok_or(UserError("Invalid heap header"))?;
+
+		write_u32(&self.memory, header_ptr, self.heads[list_index])?;
+		write_u32(&self.memory, header_ptr + 4, FREE)?;
+		self.heads[list_index] = header_ptr;
+		self.used = used;
+
+		Ok(())
+	}
+
+	/// Bytes (including chunk headers) which are currently allocated.
+	pub fn used(&self) -> u32 {
+		self.used
+	}
+
+	/// The highest number of bytes that were allocated at the same time.
+	pub fn peak(&self) -> u32 {
+		self.peak
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use wasmi::MemoryInstance;
+	use wasmi::memory_units::Pages;
+
+	fn heap(pages: usize) -> FreeingBumpHeap {
+		let memory = MemoryInstance::alloc(Pages(pages), None).unwrap();
+		FreeingBumpHeap::new(memory, 16, (pages as u32) * 65536 - 16)
+	}
+
+	#[test]
+	fn allocations_are_aligned_and_disjoint() {
+		let mut heap = heap(1);
+		let a = heap.allocate(1).unwrap();
+		let b = heap.allocate(13).unwrap();
+		assert_eq!(a % ALIGNMENT, 0);
+		assert_eq!(b % ALIGNMENT, 0);
+		assert_eq!(b, a + 8 + HEADER_SIZE);
+	}
+
+	#[test]
+	fn freed_chunks_are_reused() {
+		let mut heap = heap(1);
+		let a = heap.allocate(100).unwrap();
+		let _b = heap.allocate(100).unwrap();
+		heap.deallocate(a).unwrap();
+		assert_eq!(heap.allocate(120).unwrap(), a);
+	}
+
+	#[test]
+	fn transient_allocations_do_not_exhaust_heap() {
+		let mut heap = heap(1);
+		for _ in 0..10_000 {
+			let ptr = heap.allocate(1024).unwrap();
+			heap.deallocate(ptr).unwrap();
+		}
+		assert_eq!(heap.used(), 0);
+		assert_eq!(heap.peak(), 1024 + HEADER_SIZE);
+	}
+
+	#[test]
+	fn out_of_space_is_reported() {
+		let mut heap = heap(1);
+		assert!(heap.allocate(65536).is_err());
+		assert!(heap.allocate(MAX_POSSIBLE_ALLOCATION + 1).is_err());
+	}
+
+	#[test]
+	fn double_free_is_reported() {
+		let mut heap = heap(1);
+		let ptr = heap.allocate(8).unwrap();
+		heap.deallocate(ptr).unwrap();
+		assert!(heap.deallocate(ptr).is_err());
+		assert!(heap.deallocate(0).is_ok());
+	}
+
+	#[test]
+	fn pointers_outside_of_the_heap_are_reported() {
+		let mut heap = heap(1);
+		let ptr = heap.allocate(8).unwrap();
+		assert!(heap.deallocate(8).is_err());
+		assert!(heap.deallocate(ptr + 8).is_err());
+		assert!(heap.deallocate(u32::max_value()).is_err());
+	}
+
+	#[test]
+	fn forged_headers_are_reported() {
+		let mut heap = heap(1);
+		let ptr = heap.allocate(8).unwrap();
+		// claim the chunk is of the biggest class, which would underflow the usage.
+		write_u32(&heap.memory, ptr - HEADER_SIZE, (N - 1) as u32).unwrap();
+		assert!(heap.deallocate(ptr).is_err());
+		assert_eq!(heap.used(), 8 + HEADER_SIZE);
+
+		write_u32(&heap.memory, ptr - HEADER_SIZE, 0).unwrap();
+		heap.deallocate(ptr).unwrap();
+		// link the free chunk to one past the end of the heap.
+		write_u32(&heap.memory, ptr - HEADER_SIZE, u32::max_value() - 4).unwrap();
+		assert!(heap.allocate(8).is_err());
+		// or to one which isn't at a chunk boundary.
+		write_u32(&heap.memory, ptr - HEADER_SIZE, ptr - 4).unwrap();
+		assert!(heap.allocate(8).is_err());
+	}
+}
@@ -69,6 +69,7 @@ mod wasm_executor;
 #[macro_use]
 mod native_executor;
 mod sandbox;
+mod allocator;
 
 pub mod error;
 pub use wasm_executor::WasmExecutor;
@@ -149,12 +149,20 @@ pub trait SandboxCapabilities {
 	/// Allocate space of the specified length in the supervisor memory.
 	///
 	/// Returns pointer to the allocated block.
-	fn allocate(&mut self, len: u32) -> u32;
+	///
+	/// # Errors
+	///
+	/// Returns `Err` if the supervisor heap is exhausted.
+	fn allocate(&mut self, len: u32) -> Result<u32, UserError>;
 
 	/// Deallocate space specified by the pointer that was previously returned by [`allocate`].
 	///
 	/// [`allocate`]: #tymethod.allocate
-	fn deallocate(&mut self, ptr: u32);
+	///
+	/// # Errors
+	///
+	/// Returns `Err` if `ptr` doesn't point to an allocated block.
+	fn deallocate(&mut self, ptr: u32) -> Result<(), UserError>;
 
 	/// Write `data` into the supervisor memory at offset specified by `ptr`.
 	///
@@ -232,7 +240,7 @@ impl<'a, FE: SandboxCapabilities + Externals + 'a> Externals for GuestExternals<
 		// Move serialized arguments inside the memory and invoke dispatch thunk and
 		// then free allocated memory.
 		let invoke_args_ptr = self.supervisor_externals
-			.allocate(invoke_args_data.len() as u32);
+			.allocate(invoke_args_data.len() as u32)?;
 		self.supervisor_externals
 			.write_memory(invoke_args_ptr, &invoke_args_data)?;
 		let result = ::wasmi::FuncInstance::invoke(
@@ -245,7 +253,7 @@ impl<'a, FE: SandboxCapabilities + Externals + 'a> Externals for GuestExternals<
 			],
 			self.supervisor_externals,
 		);
-		self.supervisor_externals.deallocate(invoke_args_ptr);
+		self.supervisor_externals.deallocate(invoke_args_ptr)?;
 
 		// dispatch_thunk returns pointer to serialized arguments.
 		let (serialized_result_val_ptr, serialized_result_val_len) = match result {
@@ -263,7 +271,7 @@ impl<'a, FE: SandboxCapabilities + Externals + 'a> Externals for GuestExternals<
 		let serialized_result_val = self.supervisor_externals
 			.read_memory(serialized_result_val_ptr, serialized_result_val_len)?;
 		self.supervisor_externals
-			.deallocate(serialized_result_val_ptr);
+			.deallocate(serialized_result_val_ptr)?;
 
 		// We do not have to check the signature here, because it's automatically
 		// checked by wasmi.
@@ -26,42 +26,32 @@ use wasmi::memory_units::{Pages, Bytes};
 use state_machine::Externalities;
 use error::{Error, ErrorKind, Result};
 use wasm_utils::UserError;
-use primitives::{blake2_256, twox_128, twox_256, ed25519};
+use primitives::{blake2_256, twox_128, ed25519, sr25519, ecdsa};
+#[cfg(feature = "bls")]
+use primitives::bls;
 use primitives::hexdisplay::HexDisplay;
 use primitives::sandbox as sandbox_primitives;
 use primitives::Blake2Hasher;
 use triehash::ordered_trie_root;
 use sandbox;
+use allocator::FreeingBumpHeap;
 
 
-struct Heap {
-	end: u32,
-}
-
-impl Heap {
-	/// Construct new `Heap` struct with a given number of pages.
-	///
-	/// Returns `Err` if the heap couldn't allocate required
-	/// number of pages.
-	///
-	/// This could mean that wasm binary specifies memory
-	/// limit and we are trying to allocate beyond that limit.
-	fn new(memory: &MemoryRef, pages: usize) -> Result<Self> {
-		let prev_page_count = memory.initial();
-		memory.grow(Pages(pages)).map_err(|_| Error::from(ErrorKind::Runtime))?;
-		Ok(Heap {
-			end: Bytes::from(prev_page_count).0 as u32,
-		})
-	}
-
-	fn allocate(&mut self, size: u32) -> u32 {
-		let r = self.end;
-		self.end += size;
-		r
-	}
-
-	fn deallocate(&mut self, _offset: u32) {
-	}
+/// Grow `memory` by the given number of pages and build the runtime heap on top of them.
+///
+/// Returns `Err` if the memory couldn't be grown by the required
+/// number of pages.
+///
+/// This could mean that wasm binary specifies memory
+/// limit and we are trying to allocate beyond that limit.
+fn new_heap(memory: &MemoryRef, pages: usize) -> Result<FreeingBumpHeap> {
+	let prev_page_count = memory.initial();
+	memory.grow(Pages(pages)).map_err(|_| Error::from(ErrorKind::Runtime))?;
+	Ok(FreeingBumpHeap::new(
+		memory.clone(),
+		Bytes::from(prev_page_count).0 as u32,
+		Bytes::from(Pages(pages)).0 as u32,
+	))
 }
 
 #[cfg(feature="wasm-extern-trace")]
@@ -75,7 +65,7 @@ macro_rules! debug_trace {
 
 struct FunctionExecutor<'e, E: Externalities<Blake2Hasher> + 'e> {
 	sandbox_store: sandbox::Store,
-	heap: Heap,
+	heap: FreeingBumpHeap,
 	memory: MemoryRef,
 	table: Option<TableRef>,
 	ext: &'e mut E,
@@ -86,7 +76,7 @@ impl<'e, E: Externalities<Blake2Hasher>> FunctionExecutor<'e, E> {
 	fn new(m: MemoryRef, heap_pages: usize, t: Option<TableRef>, e: &'e mut E) -> Result<Self> {
 		Ok(FunctionExecutor {
 			sandbox_store: sandbox::Store::new(),
-			heap: Heap::new(&m, heap_pages)?,
+			heap: new_heap(&m, heap_pages)?,
 			memory: m,
 			table: t,
 			ext: e,
@@ -102,10 +92,10 @@ impl<'e, E: Externalities<Blake2Hasher>> sandbox::SandboxCapabilities for Functi
 	fn store_mut(&mut self) -> &mut sandbox::Store {
 		&mut self.sandbox_store
 	}
-	fn allocate(&mut self, len: u32) -> u32 {
+	fn allocate(&mut self, len: u32) -> ::std::result::Result<u32, UserError> {
 		self.heap.allocate(len)
 	}
-	fn deallocate(&mut self, ptr: u32) {
+	fn deallocate(&mut self, ptr: u32) -> ::std::result::Result<(), UserError> {
 		self.heap.deallocate(ptr)
 	}
 	fn write_memory(&mut self, ptr: u32, data: &[u8]) -> ::std::result::Result<(), UserError> {
@@ -116,6 +106,24 @@ impl<'e, E: Externalities<Blake2Hasher>> sandbox::SandboxCapabilities for Functi
 	}
 }
 
+impl<'e, E: Externalities<Blake2Hasher>> runtime_io::HostContext for FunctionExecutor<'e, E> {
+	fn read_memory(&self, ptr: u32, len: u32) -> ::std::result::Result<Vec<u8>, &'static str> {
+		if len == 0 {
+			return Ok(Vec::new());
+		}
+		self.memory.get(ptr, len as usize).map_err(|_| "Invalid attempt to read_memory")
+	}
+	fn write_memory(&mut self, ptr: u32, data: &[u8]) -> ::std::result::Result<(), &'static str> {
+		self.memory.set(ptr, data).map_err(|_| "Invalid attempt to write_memory")
+	}
+	fn allocate(&mut self, len: u32) -> ::std::result::Result<u32, &'static str> {
+		self.heap.allocate(len).map_err(|e| e.0)
+	}
+	fn externalities(&mut self) -> &mut Externalities<Blake2Hasher> {
+		self.ext
+	}
+}
+
 trait WritePrimitive<T: Sized> {
 	fn write_primitive(&self, offset: u32, t: T) -> ::std::result::Result<(), UserError>;
 }
@@ -140,6 +148,30 @@ impl ReadPrimitive<u32> for MemoryInstance {
 	}
 }
 
+/// Verify a BLS12-381 signature, which needs the `bls` feature; without it, a runtime calling the
+/// BLS host functions traps.
+#[cfg(feature = "bls")]
+fn bls_verify(sig: &[u8], msg: &[u8], pubkey: &[u8]) -> ::std::result::Result<bool, UserError> {
+	Ok(bls::verify(sig, msg, pubkey))
+}
+
+#[cfg(not(feature = "bls"))]
+fn bls_verify(_sig: &[u8], _msg: &[u8], _pubkey: &[u8]) -> ::std::result::Result<bool, UserError> {
+	Err(UserError("BLS signatures are not supported without the `bls` feature"))
+}
+
+/// Verify an aggregated BLS12-381 signature of each of `msgs` by the key at the same index of
+/// `pubkeys`, which needs the `bls` feature.
+#[cfg(feature = "bls")]
+fn bls_verify_aggregate(sig: &[u8], msgs: &[Vec<u8>], pubkeys: &[u8]) -> ::std::result::Result<bool, UserError> {
+	Ok(bls::verify_aggregate(sig, msgs.iter().zip(pubkeys.chunks(48))))
+}
+
+#[cfg(not(feature = "bls"))]
+fn bls_verify_aggregate(_sig: &[u8], _msgs: &[Vec<u8>], _pubkeys: &[u8]) -> ::std::result::Result<bool, UserError> {
+	Err(UserError("BLS signatures are not supported without the `bls` feature"))
+}
+
 // TODO: this macro does not support `where` clauses and that seems somewhat tricky to add
 impl_function_executor!(this: FunctionExecutor<'e, E>,
 	ext_print_utf8(utf8_data: *const u8, utf8_len: u32) => {
@@ -161,12 +193,12 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		Ok(())
 	},
 	ext_malloc(size: usize) -> *mut u8 => {
-		let r = this.heap.allocate(size);
+		let r = this.heap.allocate(size)?;
 		debug_trace!(target: "sr-io", "malloc {} bytes at {}", size, r);
 		Ok(r)
 	},
 	ext_free(addr: *mut u8) => {
-		this.heap.deallocate(addr);
+		this.heap.deallocate(addr)?;
 		debug_trace!(target: "sr-io", "free {}", addr);
 		Ok(())
 	},
@@ -192,6 +224,17 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		this.ext.clear_storage(&key);
 		Ok(())
 	},
+	ext_offchain_index_set(key_data: *const u8, key_len: u32, value_data: *const u8, value_len: u32) => {
+		let key = this.memory.get(key_data, key_len as usize).map_err(|_| UserError("Invalid attempt to determine key in ext_offchain_index_set"))?;
+		let value = this.memory.get(value_data, value_len as usize).map_err(|_| UserError("Invalid attempt to determine value in ext_offchain_index_set"))?;
+		this.ext.offchain_index_set(&key, &value);
+		Ok(())
+	},
+	ext_offchain_index_clear(key_data: *const u8, key_len: u32) => {
+		let key = this.memory.get(key_data, key_len as usize).map_err(|_| UserError("Invalid attempt to determine key in ext_offchain_index_clear"))?;
+		this.ext.offchain_index_clear(&key);
+		Ok(())
+	},
 	ext_exists_storage(key_data: *const u8, key_len: u32) -> u32 => {
 		let key = this.memory.get(key_data, key_len as usize).map_err(|_| UserError("Invalid attempt to determine key in ext_exists_storage"))?;
 		Ok(if this.ext.exists_storage(&key) { 1 } else { 0 })
@@ -221,7 +264,7 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		);
 
 		if let Some(value) = maybe_value {
-			let offset = this.heap.allocate(value.len() as u32) as u32;
+			let offset = this.heap.allocate(value.len() as u32)?;
 			this.memory.set(offset, &value).map_err(|_| UserError("Invalid attempt to set memory in ext_get_allocated_storage"))?;
 			this.memory.write_primitive(written_out, value.len() as u32)
 				.map_err(|_| UserError("Invalid attempt to write written_out in ext_get_allocated_storage"))?;
@@ -306,15 +349,6 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 		this.memory.set(out, &result).map_err(|_| UserError("Invalid attempt to set result in ext_twox_128"))?;
 		Ok(())
 	},
-	ext_twox_256(data: *const u8, len: u32, out: *mut u8) => {
-		let result = if len == 0 {
-			twox_256(&[0u8; 0])
-		} else {
-			twox_256(&this.memory.get(data, len as usize).map_err(|_| UserError("Invalid attempt to get data in ext_twox_256"))?)
-		};
-		this.memory.set(out, &result).map_err(|_| UserError("Invalid attempt to set result in ext_twox_256"))?;
-		Ok(())
-	},
 	ext_blake2_256(data: *const u8, len: u32, out: *mut u8) => {
 		let result = if len == 0 {
 			blake2_256(&[0u8; 0])
@@ -337,6 +371,84 @@ impl_function_executor!(this: FunctionExecutor<'e, E>,
 			5
 		})
 	},
+	ext_sr25519_verify(msg_data: *const u8, msg_len: u32, sig_data: *const u8, pubkey_data: *const u8) -> u32 => {
+		let mut sig = [0u8; 64];
+		this.memory.get_into(sig_data, &mut sig[..]).map_err(|_| UserError("Invalid attempt to get signature in ext_sr25519_verify"))?;
+		let mut pubkey = [0u8; 32];
+		this.memory.get_into(pubkey_data, &mut pubkey[..]).map_err(|_| UserError("Invalid attempt to get pubkey in ext_sr25519_verify"))?;
+		let msg = this.memory.get(msg_data, msg_len as usize).map_err(|_| UserError("Invalid attempt to get message in ext_sr25519_verify"))?;
+
+		Ok(if sr25519::verify(&sig, &msg, &pubkey) {
+			0
+		} else {
+			5
+		})
+	},
+	ext_bls_verify(msg_data: *const u8, msg_len: u32, sig_data: *const u8, pubkey_data: *const u8) -> u32 => {
+		let mut sig = [0u8; 96];
+		this.memory.get_into(sig_data, &mut sig[..]).map_err(|_| UserError("Invalid attempt to get signature in ext_bls_verify"))?;
+		let mut pubkey = [0u8; 48];
+		this.memory.get_into(pubkey_data, &mut pubkey[..]).map_err(|_| UserError("Invalid attempt to get pubkey in ext_bls_verify"))?;
+		let msg = this.memory.get(msg_data, msg_len as usize).map_err(|_| UserError("Invalid attempt to get message in ext_bls_verify"))?;
+
+		Ok(if bls_verify(&sig[..], &msg, &pubkey[..])? {
+			0
+		} else {
+			5
+		})
+	},
+	ext_bls_aggregate_verify(sig_data: *const u8, pubkeys_data: *const u8, msgs_data: *const u8, msg_lens_data: *const u32, count: u32) -> u32 => {
+		let mut sig = [0u8; 96];
+		this.memory.get_into(sig_data, &mut sig[..]).map_err(|_| UserError("Invalid attempt to get signature in ext_bls_aggregate_verify"))?;
+		let overflow = || UserError("Invalid attempt to get messages out of bounds in ext_bls_aggregate_verify");
+		let pubkeys_len = count.checked_mul(48).ok_or_else(overflow)?;
+		let pubkeys = this.memory.get(pubkeys_data, pubkeys_len as usize).map_err(|_| UserError("Invalid attempt to get pubkeys in ext_bls_aggregate_verify"))?;
+		let mut msgs = Vec::new();
+		let mut offset = 0u32;
+		for i in 0..count {
+			let len_ptr = i.checked_mul(4).and_then(|o| msg_lens_data.checked_add(o)).ok_or_else(overflow)?;
+			let len: u32 = this.memory.read_primitive(len_ptr)?;
+			let msg_ptr = msgs_data.checked_add(offset).ok_or_else(overflow)?;
+			msgs.push(this.memory.get(msg_ptr, len as usize).map_err(|_| UserError("Invalid attempt to get message in ext_bls_aggregate_verify"))?);
+			offset = offset.checked_add(len).ok_or_else(overflow)?;
+		}
+
+		Ok(if bls_verify_aggregate(&sig[..], &msgs, &pubkeys)? {
+			0
+		} else {
+			5
+		})
+	},
+	ext_secp256k1_ecdsa_recover(msg_data: *const u8, sig_data: *const u8, pubkey_data: *mut u8) -> u32 => {
+		let mut sig = [0u8; 65];
+		this.memory.get_into(sig_data, &mut sig[..]).map_err(|_| UserError("Invalid attempt to get signature in ext_secp256k1_ecdsa_recover"))?;
+		let mut msg = [0u8; 32];
+		this.memory.get_into(msg_data, &mut msg[..]).map_err(|_| UserError("Invalid attempt to get message in ext_secp256k1_ecdsa_recover"))?;
+
+		match ecdsa::recover(&sig, &msg) {
+			Ok(pubkey) => {
+				this.memory.set(pubkey_data, &pubkey[..]).map_err(|_| UserError("Invalid attempt to set pubkey in ext_secp256k1_ecdsa_recover"))?;
+				Ok(0)
+			}
+			Err(ecdsa::RecoverError::BadV) => Ok(1),
+			Err(ecdsa::RecoverError::BadSignature) => Ok(2),
+		}
+	},
+	ext_secp256k1_ecdsa_recover_compressed(msg_data: *const u8, sig_data: *const u8, pubkey_data: *mut u8) -> u32 => {
+		let mut sig = [0u8; 65];
+		this.memory.get_into(sig_data, &mut sig[..]).map_err(|_| UserError("Invalid attempt to get signature in ext_secp256k1_ecdsa_recover_compressed"))?;
+		let mut msg = [0u8; 32];
+		this.memory.get_into(msg_data, &mut msg[..]).map_err(|_| UserError("Invalid attempt to get message in ext_secp256k1_ecdsa_recover_compressed"))?;
+
+		match ecdsa::recover_compressed(&sig, &msg) {
+			Ok(pubkey) => {
+				this.memory.set(pubkey_data, &pubkey[..]).map_err(|_| UserError("Invalid attempt to set pubkey in ext_secp256k1_ecdsa_recover_compressed"))?;
+				Ok(0)
+			}
+			Err(ecdsa::RecoverError::BadV) => Ok(1),
+			Err(ecdsa::RecoverError::BadSignature) => Ok(2),
+		}
+	},
 	ext_sandbox_instantiate(dispatch_thunk_idx: usize, wasm_ptr: *const u8, wasm_len: usize, imports_ptr: *const u8, imports_len: usize, state: usize) -> u32 => {
 		let wasm = this.memory.get(wasm_ptr, wasm_len as usize).map_err(|_| UserError("Sandbox error"))?;
 		let raw_env_def = this.memory.get(imports_ptr, imports_len as usize).map_err(|_| UserError("Sandbox error"))?;
@@ -503,7 +615,7 @@ impl WasmExecutor {
 		let instance = intermediate_instance.run_start(&mut fec)?;
 
 		let size = data.len() as u32;
-		let offset = fec.heap.allocate(size);
+		let offset = fec.heap.allocate(size).map_err(|_| Error::from(ErrorKind::Runtime))?;
 		memory.set(offset, &data)?;
 
 		let result = instance.invoke_export(
@@ -515,6 +627,14 @@ impl WasmExecutor {
 			&mut fec
 		);
 
+		trace!(
+			target: "wasm-heap",
+			"Heap usage after `{}`: {} bytes in use, {} bytes peak",
+			method,
+			fec.heap.used(),
+			fec.heap.peak()
+		);
+
 		let returned = match result {
 			Ok(x) => x,
 			Err(e) => {
@@ -540,6 +660,7 @@ mod tests {
 	use super::*;
 	use codec::Encode;
 	use state_machine::TestExternalities;
+	use primitives::{twox_256, keccak_256, sha2_256};
 
 	// TODO: move into own crate.
 	macro_rules! map {
@@ -610,6 +731,19 @@ mod tests {
 		assert_eq!(expected, ext);
 	}
 
+	#[test]
+	fn offchain_index_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = include_bytes!("../wasm/target/wasm32-unknown-unknown/release/runtime_test.compact.wasm");
+
+		let output = WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_offchain_index", b"tx-hash").unwrap();
+
+		assert_eq!(output, b"all ok!".to_vec());
+		assert_eq!(ext.offchain_storage(b"tx-hash"), Some(b"block".to_vec()));
+		assert_eq!(ext.offchain_storage(b"cleared"), None);
+		assert_eq!(ext.storage(b"tx-hash"), None);
+	}
+
 	#[test]
 	fn blake2_256_should_work() {
 		let mut ext = TestExternalities::default();
@@ -624,6 +758,34 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn keccak_256_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = include_bytes!("../wasm/target/wasm32-unknown-unknown/release/runtime_test.compact.wasm");
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_keccak_256", &[]).unwrap(),
+			hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+		);
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_keccak_256", b"Hello world!").unwrap(),
+			keccak_256(&b"Hello world!"[..]).encode()
+		);
+	}
+
+	#[test]
+	fn sha2_256_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = include_bytes!("../wasm/target/wasm32-unknown-unknown/release/runtime_test.compact.wasm");
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_sha2_256", &[]).unwrap(),
+			hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+		);
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_sha2_256", b"Hello world!").unwrap(),
+			sha2_256(&b"Hello world!"[..]).encode()
+		);
+	}
+
 	#[test]
 	fn twox_256_should_work() {
 		let mut ext = TestExternalities::default();
@@ -678,6 +840,115 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn sr25519_verify_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = include_bytes!("../wasm/target/wasm32-unknown-unknown/release/runtime_test.compact.wasm");
+		let key = sr25519::Pair::from_seed(&blake2_256(b"test"));
+		let sig = key.sign(b"all ok!");
+		let mut calldata = vec![];
+		calldata.extend_from_slice(key.public().as_ref());
+		calldata.extend_from_slice(sig.as_ref());
+
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_sr25519_verify", &calldata).unwrap(),
+			vec![1]
+		);
+
+		// An ed25519 signature of the same key bytes isn't a valid sr25519 one.
+		let other_sig = ed25519::Pair::from_seed(&blake2_256(b"test")).sign(b"all ok!");
+		let mut calldata = vec![];
+		calldata.extend_from_slice(key.public().as_ref());
+		calldata.extend_from_slice(other_sig.as_ref());
+
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_sr25519_verify", &calldata).unwrap(),
+			vec![0]
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "bls")]
+	fn bls_verify_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = include_bytes!("../wasm/target/wasm32-unknown-unknown/release/runtime_test.compact.wasm");
+		let key = bls::Pair::from_seed(&blake2_256(b"test"));
+		let mut calldata = vec![];
+		calldata.extend_from_slice(key.public().as_ref());
+		calldata.extend_from_slice(key.sign(b"all ok!").as_ref());
+
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_bls_verify", &calldata).unwrap(),
+			vec![1]
+		);
+
+		let mut calldata = vec![];
+		calldata.extend_from_slice(key.public().as_ref());
+		calldata.extend_from_slice(key.sign(b"all is not ok!").as_ref());
+
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_bls_verify", &calldata).unwrap(),
+			vec![0]
+		);
+	}
+
+	#[test]
+	#[cfg(feature = "bls")]
+	fn bls_aggregate_verify_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = include_bytes!("../wasm/target/wasm32-unknown-unknown/release/runtime_test.compact.wasm");
+		let key_a = bls::Pair::from_seed(&blake2_256(b"a"));
+		let key_b = bls::Pair::from_seed(&blake2_256(b"b"));
+		let sig = bls::aggregate(&[key_a.sign(b"zero"), key_b.sign(b"one")]).unwrap();
+		let mut calldata = vec![];
+		calldata.extend_from_slice(sig.as_ref());
+		calldata.extend_from_slice(key_a.public().as_ref());
+		calldata.extend_from_slice(key_b.public().as_ref());
+
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_bls_aggregate_verify", &calldata).unwrap(),
+			vec![1]
+		);
+
+		let sig = bls::aggregate(&[key_a.sign(b"one"), key_b.sign(b"zero")]).unwrap();
+		let mut calldata = vec![];
+		calldata.extend_from_slice(sig.as_ref());
+		calldata.extend_from_slice(key_a.public().as_ref());
+		calldata.extend_from_slice(key_b.public().as_ref());
+
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_bls_aggregate_verify", &calldata).unwrap(),
+			vec![0]
+		);
+	}
+
+	#[test]
+	fn secp256k1_ecdsa_recover_should_work() {
+		let mut ext = TestExternalities::default();
+		let test_code = include_bytes!("../wasm/target/wasm32-unknown-unknown/release/runtime_test.compact.wasm");
+		let key = ecdsa::Pair::from_seed(&blake2_256(b"test")).unwrap();
+		let sig = key.sign(b"all ok!");
+		let mut calldata = vec![];
+		calldata.extend_from_slice(sig.as_ref());
+		calldata.extend_from_slice(&blake2_256(b"all ok!"));
+
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_secp256k1_ecdsa_recover", &calldata).unwrap(),
+			key.public().as_ref().to_vec()
+		);
+
+		let mut bad_v = sig.clone();
+		bad_v.0[64] = 42;
+		let mut calldata = vec![];
+		calldata.extend_from_slice(bad_v.as_ref());
+		calldata.extend_from_slice(&blake2_256(b"all ok!"));
+
+		assert_eq!(
+			WasmExecutor::new().call(&mut ext, 8, &test_code[..], "test_secp256k1_ecdsa_recover", &calldata).unwrap(),
+			Vec::<u8>::new()
+		);
+	}
+
 	#[test]
 	fn enumerated_trie_root_should_work() {
 		let mut ext = TestExternalities::default();
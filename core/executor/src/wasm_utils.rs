@@ -16,8 +16,9 @@
 
 //! Rust implementation of Substrate contracts.
 
-use wasmi::{ValueType, RuntimeValue, HostError};
+use wasmi::{ValueType, RuntimeValue, HostError, Signature, FuncRef, FuncInstance, Trap};
 use wasmi::nan_preserving_float::{F32, F64};
+use runtime_io::{self, HostContext};
 use std::fmt;
 
 #[derive(Debug)]
@@ -66,7 +67,9 @@ macro_rules! gen_signature {
 }
 
 macro_rules! resolve_fn {
-	(@iter $index:expr, $sig_var:ident, $name_var:ident) => ();
+	(@iter $index:expr, $sig_var:ident, $name_var:ident) => (
+		return $crate::wasm_utils::resolve_host_function($sig_var, $name_var, $index);
+	);
 	(@iter $index:expr, $sig_var:ident, $name_var:ident $name:ident ( $( $params:ty ),* ) $( -> $returns:ty )* => $($tail:tt)* ) => (
 		if $name_var == stringify!($name) {
 			let signature = gen_signature!( ( $( $params ),* ) $( -> $returns )* );
@@ -120,6 +123,54 @@ where
 	f
 }
 
+fn value_type(ty: runtime_io::ValueType) -> ValueType {
+	match ty {
+		runtime_io::ValueType::I32 => ValueType::I32,
+		runtime_io::ValueType::I64 => ValueType::I64,
+	}
+}
+
+/// Resolve `name` among the host functions declared with `#[runtime_interface]` in `sr-io`,
+/// numbering them from `offset` on.
+pub fn resolve_host_function(signature: &Signature, name: &str, offset: usize) -> Result<FuncRef, ::wasmi::Error> {
+	for (index, function) in runtime_io::host_functions().into_iter().enumerate() {
+		if function.name == name {
+			let params = function.params.iter().cloned().map(value_type).collect::<Vec<_>>();
+			let expected = Signature::new(params, function.ret.map(value_type));
+			if signature != &expected {
+				return Err(::wasmi::Error::Instantiation(
+					format!("Export {} has different signature {:?}", name, signature),
+				));
+			}
+			return Ok(FuncInstance::alloc_host(expected, offset + index));
+		}
+	}
+
+	Err(::wasmi::Error::Instantiation(
+		format!("Export {} not found", name),
+	))
+}
+
+/// Invoke the `index`th host function declared with `#[runtime_interface]` in `sr-io`.
+pub fn invoke_host_function<'a, I: Iterator<Item=&'a RuntimeValue>>(
+	context: &mut HostContext,
+	index: usize,
+	args: I,
+) -> Result<Option<RuntimeValue>, Trap> {
+	let function = runtime_io::host_functions()[index];
+	let args = args.map(|arg| match *arg {
+		RuntimeValue::I32(v) => v as u32 as u64,
+		RuntimeValue::I64(v) => v as u64,
+		_ => panic!("host functions only take integers; signatures are checked at resolve time; qed"),
+	}).collect::<Vec<_>>();
+
+	let result = (function.call)(context, &args).map_err(UserError)?;
+	Ok(result.map(|r| match function.ret {
+		Some(runtime_io::ValueType::I64) => RuntimeValue::I64(r as i64),
+		_ => RuntimeValue::I32(r as u32 as i32),
+	}))
+}
+
 #[macro_export]
 macro_rules! marshall {
 	( $args_iter:ident, $objectname:ident, ( $( $names:ident : $params:ty ),* ) -> $returns:ty => $body:tt ) => ({
@@ -146,8 +197,9 @@ macro_rules! dispatch_fn {
 		// externals are always invoked with index given by resolve_fn! at resolve time;
 		// For each next function resolve_fn! gives new index, starting from 0;
 		// Both dispatch_fn! and resolve_fn! are called with the same list of functions;
+		// past the end of the list, resolve_fn! numbers the host functions of `sr-io` from `$index`;
 		// qed;
-		panic!("fn with index {} is undefined", $index);
+		return $crate::wasm_utils::invoke_host_function($objectname, $index_ident - ($index), $args_iter);
 	};
 
 	( @iter $index:expr, $index_ident:ident, $objectname:ident, $args_iter:ident, $name:ident ( $( $names:ident : $params:ty ),* ) $( -> $returns:ty )* => $body:tt $($tail:tt)*) => (
@@ -174,10 +226,6 @@ macro_rules! impl_function_executor {
 				impl $crate::wasmi::ModuleImportResolver for Resolver {
 					fn resolve_func(&self, name: &str, signature: &$crate::wasmi::Signature) -> ::std::result::Result<$crate::wasmi::FuncRef, $crate::wasmi::Error> {
 						resolve_fn!(signature, name, $( $name( $( $params ),* ) $( -> $returns )* => )*);
-
-						Err($crate::wasmi::Error::Instantiation(
-							format!("Export {} not found", name),
-						))
 					}
 				}
 				&Resolver
@@ -12,8 +12,9 @@ extern crate sr_sandbox as sandbox;
 extern crate substrate_primitives;
 
 use runtime_io::{
-	set_storage, storage, clear_prefix, print, blake2_256,
-	twox_128, twox_256, ed25519_verify, enumerated_trie_root
+	set_storage, storage, clear_prefix, offchain_index_set, offchain_index_clear, print, blake2_256, keccak_256, sha2_256,
+	twox_128, twox_256, ed25519_verify, sr25519_verify, bls_verify, bls_aggregate_verify, secp256k1_ecdsa_recover_compressed,
+	enumerated_trie_root
 };
 
 impl_stubs!(
@@ -34,6 +35,12 @@ impl_stubs!(
 		clear_prefix(input);
 		b"all ok!".to_vec()
 	},
+	test_offchain_index NO_DECODE => |input| {
+		offchain_index_set(b"cleared", b"value");
+		offchain_index_set(input, b"block");
+		offchain_index_clear(b"cleared");
+		b"all ok!".to_vec()
+	},
 	test_empty_return NO_DECODE => |_| Vec::new(),
 	test_panic NO_DECODE => |_| panic!("test panic"),
 	test_conditional_panic NO_DECODE => |input: &[u8]| {
@@ -43,6 +50,8 @@ impl_stubs!(
 		input.to_vec()
 	},
 	test_blake2_256 NO_DECODE => |input| blake2_256(input).to_vec(),
+	test_keccak_256 NO_DECODE => |input| keccak_256(input).to_vec(),
+	test_sha2_256 NO_DECODE => |input| sha2_256(input).to_vec(),
 	test_twox_256 NO_DECODE => |input| twox_256(input).to_vec(),
 	test_twox_128 NO_DECODE => |input| twox_128(input).to_vec(),
 	test_ed25519_verify NO_DECODE => |input: &[u8]| {
@@ -55,6 +64,46 @@ impl_stubs!(
 		let msg = b"all ok!";
 		[ed25519_verify(&sig, &msg[..], &pubkey) as u8].to_vec()
 	},
+	test_sr25519_verify NO_DECODE => |input: &[u8]| {
+		let mut pubkey = [0; 32];
+		let mut sig = [0; 64];
+
+		pubkey.copy_from_slice(&input[0..32]);
+		sig.copy_from_slice(&input[32..96]);
+
+		let msg = b"all ok!";
+		[sr25519_verify(&sig, &msg[..], &pubkey) as u8].to_vec()
+	},
+	test_bls_verify NO_DECODE => |input: &[u8]| {
+		let mut pubkey = [0; 48];
+		let mut sig = [0; 96];
+
+		pubkey.copy_from_slice(&input[0..48]);
+		sig.copy_from_slice(&input[48..144]);
+
+		let msg = b"all ok!";
+		[bls_verify(&sig, &msg[..], &pubkey[..]) as u8].to_vec()
+	},
+	test_bls_aggregate_verify NO_DECODE => |input: &[u8]| {
+		let mut sig = [0; 96];
+		let mut pubkey_a = [0; 48];
+		let mut pubkey_b = [0; 48];
+
+		sig.copy_from_slice(&input[0..96]);
+		pubkey_a.copy_from_slice(&input[96..144]);
+		pubkey_b.copy_from_slice(&input[144..192]);
+
+		[bls_aggregate_verify(&sig, &[(&b"zero"[..], &pubkey_a), (&b"one"[..], &pubkey_b)]) as u8].to_vec()
+	},
+	test_secp256k1_ecdsa_recover NO_DECODE => |input: &[u8]| {
+		let mut sig = [0; 65];
+		let mut msg = [0; 32];
+
+		sig.copy_from_slice(&input[0..65]);
+		msg.copy_from_slice(&input[65..97]);
+
+		secp256k1_ecdsa_recover_compressed(&sig, &msg).map(|pubkey| pubkey.to_vec()).unwrap_or_default()
+	},
 	test_enumerated_trie_root NO_DECODE => |_| {
 		enumerated_trie_root::<substrate_primitives::Blake2Hasher>(&[&b"zero"[..], &b"one"[..], &b"two"[..]]).to_vec()
 	},
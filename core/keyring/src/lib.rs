@@ -17,6 +17,11 @@
 // tag::description[]
 //! Support code for the runtime.
 // end::description[]
+//!
+//! The well-known development accounts, each with an ed25519 and an sr25519 key pair derived from
+//! the same seed. The node runtime, whose signature is `MultiSignature`, takes either key, as two
+//! different accounts; the other runtimes in this repository sign with the ed25519 key. The
+//! `sr25519_*` helpers give the sr25519 one.
 
 #[macro_use] extern crate hex_literal;
 #[macro_use] extern crate lazy_static;
@@ -25,7 +30,8 @@ extern crate substrate_primitives;
 use std::collections::HashMap;
 use std::ops::Deref;
 use substrate_primitives::ed25519::{Pair, Public, Signature};
-pub use substrate_primitives::ed25519;
+use substrate_primitives::{AuthorityId, H256};
+pub use substrate_primitives::{ed25519, sr25519};
 
 /// Set of test accounts.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -40,20 +46,30 @@ pub enum Keyring {
 	Two,
 }
 
+const ALL_KEYS: [Keyring; 8] = [
+	Keyring::Alice,
+	Keyring::Bob,
+	Keyring::Charlie,
+	Keyring::Dave,
+	Keyring::Eve,
+	Keyring::Ferdie,
+	Keyring::One,
+	Keyring::Two,
+];
+
 impl Keyring {
+	/// All the well-known keys.
+	pub fn iter() -> impl Iterator<Item=Keyring> {
+		ALL_KEYS.iter().cloned()
+	}
+
 	pub fn from_public(who: Public) -> Option<Keyring> {
-		[
-			Keyring::Alice,
-			Keyring::Bob,
-			Keyring::Charlie,
-			Keyring::Dave,
-			Keyring::Eve,
-			Keyring::Ferdie,
-			Keyring::One,
-			Keyring::Two,
-		].iter()
-			.map(|i| *i)
-			.find(|&k| Public::from(k) == who)
+		Self::iter().find(|&k| Public::from(k) == who)
+	}
+
+	/// Find the key with the given (case-insensitive) name, e.g. `"alice"`.
+	pub fn from_name(name: &str) -> Option<Keyring> {
+		Self::iter().find(|&k| <&'static str>::from(k).eq_ignore_ascii_case(name))
 	}
 
 	pub fn from_raw_public(who: [u8; 32]) -> Option<Keyring> {
@@ -68,22 +84,72 @@ impl Keyring {
 		Public::from(self).to_raw_vec()
 	}
 
+	pub fn from_account_id(who: &H256) -> Option<Keyring> {
+		Self::from_raw_public(who.0)
+	}
+
+	/// Find the key whose sr25519 public key is `who`.
+	pub fn from_sr25519_public(who: &sr25519::Public) -> Option<Keyring> {
+		Self::iter().find(|&k| &k.sr25519_public() == who)
+	}
+
+	/// Find the key whose sr25519 account id is `who`.
+	pub fn from_sr25519_account_id(who: &H256) -> Option<Keyring> {
+		Self::from_sr25519_public(&sr25519::Public::from_raw(who.0))
+	}
+
+	/// The account id of this key, as used by the runtimes in this repository.
+	pub fn to_account_id(self) -> H256 {
+		self.to_raw_public().into()
+	}
+
+	/// The authority id of this key, as used in the genesis configuration.
+	pub fn to_authority_id(self) -> AuthorityId {
+		self.to_raw_public().into()
+	}
+
 	pub fn sign(self, msg: &[u8]) -> Signature {
 		Pair::from(self).sign(msg)
 	}
 
-	pub fn pair(self) -> Pair {
+	/// The secret seed both key pairs of this key are made from.
+	pub fn seed(self) -> [u8; 32] {
 		match self {
-			Keyring::Alice => Pair::from_seed(b"Alice                           "),
-			Keyring::Bob => Pair::from_seed(b"Bob                             "),
-			Keyring::Charlie => Pair::from_seed(b"Charlie                         "),
-			Keyring::Dave => Pair::from_seed(b"Dave                            "),
-			Keyring::Eve => Pair::from_seed(b"Eve                             "),
-			Keyring::Ferdie => Pair::from_seed(b"Ferdie                          "),
-			Keyring::One => Pair::from_seed(b"12345678901234567890123456789012"),
-			Keyring::Two => Pair::from_seed(&hex!("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60")),
+			Keyring::Alice => *b"Alice                           ",
+			Keyring::Bob => *b"Bob                             ",
+			Keyring::Charlie => *b"Charlie                         ",
+			Keyring::Dave => *b"Dave                            ",
+			Keyring::Eve => *b"Eve                             ",
+			Keyring::Ferdie => *b"Ferdie                          ",
+			Keyring::One => *b"12345678901234567890123456789012",
+			Keyring::Two => hex!("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"),
 		}
 	}
+
+	pub fn pair(self) -> Pair {
+		Pair::from_seed(&self.seed())
+	}
+
+	/// The sr25519 key pair of this key.
+	pub fn sr25519_pair(self) -> sr25519::Pair {
+		sr25519::Pair::from_seed(&self.seed())
+	}
+
+	/// The sr25519 public key of this key.
+	pub fn sr25519_public(self) -> sr25519::Public {
+		(*SR25519_PUBLIC_KEYS).get(&self).unwrap().clone()
+	}
+
+	/// The account id of the sr25519 key of this key, for a runtime whose accounts may sign with
+	/// sr25519, e.g. with `MultiSignature`.
+	pub fn to_sr25519_account_id(self) -> H256 {
+		self.sr25519_public().0.into()
+	}
+
+	/// Sign `msg` with the sr25519 key of this key.
+	pub fn sign_sr25519(self, msg: &[u8]) -> sr25519::Signature {
+		self.sr25519_pair().sign(msg)
+	}
 }
 
 impl From<Keyring> for &'static str {
@@ -103,21 +169,16 @@ impl From<Keyring> for &'static str {
 
 lazy_static! {
 	static ref PRIVATE_KEYS: HashMap<Keyring, Pair> = {
-		[
-			Keyring::Alice,
-			Keyring::Bob,
-			Keyring::Charlie,
-			Keyring::Dave,
-			Keyring::Eve,
-			Keyring::Ferdie,
-			Keyring::One,
-			Keyring::Two,
-		].iter().map(|&i| (i, i.pair())).collect()
+		Keyring::iter().map(|i| (i, i.pair())).collect()
 	};
 
 	static ref PUBLIC_KEYS: HashMap<Keyring, Public> = {
 		PRIVATE_KEYS.iter().map(|(&name, pair)| (name, pair.public())).collect()
 	};
+
+	static ref SR25519_PUBLIC_KEYS: HashMap<Keyring, sr25519::Public> = {
+		Keyring::iter().map(|i| (i, i.sr25519_pair().public())).collect()
+	};
 }
 
 impl From<Keyring> for Public {
@@ -138,6 +199,18 @@ impl From<Keyring> for [u8; 32] {
 	}
 }
 
+impl From<Keyring> for H256 {
+	fn from(k: Keyring) -> Self {
+		k.to_account_id()
+	}
+}
+
+impl From<Keyring> for AuthorityId {
+	fn from(k: Keyring) -> Self {
+		k.to_authority_id()
+	}
+}
+
 impl From<Keyring> for &'static [u8; 32] {
 	fn from(k: Keyring) -> Self {
 		(*PUBLIC_KEYS).get(&k).unwrap().as_array_ref()
@@ -174,4 +247,23 @@ mod tests {
 		assert!(!Keyring::Alice.sign(b"I am Alice!").verify(b"I am Bob!", Keyring::Alice));
 		assert!(!Keyring::Alice.sign(b"I am Alice!").verify(b"I am Alice!", Keyring::Bob));
 	}
+
+	#[test]
+	fn lookups_work() {
+		assert_eq!(Keyring::from_name("bob"), Some(Keyring::Bob));
+		assert_eq!(Keyring::from_name("Two"), Some(Keyring::Two));
+		assert_eq!(Keyring::from_name("Mallory"), None);
+		assert_eq!(Keyring::from_account_id(&Keyring::Eve.to_account_id()), Some(Keyring::Eve));
+		assert_eq!(Keyring::iter().count(), 8);
+	}
+
+	#[test]
+	fn sr25519_keys_work() {
+		let sig = Keyring::Alice.sign_sr25519(b"I am Alice!");
+		assert!(sr25519::verify_strong(&sig, b"I am Alice!", Keyring::Alice.sr25519_public()));
+		assert!(!sr25519::verify_strong(&sig, b"I am Alice!", Keyring::Bob.sr25519_public()));
+		assert!(Keyring::Alice.sr25519_public().0 != Keyring::Alice.to_raw_public());
+		assert_eq!(Keyring::from_sr25519_account_id(&Keyring::Dave.to_sr25519_account_id()), Some(Keyring::Dave));
+		assert_eq!(Keyring::from_sr25519_account_id(&Keyring::Dave.to_account_id()), None);
+	}
 }
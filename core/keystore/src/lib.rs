@@ -170,9 +170,15 @@ impl Store {
 			&mut s[..len].copy_from_slice(&seed.as_bytes()[..len]);
 		}
 
-		let pair = Pair::from_seed(&s);
-		self.additional.insert(pair.public(), s);
-		Ok(pair)
+		Ok(self.generate_from_raw_seed(s))
+	}
+
+	/// Create a new key from the given 32-byte seed. Do not place it into the store.
+	/// This is meant to be used for testing only.
+	pub fn generate_from_raw_seed(&mut self, seed: [u8; 32]) -> Pair {
+		let pair = Pair::from_seed(&seed);
+		self.additional.insert(pair.public(), seed);
+		pair
 	}
 
 	/// Load a key file with given public key.
@@ -19,14 +19,31 @@
 //! This really doesn't belong here, but is necessary for the moment. In the future
 //! it should be removed entirely to an external module for shimming on to the
 //! codec-encoded metadata.
+//!
+//! Under `std`, both the metadata and its decodable variant implement `Serialize`, the former as
+//! the latter, so that tools can dump the metadata of a runtime as JSON.
 
 #![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(feature = "std"), feature(alloc))]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
 
 extern crate parity_codec as codec;
+#[cfg(feature = "std")]
+extern crate serde;
+#[cfg(feature = "std")]
+extern crate serde_json;
 
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
 use codec::{Encode, Output};
 #[cfg(feature = "std")]
 use codec::{Decode, Input};
+#[cfg(feature = "std")]
+use serde::{Serialize, Serializer, ser::{Error as SerError, SerializeMap, SerializeStruct}};
 
 /// The metadata of a runtime encoded as JSON.
 #[derive(Eq)]
@@ -34,7 +51,194 @@ use codec::{Decode, Input};
 pub enum JsonMetadata {
 	Events { name: &'static str, events: &'static [(&'static str, fn() -> &'static str)] },
 	Module { module: &'static str, prefix: &'static str },
-	ModuleWithStorage { module: &'static str, prefix: &'static str, storage: &'static str }
+	ModuleWithStorage { module: &'static str, prefix: &'static str, storage: &'static str },
+	/// The names of the modules in the order their hooks, e.g. `on_finalise`, are called.
+	HooksOrder { modules: &'static [&'static str] },
+	/// All about a module but its events, which are part of `Events`: its calls, its storage if
+	/// it has any, its constants and its errors.
+	FullModule {
+		module: &'static str,
+		prefix: &'static str,
+		storage: Option<&'static str>,
+		constants: Vec<ModuleConstantMetadata>,
+		errors: &'static str,
+		/// The types written in the metadata of the module, e.g. `T::Balance`, with their names
+		/// in `Types`.
+		types: Vec<(&'static str, String)>,
+	},
+	/// The structure of the types the metadata refers to, under their names, e.g. `u64` or
+	/// `Vec<u8>`.
+	Types { types: Vec<(String, TypeDef)> },
+	/// The names of the modules with their index, the one of their variant in the outer `Call`
+	/// and `Event`.
+	ModuleIndices { modules: &'static [(&'static str, u8)] },
+}
+
+/// The structure of a type, as much as is needed to encode and decode it.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum TypeDef {
+	/// A primitive type, e.g. `bool` or `u64`, named after itself.
+	Primitive,
+	/// A struct, with its fields in order; the fields of a tuple struct are unnamed.
+	Struct(Vec<FieldDef>),
+	/// An enum, with its variants in the order of their indices.
+	Enum(Vec<VariantDef>),
+	/// A sequence of items of a type, encoded after its length.
+	Sequence(String),
+	/// An array of a fixed length.
+	Array(u32, String),
+	Tuple(Vec<String>),
+	/// A number type in the `Compact` encoding.
+	Compact(String),
+}
+
+/// A field of a struct or of an enum variant.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct FieldDef {
+	pub name: Option<String>,
+	pub ty: String,
+}
+
+/// A variant of an enum.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct VariantDef {
+	pub name: String,
+	/// The byte the variant is encoded with.
+	pub index: u8,
+	pub fields: Vec<FieldDef>,
+}
+
+impl Encode for TypeDef {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		match self {
+			TypeDef::Primitive => 0i8.encode_to(dest),
+			TypeDef::Struct(fields) => {
+				1i8.encode_to(dest);
+				fields.encode_to(dest);
+			},
+			TypeDef::Enum(variants) => {
+				2i8.encode_to(dest);
+				variants.encode_to(dest);
+			},
+			TypeDef::Sequence(ty) => {
+				3i8.encode_to(dest);
+				ty.as_str().encode_to(dest);
+			},
+			TypeDef::Array(len, ty) => {
+				4i8.encode_to(dest);
+				len.encode_to(dest);
+				ty.as_str().encode_to(dest);
+			},
+			TypeDef::Tuple(types) => {
+				5i8.encode_to(dest);
+				types.iter().map(|ty| ty.as_str()).collect::<Vec<_>>().encode_to(dest);
+			},
+			TypeDef::Compact(ty) => {
+				6i8.encode_to(dest);
+				ty.as_str().encode_to(dest);
+			},
+		}
+	}
+}
+
+impl Encode for FieldDef {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.name.as_ref().map(|name| name.as_str()).encode_to(dest);
+		self.ty.as_str().encode_to(dest);
+	}
+}
+
+impl Encode for VariantDef {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.name.as_str().encode_to(dest);
+		self.index.encode_to(dest);
+		self.fields.encode_to(dest);
+	}
+}
+
+#[cfg(feature = "std")]
+impl Decode for TypeDef {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		Some(match i8::decode(input)? {
+			0 => TypeDef::Primitive,
+			1 => TypeDef::Struct(Vec::decode(input)?),
+			2 => TypeDef::Enum(Vec::decode(input)?),
+			3 => TypeDef::Sequence(String::decode(input)?),
+			4 => TypeDef::Array(u32::decode(input)?, String::decode(input)?),
+			5 => TypeDef::Tuple(Vec::decode(input)?),
+			6 => TypeDef::Compact(String::decode(input)?),
+			_ => return None,
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl Decode for FieldDef {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		Some(FieldDef { name: Option::<String>::decode(input)?, ty: String::decode(input)? })
+	}
+}
+
+#[cfg(feature = "std")]
+impl Decode for VariantDef {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		Some(VariantDef { name: String::decode(input)?, index: u8::decode(input)?, fields: Vec::decode(input)? })
+	}
+}
+
+#[cfg(feature = "std")]
+impl TypeDef {
+	/// Returns the structure as JSON, e.g. `{ "sequence": "u8" }`.
+	fn into_json_string(self) -> String {
+		let fields_json = |fields: Vec<FieldDef>| {
+			let fields = fields.into_iter().map(|field| format!(
+				r#"{{ "name": {}, "type": {} }}"#,
+				field.name.as_ref().map_or("null".to_owned(), |name| json_string(name)), json_string(&field.ty)
+			)).collect::<Vec<_>>();
+			format!("[ {} ]", fields.join(", "))
+		};
+		match self {
+			TypeDef::Primitive => r#""primitive""#.to_owned(),
+			TypeDef::Struct(fields) => format!(r#"{{ "struct": {} }}"#, fields_json(fields)),
+			TypeDef::Enum(variants) => {
+				let variants = variants.into_iter().map(|variant| format!(
+					r#"{{ "name": {}, "index": {}, "fields": {} }}"#,
+					json_string(&variant.name), variant.index, fields_json(variant.fields)
+				)).collect::<Vec<_>>();
+				format!(r#"{{ "enum": [ {} ] }}"#, variants.join(", "))
+			},
+			TypeDef::Sequence(ty) => format!(r#"{{ "sequence": {} }}"#, json_string(&ty)),
+			TypeDef::Array(len, ty) => format!(r#"{{ "array": {{ "len": {}, "type": {} }} }}"#, len, json_string(&ty)),
+			TypeDef::Tuple(types) => {
+				let types = types.iter().map(|ty| json_string(ty)).collect::<Vec<_>>();
+				format!(r#"{{ "tuple": [ {} ] }}"#, types.join(", "))
+			},
+			TypeDef::Compact(ty) => format!(r#"{{ "compact": {} }}"#, json_string(&ty)),
+		}
+	}
+}
+
+/// A constant of a module, declared in `decl_module!` with `const Name: Type = value;`.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ModuleConstantMetadata {
+	pub name: &'static str,
+	pub ty: &'static str,
+	/// The encoded value of the constant.
+	pub value: Vec<u8>,
+	pub documentation: &'static [&'static str],
+}
+
+impl Encode for ModuleConstantMetadata {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.name.encode_to(dest);
+		self.ty.encode_to(dest);
+		self.value.encode_to(dest);
+		self.documentation.encode_to(dest);
+	}
 }
 
 impl Encode for JsonMetadata {
@@ -59,7 +263,28 @@ impl Encode for JsonMetadata {
 				prefix.encode_to(dest);
 				module.encode_to(dest);
 				storage.encode_to(dest);
-			}
+			},
+			JsonMetadata::HooksOrder { modules } => {
+				3i8.encode_to(dest);
+				modules.encode_to(dest);
+			},
+			JsonMetadata::FullModule { module, prefix, storage, constants, errors, types } => {
+				4i8.encode_to(dest);
+				prefix.encode_to(dest);
+				module.encode_to(dest);
+				storage.encode_to(dest);
+				constants.encode_to(dest);
+				errors.encode_to(dest);
+				types.iter().map(|(written, name)| (*written, name.as_str())).collect::<Vec<_>>().encode_to(dest);
+			},
+			JsonMetadata::Types { types } => {
+				5i8.encode_to(dest);
+				types.iter().map(|(name, def)| (name.as_str(), def)).collect::<Vec<_>>().encode_to(dest);
+			},
+			JsonMetadata::ModuleIndices { modules } => {
+				6i8.encode_to(dest);
+				modules.encode_to(dest);
+			},
 		}
 	}
 }
@@ -87,18 +312,174 @@ impl PartialEq<JsonMetadata> for JsonMetadata {
 			) => {
 				lpre == rpre && lmod == rmod && lstore == rstore
 			},
+			(
+				JsonMetadata::HooksOrder { modules: left },
+				JsonMetadata::HooksOrder { modules: right }
+			) => {
+				left == right
+			},
+			(
+				JsonMetadata::FullModule {
+					prefix: lpre, module: lmod, storage: lstore, constants: lconst, errors: lerr, types: ltypes
+				},
+				JsonMetadata::FullModule {
+					prefix: rpre, module: rmod, storage: rstore, constants: rconst, errors: rerr, types: rtypes
+				}
+			) => {
+				lpre == rpre && lmod == rmod && lstore == rstore && lconst == rconst && lerr == rerr
+					&& ltypes == rtypes
+			},
+			(JsonMetadata::Types { types: left }, JsonMetadata::Types { types: right }) => left == right,
+			(
+				JsonMetadata::ModuleIndices { modules: left },
+				JsonMetadata::ModuleIndices { modules: right }
+			) => {
+				left == right
+			},
 			_ => false,
 		}
     }
 }
 
+/// Prefixed to the encoding of versioned metadata, `meta` in little endian.
+///
+/// Unversioned metadata starts with the length of its list, which is never this large.
+pub const META_RESERVED: u32 = 0x6174656d;
+
+/// The metadata of a runtime, tagged with the version of its format.
+#[derive(Eq, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum RuntimeMetadata {
+	/// The plain list of metadata returned by runtimes predating the version discriminant.
+	V0(Vec<JsonMetadata>),
+	/// The list of metadata behind `META_RESERVED` and the version number.
+	V1(Vec<JsonMetadata>),
+	/// As `V1`, but with a `FullModule` for each module of the runtime.
+	V2(Vec<JsonMetadata>),
+}
+
+impl RuntimeMetadata {
+	/// The version of the metadata format.
+	pub fn version(&self) -> u8 {
+		match self {
+			RuntimeMetadata::V0(_) => 0,
+			RuntimeMetadata::V1(_) => 1,
+			RuntimeMetadata::V2(_) => 2,
+		}
+	}
+}
+
+impl Encode for RuntimeMetadata {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		match self {
+			RuntimeMetadata::V0(metadata) => metadata.encode_to(dest),
+			RuntimeMetadata::V1(metadata) | RuntimeMetadata::V2(metadata) => {
+				META_RESERVED.encode_to(dest);
+				self.version().encode_to(dest);
+				metadata.encode_to(dest);
+			}
+		}
+	}
+}
+
+// Serialized as the metadata it decodes to.
+#[cfg(feature = "std")]
+impl Serialize for JsonMetadata {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		JsonMetadataDecodable::decode(&mut &self.encode()[..])
+			.ok_or_else(|| S::Error::custom("metadata decodes from its own encoding"))?
+			.serialize(serializer)
+	}
+}
+
+// Serialized as the metadata it decodes to.
+#[cfg(feature = "std")]
+impl Serialize for RuntimeMetadata {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		RuntimeMetadataDecodable::decode(&mut &self.encode()[..])
+			.ok_or_else(|| S::Error::custom("metadata decodes from its own encoding"))?
+			.serialize(serializer)
+	}
+}
+
 /// Utility struct for making `JsonMetadata` decodeable.
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 #[cfg(feature = "std")]
 pub enum JsonMetadataDecodable {
 	Events { name: String, events: Vec<(String, String)> },
 	Module { module: String, prefix: String },
-	ModuleWithStorage { module: String, prefix: String, storage: String }
+	ModuleWithStorage { module: String, prefix: String, storage: String },
+	HooksOrder { modules: Vec<String> },
+	FullModule {
+		module: String,
+		prefix: String,
+		storage: Option<String>,
+		constants: Vec<ModuleConstantMetadataDecodable>,
+		errors: String,
+		types: Vec<(String, String)>,
+	},
+	Types { types: Vec<(String, TypeDef)> },
+	ModuleIndices { modules: Vec<(String, u8)> },
+}
+
+/// Utility struct for making `ModuleConstantMetadata` decodeable.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg(feature = "std")]
+pub struct ModuleConstantMetadataDecodable {
+	pub name: String,
+	pub ty: String,
+	pub value: Vec<u8>,
+	pub documentation: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl Decode for ModuleConstantMetadataDecodable {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		Some(ModuleConstantMetadataDecodable {
+			name: String::decode(input)?,
+			ty: String::decode(input)?,
+			value: Vec::<u8>::decode(input)?,
+			documentation: Vec::<String>::decode(input)?,
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl ModuleConstantMetadataDecodable {
+	/// Returns the constant as JSON, with its value as hex.
+	fn into_json_string(self) -> String {
+		let value = self.value.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+		let documentation = self.documentation.iter().map(|d| json_string(d)).collect::<Vec<_>>();
+		format!(
+			r#"{{ "name": {}, "type": {}, "value": "0x{}", "description": [ {} ] }}"#,
+			json_string(&self.name), json_string(&self.ty), value, documentation.join(", ")
+		)
+	}
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<ModuleConstantMetadata> for ModuleConstantMetadataDecodable {
+	fn eq(&self, other: &ModuleConstantMetadata) -> bool {
+		self.name == other.name && self.ty == other.ty && self.value == other.value
+			&& self.documentation.len() == other.documentation.len()
+			&& self.documentation.iter().zip(other.documentation.iter()).all(|(l, r)| l == r)
+	}
+}
+
+/// `s` as a JSON string literal.
+#[cfg(feature = "std")]
+fn json_string(s: &str) -> String {
+	let mut json = String::from("\"");
+	for c in s.chars() {
+		match c {
+			'"' => json.push_str("\\\""),
+			'\\' => json.push_str("\\\\"),
+			c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+			c => json.push(c),
+		}
+	}
+	json.push('"');
+	json
 }
 
 #[cfg(feature = "std")]
@@ -134,7 +515,40 @@ impl JsonMetadataDecodable {
 						prefix, module, storage
 					)
 				)
-			}
+			},
+			JsonMetadataDecodable::HooksOrder { modules } => {
+				let modules = modules.iter().map(|m| format!(r#""{}""#, m)).collect::<Vec<_>>();
+				("hooksOrder", format!(r#"{{ "modules": [ {} ] }}"#, modules.join(", ")))
+			},
+			JsonMetadataDecodable::FullModule { prefix, module, storage, constants, errors, types } => {
+				let constants = constants.into_iter().map(|c| c.into_json_string()).collect::<Vec<_>>();
+				let types = types.iter()
+					.map(|(written, name)| format!("{}: {}", json_string(written), json_string(name)))
+					.collect::<Vec<_>>();
+				(
+					"fullModule",
+					format!(
+						concat!(
+							r#"{{ "prefix": "{}", "module": {}, "storage": {}, "constants": [ {} ], "errors": {}, "#,
+							r#""types": {{ {} }} }}"#
+						),
+						prefix, module, storage.as_ref().map_or("null", |s| &s[..]), constants.join(", "), errors,
+						types.join(", ")
+					)
+				)
+			},
+			JsonMetadataDecodable::Types { types } => {
+				let types = types.into_iter()
+					.map(|(name, def)| format!("{}: {}", json_string(&name), def.into_json_string()))
+					.collect::<Vec<_>>();
+				("types", format!("{{ {} }}", types.join(", ")))
+			},
+			JsonMetadataDecodable::ModuleIndices { modules } => {
+				let modules = modules.into_iter()
+					.map(|(name, index)| format!("{}: {}", json_string(&name), index))
+					.collect::<Vec<_>>();
+				("moduleIndices", format!("{{ {} }}", modules.join(", ")))
+			},
 		}
 	}
 }
@@ -154,12 +568,39 @@ impl Decode for JsonMetadataDecodable {
 						.and_then(|prefix| String::decode(input).map(|v| (prefix, v)))
 						.and_then(|(prefix, module)| String::decode(input).map(|v| (prefix, module, v)))
 						.and_then(|(prefix, module, storage)| Some(JsonMetadataDecodable::ModuleWithStorage { prefix, module, storage })),
+				3 => Vec::<String>::decode(input)
+						.and_then(|modules| Some(JsonMetadataDecodable::HooksOrder { modules })),
+				4 => Some(JsonMetadataDecodable::FullModule {
+					prefix: String::decode(input)?,
+					module: String::decode(input)?,
+					storage: Option::<String>::decode(input)?,
+					constants: Vec::<ModuleConstantMetadataDecodable>::decode(input)?,
+					errors: String::decode(input)?,
+					types: Vec::<(String, String)>::decode(input)?,
+				}),
+				5 => Vec::<(String, TypeDef)>::decode(input)
+						.and_then(|types| Some(JsonMetadataDecodable::Types { types })),
+				6 => Vec::<(String, u8)>::decode(input)
+						.and_then(|modules| Some(JsonMetadataDecodable::ModuleIndices { modules })),
 				_ => None,
 			}
 		})
 	}
 }
 
+// Serialized as an object with the JSON of `into_json_string` under the name of the metadata type,
+// e.g. `{ "hooksOrder": { "modules": [ .. ] } }`.
+#[cfg(feature = "std")]
+impl Serialize for JsonMetadataDecodable {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let (mtype, json) = self.clone().into_json_string();
+		let value: serde_json::Value = serde_json::from_str(&json).map_err(S::Error::custom)?;
+		let mut map = serializer.serialize_map(Some(1))?;
+		map.serialize_entry(mtype, &value)?;
+		map.end()
+	}
+}
+
 #[cfg(feature = "std")]
 impl PartialEq<JsonMetadata> for JsonMetadataDecodable {
 	fn eq(&self, other: &JsonMetadata) -> bool {
@@ -184,7 +625,253 @@ impl PartialEq<JsonMetadata> for JsonMetadataDecodable {
 			) => {
 				lpre == rpre && lmod == rmod && lstore == rstore
 			},
+			(
+				JsonMetadataDecodable::HooksOrder { modules: left },
+				JsonMetadata::HooksOrder { modules: right }
+			) => {
+				left.len() == right.len() && left.iter().zip(right.iter()).all(|(l, r)| l == r)
+			},
+			(
+				JsonMetadataDecodable::FullModule {
+					prefix: lpre, module: lmod, storage: lstore, constants: lconst, errors: lerr, types: ltypes
+				},
+				JsonMetadata::FullModule {
+					prefix: rpre, module: rmod, storage: rstore, constants: rconst, errors: rerr, types: rtypes
+				}
+			) => {
+				lpre == rpre && lmod == rmod && lstore.as_ref().map(|s| &s[..]) == *rstore
+					&& lconst.len() == rconst.len() && lconst.iter().zip(rconst.iter()).all(|(l, r)| l == r)
+					&& lerr == rerr
+					&& ltypes.len() == rtypes.len()
+					&& ltypes.iter().zip(rtypes.iter()).all(|(l, r)| l.0 == r.0 && l.1 == r.1)
+			},
+			(JsonMetadataDecodable::Types { types: left }, JsonMetadata::Types { types: right }) => left == right,
+			(
+				JsonMetadataDecodable::ModuleIndices { modules: left },
+				JsonMetadata::ModuleIndices { modules: right }
+			) => {
+				left.len() == right.len() && left.iter().zip(right.iter()).all(|(l, r)| l.0 == r.0 && l.1 == r.1)
+			},
 			_ => false,
 		}
     }
 }
+
+/// Utility struct for making `RuntimeMetadata` decodeable.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg(feature = "std")]
+pub enum RuntimeMetadataDecodable {
+	V0(Vec<JsonMetadataDecodable>),
+	V1(Vec<JsonMetadataDecodable>),
+	V2(Vec<JsonMetadataDecodable>),
+}
+
+#[cfg(feature = "std")]
+impl RuntimeMetadataDecodable {
+	/// The version of the metadata format.
+	pub fn version(&self) -> u8 {
+		match self {
+			RuntimeMetadataDecodable::V0(_) => 0,
+			RuntimeMetadataDecodable::V1(_) => 1,
+			RuntimeMetadataDecodable::V2(_) => 2,
+		}
+	}
+
+	/// Returns the list of metadata, whatever the version it was encoded with.
+	pub fn into_json_metadata(self) -> Vec<JsonMetadataDecodable> {
+		match self {
+			RuntimeMetadataDecodable::V0(metadata) |
+			RuntimeMetadataDecodable::V1(metadata) |
+			RuntimeMetadataDecodable::V2(metadata) => metadata,
+		}
+	}
+}
+
+// Serialized as `{ "version": 2, "metadata": [ .. ] }`, which tools may dump to a file, e.g. to diff
+// the metadata of two runtimes.
+#[cfg(feature = "std")]
+impl Serialize for RuntimeMetadataDecodable {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut state = serializer.serialize_struct("RuntimeMetadata", 2)?;
+		state.serialize_field("version", &self.version())?;
+		match self {
+			RuntimeMetadataDecodable::V0(metadata) |
+			RuntimeMetadataDecodable::V1(metadata) |
+			RuntimeMetadataDecodable::V2(metadata) => state.serialize_field("metadata", metadata)?,
+		}
+		state.end()
+	}
+}
+
+#[cfg(feature = "std")]
+impl Decode for RuntimeMetadataDecodable {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		u32::decode(input).and_then(|reserved| {
+			if reserved != META_RESERVED {
+				// Unversioned metadata, `reserved` is the length of the list.
+				return (0..reserved)
+					.map(|_| JsonMetadataDecodable::decode(input))
+					.collect::<Option<Vec<_>>>()
+					.map(RuntimeMetadataDecodable::V0);
+			}
+
+			u8::decode(input).and_then(|version| {
+				match version {
+					1 => Vec::<JsonMetadataDecodable>::decode(input).map(RuntimeMetadataDecodable::V1),
+					2 => Vec::<JsonMetadataDecodable>::decode(input).map(RuntimeMetadataDecodable::V2),
+					_ => None,
+				}
+			})
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+impl PartialEq<RuntimeMetadata> for RuntimeMetadataDecodable {
+	fn eq(&self, other: &RuntimeMetadata) -> bool {
+		match (self, other) {
+			(RuntimeMetadataDecodable::V0(left), RuntimeMetadata::V0(right)) |
+			(RuntimeMetadataDecodable::V1(left), RuntimeMetadata::V1(right)) |
+			(RuntimeMetadataDecodable::V2(left), RuntimeMetadata::V2(right)) => &left[..] == &right[..],
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn module_json() -> &'static str {
+		r#"{ "name": "Module" }"#
+	}
+
+	fn metadata() -> Vec<JsonMetadata> {
+		vec![
+			JsonMetadata::Events { name: "Test", events: &[ ("module", module_json) ] },
+			JsonMetadata::ModuleWithStorage { module: "{}", prefix: "module", storage: "{}" },
+			JsonMetadata::HooksOrder { modules: &["Module", "Other"] },
+		]
+	}
+
+	#[test]
+	fn versioned_metadata_decodes() {
+		let metadata = RuntimeMetadata::V1(metadata());
+		let encoded = metadata.encode();
+
+		let decoded = RuntimeMetadataDecodable::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(decoded.version(), 1);
+		assert_eq!(decoded, metadata);
+	}
+
+	#[test]
+	fn unversioned_metadata_decodes() {
+		let encoded = metadata().encode();
+		assert_eq!(encoded, RuntimeMetadata::V0(metadata()).encode());
+
+		let decoded = RuntimeMetadataDecodable::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(decoded.version(), 0);
+		assert_eq!(&decoded.into_json_metadata()[..], &metadata()[..]);
+	}
+
+	#[test]
+	fn hooks_order_is_printed_as_json() {
+		let encoded = JsonMetadata::HooksOrder { modules: &["Module", "Other"] }.encode();
+		let decoded = JsonMetadataDecodable::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(decoded.into_json_string(), ("hooksOrder", r#"{ "modules": [ "Module", "Other" ] }"#.to_owned()));
+	}
+
+	#[test]
+	fn module_indices_are_printed_as_json() {
+		let indices = JsonMetadata::ModuleIndices { modules: &[("System", 0), ("Balances", 5)] };
+		let decoded = JsonMetadataDecodable::decode(&mut &indices.encode()[..]).unwrap();
+		assert_eq!(decoded, indices);
+		assert_eq!(decoded.into_json_string(), ("moduleIndices", r#"{ "System": 0, "Balances": 5 }"#.to_owned()));
+	}
+
+	#[test]
+	fn full_module_decodes_and_is_printed_as_json() {
+		let module = JsonMetadata::FullModule {
+			module: r#"{ "name": "Module" }"#,
+			prefix: "module",
+			storage: None,
+			constants: vec![ModuleConstantMetadata {
+				name: "Answer",
+				ty: "u32",
+				value: 42u32.encode(),
+				documentation: &[" The \"answer\"."],
+			}],
+			errors: "[ ]",
+			types: vec![("u32", "u32".to_owned())],
+		};
+		let metadata = RuntimeMetadata::V2(vec![module]);
+
+		let decoded = RuntimeMetadataDecodable::decode(&mut &metadata.encode()[..]).unwrap();
+		assert_eq!(decoded.version(), 2);
+		assert_eq!(decoded, metadata);
+		assert_eq!(
+			decoded.into_json_metadata().pop().unwrap().into_json_string(),
+			(
+				"fullModule",
+				concat!(
+					r#"{ "prefix": "module", "module": { "name": "Module" }, "storage": null, "constants": [ "#,
+						r#"{ "name": "Answer", "type": "u32", "value": "0x2a000000", "description": [ " The \"answer\"." ] }"#,
+					r#" ], "errors": [ ], "types": { "u32": "u32" } }"#
+				).to_owned()
+			)
+		);
+	}
+
+	#[test]
+	fn types_decode_and_are_printed_as_json() {
+		let field = |name: Option<&str>, ty: &str| FieldDef { name: name.map(Into::into), ty: ty.into() };
+		let types = JsonMetadata::Types { types: vec![
+			("u64".into(), TypeDef::Primitive),
+			("Vec<u8>".into(), TypeDef::Sequence("u8".into())),
+			("[u8; 32]".into(), TypeDef::Array(32, "u8".into())),
+			("(u64, bool)".into(), TypeDef::Tuple(vec!["u64".into(), "bool".into()])),
+			("Compact<u64>".into(), TypeDef::Compact("u64".into())),
+			("Ledger".into(), TypeDef::Struct(vec![field(Some("total"), "u64"), field(Some("locks"), "Vec<u8>")])),
+			("Option<u64>".into(), TypeDef::Enum(vec![
+				VariantDef { name: "None".into(), index: 0, fields: vec![] },
+				VariantDef { name: "Some".into(), index: 1, fields: vec![field(None, "u64")] },
+			])),
+		] };
+
+		let decoded = JsonMetadataDecodable::decode(&mut &types.encode()[..]).unwrap();
+		assert_eq!(decoded, types);
+		assert_eq!(
+			decoded.into_json_string(),
+			(
+				"types",
+				concat!(
+					r#"{ "u64": "primitive", "Vec<u8>": { "sequence": "u8" }, "#,
+					r#""[u8; 32]": { "array": { "len": 32, "type": "u8" } }, "#,
+					r#""(u64, bool)": { "tuple": [ "u64", "bool" ] }, "Compact<u64>": { "compact": "u64" }, "#,
+					r#""Ledger": { "struct": [ { "name": "total", "type": "u64" }, { "name": "locks", "type": "Vec<u8>" } ] }, "#,
+					r#""Option<u64>": { "enum": [ { "name": "None", "index": 0, "fields": [  ] }, "#,
+						r#"{ "name": "Some", "index": 1, "fields": [ { "name": null, "type": "u64" } ] } ] } }"#
+				).to_owned()
+			)
+		);
+	}
+
+	#[test]
+	fn metadata_is_serialized_as_json() {
+		let metadata = RuntimeMetadata::V1(vec![JsonMetadata::HooksOrder { modules: &["Module", "Other"] }]);
+		let decoded = RuntimeMetadataDecodable::decode(&mut &metadata.encode()[..]).unwrap();
+
+		let json = serde_json::to_string(&metadata).unwrap();
+		assert_eq!(json, r#"{"version":1,"metadata":[{"hooksOrder":{"modules":["Module","Other"]}}]}"#);
+		assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+	}
+
+	#[test]
+	fn unknown_version_fails_to_decode() {
+		let mut encoded = META_RESERVED.encode();
+		encoded.push(3);
+		encoded.extend(metadata().encode());
+
+		assert_eq!(RuntimeMetadataDecodable::decode(&mut &encoded[..]), None);
+	}
+}
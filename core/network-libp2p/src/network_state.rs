@@ -57,6 +57,10 @@ pub struct NetworkState {
 	reserved_only: atomic::AtomicBool,
 	/// List of the IDs of the reserved peers.
 	reserved_peers: RwLock<FnvHashSet<PeerId>>,
+	/// Named groups of peers that higher layers want to stay connected to, such as the
+	/// authorities. Like reserved peers, they don't count towards the peer limits and we always
+	/// try to connect to them. They are also never dropped for being useless.
+	priority_groups: RwLock<FnvHashMap<String, FnvHashSet<PeerId>>>,
 
 	/// Each node we discover gets assigned a new unique ID. This ID increases linearly.
 	next_node_index: atomic::AtomicUsize,
@@ -196,6 +200,7 @@ impl NetworkState {
 			}),
 			reserved_only: atomic::AtomicBool::new(config.non_reserved_mode == NonReservedPeerMode::Deny),
 			reserved_peers,
+			priority_groups: RwLock::new(Default::default()),
 			next_node_index: atomic::AtomicUsize::new(0),
 			disabled_nodes: Mutex::new(Default::default()),
 			local_private_key,
@@ -223,10 +228,15 @@ impl NetworkState {
 		// TODO: handle better
 		let connections = self.connections.read();
 
-		let num_to_attempt = if self.reserved_only.load(atomic::Ordering::Relaxed) {
+		let reserved_peers = self.reserved_peers.read();
+		let priority_groups = self.priority_groups.read();
+		let is_priority = |peer: &PeerId| priority_groups.values().any(|group| group.contains(peer));
+
+		let mut num_to_attempt = if self.reserved_only.load(atomic::Ordering::Relaxed) {
 			0
 		} else {
-			let num_open_custom_connections = num_open_custom_connections(&connections, &self.reserved_peers.read());
+			let num_open_custom_connections = num_open_custom_connections(&connections, |peer|
+				reserved_peers.contains(peer) || is_priority(peer));
 			self.max_outgoing_peers.saturating_sub(num_open_custom_connections.unreserved_outgoing)
 		};
 
@@ -247,7 +257,17 @@ impl NetworkState {
 
 				!infos.protocols.iter().any(|(_, conn)| conn.is_alive())
 			})
-			.take(num_to_attempt as usize)
+			// Priority peers are always attempted, the others only while below the limit.
+			.filter(|&(peer, _)| {
+				if is_priority(peer) {
+					true
+				} else if num_to_attempt > 0 {
+					num_to_attempt -= 1;
+					true
+				} else {
+					false
+				}
+			})
 			.map(|(addr, peer)| (addr.clone(), peer.clone()))
 			.collect();
 		(list, change)
@@ -407,6 +427,37 @@ impl NetworkState {
 		Ok(())
 	}
 
+	/// Replaces the peers of a priority group. An empty list removes the group.
+	/// Returns an error if a peer address is invalid, in which case the group is left unchanged.
+	pub fn set_priority_group(&self, group: &str, peers: &[String]) -> Result<(), Error> {
+		let mut ids = FnvHashSet::with_capacity_and_hasher(peers.len(), Default::default());
+		{
+			let mut topology = self.topology.write();
+			for peer in peers {
+				let (id, _) = parse_and_add_to_topology(peer, &mut topology)?;
+				ids.insert(id);
+			}
+		}
+
+		let mut priority_groups = self.priority_groups.write();
+		if ids.is_empty() {
+			priority_groups.remove(group);
+		} else {
+			priority_groups.insert(group.to_owned(), ids);
+		}
+		Ok(())
+	}
+
+	/// Returns true if the given peer belongs to a priority group.
+	pub fn is_priority_peer(&self, who: NodeIndex) -> bool {
+		let connections = self.connections.read();
+		let id = match connections.info_by_peer.get(&who) {
+			Some(info) => &info.id,
+			None => return false,
+		};
+		self.priority_groups.read().values().any(|group| group.contains(id))
+	}
+
 	/// Set the non-reserved peer mode.
 	pub fn set_non_reserved_mode(&self, mode: NonReservedPeerMode) {
 		match mode {
@@ -495,7 +546,10 @@ impl NetworkState {
 		// reviewed triggers one
 
 		if endpoint == Endpoint::Listener {
-			let stats = num_open_custom_connections(&connections, &self.reserved_peers.read());
+			let reserved_peers = self.reserved_peers.read();
+			let priority_groups = self.priority_groups.read();
+			let stats = num_open_custom_connections(&connections, |peer|
+				reserved_peers.contains(peer) || priority_groups.values().any(|group| group.contains(peer)));
 			if stats.unreserved_incoming >= self.max_incoming_peers {
 				debug!(target: "sub-libp2p", "Refusing incoming connection from {} because we \
 					reached max incoming peers", addr);
@@ -764,7 +818,8 @@ pub struct PeriodicUpdate {
 struct OpenCustomConnectionsNumbers {
 	/// Total number of open and pending connections.
 	pub total: u32,
-	/// Unreserved incoming number of open and pending connections.
+	/// Unreserved incoming number of open and pending connections. Priority peers count as
+	/// reserved.
 	pub unreserved_incoming: u32,
 	/// Unreserved outgoing number of open and pending connections.
 	pub unreserved_outgoing: u32,
@@ -772,7 +827,9 @@ struct OpenCustomConnectionsNumbers {
 
 /// Returns the number of open and pending connections with
 /// custom protocols.
-fn num_open_custom_connections(connections: &Connections, reserved_peers: &FnvHashSet<PeerId>) -> OpenCustomConnectionsNumbers {
+fn num_open_custom_connections<F>(connections: &Connections, is_reserved: F) -> OpenCustomConnectionsNumbers
+	where F: Fn(&PeerId) -> bool
+{
 	let filtered = connections
 		.info_by_peer
 		.values()
@@ -791,7 +848,7 @@ fn num_open_custom_connections(connections: &Connections, reserved_peers: &FnvHa
 
 	for info in filtered {
 		total += 1;
-		let node_is_reserved = reserved_peers.contains(&info.id);
+		let node_is_reserved = is_reserved(&info.id);
 		if !node_is_reserved {
 			if !info.originated.unwrap_or(true) {
 				unreserved_incoming += 1;
@@ -950,4 +1007,23 @@ mod tests {
 
 		assert!(state.assign_node_index(&example_peer).is_err());
 	}
+
+	#[test]
+	fn priority_group_membership() {
+		let state = NetworkState::new(&Default::default()).unwrap();
+		let example_peer = PublicKey::Rsa(vec![1, 2, 3, 4]).into_peer_id();
+		let addr = format!("/ip4/127.0.0.1/tcp/30333/p2p/{}", example_peer.to_base58());
+
+		let who = state.assign_node_index(&example_peer).unwrap();
+		assert!(!state.is_priority_peer(who));
+
+		state.set_priority_group("authorities", &[addr]).unwrap();
+		assert!(state.is_priority_peer(who));
+
+		assert!(state.set_priority_group("authorities", &["invalid".to_owned()]).is_err());
+		assert!(state.is_priority_peer(who));
+
+		state.set_priority_group("authorities", &[]).unwrap();
+		assert!(!state.is_priority_peer(who));
+	}
 }
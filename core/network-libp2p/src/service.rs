@@ -227,6 +227,11 @@ impl NetworkService {
 		self.shared.network_state.remove_reserved_peer(peer)
 	}
 
+	/// Replace the peers of a named priority group. An empty list removes the group.
+	pub fn set_priority_group(&self, group: &str, peers: &[String]) -> Result<(), Error> {
+		self.shared.network_state.set_priority_group(group, peers)
+	}
+
 	/// Set the non-reserved peer mode.
 	pub fn set_non_reserved_mode(&self, mode: NonReservedPeerMode) {
 		self.shared.network_state.set_non_reserved_mode(mode)
@@ -323,6 +328,8 @@ impl NetworkContext for NetworkContextImpl {
 		}
 		match reason {
 			Severity::Bad(reason) => self.inner.network_state.ban_peer(peer, reason),
+			Severity::Useless(_) if self.inner.network_state.is_priority_peer(peer) =>
+				debug!(target: "sub-libp2p", "Not dropping priority peer {}", peer),
 			Severity::Useless(_) => self.inner.network_state.drop_peer(peer),
 			Severity::Timeout => self.inner.network_state.drop_peer(peer),
 		}
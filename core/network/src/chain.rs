@@ -28,6 +28,12 @@ pub trait Client<Block: BlockT>: Send + Sync {
 	/// Import a new block. Parent is supposed to be existing in the blockchain.
 	fn import(&self, origin: BlockOrigin, header: Block::Header, justification: Justification<Block::Hash>, body: Option<Vec<Block::Extrinsic>>) -> Result<ImportResult, Error>;
 
+	/// Import a block header without executing the block. Parent is supposed to be existing in the blockchain.
+	fn import_header(&self, origin: BlockOrigin, header: Block::Header, justification: Justification<Block::Hash>) -> Result<ImportResult, Error>;
+
+	/// Import a block with its whole state instead of executing it. Parent is supposed to be existing in the blockchain.
+	fn import_with_state(&self, origin: BlockOrigin, header: Block::Header, justification: Justification<Block::Hash>, state: Vec<(Vec<u8>, Vec<u8>)>) -> Result<ImportResult, Error>;
+
 	/// Get blockchain info.
 	fn info(&self) -> Result<ClientInfo<Block>, Error>;
 
@@ -54,6 +60,9 @@ pub trait Client<Block: BlockT>: Send + Sync {
 
 	/// Get method execution proof.
 	fn execution_proof(&self, block: &Block::Hash, method: &str, data: &[u8]) -> Result<(Vec<u8>, Vec<Vec<u8>>), Error>;
+
+	/// Get a chunk of the state entries at a block, starting at the given key, whether it is the last one and its proof.
+	fn state_range(&self, block: &Block::Hash, start: &[u8], max_bytes: usize) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, Vec<Vec<u8>>), Error>;
 }
 
 impl<B, E, Block> Client<Block> for SubstrateClient<B, E, Block> where
@@ -67,6 +76,14 @@ impl<B, E, Block> Client<Block> for SubstrateClient<B, E, Block> where
 		(self as &SubstrateClient<B, E, Block>).import_block(origin, justified_header, body)
 	}
 
+	fn import_header(&self, origin: BlockOrigin, header: Block::Header, justification: Justification<Block::Hash>) -> Result<ImportResult, Error> {
+		(self as &SubstrateClient<B, E, Block>).import_header(origin, header, justification)
+	}
+
+	fn import_with_state(&self, origin: BlockOrigin, header: Block::Header, justification: Justification<Block::Hash>, state: Vec<(Vec<u8>, Vec<u8>)>) -> Result<ImportResult, Error> {
+		(self as &SubstrateClient<B, E, Block>).import_block_with_state(origin, header, justification, state)
+	}
+
 	fn info(&self) -> Result<ClientInfo<Block>, Error> {
 		(self as &SubstrateClient<B, E, Block>).info()
 	}
@@ -102,4 +119,8 @@ impl<B, E, Block> Client<Block> for SubstrateClient<B, E, Block> where
 	fn execution_proof(&self, block: &Block::Hash, method: &str, data: &[u8]) -> Result<(Vec<u8>, Vec<Vec<u8>>), Error> {
 		(self as &SubstrateClient<B, E, Block>).execution_proof(&BlockId::Hash(block.clone()), method, data)
 	}
+
+	fn state_range(&self, block: &Block::Hash, start: &[u8], max_bytes: usize) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, Vec<Vec<u8>>), Error> {
+		(self as &SubstrateClient<B, E, Block>).state_range(&BlockId::Hash(block.clone()), start, max_bytes)
+	}
 }
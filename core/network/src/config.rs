@@ -14,19 +14,52 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::str::FromStr;
+
 pub use service::Roles;
 
+/// How a node catches up with the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+	/// Download and execute all the blocks.
+	Full,
+	/// Download the headers and the state of a recent block, then execute the blocks after it.
+	/// Only used by full nodes starting from genesis.
+	Fast,
+}
+
+impl Default for SyncMode {
+	fn default() -> Self {
+		SyncMode::Full
+	}
+}
+
+impl FromStr for SyncMode {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, String> {
+		match s {
+			"full" => Ok(SyncMode::Full),
+			"fast" => Ok(SyncMode::Fast),
+			_ => Err(format!("Unknown sync mode `{}`; expected `full` or `fast`", s)),
+		}
+	}
+}
+
 /// Protocol configuration
 #[derive(Clone)]
 pub struct ProtocolConfig {
 	/// Assigned roles.
 	pub roles: Roles,
+	/// How to catch up with the chain.
+	pub sync_mode: SyncMode,
 }
 
 impl Default for ProtocolConfig {
 	fn default() -> ProtocolConfig {
 		ProtocolConfig {
 			roles: Roles::FULL,
+			sync_mode: SyncMode::Full,
 		}
 	}
 }
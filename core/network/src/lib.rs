@@ -26,6 +26,7 @@ extern crate linked_hash_map;
 extern crate parking_lot;
 extern crate substrate_primitives as primitives;
 extern crate substrate_client as client;
+extern crate substrate_state_machine as state_machine;
 extern crate sr_primitives as runtime_primitives;
 extern crate substrate_network_libp2p as network_libp2p;
 extern crate parity_codec as codec;
@@ -49,6 +50,7 @@ mod chain;
 mod blocks;
 mod on_demand;
 mod import_queue;
+mod state_sync;
 pub mod consensus_gossip;
 pub mod error;
 pub mod message;
@@ -64,5 +66,6 @@ pub use sync::{Status as SyncStatus, SyncState};
 pub use network_libp2p::{NonReservedPeerMode, NetworkConfiguration, NodeIndex, ProtocolId, ConnectionFilter, ConnectionDirection, Severity};
 pub use message::{generic as generic_message, RequestId, BftMessage, LocalizedBftMessage, ConsensusVote, SignedConsensusVote, SignedConsensusMessage, SignedConsensusProposal, Status as StatusMessage};
 pub use error::Error;
-pub use config::{Roles, ProtocolConfig};
+pub use config::{Roles, ProtocolConfig, SyncMode};
+pub use state_sync::StateDownload;
 pub use on_demand::{OnDemand, OnDemandService, RemoteResponse};
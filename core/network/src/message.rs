@@ -20,7 +20,8 @@ use runtime_primitives::traits::{Block as BlockT, Header as HeaderT};
 use codec::{Encode, Decode, Input, Output};
 pub use self::generic::{
 	BlockAnnounce, RemoteCallRequest, RemoteReadRequest,
-	RemoteHeaderRequest, RemoteHeaderResponse, ConsensusVote,
+	RemoteHeaderRequest, RemoteHeaderResponse, RemoteJustificationRequest,
+	RemoteJustificationResponse, StateRequest, ConsensusVote,
 	SignedConsensusVote, FromBlock
 };
 
@@ -145,6 +146,20 @@ pub struct RemoteReadResponse {
 	pub proof: Vec<Vec<u8>>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+/// State response.
+pub struct StateResponse {
+	/// Id of a request this response was made for.
+	pub id: RequestId,
+	/// Storage entries in ascending key order, starting at the requested key.
+	pub entries: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Whether the entries reach the end of the state.
+	pub complete: bool,
+	/// Proof that the entries are the whole state between the requested key and the last entry,
+	/// or its end if `complete`.
+	pub proof: Vec<Vec<u8>>,
+}
+
 /// Generic types.
 pub mod generic {
 	use primitives::{AuthorityId, ed25519};
@@ -152,7 +167,7 @@ pub mod generic {
 	use service::Roles;
 	use super::{
 		BlockAttributes, RemoteCallResponse, RemoteReadResponse,
-		RequestId, Transactions, Direction
+		RequestId, Transactions, Direction, StateResponse
 	};
 
 	/// Block data sent in the response.
@@ -274,6 +289,14 @@ pub mod generic {
 		RemoteHeaderRequest(RemoteHeaderRequest<Number>),
 		/// Remote header response.
 		RemoteHeaderResponse(RemoteHeaderResponse<Header>),
+		/// Remote justification request.
+		RemoteJustificationRequest(RemoteJustificationRequest<Hash>),
+		/// Remote justification response.
+		RemoteJustificationResponse(RemoteJustificationResponse<Hash>),
+		/// State request.
+		StateRequest(StateRequest<Hash>),
+		/// State response.
+		StateResponse(StateResponse),
 		/// Chain-specific message
 		#[codec(index = "255")]
 		ChainSpecific(Vec<u8>),
@@ -372,4 +395,33 @@ pub mod generic {
 		/// Header proof.
 		pub proof: Vec<Vec<u8>>,
 	}
+
+	#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+	/// Remote justification request.
+	pub struct RemoteJustificationRequest<H> {
+		/// Unique request id.
+		pub id: RequestId,
+		/// Block to request the justification of.
+		pub block: H,
+	}
+
+	#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+	/// Remote justification response.
+	pub struct RemoteJustificationResponse<H> {
+		/// Id of a request this response was made for.
+		pub id: RequestId,
+		/// Justification. None if the block is unknown or not justified.
+		pub justification: Option<Justification<H>>,
+	}
+
+	#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode)]
+	/// Request for a chunk of the state at a block.
+	pub struct StateRequest<H> {
+		/// Unique request id.
+		pub id: RequestId,
+		/// Block at which to read the state.
+		pub block: H,
+		/// Key to start from. Entries with smaller keys are not sent.
+		pub start: Vec<u8>,
+	}
 }
@@ -26,11 +26,12 @@ use linked_hash_map::Entry;
 use parking_lot::Mutex;
 use client;
 use client::light::fetcher::{Fetcher, FetchChecker, RemoteHeaderRequest,
-	RemoteCallRequest, RemoteReadRequest};
+	RemoteCallRequest, RemoteReadRequest, RemoteJustificationRequest};
 use io::SyncIo;
 use message;
 use network_libp2p::{Severity, NodeIndex};
 use service;
+use runtime_primitives::bft::Justification;
 use runtime_primitives::traits::{Block as BlockT, Header as HeaderT};
 
 /// Remote request timeout.
@@ -62,6 +63,14 @@ pub trait OnDemandService<Block: BlockT>: Send + Sync {
 
 	/// When call response is received from remote node.
 	fn on_remote_call_response(&self, io: &mut SyncIo, peer: NodeIndex, response: message::RemoteCallResponse);
+
+	/// When justification response is received from remote node.
+	fn on_remote_justification_response(
+		&self,
+		io: &mut SyncIo,
+		peer: NodeIndex,
+		response: message::RemoteJustificationResponse<Block::Hash>
+	);
 }
 
 /// On-demand requests service. Dispatches requests to appropriate peers.
@@ -95,6 +104,7 @@ enum RequestData<Block: BlockT> {
 	RemoteHeader(RemoteHeaderRequest<Block::Header>, Sender<Result<Block::Header, client::error::Error>>),
 	RemoteRead(RemoteReadRequest<Block::Header>, Sender<Result<Option<Vec<u8>>, client::error::Error>>),
 	RemoteCall(RemoteCallRequest<Block::Header>, Sender<Result<client::CallResult, client::error::Error>>),
+	RemoteJustification(RemoteJustificationRequest<Block::Header>, Sender<Result<Justification<Block::Hash>, client::error::Error>>),
 }
 
 enum Accept<Block: BlockT> {
@@ -262,6 +272,20 @@ impl<B, E> OnDemandService<B> for OnDemand<B, E> where
 			data @ _ => Accept::Unexpected(data),
 		})
 	}
+
+	fn on_remote_justification_response(&self, io: &mut SyncIo, peer: NodeIndex, response: message::RemoteJustificationResponse<B::Hash>) {
+		self.accept_response("justification", io, peer, response.id, |request| match request.data {
+			RequestData::RemoteJustification(request, sender) => match self.checker.check_justification(&request, response.justification) {
+				Ok(response) => {
+					// we do not bother if receiver has been dropped already
+					let _ = sender.send(Ok(response));
+					Accept::Ok
+				},
+				Err(error) => Accept::CheckFailed(error, RequestData::RemoteJustification(request, sender)),
+			},
+			data @ _ => Accept::Unexpected(data),
+		})
+	}
 }
 
 impl<B, E> Fetcher<B> for OnDemand<B, E> where
@@ -272,6 +296,7 @@ impl<B, E> Fetcher<B> for OnDemand<B, E> where
 	type RemoteHeaderResult = RemoteResponse<B::Header>;
 	type RemoteReadResult = RemoteResponse<Option<Vec<u8>>>;
 	type RemoteCallResult = RemoteResponse<client::CallResult>;
+	type RemoteJustificationResult = RemoteResponse<Justification<B::Hash>>;
 
 	fn remote_header(&self, request: RemoteHeaderRequest<B::Header>) -> Self::RemoteHeaderResult {
 		let (sender, receiver) = channel();
@@ -290,6 +315,12 @@ impl<B, E> Fetcher<B> for OnDemand<B, E> where
 		self.schedule_request(request.retry_count.clone(), RequestData::RemoteCall(request, sender),
 			RemoteResponse { receiver })
 	}
+
+	fn remote_justification(&self, request: RemoteJustificationRequest<B::Header>) -> Self::RemoteJustificationResult {
+		let (sender, receiver) = channel();
+		self.schedule_request(request.retry_count.clone(), RequestData::RemoteJustification(request, sender),
+			RemoteResponse { receiver })
+	}
 }
 
 impl<B, E> OnDemandCore<B, E> where
@@ -395,6 +426,11 @@ impl<Block: BlockT> Request<Block> {
 					method: data.method.clone(),
 					data: data.call_data.clone(),
 				}),
+			RequestData::RemoteJustification(ref data, _) => message::generic::Message::RemoteJustificationRequest(
+				message::RemoteJustificationRequest {
+					id: self.id,
+					block: data.block,
+				}),
 		}
 	}
 }
@@ -406,6 +442,7 @@ impl<Block: BlockT> RequestData<Block> {
 			RequestData::RemoteHeader(_, sender) => { let _ = sender.send(Err(error)); },
 			RequestData::RemoteCall(_, sender) => { let _ = sender.send(Err(error)); },
 			RequestData::RemoteRead(_, sender) => { let _ = sender.send(Err(error)); },
+			RequestData::RemoteJustification(_, sender) => { let _ = sender.send(Err(error)); },
 		}
 	}
 }
@@ -419,13 +456,14 @@ pub mod tests {
 	use parking_lot::RwLock;
 	use client;
 	use client::light::fetcher::{Fetcher, FetchChecker, RemoteHeaderRequest,
-		RemoteCallRequest, RemoteReadRequest};
+		RemoteCallRequest, RemoteReadRequest, RemoteJustificationRequest};
 	use message;
 	use network_libp2p::NodeIndex;
 	use service::{Roles, ExecuteInContext};
 	use test::TestIo;
+	use runtime_primitives::bft::Justification;
 	use super::{REQUEST_TIMEOUT, OnDemand, OnDemandService};
-	use test_client::runtime::{Block, Header};
+	use test_client::runtime::{Block, Hash, Header};
 
 	pub struct DummyExecutor;
 	struct DummyFetchChecker { ok: bool }
@@ -463,6 +501,17 @@ pub mod tests {
 				false => Err(client::error::ErrorKind::Backend("Test error".into()).into()),
 			}
 		}
+
+		fn check_justification(
+			&self,
+			_request: &RemoteJustificationRequest<Header>,
+			justification: Option<Justification<Hash>>
+		) -> client::error::Result<Justification<Hash>> {
+			match self.ok {
+				true if justification.is_some() => Ok(justification.unwrap()),
+				_ => Err(client::error::ErrorKind::Backend("Test error".into()).into()),
+			}
+		}
 	}
 
 	fn dummy(ok: bool) -> (Arc<DummyExecutor>, Arc<OnDemand<Block, DummyExecutor>>) {
@@ -733,4 +782,35 @@ pub mod tests {
 		});
 		thread.join().unwrap();
 	}
+
+	#[test]
+	fn receives_remote_justification_response() {
+		let (_x, on_demand) = dummy(true);
+		let queue = RwLock::new(VecDeque::new());
+		let mut network = TestIo::new(&queue, None);
+		on_demand.on_connect(0, Roles::FULL);
+
+		let header = dummy_header();
+		let response = on_demand.remote_justification(RemoteJustificationRequest {
+			block: header.hash(),
+			header: header.clone(),
+			authorities: Vec::new(),
+			retry_count: None,
+		});
+		let hash = header.hash();
+		let thread = ::std::thread::spawn(move || {
+			let result = response.wait().unwrap();
+			assert_eq!(result.hash, hash);
+		});
+
+		on_demand.on_remote_justification_response(&mut network, 0, message::RemoteJustificationResponse {
+			id: 0,
+			justification: Some(Justification {
+				round_number: 1,
+				hash: header.hash(),
+				signatures: Vec::new(),
+			}),
+		});
+		thread.join().unwrap();
+	}
 }
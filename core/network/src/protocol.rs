@@ -46,6 +46,8 @@ pub (crate) const CURRENT_PACKET_COUNT: u8 = 1;
 
 // Maximum allowed entries in `BlockResponse`
 const MAX_BLOCK_DATA_RESPONSE: u32 = 128;
+// Maximum size of the keys and values in a `StateResponse`
+const MAX_STATE_RESPONSE_BYTES: usize = 1024 * 1024;
 
 // Lock must always be taken in order declared here.
 pub struct Protocol<B: BlockT, S: Specialization<B>, H: ExHashT> {
@@ -82,6 +84,8 @@ struct Peer<B: BlockT, H: ExHashT> {
 	best_number: <B::Header as HeaderT>::Number,
 	/// Pending block request if any
 	block_request: Option<message::BlockRequest<B>>,
+	/// Pending state request if any
+	state_request: Option<message::StateRequest<B::Hash>>,
 	/// Request timestamp
 	request_timestamp: Option<time::Instant>,
 	/// Holds a set of transactions known to this peer.
@@ -193,7 +197,7 @@ impl<B: BlockT, S: Specialization<B>, H: ExHashT> Protocol<B, S, H> {
 		specialization: S,
 	) -> error::Result<Self> {
 		let info = chain.info()?;
-		let sync = ChainSync::new(config.roles, &info, import_queue);
+		let sync = ChainSync::new(config.roles, config.sync_mode, &info, import_queue);
 		let protocol = Protocol {
 			config: config,
 			context_data: ContextData {
@@ -273,6 +277,32 @@ impl<B: BlockT, S: Specialization<B>, H: ExHashT> Protocol<B, S, H> {
 			GenericMessage::RemoteReadResponse(response) => self.on_remote_read_response(io, who, response),
 			GenericMessage::RemoteHeaderRequest(request) => self.on_remote_header_request(io, who, request),
 			GenericMessage::RemoteHeaderResponse(response) => self.on_remote_header_response(io, who, response),
+			GenericMessage::RemoteJustificationRequest(request) => self.on_remote_justification_request(io, who, request),
+			GenericMessage::RemoteJustificationResponse(response) => self.on_remote_justification_response(io, who, response),
+			GenericMessage::StateRequest(request) => self.on_state_request(io, who, request),
+			GenericMessage::StateResponse(r) => {
+				let request = {
+					let mut peers = self.context_data.peers.write();
+					if let Some(ref mut peer) = peers.get_mut(&who) {
+						peer.request_timestamp = None;
+						match mem::replace(&mut peer.state_request, None) {
+							Some(r) => r,
+							None => {
+								io.report_peer(who, Severity::Bad("Unexpected response packet received from peer"));
+								return;
+							}
+						}
+					} else {
+						io.report_peer(who, Severity::Bad("Unexpected packet received from peer"));
+						return;
+					}
+				};
+				if request.id != r.id {
+					trace!(target: "sync", "Ignoring mismatched response packet from {} (expected {} got {})", who, request.id, r.id);
+					return;
+				}
+				self.on_state_response(io, who, request, r);
+			},
 			other => self.specialization.write().on_message(&mut ProtocolContext::new(&self.context_data, io), who, other),
 		}
 	}
@@ -441,6 +471,7 @@ impl<B: BlockT, S: Specialization<B>, H: ExHashT> Protocol<B, S, H> {
 				best_hash: status.best_hash,
 				best_number: status.best_number,
 				block_request: None,
+				state_request: None,
 				request_timestamp: None,
 				known_extrinsics: HashSet::new(),
 				known_blocks: HashSet::new(),
@@ -648,6 +679,48 @@ impl<B: BlockT, S: Specialization<B>, H: ExHashT> Protocol<B, S, H> {
 		self.on_demand.as_ref().map(|s| s.on_remote_header_response(io, who, response));
 	}
 
+	fn on_remote_justification_request(&self, io: &mut SyncIo, who: NodeIndex, request: message::RemoteJustificationRequest<B::Hash>) {
+		trace!(target: "sync", "Remote justification request {} from {} ({})",
+			request.id, who, request.block);
+		let justification = match self.context_data.chain.justification(&BlockId::Hash(request.block)) {
+			Ok(justification) => justification,
+			Err(error) => {
+				trace!(target: "sync", "Remote justification request {} from {} ({}) failed with: {}",
+					request.id, who, request.block, error);
+				None
+			},
+		};
+		self.send_message(io, who, GenericMessage::RemoteJustificationResponse(message::RemoteJustificationResponse {
+			id: request.id, justification,
+		}));
+	}
+
+	fn on_remote_justification_response(&self, io: &mut SyncIo, who: NodeIndex, response: message::RemoteJustificationResponse<B::Hash>) {
+		trace!(target: "sync", "Remote justification response {} from {}", response.id, who);
+		self.on_demand.as_ref().map(|s| s.on_remote_justification_response(io, who, response));
+	}
+
+	fn on_state_request(&self, io: &mut SyncIo, who: NodeIndex, request: message::StateRequest<B::Hash>) {
+		trace!(target: "sync", "State request {} from {} ({} at {})",
+			request.id, who, request.start.to_hex(), request.block);
+		let (entries, complete, proof) = match self.context_data.chain.state_range(&request.block, &request.start, MAX_STATE_RESPONSE_BYTES) {
+			Ok(chunk) => chunk,
+			Err(error) => {
+				trace!(target: "sync", "State request {} from {} ({} at {}) failed with: {}",
+					request.id, who, request.start.to_hex(), request.block, error);
+				(Default::default(), false, Default::default())
+			},
+		};
+		self.send_message(io, who, GenericMessage::StateResponse(message::StateResponse {
+			id: request.id, entries, complete, proof,
+		}));
+	}
+
+	fn on_state_response(&self, io: &mut SyncIo, who: NodeIndex, request: message::StateRequest<B::Hash>, response: message::StateResponse) {
+		trace!(target: "sync", "State response {} from {} with {} entries", response.id, who, response.entries.len());
+		self.sync.write().on_state_data(&mut ProtocolContext::new(&self.context_data, io), who, request, response);
+	}
+
 	/// Execute a closure with access to a network context and specialization.
 	pub fn with_spec<F, U>(&self, io: &mut SyncIo, f: F) -> U
 		where F: FnOnce(&mut S, &mut Context<B>) -> U
@@ -667,6 +740,15 @@ fn send_message<B: BlockT, H: ExHashT>(peers: &RwLock<HashMap<NodeIndex, Peer<B,
 				peer.request_timestamp = Some(time::Instant::now());
 			}
 		},
+		&mut GenericMessage::StateRequest(ref mut r) => {
+			let mut peers = peers.write();
+			if let Some(ref mut peer) = peers.get_mut(&who) {
+				r.id = peer.next_request_id;
+				peer.next_request_id = peer.next_request_id + 1;
+				peer.state_request = Some(r.clone());
+				peer.request_timestamp = Some(time::Instant::now());
+			}
+		},
 		_ => (),
 	}
 	io.send(who, message.encode());
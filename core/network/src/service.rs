@@ -319,6 +319,9 @@ pub trait ManageNetwork: Send + Sync {
 	fn remove_reserved_peer(&self, peer: String) -> Result<(), String>;
 	/// Add reserved peer
 	fn add_reserved_peer(&self, peer: String) -> Result<(), String>;
+	/// Replace the peers of a named priority group, e.g. "authorities". Connections to these
+	/// peers are kept even when they are not useful for syncing. An empty list removes the group.
+	fn set_priority_group(&self, group: String, peers: Vec<String>) -> Result<(), String>;
 }
 
 
@@ -338,4 +341,8 @@ impl<B: BlockT + 'static, S: Specialization<B>, H: ExHashT> ManageNetwork for Se
 	fn add_reserved_peer(&self, peer: String) -> Result<(), String> {
 		self.network.add_reserved_peer(&peer).map_err(|e| format!("{:?}", e))
 	}
+
+	fn set_priority_group(&self, group: String, peers: Vec<String>) -> Result<(), String> {
+		self.network.set_priority_group(&group, &peers).map_err(|e| format!("{:?}", e))
+	}
 }
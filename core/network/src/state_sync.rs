@@ -0,0 +1,193 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Downloading the state of a single block in chunks, so that a node can start from a recent
+//! block instead of executing the whole chain.
+
+use primitives::{Blake2Hasher, RlpCodec, H256};
+use runtime_primitives::traits::{Block as BlockT, Header as HeaderT};
+use state_machine::range_proof_check;
+use message::{self, RequestId};
+
+/// The state of a block, requested chunk by chunk in ascending key order. Each chunk comes with a
+/// proof against the state root of the block header and is checked on arrival.
+pub struct StateDownload<B: BlockT> {
+	header: B::Header,
+	entries: Vec<(Vec<u8>, Vec<u8>)>,
+	complete: bool,
+}
+
+impl<B: BlockT> StateDownload<B> {
+	/// Start downloading the state of the block with the given header.
+	pub fn new(header: B::Header) -> Self {
+		StateDownload {
+			header,
+			entries: Vec::new(),
+			complete: false,
+		}
+	}
+
+	/// Header of the block whose state is being downloaded.
+	pub fn header(&self) -> &B::Header {
+		&self.header
+	}
+
+	/// Whether all chunks have been received.
+	pub fn is_complete(&self) -> bool {
+		self.complete
+	}
+
+	/// The request for the next chunk, or `None` if all chunks have been received.
+	pub fn next_request(&self, id: RequestId) -> Option<message::StateRequest<B::Hash>> {
+		if self.complete {
+			return None;
+		}
+		Some(message::StateRequest {
+			id,
+			block: self.header.hash(),
+			start: self.next_key(),
+		})
+	}
+
+	/// Add the chunk of a response to the downloaded entries. Responses that do not continue the
+	/// download where the last one stopped, or whose proof does not match the state root, are
+	/// refused and leave the download unchanged.
+	pub fn import(&mut self, response: message::StateResponse) -> Result<(), &'static str> {
+		if self.complete {
+			return Err("State download is already complete");
+		}
+		if response.entries.is_empty() && !response.complete {
+			return Err("Empty state chunk");
+		}
+
+		let root = H256::from_slice(self.header.state_root().as_ref());
+		range_proof_check::<Blake2Hasher, RlpCodec>(root, response.proof, &self.next_key(), &response.entries, response.complete)
+			.map_err(|_| "Invalid state chunk proof")?;
+
+		self.entries.extend(response.entries);
+		self.complete = response.complete;
+		Ok(())
+	}
+
+	/// Finish the download, returning the header and the entries of its state.
+	pub fn finish(self) -> Result<(B::Header, Vec<(Vec<u8>, Vec<u8>)>), &'static str> {
+		if !self.complete {
+			return Err("State download is not complete");
+		}
+		Ok((self.header, self.entries))
+	}
+
+	/// The smallest key following the last downloaded one.
+	fn next_key(&self) -> Vec<u8> {
+		match self.entries.last() {
+			Some(&(ref key, _)) => {
+				let mut next = key.clone();
+				next.push(0);
+				next
+			},
+			None => Vec::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use super::StateDownload;
+	use message::StateResponse;
+	use primitives::{Blake2Hasher, RlpCodec};
+	use runtime_primitives::testing::{Block as RawBlock, Header, Digest};
+	use runtime_primitives::traits::{BlakeTwo256, Hash as HashT};
+	use state_machine::{backend::InMemory, prove_range};
+
+	type Block = RawBlock<u64>;
+
+	fn state() -> Vec<(Vec<u8>, Vec<u8>)> {
+		vec![
+			(b"a".to_vec(), b"1".to_vec()),
+			(b"b".to_vec(), b"2".to_vec()),
+			(b"c".to_vec(), b"3".to_vec()),
+		]
+	}
+
+	fn header() -> Header {
+		Header {
+			parent_hash: Default::default(),
+			number: 10,
+			state_root: BlakeTwo256::trie_root(state()),
+			extrinsics_root: Default::default(),
+			digest: Digest::default(),
+		}
+	}
+
+	fn response(state: Vec<(Vec<u8>, Vec<u8>)>, start: &[u8], max_bytes: usize) -> StateResponse {
+		let backend = InMemory::<Blake2Hasher, RlpCodec>::from(state.into_iter().collect::<HashMap<_, _>>());
+		let (entries, complete, proof) = prove_range(backend, start, max_bytes).unwrap();
+		StateResponse { id: 0, entries, complete, proof }
+	}
+
+	#[test]
+	fn downloads_state_in_chunks() {
+		let mut download = StateDownload::<Block>::new(header());
+		assert_eq!(download.next_request(0).unwrap().start, Vec::<u8>::new());
+
+		download.import(response(state(), b"", 4)).unwrap();
+		assert_eq!(download.next_request(1).unwrap().start, b"b\0".to_vec());
+		assert!(!download.is_complete());
+
+		download.import(response(state(), b"b\0", 4)).unwrap();
+		assert!(download.is_complete());
+		assert!(download.next_request(2).is_none());
+
+		let (header, entries) = download.finish().unwrap();
+		assert_eq!(header, self::header());
+		assert_eq!(entries, state());
+	}
+
+	#[test]
+	fn refuses_chunks_not_continuing_the_download() {
+		let mut download = StateDownload::<Block>::new(header());
+		download.import(response(state(), b"", 2)).unwrap();
+		assert!(download.import(response(state(), b"", 2)).is_err());
+		assert!(download.import(response(state(), b"c", 2)).is_err());
+
+		let mut empty = response(state(), b"b\0", 2);
+		empty.entries.clear();
+		empty.complete = false;
+		assert!(download.import(empty).is_err());
+		assert_eq!(download.next_request(0).unwrap().start, b"a\0".to_vec());
+	}
+
+	#[test]
+	fn refuses_chunks_not_matching_root() {
+		let mut other = state();
+		other[1].1 = b"other".to_vec();
+		let mut download = StateDownload::<Block>::new(header());
+		assert!(download.import(response(other.clone(), b"", 4)).is_err());
+
+		let mut changed = response(state(), b"", 4);
+		changed.entries[0].1 = b"other".to_vec();
+		assert!(download.import(changed).is_err());
+
+		let mut truncated = response(state(), b"", 1024);
+		truncated.entries.pop();
+		assert!(download.import(truncated).is_err());
+		assert_eq!(download.next_request(0).unwrap().start, Vec::<u8>::new());
+
+		let download = StateDownload::<Block>::new(header());
+		assert!(download.finish().is_err());
+	}
+}
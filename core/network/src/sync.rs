@@ -14,23 +14,29 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use protocol::Context;
 use network_libp2p::{Severity, NodeIndex};
-use client::{BlockStatus, BlockOrigin, ClientInfo};
+use client::{BlockStatus, BlockOrigin, ClientInfo, ImportResult};
 use client::error::Error as ClientError;
 use blocks::{self, BlockCollection};
+use runtime_primitives::bft::Justification;
 use runtime_primitives::traits::{Block as BlockT, Header as HeaderT, As, NumberFor};
 use runtime_primitives::generic::BlockId;
 use message::{self, generic::Message as GenericMessage};
 use service::Roles;
+use config::SyncMode;
 use import_queue::ImportQueue;
+use state_sync::StateDownload;
 
 // Maximum blocks to request in a single packet.
 const MAX_BLOCKS_TO_REQUEST: usize = 128;
 // Maximum blocks to store in the import queue.
 const MAX_IMPORTING_BLOCKS: usize = 2048;
+// How far behind the best seen block to download the state when fast syncing, so that peers
+// haven't pruned it yet.
+const FAST_SYNC_DISTANCE: u64 = 16;
 
 struct PeerSync<B: BlockT> {
 	pub common_hash: B::Hash,
@@ -46,6 +52,17 @@ enum PeerSyncState<B: BlockT> {
 	Available,
 	DownloadingNew(NumberFor<B>),
 	DownloadingStale(B::Hash),
+	DownloadingState,
+}
+
+// Fast sync progress: headers are downloaded up to the target block, then the state at it.
+struct FastSync<B: BlockT> {
+	// Number of the block to download the state of, zero until peers are known.
+	target: NumberFor<B>,
+	// The state download and justification of the target block, once its header is in.
+	state: Option<(StateDownload<B>, Justification<B::Hash>)>,
+	// Peers that could not provide the state at the target.
+	tried: HashSet<NodeIndex>,
 }
 
 /// Relay chain sync strategy.
@@ -57,6 +74,7 @@ pub struct ChainSync<B: BlockT> {
 	best_queued_hash: B::Hash,
 	required_block_attributes: message::BlockAttributes,
 	import_queue: Arc<ImportQueue<B>>,
+	fast_sync: Option<FastSync<B>>,
 }
 
 /// Reported sync state.
@@ -79,9 +97,24 @@ pub struct Status<B: BlockT> {
 
 impl<B: BlockT> ChainSync<B> {
 	/// Create a new instance.
-	pub(crate) fn new(role: Roles, info: &ClientInfo<B>, import_queue: Arc<ImportQueue<B>>) -> Self {
+	pub(crate) fn new(role: Roles, sync_mode: SyncMode, info: &ClientInfo<B>, import_queue: Arc<ImportQueue<B>>) -> Self {
+		// Blocks are only executed once the state is in, so there is nothing to fast sync when
+		// starting from a later block.
+		let fast_sync = if sync_mode == SyncMode::Fast
+			&& role.intersects(Roles::FULL | Roles::AUTHORITY)
+			&& info.chain.best_number == As::sa(0)
+		{
+			Some(FastSync {
+				target: As::sa(0),
+				state: None,
+				tried: HashSet::new(),
+			})
+		} else {
+			None
+		};
+
 		let mut required_block_attributes = message::BlockAttributes::HEADER | message::BlockAttributes::JUSTIFICATION;
-		if role.intersects(Roles::FULL | Roles::AUTHORITY) {
+		if role.intersects(Roles::FULL | Roles::AUTHORITY) && fast_sync.is_none() {
 			required_block_attributes |= message::BlockAttributes::BODY;
 		}
 
@@ -93,6 +126,7 @@ impl<B: BlockT> ChainSync<B> {
 			best_queued_number: info.best_queued_number.unwrap_or(info.chain.best_number),
 			required_block_attributes,
 			import_queue,
+			fast_sync,
 		}
 	}
 
@@ -227,12 +261,18 @@ impl<B: BlockT> ChainSync<B> {
 						}
 					}
 				},
-				PeerSyncState::Available => Vec::new(),
+				PeerSyncState::Available | PeerSyncState::DownloadingState => Vec::new(),
 			}
 		} else {
 			vec![]
 		};
 
+		if self.fast_sync.is_some() {
+			self.import_headers(protocol, new_blocks);
+			self.maintain_sync(protocol);
+			return;
+		}
+
 		let best_seen = self.best_seen_block();
 		let is_best = new_blocks.first().and_then(|b| b.block.header.as_ref()).map(|h| best_seen.as_ref().map_or(false, |n| h.number() >= n));
 		let origin = if is_best.unwrap_or_default() { BlockOrigin::NetworkBroadcast } else { BlockOrigin::NetworkInitialSync };
@@ -249,6 +289,39 @@ impl<B: BlockT> ChainSync<B> {
 		self.maintain_sync(protocol);
 	}
 
+	pub(crate) fn on_state_data(&mut self, protocol: &mut Context<B>, who: NodeIndex, _request: message::StateRequest<B::Hash>, response: message::StateResponse) {
+		match self.peers.get_mut(&who) {
+			Some(ref mut peer) if peer.state == PeerSyncState::DownloadingState => peer.state = PeerSyncState::Available,
+			_ => return,
+		}
+
+		let complete = {
+			let fast = match self.fast_sync {
+				Some(ref mut fast) => fast,
+				None => return,
+			};
+			let download = match fast.state {
+				Some((ref mut download, _)) => download,
+				None => return,
+			};
+			if response.entries.is_empty() && !response.complete {
+				// the peer doesn't have the state, e.g. it has been pruned
+				trace!(target: "sync", "Peer {} can't provide the state at #{}", who, fast.target);
+				fast.tried.insert(who);
+			} else if let Err(e) = download.import(response) {
+				trace!(target: "sync", "Invalid state chunk from {}: {}", who, e);
+				fast.tried.insert(who);
+				protocol.report_peer(who, Severity::Bad(&format!("Invalid state chunk: {}", e)));
+			}
+			download.is_complete()
+		};
+
+		if complete {
+			self.finish_fast_sync(protocol);
+		}
+		self.maintain_sync(protocol);
+	}
+
 	pub fn maintain_sync(&mut self, protocol: &mut Context<B>) {
 		let peers: Vec<NodeIndex> = self.peers.keys().map(|p| *p).collect();
 		for peer in peers {
@@ -322,6 +395,10 @@ impl<B: BlockT> ChainSync<B> {
 	pub(crate) fn restart(&mut self, protocol: &mut Context<B>) {
 		self.import_queue.clear();
 		self.blocks.clear();
+		if let Some(ref mut fast) = self.fast_sync {
+			fast.state = None;
+			fast.tried.clear();
+		}
 		let ids: Vec<NodeIndex> = self.peers.keys().map(|p| *p).collect();
 		for id in ids {
 			self.new_peer(protocol, id);
@@ -346,6 +423,10 @@ impl<B: BlockT> ChainSync<B> {
 
 	// Download old block.
 	fn download_stale(&mut self, protocol: &mut Context<B>, who: NodeIndex, hash: &B::Hash) {
+		// all the blocks up to the fast sync target are downloaded anyway
+		if self.fast_sync.is_some() {
+			return;
+		}
 		if let Some(ref mut peer) = self.peers.get_mut(&who) {
 			match peer.state {
 				PeerSyncState::Available => {
@@ -367,6 +448,11 @@ impl<B: BlockT> ChainSync<B> {
 
 	// Issue a request for a peer to download new blocks, if any are available
 	fn download_new(&mut self, protocol: &mut Context<B>, who: NodeIndex) {
+		self.update_fast_sync_target(protocol);
+		if self.fast_sync.as_ref().map_or(false, |fast| fast.state.is_some()) {
+			return self.download_state(protocol, who);
+		}
+		let max_number = self.fast_sync.as_ref().map(|fast| fast.target);
 		if let Some(ref mut peer) = self.peers.get_mut(&who) {
 			let import_status = self.import_queue.status();
 			// when there are too many blocks in the queue => do not try to download new blocks
@@ -379,7 +465,9 @@ impl<B: BlockT> ChainSync<B> {
 			trace!(target: "sync", "Considering new block download from {}, common block is {}, best is {:?}", who, common_number, peer.best_number);
 			match peer.state {
 				PeerSyncState::Available => {
-					if let Some(range) = self.blocks.needed_blocks(who, MAX_BLOCKS_TO_REQUEST, peer.best_number, common_number) {
+					// while fast syncing, headers are only downloaded up to the target
+					let best_number = max_number.map_or(peer.best_number, |n| ::std::cmp::min(n, peer.best_number));
+					if let Some(range) = self.blocks.needed_blocks(who, MAX_BLOCKS_TO_REQUEST, best_number, common_number) {
 						trace!(target: "sync", "Requesting blocks from {}, ({} to {})", who, range.start, range.end);
 						let request = message::generic::BlockRequest {
 							id: 0,
@@ -400,6 +488,170 @@ impl<B: BlockT> ChainSync<B> {
 		}
 	}
 
+	// Request the next chunk of the state at the fast sync target from a peer. The chunks are
+	// requested from one peer at a time since each one starts where the last one stopped.
+	fn download_state(&mut self, protocol: &mut Context<B>, who: NodeIndex) {
+		if self.peers.values().any(|p| p.state == PeerSyncState::DownloadingState) {
+			return;
+		}
+		let fast = match self.fast_sync {
+			Some(ref fast) => fast,
+			None => return,
+		};
+		let download = match fast.state {
+			Some((ref download, _)) => download,
+			None => return,
+		};
+		if let Some(ref mut peer) = self.peers.get_mut(&who) {
+			if peer.state != PeerSyncState::Available || peer.best_number < fast.target || fast.tried.contains(&who) {
+				return;
+			}
+			if let Some(request) = download.next_request(0) {
+				trace!(target: "sync", "Requesting the state at #{} from {}", fast.target, who);
+				peer.state = PeerSyncState::DownloadingState;
+				protocol.send_message(who, GenericMessage::StateRequest(request));
+			}
+		}
+	}
+
+	// Pick the fast sync target once peers are known, and move it forward when no peer is left
+	// that can provide the state at the current one.
+	fn update_fast_sync_target(&mut self, protocol: &mut Context<B>) {
+		let best_seen = match self.best_seen_block() {
+			Some(best_seen) => best_seen,
+			None => return,
+		};
+		let target = match self.fast_sync {
+			Some(ref fast) => fast.target,
+			None => return,
+		};
+
+		if target == As::sa(0) {
+			if best_seen > As::sa(FAST_SYNC_DISTANCE) {
+				debug!(target: "sync", "Fast syncing to #{}", best_seen - As::sa(FAST_SYNC_DISTANCE));
+				if let Some(ref mut fast) = self.fast_sync {
+					fast.target = best_seen - As::sa(FAST_SYNC_DISTANCE);
+				}
+			} else {
+				debug!(target: "sync", "Chain is too short to fast sync, best seen block is #{}", best_seen);
+				self.stop_fast_sync();
+			}
+			return;
+		}
+
+		let held = {
+			let fast = match self.fast_sync {
+				Some(ref mut fast) => fast,
+				None => return,
+			};
+			if fast.state.is_none() || best_seen <= target + As::sa(FAST_SYNC_DISTANCE) {
+				return;
+			}
+			let waiting = {
+				let tried = &fast.tried;
+				self.peers.iter().any(|(who, p)| p.state == PeerSyncState::DownloadingState || (p.best_number >= target && !tried.contains(who)))
+			};
+			if waiting {
+				return;
+			}
+			debug!(target: "sync", "No peer can provide the state at #{}, fast syncing to #{}", target, best_seen - As::sa(FAST_SYNC_DISTANCE));
+			fast.target = best_seen - As::sa(FAST_SYNC_DISTANCE);
+			fast.tried.clear();
+			fast.state.take()
+		};
+
+		// the old target becomes an ordinary header on the way to the new one
+		if let Some((download, justification)) = held {
+			let header = download.header().clone();
+			let hash = header.hash();
+			match protocol.client().import_header(BlockOrigin::NetworkInitialSync, header, justification) {
+				Ok(ImportResult::Queued) | Ok(ImportResult::AlreadyInChain) => self.block_imported(&hash, target),
+				result => {
+					debug!(target: "sync", "Error importing header #{} ({}): {:?}", target, hash, result);
+					self.restart(protocol);
+				},
+			}
+		}
+	}
+
+	// Import the headers downloaded while fast syncing, holding back the one at the target to
+	// download its state.
+	fn import_headers(&mut self, protocol: &mut Context<B>, blocks: Vec<blocks::BlockData<B>>) {
+		for block in blocks {
+			let origin = block.origin;
+			let (header, justification) = match (block.block.header, block.block.justification) {
+				(Some(header), Some(justification)) => (header, justification),
+				_ => {
+					protocol.report_peer(origin, Severity::Bad("Block without header or justification received while fast syncing"));
+					self.restart(protocol);
+					return;
+				},
+			};
+			let hash = header.hash();
+			let number = *header.number();
+			let target = match self.fast_sync {
+				Some(ref fast) => fast.target,
+				None => return,
+			};
+
+			if number > target {
+				break;
+			}
+			if number == target {
+				trace!(target: "sync", "Downloading the state at #{} ({})", number, hash);
+				if let Some(ref mut fast) = self.fast_sync {
+					fast.state = Some((StateDownload::new(header), justification));
+				}
+				self.block_imported(&hash, number);
+				break;
+			}
+			match protocol.client().import_header(BlockOrigin::NetworkInitialSync, header, justification) {
+				Ok(ImportResult::Queued) | Ok(ImportResult::AlreadyInChain) => self.block_imported(&hash, number),
+				Ok(result) => {
+					trace!(target: "sync", "Header #{} ({}) from {} not imported: {:?}", number, hash, origin, result);
+					protocol.report_peer(origin, Severity::Bad(&format!("Header #{} could not be imported: {:?}", number, result)));
+					self.restart(protocol);
+					return;
+				},
+				Err(e) => {
+					debug!(target: "sync", "Error importing header #{} ({}): {:?}", number, hash, e);
+					self.restart(protocol);
+					return;
+				},
+			}
+		}
+	}
+
+	// Import the target block with the downloaded state and continue with full sync after it.
+	fn finish_fast_sync(&mut self, protocol: &mut Context<B>) {
+		let (download, justification) = match self.fast_sync.as_mut().and_then(|fast| fast.state.take()) {
+			Some(state) => state,
+			None => return,
+		};
+		let (header, state) = match download.finish() {
+			Ok(finished) => finished,
+			Err(_) => return,
+		};
+		let hash = header.hash();
+		let number = *header.number();
+		match protocol.client().import_with_state(BlockOrigin::NetworkInitialSync, header, justification, state) {
+			Ok(ImportResult::Queued) | Ok(ImportResult::AlreadyInChain) => {
+				debug!(target: "sync", "Fast sync complete at #{} ({})", number, hash);
+				self.stop_fast_sync();
+				self.block_imported(&hash, number);
+			},
+			result => {
+				debug!(target: "sync", "Error importing the state at #{} ({}): {:?}", number, hash, result);
+				self.restart(protocol);
+			},
+		}
+	}
+
+	fn stop_fast_sync(&mut self) {
+		self.fast_sync = None;
+		self.required_block_attributes |= message::BlockAttributes::BODY;
+	}
+
 	fn request_ancestry(protocol: &mut Context<B>, who: NodeIndex, block: NumberFor<B>) {
 		trace!(target: "sync", "Requesting ancestry block #{} from {}", block, who);
 		let request = message::generic::BlockRequest {
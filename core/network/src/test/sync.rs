@@ -17,7 +17,7 @@
 use client::backend::Backend;
 use client::blockchain::HeaderBackend as BlockchainHeaderBackend;
 use sync::SyncState;
-use Roles;
+use {Roles, SyncMode};
 use super::*;
 
 #[test]
@@ -44,6 +44,27 @@ fn sync_from_two_peers_with_ancestry_search_works() {
 	assert!(net.peer(0).client.backend().blockchain().canon_equals_to(net.peer(1).client.backend().blockchain()));
 }
 
+#[test]
+fn fast_sync_from_two_peers_works() {
+	::env_logger::init().ok();
+	let mut net = TestNet::new(0);
+	let mut fast_config = ProtocolConfig::default();
+	fast_config.sync_mode = SyncMode::Fast;
+	net.add_peer(&fast_config);
+	net.add_peer(&ProtocolConfig::default());
+	net.add_peer(&ProtocolConfig::default());
+	net.peer(1).push_blocks(100, false);
+	net.peer(2).push_blocks(100, false);
+	net.sync();
+
+	let info = net.peer(0).client.info().unwrap();
+	assert_eq!(info.chain.best_number, 100);
+	assert_eq!(info.chain.best_hash, net.peer(1).client.info().unwrap().chain.best_hash);
+	// only the blocks after the fast sync target were downloaded in full
+	assert!(net.peer(0).client.body(&BlockId::Number(1)).unwrap().is_none());
+	assert!(net.peer(0).client.body(&BlockId::Number(100)).unwrap().is_some());
+}
+
 #[test]
 fn sync_long_chain_works() {
 	let mut net = TestNet::new(2);
@@ -0,0 +1,256 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+// tag::description[]
+//! Simple BLS12-381 signature API.
+// end::description[]
+//!
+//! Public keys live in G1 (48 bytes compressed) and signatures in G2 (96 bytes compressed).
+//! Messages are hashed onto G2 following the IETF `hash_to_curve` draft, using the ciphersuite
+//! of the "basic" BLS signature scheme.
+//!
+//! As in the basic scheme, an aggregate signature is only valid over distinct messages: were the
+//! same message allowed twice, a key made up from the others (a rogue key) could forge one
+//! without any proof of possession of the secret keys.
+
+use bls12_381::{G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar};
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::multi_miller_loop;
+use blake2_rfc;
+use ring::rand::{SecureRandom, SystemRandom};
+use bls_sha2 as sha2;
+
+/// Domain separation tag of the hash onto G2.
+const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// A public key, a compressed point of G1.
+#[derive(Clone)]
+pub struct Public(pub [u8; 48]);
+
+/// A signature, a compressed point of G2.
+#[derive(Clone)]
+pub struct Signature(pub [u8; 96]);
+
+/// A key pair.
+pub struct Pair {
+	secret: Scalar,
+	public: G1Affine,
+}
+
+impl PartialEq for Public {
+	fn eq(&self, other: &Public) -> bool {
+		&self.0[..] == &other.0[..]
+	}
+}
+
+impl Eq for Public {}
+
+impl ::std::hash::Hash for Public {
+	fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+		self.0[..].hash(state);
+	}
+}
+
+impl PartialEq for Signature {
+	fn eq(&self, other: &Signature) -> bool {
+		&self.0[..] == &other.0[..]
+	}
+}
+
+impl Eq for Signature {}
+
+impl ::std::fmt::Debug for Public {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		write!(f, "{}", ::hexdisplay::HexDisplay::from(&&self.0[..]))
+	}
+}
+
+impl ::std::fmt::Debug for Signature {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		write!(f, "{}", ::hexdisplay::HexDisplay::from(&&self.0[..]))
+	}
+}
+
+impl AsRef<[u8]> for Public {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+impl AsRef<[u8]> for Signature {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+fn hash_to_g2(message: &[u8]) -> G2Affine {
+	<G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(message, DST).into()
+}
+
+fn public_from_slice(public: &[u8]) -> Option<G1Affine> {
+	if public.len() != 48 {
+		return None;
+	}
+	let mut raw = [0u8; 48];
+	raw.copy_from_slice(public);
+	let point: Option<G1Affine> = G1Affine::from_compressed(&raw).into();
+	// the identity is a valid encoding, but not a valid public key.
+	point.filter(|p| !bool::from(p.is_identity()))
+}
+
+fn signature_from_slice(sig: &[u8]) -> Option<G2Affine> {
+	if sig.len() != 96 {
+		return None;
+	}
+	let mut raw = [0u8; 96];
+	raw.copy_from_slice(sig);
+	G2Affine::from_compressed(&raw).into()
+}
+
+/// Verify a message without type checking the parameters' types for the right size.
+pub fn verify<P: AsRef<[u8]>>(sig: &[u8], message: &[u8], public: P) -> bool {
+	verify_aggregate(sig, ::std::iter::once((message, public)))
+}
+
+/// Verify an aggregate signature of each message by its respective signer.
+///
+/// Returns `false` if there are no signers at all, or if any message is signed more than once.
+pub fn verify_aggregate<I, M, P>(sig: &[u8], signed: I) -> bool where
+	I: IntoIterator<Item = (M, P)>,
+	M: AsRef<[u8]>,
+	P: AsRef<[u8]>,
+{
+	let sig = match signature_from_slice(sig) {
+		Some(sig) => sig,
+		None => return false,
+	};
+
+	let mut g1s = vec![-G1Affine::generator()];
+	let mut g2s = vec![G2Prepared::from(sig)];
+	let mut messages = ::std::collections::HashSet::new();
+	for (message, public) in signed {
+		if !messages.insert(message.as_ref().to_vec()) {
+			return false;
+		}
+		match public_from_slice(public.as_ref()) {
+			Some(public) => g1s.push(public),
+			None => return false,
+		}
+		g2s.push(G2Prepared::from(hash_to_g2(message.as_ref())));
+	}
+	if g1s.len() == 1 {
+		return false;
+	}
+
+	let terms = g1s.iter().zip(g2s.iter()).collect::<Vec<_>>();
+	multi_miller_loop(&terms[..]).final_exponentiation() == Gt::identity()
+}
+
+/// Aggregate a number of signatures into one, `None` if any of them is malformed or if there
+/// are none.
+pub fn aggregate<S: AsRef<[u8]>>(sigs: &[S]) -> Option<Signature> {
+	if sigs.is_empty() {
+		return None;
+	}
+	let mut acc = G2Projective::identity();
+	for sig in sigs {
+		acc += signature_from_slice(sig.as_ref())?;
+	}
+	Some(Signature(G2Affine::from(acc).to_compressed()))
+}
+
+impl Signature {
+	/// Verify this signature on a message by `public`.
+	pub fn verify<P: AsRef<[u8]>>(&self, message: &[u8], public: P) -> bool {
+		verify(&self.0[..], message, public)
+	}
+}
+
+impl Pair {
+	/// Generate new secure (random) key pair.
+	pub fn generate() -> Pair {
+		let mut seed = [0u8; 32];
+		SystemRandom::new().fill(&mut seed).expect("system randomness is available; qed");
+		Self::from_seed(&seed)
+	}
+
+	/// Make a new key pair from a secret seed.
+	/// NOTE: this is used primarily for tests.
+	pub fn from_seed(seed: &[u8; 32]) -> Pair {
+		let mut wide = [0u8; 64];
+		wide.copy_from_slice(blake2_rfc::blake2b::blake2b(64, b"bls12-381", seed).as_bytes());
+		let secret = Scalar::from_bytes_wide(&wide);
+		let public = G1Affine::from(G1Projective::generator() * secret);
+		Pair { secret, public }
+	}
+
+	/// Sign a message.
+	pub fn sign(&self, message: &[u8]) -> Signature {
+		let sig = G2Affine::from(hash_to_g2(message) * self.secret);
+		Signature(sig.to_compressed())
+	}
+
+	/// Get the public key.
+	pub fn public(&self) -> Public {
+		Public(self.public.to_compressed())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn seeded_pair_should_work() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012");
+		let public = pair.public();
+		let signature = pair.sign(b"all ok!");
+		assert!(signature.verify(b"all ok!", &public));
+		assert!(!signature.verify(b"all is not ok!", &public));
+		assert!(!signature.verify(b"all ok!", Pair::generate().public()));
+	}
+
+	#[test]
+	fn aggregate_signature_should_work() {
+		let pairs = (0u8..3).map(|i| Pair::from_seed(&[i; 32])).collect::<Vec<_>>();
+		let messages: [&[u8]; 3] = [b"zero", b"one", b"two"];
+		let sigs = pairs.iter().zip(messages.iter()).map(|(p, m)| p.sign(m)).collect::<Vec<_>>();
+		let agg = aggregate(&sigs).unwrap();
+
+		let signed = || messages.iter().zip(pairs.iter().map(|p| p.public()));
+		assert!(verify_aggregate(&agg.0[..], signed()));
+		assert!(!verify_aggregate(&agg.0[..], signed().take(2)));
+		assert!(!verify_aggregate(&sigs[0].0[..], signed()));
+		assert!(!verify_aggregate(&agg.0[..], ::std::iter::empty::<(&[u8], Public)>()));
+	}
+
+	#[test]
+	fn aggregate_signature_over_a_repeated_message_is_rejected() {
+		let pairs = (0u8..2).map(|i| Pair::from_seed(&[i; 32])).collect::<Vec<_>>();
+		let sigs = pairs.iter().map(|p| p.sign(b"same")).collect::<Vec<_>>();
+		let agg = aggregate(&sigs).unwrap();
+		assert!(!verify_aggregate(&agg.0[..], pairs.iter().map(|p| (&b"same"[..], p.public()))));
+	}
+
+	#[test]
+	fn malformed_input_is_rejected() {
+		let pair = Pair::from_seed(&[1; 32]);
+		let signature = pair.sign(b"all ok!");
+		assert!(!verify(&signature.0[..95], b"all ok!", &pair.public()));
+		assert!(!verify(&signature.0[..], b"all ok!", &[0u8; 48][..]));
+		assert!(aggregate::<Signature>(&[]).is_none());
+	}
+}
@@ -0,0 +1,189 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compact (variable-length) encoding of unsigned integers.
+//!
+//! The two least significant bits of the first byte tell the encoding mode:
+//!
+//! - `0b00`: single byte mode; the upper six bits are the value (values below 2^6).
+//! - `0b01`: two byte mode; the upper six bits and the following byte are the little-endian
+//!   value (values below 2^14).
+//! - `0b10`: four byte mode; the upper six bits and the following three bytes are the
+//!   little-endian value (values below 2^30).
+//! - `0b11`: big integer mode; the upper six bits are the number of bytes following minus four,
+//!   the value is contained, little-endian, in those bytes.
+//!
+//! Each value has exactly one encoding, in the smallest mode and with as few bytes as it fits in;
+//! decoding rejects any other.
+
+use codec::{Encode, Decode, Input, Output};
+
+/// Wrapper which encodes the inner unsigned integer in the compact format.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct Compact<T>(pub T);
+
+impl<T> From<T> for Compact<T> {
+	fn from(x: T) -> Self {
+		Compact(x)
+	}
+}
+
+/// Unsigned integer types which have a compact encoding.
+pub trait HasCompact: Copy {
+	/// Widen the value to a `u128`.
+	fn to_u128(self) -> u128;
+
+	/// Narrow a `u128` back to this type, if it fits.
+	fn from_u128(x: u128) -> Option<Self>;
+}
+
+macro_rules! impl_has_compact {
+	( $( $t:ty ),* ) => { $(
+		impl HasCompact for $t {
+			fn to_u128(self) -> u128 {
+				self as u128
+			}
+
+			fn from_u128(x: u128) -> Option<Self> {
+				if x > <$t>::max_value() as u128 {
+					None
+				} else {
+					Some(x as $t)
+				}
+			}
+		}
+	)* }
+}
+
+impl_has_compact!(u8, u16, u32, u64, u128);
+
+impl<T: HasCompact> Encode for Compact<T> {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		match self.0.to_u128() {
+			x @ 0..=0b0011_1111 => dest.push_byte((x as u8) << 2),
+			x @ 0..=0b0011_1111_1111_1111 => {
+				let x = ((x as u16) << 2) | 0b01;
+				dest.write(&[x as u8, (x >> 8) as u8]);
+			}
+			x @ 0..=0b0011_1111_1111_1111_1111_1111_1111_1111 => {
+				let x = ((x as u32) << 2) | 0b10;
+				dest.write(&[x as u8, (x >> 8) as u8, (x >> 16) as u8, (x >> 24) as u8]);
+			}
+			x => {
+				let bytes = 16 - x.leading_zeros() / 8;
+				dest.push_byte((((bytes - 4) as u8) << 2) | 0b11);
+				for i in 0..bytes {
+					dest.push_byte((x >> (8 * i)) as u8);
+				}
+			}
+		}
+	}
+}
+
+impl<T: HasCompact> Decode for Compact<T> {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		let prefix = input.read_byte()?;
+		let x = match prefix & 0b11 {
+			0b00 => u128::from(prefix >> 2),
+			0b01 => {
+				let x = u128::from(prefix >> 2) | (u128::from(input.read_byte()?) << 6);
+				if x <= 0b0011_1111 {
+					// non-canonical encoding.
+					return None;
+				}
+				x
+			}
+			0b10 => {
+				let mut x = u128::from(prefix >> 2);
+				for i in 0..3 {
+					x |= u128::from(input.read_byte()?) << (6 + 8 * i);
+				}
+				if x <= 0b0011_1111_1111_1111 {
+					return None;
+				}
+				x
+			}
+			_ => {
+				let bytes = (prefix >> 2) as u32 + 4;
+				if bytes > 16 {
+					return None;
+				}
+				let mut x = 0u128;
+				let mut last = 0u8;
+				for i in 0..bytes {
+					last = input.read_byte()?;
+					x |= u128::from(last) << (8 * i);
+				}
+				// non-canonical encoding: more bytes than the value needs, or a value which fits in a
+				// smaller mode.
+				if last == 0 || x <= 0b0011_1111_1111_1111_1111_1111_1111_1111 {
+					return None;
+				}
+				x
+			}
+		};
+		T::from_u128(x).map(Compact)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn compact_encoding_works() {
+		let tests: [(u64, usize); 10] = [
+			(0, 1), (63, 1), (64, 2), (16383, 2), (16384, 4), (1073741823, 4),
+			(1073741824, 5), ((1 << 32) - 1, 5), (1 << 32, 6), (u64::max_value(), 9),
+		];
+		for &(n, l) in tests.iter() {
+			let encoded = Compact(n).encode();
+			assert_eq!(encoded.len(), l);
+			assert_eq!(<Compact<u64>>::decode(&mut &encoded[..]), Some(Compact(n)));
+		}
+	}
+
+	#[test]
+	fn compact_encoding_matches_reference_bytes() {
+		assert_eq!(Compact(1u32).encode(), vec![0x04]);
+		assert_eq!(Compact(69u32).encode(), vec![0x15, 0x01]);
+		assert_eq!(Compact(65535u32).encode(), vec![0xfe, 0xff, 0x03, 0x00]);
+		assert_eq!(Compact(1u64 << 32).encode(), vec![0x07, 0x00, 0x00, 0x00, 0x00, 0x01]);
+		assert_eq!(Compact(u128::max_value()).encode(), vec![0x33].into_iter().chain(vec![0xff; 16]).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn compact_encoding_works_for_u128() {
+		for &n in [0, 1 << 40, u64::max_value() as u128, (u64::max_value() as u128) + 1, u128::max_value()].iter() {
+			let encoded = Compact(n).encode();
+			assert_eq!(<Compact<u128>>::decode(&mut &encoded[..]), Some(Compact(n)));
+		}
+		assert_eq!(<Compact<u64>>::decode(&mut &Compact(1u128 << 64).encode()[..]), None);
+	}
+
+	#[test]
+	fn compact_decoding_rejects_overflow_and_non_canonical() {
+		assert_eq!(<Compact<u8>>::decode(&mut &Compact(256u32).encode()[..]), None);
+		assert_eq!(<Compact<u32>>::decode(&mut &[0b01u8, 0][..]), None);
+		assert_eq!(<Compact<u32>>::decode(&mut &[0b10u8, 0, 0, 0][..]), None);
+		// 2^30 - 1 fits in the four byte mode.
+		assert_eq!(<Compact<u32>>::decode(&mut &[0b11u8, 0xff, 0xff, 0xff, 0x3f][..]), None);
+		// 2^32 with a trailing zero byte.
+		assert_eq!(<Compact<u64>>::decode(&mut &[0b1011u8, 0, 0, 0, 0, 1, 0][..]), None);
+		assert_eq!(<Compact<u64>>::decode(&mut &[0b0111u8, 0, 0, 0, 0, 1][..]), Some(Compact(1 << 32)));
+	}
+}
@@ -0,0 +1,193 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding of input that can't be trusted, e.g. extrinsics received from the network.
+//!
+//! `Decode` of a recursive type, e.g. a call holding another call, recurses as deep as the input
+//! nests it, so a crafted input may exhaust the stack before its decoding fails. Such a type
+//! enters a `DepthGuard` each time it is decoded, which fails once deeper than the limit given to
+//! `decode_with_depth_limit`. The limit holds on the current thread until that decoding returns;
+//! outside of it, decoding is as deep as the input.
+
+use codec::{Decode, Input};
+
+/// The depth recursive types are decoded to in extrinsics and in the input of the runtime.
+pub const MAX_DECODE_DEPTH: u32 = 256;
+
+/// The depth of the value being decoded, and the limit it may not go past.
+#[derive(Clone, Copy)]
+struct Depth {
+	current: u32,
+	limit: u32,
+}
+
+const UNLIMITED: Depth = Depth { current: 0, limit: u32::max_value() };
+
+#[cfg(feature = "std")]
+thread_local!(static DEPTH: ::std::cell::Cell<Depth> = ::std::cell::Cell::new(UNLIMITED));
+
+#[cfg(feature = "std")]
+fn with_depth<R, F: FnOnce(&mut Depth) -> R>(f: F) -> R {
+	DEPTH.with(|cell| {
+		let mut depth = cell.get();
+		let res = f(&mut depth);
+		cell.set(depth);
+		res
+	})
+}
+
+// The runtime runs on a single thread without `std`.
+#[cfg(not(feature = "std"))]
+static mut DEPTH: Depth = UNLIMITED;
+
+#[cfg(not(feature = "std"))]
+fn with_depth<R, F: FnOnce(&mut Depth) -> R>(f: F) -> R {
+	unsafe { f(&mut DEPTH) }
+}
+
+/// One more level of a recursive type being decoded, left when dropped.
+#[must_use = "the level is left as soon as the guard is dropped"]
+pub struct DepthGuard(());
+
+impl DepthGuard {
+	/// Enter one more level, or `None` if that is past the limit, in which case the decoding
+	/// should fail.
+	pub fn enter() -> Option<DepthGuard> {
+		with_depth(|depth| if depth.current < depth.limit {
+			depth.current += 1;
+			Some(DepthGuard(()))
+		} else {
+			None
+		})
+	}
+}
+
+impl Drop for DepthGuard {
+	fn drop(&mut self) {
+		with_depth(|depth| depth.current -= 1)
+	}
+}
+
+/// Restores the limit of an enclosing decoding when dropped, even if decoding panics.
+struct RestoreLimit(u32);
+
+impl Drop for RestoreLimit {
+	fn drop(&mut self) {
+		let limit = self.0;
+		with_depth(|depth| depth.limit = limit)
+	}
+}
+
+/// Decoding of the whole of an input.
+pub trait DecodeAll: Sized {
+	/// Decode a value from `input`, failing if any of it is left over.
+	fn decode_all(input: &[u8]) -> Option<Self>;
+}
+
+impl<T: Decode> DecodeAll for T {
+	fn decode_all(mut input: &[u8]) -> Option<Self> {
+		let value = T::decode(&mut input)?;
+		if input.is_empty() {
+			Some(value)
+		} else {
+			None
+		}
+	}
+}
+
+/// Decoding which fails rather than nesting recursive types too deep.
+pub trait DecodeLimit: Sized {
+	/// Decode a value from `input`, nesting recursive types at most `limit` deep.
+	fn decode_with_depth_limit<I: Input>(limit: u32, input: &mut I) -> Option<Self>;
+
+	/// Decode a value from the whole of `input`, nesting recursive types at most `limit` deep.
+	fn decode_all_with_depth_limit(limit: u32, input: &[u8]) -> Option<Self>;
+}
+
+impl<T: Decode> DecodeLimit for T {
+	fn decode_with_depth_limit<I: Input>(limit: u32, input: &mut I) -> Option<Self> {
+		// Within another limited decoding, the tighter of both limits holds.
+		let enclosing = with_depth(|depth| {
+			let enclosing = depth.limit;
+			depth.limit = depth.limit.min(depth.current.saturating_add(limit));
+			enclosing
+		});
+		let _restore = RestoreLimit(enclosing);
+		T::decode(input)
+	}
+
+	fn decode_all_with_depth_limit(limit: u32, mut input: &[u8]) -> Option<Self> {
+		let value = T::decode_with_depth_limit(limit, &mut input)?;
+		if input.is_empty() {
+			Some(value)
+		} else {
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+
+	/// A call which may hold another one, as the calls of a runtime may.
+	#[derive(Debug, PartialEq, Encode)]
+	enum Nested {
+		Leaf,
+		Node(Box<Nested>),
+	}
+
+	impl Decode for Nested {
+		fn decode<I: Input>(input: &mut I) -> Option<Self> {
+			let _guard = DepthGuard::enter()?;
+			match input.read_byte()? {
+				0 => Some(Nested::Leaf),
+				1 => Some(Nested::Node(Box::new(Nested::decode(input)?))),
+				_ => None,
+			}
+		}
+	}
+
+	fn nested(depth: usize) -> Nested {
+		(1..depth).fold(Nested::Leaf, |inner, _| Nested::Node(Box::new(inner)))
+	}
+
+	#[test]
+	fn recursive_types_are_decoded_up_to_the_limit() {
+		let encoded = nested(3).encode();
+		assert_eq!(Nested::decode_with_depth_limit(3, &mut &encoded[..]), Some(nested(3)));
+		assert_eq!(Nested::decode_with_depth_limit(2, &mut &encoded[..]), None);
+		// The limit is gone once the limited decoding returns.
+		assert_eq!(Nested::decode(&mut &encoded[..]), Some(nested(3)));
+	}
+
+	#[test]
+	fn deeply_nested_input_fails_without_exhausting_the_stack() {
+		let encoded = vec![1u8; 1_000_000];
+		assert_eq!(Nested::decode_with_depth_limit(MAX_DECODE_DEPTH, &mut &encoded[..]), None);
+	}
+
+	#[test]
+	fn decode_all_refuses_left_over_input() {
+		let mut encoded = 5u32.encode();
+		assert_eq!(u32::decode_all(&encoded), Some(5));
+		encoded.push(0);
+		assert_eq!(u32::decode_all(&encoded), None);
+		assert_eq!(u32::decode_all_with_depth_limit(1, &encoded), None);
+		assert_eq!(u16::decode_all(&[1, 0, 0][..]), None);
+	}
+}
@@ -0,0 +1,297 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+// tag::description[]
+//! Simple ECDSA (secp256k1) API.
+// end::description[]
+//!
+//! Messages are hashed with Blake2-256 before being signed. Signatures are recoverable: they are
+//! 65 bytes long, the 64-byte `r || s` followed by the recovery id.
+//!
+//! A key also has an Ethereum address, and may sign Ethereum messages, which are hashed with
+//! Keccak-256 after being prefixed as by `eth_sign`.
+
+use blake2_rfc;
+use secp256k1;
+use ring::rand::{SecureRandom, SystemRandom};
+use hash::H160;
+use hashing::keccak_256;
+
+/// A public key, in its 33-byte compressed form.
+#[derive(Clone)]
+pub struct Public(pub [u8; 33]);
+
+/// A recoverable signature.
+#[derive(Clone)]
+pub struct Signature(pub [u8; 65]);
+
+/// A key pair.
+pub struct Pair {
+	public: secp256k1::PublicKey,
+	secret: secp256k1::SecretKey,
+}
+
+impl PartialEq for Public {
+	fn eq(&self, other: &Public) -> bool {
+		&self.0[..] == &other.0[..]
+	}
+}
+
+impl Eq for Public {}
+
+impl ::std::hash::Hash for Public {
+	fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+		self.0[..].hash(state);
+	}
+}
+
+impl PartialEq for Signature {
+	fn eq(&self, other: &Signature) -> bool {
+		&self.0[..] == &other.0[..]
+	}
+}
+
+impl Eq for Signature {}
+
+impl ::std::fmt::Debug for Signature {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		write!(f, "{}", ::hexdisplay::HexDisplay::from(&&self.0[..]))
+	}
+}
+
+impl ::std::fmt::Debug for Public {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		write!(f, "{}", ::hexdisplay::HexDisplay::from(&&self.0[..]))
+	}
+}
+
+impl AsRef<[u8]> for Public {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+impl AsRef<[u8]> for Signature {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+/// An error recovering the signer of a signature.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum RecoverError {
+	/// The recovery id is invalid.
+	BadV,
+	/// The signature doesn't belong to any public key.
+	BadSignature,
+}
+
+fn recover_public(sig: &[u8; 65], msg_hash: &[u8; 32]) -> Result<secp256k1::PublicKey, RecoverError> {
+	let mut rs = [0u8; 64];
+	rs.copy_from_slice(&sig[..64]);
+	let v = secp256k1::RecoveryId::parse(if sig[64] > 26 { sig[64] - 27 } else { sig[64] })
+		.map_err(|_| RecoverError::BadV)?;
+	let rs = secp256k1::Signature::parse(&rs);
+	secp256k1::recover(&secp256k1::Message::parse(msg_hash), &rs, &v)
+		.map_err(|_| RecoverError::BadSignature)
+}
+
+/// Recover the uncompressed public key (without the leading `0x04` tag) which signed the given
+/// message hash.
+///
+/// The recovery id may be given either as `0`/`1` or in the Ethereum style as `27`/`28`.
+pub fn recover(sig: &[u8; 65], msg_hash: &[u8; 32]) -> Result<[u8; 64], RecoverError> {
+	let public = recover_public(sig, msg_hash)?;
+	let mut res = [0u8; 64];
+	res.copy_from_slice(&public.serialize()[1..65]);
+	Ok(res)
+}
+
+/// Recover the compressed public key which signed the given message hash.
+pub fn recover_compressed(sig: &[u8; 65], msg_hash: &[u8; 32]) -> Result<[u8; 33], RecoverError> {
+	recover_public(sig, msg_hash).map(|public| public.serialize_compressed())
+}
+
+/// The Ethereum address of an uncompressed public key (without the leading `0x04` tag): the last
+/// 20 bytes of its Keccak-256 hash.
+pub fn eth_address(public: &[u8; 64]) -> H160 {
+	H160::from_slice(&keccak_256(&public[..])[12..])
+}
+
+/// The message `eth_sign` signs for `what`: `what` prefixed with `"\x19Ethereum Signed Message:\n"`
+/// and its length in decimal.
+pub fn ethereum_signable_message(what: &[u8]) -> Vec<u8> {
+	let mut v = b"\x19Ethereum Signed Message:\n".to_vec();
+	v.extend(what.len().to_string().as_bytes());
+	v.extend_from_slice(what);
+	v
+}
+
+/// Recover the Ethereum address which signed `what` with `eth_sign`.
+pub fn eth_recover(sig: &Signature, what: &[u8]) -> Option<H160> {
+	let hash = keccak_256(&ethereum_signable_message(what));
+	recover(&sig.0, &hash).ok().map(|public| eth_address(&public))
+}
+
+/// Verify a signature on a message.
+pub fn verify<P: AsRef<[u8]>>(sig: &Signature, message: &[u8], public: P) -> bool {
+	let hash = blake2_256(message);
+	match recover_compressed(&sig.0, &hash) {
+		Ok(recovered) => &recovered[..] == public.as_ref(),
+		Err(_) => false,
+	}
+}
+
+fn blake2_256(data: &[u8]) -> [u8; 32] {
+	let mut r = [0u8; 32];
+	r.copy_from_slice(blake2_rfc::blake2b::blake2b(32, &[], data).as_bytes());
+	r
+}
+
+impl Public {
+	/// The Ethereum address of this key.
+	pub fn eth_address(&self) -> H160 {
+		let public = secp256k1::PublicKey::parse_compressed(&self.0)
+			.expect("only valid keys are constructed; qed");
+		let mut r = [0u8; 64];
+		r.copy_from_slice(&public.serialize()[1..65]);
+		eth_address(&r)
+	}
+}
+
+impl Signature {
+	/// Recover the public key of the signer of `message`.
+	pub fn recover(&self, message: &[u8]) -> Option<Public> {
+		recover_compressed(&self.0, &blake2_256(message)).ok().map(Public)
+	}
+}
+
+impl Pair {
+	/// Generate new secure (random) key pair.
+	pub fn generate() -> Pair {
+		let rng = SystemRandom::new();
+		loop {
+			let mut seed = [0u8; 32];
+			rng.fill(&mut seed).expect("system randomness is available; qed");
+			if let Some(pair) = Self::from_seed(&seed) {
+				return pair;
+			}
+		}
+	}
+
+	/// Make a new key pair from a secret seed, `None` if the seed isn't a valid secret key.
+	/// NOTE: this is used primarily for tests.
+	pub fn from_seed(seed: &[u8; 32]) -> Option<Pair> {
+		let secret = secp256k1::SecretKey::parse(seed).ok()?;
+		let public = secp256k1::PublicKey::from_secret_key(&secret);
+		Some(Pair { public, secret })
+	}
+
+	/// The secret seed of this pair.
+	pub fn seed(&self) -> [u8; 32] {
+		self.secret.serialize()
+	}
+
+	/// Sign a message.
+	pub fn sign(&self, message: &[u8]) -> Signature {
+		self.sign_prehashed(&blake2_256(message))
+	}
+
+	/// Sign a message as `eth_sign` does, with an Ethereum-style recovery id of `27` or `28`.
+	pub fn sign_ethereum(&self, what: &[u8]) -> Signature {
+		let mut sig = self.sign_prehashed(&keccak_256(&ethereum_signable_message(what)));
+		sig.0[64] += 27;
+		sig
+	}
+
+	/// Sign the 32-byte hash of a message.
+	pub fn sign_prehashed(&self, hash: &[u8; 32]) -> Signature {
+		let message = secp256k1::Message::parse(hash);
+		let (sig, v) = secp256k1::sign(&message, &self.secret)
+			.expect("message is a 32-byte hash and the secret key is valid; qed");
+		let mut r = [0u8; 65];
+		r[..64].copy_from_slice(&sig.serialize()[..]);
+		r[64] = v.serialize();
+		Signature(r)
+	}
+
+	/// Get the public key.
+	pub fn public(&self) -> Public {
+		Public(self.public.serialize_compressed())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn generated_pair_should_work() {
+		let pair = Pair::generate();
+		let public = pair.public();
+		let message = b"Something important";
+		let signature = pair.sign(&message[..]);
+		assert!(verify(&signature, &message[..], &public));
+		assert!(!verify(&signature, b"Something else", &public));
+	}
+
+	#[test]
+	fn seeded_pair_should_work() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012").unwrap();
+		assert_eq!(Pair::from_seed(&pair.seed()).unwrap().public(), pair.public());
+		let signature = pair.sign(b"all ok!");
+		assert_eq!(signature.recover(b"all ok!"), Some(pair.public()));
+	}
+
+	#[test]
+	fn recover_works_with_ethereum_style_recovery_id() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012").unwrap();
+		let mut signature = pair.sign(b"all ok!");
+		let hash = blake2_256(b"all ok!");
+		let uncompressed = recover(&signature.0, &hash).unwrap();
+
+		signature.0[64] += 27;
+		assert_eq!(&recover(&signature.0, &hash).unwrap()[..], &uncompressed[..]);
+		assert_eq!(&recover_compressed(&signature.0, &hash).unwrap()[..], &pair.public().0[..]);
+
+		signature.0[64] = 42;
+		assert_eq!(recover(&signature.0, &hash).err(), Some(RecoverError::BadV));
+	}
+
+	#[test]
+	fn eth_address_is_derived_from_the_uncompressed_key() {
+		let mut seed = [0u8; 32];
+		seed[31] = 1;
+		let pair = Pair::from_seed(&seed).unwrap();
+		assert_eq!(pair.public().eth_address(), H160::from(hex!("7e5f4552091a69125d5dfcb7b8c2659029395bdf")));
+	}
+
+	#[test]
+	fn ethereum_signatures_are_recovered_to_their_address() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012").unwrap();
+		assert_eq!(&ethereum_signable_message(b"all ok!")[..], &b"\x19Ethereum Signed Message:\n7all ok!"[..]);
+		let signature = pair.sign_ethereum(b"all ok!");
+		assert!(signature.0[64] == 27 || signature.0[64] == 28);
+		assert_eq!(eth_recover(&signature, b"all ok!"), Some(pair.public().eth_address()));
+		assert!(eth_recover(&signature, b"all not ok!") != Some(pair.public().eth_address()));
+	}
+
+	#[test]
+	fn invalid_seed_is_rejected() {
+		assert!(Pair::from_seed(&[0u8; 32]).is_none());
+		assert!(Pair::from_seed(&[0xff; 32]).is_none());
+	}
+}
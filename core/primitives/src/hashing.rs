@@ -18,6 +18,8 @@
 
 use blake2_rfc;
 use twox_hash;
+use tiny_keccak;
+use sha2::{Digest, Sha256};
 
 /// Do a Blake2 512-bit hash and place result in `dest`.
 pub fn blake2_512_into(data: &[u8], dest: &mut [u8; 64]) {
@@ -104,3 +106,25 @@ pub fn twox_256(data: &[u8]) -> [u8; 32] {
 	twox_256_into(data, &mut r);
 	r
 }
+
+/// Do a keccak 256-bit hash and place result in `dest`.
+pub fn keccak_256_into(data: &[u8], dest: &mut [u8; 32]) {
+	*dest = tiny_keccak::keccak256(data);
+}
+
+/// Do a keccak 256-bit hash and return result.
+pub fn keccak_256(data: &[u8]) -> [u8; 32] {
+	tiny_keccak::keccak256(data)
+}
+
+/// Do a sha2 256-bit hash and place result in `dest`.
+pub fn sha2_256_into(data: &[u8], dest: &mut [u8; 32]) {
+	dest.copy_from_slice(&Sha256::digest(data)[..]);
+}
+
+/// Do a sha2 256-bit hash and return result.
+pub fn sha2_256(data: &[u8]) -> [u8; 32] {
+	let mut r = [0; 32];
+	sha2_256_into(data, &mut r);
+	r
+}
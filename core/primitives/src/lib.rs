@@ -46,11 +46,23 @@ extern crate twox_hash;
 #[cfg(feature = "std")]
 extern crate blake2_rfc;
 #[cfg(feature = "std")]
+extern crate tiny_keccak;
+#[cfg(feature = "std")]
+extern crate sha2;
+#[cfg(feature = "std")]
 extern crate ring;
 #[cfg(feature = "std")]
 extern crate base58;
 #[cfg(feature = "std")]
 extern crate untrusted;
+#[cfg(feature = "std")]
+extern crate secp256k1;
+#[cfg(feature = "std")]
+extern crate schnorrkel;
+#[cfg(feature = "bls")]
+extern crate bls12_381;
+#[cfg(feature = "bls")]
+extern crate bls_sha2;
 #[cfg(test)]
 #[macro_use]
 extern crate hex_literal;
@@ -96,14 +108,23 @@ pub mod bytes;
 #[cfg(feature = "std")]
 pub mod hashing;
 #[cfg(feature = "std")]
-pub use hashing::{blake2_256, twox_128, twox_256};
+pub use hashing::{blake2_256, twox_128, twox_256, keccak_256, sha2_256};
 #[cfg(feature = "std")]
 pub mod hexdisplay;
 #[cfg(feature = "std")]
 pub mod ed25519;
+#[cfg(feature = "std")]
+pub mod sr25519;
+#[cfg(feature = "std")]
+pub mod ecdsa;
+#[cfg(feature = "bls")]
+pub mod bls;
 
 pub mod u32_trait;
 
+pub mod compact;
+pub mod decode_limit;
+
 pub mod hash;
 mod hasher;
 pub mod sandbox;
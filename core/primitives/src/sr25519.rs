@@ -0,0 +1,205 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+// tag::description[]
+//! Simple sr25519 (Schnorr-Ristretto) API.
+// end::description[]
+//!
+//! Messages are signed within the `substrate` signing context, so that a signature made for
+//! Substrate can't be replayed by another protocol using the same keys.
+
+use blake2_rfc;
+use schnorrkel::{signing_context, Keypair, MiniSecretKey, PublicKey};
+use ring::rand::{SecureRandom, SystemRandom};
+use base58::{ToBase58, FromBase58};
+use hash::H512;
+
+pub use ed25519::PublicError;
+
+/// The context messages are signed in.
+const SIGNING_CTX: &[u8] = b"substrate";
+
+/// Alias to 512-bit hash when used in the context of a signature on the relay chain.
+pub type Signature = H512;
+
+/// Verify a message without type checking the parameters' types for the right size.
+pub fn verify<P: AsRef<[u8]>>(sig: &[u8], message: &[u8], public: P) -> bool {
+	let signature = match ::schnorrkel::Signature::from_bytes(sig) {
+		Ok(signature) => signature,
+		Err(_) => return false,
+	};
+	match PublicKey::from_bytes(public.as_ref()) {
+		Ok(public) => public.verify(signing_context(SIGNING_CTX).bytes(message), &signature),
+		Err(_) => false,
+	}
+}
+
+/// A public key.
+#[derive(PartialEq, Eq, Clone, Hash)]
+pub struct Public(pub [u8; 32]);
+
+/// A key pair.
+pub struct Pair(Keypair);
+
+impl Public {
+	/// A new instance from the given 32-byte `data`.
+	pub fn from_raw(data: [u8; 32]) -> Self {
+		Public(data)
+	}
+
+	/// A new instance from the given slice that should be 32 bytes long.
+	pub fn from_slice(data: &[u8]) -> Self {
+		let mut r = [0u8; 32];
+		r.copy_from_slice(data);
+		Public(r)
+	}
+
+	/// Some if the string is a properly encoded SS58Check address.
+	pub fn from_ss58check(s: &str) -> Result<Self, PublicError> {
+		let d = s.from_base58().map_err(|_| PublicError::BadBase58)?;
+		if d.len() != 35 {
+			return Err(PublicError::BadLength);
+		}
+		if d[0] != 42 {
+			return Err(PublicError::UnknownVersion);
+		}
+		if d[33..35] != blake2_rfc::blake2b::blake2b(64, &[], &d[0..33]).as_bytes()[0..2] {
+			return Err(PublicError::InvalidChecksum);
+		}
+		Ok(Self::from_slice(&d[1..33]))
+	}
+
+	/// Return a slice filled with raw data.
+	pub fn as_slice(&self) -> &[u8] {
+		&self.0[..]
+	}
+
+	/// Return the ss58-check string for this key.
+	pub fn to_ss58check(&self) -> String {
+		let mut v = vec![42u8];
+		v.extend(self.as_slice());
+		let r = blake2_rfc::blake2b::blake2b(64, &[], &v);
+		v.extend(&r.as_bytes()[0..2]);
+		v.to_base58()
+	}
+}
+
+impl AsRef<[u8; 32]> for Public {
+	fn as_ref(&self) -> &[u8; 32] {
+		&self.0
+	}
+}
+
+impl AsRef<[u8]> for Public {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+impl Into<[u8; 32]> for Public {
+	fn into(self) -> [u8; 32] {
+		self.0
+	}
+}
+
+impl AsRef<Public> for Public {
+	fn as_ref(&self) -> &Public {
+		&self
+	}
+}
+
+impl ::std::fmt::Display for Public {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		write!(f, "{}", self.to_ss58check())
+	}
+}
+
+impl ::std::fmt::Debug for Public {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		let s = self.to_ss58check();
+		write!(f, "{} ({}...)", ::hexdisplay::HexDisplay::from(&self.0), &s[0..8])
+	}
+}
+
+impl Pair {
+	/// Generate new secure (random) key pair.
+	pub fn generate() -> Pair {
+		let mut seed = [0u8; 32];
+		SystemRandom::new().fill(&mut seed).expect("system randomness is available; qed");
+		Self::from_seed(&seed)
+	}
+
+	/// Make a new key pair from a secret seed, which is expanded into the secret key.
+	/// NOTE: this is used primarily for tests.
+	pub fn from_seed(seed: &[u8; 32]) -> Pair {
+		let mini = MiniSecretKey::from_bytes(&seed[..]).expect("seed has valid length; qed");
+		Pair(mini.expand_to_keypair())
+	}
+
+	/// Sign a message.
+	pub fn sign(&self, message: &[u8]) -> Signature {
+		let context = signing_context(SIGNING_CTX);
+		Signature::from(self.0.sign(context.bytes(message)).to_bytes())
+	}
+
+	/// Get the public key.
+	pub fn public(&self) -> Public {
+		Public(self.0.public.to_bytes())
+	}
+}
+
+/// Verify a signature on a message.
+pub fn verify_strong<P: AsRef<Public>>(sig: &Signature, message: &[u8], pubkey: P) -> bool {
+	verify(&sig.0[..], message, &pubkey.as_ref().0[..])
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn generated_pair_should_work() {
+		let pair = Pair::generate();
+		let public = pair.public();
+		let message = b"Something important";
+		let signature = pair.sign(&message[..]);
+		assert!(verify_strong(&signature, &message[..], &public));
+		assert!(!verify_strong(&signature, b"Something else", &public));
+	}
+
+	#[test]
+	fn seeded_pair_should_work() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012");
+		let public = pair.public();
+		assert_eq!(Pair::from_seed(b"12345678901234567890123456789012").public(), public);
+		let signature = pair.sign(b"all ok!");
+		assert!(verify(&signature.0[..], b"all ok!", &public));
+		assert!(!verify(&signature.0[..], b"all ok!", &Pair::generate().public()));
+	}
+
+	#[test]
+	fn ed25519_signature_is_not_valid() {
+		let pair = ::ed25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let signature = pair.sign(b"all ok!");
+		assert!(!verify(&signature.0[..], b"all ok!", &pair.public()));
+	}
+
+	#[test]
+	fn ss58check_roundtrip_works() {
+		let public = Pair::from_seed(b"12345678901234567890123456789012").public();
+		assert_eq!(Public::from_ss58check(&public.to_ss58check()).unwrap(), public);
+	}
+}
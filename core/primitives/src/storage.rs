@@ -30,6 +30,27 @@ pub struct StorageKey(#[cfg_attr(feature = "std", serde(with="bytes"))] pub Vec<
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug, Hash, PartialOrd, Ord, Clone))]
 pub struct StorageData(#[cfg_attr(feature = "std", serde(with="bytes"))] pub Vec<u8>);
 
+/// Storage keys which have a meaning outside of the runtime.
+///
+/// These are stored as-is, i.e. without hashing.
+pub mod well_known_keys {
+	/// Wasm code of the runtime.
+	pub const CODE: &'static [u8] = b":code";
+
+	/// Number of wasm linear memory pages required for execution of the runtime.
+	pub const HEAP_PAGES: &'static [u8] = b":heappages";
+
+	/// Prefix of the authority set entries; each is followed by its encoded `u32` index.
+	pub const AUTHORITY_PREFIX: &'static [u8] = b":auth:";
+
+	/// Number of entries in the authority set.
+	pub const AUTHORITY_COUNT: &'static [u8] = b":auth:len";
+
+	/// Index of the extrinsic being applied, present only while the extrinsics of a block are
+	/// applied.
+	pub const EXTRINSIC_INDEX: &'static [u8] = b":extrinsic_index";
+}
+
 /// Storage change set
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug, PartialEq, Eq))]
 pub struct StorageChangeSet<Hash> {
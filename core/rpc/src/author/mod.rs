@@ -19,7 +19,6 @@
 use std::sync::Arc;
 
 use client::{self, Client};
-use codec::Decode;
 use extrinsic_pool::{
 	Pool,
 	IntoPoolError,
@@ -33,6 +32,7 @@ use extrinsic_pool::{
 use jsonrpc_macros::pubsub;
 use jsonrpc_pubsub::SubscriptionId;
 use primitives::{Bytes, Blake2Hasher, RlpCodec};
+use primitives::decode_limit::DecodeAll;
 use rpc::futures::{Sink, Stream, Future};
 use runtime_primitives::{generic, traits};
 use subscriptions::Subscriptions;
@@ -108,7 +108,7 @@ impl<B, E, P> AuthorApi<ExHash<P>, ExtrinsicFor<P>, AllExtrinsics<P>> for Author
 	type Metadata = ::metadata::Metadata;
 
 	fn submit_extrinsic(&self, xt: Bytes) -> Result<ExHash<P>> {
-		let dxt = Decode::decode(&mut &xt[..]).ok_or(error::Error::from(error::ErrorKind::BadFormat))?;
+		let dxt = DecodeAll::decode_all(&xt[..]).ok_or(error::Error::from(error::ErrorKind::BadFormat))?;
 		self.submit_rich_extrinsic(dxt)
 	}
 
@@ -130,7 +130,7 @@ impl<B, E, P> AuthorApi<ExHash<P>, ExtrinsicFor<P>, AllExtrinsics<P>> for Author
 	fn watch_extrinsic(&self, _metadata: Self::Metadata, subscriber: pubsub::Subscriber<Status<ExHash<P>>>, xt: Bytes) {
 		let submit = || -> Result<_> {
 			let best_block_hash = self.client.info()?.chain.best_hash;
-			let dxt = <<P as PoolChainApi>::Block as traits::Block>::Extrinsic::decode(&mut &xt[..]).ok_or(error::Error::from(error::ErrorKind::BadFormat))?;
+			let dxt = <<P as PoolChainApi>::Block as traits::Block>::Extrinsic::decode_all(&xt[..]).ok_or(error::Error::from(error::ErrorKind::BadFormat))?;
 			self.pool
 				.submit_and_watch(&generic::BlockId::hash(best_block_hash), dxt)
 				.map_err(|e| e.into_pool_error()
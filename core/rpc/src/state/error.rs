@@ -44,10 +44,17 @@ error_chain! {
 	}
 }
 
+const ERROR: i64 = 4000;
+
 impl From<Error> for rpc::Error {
 	fn from(e: Error) -> Self {
 		match e {
 			Error(ErrorKind::Unimplemented, _) => errors::unimplemented(),
+			Error(ErrorKind::Client(client::error::ErrorKind::StatePruned(block)), _) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(ERROR + 1),
+				message: "State already discarded for the requested block".into(),
+				data: Some(block.into()),
+			},
 			e => errors::internal(e),
 		}
 	}
@@ -64,10 +64,19 @@ build_rpc_trait! {
 		#[rpc(name = "state_getStorageSize", alias = ["state_getStorageSizeAt", ])]
 		fn storage_size(&self, StorageKey, Trailing<Hash>) -> Result<Option<u64>>;
 
+		/// Returns an entry of the node's local offchain database, as indexed by the runtime.
+		#[rpc(name = "state_getOffchainStorage")]
+		fn offchain_storage(&self, StorageKey) -> Result<Option<StorageData>>;
+
 		/// Returns the runtime metadata as JSON.
 		#[rpc(name = "state_getMetadata")]
 		fn metadata(&self, Trailing<Hash>) -> Result<serde_json::Value>;
 
+		/// Returns the events deposited in a block, decoded with the runtime metadata, as
+		/// `{ "phase", "module", "name", "params", "topics" }` objects.
+		#[rpc(name = "state_getEvents")]
+		fn events(&self, Trailing<Hash>) -> Result<Vec<serde_json::Value>>;
+
 		/// Query historical storage entries (by key) starting from a block given as the second parameter.
 		///
 		/// NOTE This first returned result contains the initial state of storage for all keys.
@@ -84,6 +93,16 @@ build_rpc_trait! {
 			#[rpc(name = "state_unsubscribeStorage")]
 			fn unsubscribe_storage(&self, SubscriptionId) -> RpcResult<bool>;
 		}
+
+		#[pubsub(name = "state_events")] {
+			/// New events subscription, notified with `{ "block", "events" }` for every imported block.
+			#[rpc(name = "state_subscribeEvents")]
+			fn subscribe_events(&self, Self::Metadata, pubsub::Subscriber<serde_json::Value>);
+
+			/// Unsubscribe from events subscription
+			#[rpc(name = "state_unsubscribeEvents")]
+			fn unsubscribe_events(&self, SubscriptionId) -> RpcResult<bool>;
+		}
 	}
 }
 
@@ -143,12 +162,23 @@ impl<B, E, Block> StateApi<Block::Hash> for State<B, E, Block> where
 		Ok(self.storage(key, block)?.map(|x| x.0.len() as u64))
 	}
 
+	fn offchain_storage(&self, key: StorageKey) -> Result<Option<StorageData>> {
+		trace!(target: "rpc", "Querying offchain storage for key {}", HexDisplay::from(&key.0));
+		Ok(self.client.offchain_storage(&key)?)
+	}
+
 	fn metadata(&self, block: Trailing<Block::Hash>) -> Result<serde_json::Value> {
 		let block = self.unwrap_or_best(block)?;
 		let metadata = self.client.json_metadata(&BlockId::Hash(block))?;
 		serde_json::from_str(&metadata).map_err(Into::into)
 	}
 
+	fn events(&self, block: Trailing<Block::Hash>) -> Result<Vec<serde_json::Value>> {
+		let block = self.unwrap_or_best(block)?;
+		let events = self.client.events(&BlockId::Hash(block))?;
+		Ok(events.into_iter().map(client::events::EventRecord::into_json).collect())
+	}
+
 	fn query_storage(&self, keys: Vec<StorageKey>, from: Block::Hash, to: Trailing<Block::Hash>) -> Result<Vec<StorageChangeSet<Block::Hash>>> {
 		let to = self.unwrap_or_best(to)?;
 
@@ -265,6 +295,38 @@ impl<B, E, Block> StateApi<Block::Hash> for State<B, E, Block> where
 	fn unsubscribe_storage(&self, id: SubscriptionId) -> RpcResult<bool> {
 		Ok(self.subscriptions.cancel(id))
 	}
+
+	fn subscribe_events(&self, _meta: Self::Metadata, subscriber: pubsub::Subscriber<serde_json::Value>) {
+		let client = self.client.clone();
+		self.subscriptions.add(subscriber, |sink| {
+			let stream = self.client.import_notification_stream()
+				.filter_map(move |notification| match client.events(&BlockId::Hash(notification.hash)) {
+					Ok(events) => Some(Ok(events_json(notification.hash, events))),
+					Err(e) => {
+						warn!("Error decoding the events of {:?}: {:?}", notification.hash, e);
+						None
+					},
+				})
+				.map_err(|e| warn!("Block notification stream error: {:?}", e));
+
+			sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(stream)
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+		})
+	}
+
+	fn unsubscribe_events(&self, id: SubscriptionId) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(id))
+	}
+}
+
+fn events_json<H: AsRef<[u8]>>(block: H, events: Vec<client::events::EventRecord>) -> serde_json::Value {
+	let mut json = serde_json::Map::new();
+	json.insert("block".into(), format!("0x{}", HexDisplay::from(&block.as_ref())).into());
+	json.insert("events".into(), events.into_iter().map(client::events::EventRecord::into_json).collect());
+	serde_json::Value::Object(json)
 }
 
 fn invalid_block_range<H: Header>(from: Option<H>, to: Option<H>, reason: String) -> error::ErrorKind {
@@ -35,6 +35,18 @@ fn should_return_storage() {
 	)
 }
 
+#[test]
+fn should_return_offchain_storage() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let client = State::new(client, core.executor());
+
+	assert_matches!(
+		client.offchain_storage(StorageKey(vec![10])),
+		Ok(None)
+	)
+}
+
 #[test]
 fn should_call_contract() {
 	let core = ::tokio::runtime::Runtime::new().unwrap();
@@ -48,6 +60,19 @@ fn should_call_contract() {
 	)
 }
 
+#[test]
+fn should_refuse_events_without_types_in_metadata() {
+	let core = ::tokio::runtime::Runtime::new().unwrap();
+	let client = Arc::new(test_client::new());
+	let genesis_hash = client.genesis_hash();
+	let client = State::new(client, core.executor());
+
+	assert_matches!(
+		client.events(Some(genesis_hash).into()),
+		Err(Error(ErrorKind::Client(client::error::ErrorKind::EventsDecode(_)), _))
+	)
+}
+
 #[test]
 fn should_notify_about_storage_changes() {
 	let mut core = ::tokio::runtime::Runtime::new().unwrap();
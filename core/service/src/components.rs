@@ -198,6 +198,7 @@ impl<Factory: ServiceFactory> Components for FullComponents<Factory> {
 			cache_size: None,
 			path: config.database_path.as_str().into(),
 			pruning: config.pruning.clone(),
+			backend: config.database,
 		};
 		Ok((Arc::new(client_db::new_client(db_settings, executor, &config.chain_spec, config.execution_strategy)?), None))
 	}
@@ -238,6 +239,7 @@ impl<Factory: ServiceFactory> Components for LightComponents<Factory>
 			cache_size: None,
 			path: config.database_path.as_str().into(),
 			pruning: config.pruning.clone(),
+			backend: config.database,
 		};
 		let db_storage = client_db::light::LightStorage::new(db_settings)?;
 		let light_blockchain = client::light::new_light_blockchain(db_storage);
@@ -20,9 +20,9 @@ use std::net::SocketAddr;
 use extrinsic_pool;
 use chain_spec::ChainSpec;
 pub use client::ExecutionStrategy;
-pub use network::Roles;
+pub use network::{Roles, SyncMode};
 pub use network::NetworkConfiguration;
-pub use client_db::PruningMode;
+pub use client_db::{PruningMode, DatabaseBackend};
 use runtime_primitives::BuildStorage;
 use serde::{Serialize, de::DeserializeOwned};
 use target_info::Target;
@@ -45,6 +45,8 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub keystore_path: String,
 	/// Path to the database.
 	pub database_path: String,
+	/// The database to store the chain in.
+	pub database: DatabaseBackend,
 	/// Pruning settings.
 	pub pruning: PruningMode,
 	/// Additional key seeds.
@@ -59,6 +61,8 @@ pub struct Configuration<C, G: Serialize + DeserializeOwned + BuildStorage> {
 	pub name: String,
 	/// Execution strategy.
 	pub execution_strategy: ExecutionStrategy,
+	/// How to catch up with the chain.
+	pub sync_mode: SyncMode,
 	/// RPC over HTTP binding address. `None` if disabled.
 	pub rpc_http: Option<SocketAddr>,
 	/// RPC over Websockets binding address. `None` if disabled.
@@ -81,11 +85,13 @@ impl<C: Default, G: Serialize + DeserializeOwned + BuildStorage> Configuration<C
 			network: Default::default(),
 			keystore_path: Default::default(),
 			database_path: Default::default(),
+			database: Default::default(),
 			keys: Default::default(),
 			custom: Default::default(),
 			telemetry: Default::default(),
 			pruning: PruningMode::default(),
 			execution_strategy: ExecutionStrategy::Both,
+			sync_mode: SyncMode::Full,
 			rpc_http: None,
 			rpc_ws: None,
 			telemetry_url: None,
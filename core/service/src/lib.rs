@@ -26,6 +26,7 @@ extern crate exit_future;
 extern crate serde;
 extern crate serde_json;
 extern crate substrate_keystore as keystore;
+extern crate substrate_keyring as keyring;
 extern crate substrate_primitives as primitives;
 extern crate sr_primitives as runtime_primitives;
 extern crate substrate_network as network;
@@ -62,6 +63,7 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use futures::prelude::*;
 use keystore::Store as Keystore;
+use keyring::Keyring;
 use client::BlockchainEvents;
 use runtime_primitives::traits::{Header, As};
 use runtime_primitives::generic::BlockId;
@@ -71,7 +73,7 @@ use substrate_executor::NativeExecutor;
 use codec::{Encode, Decode};
 
 pub use self::error::{ErrorKind, Error};
-pub use config::{Configuration, Roles, PruningMode};
+pub use config::{Configuration, Roles, PruningMode, DatabaseBackend, SyncMode};
 pub use chain_spec::ChainSpec;
 pub use extrinsic_pool::{Pool as ExtrinsicPool, Options as ExtrinsicPoolOptions, ChainApi, VerifiedTransaction, IntoPoolError};
 pub use client::ExecutionStrategy;
@@ -127,7 +129,11 @@ impl<Components> Service<Components>
 
 		let mut keystore = Keystore::open(config.keystore_path.as_str().into())?;
 		for seed in &config.keys {
-			keystore.generate_from_seed(seed)?;
+			// the name of a development key, e.g. `Alice` with `--dev`, stands for its seed.
+			match Keyring::from_name(seed) {
+				Some(key) => { keystore.generate_from_raw_seed(key.seed()); },
+				None => { keystore.generate_from_seed(seed)?; },
+			}
 		}
 
 		// Keep the public key for telemetry
@@ -162,6 +168,7 @@ impl<Components> Service<Components>
 		let network_params = network::Params {
 			config: network::ProtocolConfig {
 				roles: config.roles,
+				sync_mode: config.sync_mode,
 			},
 			network_config: config.network,
 			chain: client.clone(),
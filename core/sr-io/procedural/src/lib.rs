@@ -0,0 +1,67 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+// tag::description[]
+//! Procedural macros used by the runtime I/O library.
+// end::description[]
+
+#![recursion_limit = "256"]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+mod runtime_interface;
+
+use proc_macro::TokenStream;
+
+/// Declare a set of host functions once, as a trait whose methods carry their native
+/// implementation, e.g.
+///
+/// ```rust,ignore
+/// #[runtime_interface]
+/// pub trait Hashing {
+/// 	/// Conduct a 256-bit Blake2 hash.
+/// 	fn blake2_256(data: &[u8]) -> [u8; 32] {
+/// 		primitives::blake2_256(data)
+/// 	}
+/// }
+/// ```
+///
+/// The trait is replaced by a module named after it in snake case (`hashing`), holding one
+/// function per method:
+///
+/// - with `std`, the function runs the native implementation, and the module also exports a
+///   `HOST_FUNCTIONS` table with which the executor provides the function to Wasm runtimes;
+/// - without `std`, the function calls the host through an `extern "C"` import named
+///   `ext_<module>_<function>_version_<n>`.
+///
+/// A method may be declared several times with different `#[version(n)]` attributes (no
+/// attribute means version 1). The host provides every version, so runtimes built against an
+/// older version keep working, while the runtime side and native code use the latest one.
+///
+/// Parameters may be `&[u8]`, `&[u8; N]`, `u32`, `u64`, `i32`, `i64` and `bool`; return types
+/// may be those integer types, `bool`, `[u8; N]`, `Vec<u8>` and `Option<Vec<u8>>`.
+///
+/// The generated code expects the declaring module to have `sr-io`'s `HostContext`,
+/// `HostFunction`, `ValueType` and `with_externalities` in scope when built with `std`.
+#[proc_macro_attribute]
+pub fn runtime_interface(attr: TokenStream, item: TokenStream) -> TokenStream {
+	runtime_interface::runtime_interface_impl(attr, item)
+}
@@ -0,0 +1,366 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsing of `#[runtime_interface]` traits.
+
+use proc_macro::TokenStream;
+use syn::{
+	Attribute, Block, Expr, FnArg, Ident, ItemTrait, Lit, Meta, NestedMeta, Pat, PathArguments,
+	GenericArgument, ReturnType, TraitItem, TraitItemMethod, Type, Visibility,
+};
+use syn::parse::Result;
+use syn::spanned::Spanned;
+
+mod transformation;
+
+pub fn runtime_interface_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+	if !attr.is_empty() {
+		let attr = proc_macro2::TokenStream::from(attr);
+		return syn::Error::new(attr.span(), "`#[runtime_interface]` takes no arguments").to_compile_error().into();
+	}
+	let item = parse_macro_input!(item as ItemTrait);
+	match InterfaceDefinition::from_trait(item) {
+		Ok(def) => transformation::expand(def).into(),
+		Err(e) => e.to_compile_error().into(),
+	}
+}
+
+/// The trait declaring an interface, e.g. `pub trait Hashing { .. }`.
+pub struct InterfaceDefinition {
+	pub docs: Vec<Attribute>,
+	pub visibility: Visibility,
+	/// The name of the generated module: the trait name in snake case.
+	pub module_name: Ident,
+	pub functions: Vec<Function>,
+}
+
+/// All versions of a host function, ordered by version.
+pub struct Function {
+	pub name: Ident,
+	pub versions: Vec<FunctionVersion>,
+}
+
+impl Function {
+	pub fn latest(&self) -> &FunctionVersion {
+		self.versions.last().expect("a function is only created along with its first version; qed")
+	}
+}
+
+/// A single version of a host function, e.g. `#[version(2)] fn foo(data: &[u8]) -> u32 { .. }`.
+pub struct FunctionVersion {
+	pub docs: Vec<Attribute>,
+	pub version: u32,
+	pub params: Vec<Param>,
+	pub ret: Return,
+	pub ret_type: ReturnType,
+	pub body: Block,
+}
+
+pub struct Param {
+	pub name: Ident,
+	pub ty: Type,
+	pub kind: ParamKind,
+}
+
+/// How a parameter crosses the Wasm boundary.
+pub enum ParamKind {
+	/// `&[u8]`, passed as pointer and length.
+	Slice,
+	/// `&[u8; N]`, passed as pointer.
+	Array(Expr),
+	/// An integer, passed as is; `wide` if it is 64 bits.
+	Int { wide: bool },
+	/// `bool`, passed as a 32-bit integer.
+	Bool,
+}
+
+/// How a return value crosses the Wasm boundary.
+pub enum Return {
+	Unit,
+	/// An integer, returned as is; `wide` if it is 64 bits.
+	Int { wide: bool },
+	/// `bool`, returned as a 32-bit integer.
+	Bool,
+	/// `[u8; N]`, written by the host to an output pointer passed as the last parameter.
+	Array(Expr),
+	/// `Vec<u8>`, allocated by the host; its length is written to an output pointer passed as
+	/// the last parameter.
+	Vec,
+	/// `Option<Vec<u8>>`, like `Vec<u8>` with a length of `u32::max_value()` meaning `None`.
+	OptionVec,
+}
+
+impl InterfaceDefinition {
+	fn from_trait(item: ItemTrait) -> Result<Self> {
+		if let Some(token) = item.unsafety {
+			return Err(syn::Error::new(token.span(), "runtime interfaces may not be unsafe"));
+		}
+		if !item.generics.params.is_empty() || !item.supertraits.is_empty() {
+			return Err(syn::Error::new(item.ident.span(), "runtime interfaces may not be generic or have supertraits"));
+		}
+
+		let mut docs = Vec::new();
+		for attr in item.attrs {
+			if !is_doc(&attr) {
+				return Err(syn::Error::new(attr.span(), "unsupported attribute; runtime interfaces only accept doc comments"));
+			}
+			docs.push(attr);
+		}
+
+		let mut functions: Vec<Function> = Vec::new();
+		for trait_item in item.items {
+			let method = match trait_item {
+				TraitItem::Method(method) => method,
+				other => return Err(syn::Error::new(other.span(), "runtime interfaces may only contain functions")),
+			};
+			let name = method.sig.ident.clone();
+			let version = FunctionVersion::from_method(method)?;
+
+			match functions.iter_mut().find(|f| f.name == name) {
+				Some(function) => {
+					if function.versions.iter().any(|v| v.version == version.version) {
+						return Err(syn::Error::new(
+							name.span(),
+							format!("version {} of `{}` is declared more than once", version.version, name),
+						));
+					}
+					let at = function.versions.iter().position(|v| v.version > version.version)
+						.unwrap_or(function.versions.len());
+					function.versions.insert(at, version);
+				},
+				None => functions.push(Function { name, versions: vec![version] }),
+			}
+		}
+
+		Ok(InterfaceDefinition {
+			docs,
+			visibility: item.vis,
+			module_name: Ident::new(&snake_case(&item.ident.to_string()), item.ident.span()),
+			functions,
+		})
+	}
+}
+
+impl FunctionVersion {
+	fn from_method(method: TraitItemMethod) -> Result<Self> {
+		let TraitItemMethod { attrs, sig, default, .. } = method;
+		if sig.constness.is_some() || sig.unsafety.is_some() || sig.asyncness.is_some() || sig.abi.is_some() {
+			return Err(syn::Error::new(sig.ident.span(), "host functions must be plain `fn`s"));
+		}
+		if !sig.decl.generics.params.is_empty() || sig.decl.variadic.is_some() {
+			return Err(syn::Error::new(sig.ident.span(), "host functions may not be generic or variadic"));
+		}
+
+		let mut docs = Vec::new();
+		let mut version = None;
+		for attr in attrs {
+			if is_doc(&attr) {
+				docs.push(attr);
+			} else if attr.path.segments.len() == 1 && attr.path.segments[0].ident == "version" {
+				if version.is_some() {
+					return Err(syn::Error::new(attr.span(), "duplicate `#[version]` attribute"));
+				}
+				version = Some(version_of(&attr)?);
+			} else {
+				return Err(syn::Error::new(
+					attr.span(),
+					"unsupported attribute; host functions only accept doc comments and `#[version(n)]`",
+				));
+			}
+		}
+
+		let mut params = Vec::new();
+		for input in &sig.decl.inputs {
+			match *input {
+				FnArg::Captured(ref arg) => {
+					let name = match arg.pat {
+						Pat::Ident(ref pat) if pat.by_ref.is_none() && pat.subpat.is_none() => pat.ident.clone(),
+						ref pat => return Err(syn::Error::new(pat.span(), "expected a parameter name")),
+					};
+					params.push(Param { name, ty: arg.ty.clone(), kind: param_kind(&arg.ty)? });
+				},
+				ref other => return Err(syn::Error::new(other.span(), "host functions may not take `self`")),
+			}
+		}
+
+		let ret = match sig.decl.output {
+			ReturnType::Default => Return::Unit,
+			ReturnType::Type(_, ref ty) => return_kind(ty)?,
+		};
+
+		let body = match default {
+			Some(body) => body,
+			None => return Err(syn::Error::new(
+				sig.ident.span(),
+				"host functions must have a body, which is their native implementation",
+			)),
+		};
+
+		Ok(FunctionVersion {
+			docs,
+			version: version.unwrap_or(1),
+			params,
+			ret,
+			ret_type: sig.decl.output.clone(),
+			body,
+		})
+	}
+}
+
+fn is_doc(attr: &Attribute) -> bool {
+	attr.path.segments.len() == 1 && attr.path.segments[0].ident == "doc"
+}
+
+/// The `n` of `#[version(n)]`.
+fn version_of(attr: &Attribute) -> Result<u32> {
+	if let Ok(Meta::List(ref list)) = attr.parse_meta() {
+		if list.nested.len() == 1 {
+			if let NestedMeta::Literal(Lit::Int(ref lit)) = list.nested[0] {
+				if lit.value() >= 1 && lit.value() <= u64::from(u32::max_value()) {
+					return Ok(lit.value() as u32);
+				}
+			}
+		}
+	}
+	Err(syn::Error::new(attr.span(), "expected `#[version(n)]` with `n` at least 1"))
+}
+
+fn param_kind(ty: &Type) -> Result<ParamKind> {
+	if let Type::Reference(ref reference) = *ty {
+		if reference.mutability.is_none() {
+			match *reference.elem {
+				Type::Slice(ref slice) if is_u8(&slice.elem) => return Ok(ParamKind::Slice),
+				Type::Array(ref array) if is_u8(&array.elem) => return Ok(ParamKind::Array(array.len.clone())),
+				_ => {},
+			}
+		}
+	}
+	match ident_of(ty).map(|i| i.to_string()).as_ref().map(|s| s.as_str()) {
+		Some("u32") | Some("i32") => Ok(ParamKind::Int { wide: false }),
+		Some("u64") | Some("i64") => Ok(ParamKind::Int { wide: true }),
+		Some("bool") => Ok(ParamKind::Bool),
+		_ => Err(syn::Error::new(
+			ty.span(),
+			"unsupported parameter type; expected `&[u8]`, `&[u8; N]`, `bool` or a 32 or 64-bit integer",
+		)),
+	}
+}
+
+fn return_kind(ty: &Type) -> Result<Return> {
+	match *ty {
+		Type::Tuple(ref tuple) if tuple.elems.is_empty() => return Ok(Return::Unit),
+		Type::Array(ref array) if is_u8(&array.elem) => return Ok(Return::Array(array.len.clone())),
+		_ => {},
+	}
+	if generic_of(ty, "Vec").map_or(false, is_u8) {
+		return Ok(Return::Vec);
+	}
+	if generic_of(ty, "Option").and_then(|ty| generic_of(ty, "Vec")).map_or(false, is_u8) {
+		return Ok(Return::OptionVec);
+	}
+	match ident_of(ty).map(|i| i.to_string()).as_ref().map(|s| s.as_str()) {
+		Some("u32") | Some("i32") => Ok(Return::Int { wide: false }),
+		Some("u64") | Some("i64") => Ok(Return::Int { wide: true }),
+		Some("bool") => Ok(Return::Bool),
+		_ => Err(syn::Error::new(
+			ty.span(),
+			"unsupported return type; expected `[u8; N]`, `Vec<u8>`, `Option<Vec<u8>>`, `bool` or a 32 or 64-bit integer",
+		)),
+	}
+}
+
+/// The identifier of a plain, single-segment type path such as `u32`.
+fn ident_of(ty: &Type) -> Option<&Ident> {
+	match *ty {
+		Type::Path(ref path) if path.qself.is_none() && path.path.segments.len() == 1 => {
+			let segment = &path.path.segments[0];
+			match segment.arguments {
+				PathArguments::None => Some(&segment.ident),
+				_ => None,
+			}
+		},
+		_ => None,
+	}
+}
+
+/// `T` if `ty` is `wrapper<T>`.
+fn generic_of<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+	match *ty {
+		Type::Path(ref path) if path.qself.is_none() && path.path.segments.len() == 1 => {
+			let segment = &path.path.segments[0];
+			match segment.arguments {
+				PathArguments::AngleBracketed(ref args) if segment.ident == wrapper && args.args.len() == 1 =>
+					match args.args[0] {
+						GenericArgument::Type(ref ty) => Some(ty),
+						_ => None,
+					},
+				_ => None,
+			}
+		},
+		_ => None,
+	}
+}
+
+fn is_u8(ty: &Type) -> bool {
+	ident_of(ty).map_or(false, |i| i == "u8")
+}
+
+fn snake_case(name: &str) -> String {
+	let mut res = String::new();
+	for (i, c) in name.chars().enumerate() {
+		if c.is_uppercase() {
+			if i != 0 {
+				res.push('_');
+			}
+			res.extend(c.to_lowercase());
+		} else {
+			res.push(c);
+		}
+	}
+	res
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ty(s: &str) -> Type {
+		syn::parse_str(s).unwrap()
+	}
+
+	#[test]
+	fn types_are_classified() {
+		assert!(match param_kind(&ty("&[u8]")) { Ok(ParamKind::Slice) => true, _ => false });
+		assert!(match param_kind(&ty("&[u8; 32]")) { Ok(ParamKind::Array(_)) => true, _ => false });
+		assert!(match param_kind(&ty("u64")) { Ok(ParamKind::Int { wide: true }) => true, _ => false });
+		assert!(match param_kind(&ty("bool")) { Ok(ParamKind::Bool) => true, _ => false });
+		assert!(param_kind(&ty("&mut [u8]")).is_err());
+		assert!(param_kind(&ty("Vec<u8>")).is_err());
+
+		assert!(match return_kind(&ty("()")) { Ok(Return::Unit) => true, _ => false });
+		assert!(match return_kind(&ty("[u8; 16]")) { Ok(Return::Array(_)) => true, _ => false });
+		assert!(match return_kind(&ty("Vec<u8>")) { Ok(Return::Vec) => true, _ => false });
+		assert!(match return_kind(&ty("Option<Vec<u8>>")) { Ok(Return::OptionVec) => true, _ => false });
+		assert!(match return_kind(&ty("i32")) { Ok(Return::Int { wide: false }) => true, _ => false });
+		assert!(return_kind(&ty("Option<u32>")).is_err());
+	}
+
+	#[test]
+	fn trait_names_become_snake_case() {
+		assert_eq!(snake_case("Hashing"), "hashing");
+		assert_eq!(snake_case("OffchainIndex"), "offchain_index");
+	}
+}
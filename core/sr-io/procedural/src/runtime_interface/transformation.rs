@@ -0,0 +1,374 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Code generation of `#[runtime_interface]`.
+
+use proc_macro2::{Span, TokenStream};
+use syn::{Ident, LitStr};
+
+use super::{Function, FunctionVersion, InterfaceDefinition, ParamKind, Return};
+
+pub fn expand(def: InterfaceDefinition) -> TokenStream {
+	let InterfaceDefinition {
+		ref docs,
+		ref visibility,
+		ref module_name,
+		ref functions,
+	} = def;
+
+	let mut native = Vec::new();
+	let mut host_functions = Vec::new();
+	let mut externs = Vec::new();
+	let mut wasm = Vec::new();
+
+	for function in functions {
+		native.push(expand_native(function));
+		for version in &function.versions {
+			host_functions.push(expand_host_function(module_name, function, version));
+		}
+		let (import, call) = expand_wasm(module_name, function);
+		externs.push(import);
+		wasm.push(call);
+	}
+
+	let host_function_entries = functions.iter().flat_map(|function| {
+		function.versions.iter().map(move |version| host_function_entry(module_name, function, version))
+	});
+
+	quote! {
+		#(#docs)*
+		#[cfg(feature = "std")]
+		#visibility mod #module_name {
+			#[allow(unused_imports)]
+			use super::*;
+
+			#(#native)*
+
+			#(#host_functions)*
+
+			/// Every version of every function of this interface, as provided to Wasm runtimes.
+			pub static HOST_FUNCTIONS: &[HostFunction] = &[ #(#host_function_entries,)* ];
+		}
+
+		#(#docs)*
+		#[cfg(not(feature = "std"))]
+		#visibility mod #module_name {
+			#[allow(unused_imports)]
+			use super::*;
+
+			extern "C" {
+				#(#externs)*
+			}
+
+			#(#wasm)*
+		}
+	}
+}
+
+fn ident(name: &str) -> Ident {
+	Ident::new(name, Span::call_site())
+}
+
+/// The private function holding the native implementation of the given version.
+fn version_fn(function: &Function, version: &FunctionVersion) -> Ident {
+	ident(&format!("{}_version_{}", function.name, version.version))
+}
+
+/// The name of the Wasm import of the given version.
+fn extern_name(module_name: &Ident, function: &Function, version: &FunctionVersion) -> Ident {
+	ident(&format!("ext_{}_{}_version_{}", module_name, function.name, version.version))
+}
+
+/// The public function, which runs the latest version, and the native implementation of each
+/// version.
+fn expand_native(function: &Function) -> TokenStream {
+	let latest = function.latest();
+	let name = &function.name;
+	let docs = &latest.docs;
+	let params = latest.params.iter().map(|p| {
+		let (name, ty) = (&p.name, &p.ty);
+		quote! { #name: #ty }
+	});
+	let args = latest.params.iter().map(|p| &p.name);
+	let ret = &latest.ret_type;
+	let latest_fn = version_fn(function, latest);
+
+	let versions = function.versions.iter().map(|version| {
+		let name = version_fn(function, version);
+		let params = version.params.iter().map(|p| {
+			let (name, ty) = (&p.name, &p.ty);
+			quote! { #name: #ty }
+		});
+		let ret = &version.ret_type;
+		let body = &version.body;
+		quote! {
+			fn #name( #(#params),* ) #ret #body
+		}
+	});
+
+	quote! {
+		#(#docs)*
+		pub fn #name( #(#params),* ) #ret {
+			#latest_fn( #(#args),* )
+		}
+
+		#(#versions)*
+	}
+}
+
+/// The function the executor calls when the runtime imports the given version: it reads the
+/// arguments out of the runtime's memory, runs the native implementation and passes the result
+/// back.
+fn expand_host_function(module_name: &Ident, function: &Function, version: &FunctionVersion) -> TokenStream {
+	let name = extern_name(module_name, function, version);
+	let version_fn = version_fn(function, version);
+	let error = |what: &str| LitStr::new(&format!("Invalid attempt to {} in {}", what, name), Span::call_site());
+
+	let mut index = 0usize;
+	let mut read_args = Vec::new();
+	let mut call_args = Vec::new();
+	for param in &version.params {
+		let name = &param.name;
+		match param.kind {
+			ParamKind::Slice => {
+				let (ptr, len) = (index, index + 1);
+				let error = error(&format!("get {}", name));
+				read_args.push(quote! {
+					let #name = context.read_memory(args[#ptr] as u32, args[#len] as u32).map_err(|_| #error)?;
+				});
+				call_args.push(quote! { &#name[..] });
+				index += 2;
+			},
+			ParamKind::Array(ref len) => {
+				let error = error(&format!("get {}", name));
+				read_args.push(quote! {
+					let mut #name = [0u8; #len];
+					#name.copy_from_slice(&context.read_memory(args[#index] as u32, #len as u32).map_err(|_| #error)?);
+				});
+				call_args.push(quote! { &#name });
+				index += 1;
+			},
+			ParamKind::Int { .. } => {
+				let ty = &param.ty;
+				read_args.push(quote! { let #name = args[#index] as #ty; });
+				call_args.push(quote! { #name });
+				index += 1;
+			},
+			ParamKind::Bool => {
+				read_args.push(quote! { let #name = args[#index] != 0; });
+				call_args.push(quote! { #name });
+				index += 1;
+			},
+		}
+	}
+
+	let write_vec = {
+		let allocate = error("allocate result");
+		let set = error("set result");
+		let written = error("write written_out");
+		quote! {
+			let offset = context.allocate(result.len() as u32).map_err(|_| #allocate)?;
+			context.write_memory(offset, &result).map_err(|_| #set)?;
+			let len = result.len() as u32;
+			context.write_memory(written_out, &[len as u8, (len >> 8) as u8, (len >> 16) as u8, (len >> 24) as u8])
+				.map_err(|_| #written)?;
+			Ok(Some(offset as u64))
+		}
+	};
+	let call = quote! {
+		with_externalities(context.externalities(), || #version_fn( #(#call_args),* ))
+	};
+	let ret = match version.ret {
+		Return::Unit => quote! { Ok(None) },
+		Return::Int { .. } | Return::Bool => quote! { Ok(Some(result as u64)) },
+		Return::Array(_) => {
+			let set = error("set result");
+			quote! {
+				context.write_memory(args[#index] as u32, &result[..]).map_err(|_| #set)?;
+				Ok(None)
+			}
+		},
+		Return::Vec => quote! {
+			let written_out = args[#index] as u32;
+			#write_vec
+		},
+		Return::OptionVec => {
+			let written = error("write failed written_out");
+			quote! {
+				let written_out = args[#index] as u32;
+				match result {
+					Some(result) => {
+						#write_vec
+					},
+					None => {
+						context.write_memory(written_out, &[0xff; 4]).map_err(|_| #written)?;
+						Ok(Some(0))
+					},
+				}
+			}
+		},
+	};
+
+	let call_result = match version.ret {
+		Return::Unit => quote! { #call; },
+		_ => quote! { let result = #call; },
+	};
+
+	quote! {
+		#[doc(hidden)]
+		fn #name(context: &mut HostContext, args: &[u64]) -> Result<Option<u64>, &'static str> {
+			#(#read_args)*
+			#call_result
+			#ret
+		}
+	}
+}
+
+/// The entry of the given version in `HOST_FUNCTIONS`.
+fn host_function_entry(module_name: &Ident, function: &Function, version: &FunctionVersion) -> TokenStream {
+	let name = extern_name(module_name, function, version);
+	let name_str = LitStr::new(&name.to_string(), Span::call_site());
+
+	let mut params = Vec::new();
+	for param in &version.params {
+		match param.kind {
+			ParamKind::Slice => {
+				params.push(quote! { ValueType::I32 });
+				params.push(quote! { ValueType::I32 });
+			},
+			ParamKind::Int { wide: true } => params.push(quote! { ValueType::I64 }),
+			ParamKind::Array(_) | ParamKind::Int { wide: false } | ParamKind::Bool =>
+				params.push(quote! { ValueType::I32 }),
+		}
+	}
+	let ret = match version.ret {
+		Return::Unit => quote! { None },
+		Return::Int { wide: true } => quote! { Some(ValueType::I64) },
+		Return::Int { wide: false } | Return::Bool => quote! { Some(ValueType::I32) },
+		Return::Array(_) => {
+			params.push(quote! { ValueType::I32 });
+			quote! { None }
+		},
+		Return::Vec | Return::OptionVec => {
+			params.push(quote! { ValueType::I32 });
+			quote! { Some(ValueType::I32) }
+		},
+	};
+
+	quote! {
+		HostFunction {
+			name: #name_str,
+			params: &[ #(#params),* ],
+			ret: #ret,
+			call: #name,
+		}
+	}
+}
+
+/// The import of the latest version, and the public function calling it.
+fn expand_wasm(module_name: &Ident, function: &Function) -> (TokenStream, TokenStream) {
+	let latest = function.latest();
+	let name = &function.name;
+	let docs = &latest.docs;
+	let extern_name = extern_name(module_name, function, latest);
+
+	let mut extern_params = Vec::new();
+	let mut args = Vec::new();
+	for param in &latest.params {
+		let name = &param.name;
+		let data = ident(&format!("{}_data", name));
+		match param.kind {
+			ParamKind::Slice => {
+				let len = ident(&format!("{}_len", name));
+				extern_params.push(quote! { #data: *const u8, #len: u32 });
+				args.push(quote! { #name.as_ptr(), #name.len() as u32 });
+			},
+			ParamKind::Array(_) => {
+				extern_params.push(quote! { #data: *const u8 });
+				args.push(quote! { #name.as_ptr() });
+			},
+			ParamKind::Int { .. } => {
+				let ty = &param.ty;
+				extern_params.push(quote! { #name: #ty });
+				args.push(quote! { #name });
+			},
+			ParamKind::Bool => {
+				extern_params.push(quote! { #name: u32 });
+				args.push(quote! { #name as u32 });
+			},
+		}
+	}
+
+	let ret = &latest.ret_type;
+	let (extern_ret, body) = match latest.ret {
+		Return::Unit => (quote! {}, quote! {
+			unsafe { #extern_name( #(#args),* ) }
+		}),
+		Return::Int { .. } => (quote! { #ret }, quote! {
+			unsafe { #extern_name( #(#args),* ) }
+		}),
+		Return::Bool => (quote! { -> u32 }, quote! {
+			unsafe { #extern_name( #(#args),* ) != 0 }
+		}),
+		Return::Array(ref len) => {
+			extern_params.push(quote! { out: *mut u8 });
+			(quote! {}, quote! {
+				let mut result = [0u8; #len];
+				unsafe { #extern_name( #(#args,)* result.as_mut_ptr()) };
+				result
+			})
+		},
+		Return::Vec => {
+			extern_params.push(quote! { written_out: *mut u32 });
+			(quote! { -> *mut u8 }, quote! {
+				let mut length: u32 = 0;
+				unsafe {
+					let ptr = #extern_name( #(#args,)* &mut length);
+					Vec::from_raw_parts(ptr, length as usize, length as usize)
+				}
+			})
+		},
+		Return::OptionVec => {
+			extern_params.push(quote! { written_out: *mut u32 });
+			(quote! { -> *mut u8 }, quote! {
+				let mut length: u32 = 0;
+				unsafe {
+					let ptr = #extern_name( #(#args,)* &mut length);
+					if length == u32::max_value() {
+						None
+					} else {
+						Some(Vec::from_raw_parts(ptr, length as usize, length as usize))
+					}
+				}
+			})
+		},
+	};
+
+	let params = latest.params.iter().map(|p| {
+		let (name, ty) = (&p.name, &p.ty);
+		quote! { #name: #ty }
+	});
+
+	(
+		quote! { fn #extern_name( #(#extern_params),* ) #extern_ret; },
+		quote! {
+			#(#docs)*
+			pub fn #name( #(#params),* ) #ret {
+				#body
+			}
+		},
+	)
+}
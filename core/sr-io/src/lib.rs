@@ -28,8 +28,88 @@
 #![cfg_attr(feature = "std", doc = "Substrate runtime standard library as compiled when linked with Rust's standard library.")]
 #![cfg_attr(not(feature = "std"), doc = "Substrate's runtime standard library as compiled without Rust's standard library.")]
 
+#[macro_use]
+extern crate sr_io_procedural;
+
+/// Error verifying ECDSA signature
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EcdsaVerifyError {
+	/// Incorrect value of V
+	BadV,
+	/// Invalid signature
+	BadSignature,
+}
+
 #[cfg(feature = "std")]
 include!("../with_std.rs");
 
 #[cfg(not(feature = "std"))]
 include!("../without_std.rs");
+
+/// Hashing functions.
+///
+/// `blake2_256` and `twox_128` are still provided through hand-written imports, since
+/// `substrate-primitives` imports the former itself and the executor traces the preimages of
+/// the latter.
+#[runtime_interface]
+pub trait Hashing {
+	/// Conduct a 256-bit Keccak hash.
+	fn keccak_256(data: &[u8]) -> [u8; 32] {
+		primitives::keccak_256(data)
+	}
+
+	/// Conduct a 256-bit Sha2 hash.
+	fn sha2_256(data: &[u8]) -> [u8; 32] {
+		primitives::sha2_256(data)
+	}
+
+	/// Conduct four XX hashes to give a 256-bit result.
+	fn twox_256(data: &[u8]) -> [u8; 32] {
+		primitives::twox_256(data)
+	}
+}
+
+pub use hashing::{keccak_256, sha2_256, twox_256};
+
+/// Walking the keys of the storage.
+#[runtime_interface]
+pub trait Storage {
+	/// The smallest key in storage that is greater than `key`, or `None` if there is none.
+	fn next_key(key: &[u8]) -> Option<Vec<u8>> {
+		ext::with(|ext| ext.next_storage_key(key))
+			.expect("next_key cannot be called outside of an Externalities-provided environment.")
+	}
+}
+
+pub use storage::next_key as next_storage_key;
+
+/// Logging through the node's logger.
+#[runtime_interface]
+pub trait Logging {
+	/// Log `message` under the target `runtime::<target>`, or `runtime` if `target` is empty.
+	/// Levels go from 1 (error) to 5 (trace).
+	fn log(level: u32, target: &[u8], message: &[u8]) {
+		let level = match level {
+			1 => ::log::LogLevel::Error,
+			2 => ::log::LogLevel::Warn,
+			3 => ::log::LogLevel::Info,
+			4 => ::log::LogLevel::Debug,
+			_ => ::log::LogLevel::Trace,
+		};
+		let target = match ::std::str::from_utf8(target) {
+			Ok(target) if !target.is_empty() => format!("runtime::{}", target),
+			_ => "runtime".to_owned(),
+		};
+		log!(target: &target, level, "{}", String::from_utf8_lossy(message));
+	}
+}
+
+/// The host functions declared with `#[runtime_interface]`, which the executor provides to Wasm
+/// runtimes in addition to its own.
+#[cfg(feature = "std")]
+pub fn host_functions() -> Vec<&'static HostFunction> {
+	hashing::HOST_FUNCTIONS.iter()
+		.chain(storage::HOST_FUNCTIONS)
+		.chain(logging::HOST_FUNCTIONS)
+		.collect()
+}
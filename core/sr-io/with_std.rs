@@ -24,11 +24,15 @@ extern crate substrate_state_machine;
 extern crate triehash;
 extern crate hashdb;
 extern crate rlp;
+#[macro_use]
+extern crate log;
 
 #[doc(hidden)]
 pub extern crate parity_codec as codec;
+#[doc(hidden)]
+pub use primitives::decode_limit;
 // re-export hashing functions.
-pub use primitives::{blake2_256, twox_128, twox_256, ed25519};
+pub use primitives::{blake2_256, twox_128, ed25519, sr25519, ecdsa};
 
 pub use primitives::Blake2Hasher;
 // Switch to this after PoC-3
@@ -76,6 +80,21 @@ pub fn clear_storage(key: &[u8]) {
 	);
 }
 
+/// Set `key` to `value` in the local offchain database. The write is applied when the block
+/// is imported and is not part of consensus.
+pub fn offchain_index_set(key: &[u8], value: &[u8]) {
+	ext::with(|ext|
+		ext.offchain_index_set(key, value)
+	);
+}
+
+/// Clear `key` in the local offchain database.
+pub fn offchain_index_clear(key: &[u8]) {
+	ext::with(|ext|
+		ext.offchain_index_clear(key)
+	);
+}
+
 /// Check whether a given `key` exists in storage.
 pub fn exists_storage(key: &[u8]) -> bool {
 	ext::with(|ext|
@@ -141,6 +160,44 @@ pub fn ed25519_verify<P: AsRef<[u8]>>(sig: &[u8; 64], msg: &[u8], pubkey: P) ->
 	ed25519::verify(sig, msg, pubkey)
 }
 
+/// Verify a sr25519 signature.
+pub fn sr25519_verify<P: AsRef<[u8]>>(sig: &[u8; 64], msg: &[u8], pubkey: P) -> bool {
+	sr25519::verify(sig, msg, pubkey)
+}
+
+/// Verify a BLS12-381 signature.
+#[cfg(feature = "bls")]
+pub fn bls_verify<P: AsRef<[u8]>>(sig: &[u8; 96], msg: &[u8], pubkey: P) -> bool {
+	primitives::bls::verify(&sig[..], msg, pubkey)
+}
+
+/// Verify an aggregated BLS12-381 signature of each message by its respective signer.
+#[cfg(feature = "bls")]
+pub fn bls_aggregate_verify(sig: &[u8; 96], signed: &[(&[u8], &[u8; 48])]) -> bool {
+	primitives::bls::verify_aggregate(&sig[..], signed.iter().map(|&(msg, pubkey)| (msg, &pubkey[..])))
+}
+
+/// Verify and recover a SECP256k1 ECDSA signature.
+/// - `sig` is passed in RSV format. V should be either 0/1 or 27/28.
+/// - returns the uncompressed public key, without the leading `0x04` tag.
+pub fn secp256k1_ecdsa_recover(sig: &[u8; 65], msg: &[u8; 32]) -> Result<[u8; 64], EcdsaVerifyError> {
+	ecdsa::recover(sig, msg).map_err(ecdsa_error)
+}
+
+/// Verify and recover a SECP256k1 ECDSA signature.
+/// - `sig` is passed in RSV format. V should be either 0/1 or 27/28.
+/// - returns the compressed public key.
+pub fn secp256k1_ecdsa_recover_compressed(sig: &[u8; 65], msg: &[u8; 32]) -> Result<[u8; 33], EcdsaVerifyError> {
+	ecdsa::recover_compressed(sig, msg).map_err(ecdsa_error)
+}
+
+fn ecdsa_error(e: ecdsa::RecoverError) -> EcdsaVerifyError {
+	match e {
+		ecdsa::RecoverError::BadV => EcdsaVerifyError::BadV,
+		ecdsa::RecoverError::BadSignature => EcdsaVerifyError::BadSignature,
+	}
+}
+
 /// Execute the given closure with global function available whose functionality routes into the
 /// externalities `ext`. Forwards the value that the closure returns.
 // NOTE: need a concrete hasher here due to limitations of the `environmental!` macro, otherwise a type param would have been fine I think.
@@ -148,6 +205,38 @@ pub fn with_externalities<R, F: FnOnce() -> R>(ext: &mut Externalities<Blake2Has
 	ext::using(ext, f)
 }
 
+/// The type of a value passed to or returned from a host function.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValueType {
+	I32,
+	I64,
+}
+
+/// A host function declared with `#[runtime_interface]`, as seen by the executor.
+pub struct HostFunction {
+	/// The name under which Wasm runtimes import the function.
+	pub name: &'static str,
+	/// The types of the parameters.
+	pub params: &'static [ValueType],
+	/// The type of the return value, if any.
+	pub ret: Option<ValueType>,
+	/// Call the function with the given arguments, which are zero-extended to 64 bits, as is
+	/// the return value.
+	pub call: fn(&mut HostContext, &[u64]) -> Result<Option<u64>, &'static str>,
+}
+
+/// What a host function needs of the Wasm instance calling it.
+pub trait HostContext {
+	/// Read `len` bytes of the instance's memory from `ptr`.
+	fn read_memory(&self, ptr: u32, len: u32) -> Result<Vec<u8>, &'static str>;
+	/// Write `data` to the instance's memory at `ptr`.
+	fn write_memory(&mut self, ptr: u32, data: &[u8]) -> Result<(), &'static str>;
+	/// Allocate `len` bytes on the instance's heap, which the runtime then owns.
+	fn allocate(&mut self, len: u32) -> Result<u32, &'static str>;
+	/// The externalities the instance is executed with.
+	fn externalities(&mut self) -> &mut Externalities<Blake2Hasher>;
+}
+
 /// Trait for things which can be printed.
 pub trait Printable {
 	fn print(self);
@@ -193,8 +282,10 @@ macro_rules! impl_stubs {
 		Some($invoke($data))
 	};
 	(@METHOD $data: ident $new_name: ident => $invoke:expr) => {{
-		let mut data = $data;
-		let input = match $crate::codec::Decode::decode(&mut data) {
+		let input = match $crate::decode_limit::DecodeLimit::decode_all_with_depth_limit(
+			$crate::decode_limit::MAX_DECODE_DEPTH,
+			$data,
+		) {
 			Some(input) => input,
 			None => panic!("Bad input data provided to {}", stringify!($new_name)),
 		};
@@ -263,4 +354,19 @@ mod std_tests {
 			assert!(storage(b":abc").is_none());
 		});
 	}
+
+	#[test]
+	fn next_storage_key_works() {
+		let mut t: TestExternalities<Blake2Hasher> = map![
+			b":a".to_vec() => b"\x0b\0\0\0Hello world".to_vec(),
+			b":abc".to_vec() => b"\x0b\0\0\0Hello world".to_vec(),
+			b":abdd".to_vec() => b"\x0b\0\0\0Hello world".to_vec()
+		];
+
+		with_externalities(&mut t, || {
+			assert_eq!(next_storage_key(b":a"), Some(b":abc".to_vec()));
+			assert_eq!(next_storage_key(b":abd"), Some(b":abdd".to_vec()));
+			assert_eq!(next_storage_key(b":abdd"), None);
+		});
+	}
 }
@@ -23,6 +23,8 @@ pub extern crate sr_std as rstd;
 
 #[doc(hidden)]
 pub extern crate parity_codec as codec;
+#[doc(hidden)]
+pub use primitives::decode_limit;
 
 use core::intrinsics;
 use rstd::vec::Vec;
@@ -59,6 +61,8 @@ extern "C" {
 	fn ext_print_num(value: u64);
 	fn ext_set_storage(key_data: *const u8, key_len: u32, value_data: *const u8, value_len: u32);
 	fn ext_clear_storage(key_data: *const u8, key_len: u32);
+	fn ext_offchain_index_set(key_data: *const u8, key_len: u32, value_data: *const u8, value_len: u32);
+	fn ext_offchain_index_clear(key_data: *const u8, key_len: u32);
 	fn ext_exists_storage(key_data: *const u8, key_len: u32) -> u32;
 	fn ext_clear_prefix(prefix_data: *const u8, prefix_len: u32);
 	fn ext_get_allocated_storage(key_data: *const u8, key_len: u32, written_out: *mut u32) -> *mut u8;
@@ -68,8 +72,12 @@ extern "C" {
 	fn ext_chain_id() -> u64;
 	fn ext_blake2_256(data: *const u8, len: u32, out: *mut u8);
 	fn ext_twox_128(data: *const u8, len: u32, out: *mut u8);
-	fn ext_twox_256(data: *const u8, len: u32, out: *mut u8);
 	fn ext_ed25519_verify(msg_data: *const u8, msg_len: u32, sig_data: *const u8, pubkey_data: *const u8) -> u32;
+	fn ext_sr25519_verify(msg_data: *const u8, msg_len: u32, sig_data: *const u8, pubkey_data: *const u8) -> u32;
+	fn ext_bls_verify(msg_data: *const u8, msg_len: u32, sig_data: *const u8, pubkey_data: *const u8) -> u32;
+	fn ext_bls_aggregate_verify(sig_data: *const u8, pubkeys_data: *const u8, msgs_data: *const u8, msg_lens_data: *const u32, count: u32) -> u32;
+	fn ext_secp256k1_ecdsa_recover(msg_data: *const u8, sig_data: *const u8, pubkey_data: *mut u8) -> u32;
+	fn ext_secp256k1_ecdsa_recover_compressed(msg_data: *const u8, sig_data: *const u8, pubkey_data: *mut u8) -> u32;
 }
 
 /// Ensures we use the right crypto when calling into native
@@ -127,6 +135,26 @@ pub fn clear_storage(key: &[u8]) {
 	}
 }
 
+/// Set `key` to `value` in the local offchain database. The write is applied when the block
+/// is imported and is not part of consensus.
+pub fn offchain_index_set(key: &[u8], value: &[u8]) {
+	unsafe {
+		ext_offchain_index_set(
+			key.as_ptr(), key.len() as u32,
+			value.as_ptr(), value.len() as u32
+		);
+	}
+}
+
+/// Clear `key` in the local offchain database.
+pub fn offchain_index_clear(key: &[u8]) {
+	unsafe {
+		ext_offchain_index_clear(
+			key.as_ptr(), key.len() as u32
+		);
+	}
+}
+
 /// Determine whether a particular key exists in storage.
 pub fn exists_storage(key: &[u8]) -> bool {
 	unsafe {
@@ -214,15 +242,6 @@ pub fn blake2_256(data: &[u8]) -> [u8; 32] {
 	result
 }
 
-/// Conduct four XX hashes to give a 256-bit result.
-pub fn twox_256(data: &[u8]) -> [u8; 32] {
-	let mut result: [u8; 32] = Default::default();
-	unsafe {
-		ext_twox_256(data.as_ptr(), data.len() as u32, result.as_mut_ptr());
-	}
-	result
-}
-
 /// Conduct two XX hashes to give a 128-bit result.
 pub fn twox_128(data: &[u8]) -> [u8; 16] {
 	let mut result: [u8; 16] = Default::default();
@@ -239,6 +258,69 @@ pub fn ed25519_verify<P: AsRef<[u8]>>(sig: &[u8; 64], msg: &[u8], pubkey: P) ->
 	}
 }
 
+/// Verify a sr25519 signature.
+pub fn sr25519_verify<P: AsRef<[u8]>>(sig: &[u8; 64], msg: &[u8], pubkey: P) -> bool {
+	unsafe {
+		ext_sr25519_verify(msg.as_ptr(), msg.len() as u32, sig.as_ptr(), pubkey.as_ref().as_ptr()) == 0
+	}
+}
+
+/// Verify a BLS12-381 signature.
+pub fn bls_verify<P: AsRef<[u8]>>(sig: &[u8; 96], msg: &[u8], pubkey: P) -> bool {
+	unsafe {
+		ext_bls_verify(msg.as_ptr(), msg.len() as u32, sig.as_ptr(), pubkey.as_ref().as_ptr()) == 0
+	}
+}
+
+/// Verify an aggregated BLS12-381 signature of each message by its respective signer.
+pub fn bls_aggregate_verify(sig: &[u8; 96], signed: &[(&[u8], &[u8; 48])]) -> bool {
+	let pubkeys = signed.iter().fold(Vec::new(), |mut acc, &(_, pubkey)| { acc.extend_from_slice(&pubkey[..]); acc });
+	let lengths = signed.iter().map(|&(msg, _)| (msg.len() as u32).to_le()).collect::<Vec<_>>();
+	let msgs = signed.iter().fold(Vec::new(), |mut acc, &(msg, _)| { acc.extend_from_slice(msg); acc });
+	unsafe {
+		ext_bls_aggregate_verify(
+			sig.as_ptr(),
+			pubkeys.as_ptr(),
+			msgs.as_ptr(),
+			lengths.as_ptr(),
+			lengths.len() as u32
+		) == 0
+	}
+}
+
+/// Verify and recover a SECP256k1 ECDSA signature.
+/// - `sig` is passed in RSV format. V should be either 0/1 or 27/28.
+/// - returns the uncompressed public key, without the leading `0x04` tag.
+pub fn secp256k1_ecdsa_recover(sig: &[u8; 65], msg: &[u8; 32]) -> Result<[u8; 64], EcdsaVerifyError> {
+	let mut pubkey = [0u8; 64];
+	match unsafe {
+		ext_secp256k1_ecdsa_recover(msg.as_ptr(), sig.as_ptr(), pubkey.as_mut_ptr())
+	} {
+		0 => Ok(pubkey),
+		r => Err(ecdsa_error(r)),
+	}
+}
+
+/// Verify and recover a SECP256k1 ECDSA signature.
+/// - `sig` is passed in RSV format. V should be either 0/1 or 27/28.
+/// - returns the compressed public key.
+pub fn secp256k1_ecdsa_recover_compressed(sig: &[u8; 65], msg: &[u8; 32]) -> Result<[u8; 33], EcdsaVerifyError> {
+	let mut pubkey = [0u8; 33];
+	match unsafe {
+		ext_secp256k1_ecdsa_recover_compressed(msg.as_ptr(), sig.as_ptr(), pubkey.as_mut_ptr())
+	} {
+		0 => Ok(pubkey),
+		r => Err(ecdsa_error(r)),
+	}
+}
+
+fn ecdsa_error(code: u32) -> EcdsaVerifyError {
+	match code {
+		1 => EcdsaVerifyError::BadV,
+		_ => EcdsaVerifyError::BadSignature,
+	}
+}
+
 /// Trait for things which can be printed.
 pub trait Printable {
 	fn print(self);
@@ -280,6 +362,7 @@ macro_rules! impl_stubs {
 	};
 	( @METHOD $new_name:ident NO_DECODE => $invoke:expr ) => {
 		#[no_mangle]
+		#[allow(non_snake_case)]
 		pub fn $new_name(input_data: *mut u8, input_len: usize) -> u64 {
 			let input: &[u8] = if input_len == 0 {
 				&[0u8; 0]
@@ -301,8 +384,9 @@ macro_rules! impl_stubs {
 	};
 	( @METHOD $new_name:ident => $invoke:expr ) => {
 		#[no_mangle]
+		#[allow(non_snake_case)]
 		pub fn $new_name(input_data: *mut u8, input_len: usize) -> u64 {
-			let mut input = if input_len == 0 {
+			let input = if input_len == 0 {
 				&[0u8; 0]
 			} else {
 				unsafe {
@@ -310,7 +394,12 @@ macro_rules! impl_stubs {
 				}
 			};
 
-			let input = match $crate::codec::Decode::decode(&mut input) {
+			// The input, e.g. a block to execute, may hold extrinsics from anyone: it is decoded
+			// whole, with a limited depth.
+			let input = match $crate::decode_limit::DecodeLimit::decode_all_with_depth_limit(
+				$crate::decode_limit::MAX_DECODE_DEPTH,
+				input,
+			) {
 				Some(input) => input,
 				None => panic!("Bad input data provided to {}", stringify!($name)),
 			};
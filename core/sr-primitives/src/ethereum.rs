@@ -0,0 +1,86 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ethereum addresses, for modules checking that the holder of an address signed something, e.g.
+//! a claim of the tokens airdropped to it.
+//!
+//! Ethereum wallets sign with `eth_sign`, which prefixes the message before hashing it with
+//! Keccak-256; the signer is recovered from the `EcdsaSignature`.
+
+use rstd::prelude::*;
+use runtime_io;
+use substrate_primitives::H160;
+use EcdsaSignature;
+
+/// The address of an Ethereum account.
+pub type EthereumAddress = H160;
+
+/// The address of an uncompressed public key (without the leading `0x04` tag): the last 20 bytes
+/// of its Keccak-256 hash.
+pub fn address_of(public: &[u8; 64]) -> EthereumAddress {
+	EthereumAddress::from_slice(&runtime_io::keccak_256(&public[..])[12..])
+}
+
+/// The message `eth_sign` signs for `what`: `what` prefixed with `"\x19Ethereum Signed Message:\n"`
+/// and its length in decimal.
+pub fn signable_message(what: &[u8]) -> Vec<u8> {
+	let mut len = Vec::new();
+	let mut n = what.len();
+	loop {
+		len.push(b'0' + (n % 10) as u8);
+		n /= 10;
+		if n == 0 {
+			break;
+		}
+	}
+	len.reverse();
+
+	let mut v = b"\x19Ethereum Signed Message:\n".to_vec();
+	v.extend(len);
+	v.extend_from_slice(what);
+	v
+}
+
+/// The address which signed `what` with `eth_sign`, `None` if the signature is invalid.
+pub fn recover_signer(sig: &EcdsaSignature, what: &[u8]) -> Option<EthereumAddress> {
+	let hash = runtime_io::keccak_256(&signable_message(what));
+	runtime_io::secp256k1_ecdsa_recover(&sig.to_raw(), &hash).ok().map(|public| address_of(&public))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use substrate_primitives::{ecdsa, H512};
+
+	#[test]
+	fn signable_message_is_the_one_of_eth_sign() {
+		for what in &[&b""[..], &b"all ok!"[..], &[42u8; 1234][..]] {
+			assert_eq!(signable_message(what), ecdsa::ethereum_signable_message(what));
+		}
+	}
+
+	#[test]
+	fn signers_are_recovered_to_their_address() {
+		let pair = ecdsa::Pair::from_seed(b"12345678901234567890123456789012").unwrap();
+		let raw = pair.sign_ethereum(b"all ok!").0;
+		let sig = EcdsaSignature(H512::from_slice(&raw[..64]), raw[64]);
+		assert_eq!(recover_signer(&sig, b"all ok!"), Some(pair.public().eth_address()));
+		assert!(recover_signer(&sig, b"all not ok!") != Some(pair.public().eth_address()));
+
+		let bad_v = EcdsaSignature(sig.0, 42);
+		assert_eq!(recover_signer(&bad_v, b"all ok!"), None);
+	}
+}
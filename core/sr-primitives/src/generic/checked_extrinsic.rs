@@ -17,42 +17,55 @@
 //! Generic implementation of an extrinsic that has passed the verification
 //! stage.
 
-use traits::{self, Member, SimpleArithmetic, MaybeDisplay};
+use traits::{self, Member, MaybeDisplay, SignedExtension};
+use ApplyError;
 
 /// Definition of something that the external world might want to say; its
 /// existence implies that it has been checked and is good, particularly with
 /// regards to the signature.
 #[derive(PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
-pub struct CheckedExtrinsic<AccountId, Index, Call> {
-	/// Who this purports to be from, if anyone (note this is not a signature).
-	pub signed: Option<AccountId>,
-	/// The number of extrinsics have come before from the same signer.
-	pub index: Index,
+pub struct CheckedExtrinsic<AccountId, Call, Extra> {
+	/// Who this purports to be from and its signed extensions, if anyone (note this is not a
+	/// signature).
+	pub signed: Option<(AccountId, Extra)>,
 	/// The function that should be called.
 	pub function: Call,
 }
 
-impl<AccountId, Index, Call> traits::Applyable
-	for CheckedExtrinsic<AccountId, Index, Call>
+impl<AccountId, Call, Extra> traits::Applyable
+	for CheckedExtrinsic<AccountId, Call, Extra>
 where
 	AccountId: Member + MaybeDisplay,
-	Index: Member + MaybeDisplay + SimpleArithmetic,
-	Call: Member 
+	Call: Member,
+	Extra: SignedExtension<AccountId, Call>,
 {
-	type Index = Index;
 	type AccountId = AccountId;
 	type Call = Call;
 
-	fn index(&self) -> &Self::Index {
-		&self.index
+	fn sender(&self) -> Option<&Self::AccountId> {
+		self.signed.as_ref().map(|&(ref who, _)| who)
 	}
 
-	fn sender(&self) -> Option<&Self::AccountId> {
-		self.signed.as_ref()
+	fn call(&self) -> &Self::Call {
+		&self.function
+	}
+
+	fn validate(&self, len: usize) -> Result<(), ApplyError> {
+		match self.signed {
+			Some((ref who, ref extra)) => extra.validate(who, &self.function, len),
+			None => Extra::validate_unsigned(&self.function, len),
+		}
+	}
+
+	fn pre_dispatch(&self, len: usize) -> Result<(), ApplyError> {
+		match self.signed {
+			Some((ref who, ref extra)) => extra.pre_dispatch(who, &self.function, len),
+			None => Extra::pre_dispatch_unsigned(&self.function, len),
+		}
 	}
 
 	fn deconstruct(self) -> (Self::Call, Option<Self::AccountId>) {
-		(self.function, self.signed)
+		(self.function, self.signed.map(|(who, _)| who))
 	}
 }
@@ -16,13 +16,14 @@
 
 //! Tests for the generic implementations of Extrinsic/Header/Block.
 
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, Input, Output};
 use substrate_primitives::{H256, H512};
+use substrate_primitives::decode_limit::{DepthGuard, MAX_DECODE_DEPTH};
 use super::{Digest, Header, DigestItem, UncheckedExtrinsic};
 
 type Block = super::Block<
 	Header<u64, ::traits::BlakeTwo256, DigestItem<u32>>,
-	UncheckedExtrinsic<H256, u64, u64, ::Ed25519Signature>,
+	UncheckedExtrinsic<H256, u64, ::Ed25519Signature, ()>,
 >;
 
 #[test]
@@ -40,17 +41,18 @@ fn block_roundtrip_serialization() {
 		},
 		extrinsics: vec![
 			UncheckedExtrinsic::new_signed(
-				0,
 				100,
 				[255u8; 32].into(),
-				H512::from([0u8; 64]).into()
+				H512::from([0u8; 64]).into(),
+				()
 			),
 			UncheckedExtrinsic::new_signed(
-				100,
 				99,
 				[128u8; 32].into(),
-				H512::from([255u8; 64]).into()
-			)
+				H512::from([255u8; 64]).into(),
+				()
+			),
+			UncheckedExtrinsic::new_unsigned(98)
 		]
 	};
 
@@ -102,4 +104,46 @@ fn non_system_digest_item_encoding() {
 
 	let decoded: DigestItem<u32> = Decode::decode(&mut &encoded[..]).unwrap();
 	assert_eq!(item, decoded);
-}
\ No newline at end of file
+}
+/// A call which may hold another one, as a proposal does.
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum NestedCall {
+	Noop,
+	Propose(Box<NestedCall>),
+}
+
+impl Encode for NestedCall {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		match *self {
+			NestedCall::Noop => dest.push_byte(0),
+			NestedCall::Propose(ref call) => {
+				dest.push_byte(1);
+				call.encode_to(dest);
+			},
+		}
+	}
+}
+
+impl Decode for NestedCall {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		let _guard = DepthGuard::enter()?;
+		match input.read_byte()? {
+			0 => Some(NestedCall::Noop),
+			1 => Some(NestedCall::Propose(Box::new(Decode::decode(input)?))),
+			_ => None,
+		}
+	}
+}
+
+#[test]
+fn extrinsic_calls_are_decoded_up_to_the_depth_limit() {
+	type Extrinsic = UncheckedExtrinsic<H256, NestedCall, ::Ed25519Signature, ()>;
+	let nested = |depth: u32| (1..depth).fold(NestedCall::Noop, |call, _| NestedCall::Propose(Box::new(call)));
+
+	let xt = Extrinsic { signature: None, function: nested(MAX_DECODE_DEPTH) };
+	let encoded = xt.encode();
+	assert_eq!(Extrinsic::decode(&mut &encoded[..]), Some(xt));
+
+	let xt = Extrinsic { signature: None, function: nested(MAX_DECODE_DEPTH + 1) };
+	assert_eq!(Extrinsic::decode(&mut &xt.encode()[..]), None);
+}
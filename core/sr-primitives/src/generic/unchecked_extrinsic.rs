@@ -21,37 +21,34 @@ use std::fmt;
 
 use rstd::prelude::*;
 use codec::{Decode, Encode, Input};
-use traits::{self, Member, SimpleArithmetic, MaybeDisplay};
+use substrate_primitives::decode_limit::{DecodeLimit, MAX_DECODE_DEPTH};
+use traits::{self, Member, MaybeDisplay, SignedExtension};
 use super::CheckedExtrinsic;
 
 /// A extrinsic right from the external world. This is unchecked and so
 /// can contain a signature.
 #[derive(PartialEq, Eq, Clone)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-pub struct UncheckedExtrinsic<Address, Index, Call, Signature> {
-	/// The signature and address, if this is a signed extrinsic.
-	pub signature: Option<(Address, Signature)>,
-	/// The number of extrinsics have come before from the same signer.
-	pub index: Index,
+pub struct UncheckedExtrinsic<Address, Call, Signature, Extra> {
+	/// The address, signature and signed extensions, if this is a signed extrinsic.
+	pub signature: Option<(Address, Signature, Extra)>,
 	/// The function that should be called.
 	pub function: Call,
 }
 
-impl<Address, Index, Call, Signature> UncheckedExtrinsic<Address, Index, Call, Signature> {
+impl<Address, Call, Signature, Extra> UncheckedExtrinsic<Address, Call, Signature, Extra> {
 	/// New instance of a signed extrinsic aka "transaction".
-	pub fn new_signed(index: Index, function: Call, signed: Address, signature: Signature) -> Self {
+	pub fn new_signed(function: Call, signed: Address, signature: Signature, extra: Extra) -> Self {
 		UncheckedExtrinsic {
-			signature: Some((signed, signature)),
-			index,
+			signature: Some((signed, signature, extra)),
 			function,
 		}
 	}
 
 	/// New instance of an unsigned extrinsic aka "inherent".
-	pub fn new_unsigned(index: Index, function: Call) -> Self {
+	pub fn new_unsigned(function: Call) -> Self {
 		UncheckedExtrinsic {
 			signature: None,
-			index,
 			function,
 		}
 	}
@@ -62,48 +59,47 @@ impl<Address, Index, Call, Signature> UncheckedExtrinsic<Address, Index, Call, S
 	}
 }
 
-impl<Address, AccountId, Index, Call, Signature, ThisLookup> traits::Checkable<ThisLookup>
-	for UncheckedExtrinsic<Address, Index, Call, Signature>
+impl<Address, AccountId, Call, Signature, Extra, ThisLookup> traits::Checkable<ThisLookup>
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
 where
 	Address: Member + MaybeDisplay,
-	Index: Encode + Member + MaybeDisplay + SimpleArithmetic,
 	Call: Encode + Member,
 	Signature: Member + traits::Verify<Signer=AccountId>,
+	Extra: SignedExtension<AccountId, Call>,
 	AccountId: Member + MaybeDisplay,
 	ThisLookup: FnOnce(Address) -> Result<AccountId, &'static str>,
 {
-	type Checked = CheckedExtrinsic<AccountId, Index, Call>;
+	type Checked = CheckedExtrinsic<AccountId, Call, Extra>;
 
 	fn check_with(self, lookup: ThisLookup) -> Result<Self::Checked, &'static str> {
 		Ok(match self.signature {
-			Some((signed, signature)) => {
-				let payload = (self.index, self.function);
+			Some((signed, signature, extra)) => {
+				// The signed extensions are signed along with the call.
+				let payload = (self.function, extra);
 				let signed = lookup(signed)?;
 				if !::verify_encoded_lazy(&signature, &payload, &signed) {
 					return Err("bad signature in extrinsic")
 				}
 				CheckedExtrinsic {
-					signed: Some(signed),
-					index: payload.0,
-					function: payload.1,
+					signed: Some((signed, payload.1)),
+					function: payload.0,
 				}
 			}
 			None => CheckedExtrinsic {
 				signed: None,
-				index: self.index,
 				function: self.function,
 			},
 		})
 	}
 }
 
-impl<Address, Index, Call, Signature> Decode
-	for UncheckedExtrinsic<Address, Index, Call, Signature>
+impl<Address, Call, Signature, Extra> Decode
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
 where
 	Address: Decode,
 	Signature: Decode,
-	Index: Decode,
 	Call: Decode,
+	Extra: Decode,
 {
 	fn decode<I: Input>(input: &mut I) -> Option<Self> {
 		// This is a little more complicated than usual since the binary format must be compatible
@@ -114,19 +110,19 @@ where
 
 		Some(UncheckedExtrinsic {
 			signature: Decode::decode(input)?,
-			index: Decode::decode(input)?,
-			function: Decode::decode(input)?,
+			// Extrinsics come from anyone, and a call may nest others as deep as its encoding does.
+			function: Call::decode_with_depth_limit(MAX_DECODE_DEPTH, input)?,
 		})
 	}
 }
 
-impl<Address, Index, Call, Signature> Encode
-	for UncheckedExtrinsic<Address, Index, Call, Signature>
+impl<Address, Call, Signature, Extra> Encode
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
 where
 	Address: Encode,
 	Signature: Encode,
-	Index: Encode,
 	Call: Encode,
+	Extra: Encode,
 {
 	fn encode(&self) -> Vec<u8> {
 		let mut v = Vec::new();
@@ -136,7 +132,6 @@ where
 		v.extend(&[0u8; 4]);
 
 		self.signature.encode_to(&mut v);
-		self.index.encode_to(&mut v);
 		self.function.encode_to(&mut v);
 
 		let length = (v.len() - 4) as u32;
@@ -148,12 +143,12 @@ where
 
 /// TODO: use derive when possible.
 #[cfg(feature = "std")]
-impl<Address, Index, Call, Signature> fmt::Debug for UncheckedExtrinsic<Address, Index, Call, Signature> where
+impl<Address, Call, Signature, Extra> fmt::Debug for UncheckedExtrinsic<Address, Call, Signature, Extra> where
 	Address: fmt::Debug,
-	Index: fmt::Debug,
 	Call: fmt::Debug,
+	Extra: fmt::Debug,
 {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "UncheckedExtrinsic({:?}, {:?}, {:?})", self.signature.as_ref().map(|x| &x.0), self.function, self.index)
+		write!(f, "UncheckedExtrinsic({:?}, {:?})", self.signature.as_ref().map(|x| (&x.0, &x.2)), self.function)
 	}
 }
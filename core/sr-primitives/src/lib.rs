@@ -58,12 +58,39 @@ pub mod testing;
 pub mod traits;
 pub mod generic;
 pub mod bft;
+pub mod logging;
+pub mod ethereum;
 
-use traits::{Verify, Lazy};
+use traits::{Verify, IdentifyAccount, Lazy};
 
 #[cfg(feature = "std")]
 pub use serde::{Serialize, de::DeserializeOwned};
 
+/// Log a message from the runtime through the node's logger, under the target
+/// `runtime::<target>`, or `runtime` when no target is given.
+///
+/// ```ignore
+/// runtime_log!(target: "balances", Debug, "transfer to a new account");
+/// runtime_log!(Warn, "unexpected empty storage");
+/// ```
+#[macro_export]
+macro_rules! runtime_log {
+	(target: $target:expr, $level:ident, $message:expr) => {
+		$crate::logging::log($crate::logging::Level::$level, $target, $message)
+	};
+	($level:ident, $message:expr) => {
+		$crate::logging::log($crate::logging::Level::$level, "", $message)
+	};
+}
+
+/// Print a message from the runtime at the info level, under the target `runtime`.
+#[macro_export]
+macro_rules! runtime_print {
+	($message:expr) => {
+		$crate::logging::log($crate::logging::Level::Info, "", $message)
+	};
+}
+
 /// A set of key value pairs for storage.
 #[cfg(feature = "std")]
 pub type StorageMap = HashMap<Vec<u8>, Vec<u8>>;
@@ -77,6 +104,19 @@ pub trait BuildStorage {
 		r
 	}
 	fn build_storage(self) -> Result<StorageMap, String>;
+	/// Build the storage into `storage`, keeping what it already holds. Fails without touching
+	/// `storage`, naming the key, if a key is given a value other than the one it already has.
+	fn assimilate_storage(self, storage: &mut StorageMap) -> Result<(), String> where Self: Sized {
+		let built = self.build_storage()?;
+		if let Some((key, _)) = built.iter().find(|&(key, value)| storage.get(key).map_or(false, |v| v != value)) {
+			return Err(format!(
+				"conflicting genesis values for storage key 0x{}",
+				substrate_primitives::hexdisplay::HexDisplay::from(key),
+			));
+		}
+		storage.extend(built);
+		Ok(())
+	}
 }
 
 #[cfg(feature = "std")]
@@ -138,6 +178,161 @@ impl From<H512> for Ed25519Signature {
 	}
 }
 
+/// Sr25519 signature verify.
+///
+/// Either this or `Ed25519Signature` may be the signature of the extrinsics of a runtime, which
+/// their signers are then authenticated with; both are signed by a 32-byte public key.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct Sr25519Signature(pub H512);
+
+impl Verify for Sr25519Signature {
+	type Signer = H256;
+	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &Self::Signer) -> bool {
+		runtime_io::sr25519_verify(&(self.0).0, msg.get(), &signer.0[..])
+	}
+}
+
+impl From<H512> for Sr25519Signature {
+	fn from(h: H512) -> Sr25519Signature {
+		Sr25519Signature(h)
+	}
+}
+
+/// ECDSA (secp256k1) signature verify: the recoverable signature of the Blake2-256 hash of a
+/// message, `r || s` followed by the recovery id.
+///
+/// The signer is identified by the Blake2-256 hash of its compressed public key, which is
+/// recovered from the signature.
+#[derive(Eq, PartialEq, Clone, Default, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct EcdsaSignature(pub H512, pub u8);
+
+impl EcdsaSignature {
+	/// The 65 bytes of the signature.
+	pub fn to_raw(&self) -> [u8; 65] {
+		let mut r = [0u8; 65];
+		r[..64].copy_from_slice(&(self.0).0[..]);
+		r[64] = self.1;
+		r
+	}
+}
+
+impl Verify for EcdsaSignature {
+	type Signer = H256;
+	fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &Self::Signer) -> bool {
+		let hash = runtime_io::blake2_256(msg.get());
+		match runtime_io::secp256k1_ecdsa_recover_compressed(&self.to_raw(), &hash) {
+			Ok(public) => runtime_io::blake2_256(&public[..]) == signer.0,
+			Err(_) => false,
+		}
+	}
+}
+
+/// A compressed ECDSA (secp256k1) public key: the tag of the parity of `y`, `2` or `3`, then `x`.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct EcdsaPublic(pub u8, pub H256);
+
+impl EcdsaPublic {
+	/// The 33 bytes of the public key.
+	pub fn to_raw(&self) -> [u8; 33] {
+		let mut r = [0u8; 33];
+		r[0] = self.0;
+		r[1..].copy_from_slice(&(self.1).0[..]);
+		r
+	}
+}
+
+impl IdentifyAccount for EcdsaPublic {
+	type AccountId = H256;
+	fn into_account(self) -> H256 {
+		runtime_io::blake2_256(&self.to_raw()[..]).into()
+	}
+}
+
+/// A signature of any of the schemes a runtime may accept.
+///
+/// Used as the signature of the extrinsics of a runtime, its accounts may sign with any of them.
+/// The account signed for is the one `MultiSigner` identifies.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum MultiSignature {
+	/// An ed25519 signature.
+	Ed25519(Ed25519Signature),
+	/// An sr25519 signature.
+	Sr25519(Sr25519Signature),
+	/// An ECDSA (secp256k1) signature.
+	Ecdsa(EcdsaSignature),
+}
+
+impl Default for MultiSignature {
+	fn default() -> Self {
+		MultiSignature::Ed25519(Default::default())
+	}
+}
+
+impl Verify for MultiSignature {
+	type Signer = H256;
+	fn verify<L: Lazy<[u8]>>(&self, msg: L, signer: &Self::Signer) -> bool {
+		match *self {
+			MultiSignature::Ed25519(ref sig) => sig.verify(msg, signer),
+			MultiSignature::Sr25519(ref sig) => sig.verify(msg, signer),
+			MultiSignature::Ecdsa(ref sig) => sig.verify(msg, signer),
+		}
+	}
+}
+
+impl From<Ed25519Signature> for MultiSignature {
+	fn from(sig: Ed25519Signature) -> Self {
+		MultiSignature::Ed25519(sig)
+	}
+}
+
+impl From<Sr25519Signature> for MultiSignature {
+	fn from(sig: Sr25519Signature) -> Self {
+		MultiSignature::Sr25519(sig)
+	}
+}
+
+impl From<EcdsaSignature> for MultiSignature {
+	fn from(sig: EcdsaSignature) -> Self {
+		MultiSignature::Ecdsa(sig)
+	}
+}
+
+/// A public key of any of the schemes of `MultiSignature`, identifying the account it signs for.
+///
+/// An ed25519 or sr25519 key is its own account rather than its hash: neither signature lets the
+/// key be recovered, so it has to be the account for `MultiSignature` to be verified against the
+/// account alone. An ECDSA key, which is longer, is recovered from its signatures and identifies
+/// the account of its Blake2-256 hash.
+///
+/// As the scheme is not part of the account, an ed25519 and an sr25519 key with the same bytes
+/// identify the same account, which signatures of either scheme are then valid for. Finding a key
+/// of one scheme with the bytes of a key of the other is as hard as breaking that scheme, so an
+/// account is only ever controlled by the key it was created with.
+#[derive(Eq, PartialEq, Clone, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub enum MultiSigner {
+	/// An ed25519 public key.
+	Ed25519(H256),
+	/// An sr25519 public key.
+	Sr25519(H256),
+	/// A compressed ECDSA (secp256k1) public key.
+	Ecdsa(EcdsaPublic),
+}
+
+impl IdentifyAccount for MultiSigner {
+	type AccountId = H256;
+	fn into_account(self) -> H256 {
+		match self {
+			MultiSigner::Ed25519(who) | MultiSigner::Sr25519(who) => who,
+			MultiSigner::Ecdsa(who) => who.into_account(),
+		}
+	}
+}
+
 #[derive(Eq, PartialEq, Clone, Copy, Decode)]
 #[cfg_attr(feature = "std", derive(Debug, Serialize))]
 #[repr(u8)]
@@ -168,6 +363,8 @@ pub enum ApplyError {
 	Future = 2,
 	/// Sending account had too low a balance.
 	CantPay = 3,
+	/// The block has no room left for the weight of the extrinsic.
+	FullBlock = 255,
 }
 
 impl codec::Encode for ApplyError {
@@ -233,7 +430,7 @@ macro_rules! impl_outer_config {
 				let mut s = $crate::StorageMap::new();
 				$(
 					if let Some(extra) = self.$snake {
-						s.extend(extra.build_storage()?);
+						$crate::BuildStorage::assimilate_storage(extra, &mut s)?;
 					}
 				)*
 				Ok(s)
@@ -430,4 +627,75 @@ mod tests {
 			_ => panic!("unexpected generic_auth_change: {:?}", generic_auth_change),
 		}
 	}
+
+	#[test]
+	fn signatures_are_verified_with_their_own_scheme() {
+		use substrate_primitives::{ed25519, sr25519, H256};
+		use traits::Verify;
+		use {Ed25519Signature, Sr25519Signature};
+
+		let pair = sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let signer = H256::from(pair.public().0);
+		let signature = pair.sign(b"all ok!");
+		assert!(Sr25519Signature(signature).verify(&b"all ok!"[..], &signer));
+		assert!(!Sr25519Signature(signature).verify(&b"all not ok!"[..], &signer));
+		assert!(!Ed25519Signature(signature).verify(&b"all ok!"[..], &signer));
+
+		let pair = ed25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let signer = H256::from(pair.public().0);
+		let signature = pair.sign(b"all ok!");
+		assert!(Ed25519Signature(signature).verify(&b"all ok!"[..], &signer));
+		assert!(!Sr25519Signature(signature).verify(&b"all ok!"[..], &signer));
+	}
+
+	#[test]
+	fn multi_signatures_are_verified_for_the_account_of_their_signer() {
+		use substrate_primitives::{ed25519, sr25519, ecdsa, H256, H512};
+		use traits::{Verify, IdentifyAccount};
+		use {Ed25519Signature, Sr25519Signature, EcdsaSignature, EcdsaPublic, MultiSignature, MultiSigner};
+
+		let seed = b"12345678901234567890123456789012";
+
+		let pair = ed25519::Pair::from_seed(seed);
+		let account = MultiSigner::Ed25519(pair.public().0.into()).into_account();
+		let signature: MultiSignature = Ed25519Signature(pair.sign(b"all ok!")).into();
+		assert!(signature.verify(&b"all ok!"[..], &account));
+		assert!(!signature.verify(&b"all not ok!"[..], &account));
+
+		let pair = sr25519::Pair::from_seed(seed);
+		let account = MultiSigner::Sr25519(pair.public().0.into()).into_account();
+		let signature: MultiSignature = Sr25519Signature(pair.sign(b"all ok!")).into();
+		assert!(signature.verify(&b"all ok!"[..], &account));
+		assert!(!signature.verify(&b"all ok!"[..], &H256::from(ed25519::Pair::from_seed(seed).public().0)));
+
+		let pair = ecdsa::Pair::from_seed(seed).unwrap();
+		let public = pair.public().0;
+		let account = MultiSigner::Ecdsa(EcdsaPublic(public[0], H256::from_slice(&public[1..]))).into_account();
+		assert_eq!(account, H256::from(substrate_primitives::blake2_256(&public[..])));
+		let raw = pair.sign(b"all ok!").0;
+		let signature: MultiSignature = EcdsaSignature(H512::from_slice(&raw[..64]), raw[64]).into();
+		assert_eq!(signature.encode(), [&[2u8][..], &raw[..]].concat());
+		assert!(signature.verify(&b"all ok!"[..], &account));
+		assert!(!signature.verify(&b"all not ok!"[..], &account));
+		assert!(!signature.verify(&b"all ok!"[..], &H256::from_slice(&public[1..])));
+	}
+
+	#[test]
+	fn keys_of_different_schemes_with_the_same_bytes_identify_the_same_account() {
+		use substrate_primitives::{ed25519, sr25519, H256};
+		use traits::{Verify, IdentifyAccount};
+		use {Ed25519Signature, Sr25519Signature, EcdsaPublic, MultiSignature, MultiSigner};
+
+		let seed = b"12345678901234567890123456789012";
+		let key = H256::from(ed25519::Pair::from_seed(seed).public().0);
+		let ed25519 = MultiSigner::Ed25519(key).into_account();
+		assert_eq!(ed25519, MultiSigner::Sr25519(key).into_account());
+		assert!(ed25519 != MultiSigner::Ecdsa(EcdsaPublic(2, key)).into_account());
+
+		// only the key the account was created with signs for it
+		let signature: MultiSignature = Ed25519Signature(ed25519::Pair::from_seed(seed).sign(b"all ok!")).into();
+		assert!(signature.verify(&b"all ok!"[..], &ed25519));
+		let signature: MultiSignature = Sr25519Signature(sr25519::Pair::from_seed(seed).sign(b"all ok!")).into();
+		assert!(!signature.verify(&b"all ok!"[..], &ed25519));
+	}
 }
@@ -0,0 +1,41 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Logging from the runtime through the node's logger. See `runtime_log!` and `runtime_print!`.
+
+use runtime_io;
+
+/// Severity of a runtime log message.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Level {
+	/// Something went wrong.
+	Error = 1,
+	/// Something looks wrong.
+	Warn = 2,
+	/// Useful information.
+	Info = 3,
+	/// Information for debugging.
+	Debug = 4,
+	/// Very detailed information for debugging.
+	Trace = 5,
+}
+
+/// Log `message` under the target `runtime::<target>`, or `runtime` if `target` is empty. The
+/// node filters these like any other target, e.g. with `--log runtime::balances=debug`.
+pub fn log(level: Level, target: &str, message: &str) {
+	runtime_io::logging::log(level as u32, target.as_bytes(), message.as_bytes())
+}
@@ -19,7 +19,8 @@
 use serde::{Serialize, de::DeserializeOwned};
 use std::fmt::Debug;
 use codec::Codec;
-use traits::{self, Checkable, Applyable, BlakeTwo256};
+use traits::{self, Checkable, Applyable, BlakeTwo256, SignedExtension};
+use ApplyError;
 
 pub use substrate_primitives::H256;
 
@@ -118,22 +119,37 @@ impl<Xt: 'static + Codec + Sized + Send + Sync + Serialize + DeserializeOwned +
 	}
 }
 
+/// An extrinsic whose sender and signed extensions, if signed, are taken as checked.
 #[derive(PartialEq, Eq, Clone, Serialize, Deserialize, Debug, Encode, Decode)]
-pub struct TestXt<Call>(pub Option<u64>, pub u64, pub Call);
+pub struct TestXt<Call, Extra = ()>(pub Option<(u64, Extra)>, pub Call);
 
-impl<Call: Codec + Sync + Send + Serialize, Context> Checkable<Context> for TestXt<Call> {
+impl<Call: Codec + Sync + Send + Serialize, Extra: Codec + Sync + Send + Serialize, Context> Checkable<Context>
+	for TestXt<Call, Extra>
+{
 	type Checked = Self;
 	fn check_with(self, _: Context) -> Result<Self::Checked, &'static str> { Ok(self) }
 }
-impl<Call> Applyable for TestXt<Call> where
+impl<Call, Extra> Applyable for TestXt<Call, Extra> where
 	Call: 'static + Sized + Send + Sync + Clone + Eq + Codec + Debug + Serialize + DeserializeOwned,
+	Extra: SignedExtension<u64, Call>,
 {
 	type AccountId = u64;
-	type Index = u64;
 	type Call = Call;
-	fn sender(&self) -> Option<&u64> { self.0.as_ref() }
-	fn index(&self) -> &u64 { &self.1 }
+	fn sender(&self) -> Option<&u64> { self.0.as_ref().map(|&(ref who, _)| who) }
+	fn call(&self) -> &Call { &self.1 }
+	fn validate(&self, len: usize) -> Result<(), ApplyError> {
+		match self.0 {
+			Some((ref who, ref extra)) => extra.validate(who, &self.1, len),
+			None => Extra::validate_unsigned(&self.1, len),
+		}
+	}
+	fn pre_dispatch(&self, len: usize) -> Result<(), ApplyError> {
+		match self.0 {
+			Some((ref who, ref extra)) => extra.pre_dispatch(who, &self.1, len),
+			None => Extra::pre_dispatch_unsigned(&self.1, len),
+		}
+	}
 	fn deconstruct(self) -> (Self::Call, Option<Self::AccountId>) {
-		(self.2, self.0)
+		(self.1, self.0.map(|(who, _)| who))
 	}
 }
@@ -47,6 +47,25 @@ pub trait Verify {
 	fn verify<L: Lazy<[u8]>>(&self, msg: L, signer: &Self::Signer) -> bool;
 }
 
+/// Something identifying an account, such as the public key signing for it.
+///
+/// A 32-byte public key whose signatures can't recover it, i.e. ed25519 or sr25519, is its own
+/// account, so that signatures can be checked against the account; see `MultiSigner`.
+pub trait IdentifyAccount {
+	/// The account identified.
+	type AccountId;
+	/// The account identified by this.
+	fn into_account(self) -> Self::AccountId;
+}
+
+/// An ed25519 or sr25519 public key, which is its own account.
+impl IdentifyAccount for substrate_primitives::H256 {
+	type AccountId = Self;
+	fn into_account(self) -> Self {
+		self
+	}
+}
+
 /// Some sort of check on the origin is performed by this object.
 pub trait EnsureOrigin<OuterOrigin> {
 	type Success;
@@ -186,6 +205,15 @@ pub trait OnFinalise<BlockNumber> {
 
 impl<N> OnFinalise<N> for () {}
 
+/// Work done outside of consensus after a block has been imported, such as submitting
+/// heartbeats or price feeds. Changes made to the state are discarded.
+pub trait OffchainWorker<BlockNumber> {
+	/// The block `n` has been imported. Implement to have something happen.
+	fn offchain_worker(_n: BlockNumber) {}
+}
+
+impl<N> OffchainWorker<N> for () {}
+
 macro_rules! tuple_impl {
 	($one:ident,) => {
 		impl<Number: Copy, $one: OnFinalise<Number>> OnFinalise<Number> for ($one,) {
@@ -193,6 +221,11 @@ macro_rules! tuple_impl {
 				$one::on_finalise(n);
 			}
 		}
+		impl<Number: Copy, $one: OffchainWorker<Number>> OffchainWorker<Number> for ($one,) {
+			fn offchain_worker(n: Number) {
+				$one::offchain_worker(n);
+			}
+		}
 	};
 	($first:ident, $($rest:ident,)+) => {
 		impl<
@@ -205,6 +238,16 @@ macro_rules! tuple_impl {
 				$($rest::on_finalise(n);)+
 			}
 		}
+		impl<
+			Number: Copy,
+			$first: OffchainWorker<Number>,
+			$($rest: OffchainWorker<Number>),+
+		> OffchainWorker<Number> for ($first, $($rest),+) {
+			fn offchain_worker(n: Number) {
+				$first::offchain_worker(n);
+				$($rest::offchain_worker(n);)+
+			}
+		}
 		tuple_impl!($($rest,)+);
 	}
 }
@@ -277,6 +320,52 @@ impl Hash for BlakeTwo256 {
 	}
 }
 
+macro_rules! impl_blake2_trie_hash {
+	( $( $(#[$attr:meta])* $name:ident => $hash:path; )* ) => { $(
+		$(#[$attr])*
+		#[derive(PartialEq, Eq, Clone)]
+		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+		pub struct $name;
+
+		impl Hash for $name {
+			type Output = substrate_primitives::H256;
+			fn hash(s: &[u8]) -> Self::Output {
+				$hash(s).into()
+			}
+			fn enumerated_trie_root(items: &[&[u8]]) -> Self::Output {
+				runtime_io::enumerated_trie_root::<Blake2Hasher>(items).into()
+			}
+			fn trie_root<
+				I: IntoIterator<Item = (A, B)>,
+				A: AsRef<[u8]> + Ord,
+				B: AsRef<[u8]>
+			>(input: I) -> Self::Output {
+				runtime_io::trie_root::<Blake2Hasher, _, _, _>(input).into()
+			}
+			fn ordered_trie_root<
+				I: IntoIterator<Item = A>,
+				A: AsRef<[u8]>
+			>(input: I) -> Self::Output {
+				runtime_io::ordered_trie_root::<Blake2Hasher, _, _>(input).into()
+			}
+			fn storage_root() -> Self::Output {
+				runtime_io::storage_root().into()
+			}
+		}
+	)* }
+}
+
+impl_blake2_trie_hash! {
+	/// Keccak-256 Hash implementation.
+	///
+	/// Only `hash` uses Keccak; trie roots are those of the Blake2-flavoured state trie.
+	Keccak256 => runtime_io::keccak_256;
+	/// Sha2-256 Hash implementation.
+	///
+	/// Only `hash` uses Sha2; trie roots are those of the Blake2-flavoured state trie.
+	Sha256 => runtime_io::sha2_256;
+}
+
 /// Something that can be checked for equality and printed out to a debug channel if bad.
 pub trait CheckEqual {
 	fn check_equal(&self, other: &Self);
@@ -430,17 +519,87 @@ impl<T: BlindCheckable, Context> Checkable<Context> for T {
 /// enact a piece of extrinsic information by marshalling and dispatching to a named functioon
 /// call.
 ///
-/// Also provides information on to whom this information is attributable and an index that allows
-/// each piece of attributable information to be disambiguated.
+/// Also provides information on to whom this information is attributable, and the checks of its
+/// signed extensions.
 pub trait Applyable: Sized + Send + Sync {
 	type AccountId: Member + MaybeDisplay;
-	type Index: Member + MaybeDisplay + SimpleArithmetic;
 	type Call: Member;
-	fn index(&self) -> &Self::Index;
 	fn sender(&self) -> Option<&Self::AccountId>;
+	fn call(&self) -> &Self::Call;
+	/// Check the extrinsic against the current state without changing it, e.g. for the
+	/// transaction pool. `len` is the length of the encoded extrinsic.
+	fn validate(&self, len: usize) -> Result<(), ::ApplyError>;
+	/// Check the extrinsic right before its dispatch, making the changes that go with the checks,
+	/// e.g. paying the fees.
+	fn pre_dispatch(&self, len: usize) -> Result<(), ::ApplyError>;
 	fn deconstruct(self) -> (Self::Call, Option<Self::AccountId>);
 }
 
+/// Data encoded and signed alongside the call of an extrinsic, and the check that goes with it,
+/// e.g. the nonce of the sender. A runtime puts the extensions of its extrinsics in a tuple.
+pub trait SignedExtension<AccountId, Call>: Codec + Member {
+	/// Check a signed extrinsic of `who` against the current state without changing it. `len` is
+	/// the length of the encoded extrinsic.
+	fn validate(&self, _who: &AccountId, _call: &Call, _len: usize) -> Result<(), ::ApplyError> {
+		Ok(())
+	}
+
+	/// Check a signed extrinsic of `who` right before its dispatch, making the changes that go
+	/// with the check.
+	fn pre_dispatch(&self, who: &AccountId, call: &Call, len: usize) -> Result<(), ::ApplyError> {
+		self.validate(who, call, len)
+	}
+
+	/// As `validate`, for an unsigned extrinsic.
+	fn validate_unsigned(_call: &Call, _len: usize) -> Result<(), ::ApplyError> {
+		Ok(())
+	}
+
+	/// As `pre_dispatch`, for an unsigned extrinsic.
+	fn pre_dispatch_unsigned(call: &Call, len: usize) -> Result<(), ::ApplyError> {
+		Self::validate_unsigned(call, len)
+	}
+}
+
+macro_rules! tuple_impl_signed_extension {
+	($($ext:ident),*) => {
+		#[allow(non_snake_case)]
+		impl<AccountId, Call, $($ext: SignedExtension<AccountId, Call>),*> SignedExtension<AccountId, Call>
+			for ($($ext,)*)
+		{
+			fn validate(&self, _who: &AccountId, _call: &Call, _len: usize) -> Result<(), ::ApplyError> {
+				let ($(ref $ext,)*) = *self;
+				$( $ext.validate(_who, _call, _len)?; )*
+				Ok(())
+			}
+
+			fn pre_dispatch(&self, _who: &AccountId, _call: &Call, _len: usize) -> Result<(), ::ApplyError> {
+				let ($(ref $ext,)*) = *self;
+				$( $ext.pre_dispatch(_who, _call, _len)?; )*
+				Ok(())
+			}
+
+			fn validate_unsigned(_call: &Call, _len: usize) -> Result<(), ::ApplyError> {
+				$( $ext::validate_unsigned(_call, _len)?; )*
+				Ok(())
+			}
+
+			fn pre_dispatch_unsigned(_call: &Call, _len: usize) -> Result<(), ::ApplyError> {
+				$( $ext::pre_dispatch_unsigned(_call, _len)?; )*
+				Ok(())
+			}
+		}
+	}
+}
+
+tuple_impl_signed_extension!();
+tuple_impl_signed_extension!(A);
+tuple_impl_signed_extension!(A, B);
+tuple_impl_signed_extension!(A, B, C);
+tuple_impl_signed_extension!(A, B, C, D);
+tuple_impl_signed_extension!(A, B, C, D, E);
+tuple_impl_signed_extension!(A, B, C, D, E, F);
+
 /// Something that acts like a `Digest` - it can have `Log`s `push`ed onto it and these `Log`s are
 /// each `Codec`.
 pub trait Digest: Member + Default {
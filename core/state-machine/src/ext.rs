@@ -137,6 +137,25 @@ where
 		}
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+		let mut after = key.to_vec();
+		loop {
+			let backend_next = self.backend.next_storage_key(&after)
+				.expect("Externalities not allowed to fail within runtime");
+			let overlay_next = self.overlay.next_key(&after).map(|k| k.to_vec());
+			let next = match (backend_next, overlay_next) {
+				(Some(backend_next), Some(overlay_next)) => ::std::cmp::min(backend_next, overlay_next),
+				(backend_next, overlay_next) => backend_next.or(overlay_next)?,
+			};
+
+			// the candidate may have been deleted in the overlay.
+			if self.exists_storage(&next) {
+				return Some(next);
+			}
+			after = next;
+		}
+	}
+
 	fn place_storage(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
 		self.mark_dirty();
 		self.overlay.set_storage(key, value);
@@ -150,6 +169,10 @@ where
 		});
 	}
 
+	fn place_offchain_index(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+		self.overlay.set_offchain_index(key, value);
+	}
+
 	fn chain_id(&self) -> u64 {
 		42
 	}
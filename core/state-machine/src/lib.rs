@@ -45,6 +45,7 @@ use patricia_trie::NodeCodec;
 use rlp::Encodable;
 use heapsize::HeapSizeOf;
 use codec::Decode;
+use primitives::storage::well_known_keys;
 
 pub mod backend;
 mod ext;
@@ -65,6 +66,8 @@ pub use trie_backend::{TryIntoTrieBackend, TrieBackend, Storage, DBValue};
 pub struct OverlayedChanges {
 	prospective: HashMap<Vec<u8>, Option<Vec<u8>>>,
 	committed: HashMap<Vec<u8>, Option<Vec<u8>>>,
+	prospective_offchain: HashMap<Vec<u8>, Option<Vec<u8>>>,
+	committed_offchain: HashMap<Vec<u8>, Option<Vec<u8>>>,
 }
 
 impl OverlayedChanges {
@@ -77,6 +80,16 @@ impl OverlayedChanges {
 			.map(|x| x.as_ref().map(AsRef::as_ref))
 	}
 
+	/// The smallest key greater than `key` that has been changed, whether it has been set or
+	/// deleted.
+	fn next_key(&self, key: &[u8]) -> Option<&[u8]> {
+		self.prospective.keys()
+			.chain(self.committed.keys())
+			.filter(|k| &k[..] > key)
+			.min()
+			.map(|k| &k[..])
+	}
+
 	/// Inserts the given key-value pair into the prospective change set.
 	///
 	/// `None` can be used to delete a value specified by the given key.
@@ -84,6 +97,13 @@ impl OverlayedChanges {
 		self.prospective.insert(key, val);
 	}
 
+	/// Inserts the given key-value pair into the prospective offchain index change set.
+	///
+	/// `None` can be used to delete a value specified by the given key.
+	fn set_offchain_index(&mut self, key: Vec<u8>, val: Option<Vec<u8>>) {
+		self.prospective_offchain.insert(key, val);
+	}
+
 	/// Removes all key-value pairs which keys share the given prefix.
 	///
 	/// NOTE that this doesn't take place immediately but written into the prospective
@@ -111,6 +131,7 @@ impl OverlayedChanges {
 	/// Discard prospective changes to state.
 	pub fn discard_prospective(&mut self) {
 		self.prospective.clear();
+		self.prospective_offchain.clear();
 	}
 
 	/// Commit prospective changes to state.
@@ -120,6 +141,11 @@ impl OverlayedChanges {
 		} else {
 			self.committed.extend(self.prospective.drain());
 		}
+		if self.committed_offchain.is_empty() {
+			::std::mem::swap(&mut self.prospective_offchain, &mut self.committed_offchain);
+		} else {
+			self.committed_offchain.extend(self.prospective_offchain.drain());
+		}
 	}
 
 	/// Drain committed changes to an iterator.
@@ -131,6 +157,15 @@ impl OverlayedChanges {
 		self.committed.drain()
 	}
 
+	/// Drain committed offchain index changes to an iterator.
+	///
+	/// Panics:
+	/// Will panic if there are any uncommitted prospective offchain index changes.
+	pub fn drain_offchain<'a>(&'a mut self) -> impl Iterator<Item=(Vec<u8>, Option<Vec<u8>>)> + 'a {
+		assert!(self.prospective_offchain.is_empty());
+		self.committed_offchain.drain()
+	}
+
 	/// Consume `OverlayedChanges` and take committed set.
 	///
 	/// Panics:
@@ -190,9 +225,26 @@ pub trait Externalities<H: Hasher> {
 	/// Clear storage entries which keys are start with the given prefix.
 	fn clear_prefix(&mut self, prefix: &[u8]);
 
+	/// Get the smallest key in storage that is greater than `key`, or None if there is none.
+	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>>;
+
 	/// Set or clear a storage entry (`key`) of current contract being called (effective immediately).
 	fn place_storage(&mut self, key: Vec<u8>, value: Option<Vec<u8>>);
 
+	/// Set an entry (`key`) of the local offchain database. Unlike storage, the offchain
+	/// database is not part of consensus; the write is applied when the block is imported.
+	fn offchain_index_set(&mut self, key: &[u8], value: &[u8]) {
+		self.place_offchain_index(key.to_vec(), Some(value.to_vec()));
+	}
+
+	/// Clear an entry (`key`) of the local offchain database.
+	fn offchain_index_clear(&mut self, key: &[u8]) {
+		self.place_offchain_index(key.to_vec(), None);
+	}
+
+	/// Set or clear an entry (`key`) of the local offchain database.
+	fn place_offchain_index(&mut self, key: Vec<u8>, value: Option<Vec<u8>>);
+
 	/// Get the identity of the chain.
 	fn chain_id(&self) -> u64;
 
@@ -326,11 +378,11 @@ where
 	let strategy: ExecutionStrategy = (&manager).into();
 
 	// make a copy.
-	let code = ext::Ext::new(overlay, backend).storage(b":code")
+	let code = ext::Ext::new(overlay, backend).storage(well_known_keys::CODE)
 		.ok_or_else(|| Box::new(ExecutionError::CodeEntryDoesNotExist) as Box<Error>)?
 		.to_vec();
 
-	let heap_pages = ext::Ext::new(overlay, backend).storage(b":heappages")
+	let heap_pages = ext::Ext::new(overlay, backend).storage(well_known_keys::HEAP_PAGES)
 		.and_then(|v| u64::decode(&mut &v[..])).unwrap_or(8) as usize;
 
 	let result = {
@@ -456,12 +508,33 @@ where
 	H: Hasher,
 	C: NodeCodec<H>,
 	H::Out: Ord + Encodable + HeapSizeOf
+{
+	prove_read_keys(backend, &[key])
+		.map(|(mut values, proof)| (values.pop().expect("one value is read per key; qed"), proof))
+}
+
+/// Generate a single storage read proof for several keys, returning their values in the order
+/// of the keys. The proof holds the trie nodes visited to read all of them.
+pub fn prove_read_keys<B, H, C, I>(
+	backend: B,
+	keys: I,
+) -> Result<(Vec<Option<Vec<u8>>>, Vec<Vec<u8>>), Box<Error>>
+where
+	B: TryIntoTrieBackend<H, C>,
+	H: Hasher,
+	C: NodeCodec<H>,
+	H::Out: Ord + Encodable + HeapSizeOf,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
 {
 	let trie_backend = backend.try_into_trie_backend()
 		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<Error>)?;
 	let proving_backend = proving_backend::ProvingBackend::<H, C>::new(trie_backend);
-	let result = proving_backend.storage(key).map_err(|e| Box::new(e) as Box<Error>)?;
-	Ok((result, proving_backend.extract_proof()))
+	let values = keys.into_iter()
+		.map(|key| proving_backend.storage(key.as_ref()))
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|e| Box::new(e) as Box<Error>)?;
+	Ok((values, proving_backend.extract_proof()))
 }
 
 /// Check storage read proof, generated by `prove_read` call.
@@ -474,9 +547,68 @@ where
 	H: Hasher,
 	C: NodeCodec<H>,
 	H::Out: Ord + Encodable + HeapSizeOf
+{
+	read_proof_check_keys::<H, C, _>(root, proof, &[key])
+		.map(|mut values| values.pop().expect("one value is read per key; qed"))
+}
+
+/// Check a storage read proof of several keys, generated by `prove_read_keys`, returning their
+/// values in the order of the keys. Fails if the proof misses a node needed to read any of them.
+pub fn read_proof_check_keys<H, C, I>(
+	root: H::Out,
+	proof: Vec<Vec<u8>>,
+	keys: I,
+) -> Result<Vec<Option<Vec<u8>>>, Box<Error>>
+where
+	H: Hasher,
+	C: NodeCodec<H>,
+	H::Out: Ord + Encodable + HeapSizeOf,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
 {
 	let backend = proving_backend::create_proof_check_backend::<H, C>(root, proof)?;
-	backend.storage(key).map_err(|e| Box::new(e) as Box<Error>)
+	keys.into_iter()
+		.map(|key| backend.storage(key.as_ref()))
+		.collect::<Result<Vec<_>, _>>()
+		.map_err(|e| Box::new(e) as Box<Error>)
+}
+
+/// Generate a proof of a range of the storage: the entries with keys not smaller than `start`, in
+/// key order, stopping before they exceed `max_bytes` (but with at least one). Returns the entries,
+/// whether they reach the end of the storage, and the proof.
+pub fn prove_range<B, H, C>(
+	backend: B,
+	start: &[u8],
+	max_bytes: usize,
+) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, Vec<Vec<u8>>), Box<Error>>
+where
+	B: TryIntoTrieBackend<H, C>,
+	H: Hasher,
+	C: NodeCodec<H>,
+	H::Out: Ord + Encodable + HeapSizeOf,
+{
+	let trie_backend = backend.try_into_trie_backend()
+		.ok_or_else(|| Box::new(ExecutionError::UnableToGenerateProof) as Box<Error>)?;
+	proving_backend::prove_range(&trie_backend, start, max_bytes)
+		.map_err(|e| Box::new(e) as Box<Error>)
+}
+
+/// Check a storage range proof, generated by `prove_range` call. Fails unless `entries` are all
+/// the entries from `start` on up to the last of them and, if `complete`, there are no more.
+pub fn range_proof_check<H, C>(
+	root: H::Out,
+	proof: Vec<Vec<u8>>,
+	start: &[u8],
+	entries: &[(Vec<u8>, Vec<u8>)],
+	complete: bool,
+) -> Result<(), Box<Error>>
+where
+	H: Hasher,
+	C: NodeCodec<H>,
+	H::Out: Ord + Encodable + HeapSizeOf,
+{
+	let backend = proving_backend::create_proof_check_backend::<H, C>(root, proof)?;
+	proving_backend::check_range(&backend, start, entries, complete)
 }
 
 #[cfg(test)]
@@ -543,6 +675,21 @@ mod tests {
 		assert!(overlayed.storage(&key).unwrap().is_none());
 	}
 
+	#[test]
+	fn offchain_index_follows_prospective_changes() {
+		let backend = InMemory::<Blake2Hasher, RlpCodec>::default();
+		let mut overlay = OverlayedChanges::default();
+
+		Ext::new(&mut overlay, &backend).offchain_index_set(b"tx", b"block");
+		overlay.commit_prospective();
+		Ext::new(&mut overlay, &backend).offchain_index_clear(b"tx");
+		Ext::new(&mut overlay, &backend).offchain_index_set(b"other", b"value");
+		overlay.discard_prospective();
+
+		assert_eq!(overlay.drain_offchain().collect::<Vec<_>>(), vec![(b"tx".to_vec(), Some(b"block".to_vec()))]);
+		assert!(overlay.storage(b"tx").is_none());
+	}
+
 	macro_rules! map {
 		($( $name:expr => $value:expr ),*) => (
 			vec![ $( ( $name, $value ) ),* ].into_iter().collect()
@@ -568,6 +715,7 @@ mod tests {
 				b"dogglesworth".to_vec() => Some(b"cat".to_vec()),
 				b"doug".to_vec() => None
 			],
+			..Default::default()
 		};
 		let mut ext = Ext::new(&mut overlay, &backend);
 		const ROOT: [u8; 32] = hex!("6ca394ff9b13d6690a51dea30b1b5c43108e52944d30b9095227c49bae03ff8b");
@@ -653,6 +801,7 @@ mod tests {
 				b"abd".to_vec() => Some(b"69".to_vec()),
 				b"bbd".to_vec() => Some(b"42".to_vec())
 			],
+			..Default::default()
 		};
 
 		{
@@ -675,6 +824,35 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn next_storage_key_in_ext_works() {
+		let initial: HashMap<_, _> = map![
+			b"aaa".to_vec() => b"0".to_vec(),
+			b"abb".to_vec() => b"1".to_vec(),
+			b"abc".to_vec() => b"2".to_vec(),
+			b"bbb".to_vec() => b"3".to_vec()
+		];
+		let backend = InMemory::<Blake2Hasher, RlpCodec>::from(initial).try_into_trie_backend().unwrap();
+		let mut overlay = OverlayedChanges {
+			committed: map![
+				b"aba".to_vec() => Some(b"1312".to_vec()),
+				b"abb".to_vec() => None
+			],
+			prospective: map![
+				b"abc".to_vec() => None,
+				b"aba".to_vec() => None,
+				b"abd".to_vec() => Some(b"69".to_vec())
+			],
+			..Default::default()
+		};
+
+		let ext = Ext::new(&mut overlay, &backend);
+		assert_eq!(ext.next_storage_key(b""), Some(b"aaa".to_vec()));
+		assert_eq!(ext.next_storage_key(b"aaa"), Some(b"abd".to_vec()));
+		assert_eq!(ext.next_storage_key(b"abd"), Some(b"bbb".to_vec()));
+		assert_eq!(ext.next_storage_key(b"bbb"), None);
+	}
+
 	#[test]
 	fn prove_read_and_proof_check_works() {
 		// fetch read proof from 'remote' full node
@@ -688,4 +866,57 @@ mod tests {
 		assert_eq!(local_result1, Some(vec![24]));
 		assert_eq!(local_result2, false);
 	}
+
+	#[test]
+	fn prove_read_keys_and_proof_check_works() {
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let keys: &[&[u8]] = &[b"value1", b"key", b"missing"];
+		let (values, proof) = prove_read_keys(remote_backend, keys).unwrap();
+		assert_eq!(values, vec![Some(vec![42]), Some(b"value".to_vec()), None]);
+
+		let checked = read_proof_check_keys::<Blake2Hasher, RlpCodec, _>(remote_root, proof.clone(), keys).unwrap();
+		assert_eq!(checked, values);
+		assert_eq!(
+			read_proof_check_keys::<Blake2Hasher, RlpCodec, _>(remote_root, proof.clone(), &[b"value2"]).unwrap(),
+			vec![Some(vec![24])],
+		);
+		assert!(read_proof_check_keys::<Blake2Hasher, RlpCodec, _>(remote_root, proof, &[&[0xff][..]]).is_err());
+	}
+
+	#[test]
+	fn prove_range_and_proof_check_works() {
+		let remote_backend = trie_backend::tests::test_trie();
+		let remote_root = remote_backend.storage_root(::std::iter::empty()).0;
+		let mut pairs = remote_backend.pairs();
+		pairs.sort();
+
+		let mut start = Vec::new();
+		let mut downloaded = Vec::new();
+		loop {
+			let (entries, complete, proof) = prove_range(trie_backend::tests::test_trie(), &start, 16).unwrap();
+			assert!(!entries.is_empty());
+			range_proof_check::<Blake2Hasher, RlpCodec>(remote_root, proof.clone(), &start, &entries, complete).unwrap();
+
+			// claiming the wrong end, dropping or changing an entry is detected
+			assert!(range_proof_check::<Blake2Hasher, RlpCodec>(remote_root, proof.clone(), &start, &entries, !complete).is_err());
+			assert!(range_proof_check::<Blake2Hasher, RlpCodec>(remote_root, proof.clone(), &start, &entries[1..], complete).is_err());
+			let mut changed = entries.clone();
+			changed[0].1.push(0);
+			assert!(range_proof_check::<Blake2Hasher, RlpCodec>(remote_root, proof, &start, &changed, complete).is_err());
+
+			start = entries.last().unwrap().0.clone();
+			start.push(0);
+			downloaded.extend(entries);
+			if complete {
+				break;
+			}
+		}
+		assert_eq!(downloaded, pairs);
+
+		// the proof of a range doesn't prove the ones after it
+		let (_, _, proof) = prove_range(trie_backend::tests::test_trie(), &[], 16).unwrap();
+		let (entries, complete, _) = prove_range(trie_backend::tests::test_trie(), &[200], 16).unwrap();
+		assert!(range_proof_check::<Blake2Hasher, RlpCodec>(remote_root, proof, &[200], &entries, complete).is_err());
+	}
 }
@@ -17,8 +17,10 @@
 //! Proving state machine backend.
 
 use std::cell::RefCell;
-use hashdb::{Hasher, HashDB};
+use std::collections::HashMap;
+use hashdb::{Hasher, HashDB, AsHashDB, DBValue};
 use memorydb::MemoryDB;
+use parking_lot::Mutex;
 use patricia_trie::{TrieDB, Trie, Recorder, NodeCodec};
 use trie_backend::{TrieBackend, Ephemeral};
 use {Error, ExecutionError, Backend, TryIntoTrieBackend};
@@ -78,6 +80,10 @@ where
 		self.backend.for_keys_with_prefix(prefix, f)
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+		self.backend.next_storage_key(key)
+	}
+
 	fn pairs(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
 		self.backend.pairs()
 	}
@@ -95,6 +101,127 @@ impl<H: Hasher, C: NodeCodec<H>> TryIntoTrieBackend<H, C> for ProvingBackend<H,
 	}
 }
 
+/// Hash db which records the trie nodes read through it, for proving reads which can't use a
+/// `Recorder`, such as iterations.
+struct RecordingDb<'a, H: 'a + Hasher> {
+	db: Ephemeral<'a, H>,
+	recorded: Mutex<HashMap<H::Out, DBValue>>,
+}
+
+impl<'a, H: Hasher> AsHashDB<H> for RecordingDb<'a, H> where H::Out: HeapSizeOf {
+	fn as_hashdb(&self) -> &HashDB<H> { self }
+	fn as_hashdb_mut(&mut self) -> &mut HashDB<H> { self }
+}
+
+impl<'a, H: Hasher> HashDB<H> for RecordingDb<'a, H> where H::Out: HeapSizeOf {
+	fn keys(&self) -> HashMap<H::Out, i32> {
+		self.db.keys()
+	}
+
+	fn get(&self, key: &H::Out) -> Option<DBValue> {
+		let value = self.db.get(key);
+		if let Some(ref value) = value {
+			self.recorded.lock().insert(*key, value.clone());
+		}
+		value
+	}
+
+	fn contains(&self, key: &H::Out) -> bool {
+		self.get(key).is_some()
+	}
+
+	fn insert(&mut self, value: &[u8]) -> H::Out {
+		self.db.insert(value)
+	}
+
+	fn emplace(&mut self, key: H::Out, value: DBValue) {
+		self.db.emplace(key, value)
+	}
+
+	fn remove(&mut self, key: &H::Out) {
+		self.db.remove(key)
+	}
+}
+
+/// Read the entries of the trie with keys not smaller than `start`, in key order, along with the
+/// trie nodes visited. Stops before the entries exceed `max_bytes`, but reads at least one, and
+/// tells whether the last entry of the trie was reached. The entry following the range is read to
+/// find that out, so the nodes also prove whether there are more entries.
+pub(crate) fn prove_range<H, C>(
+	backend: &TrieBackend<H, C>,
+	start: &[u8],
+	max_bytes: usize,
+) -> Result<(Vec<(Vec<u8>, Vec<u8>)>, bool, Vec<Vec<u8>>), String>
+where
+	H: Hasher,
+	C: NodeCodec<H>,
+	H::Out: HeapSizeOf,
+{
+	let mut read_overlay = MemoryDB::new();
+	let db = RecordingDb {
+		db: Ephemeral::new(backend.backend_storage(), &mut read_overlay),
+		recorded: Mutex::new(HashMap::new()),
+	};
+
+	let (entries, complete) = {
+		let map_e = |e| format!("Trie lookup error: {}", e);
+		let trie = TrieDB::<H, C>::new(&db, backend.root()).map_err(map_e)?;
+		let mut iter = trie.iter().map_err(map_e)?;
+		iter.seek(start).map_err(map_e)?;
+
+		let mut entries = Vec::new();
+		let mut size = 0;
+		let mut complete = true;
+		for x in iter {
+			let (key, value) = x.map_err(map_e)?;
+			let entry_size = key.len() + value.len();
+			if !entries.is_empty() && size + entry_size > max_bytes {
+				complete = false;
+				break;
+			}
+			size += entry_size;
+			entries.push((key.to_vec(), value.to_vec()));
+		}
+		(entries, complete)
+	};
+
+	let proof = db.recorded.into_inner().into_iter().map(|(_, node)| node.to_vec()).collect();
+	Ok((entries, complete, proof))
+}
+
+/// Check a proof generated by `prove_range`: the `entries` must be the entries of the trie with
+/// keys not smaller than `start` up to the last of them, and if `complete` there must be no more.
+pub(crate) fn check_range<H, C>(
+	backend: &TrieBackend<H, C>,
+	start: &[u8],
+	entries: &[(Vec<u8>, Vec<u8>)],
+	complete: bool,
+) -> Result<(), Box<Error>>
+where
+	H: Hasher,
+	C: NodeCodec<H>,
+	H::Out: HeapSizeOf,
+{
+	let invalid = || Box::new(ExecutionError::InvalidProof) as Box<Error>;
+	let mut read_overlay = MemoryDB::new();
+	let db = Ephemeral::new(backend.backend_storage(), &mut read_overlay);
+	let trie = TrieDB::<H, C>::new(&db, backend.root()).map_err(|_| invalid())?;
+	let mut iter = trie.iter().map_err(|_| invalid())?;
+	iter.seek(start).map_err(|_| invalid())?;
+
+	for &(ref key, ref value) in entries {
+		match iter.next() {
+			Some(Ok((ref k, ref v))) if &k[..] == &key[..] && &v[..] == &value[..] => {},
+			_ => return Err(invalid()),
+		}
+	}
+	match iter.next() {
+		None if complete => Ok(()),
+		Some(Ok(_)) if !complete => Ok(()),
+		_ => Err(invalid()),
+	}
+}
+
 /// Create proof check backend.
 pub fn create_proof_check_backend<H, C>(
 	root: H::Out,
@@ -29,18 +29,23 @@ use std::iter::FromIterator;
 #[derive(Debug)]
 pub struct TestExternalities<H> {
 	inner: HashMap<Vec<u8>, Vec<u8>>,
+	offchain: HashMap<Vec<u8>, Vec<u8>>,
 	_hasher: PhantomData<H>,
 }
 
 impl<H: Hasher> TestExternalities<H> {
 	/// Create a new instance of `TestExternalities`
 	pub fn new() -> Self {
-		TestExternalities {inner: HashMap::new(), _hasher: PhantomData}
+		TestExternalities {inner: HashMap::new(), offchain: HashMap::new(), _hasher: PhantomData}
 	}
 	/// Insert key/value
 	pub fn insert(&mut self, k: Vec<u8>, v: Vec<u8>) -> Option<Vec<u8>> {
 		self.inner.insert(k, v)
 	}
+	/// Get an entry of the offchain database written through the offchain index.
+	pub fn offchain_storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.offchain.get(key).map(|x| x.to_vec())
+	}
 }
 
 impl<H: Hasher> PartialEq for TestExternalities<H> {
@@ -71,7 +76,7 @@ impl<H: Hasher> From<TestExternalities<H>> for HashMap<Vec<u8>, Vec<u8>> {
 
 impl<H: Hasher> From< HashMap<Vec<u8>, Vec<u8>> > for TestExternalities<H> {
 	fn from(hashmap: HashMap<Vec<u8>, Vec<u8>>) -> Self {
-		TestExternalities { inner: hashmap, _hasher: PhantomData }
+		TestExternalities { inner: hashmap, offchain: HashMap::new(), _hasher: PhantomData }
 	}
 }
 
@@ -94,6 +99,17 @@ impl<H: Hasher> Externalities<H> for TestExternalities<H> where H::Out: Ord + En
 		)
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.inner.keys().filter(|k| &k[..] > key).min().cloned()
+	}
+
+	fn place_offchain_index(&mut self, key: Vec<u8>, maybe_value: Option<Vec<u8>>) {
+		match maybe_value {
+			Some(value) => { self.offchain.insert(key, value); }
+			None => { self.offchain.remove(&key); }
+		}
+	}
+
 	fn chain_id(&self) -> u64 { 42 }
 
 	fn storage_root(&mut self) -> H::Out {
@@ -135,6 +135,28 @@ impl<H: Hasher, C: NodeCodec<H>> Backend<H, C> for TrieBackend<H, C> where H::Ou
 		}
 	}
 
+	fn next_storage_key(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+		let mut read_overlay = MemoryDB::new();
+		let eph = Ephemeral {
+			storage: &self.storage,
+			overlay: &mut read_overlay,
+		};
+
+		let map_e = |e| format!("Trie lookup error: {}", e);
+		let trie = TrieDB::<H, C>::new(&eph, &self.root).map_err(map_e)?;
+		let mut iter = trie.iter().map_err(map_e)?;
+		iter.seek(key).map_err(map_e)?;
+
+		for x in iter {
+			let (next, _) = x.map_err(map_e)?;
+			if &next[..] != key {
+				return Ok(Some(next));
+			}
+		}
+
+		Ok(None)
+	}
+
 	fn pairs(&self) -> Vec<(Vec<u8>, Vec<u8>)> {
 		let mut read_overlay = MemoryDB::new();
 		let eph = Ephemeral {
@@ -359,4 +381,15 @@ pub mod tests {
 		expected.insert(b"value2".to_vec());
 		assert_eq!(seen, expected);
 	}
+
+	#[test]
+	fn next_storage_key_works() {
+		let trie = test_trie();
+		assert_eq!(trie.next_storage_key(b"").unwrap(), Some(b":code".to_vec()));
+		assert_eq!(trie.next_storage_key(b"key").unwrap(), Some(b"value1".to_vec()));
+		assert_eq!(trie.next_storage_key(b"value").unwrap(), Some(b"value1".to_vec()));
+		assert_eq!(trie.next_storage_key(b"value1").unwrap(), Some(b"value2".to_vec()));
+		assert_eq!(trie.next_storage_key(b"value2").unwrap(), Some(vec![128]));
+		assert_eq!(trie.next_storage_key(&[254]).unwrap(), None);
+	}
 }
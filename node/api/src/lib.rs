@@ -25,12 +25,12 @@ extern crate substrate_client as client;
 extern crate substrate_primitives;
 
 pub use client::error::{Error, ErrorKind, Result};
-use runtime::Address;
+use runtime::{Address, ApplyError, InherentData};
 use client::backend::Backend;
 use client::block_builder::BlockBuilder as ClientBlockBuilder;
 use client::{Client, CallExecutor};
 use primitives::{
-	AccountId, Block, BlockId, Hash, Index, InherentData,
+	AccountId, Block, BlockId, BlockNumber, Hash, Index,
 	SessionKey, Timestamp, UncheckedExtrinsic,
 };
 use substrate_primitives::{Blake2Hasher, RlpCodec};
@@ -69,16 +69,37 @@ pub trait Api {
 	/// Get the account id of an address at a block.
 	fn lookup(&self, at: &BlockId, address: Address) -> Result<Option<AccountId>>;
 
+	/// Check the signature and the signed extensions of a transaction against the state at a
+	/// block, without applying it. A `Future` error means it may become valid later.
+	fn validate_transaction(
+		&self,
+		at: &BlockId,
+		transaction: &UncheckedExtrinsic,
+	) -> Result<::std::result::Result<(), ApplyError>>;
+
 	/// Evaluate a block. Returns true if the block is good, false if it is known to be bad,
 	/// and an error if we can't evaluate for some reason.
 	fn evaluate_block(&self, at: &BlockId, block: Block) -> Result<bool>;
 
+	/// Run the offchain workers of the runtime for the imported block `at`, numbered `number`.
+	/// Their changes to the state are discarded.
+	fn offchain_worker(&self, at: &BlockId, number: BlockNumber) -> Result<()>;
+
 	/// Build a block on top of the given, with inherent extrinsics pre-pushed.
 	fn build_block(&self, at: &BlockId, inherent_data: InherentData) -> Result<Self::BlockBuilder>;
 
 	/// Attempt to produce the (encoded) inherent extrinsics for a block being built upon the given.
 	/// This may vary by runtime and will fail if a runtime doesn't follow the same API.
 	fn inherent_extrinsics(&self, at: &BlockId, inherent_data: InherentData) -> Result<Vec<UncheckedExtrinsic>>;
+
+	/// Check the inherent extrinsics of a block against the local inherent data, as of the block
+	/// it is built upon. Gives the reason of the first failed check, if any.
+	fn check_inherents(
+		&self,
+		at: &BlockId,
+		block: &Block,
+		inherent_data: &InherentData,
+	) -> Result<::std::result::Result<(), String>>;
 }
 
 impl<B, E> BlockBuilder for ClientBlockBuilder<B, E, Block, Blake2Hasher, RlpCodec>
@@ -130,6 +151,10 @@ where
 		}
 	}
 
+	fn offchain_worker(&self, at: &BlockId, number: BlockNumber) -> Result<()> {
+		self.call_api_at(at, "offchain_worker", &number)
+	}
+
 	fn index(&self, at: &BlockId, account: AccountId) -> Result<Index> {
 		self.call_api_at(at, "account_nonce", &account)
 	}
@@ -138,6 +163,14 @@ where
 		self.call_api_at(at, "lookup_address", &address)
 	}
 
+	fn validate_transaction(
+		&self,
+		at: &BlockId,
+		transaction: &UncheckedExtrinsic,
+	) -> Result<::std::result::Result<(), ApplyError>> {
+		self.call_api_at(at, "validate_transaction", transaction)
+	}
+
 	fn build_block(&self, at: &BlockId, inherent_data: InherentData) -> Result<Self::BlockBuilder> {
 		let mut block_builder = self.new_block_at(at)?;
 		for inherent in self.inherent_extrinsics(at, inherent_data)? {
@@ -151,5 +184,14 @@ where
 		let runtime_version = self.runtime_version_at(at)?;
 		self.call_api_at(at, "inherent_extrinsics", &(inherent_data, runtime_version.spec_version))
 	}
+
+	fn check_inherents(
+		&self,
+		at: &BlockId,
+		block: &Block,
+		inherent_data: &InherentData,
+	) -> Result<::std::result::Result<(), String>> {
+		self.call_api_at(at, "check_inherents", &(block, inherent_data))
+	}
 }
 
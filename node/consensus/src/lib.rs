@@ -202,7 +202,7 @@ impl<C> bft::Proposer<Block> for Proposer<C>
 	fn propose(&self) -> Result<Block, Error> {
 		use node_api::BlockBuilder;
 		use runtime_primitives::traits::{Hash as HashT, BlakeTwo256};
-		use node_primitives::InherentData;
+		use node_runtime::ProvideInherent;
 
 		const MAX_VOTE_OFFLINE_SECONDS: Duration = Duration::from_secs(60);
 
@@ -223,10 +223,9 @@ impl<C> bft::Proposer<Block> for Proposer<C>
 				)
 		}
 
-		let inherent_data = InherentData {
-			timestamp,
-			offline_indices,
-		};
+		let mut inherent_data = local_inherent_data(timestamp);
+		inherent_data.put_data(<node_runtime::Consensus as ProvideInherent>::INHERENT_IDENTIFIER, &offline_indices)
+			.expect("only the timestamp is put in the local inherent data; qed");
 
 		let mut block_builder = self.client.build_block(&self.parent_id, inherent_data)?;
 
@@ -323,6 +322,17 @@ impl<C> bft::Proposer<Block> for Proposer<C>
 			}
 		};
 
+		// refuse to vote if the inherents of this block don't match our view, e.g. its timestamp
+		// is too far in the future.
+		match self.client.check_inherents(&self.parent_id, unchecked_proposal, &local_inherent_data(current_timestamp)) {
+			Ok(Ok(())) => {},
+			Ok(Err(reason)) => {
+				debug!(target: "bft", "Invalid inherents in proposal: {}", reason);
+				return Box::new(future::ok(false));
+			},
+			Err(e) => return Box::new(future::err(e.into())),
+		}
+
 		// refuse to vote if this block says a validator is offline that we
 		// think isn't.
 		let offline = proposal.noted_offline();
@@ -400,16 +410,20 @@ impl<C> bft::Proposer<Block> for Proposer<C>
 						=> MisbehaviorKind::BftDoubleCommit(round as u32, (h1, s1.signature), (h2, s2.signature)),
 				}
 			};
-			let payload = (next_index, Call::Consensus(ConsensusCall::report_misbehavior(report)));
-			let signature = self.local_key.sign(&payload.encode()).into();
+			let payload = (
+				Call::Consensus(ConsensusCall::report_misbehavior(report)),
+				node_runtime::signed_extra(next_index),
+			);
+			let signature = runtime_primitives::Ed25519Signature(self.local_key.sign(&payload.encode())).into();
 			next_index += 1;
 
 			let local_id = self.local_key.public().0.into();
-			let extrinsic = UncheckedExtrinsic {
-				signature: Some((node_runtime::RawAddress::Id(local_id), signature)),
-				index: payload.0,
-				function: payload.1,
-			};
+			let extrinsic = UncheckedExtrinsic::new_signed(
+				payload.0,
+				node_runtime::RawAddress::Id(local_id),
+				signature,
+				payload.1,
+			);
 			let uxt: GenericExtrinsic = Decode::decode(&mut extrinsic.encode().as_slice()).expect("Encoded extrinsic is valid");
 			self.transaction_pool.submit_one(&BlockId::hash(self.parent_hash), uxt)
 				.expect("locally signed extrinsic is valid; qed");
@@ -437,6 +451,17 @@ impl<C> bft::Proposer<Block> for Proposer<C>
 	}
 }
 
+/// The inherent data of the local node at `timestamp`, which the inherents of proposals are
+/// checked against.
+fn local_inherent_data(timestamp: Timestamp) -> node_runtime::InherentData {
+	use node_runtime::ProvideInherent;
+
+	let mut data = node_runtime::InherentData::new();
+	data.put_data(<node_runtime::Timestamp as ProvideInherent>::INHERENT_IDENTIFIER, &timestamp)
+		.expect("the data is empty; qed");
+	data
+}
+
 fn current_timestamp() -> Timestamp {
 	time::SystemTime::now().duration_since(time::UNIX_EPOCH)
 		.expect("now always later than unix epoch; qed")
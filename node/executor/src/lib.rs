@@ -28,7 +28,7 @@ extern crate triehash;
 
 #[cfg(test)] extern crate substrate_keyring as keyring;
 #[cfg(test)] extern crate sr_primitives as runtime_primitives;
-#[cfg(test)] extern crate srml_support as runtime_support;
+#[cfg(test)] #[macro_use] extern crate srml_support as runtime_support;
 #[cfg(test)] extern crate srml_balances as balances;
 #[cfg(test)] extern crate srml_session as session;
 #[cfg(test)] extern crate srml_staking as staking;
@@ -48,16 +48,16 @@ mod tests {
 	use substrate_executor::{WasmExecutor, NativeExecutionDispatch};
 	use codec::{Encode, Decode, Joiner};
 	use keyring::Keyring;
-	use runtime_support::{Hashable, StorageValue, StorageMap};
+	use runtime_support::Hashable;
 	use state_machine::{CodeExecutor, TestExternalities};
-	use primitives::{twox_128, Blake2Hasher, ed25519::{Public, Pair}};
+	use primitives::{Blake2Hasher, ed25519::{Public, Pair}};
 	use node_primitives::{Hash, BlockNumber, AccountId};
 	use runtime_primitives::traits::Header as HeaderT;
-	use runtime_primitives::{ApplyOutcome, ApplyError, ApplyResult};
+	use runtime_primitives::{ApplyOutcome, ApplyError, ApplyResult, Ed25519Signature, Sr25519Signature};
 	use {balances, staking, session, system, consensus, timestamp, treasury};
 	use system::{EventRecord, Phase};
 	use node_runtime::{Header, Block, UncheckedExtrinsic, CheckedExtrinsic, Call, Runtime, Balances,
-		BuildStorage, GenesisConfig, BalancesConfig, SessionConfig, StakingConfig, System, Event};
+		BuildStorage, GenesisConfig, BalancesConfig, SessionConfig, StakingConfig, System, Event, signed_extra};
 
 	const BLOATY_CODE: &[u8] = include_bytes!("../../runtime/wasm/target/wasm32-unknown-unknown/release/node_runtime.wasm");
 	const COMPACT_CODE: &[u8] = include_bytes!("../../runtime/wasm/target/wasm32-unknown-unknown/release/node_runtime.compact.wasm");
@@ -79,28 +79,19 @@ mod tests {
 
 	fn sign(xt: CheckedExtrinsic) -> UncheckedExtrinsic {
 		match xt.signed {
-			Some(signed) => {
-				let payload = (xt.index, xt.function);
+			Some((signed, extra)) => {
+				let payload = (xt.function, extra);
 				let pair = Pair::from(Keyring::from_public(Public::from_raw(signed.clone().into())).unwrap());
-				let signature = pair.sign(&payload.encode()).into();
-				UncheckedExtrinsic {
-					signature: Some((balances::address::Address::Id(signed), signature)),
-					index: payload.0,
-					function: payload.1,
-				}
+				let signature = Ed25519Signature(pair.sign(&payload.encode())).into();
+				UncheckedExtrinsic::new_signed(payload.0, balances::address::Address::Id(signed), signature, payload.1)
 			}
-			None => UncheckedExtrinsic {
-				signature: None,
-				index: xt.index,
-				function: xt.function,
-			},
+			None => UncheckedExtrinsic::new_unsigned(xt.function),
 		}
 	}
 
 	fn xt() -> UncheckedExtrinsic {
 		sign(CheckedExtrinsic {
-			signed: Some(alice()),
-			index: 0,
+			signed: Some((alice(), signed_extra(0))),
 			function: Call::Balances(balances::Call::transfer::<Runtime>(bob().into(), 69)),
 		})
 	}
@@ -116,15 +107,15 @@ mod tests {
 	#[test]
 	fn panic_execution_with_foreign_code_gives_error() {
 		let mut t: TestExternalities<Blake2Hasher> = map![
-			twox_128(&<balances::FreeBalance<Runtime>>::key_for(alice())).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TotalIssuance<Runtime>>::key()).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TransactionBaseFee<Runtime>>::key()).to_vec() => vec![70u8; 8],
-			twox_128(<balances::TransactionByteFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::ExistentialDeposit<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::CreationFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransferFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::NextEnumSet<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(&<system::BlockHash<Runtime>>::key_for(0)).to_vec() => vec![0u8; 32]
+			storage_key!(balances::FreeBalance<Runtime> => alice()).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TotalIssuance<Runtime>).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TransactionBaseFee<Runtime>).to_vec() => vec![70u8; 8],
+			storage_key!(balances::TransactionByteFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::ExistentialDeposit<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::CreationFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransferFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::NextEnumSet<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(system::BlockHash<Runtime> => 0).to_vec() => vec![0u8; 32]
 		];
 
 		let r = executor().call(&mut t, 8, BLOATY_CODE, "initialise_block", &vec![].and(&from_block_number(1u64)), true).0;
@@ -137,15 +128,15 @@ mod tests {
 	#[test]
 	fn bad_extrinsic_with_native_equivalent_code_gives_error() {
 		let mut t: TestExternalities<Blake2Hasher> = map![
-			twox_128(&<balances::FreeBalance<Runtime>>::key_for(alice())).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TotalIssuance<Runtime>>::key()).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TransactionBaseFee<Runtime>>::key()).to_vec() => vec![70u8; 8],
-			twox_128(<balances::TransactionByteFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::ExistentialDeposit<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::CreationFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransferFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::NextEnumSet<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(&<system::BlockHash<Runtime>>::key_for(0)).to_vec() => vec![0u8; 32]
+			storage_key!(balances::FreeBalance<Runtime> => alice()).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TotalIssuance<Runtime>).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TransactionBaseFee<Runtime>).to_vec() => vec![70u8; 8],
+			storage_key!(balances::TransactionByteFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::ExistentialDeposit<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::CreationFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransferFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::NextEnumSet<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(system::BlockHash<Runtime> => 0).to_vec() => vec![0u8; 32]
 		];
 
 		let r = executor().call(&mut t, 8, COMPACT_CODE, "initialise_block", &vec![].and(&from_block_number(1u64)), true).0;
@@ -158,15 +149,15 @@ mod tests {
 	#[test]
 	fn successful_execution_with_native_equivalent_code_gives_ok() {
 		let mut t: TestExternalities<Blake2Hasher> = map![
-			twox_128(&<balances::FreeBalance<Runtime>>::key_for(alice())).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TotalIssuance<Runtime>>::key()).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TransactionBaseFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransactionByteFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::ExistentialDeposit<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::CreationFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransferFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::NextEnumSet<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(&<system::BlockHash<Runtime>>::key_for(0)).to_vec() => vec![0u8; 32]
+			storage_key!(balances::FreeBalance<Runtime> => alice()).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TotalIssuance<Runtime>).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TransactionBaseFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransactionByteFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::ExistentialDeposit<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::CreationFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransferFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::NextEnumSet<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(system::BlockHash<Runtime> => 0).to_vec() => vec![0u8; 32]
 		];
 
 		let r = executor().call(&mut t, 8, COMPACT_CODE, "initialise_block", &vec![].and(&from_block_number(1u64)), true).0;
@@ -183,15 +174,15 @@ mod tests {
 	#[test]
 	fn successful_execution_with_foreign_code_gives_ok() {
 		let mut t: TestExternalities<Blake2Hasher> = map![
-			twox_128(&<balances::FreeBalance<Runtime>>::key_for(alice())).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TotalIssuance<Runtime>>::key()).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TransactionBaseFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransactionByteFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::ExistentialDeposit<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::CreationFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransferFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::NextEnumSet<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(&<system::BlockHash<Runtime>>::key_for(0)).to_vec() => vec![0u8; 32]
+			storage_key!(balances::FreeBalance<Runtime> => alice()).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TotalIssuance<Runtime>).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TransactionBaseFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransactionByteFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::ExistentialDeposit<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::CreationFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransferFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::NextEnumSet<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(system::BlockHash<Runtime> => 0).to_vec() => vec![0u8; 32]
 		];
 
 		let r = executor().call(&mut t, 8, BLOATY_CODE, "initialise_block", &vec![].and(&from_block_number(1u64)), true).0;
@@ -205,6 +196,36 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn successful_execution_of_sr25519_signed_extrinsic_gives_ok() {
+		let alice = Keyring::Alice.to_sr25519_account_id();
+		let mut t: TestExternalities<Blake2Hasher> = map![
+			storage_key!(balances::FreeBalance<Runtime> => alice).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TotalIssuance<Runtime>).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TransactionBaseFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransactionByteFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::ExistentialDeposit<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::CreationFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransferFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::NextEnumSet<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(system::BlockHash<Runtime> => 0).to_vec() => vec![0u8; 32]
+		];
+
+		let payload = (Call::Balances(balances::Call::transfer::<Runtime>(bob().into(), 69)), signed_extra(0));
+		let signature = Sr25519Signature(Keyring::Alice.sign_sr25519(&payload.encode())).into();
+		let xt = UncheckedExtrinsic::new_signed(payload.0, balances::address::Address::Id(alice), signature, payload.1);
+
+		let r = executor().call(&mut t, 8, COMPACT_CODE, "initialise_block", &vec![].and(&from_block_number(1u64)), true).0;
+		assert!(r.is_ok());
+		let r = executor().call(&mut t, 8, COMPACT_CODE, "apply_extrinsic", &vec![].and(&xt), true).0;
+		assert!(r.is_ok());
+
+		runtime_io::with_externalities(&mut t, || {
+			assert_eq!(Balances::total_balance(&alice), 42);
+			assert_eq!(Balances::total_balance(&bob()), 69);
+		});
+	}
+
 	fn new_test_ext() -> TestExternalities<Blake2Hasher> {
 		use keyring::Keyring::*;
 		let three = [3u8; 32].into();
@@ -269,12 +290,10 @@ mod tests {
 			vec![
 				CheckedExtrinsic {
 					signed: None,
-					index: 0,
 					function: Call::Timestamp(timestamp::Call::set(42)),
 				},
 				CheckedExtrinsic {
-					signed: Some(alice()),
-					index: 0,
+					signed: Some((alice(), signed_extra(0))),
 					function: Call::Balances(balances::Call::transfer(bob().into(), 69)),
 				},
 			]
@@ -289,17 +308,14 @@ mod tests {
 			vec![
 				CheckedExtrinsic {
 					signed: None,
-					index: 0,
 					function: Call::Timestamp(timestamp::Call::set(52)),
 				},
 				CheckedExtrinsic {
-					signed: Some(bob()),
-					index: 0,
+					signed: Some((bob(), signed_extra(0))),
 					function: Call::Balances(balances::Call::transfer(alice().into(), 5)),
 				},
 				CheckedExtrinsic {
-					signed: Some(alice()),
-					index: 1,
+					signed: Some((alice(), signed_extra(1))),
 					function: Call::Balances(balances::Call::transfer(bob().into(), 15)),
 				}
 			]
@@ -314,12 +330,10 @@ mod tests {
 			vec![
 				CheckedExtrinsic {
 					signed: None,
-					index: 0,
 					function: Call::Timestamp(timestamp::Call::set(42)),
 				},
 				CheckedExtrinsic {
-					signed: Some(alice()),
-					index: 0,
+					signed: Some((alice(), signed_extra(0))),
 					function: Call::Consensus(consensus::Call::remark(vec![0; 120000])),
 				}
 			]
@@ -338,11 +352,13 @@ mod tests {
 			assert_eq!(System::events(), vec![
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(0),
-					event: Event::system(system::Event::ExtrinsicSuccess)
+					event: Event::system(system::Event::ExtrinsicSuccess),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(1),
-					event: Event::balances(balances::RawEvent::NewAccount(bob(), 1, balances::NewAccountOutcome::NoHint))
+					event: Event::balances(balances::RawEvent::NewAccount(bob(), 1, balances::NewAccountOutcome::NoHint)),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(1),
@@ -351,23 +367,28 @@ mod tests {
 						hex!["d7568e5f0a7eda67a82691ff379ac4bba4f9c9b859fe779b5d46363b61ad2db9"].into(),
 						69,
 						0
-					))
+					)),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(1),
-					event: Event::system(system::Event::ExtrinsicSuccess)
+					event: Event::system(system::Event::ExtrinsicSuccess),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Finalization,
-					event: Event::treasury(treasury::RawEvent::Spending(0))
+					event: Event::treasury(treasury::RawEvent::Spending(0)),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Finalization,
-					event: Event::treasury(treasury::RawEvent::Burnt(0))
+					event: Event::treasury(treasury::RawEvent::Burnt(0)),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Finalization,
-					event: Event::treasury(treasury::RawEvent::Rollover(0))
+					event: Event::treasury(treasury::RawEvent::Rollover(0)),
+					topics: vec![],
 				}
 			]);
 		});
@@ -380,7 +401,8 @@ mod tests {
 			assert_eq!(System::events(), vec![
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(0),
-					event: Event::system(system::Event::ExtrinsicSuccess)
+					event: Event::system(system::Event::ExtrinsicSuccess),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(1),
@@ -391,11 +413,13 @@ mod tests {
 							5,
 							0
 						)
-					)
+					),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(1),
-					event: Event::system(system::Event::ExtrinsicSuccess)
+					event: Event::system(system::Event::ExtrinsicSuccess),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(2),
@@ -406,31 +430,38 @@ mod tests {
 							15,
 							0
 						)
-					)
+					),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(2),
-					event: Event::system(system::Event::ExtrinsicSuccess)
+					event: Event::system(system::Event::ExtrinsicSuccess),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Finalization,
-					event: Event::session(session::RawEvent::NewSession(1))
+					event: Event::session(session::RawEvent::NewSession(1)),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Finalization,
-					event: Event::staking(staking::RawEvent::Reward(0))
+					event: Event::staking(staking::RawEvent::Reward(0)),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Finalization,
-					event: Event::treasury(treasury::RawEvent::Spending(0))
+					event: Event::treasury(treasury::RawEvent::Spending(0)),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Finalization,
-					event: Event::treasury(treasury::RawEvent::Burnt(0))
+					event: Event::treasury(treasury::RawEvent::Burnt(0)),
+					topics: vec![],
 				},
 				EventRecord {
 					phase: Phase::Finalization,
-					event: Event::treasury(treasury::RawEvent::Rollover(0))
+					event: Event::treasury(treasury::RawEvent::Rollover(0)),
+					topics: vec![],
 				}
 			]);
 		});
@@ -482,15 +513,15 @@ mod tests {
 	#[test]
 	fn panic_execution_gives_error() {
 		let mut t: TestExternalities<Blake2Hasher> = map![
-			twox_128(&<balances::FreeBalance<Runtime>>::key_for(alice())).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TotalIssuance<Runtime>>::key()).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TransactionBaseFee<Runtime>>::key()).to_vec() => vec![70u8; 8],
-			twox_128(<balances::TransactionByteFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::ExistentialDeposit<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::CreationFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransferFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::NextEnumSet<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(&<system::BlockHash<Runtime>>::key_for(0)).to_vec() => vec![0u8; 32]
+			storage_key!(balances::FreeBalance<Runtime> => alice()).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TotalIssuance<Runtime>).to_vec() => vec![69u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TransactionBaseFee<Runtime>).to_vec() => vec![70u8; 8],
+			storage_key!(balances::TransactionByteFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::ExistentialDeposit<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::CreationFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransferFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::NextEnumSet<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(system::BlockHash<Runtime> => 0).to_vec() => vec![0u8; 32]
 		];
 
 		let foreign_code = include_bytes!("../../runtime/wasm/target/wasm32-unknown-unknown/release/node_runtime.wasm");
@@ -504,15 +535,15 @@ mod tests {
 	#[test]
 	fn successful_execution_gives_ok() {
 		let mut t: TestExternalities<Blake2Hasher> = map![
-			twox_128(&<balances::FreeBalance<Runtime>>::key_for(alice())).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TotalIssuance<Runtime>>::key()).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
-			twox_128(<balances::TransactionBaseFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransactionByteFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::ExistentialDeposit<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::CreationFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::TransferFee<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(<balances::NextEnumSet<Runtime>>::key()).to_vec() => vec![0u8; 8],
-			twox_128(&<system::BlockHash<Runtime>>::key_for(0)).to_vec() => vec![0u8; 32]
+			storage_key!(balances::FreeBalance<Runtime> => alice()).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TotalIssuance<Runtime>).to_vec() => vec![111u8, 0, 0, 0, 0, 0, 0, 0],
+			storage_key!(balances::TransactionBaseFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransactionByteFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::ExistentialDeposit<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::CreationFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::TransferFee<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(balances::NextEnumSet<Runtime>).to_vec() => vec![0u8; 8],
+			storage_key!(system::BlockHash<Runtime> => 0).to_vec() => vec![0u8; 32]
 		];
 
 		let foreign_code = include_bytes!("../../runtime/wasm/target/wasm32-unknown-unknown/release/node_runtime.compact.wasm");
@@ -40,14 +40,14 @@ use rstd::prelude::*;
 use runtime_primitives::generic;
 #[cfg(feature = "std")]
 use primitives::bytes;
-use runtime_primitives::traits::BlakeTwo256;
+use runtime_primitives::traits::{BlakeTwo256, Verify, IdentifyAccount};
 
 /// An index to a block.
 pub type BlockNumber = u64;
 
-/// Alias to Ed25519 pubkey that identifies an account on the chain. This will almost
-/// certainly continue to be the same as the substrate's `AuthorityId`.
-pub type AccountId = ::primitives::H256;
+/// Alias to the signer of a `Signature` that identifies an account on the chain: the public key
+/// of an ed25519 or sr25519 signer, the hash of the public key of an ECDSA one.
+pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
 
 /// The type for looking up accounts. We don't expect more than 4 billion of them, but you
 /// never know...
@@ -66,8 +66,9 @@ pub type Index = u64;
 /// A hash of some data used by the chain.
 pub type Hash = primitives::H256;
 
-/// Alias to 512-bit hash when used in the context of a signature on the chain.
-pub type Signature = runtime_primitives::Ed25519Signature;
+/// The signature of an extrinsic on the chain: accounts may sign with any of ed25519, sr25519 and
+/// ECDSA.
+pub type Signature = runtime_primitives::MultiSignature;
 
 /// A timestamp: seconds since the unix epoch.
 pub type Timestamp = u64;
@@ -83,12 +84,3 @@ pub type BlockId = generic::BlockId<Block>;
 #[derive(PartialEq, Eq, Clone, Default, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
 pub struct UncheckedExtrinsic(#[cfg_attr(feature = "std", serde(with="bytes"))] pub Vec<u8>);
-///
-/// Inherent data to include in a block.
-#[derive(Encode, Decode)]
-pub struct InherentData {
-	/// Current timestamp.
-	pub timestamp: Timestamp,
-	/// Indices of offline validators.
-	pub offline_indices: Vec<u32>,
-}
@@ -63,29 +63,26 @@ mod checked_block;
 use rstd::prelude::*;
 use substrate_primitives::u32_trait::{_2, _4};
 use codec::{Encode, Decode, Input};
-use node_primitives::{AccountId, AccountIndex, Balance, BlockNumber, Hash, Index, SessionKey, Signature, InherentData};
+use node_primitives::{AccountId, AccountIndex, Balance, BlockNumber, Hash, Index, SessionKey, Signature};
 use runtime_primitives::generic;
 use runtime_primitives::traits::{Convert, BlakeTwo256, DigestItem};
 use version::RuntimeVersion;
+use srml_support::Get;
 use council::{motions as council_motions, voting as council_voting};
 
 #[cfg(any(feature = "std", test))]
 pub use runtime_primitives::BuildStorage;
 pub use consensus::Call as ConsensusCall;
 pub use timestamp::Call as TimestampCall;
-pub use runtime_primitives::Permill;
+pub use runtime_primitives::{Permill, ApplyError};
+pub use srml_support::dispatch::{DispatchClass, GetDispatchInfo};
+pub use srml_support::inherent::{InherentData, ProvideInherent};
 #[cfg(any(feature = "std", test))]
 pub use checked_block::CheckedBlock;
 
 const TIMESTAMP_SET_POSITION: u32 = 0;
 const NOTE_OFFLINE_POSITION: u32 = 1;
 
-// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
-#[derive(Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
-/// Runtime type used to collate and parameterize the various modules.
-pub struct Runtime;
-
 /// Runtime version.
 pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: ver_str!("node"),
@@ -95,6 +92,15 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	impl_version: 0,
 };
 
+/// The `spec_version` of `VERSION`, for the system module.
+pub struct SpecVersion;
+
+impl Get<u32> for SpecVersion {
+	fn get() -> u32 {
+		VERSION.spec_version
+	}
+}
+
 impl system::Trait for Runtime {
 	type Origin = Origin;
 	type Index = Index;
@@ -105,11 +111,10 @@ impl system::Trait for Runtime {
 	type AccountId = AccountId;
 	type Header = generic::Header<BlockNumber, BlakeTwo256, Log>;
 	type Event = Event;
+	type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+	type SpecVersion = SpecVersion;
 }
 
-/// System module for this concrete runtime.
-pub type System = system::Module<Runtime>;
-
 impl balances::Trait for Runtime {
 	type Balance = Balance;
 	type AccountIndex = AccountIndex;
@@ -118,9 +123,6 @@ impl balances::Trait for Runtime {
 	type Event = Event;
 }
 
-/// Staking module for this concrete runtime.
-pub type Balances = balances::Module<Runtime>;
-
 impl consensus::Trait for Runtime {
 	const NOTE_OFFLINE_POSITION: u32 = NOTE_OFFLINE_POSITION;
 	type Log = Log;
@@ -128,17 +130,11 @@ impl consensus::Trait for Runtime {
 	type OnOfflineValidator = Staking;
 }
 
-/// Consensus module for this concrete runtime.
-pub type Consensus = consensus::Module<Runtime>;
-
 impl timestamp::Trait for Runtime {
 	const TIMESTAMP_SET_POSITION: u32 = TIMESTAMP_SET_POSITION;
 	type Moment = u64;
 }
 
-/// Timestamp module for this concrete runtime.
-pub type Timestamp = timestamp::Module<Runtime>;
-
 /// Session key conversion.
 pub struct SessionKeyConversion;
 impl Convert<AccountId, SessionKey> for SessionKeyConversion {
@@ -153,153 +149,68 @@ impl session::Trait for Runtime {
 	type Event = Event;
 }
 
-/// Session module for this concrete runtime.
-pub type Session = session::Module<Runtime>;
-
 impl staking::Trait for Runtime {
 	type OnRewardMinted = Treasury;
+	type Reward = Balances;
+	type Slash = Treasury;
 	type Event = Event;
 }
 
-/// Staking module for this concrete runtime.
-pub type Staking = staking::Module<Runtime>;
-
 impl democracy::Trait for Runtime {
 	type Proposal = Call;
 	type Event = Event;
 }
 
-/// Democracy module for this concrete runtime.
-pub type Democracy = democracy::Module<Runtime>;
-
 impl council::Trait for Runtime {
 	type Event = Event;
 }
 
-/// Council module for this concrete runtime.
-pub type Council = council::Module<Runtime>;
-
 impl council::voting::Trait for Runtime {
 	type Event = Event;
 }
 
-/// Council voting module for this concrete runtime.
-pub type CouncilVoting = council::voting::Module<Runtime>;
-
 impl council::motions::Trait for Runtime {
 	type Origin = Origin;
 	type Proposal = Call;
 	type Event = Event;
 }
 
-/// Council motions module for this concrete runtime.
-pub type CouncilMotions = council_motions::Module<Runtime>;
-
 impl treasury::Trait for Runtime {
 	type ApproveOrigin = council_motions::EnsureMembers<_4>;
 	type RejectOrigin = council_motions::EnsureMembers<_2>;
 	type Event = Event;
 }
 
-/// Treasury module for this concrete runtime.
-pub type Treasury = treasury::Module<Runtime>;
-
 impl contract::Trait for Runtime {
 	type Gas = u64;
 	type DetermineContractAddress = contract::SimpleAddressDeterminator<Runtime>;
 }
 
-/// Contract module for this concrete runtime.
-pub type Contract = contract::Module<Runtime>;
-
-impl_outer_event! {
-	pub enum Event for Runtime {
-		//consensus,
-		balances<T>,
-		//timetstamp,
-		session<T>,
-		staking<T>,
-		democracy<T>,
-		council<T>,
-		council_voting<T>,
-		council_motions<T>,
-		treasury<T>,
-	}
-}
-
-impl_outer_log! {
-	pub enum Log(InternalLog: DigestItem<SessionKey>) for Runtime {
-		consensus(AuthoritiesChange)
+construct_runtime!(
+	/// Runtime type used to collate and parameterize the various modules.
+	pub enum Runtime with Log(InternalLog: DigestItem<SessionKey>) {
+		System: system::{Module, Storage, Config, Event},
+		Consensus: consensus::{Module, Call, Storage, Config, Log(AuthoritiesChange)},
+		Balances: balances::{Module, Call, Storage, Config, Event<T>},
+		Timestamp: timestamp::{Module, Call, Storage, Config},
+		Session: session::{Module, Call, Storage, Config, Event<T>},
+		Staking: staking::{Module, Call, Storage, Config, Event<T>},
+		Democracy: democracy::{Module, Call, Storage, Config, Event<T>},
+		Council: council::{Module, Call, Storage, Config, Event<T>},
+		CouncilVoting: council_voting::{Module, Call, Storage, Event<T>},
+		CouncilMotions: council_motions::{Module, Call, Storage, Event<T>, Origin},
+		Treasury: treasury::{Module, Call, Storage, Config, Event<T>},
+		Contract: contract::{Module, Call, Storage, Config},
 	}
-}
-
-impl_outer_origin! {
-	pub enum Origin for Runtime {
-		council_motions
-	}
-}
+);
 
-impl_outer_dispatch! {
-	pub enum Call where origin: Origin {
-		Consensus,
-		Balances,
+impl_outer_inherent! {
+	for Runtime where Call = Call {
 		Timestamp,
-		Session,
-		Staking,
-		Democracy,
-		Council,
-		CouncilVoting,
-		CouncilMotions,
-		Treasury,
-		Contract,
-	}
-}
-
-impl_outer_config! {
-	pub struct GenesisConfig for Runtime {
-		SystemConfig => system,
-		ConsensusConfig => consensus,
-		ContractConfig => contract,
-		BalancesConfig => balances,
-		TimestampConfig => timestamp,
-		SessionConfig => session,
-		StakingConfig => staking,
-		DemocracyConfig => democracy,
-		CouncilConfig => council,
-		TreasuryConfig => treasury,
+		Consensus,
 	}
 }
 
-type AllModules = (
-	Consensus,
-	Balances,
-	Timestamp,
-	Session,
-	Staking,
-	Democracy,
-	Council,
-	CouncilVoting,
-	CouncilMotions,
-	Treasury,
-	Contract,
-);
-
-impl_json_metadata!(
-	for Runtime with modules
-		system::Module with Storage,
-		consensus::Module with Storage,
-		balances::Module with Storage,
-		timestamp::Module with Storage,
-		session::Module with Storage,
-		staking::Module with Storage,
-		democracy::Module with Storage,
-		council::Module with Storage,
-		council_voting::Module with Storage,
-		council_motions::Module with Storage,
-		treasury::Module with Storage,
-		contract::Module with Storage,
-);
-
 impl DigestItem for Log {
 	type AuthorityId = SessionKey;
 
@@ -320,23 +231,44 @@ pub type Header = generic::Header<BlockNumber, BlakeTwo256, Log>;
 pub type Block = generic::Block<Header, UncheckedExtrinsic>;
 /// BlockId type as expected by this runtime.
 pub type BlockId = generic::BlockId<Block>;
+/// The signed extensions of the extrinsics: the era, the nonce, the weight and the fees.
+pub type SignedExtra = (
+	system::CheckEra<Runtime>,
+	system::CheckNonce<Runtime>,
+	system::CheckWeight<Runtime>,
+	balances::TakeFees<Runtime>,
+);
 /// Unchecked extrinsic type as expected by this runtime.
-pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Index, Call, Signature>;
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
-pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Index, Call>;
+pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive = executive::Executive<Runtime, Block, Balances, Balances, AllModules>;
+pub type Executive = executive::Executive<Runtime, Block, Balances, AllModules>;
+
+/// The signed extensions of an extrinsic signed with the nonce `index`, valid in any block.
+pub fn signed_extra(index: Index) -> SignedExtra {
+	(
+		system::CheckEra::immortal(),
+		system::CheckNonce(index),
+		system::CheckWeight::new(),
+		balances::TakeFees::new(),
+	)
+}
 
 pub mod api {
 	impl_stubs!(
 		version => |()| super::VERSION,
-		json_metadata => |()| super::Runtime::json_metadata(),
+		Metadata_metadata => |()| super::Runtime::metadata(),
+		json_metadata => |()| super::Runtime::metadata(),
 		authorities => |()| super::Consensus::authorities(),
 		initialise_block => |header| super::Executive::initialise_block(&header),
 		apply_extrinsic => |extrinsic| super::Executive::apply_extrinsic(extrinsic),
+		validate_transaction => |extrinsic| super::Executive::validate_transaction(extrinsic),
 		execute_block => |block| super::Executive::execute_block(block),
 		finalise_block => |()| super::Executive::finalise_block(),
+		offchain_worker => |number| super::Executive::offchain_worker(number),
 		inherent_extrinsics => |(inherent, spec_version)| super::inherent_extrinsics(inherent, spec_version),
+		check_inherents => |(block, inherent)| super::check_inherents(block, inherent),
 		validator_count => |()| super::Session::validator_count(),
 		validators => |()| super::Session::validators(),
 		timestamp => |()| super::Timestamp::get(),
@@ -348,21 +280,11 @@ pub mod api {
 
 /// Produces the list of inherent extrinsics.
 fn inherent_extrinsics(data: InherentData, _spec_version: u32) -> Vec<UncheckedExtrinsic> {
-	let make_inherent = |function| UncheckedExtrinsic {
-		signature: Default::default(),
-		function,
-		index: 0,
-	};
-
-	let mut inherent = vec![
-		make_inherent(Call::Timestamp(TimestampCall::set(data.timestamp))),
-	];
-
-	if !data.offline_indices.is_empty() {
-		inherent.push(make_inherent(
-				Call::Consensus(ConsensusCall::note_offline(data.offline_indices))
-		));
-	}
+	Runtime::create_inherents(&data).into_iter().map(UncheckedExtrinsic::new_unsigned).collect()
+}
 
-	inherent
+/// Checks the inherent extrinsics of a block against the local inherent data.
+fn check_inherents(block: Block, data: InherentData) -> Result<(), &'static str> {
+	let inherents = block.extrinsics.iter().filter(|xt| !xt.is_signed()).map(|xt| &xt.function);
+	Runtime::check_inherents(inherents, &data)
 }
@@ -16,7 +16,8 @@
 
 //! Substrate chain configurations.
 
-use primitives::{AuthorityId, ed25519};
+use primitives::AuthorityId;
+use keyring::Keyring;
 use node_runtime::{GenesisConfig, ConsensusConfig, CouncilConfig, DemocracyConfig,
 	SessionConfig, StakingConfig, TimestampConfig, BalancesConfig, TreasuryConfig,
 	ContractConfig, Permill};
@@ -122,14 +123,11 @@ pub fn staging_testnet_config() -> ChainSpec<GenesisConfig> {
 }
 
 fn testnet_genesis(initial_authorities: Vec<AuthorityId>) -> GenesisConfig {
-	let endowed_accounts = vec![
-		ed25519::Pair::from_seed(b"Alice                           ").public().0.into(),
-		ed25519::Pair::from_seed(b"Bob                             ").public().0.into(),
-		ed25519::Pair::from_seed(b"Charlie                         ").public().0.into(),
-		ed25519::Pair::from_seed(b"Dave                            ").public().0.into(),
-		ed25519::Pair::from_seed(b"Eve                             ").public().0.into(),
-		ed25519::Pair::from_seed(b"Ferdie                          ").public().0.into(),
-	];
+	// accounts may sign with either key of a development account, which are different accounts.
+	let endowed_accounts = [Keyring::Alice, Keyring::Bob, Keyring::Charlie, Keyring::Dave, Keyring::Eve, Keyring::Ferdie]
+		.iter()
+		.flat_map(|&k| vec![k.to_account_id(), k.to_sr25519_account_id()])
+		.collect::<Vec<_>>();
 	GenesisConfig {
 		consensus: Some(ConsensusConfig {
 			code: include_bytes!("../../runtime/wasm/target/wasm32-unknown-unknown/release/node_runtime.compact.wasm").to_vec(),
@@ -204,7 +202,7 @@ fn testnet_genesis(initial_authorities: Vec<AuthorityId>) -> GenesisConfig {
 
 fn development_config_genesis() -> GenesisConfig {
 	testnet_genesis(vec![
-		ed25519::Pair::from_seed(b"Alice                           ").public().into(),
+		Keyring::Alice.to_authority_id(),
 	])
 }
 
@@ -215,8 +213,8 @@ pub fn development_config() -> ChainSpec<GenesisConfig> {
 
 fn local_testnet_genesis() -> GenesisConfig {
 	testnet_genesis(vec![
-		ed25519::Pair::from_seed(b"Alice                           ").public().into(),
-		ed25519::Pair::from_seed(b"Bob                             ").public().into(),
+		Keyring::Alice.to_authority_id(),
+		Keyring::Bob.to_authority_id(),
 	])
 }
 
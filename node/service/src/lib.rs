@@ -26,10 +26,12 @@ extern crate node_network;
 extern crate node_transaction_pool as transaction_pool;
 extern crate node_consensus as consensus;
 extern crate substrate_primitives as primitives;
+extern crate substrate_keyring as keyring;
 extern crate substrate_network as network;
 extern crate substrate_client as client;
 extern crate substrate_service as service;
 extern crate tokio;
+extern crate futures;
 
 #[macro_use]
 extern crate log;
@@ -42,9 +44,10 @@ use std::sync::Arc;
 
 use transaction_pool::TransactionPool;
 use node_api::Api;
-use node_primitives::{Block, Hash};
+use node_primitives::{Block, BlockId, Hash};
 use node_runtime::GenesisConfig;
-use client::Client;
+use client::{Client, BlockchainEvents};
+use futures::Stream;
 use node_network::{Protocol as DemoProtocol, consensus::ConsensusNetwork};
 use tokio::runtime::TaskExecutor;
 use service::FactoryFullConfiguration;
@@ -167,6 +170,21 @@ pub fn new_full(config: Configuration, executor: TaskExecutor)
 {
 	let is_validator = (config.roles & Roles::AUTHORITY) == Roles::AUTHORITY;
 	let service = service::Service::<FullComponents<Factory>>::new(config, executor.clone())?;
+	// Run the offchain workers on top of every new best block
+	{
+		let client = service.client();
+		let workers = service.client().import_notification_stream()
+			.for_each(move |notification| {
+				if notification.is_new_best {
+					let number = notification.header.number;
+					if let Err(e) = client.offchain_worker(&BlockId::hash(notification.hash), number) {
+						warn!("Offchain workers failed at block {}: {:?}", number, e);
+					}
+				}
+				Ok(())
+			});
+		executor.spawn(workers);
+	}
 	// Spin consensus service if configured
 	let consensus = if is_validator {
 		// Load the first available key
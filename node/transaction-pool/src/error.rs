@@ -17,7 +17,7 @@
 use extrinsic_pool;
 use node_api;
 use primitives::Hash;
-use runtime::{Address, UncheckedExtrinsic};
+use runtime::{Address, UncheckedExtrinsic, ApplyError};
 
 error_chain! {
 	links {
@@ -40,6 +40,11 @@ error_chain! {
 			description("Transaction had bad signature."),
 			display("Transaction had bad signature: {}", e),
 		}
+		/// Attempted to queue a transaction failing the checks of its signed extensions.
+		Invalid(e: ApplyError) {
+			description("Transaction is invalid."),
+			display("Transaction is invalid: {:?}", e),
+		}
 		/// Attempted to queue a transaction that is already in the pool.
 		AlreadyImported(hash: Hash) {
 			description("Transaction is already in the pool."),
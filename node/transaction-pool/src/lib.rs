@@ -45,7 +45,7 @@ use codec::{Decode, Encode};
 use extrinsic_pool::{Readiness, scoring::{Change, Choice}, VerifiedFor, ExtrinsicFor};
 use node_api::Api;
 use primitives::{AccountId, BlockId, Block, Hash, Index};
-use runtime::{Address, UncheckedExtrinsic, RawAddress};
+use runtime::{Address, UncheckedExtrinsic, RawAddress, DispatchClass, GetDispatchInfo, ApplyError};
 use sr_primitives::traits::{Bounded, Checkable, Hash as HashT, BlakeTwo256};
 
 pub use extrinsic_pool::{Options, Status, LightStatus, VerifiedTransaction as VerifiedTransactionOps};
@@ -69,6 +69,8 @@ pub struct VerifiedTransaction {
 	pub sender: AccountId,
 	/// Transaction index.
 	pub index: Index,
+	/// Whether the call is operational, e.g. a misbehaviour report.
+	pub operational: bool,
 	encoded_size: usize,
 }
 
@@ -135,7 +137,7 @@ impl<A> extrinsic_pool::ChainApi for ChainApi<A> where
 	type Score = u64;
 	type Event = ();
 
-	fn verify_transaction(&self, _at: &BlockId, xt: &ExtrinsicFor<Self>) -> Result<Self::VEx> {
+	fn verify_transaction(&self, at: &BlockId, xt: &ExtrinsicFor<Self>) -> Result<Self::VEx> {
 		let encoded = xt.encode();
 		let uxt = UncheckedExtrinsic::decode(&mut encoded.as_slice()).ok_or_else(|| ErrorKind::InvalidExtrinsicFormat)?;
 		if !uxt.is_signed() {
@@ -154,8 +156,15 @@ impl<A> extrinsic_pool::ChainApi for ChainApi<A> where
 				RawAddress::Index(_) => Err("Index based addresses are not supported".into()),// TODO: Make index addressing optional in substrate
 			}
 		})?;
-		let sender = checked.signed.expect("Only signed extrinsics are allowed at this point");
-
+		let (sender, extra) = checked.signed.expect("Only signed extrinsics are allowed at this point");
+		let index = (extra.1).0;
+		let operational = checked.function.get_dispatch_info().class == DispatchClass::Operational;
+
+		// A transaction with a nonce ahead of its sender's is kept until it is ready.
+		match self.api.validate_transaction(at, xt)? {
+			Ok(()) | Err(ApplyError::Future) => {},
+			Err(e) => bail!(ErrorKind::Invalid(e)),
+		}
 
 		if encoded_size < 1024 {
 			debug!(target: "transaction-pool", "Transaction verified: {} => {:?}", hash, uxt);
@@ -164,8 +173,9 @@ impl<A> extrinsic_pool::ChainApi for ChainApi<A> where
 		}
 
 		Ok(VerifiedTransaction {
-			index: checked.index,
+			index,
 			sender,
+			operational,
 			hash,
 			encoded_size,
 		})
@@ -222,9 +232,9 @@ impl<A> extrinsic_pool::ChainApi for ChainApi<A> where
 		_change: Change<()>
 	) {
 		for i in 0..xts.len() {
-			// all the same score since there are no fees.
-			// TODO: prioritize things like misbehavior or fishermen reports
-			scores[i] = 1;
+			// operational calls, e.g. misbehaviour reports, go first; the others all have the same
+			// score since there are no fees.
+			scores[i] = if xts[i].verified.operational { 2 } else { 1 };
 		}
 	}
 
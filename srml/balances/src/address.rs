@@ -19,6 +19,7 @@
 #[cfg(feature = "std")]
 use std::fmt;
 use super::{Member, Decode, Encode, As, Input, Output};
+use runtime_support::type_info::{self, TypeDef, TypeInfo, TypeRegistry};
 
 /// A vetted and verified extrinsic from the external world.
 #[derive(PartialEq, Eq, Clone)]
@@ -101,6 +102,23 @@ impl<AccountId, AccountIndex> Encode for Address<AccountId, AccountIndex> where
 	}
 }
 
+// The encoding of an address, its first byte telling the size of the index or that it is an
+// account ID, isn't one the registry describes: clients know it by the name of the type.
+impl<AccountId, AccountIndex> TypeInfo for Address<AccountId, AccountIndex> where
+	AccountId: Member + TypeInfo,
+	AccountIndex: Member + TypeInfo,
+{
+	fn type_name() -> type_info::String {
+		type_info::generic_name("Address", &[AccountId::type_name(), AccountIndex::type_name()])
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		registry.register::<AccountId>();
+		registry.register::<AccountIndex>();
+		TypeDef::Primitive
+	}
+}
+
 impl<AccountId, AccountIndex> Default for Address<AccountId, AccountIndex> where
 	AccountId: Member + Default,
 	AccountIndex: Member,
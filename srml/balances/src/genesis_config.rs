@@ -61,23 +61,23 @@ impl<T: Trait> primitives::BuildStorage for GenesisConfig<T> {
 		let total_issuance: T::Balance = self.balances.iter().fold(Zero::zero(), |acc, &(_, n)| acc + n);
 
 		let mut r: runtime_io::TestExternalities<Blake2Hasher> = map![
-			Self::hash(<NextEnumSet<T>>::key()).to_vec() => T::AccountIndex::sa(self.balances.len() / ENUM_SET_SIZE).encode(),
-			Self::hash(<TransactionBaseFee<T>>::key()).to_vec() => self.transaction_base_fee.encode(),
-			Self::hash(<TransactionByteFee<T>>::key()).to_vec() => self.transaction_byte_fee.encode(),
-			Self::hash(<TransferFee<T>>::key()).to_vec() => self.transfer_fee.encode(),
-			Self::hash(<CreationFee<T>>::key()).to_vec() => self.creation_fee.encode(),
-			Self::hash(<ExistentialDeposit<T>>::key()).to_vec() => self.existential_deposit.encode(),
-			Self::hash(<ReclaimRebate<T>>::key()).to_vec() => self.reclaim_rebate.encode(),
-			Self::hash(<TotalIssuance<T>>::key()).to_vec() => total_issuance.encode()
+			<NextEnumSet<T>>::key().to_vec() => T::AccountIndex::sa(self.balances.len() / ENUM_SET_SIZE).encode(),
+			<TransactionBaseFee<T>>::key().to_vec() => self.transaction_base_fee.encode(),
+			<TransactionByteFee<T>>::key().to_vec() => self.transaction_byte_fee.encode(),
+			<TransferFee<T>>::key().to_vec() => self.transfer_fee.encode(),
+			<CreationFee<T>>::key().to_vec() => self.creation_fee.encode(),
+			<ExistentialDeposit<T>>::key().to_vec() => self.existential_deposit.encode(),
+			<ReclaimRebate<T>>::key().to_vec() => self.reclaim_rebate.encode(),
+			<TotalIssuance<T>>::key().to_vec() => total_issuance.encode()
 		];
 
 		let ids: Vec<_> = self.balances.iter().map(|x| x.0.clone()).collect();
 		for i in 0..(ids.len() + ENUM_SET_SIZE - 1) / ENUM_SET_SIZE {
-			r.insert(Self::hash(&<EnumSet<T>>::key_for(T::AccountIndex::sa(i))).to_vec(),
+			r.insert(<EnumSet<T>>::key_for(T::AccountIndex::sa(i)),
 				ids[i * ENUM_SET_SIZE..ids.len().min((i + 1) * ENUM_SET_SIZE)].to_owned().encode());
 		}
 		for (who, value) in self.balances.into_iter() {
-			r.insert(Self::hash(&<FreeBalance<T>>::key_for(who)).to_vec(), value.encode());
+			r.insert(<FreeBalance<T>>::key_for(who), value.encode());
 		}
 		Ok(r.into())
 	}
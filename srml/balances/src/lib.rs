@@ -42,10 +42,13 @@ extern crate srml_system as system;
 
 use rstd::prelude::*;
 use rstd::{cmp, result};
+use rstd::marker::PhantomData;
 use codec::{Encode, Decode, Codec, Input, Output};
-use runtime_support::{StorageValue, StorageMap, Parameter};
-use runtime_support::dispatch::Result;
-use primitives::traits::{Zero, One, SimpleArithmetic, OnFinalise, MakePayment,
+use runtime_support::{StorageValue, StorageMap, Parameter, TypeInfo};
+use runtime_support::imbalance::{PositiveImbalance, NegativeImbalance, OnUnbalanced};
+use runtime_support::dispatch::{Result, GetDispatchInfo, HasCompact};
+use primitives::ApplyError;
+use primitives::traits::{Zero, One, SimpleArithmetic, MakePayment, SignedExtension,
 	As, Lookup, Member, CheckedAdd, CheckedSub};
 use address::Address as RawAddress;
 use system::ensure_signed;
@@ -111,7 +114,8 @@ impl<AccountId> EnsureAccountLiquid<AccountId> for () {
 
 pub trait Trait: system::Trait {
 	/// The balance of an account.
-	type Balance: Parameter + SimpleArithmetic + Codec + Default + Copy + As<Self::AccountIndex> + As<usize> + As<u64>;
+	type Balance: Parameter + SimpleArithmetic + Codec + Default + Copy + As<Self::AccountIndex> + As<usize> + As<u64>
+		+ HasCompact;
 	/// Type used for storing an account's index; implies the maximum number of accounts the system
 	/// can hold.
 	type AccountIndex: Parameter + Member + Codec + SimpleArithmetic + As<u8> + As<u16> + As<u32> + As<u64> + As<usize> + Copy;
@@ -130,8 +134,12 @@ pub trait Trait: system::Trait {
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-		fn transfer(origin, dest: RawAddress<T::AccountId, T::AccountIndex>, value: T::Balance) -> Result;
-		fn set_balance(who: RawAddress<T::AccountId, T::AccountIndex>, free: T::Balance, reserved: T::Balance) -> Result;
+		fn transfer(origin, dest: RawAddress<T::AccountId, T::AccountIndex>, #[compact] value: T::Balance) -> Result;
+		fn set_balance(
+			who: RawAddress<T::AccountId, T::AccountIndex>,
+			#[compact] free: T::Balance,
+			#[compact] reserved: T::Balance
+		) -> Result;
 	}
 }
 
@@ -207,7 +215,7 @@ decl_storage! {
 
 /// Whatever happened about the hint given when creating the new account.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
-#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy)]
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, TypeInfo)]
 pub enum NewAccountOutcome {
 	NoHint,
 	GoodHint,
@@ -415,29 +423,31 @@ impl<T: Trait> Module<T> {
 	/// free balance. This function cannot fail.
 	///
 	/// As much funds up to `value` will be deducted as possible. If this is less than `value`,
-	/// then `Some(remaining)` will be returned. Full completion is given by `None`.
-	pub fn slash(who: &T::AccountId, value: T::Balance) -> Option<T::Balance> {
+	/// then `Some(remaining)` will be returned along with the funds deducted. Full completion is
+	/// given by `None`. The funds deducted must be handed to an `OnUnbalanced` handler.
+	pub fn slash(who: &T::AccountId, value: T::Balance) -> (NegativeImbalance<T::Balance>, Option<T::Balance>) {
 		let free_balance = Self::free_balance(who);
 		let free_slash = cmp::min(free_balance, value);
 		Self::set_free_balance(who, free_balance - free_slash);
-		Self::decrease_total_stake_by(free_slash);
+		let imbalance = NegativeImbalance::new(free_slash);
 		if free_slash < value {
-			Self::slash_reserved(who, value - free_slash)
+			let (reserved_imbalance, remaining) = Self::slash_reserved(who, value - free_slash);
+			(imbalance.merge(reserved_imbalance), remaining)
 		} else {
-			None
+			(imbalance, None)
 		}
 	}
 
-	/// Adds up to `value` to the free balance of `who`.
+	/// Adds up to `value` to the free balance of `who`, giving the funds created, which must be
+	/// handed to an `OnUnbalanced` handler.
 	///
 	/// If `who` doesn't exist, nothing is done and an Err returned.
-	pub fn reward(who: &T::AccountId, value: T::Balance) -> Result {
+	pub fn reward(who: &T::AccountId, value: T::Balance) -> result::Result<PositiveImbalance<T::Balance>, &'static str> {
 		if Self::total_balance(who).is_zero() {
 			return Err("beneficiary account must pre-exist");
 		}
 		Self::set_free_balance(who, Self::free_balance(who) + value);
-		Self::increase_total_stake_by(value);
-		Ok(())
+		Ok(PositiveImbalance::new(value))
 	}
 
 	/// Moves `value` from balance to reserved balance.
@@ -475,17 +485,18 @@ impl<T: Trait> Module<T> {
 	/// Deducts up to `value` from reserved balance of `who`. This function cannot fail.
 	///
 	/// As much funds up to `value` will be deducted as possible. If this is less than `value`,
-	/// then `Some(remaining)` will be returned. Full completion is given by `None`.
-	pub fn slash_reserved(who: &T::AccountId, value: T::Balance) -> Option<T::Balance> {
+	/// then `Some(remaining)` will be returned along with the funds deducted. Full completion is
+	/// given by `None`. The funds deducted must be handed to an `OnUnbalanced` handler.
+	pub fn slash_reserved(who: &T::AccountId, value: T::Balance) -> (NegativeImbalance<T::Balance>, Option<T::Balance>) {
 		let b = Self::reserved_balance(who);
 		let slash = cmp::min(b, value);
 		Self::set_reserved_balance(who, b - slash);
-		Self::decrease_total_stake_by(slash);
-		if value == slash {
+		let remaining = if value == slash {
 			None
 		} else {
 			Some(value - slash)
-		}
+		};
+		(NegativeImbalance::new(slash), remaining)
 	}
 
 	/// Moves up to `value` from reserved balance of account `slashed` to free balance of account
@@ -632,11 +643,6 @@ impl<T: Trait> Module<T> {
 	}
 }
 
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
-	fn on_finalise(_n: T::BlockNumber) {
-	}
-}
-
 impl<T: Trait> Lookup for Module<T> {
 	type Source = address::Address<T::AccountId, T::AccountIndex>;
 	type Target = T::AccountId;
@@ -648,15 +654,78 @@ impl<T: Trait> Lookup for Module<T> {
 	}
 }
 
+/// Funds minted: the total issuance is increased by them.
+impl<T: Trait> OnUnbalanced<PositiveImbalance<T::Balance>> for Module<T> {
+	fn on_unbalanced(amount: PositiveImbalance<T::Balance>) {
+		Self::increase_total_stake_by(amount.peek());
+	}
+}
+
+/// Funds burnt: the total issuance is decreased by them.
+impl<T: Trait> OnUnbalanced<NegativeImbalance<T::Balance>> for Module<T> {
+	fn on_unbalanced(amount: NegativeImbalance<T::Balance>) {
+		Self::decrease_total_stake_by(amount.peek());
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The fee of a transaction of encoded length `encoded_len` bytes.
+	fn transaction_fee(encoded_len: usize) -> T::Balance {
+		Self::transaction_base_fee() + Self::transaction_byte_fee() * <T::Balance as As<u64>>::sa(encoded_len as u64)
+	}
+
+	/// Whether `who` can pay the fee of a transaction of encoded length `encoded_len` bytes.
+	fn can_pay_fee(who: &T::AccountId, encoded_len: usize) -> bool {
+		Self::free_balance(who) >= Self::transaction_fee(encoded_len) + Self::existential_deposit()
+	}
+}
+
 impl<T: Trait> MakePayment<T::AccountId> for Module<T> {
 	fn make_payment(transactor: &T::AccountId, encoded_len: usize) -> Result {
-		let b = Self::free_balance(transactor);
-		let transaction_fee = Self::transaction_base_fee() + Self::transaction_byte_fee() * <T::Balance as As<u64>>::sa(encoded_len as u64);
-		if b < transaction_fee + Self::existential_deposit() {
+		if !Self::can_pay_fee(transactor, encoded_len) {
 			return Err("not enough funds for transaction fee");
 		}
-		Self::set_free_balance(transactor, b - transaction_fee);
+		let transaction_fee = Self::transaction_fee(encoded_len);
+		Self::set_free_balance(transactor, Self::free_balance(transactor) - transaction_fee);
 		Self::decrease_total_stake_by(transaction_fee);
 		Ok(())
 	}
 }
+
+/// Signed extension paying the fee of the extrinsic from the free balance of its sender, unless
+/// its call is exempt.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct TakeFees<T: Trait>(PhantomData<T>);
+
+impl<T: Trait> TakeFees<T> {
+	pub fn new() -> Self {
+		TakeFees(PhantomData)
+	}
+}
+
+impl<T: Trait> Encode for TakeFees<T> {
+	fn encode_to<W: Output>(&self, _dest: &mut W) {}
+}
+
+impl<T: Trait> Decode for TakeFees<T> {
+	fn decode<I: Input>(_input: &mut I) -> Option<Self> {
+		Some(TakeFees::new())
+	}
+}
+
+impl<T: Trait, Call: GetDispatchInfo> SignedExtension<T::AccountId, Call> for TakeFees<T> {
+	fn validate(&self, who: &T::AccountId, call: &Call, len: usize) -> result::Result<(), ApplyError> {
+		if call.get_dispatch_info().pays_fee && !<Module<T>>::can_pay_fee(who, len) {
+			return Err(ApplyError::CantPay);
+		}
+		Ok(())
+	}
+
+	fn pre_dispatch(&self, who: &T::AccountId, call: &Call, len: usize) -> result::Result<(), ApplyError> {
+		if !call.get_dispatch_info().pays_fee {
+			return Ok(());
+		}
+		<Module<T> as MakePayment<T::AccountId>>::make_payment(who, len).map_err(|_| ApplyError::CantPay)
+	}
+}
@@ -41,6 +41,8 @@ impl system::Trait for Runtime {
 	type AccountId = u64;
 	type Header = Header;
 	type Event = ();
+	type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+	type SpecVersion = ();
 }
 impl Trait for Runtime {
 	type Balance = u64;
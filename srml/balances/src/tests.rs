@@ -26,9 +26,13 @@ use mock::{Balances, System, Runtime, new_test_ext, new_test_ext2};
 fn reward_should_work() {
 	with_externalities(&mut new_test_ext(0, true), || {
 		assert_eq!(Balances::total_balance(&1), 10);
-		assert_ok!(Balances::reward(&1, 10));
+		let imbalance = Balances::reward(&1, 10).unwrap();
+		assert_eq!(imbalance.peek(), 10);
 		assert_eq!(Balances::total_balance(&1), 20);
+		assert_eq!(<TotalIssuance<Runtime>>::get(), 100);
+		Balances::on_unbalanced(imbalance);
 		assert_eq!(<TotalIssuance<Runtime>>::get(), 110);
+		assert!(Balances::reward(&9, 10).is_err());
 	});
 }
 
@@ -152,7 +156,9 @@ fn reserved_balance_should_prevent_reclaim_count() {
 		assert_eq!(Balances::lookup_index(1), Some(2));					// but fails.
 		assert_eq!(System::account_nonce(&2), 1);
 
-		assert_eq!(Balances::slash(&2, 256 * 18 + 2), None);				// account 2 gets slashed
+		let (imbalance, remaining) = Balances::slash(&2, 256 * 18 + 2);	// account 2 gets slashed
+		assert_eq!(remaining, None);
+		Balances::on_unbalanced(imbalance);
 		assert_eq!(Balances::total_balance(&2), 0);						// "free" account deleted."
 		assert_eq!(System::account_nonce(&2), 0);
 
@@ -180,7 +186,9 @@ fn reserved_balance_should_prevent_reclaim_count2() {
 		assert_eq!(Balances::lookup_index(1), Some(2));					// but fails.
 		assert_eq!(System::account_nonce(&2), 1);
 
-		assert_eq!(Balances::slash(&2, 256 * 18 + 2), None);				// account 2 gets slashed
+		let (imbalance, remaining) = Balances::slash(&2, 256 * 18 + 2);	// account 2 gets slashed
+		assert_eq!(remaining, None);
+		Balances::on_unbalanced(imbalance);
 		assert_eq!(Balances::total_balance(&2), 0);						// "free" account deleted."
 		assert_eq!(System::account_nonce(&2), 0);
 
@@ -266,7 +274,9 @@ fn slashing_balance_should_work() {
 		Balances::set_free_balance(&1, 111);
 		Balances::increase_total_stake_by(111);
 		assert_ok!(Balances::reserve(&1, 69));
-		assert!(Balances::slash(&1, 69).is_none());
+		let (imbalance, remaining) = Balances::slash(&1, 69);
+		assert_eq!((imbalance.peek(), remaining), (69, None));
+		Balances::on_unbalanced(imbalance);
 		assert_eq!(Balances::free_balance(&1), 0);
 		assert_eq!(Balances::reserved_balance(&1), 42);
 		assert_eq!(<TotalIssuance<Runtime>>::get(), 44);
@@ -279,7 +289,9 @@ fn slashing_incomplete_balance_should_work() {
 		Balances::set_free_balance(&1, 42);
 		Balances::increase_total_stake_by(42);
 		assert_ok!(Balances::reserve(&1, 21));
-		assert!(Balances::slash(&1, 69).is_some());
+		let (imbalance, remaining) = Balances::slash(&1, 69);
+		assert_eq!((imbalance.peek(), remaining), (42, Some(27)));
+		Balances::on_unbalanced(imbalance);
 		assert_eq!(Balances::free_balance(&1), 0);
 		assert_eq!(Balances::reserved_balance(&1), 0);
 		assert_eq!(<TotalIssuance<Runtime>>::get(), 2);
@@ -303,7 +315,9 @@ fn slashing_reserved_balance_should_work() {
 		Balances::set_free_balance(&1, 111);
 		Balances::increase_total_stake_by(111);
 		assert_ok!(Balances::reserve(&1, 111));
-		assert!(Balances::slash_reserved(&1, 42).is_none());
+		let (imbalance, remaining) = Balances::slash_reserved(&1, 42);
+		assert_eq!((imbalance.peek(), remaining), (42, None));
+		Balances::on_unbalanced(imbalance);
 		assert_eq!(Balances::reserved_balance(&1), 69);
 		assert_eq!(Balances::free_balance(&1), 0);
 		assert_eq!(<TotalIssuance<Runtime>>::get(), 71);
@@ -316,7 +330,9 @@ fn slashing_incomplete_reserved_balance_should_work() {
 		Balances::set_free_balance(&1, 111);
 		Balances::increase_total_stake_by(111);
 		assert_ok!(Balances::reserve(&1, 42));
-		assert!(Balances::slash_reserved(&1, 69).is_some());
+		let (imbalance, remaining) = Balances::slash_reserved(&1, 69);
+		assert_eq!((imbalance.peek(), remaining), (42, Some(27)));
+		Balances::on_unbalanced(imbalance);
 		assert_eq!(Balances::free_balance(&1), 69);
 		assert_eq!(Balances::reserved_balance(&1), 0);
 		assert_eq!(<TotalIssuance<Runtime>>::get(), 71);
@@ -402,3 +418,12 @@ fn account_removal_on_free_too_low() {
 		assert_eq!(<TotalIssuance<Runtime>>::get(), 642);
 	});
 }
+
+#[test]
+fn transfer_value_is_compact_encoded() {
+	let call = Call::<Runtime>::transfer(RawAddress::Index(1), 69);
+	let encoded = call.encode();
+	// The index of `transfer`, the address of index 1 and the value on two bytes.
+	assert_eq!(encoded, vec![0, 1, 0x15, 0x01]);
+	assert_eq!(Call::<Runtime>::decode(&mut &encoded[..]), Some(call));
+}
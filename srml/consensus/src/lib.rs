@@ -44,19 +44,21 @@ extern crate substrate_primitives;
 use rstd::prelude::*;
 use runtime_support::{storage, Parameter};
 use runtime_support::dispatch::Result;
+use runtime_support::inherent::{InherentData, InherentIdentifier, ProvideInherent};
 use runtime_support::storage::StorageValue;
 use runtime_support::storage::unhashed::StorageVec;
-use primitives::traits::{MaybeSerializeDebug, OnFinalise, Member, DigestItem};
+use primitives::traits::{MaybeSerializeDebug, Member, DigestItem};
 use primitives::bft::MisbehaviorReport;
-use system::{ensure_signed, ensure_inherent};
+use system::ensure_signed;
 
+use substrate_primitives::storage::well_known_keys;
 #[cfg(any(feature = "std", test))]
 use substrate_primitives::Blake2Hasher;
 #[cfg(any(feature = "std", test))]
 use std::collections::HashMap;
 
-pub const AUTHORITY_AT: &'static [u8] = b":auth:";
-pub const AUTHORITY_COUNT: &'static [u8] = b":auth:len";
+pub const AUTHORITY_AT: &'static [u8] = well_known_keys::AUTHORITY_PREFIX;
+pub const AUTHORITY_COUNT: &'static [u8] = well_known_keys::AUTHORITY_COUNT;
 
 struct AuthorityStorageVec<S: codec::Codec + Default>(rstd::marker::PhantomData<S>);
 impl<S: codec::Codec + Default> StorageVec for AuthorityStorageVec<S> {
@@ -64,7 +66,11 @@ impl<S: codec::Codec + Default> StorageVec for AuthorityStorageVec<S> {
 	const PREFIX: &'static [u8] = AUTHORITY_AT;
 }
 
-pub const CODE: &'static [u8] = b":code";
+pub const CODE: &'static [u8] = well_known_keys::CODE;
+
+/// The identifier of the inherent data of this module, the indices of the validators reported
+/// offline.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"offlrep0";
 
 pub type KeyValue = (Vec<u8>, Vec<u8>);
 
@@ -129,11 +135,16 @@ decl_storage! {
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		#[operational]
+		#[pays_fee = no]
 		fn report_misbehavior(origin, report: MisbehaviorReport<T::Hash, T::BlockNumber>) -> Result;
-		fn note_offline(origin, offline_val_indices: Vec<u32>) -> Result;
+		#[require_none]
+		fn note_offline(offline_val_indices: Vec<u32>) -> Result;
 		fn remark(origin, remark: Vec<u8>) -> Result;
 		fn set_code(new: Vec<u8>) -> Result;
 		fn set_storage(items: Vec<KeyValue>) -> Result;
+
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
@@ -145,7 +156,7 @@ impl<T: Trait> Module<T> {
 
 	/// Set the new code.
 	fn set_code(new: Vec<u8>) -> Result {
-		storage::unhashed::put_raw(CODE, &new);
+		storage::well_known_keys::Code::put(&new);
 		Ok(())
 	}
 
@@ -167,8 +178,7 @@ impl<T: Trait> Module<T> {
 	/// Note the previous block's validator missed their opportunity to propose a block. This only comes in
 	/// if 2/3+1 of the validators agree that no proposal was submitted. It's only relevant
 	/// for the previous block.
-	fn note_offline(origin: T::Origin, offline_val_indices: Vec<u32>) -> Result {
-		ensure_inherent(origin)?;
+	fn note_offline(offline_val_indices: Vec<u32>) -> Result {
 		assert!(
 			<system::Module<T>>::extrinsic_index() == Some(T::NOTE_OFFLINE_POSITION),
 			"note_offline extrinsic must be at position {} in the block",
@@ -221,7 +231,7 @@ impl<T: Trait> Module<T> {
 }
 
 /// Finalization hook for the consensus module.
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+impl<T: Trait> Module<T> {
 	fn on_finalise(_n: T::BlockNumber) {
 		if let Some(_) = <OriginalAuthorities<T>>::take() {
 			// TODO: call Self::deposit_log
@@ -229,6 +239,24 @@ impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
 	}
 }
 
+impl<T: Trait> ProvideInherent for Module<T> {
+	type Call = Call<T>;
+
+	const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+	fn create_inherent(data: &InherentData) -> Option<Call<T>> {
+		data.get_data::<Vec<u32>>(&INHERENT_IDENTIFIER)
+			.ok()
+			.and_then(|indices| indices)
+			.and_then(|indices| if indices.is_empty() { None } else { Some(Call::note_offline(indices)) })
+	}
+
+	fn check_inherent(_call: &Call<T>, _data: &InherentData) -> Result {
+		// The reports are checked by the consensus of the node, which tracks the offline validators.
+		Ok(())
+	}
+}
+
 #[cfg(any(feature = "std", test))]
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
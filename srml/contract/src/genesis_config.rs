@@ -22,7 +22,7 @@ use {Trait, ContractFee, CallBaseFee, CreateBaseFee, GasPrice, MaxDepth, BlockGa
 
 use runtime_primitives;
 use runtime_primitives::traits::As;
-use runtime_io::{self, twox_128};
+use runtime_io;
 use runtime_support::StorageValue;
 use codec::Encode;
 use std::collections::HashMap;
@@ -56,12 +56,12 @@ impl<T: Trait> Default for GenesisConfig<T> {
 impl<T: Trait> runtime_primitives::BuildStorage for GenesisConfig<T> {
 	fn build_storage(self) -> ::std::result::Result<HashMap<Vec<u8>, Vec<u8>>, String> {
 		let r: runtime_io::TestExternalities<Blake2Hasher> = map![
-			twox_128(<ContractFee<T>>::key()).to_vec() => self.contract_fee.encode(),
-			twox_128(<CallBaseFee<T>>::key()).to_vec() => self.call_base_fee.encode(),
-			twox_128(<CreateBaseFee<T>>::key()).to_vec() => self.create_base_fee.encode(),
-			twox_128(<GasPrice<T>>::key()).to_vec() => self.gas_price.encode(),
-			twox_128(<MaxDepth<T>>::key()).to_vec() => self.max_depth.encode(),
-			twox_128(<BlockGasLimit<T>>::key()).to_vec() => self.block_gas_limit.encode()
+			<ContractFee<T>>::key().to_vec() => self.contract_fee.encode(),
+			<CallBaseFee<T>>::key().to_vec() => self.call_base_fee.encode(),
+			<CreateBaseFee<T>>::key().to_vec() => self.create_base_fee.encode(),
+			<GasPrice<T>>::key().to_vec() => self.gas_price.encode(),
+			<MaxDepth<T>>::key().to_vec() => self.max_depth.encode(),
+			<BlockGasLimit<T>>::key().to_vec() => self.block_gas_limit.encode()
 		];
 		Ok(r.into())
 	}
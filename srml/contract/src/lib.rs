@@ -86,7 +86,6 @@ extern crate assert_matches;
 extern crate wabt;
 
 mod account_db;
-mod double_map;
 mod exec;
 mod vm;
 mod gas;
@@ -100,14 +99,13 @@ mod tests;
 pub use genesis_config::GenesisConfig;
 use exec::ExecutionContext;
 use account_db::{AccountDb, OverlayAccountDb};
-use double_map::StorageDoubleMap;
 
 use rstd::prelude::*;
 use rstd::marker::PhantomData;
 use codec::Codec;
-use runtime_primitives::traits::{Hash, As, SimpleArithmetic, OnFinalise};
+use runtime_primitives::traits::{Hash, As, SimpleArithmetic};
 use runtime_support::dispatch::Result;
-use runtime_support::{Parameter, StorageMap, StorageValue};
+use runtime_support::{Parameter, StorageMap, StorageValue, StorageDoubleMap};
 use system::ensure_signed;
 
 pub trait Trait: balances::Trait {
@@ -166,6 +164,8 @@ decl_module! {
 			ctor: Vec<u8>,
 			data: Vec<u8>
 		) -> Result;
+
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
@@ -188,23 +188,14 @@ decl_storage! {
 
 		/// The code associated with an account.
 		pub CodeOf: default map [ T::AccountId => Vec<u8> ];	// TODO Vec<u8> values should be optimised to not do a length prefix.
+		/// The storage items associated with an account/key.
+		StorageOf: double_map T::AccountId, Vec<u8> => Vec<u8>;
 	}
 }
 
 // TODO: consider storing upper-bound for contract's gas limit in fixed-length runtime
 // code in contract itself and use that.
 
-/// The storage items associated with an account/key.
-///
-/// TODO: keys should also be able to take AsRef<KeyType> to ensure Vec<u8>s can be passed as &[u8]
-pub(crate) struct StorageOf<T>(::rstd::marker::PhantomData<T>);
-impl<T: Trait> double_map::StorageDoubleMap for StorageOf<T> {
-	const PREFIX: &'static [u8] = b"con:sto:";
-	type Key1 = T::AccountId;
-	type Key2 = Vec<u8>;
-	type Value = Vec<u8>;
-}
-
 impl<T: Trait> Module<T> {
 	/// Make a call to a specified account, optionally transferring some balance.
 	fn call(
@@ -297,7 +288,7 @@ impl<T: Trait> balances::OnFreeBalanceZero<T::AccountId> for Module<T> {
 }
 
 /// Finalization hook for the smart-contract module.
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+impl<T: Trait> Module<T> {
 	fn on_finalise(_n: T::BlockNumber) {
 		<GasSpent<T>>::kill();
 	}
@@ -106,20 +106,20 @@ impl<T: seats::Trait + voting::Trait + motions::Trait> primitives::BuildStorage
 		use codec::Encode;
 
 		Ok(map![
-			Self::hash(<seats::CandidacyBond<T>>::key()).to_vec() => self.candidacy_bond.encode(),
-			Self::hash(<seats::VotingBond<T>>::key()).to_vec() => self.voter_bond.encode(),
-			Self::hash(<seats::PresentSlashPerVoter<T>>::key()).to_vec() => self.present_slash_per_voter.encode(),
-			Self::hash(<seats::CarryCount<T>>::key()).to_vec() => self.carry_count.encode(),
-			Self::hash(<seats::PresentationDuration<T>>::key()).to_vec() => self.presentation_duration.encode(),
-			Self::hash(<seats::VotingPeriod<T>>::key()).to_vec() => self.approval_voting_period.encode(),
-			Self::hash(<seats::TermDuration<T>>::key()).to_vec() => self.term_duration.encode(),
-			Self::hash(<seats::DesiredSeats<T>>::key()).to_vec() => self.desired_seats.encode(),
-			Self::hash(<seats::InactiveGracePeriod<T>>::key()).to_vec() => self.inactive_grace_period.encode(),
-			Self::hash(<seats::ActiveCouncil<T>>::key()).to_vec() => self.active_council.encode(),
-
-			Self::hash(<voting::CooloffPeriod<T>>::key()).to_vec() => self.cooloff_period.encode(),
-			Self::hash(<voting::VotingPeriod<T>>::key()).to_vec() => self.voting_period.encode(),
-			Self::hash(<voting::Proposals<T>>::key()).to_vec() => vec![0u8; 0].encode()
+			<seats::CandidacyBond<T>>::key().to_vec() => self.candidacy_bond.encode(),
+			<seats::VotingBond<T>>::key().to_vec() => self.voter_bond.encode(),
+			<seats::PresentSlashPerVoter<T>>::key().to_vec() => self.present_slash_per_voter.encode(),
+			<seats::CarryCount<T>>::key().to_vec() => self.carry_count.encode(),
+			<seats::PresentationDuration<T>>::key().to_vec() => self.presentation_duration.encode(),
+			<seats::VotingPeriod<T>>::key().to_vec() => self.approval_voting_period.encode(),
+			<seats::TermDuration<T>>::key().to_vec() => self.term_duration.encode(),
+			<seats::DesiredSeats<T>>::key().to_vec() => self.desired_seats.encode(),
+			<seats::InactiveGracePeriod<T>>::key().to_vec() => self.inactive_grace_period.encode(),
+			<seats::ActiveCouncil<T>>::key().to_vec() => self.active_council.encode(),
+
+			<voting::CooloffPeriod<T>>::key().to_vec() => self.cooloff_period.encode(),
+			<voting::VotingPeriod<T>>::key().to_vec() => self.voting_period.encode(),
+			<voting::Proposals<T>>::key().to_vec() => vec![0u8; 0].encode()
 		])
 	}
 }
@@ -168,6 +168,8 @@ mod tests {
 		type AccountId = u64;
 		type Header = Header;
 		type Event = Event;
+		type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Test {
 		type Balance = u64;
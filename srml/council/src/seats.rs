@@ -17,9 +17,10 @@
 //! Council system: Handles the voting in and maintenance of council members.
 
 use rstd::prelude::*;
-use primitives::traits::{Zero, One, As, Lookup, OnFinalise};
+use primitives::traits::{Zero, One, As, Lookup};
 use runtime_io::print;
 use srml_support::{StorageValue, StorageMap, dispatch::Result};
+use srml_support::imbalance::OnUnbalanced;
 use democracy;
 use balances::{self, address::Address};
 use system::{self, ensure_signed};
@@ -96,6 +97,8 @@ decl_module! {
 		fn remove_member(who: Address<T::AccountId, T::AccountIndex>) -> Result;
 		fn set_presentation_duration(count: T::BlockNumber) -> Result;
 		fn set_term_duration(count: T::BlockNumber) -> Result;
+
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
@@ -295,7 +298,8 @@ impl<T: Trait> Module<T> {
 			<balances::Module<T>>::repatriate_reserved(&who, &reporter, Self::voting_bond())?;
 			Self::deposit_event(RawEvent::VoterReaped(who, reporter));
 		} else {
-			<balances::Module<T>>::slash_reserved(&reporter, Self::voting_bond());
+			let (imbalance, _) = <balances::Module<T>>::slash_reserved(&reporter, Self::voting_bond());
+			<balances::Module<T>>::on_unbalanced(imbalance);
 			Self::deposit_event(RawEvent::BadReaperSlashed(reporter));
 		}
 		Ok(())
@@ -396,7 +400,8 @@ impl<T: Trait> Module<T> {
 		} else {
 			// we can rest assured it will be Ok since we checked `can_slash` earlier; still
 			// better safe than sorry.
-			let _ = <balances::Module<T>>::slash(&who, bad_presentation_punishment);
+			let (imbalance, _) = <balances::Module<T>>::slash(&who, bad_presentation_punishment);
+			<balances::Module<T>>::on_unbalanced(imbalance);
 			Err(if dupe { "duplicate presentation" } else { "incorrect total" })
 		}
 	}
@@ -550,7 +555,7 @@ impl<T: Trait> Module<T> {
 	}
 }
 
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+impl<T: Trait> Module<T> {
 	fn on_finalise(n: T::BlockNumber) {
 		if let Err(e) = Self::end_block(n) {
 			print("Guru meditation");
@@ -18,7 +18,7 @@
 
 use rstd::prelude::*;
 use rstd::borrow::Borrow;
-use primitives::traits::{OnFinalise, Hash};
+use primitives::traits::{Hash};
 use runtime_io::print;
 use srml_support::dispatch::Result;
 use srml_support::{StorageValue, StorageMap, IsSubType};
@@ -38,6 +38,8 @@ decl_module! {
 
 		fn set_cooloff_period(blocks: T::BlockNumber) -> Result;
 		fn set_voting_period(blocks: T::BlockNumber) -> Result;
+
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
@@ -226,7 +228,7 @@ impl<T: Trait> Module<T> {
 	}
 }
 
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+impl<T: Trait> Module<T> {
 	fn on_finalise(n: T::BlockNumber) {
 		if let Err(e) = Self::end_block(n) {
 			print("Guru meditation");
@@ -43,8 +43,8 @@ extern crate srml_system as system;
 
 use rstd::prelude::*;
 use rstd::result;
-use primitives::traits::{Zero, OnFinalise, As, MaybeSerializeDebug};
-use srml_support::{StorageValue, StorageMap, Parameter, Dispatchable, IsSubType};
+use primitives::traits::{Zero, As, MaybeSerializeDebug};
+use srml_support::{StorageValue, StorageMap, Parameter, Dispatchable, IsSubType, TypeInfo};
 use srml_support::dispatch::Result;
 use system::ensure_signed;
 
@@ -67,12 +67,16 @@ pub trait Trait: balances::Trait + Sized {
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-		fn propose(origin, proposal: Box<T::Proposal>, value: T::Balance) -> Result;
-		fn second(origin, proposal: PropIndex) -> Result;
-		fn vote(origin, ref_index: ReferendumIndex, approve_proposal: bool) -> Result;
+		fn deposit_event<T>() = default;
+
+		fn propose(origin, proposal: Box<T::Proposal>, #[compact] value: T::Balance) -> Result;
+		fn second(origin, #[compact] proposal: PropIndex) -> Result;
+		fn vote(origin, #[compact] ref_index: ReferendumIndex, approve_proposal: bool) -> Result;
 
 		fn start_referendum(proposal: Box<T::Proposal>, vote_threshold: VoteThreshold) -> Result;
-		fn cancel_referendum(ref_index: ReferendumIndex) -> Result;
+		fn cancel_referendum(#[compact] ref_index: ReferendumIndex) -> Result;
+
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
@@ -121,12 +125,6 @@ decl_event!(
 );
 
 impl<T: Trait> Module<T> {
-
-	/// Deposit one of this module's events.
-	fn deposit_event(event: Event<T>) {
-		<system::Module<T>>::deposit_event(<T as Trait>::Event::from(event).into());
-	}
-
 	// exposed immutables.
 
 	/// Get the amount locked in support of `proposal`; `None` if proposal isn't a valid proposal
@@ -309,7 +307,7 @@ impl<T: Trait> Module<T> {
 	}
 }
 
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+impl<T: Trait> Module<T> {
 	fn on_finalise(n: T::BlockNumber) {
 		if let Err(e) = Self::end_block(n) {
 			runtime_io::print(e);
@@ -356,12 +354,12 @@ impl<T: Trait> primitives::BuildStorage for GenesisConfig<T>
 		use codec::Encode;
 
 		Ok(map![
-			Self::hash(<LaunchPeriod<T>>::key()).to_vec() => self.launch_period.encode(),
-			Self::hash(<VotingPeriod<T>>::key()).to_vec() => self.voting_period.encode(),
-			Self::hash(<MinimumDeposit<T>>::key()).to_vec() => self.minimum_deposit.encode(),
-			Self::hash(<ReferendumCount<T>>::key()).to_vec() => (0 as ReferendumIndex).encode(),
-			Self::hash(<NextTally<T>>::key()).to_vec() => (0 as ReferendumIndex).encode(),
-			Self::hash(<PublicPropCount<T>>::key()).to_vec() => (0 as PropIndex).encode()
+			<LaunchPeriod<T>>::key().to_vec() => self.launch_period.encode(),
+			<VotingPeriod<T>>::key().to_vec() => self.voting_period.encode(),
+			<MinimumDeposit<T>>::key().to_vec() => self.minimum_deposit.encode(),
+			<ReferendumCount<T>>::key().to_vec() => (0 as ReferendumIndex).encode(),
+			<NextTally<T>>::key().to_vec() => (0 as ReferendumIndex).encode(),
+			<PublicPropCount<T>>::key().to_vec() => (0 as PropIndex).encode()
 		])
 	}
 }
@@ -399,6 +397,8 @@ mod tests {
 		type AccountId = u64;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Test {
 		type Balance = u64;
@@ -48,7 +48,7 @@ extern crate parity_codec as codec;
 #[macro_use]
 extern crate srml_support as runtime_support;
 
-// Needed for various traits. In our case, `OnFinalise`.
+// Needed for various traits. In our case, `OnFinalise`, which the tests call directly.
 extern crate sr_primitives as runtime_primitives;
 // `system` module provides us with all sorts of useful stuff and macros
 // depend on it being around.
@@ -58,7 +58,6 @@ extern crate srml_system as system;
 // might find it useful).
 extern crate srml_balances as balances;
 
-use runtime_primitives::traits::OnFinalise;
 use runtime_support::{StorageValue, dispatch::Result};
 use system::ensure_signed;
 
@@ -101,10 +100,17 @@ pub trait Trait: balances::Trait {
 // to the above bullets: `::Signed(AccountId)`, `::Root` and `::Inherent`. You should always match
 // against them as the first thing you do in your function. There are three convenience calls
 // in system that do the matching for you and return a convenient result: `ensure_signed`,
-// `ensure_root` and `ensure_inherent`.
+// `ensure_root` and `ensure_none`.
+//
+// A function which doesn't take `origin` may only be called by root, which is checked before it
+// is dispatched. Declaring it `#[require_none]` checks that it is called by an unsigned extrinsic,
+// e.g. an inherent, instead.
 decl_module! {
 	// Simple declaration of the `Module` type. Lets the macro know what its working on.
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// Deposit one of this module's events, using the default implementation.
+		fn deposit_event<T>() = default;
+
 		/// This is your public interface. Be extremely careful.
 		/// This is just a simple example of how to interact with the module from the external
 		/// world.
@@ -114,6 +120,9 @@ decl_module! {
 
 		/// A privileged call; in this case it resets our dummy value to something new.
 		fn set_dummy(new_dummy: T::Balance) -> Result;
+
+		/// Called by the executive once the last extrinsic of the block has been applied.
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
@@ -174,12 +183,6 @@ decl_storage! {
 // functions that do not write to storage and operation functions that do.
 // - Private functions. These are your usual private utilities unavailable to other modules.
 impl<T: Trait> Module<T> {
-	/// Deposit one of this module's events.
-	// TODO: move into `decl_module` macro.
-	fn deposit_event(event: Event<T>) {
-		<system::Module<T>>::deposit_event(<T as Trait>::Event::from(event).into());
-	}
-
 	// Implement Calls and add public immutables and private mutables.
 
 	// Implement dispatched function `accumulate_dummy`. This just increases the value
@@ -271,8 +274,9 @@ impl<T: Trait> Module<T> {
 	}
 }
 
-// This trait expresses what should happen when the block is finalised.
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+// What should happen when the block is finalised. `decl_module!` calls this through `OnFinalise`,
+// as the module declares `fn on_finalise`.
+impl<T: Trait> Module<T> {
 	fn on_finalise(_: T::BlockNumber) {
 		// Anything that needs to be done at the end of the block.
 		// We just kill our dummy storage item.
@@ -307,16 +311,16 @@ impl<T: Trait> Default for GenesisConfig<T> {
 //
 // Ideally this would re-use the `::put` logic in the storage item type for introducing
 // the values into the `StorageMap` (which is just a `HashMap<Vec<u8>, Vec<u8>>`). That
-// is not yet in place, though, so for now we do everything "manually", using `hash`,
-// `::key()` and `.to_vec()` for the key and `.encode()` for the value.
+// is not yet in place, though, so for now we do everything "manually", using `::key()` and
+// `.to_vec()` for the key and `.encode()` for the value.
 #[cfg(feature = "std")]
 impl<T: Trait> runtime_primitives::BuildStorage for GenesisConfig<T>
 {
 	fn build_storage(self) -> ::std::result::Result<runtime_primitives::StorageMap, String> {
 		use codec::Encode;
 		Ok(map![
-			Self::hash(<Dummy<T>>::key()).to_vec() => self.dummy.encode(),
-			Self::hash(<Foo<T>>::key()).to_vec() => self.foo.encode()
+			<Dummy<T>>::key().to_vec() => self.dummy.encode(),
+			<Foo<T>>::key().to_vec() => self.foo.encode()
 		])
 	}
 }
@@ -328,7 +332,7 @@ mod tests {
 	use runtime_io::with_externalities;
 	use substrate_primitives::{H256, Blake2Hasher};
 	use runtime_primitives::BuildStorage;
-	use runtime_primitives::traits::{BlakeTwo256};
+	use runtime_primitives::traits::{BlakeTwo256, OnFinalise};
 
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are requried.
@@ -353,6 +357,8 @@ mod tests {
 		type AccountId = u64;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Test {
 		type Balance = u64;
@@ -51,23 +51,40 @@ use rstd::prelude::*;
 use rstd::marker::PhantomData;
 use rstd::result;
 use primitives::traits::{self, Header, Zero, One, Checkable, Applyable, CheckEqual, OnFinalise,
-	MakePayment, Hash};
+	OffchainWorker, Hash};
 use runtime_support::Dispatchable;
+use runtime_support::storage::{OnStorageMigration, OnRuntimeUpgrade};
+use runtime_support::storage::transactional::with_transaction;
 use codec::{Codec, Encode};
 use system::extrinsics_root;
 use primitives::{ApplyOutcome, ApplyError};
 
 mod internal {
+	use runtime_support::dispatch::DispatchError;
+
 	pub enum ApplyError {
 		BadSignature(&'static str),
 		Stale,
 		Future,
 		CantPay,
+		FullBlock,
+	}
+
+	impl From<::primitives::ApplyError> for ApplyError {
+		fn from(e: ::primitives::ApplyError) -> Self {
+			match e {
+				::primitives::ApplyError::BadSignature => ApplyError::BadSignature("bad signature in extrinsic"),
+				::primitives::ApplyError::Stale => ApplyError::Stale,
+				::primitives::ApplyError::Future => ApplyError::Future,
+				::primitives::ApplyError::CantPay => ApplyError::CantPay,
+				::primitives::ApplyError::FullBlock => ApplyError::FullBlock,
+			}
+		}
 	}
 
 	pub enum ApplyOutcome {
 		Success,
-		Fail(&'static str),
+		Fail(DispatchError),
 	}
 }
 
@@ -75,26 +92,30 @@ pub struct Executive<
 	System,
 	Block,
 	Lookup,
-	Payment,
 	Finalisation,
->(PhantomData<(System, Block, Lookup, Payment, Finalisation)>);
+>(PhantomData<(System, Block, Lookup, Finalisation)>);
 
 impl<
 	Address,
 	System: system::Trait,
 	Block: traits::Block<Header=System::Header, Hash=System::Hash>,
 	Lookup: traits::Lookup<Source=Address, Target=System::AccountId>,
-	Payment: MakePayment<System::AccountId>,
-	Finalisation: OnFinalise<System::BlockNumber>,
-> Executive<System, Block, Lookup, Payment, Finalisation> where
+	Finalisation: OnFinalise<System::BlockNumber> + OffchainWorker<System::BlockNumber>
+		+ OnStorageMigration + OnRuntimeUpgrade,
+> Executive<System, Block, Lookup, Finalisation> where
 	Block::Extrinsic: Checkable<fn(Address) -> Result<System::AccountId, &'static str>> + Codec,
-	<Block::Extrinsic as Checkable<fn(Address) -> Result<System::AccountId, &'static str>>>::Checked: Applyable<Index=System::Index, AccountId=System::AccountId>,
+	<Block::Extrinsic as Checkable<fn(Address) -> Result<System::AccountId, &'static str>>>::Checked: Applyable<AccountId=System::AccountId>,
 	<<Block::Extrinsic as Checkable<fn(Address) -> Result<System::AccountId, &'static str>>>::Checked as Applyable>::Call: Dispatchable,
 	<<<Block::Extrinsic as Checkable<fn(Address) -> Result<System::AccountId, &'static str>>>::Checked as Applyable>::Call as Dispatchable>::Origin: From<Option<System::AccountId>>
 {
 	/// Start the execution of a particular block.
 	pub fn initialise_block(header: &System::Header) {
 		<system::Module<System>>::initialise(header.number(), header.parent_hash(), header.extrinsics_root());
+		if <system::Module<System>>::note_spec_version() {
+			let weight = Finalisation::on_runtime_upgrade();
+			<system::Module<System>>::note_initialisation_weight(weight);
+		}
+		Finalisation::on_storage_migration();
 	}
 
 	fn initial_checks(block: &Block) {
@@ -143,6 +164,12 @@ impl<
 		<system::Module<System>>::finalise()
 	}
 
+	/// Run the offchain workers of the modules on the state of the imported block `n`. It is up to
+	/// the caller to discard the changes made to the state.
+	pub fn offchain_worker(n: System::BlockNumber) {
+		<Finalisation as OffchainWorker<System::BlockNumber>>::offchain_worker(n)
+	}
+
 	/// Apply extrinsic outside of the block execution function.
 	/// This doesn't attempt to validate anything regarding the block, but it builds a list of uxt
 	/// hashes.
@@ -157,18 +184,33 @@ impl<
 			Err(internal::ApplyError::BadSignature(_)) => Err(ApplyError::BadSignature),
 			Err(internal::ApplyError::Stale) => Err(ApplyError::Stale),
 			Err(internal::ApplyError::Future) => Err(ApplyError::Future),
+			Err(internal::ApplyError::FullBlock) => Err(ApplyError::FullBlock),
 		}
 	}
 
+	/// Check an extrinsic against the current state without changing it, as the transaction pool
+	/// does before accepting it: its signature, then its signed extensions. A `Future` error means
+	/// it may be valid later.
+	pub fn validate_transaction(uxt: Block::Extrinsic) -> result::Result<(), ApplyError> {
+		let encoded_len = uxt.encode().len();
+		let xt = uxt.check_with(Lookup::lookup).map_err(|_| ApplyError::BadSignature)?;
+		xt.validate(encoded_len)
+	}
+
 	/// Apply an extrinsic inside the block execution function.
 	fn apply_extrinsic_no_note(uxt: Block::Extrinsic) {
 		let l = uxt.encode().len();
 		match Self::apply_extrinsic_no_note_with_len(uxt, l) {
 			Ok(internal::ApplyOutcome::Success) => (),
-			Ok(internal::ApplyOutcome::Fail(e)) => runtime_io::print(e),
+			Ok(internal::ApplyOutcome::Fail(e)) => {
+				runtime_io::print("Extrinsic failed in module and with error:");
+				runtime_io::print(e.module as u64);
+				runtime_io::print(e.error as u64);
+			},
 			Err(internal::ApplyError::CantPay) => panic!("All extrinsics should have sender able to pay their fees"),
 			Err(internal::ApplyError::BadSignature(_)) => panic!("All extrinsics should be properly signed"),
 			Err(internal::ApplyError::Stale) | Err(internal::ApplyError::Future) => panic!("All extrinsics should have the correct nonce"),
+			Err(internal::ApplyError::FullBlock) => panic!("All extrinsics should fit in the block"),
 		}
 	}
 
@@ -177,21 +219,11 @@ impl<
 		// Verify the signature is good.
 		let xt = uxt.check_with(Lookup::lookup).map_err(internal::ApplyError::BadSignature)?;
 
-		if let Some(sender) = xt.sender() {
-			// check index
-			let expected_index = <system::Module<System>>::account_nonce(sender);
-			if xt.index() != &expected_index { return Err(
-				if xt.index() < &expected_index { internal::ApplyError::Stale } else { internal::ApplyError::Future }
-			) }
+		// check the signed extensions, e.g. the nonce and the fees; the changes they make are kept
+		// only if they all pass.
+		with_transaction(|| xt.pre_dispatch(encoded_len))?;
 
-			// pay any fees.
-			Payment::make_payment(sender, encoded_len).map_err(|_| internal::ApplyError::CantPay)?;
-
-			// AUDIT: Under no circumstances may this function panic from here onwards.
-
-			// increment nonce in storage
-			<system::Module<System>>::inc_account_nonce(sender);
-		}
+		// AUDIT: Under no circumstances may this function panic from here onwards.
 
 		// decode parameters and dispatch
 		let (f, s) = xt.deconstruct();
@@ -259,6 +291,8 @@ mod tests {
 		type AccountId = u64;
 		type Header = Header;
 		type Event = MetaEvent;
+		type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Runtime {
 		type Balance = u64;
@@ -268,11 +302,15 @@ mod tests {
 		type Event = MetaEvent;
 	}
 
-	type TestXt = primitives::testing::TestXt<Call<Runtime>>;
-	type Executive = super::Executive<Runtime, Block<TestXt>, NullLookup, balances::Module<Runtime>, ()>;
+	type SignedExtra = (system::CheckNonce<Runtime>, system::CheckWeight<Runtime>, balances::TakeFees<Runtime>);
+	type TestXt = primitives::testing::TestXt<Call<Runtime>, SignedExtra>;
+	type Executive = super::Executive<Runtime, Block<TestXt>, NullLookup, ()>;
 
-	#[test]
-	fn balance_transfer_dispatch_works() {
+	fn sign(who: u64, nonce: u64, call: Call<Runtime>) -> TestXt {
+		primitives::testing::TestXt(Some((who, (system::CheckNonce(nonce), system::CheckWeight::new(), balances::TakeFees::new()))), call)
+	}
+
+	fn new_test_ext_with_fees() -> runtime_io::TestExternalities<Blake2Hasher> {
 		let mut t = system::GenesisConfig::<Runtime>::default().build_storage().unwrap();
 		t.extend(balances::GenesisConfig::<Runtime> {
 			balances: vec![(1, 111)],
@@ -283,9 +321,13 @@ mod tests {
 			creation_fee: 0,
 			reclaim_rebate: 0,
 		}.build_storage().unwrap());
-		let xt = primitives::testing::TestXt(Some(1), 0, Call::transfer(2.into(), 69));
-		let mut t = runtime_io::TestExternalities::from(t);
-		with_externalities(&mut t, || {
+		t.into()
+	}
+
+	#[test]
+	fn balance_transfer_dispatch_works() {
+		let xt = sign(1, 0, Call::transfer(2.into(), 69));
+		with_externalities(&mut new_test_ext_with_fees(), || {
 			Executive::initialise_block(&Header::new(1, H256::default(), H256::default(), [69u8; 32].into(), Digest::default()));
 			Executive::apply_extrinsic(xt).unwrap();
 			assert_eq!(<balances::Module<Runtime>>::total_balance(&1), 32);
@@ -352,11 +394,36 @@ mod tests {
 	#[test]
 	fn bad_extrinsic_not_inserted() {
 		let mut t = new_test_ext();
-		let xt = primitives::testing::TestXt(Some(1), 42, Call::transfer(33.into(), 69));
+		let xt = sign(1, 42, Call::transfer(33.into(), 69));
 		with_externalities(&mut t, || {
 			Executive::initialise_block(&Header::new(1, H256::default(), H256::default(), [69u8; 32].into(), Digest::default()));
 			assert!(Executive::apply_extrinsic(xt).is_err());
 			assert_eq!(<system::Module<Runtime>>::extrinsic_index(), Some(0));
 		});
 	}
+
+	#[test]
+	fn signed_extensions_are_validated_without_changes() {
+		with_externalities(&mut new_test_ext_with_fees(), || {
+			assert_eq!(Executive::validate_transaction(sign(1, 0, Call::transfer(2.into(), 69))), Ok(()));
+			assert_eq!(Executive::validate_transaction(sign(1, 1, Call::transfer(2.into(), 69))), Err(ApplyError::Future));
+			assert_eq!(Executive::validate_transaction(sign(2, 0, Call::transfer(1.into(), 1))), Err(ApplyError::CantPay));
+			assert_eq!(<system::Module<Runtime>>::account_nonce(&1), 0);
+			assert_eq!(<balances::Module<Runtime>>::free_balance(&1), 111);
+		});
+	}
+
+	#[test]
+	fn extrinsics_not_fitting_in_the_block_are_rejected() {
+		use runtime_support::Get;
+
+		let mut t = new_test_ext();
+		let xt = sign(1, 0, Call::transfer(33.into(), 69));
+		with_externalities(&mut t, || {
+			Executive::initialise_block(&Header::new(1, H256::default(), H256::default(), [69u8; 32].into(), Digest::default()));
+			<system::Module<Runtime>>::note_extrinsic_weight(system::DefaultMaximumBlockWeight::get());
+			assert_eq!(Executive::apply_extrinsic(xt), Err(ApplyError::FullBlock));
+			assert_eq!(<system::Module<Runtime>>::account_nonce(&1), 0);
+		});
+	}
 }
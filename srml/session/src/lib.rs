@@ -49,7 +49,7 @@ extern crate srml_system as system;
 extern crate srml_timestamp as timestamp;
 
 use rstd::prelude::*;
-use primitives::traits::{Zero, One, OnFinalise, Convert, As};
+use primitives::traits::{Zero, One, Convert, As};
 use runtime_support::{StorageValue, StorageMap};
 use runtime_support::dispatch::Result;
 use system::ensure_signed;
@@ -75,10 +75,14 @@ pub trait Trait: timestamp::Trait {
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
 		fn set_key(origin, key: T::SessionKey) -> Result;
 
-		fn set_length(new: T::BlockNumber) -> Result;
+		fn set_length(#[compact] new: T::BlockNumber) -> Result;
 		fn force_new_session(apply_rewards: bool) -> Result;
+
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
@@ -116,15 +120,9 @@ decl_storage! {
 }
 
 impl<T: Trait> Module<T> {
-
-	/// Deposit one of this module's events.
-	fn deposit_event(event: Event<T>) {
-		<system::Module<T>>::deposit_event(<T as Trait>::Event::from(event).into());
-	}
-
 	/// The number of validators currently.
 	pub fn validator_count() -> u32 {
-		<Validators<T>>::get().len() as u32	// TODO: can probably optimised
+		<Validators<T>>::decode_len().expect("Required values must be in storage")
 	}
 
 	/// The last length change, if there was one, zero if not.
@@ -236,7 +234,7 @@ impl<T: Trait> Module<T> {
 	}
 }
 
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+impl<T: Trait> Module<T> {
 	fn on_finalise(n: T::BlockNumber) {
 		Self::check_rotate_session(n);
 	}
@@ -270,10 +268,10 @@ impl<T: Trait> primitives::BuildStorage for GenesisConfig<T>
 		use codec::Encode;
 		use primitives::traits::As;
 		Ok(map![
-			Self::hash(<SessionLength<T>>::key()).to_vec() => self.session_length.encode(),
-			Self::hash(<CurrentIndex<T>>::key()).to_vec() => T::BlockNumber::sa(0).encode(),
-			Self::hash(<CurrentStart<T>>::key()).to_vec() => T::Moment::zero().encode(),
-			Self::hash(<Validators<T>>::key()).to_vec() => self.validators.encode()
+			<SessionLength<T>>::key().to_vec() => self.session_length.encode(),
+			<CurrentIndex<T>>::key().to_vec() => T::BlockNumber::sa(0).encode(),
+			<CurrentStart<T>>::key().to_vec() => T::Moment::zero().encode(),
+			<Validators<T>>::key().to_vec() => self.validators.encode()
 		])
 	}
 }
@@ -309,6 +307,8 @@ mod tests {
 		type AccountId = u64;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+		type SpecVersion = ();
 	}
 	impl timestamp::Trait for Test {
 		const TIMESTAMP_SET_POSITION: u32 = 0;
@@ -62,15 +62,15 @@ impl<T: Trait> Default for GenesisConfig<T> {
 impl<T: Trait> primitives::BuildStorage for GenesisConfig<T> {
 	fn build_storage(self) -> ::std::result::Result<HashMap<Vec<u8>, Vec<u8>>, String> {
 		let r: runtime_io::TestExternalities<Blake2Hasher> = map![
-			Self::hash(<Intentions<T>>::key()).to_vec() => self.intentions.encode(),
-			Self::hash(<SessionsPerEra<T>>::key()).to_vec() => self.sessions_per_era.encode(),
-			Self::hash(<ValidatorCount<T>>::key()).to_vec() => self.validator_count.encode(),
-			Self::hash(<MinimumValidatorCount<T>>::key()).to_vec() => self.minimum_validator_count.encode(),
-			Self::hash(<BondingDuration<T>>::key()).to_vec() => self.bonding_duration.encode(),
-			Self::hash(<CurrentEra<T>>::key()).to_vec() => self.current_era.encode(),
-			Self::hash(<SessionReward<T>>::key()).to_vec() => self.session_reward.encode(),
-			Self::hash(<OfflineSlash<T>>::key()).to_vec() => self.offline_slash.encode(),
-			Self::hash(<OfflineSlashGrace<T>>::key()).to_vec() => self.offline_slash_grace.encode()
+			<Intentions<T>>::key().to_vec() => self.intentions.encode(),
+			<SessionsPerEra<T>>::key().to_vec() => self.sessions_per_era.encode(),
+			<ValidatorCount<T>>::key().to_vec() => self.validator_count.encode(),
+			<MinimumValidatorCount<T>>::key().to_vec() => self.minimum_validator_count.encode(),
+			<BondingDuration<T>>::key().to_vec() => self.bonding_duration.encode(),
+			<CurrentEra<T>>::key().to_vec() => self.current_era.encode(),
+			<SessionReward<T>>::key().to_vec() => self.session_reward.encode(),
+			<OfflineSlash<T>>::key().to_vec() => self.offline_slash.encode(),
+			<OfflineSlashGrace<T>>::key().to_vec() => self.offline_slash_grace.encode()
 		];
 		Ok(r.into())
 	}
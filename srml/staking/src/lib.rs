@@ -48,10 +48,11 @@ extern crate srml_system as system;
 extern crate srml_timestamp as timestamp;
 
 use rstd::prelude::*;
-use runtime_support::{Parameter, StorageValue, StorageMap};
+use runtime_support::{Parameter, StorageValue, StorageMap, TypeInfo};
 use runtime_support::dispatch::Result;
+use runtime_support::imbalance::{PositiveImbalance, NegativeImbalance, OnUnbalanced};
 use session::OnSessionChange;
-use primitives::traits::{Zero, One, Bounded, OnFinalise,
+use primitives::traits::{Zero, One, Bounded,
 	As, Lookup};
 use balances::{address::Address, OnDilution};
 use system::ensure_signed;
@@ -75,7 +76,7 @@ pub enum LockStatus<BlockNumber: Parameter> {
 }
 
 /// Preference of what happens on a slash event.
-#[derive(PartialEq, Eq, Clone, Encode, Decode)]
+#[derive(PartialEq, Eq, Clone, Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
 pub struct ValidatorPrefs<Balance> {
 	/// Validator should ensure this many more slashes than is necessary before being unstaked.
@@ -97,6 +98,12 @@ pub trait Trait: balances::Trait + session::Trait {
 	/// Some tokens minted.
 	type OnRewardMinted: OnDilution<<Self as balances::Trait>::Balance>;
 
+	/// Handler for the funds minted by the session rewards.
+	type Reward: OnUnbalanced<PositiveImbalance<<Self as balances::Trait>::Balance>>;
+
+	/// Handler for the funds slashed from offline validators and their nominators.
+	type Slash: OnUnbalanced<NegativeImbalance<<Self as balances::Trait>::Balance>>;
+
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 }
@@ -104,17 +111,19 @@ pub trait Trait: balances::Trait + session::Trait {
 decl_module! {
 	#[cfg_attr(feature = "std", serde(bound(deserialize = "T::Balance: ::serde::de::DeserializeOwned")))]
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
 		fn stake(origin) -> Result;
-		fn unstake(origin, intentions_index: u32) -> Result;
+		fn unstake(origin, #[compact] intentions_index: u32) -> Result;
 		fn nominate(origin, target: Address<T::AccountId, T::AccountIndex>) -> Result;
-		fn unnominate(origin, target_index: u32) -> Result;
-		fn register_preferences(origin, intentions_index: u32, prefs: ValidatorPrefs<T::Balance>) -> Result;
+		fn unnominate(origin, #[compact] target_index: u32) -> Result;
+		fn register_preferences(origin, #[compact] intentions_index: u32, prefs: ValidatorPrefs<T::Balance>) -> Result;
 
-		fn set_sessions_per_era(new: T::BlockNumber) -> Result;
-		fn set_bonding_duration(new: T::BlockNumber) -> Result;
-		fn set_validator_count(new: u32) -> Result;
+		fn set_sessions_per_era(#[compact] new: T::BlockNumber) -> Result;
+		fn set_bonding_duration(#[compact] new: T::BlockNumber) -> Result;
+		fn set_validator_count(#[compact] new: u32) -> Result;
 		fn force_new_era(apply_rewards: bool) -> Result;
-		fn set_offline_slash_grace(new: u32) -> Result;
+		fn set_offline_slash_grace(#[compact] new: u32) -> Result;
 	}
 }
 
@@ -182,12 +191,6 @@ decl_storage! {
 }
 
 impl<T: Trait> Module<T> {
-
-	/// Deposit one of this module's events.
-	fn deposit_event(event: Event<T>) {
-		<system::Module<T>>::deposit_event(<T as Trait>::Event::from(event).into());
-	}
-
 	// PUBLIC IMMUTABLES
 
 	/// MinimumValidatorCount getter, introduces a default.
@@ -370,16 +373,20 @@ impl<T: Trait> Module<T> {
 			return
 		}
 
-		if let Some(rem) = <balances::Module<T>>::slash(v, slash) {
+		let (mut imbalance, remaining) = <balances::Module<T>>::slash(v, slash);
+		if let Some(rem) = remaining {
 			let noms = Self::current_nominators_for(v);
 			let total = noms.iter().map(<balances::Module<T>>::total_balance).fold(T::Balance::zero(), |acc, x| acc + x);
 			if !total.is_zero() {
 				let safe_mul_rational = |b| b * rem / total;// TODO: avoid overflow
 				for n in noms.iter() {
-					let _ = <balances::Module<T>>::slash(n, safe_mul_rational(<balances::Module<T>>::total_balance(n)));	// best effort - not much that can be done on fail.
+					// best effort - not much that can be done on fail.
+					let (nominator_imbalance, _) = <balances::Module<T>>::slash(n, safe_mul_rational(<balances::Module<T>>::total_balance(n)));
+					imbalance.subsume(nominator_imbalance);
 				}
 			}
 		}
+		T::Slash::on_unbalanced(imbalance);
 	}
 
 	/// Reward a given validator by a specific amount. Add the reward to their, and their nominators'
@@ -387,6 +394,7 @@ impl<T: Trait> Module<T> {
 	fn reward_validator(who: &T::AccountId, reward: T::Balance) {
 		let off_the_table = reward.min(Self::validator_preferences(who).validator_payment);
 		let reward = reward - off_the_table;
+		let mut imbalance = PositiveImbalance::zero();
 		let validator_cut = if reward.is_zero() {
 			Zero::zero()
 		} else {
@@ -397,11 +405,16 @@ impl<T: Trait> Module<T> {
 				.max(One::one());
 			let safe_mul_rational = |b| b * reward / total;// TODO: avoid overflow
 			for n in noms.iter() {
-				let _ = <balances::Module<T>>::reward(n, safe_mul_rational(<balances::Module<T>>::total_balance(n)));
+				if let Ok(nominator_imbalance) = <balances::Module<T>>::reward(n, safe_mul_rational(<balances::Module<T>>::total_balance(n))) {
+					imbalance.subsume(nominator_imbalance);
+				}
 			}
 			safe_mul_rational(<balances::Module<T>>::total_balance(who))
 		};
-		let _ = <balances::Module<T>>::reward(who, validator_cut + off_the_table);
+		if let Ok(validator_imbalance) = <balances::Module<T>>::reward(who, validator_cut + off_the_table) {
+			imbalance.subsume(validator_imbalance);
+		}
+		T::Reward::on_unbalanced(imbalance);
 	}
 
 	/// Actually carry out the unstake operation.
@@ -510,11 +523,6 @@ impl<T: Trait> Module<T> {
 	}
 }
 
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
-	fn on_finalise(_n: T::BlockNumber) {
-	}
-}
-
 impl<T: Trait> OnSessionChange<T::Moment> for Module<T> {
 	fn on_session_change(elapsed: T::Moment, should_reward: bool) {
 		Self::new_session(elapsed, should_reward);
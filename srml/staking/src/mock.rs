@@ -48,6 +48,8 @@ impl system::Trait for Test {
 	type AccountId = u64;
 	type Header = Header;
 	type Event = ();
+	type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+	type SpecVersion = ();
 }
 impl balances::Trait for Test {
 	type Balance = u64;
@@ -67,6 +69,8 @@ impl timestamp::Trait for Test {
 }
 impl Trait for Test {
 	type OnRewardMinted = ();
+	type Reward = balances::Module<Test>;
+	type Slash = balances::Module<Test>;
 	type Event = ();
 }
 
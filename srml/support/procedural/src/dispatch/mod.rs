@@ -0,0 +1,534 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsing of `decl_module!`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
+use syn::{Attribute, Expr, Ident, ItemEnum, ItemFn, LitStr, Meta, Type, Visibility};
+use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
+use utils::{self, ModuleGenerics};
+
+mod transformation;
+
+mod keyword {
+	custom_keyword!(origin);
+	custom_keyword!(system);
+	custom_keyword!(default);
+}
+
+pub fn decl_module_impl(input: TokenStream) -> TokenStream {
+	let def = parse_macro_input!(input as ModuleDefinition);
+	transformation::expand(def).into()
+}
+
+/// The whole `decl_module!` invocation, prefixed with the path to `srml-support`.
+pub struct ModuleDefinition {
+	pub scrate: TokenTree,
+	/// Attributes of the module, applied to the `Call` enum.
+	pub attrs: Vec<Attribute>,
+	pub module_name: Ident,
+	pub generics: ModuleGenerics,
+	pub call_type: Ident,
+	pub origin_type: Type,
+	/// The module providing `ensure_root`, `system` unless given with `where system = ..`.
+	pub system: Ident,
+	pub functions: Vec<Dispatchable>,
+	/// `fn on_storage_migration(from: u32);`, run when the storage layout declared by the module
+	/// is newer than the one of the state.
+	pub on_storage_migration: Option<Hook>,
+	/// `fn on_finalise(n: T::BlockNumber);`, run at the end of each block.
+	pub on_finalise: Option<Hook>,
+	/// `fn on_runtime_upgrade() -> Weight;`, run once by the first block of a new runtime.
+	pub on_runtime_upgrade: Option<Hook>,
+	/// `fn offchain_worker(n: T::BlockNumber);`, run outside of consensus after a block import.
+	pub offchain_worker: Option<Hook>,
+	/// `fn deposit_event<T>() = default;`, generating the function depositing the events of the
+	/// module.
+	pub deposit_event: Option<DepositEvent>,
+	/// The `Error` of the module declared with `decl_error!`, given with `type Error = Error;`.
+	pub error_type: Option<Type>,
+	/// `pub enum Origin { .. }`, the origins of the module, which become variants of the outer
+	/// `Origin`.
+	pub origin: Option<ItemEnum>,
+	/// Functions given with a body, which are inherent functions of the module rather than calls.
+	pub helpers: Vec<ItemFn>,
+	/// `const Name: Type = value;`, the constants of the module, which are part of the metadata.
+	pub constants: Vec<Constant>,
+}
+
+/// A dispatchable function, e.g. `fn transfer(origin, dest: T::AccountId) -> Result;`.
+pub struct Dispatchable {
+	pub attrs: FnAttributes,
+	/// The index of the variant of the call, the one given with `#[codec(index = N)]`, or else the
+	/// position of the function among the dispatchable ones.
+	pub index: u8,
+	pub name: Ident,
+	/// Whether the function takes the origin; otherwise it may only be called by root.
+	pub takes_origin: bool,
+	pub params: Vec<Param>,
+}
+
+/// What is known about a dispatchable from its attributes.
+#[derive(Default)]
+pub struct FnAttributes {
+	pub docs: Vec<LitStr>,
+	/// The index of the call given with `#[codec(index = N)]`.
+	pub index: Option<u8>,
+	/// The weight given with `#[weight = ..]`, `DEFAULT_WEIGHT` otherwise.
+	pub weight: Option<Expr>,
+	/// Whether the function is declared `#[operational]`.
+	pub operational: bool,
+	/// Whether the sender pays the fees, as given with `#[pays_fee = ..]`; they do by default.
+	pub pays_fee: Option<bool>,
+	/// The origin checked before dispatching a function which doesn't take it, given with
+	/// `#[require_root]` or `#[require_none]`; root by default.
+	pub required_origin: Option<RequiredOrigin>,
+}
+
+/// The origin a function which doesn't take it must be called with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RequiredOrigin {
+	Root,
+	None,
+}
+
+impl RequiredOrigin {
+	/// The origin required by a `#[require_root]` or `#[require_none]` attribute; `None` if
+	/// `attr` is neither.
+	fn of(attr: &Attribute) -> Option<Self> {
+		match attr.interpret_meta() {
+			Some(Meta::Word(ref word)) if word == "require_root" => Some(RequiredOrigin::Root),
+			Some(Meta::Word(ref word)) if word == "require_none" => Some(RequiredOrigin::None),
+			_ => None,
+		}
+	}
+}
+
+/// A function of the module called on some event rather than dispatched, e.g.
+/// `fn on_storage_migration(from: u32);`.
+pub struct Hook {
+	pub name: Ident,
+	pub params: Vec<Param>,
+	pub output: Option<Type>,
+}
+
+/// `fn deposit_event<T>() = default;`, or `fn deposit_event() = default;` for a module whose
+/// `Event` is not generic.
+pub struct DepositEvent {
+	pub name: Ident,
+	/// The arguments of the `Event` type of the module, e.g. `T, I`.
+	pub event_generics: Vec<Ident>,
+}
+
+/// A constant of the module, e.g. `const MaximumBlockWeight: Weight = T::MaximumBlockWeight::get();`.
+pub struct Constant {
+	pub docs: Vec<LitStr>,
+	pub name: Ident,
+	pub ty: Type,
+	pub value: Expr,
+}
+
+pub struct Param {
+	pub compact: bool,
+	pub name: Ident,
+	pub ty: Type,
+}
+
+impl Parse for ModuleDefinition {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let scrate = input.parse()?;
+		input.parse::<Token![;]>()?;
+
+		let attrs = input.call(Attribute::parse_outer)?;
+		input.parse::<Token![pub]>()?;
+		input.parse::<Token![struct]>()?;
+		let module_name: Ident = input.parse()?;
+		let generics = input.parse()?;
+		input.parse::<Token![for]>()?;
+		input.parse::<Token![enum]>()?;
+		let call_type = input.parse()?;
+		input.parse::<Token![where]>()?;
+		input.parse::<keyword::origin>()?;
+		input.parse::<Token![:]>()?;
+		let origin_type = input.parse()?;
+
+		let system = if input.peek(Token![where]) {
+			input.parse::<Token![where]>()?;
+			input.parse::<keyword::system>()?;
+			input.parse::<Token![=]>()?;
+			input.parse()?
+		} else {
+			Ident::new("system", module_name.span())
+		};
+
+		let content;
+		braced!(content in input);
+		let mut functions = Vec::new();
+		let mut on_storage_migration = None;
+		let mut on_finalise = None;
+		let mut on_runtime_upgrade = None;
+		let mut offchain_worker = None;
+		let mut deposit_event = None;
+		let mut error_type = None;
+		let mut origin = None;
+		let mut helpers = Vec::new();
+		let mut constants = Vec::<Constant>::new();
+		while !content.is_empty() {
+			if content.fork().parse::<ItemFn>().is_ok() {
+				helpers.push(content.parse()?);
+			} else if Constant::peek(&content) {
+				let constant: Constant = content.parse()?;
+				if constants.iter().any(|c| c.name == constant.name) {
+					return Err(syn::Error::new(constant.name.span(), "duplicate constant"));
+				}
+				constants.push(constant);
+			} else if peek_origin(&content) {
+				let item: ItemEnum = content.parse()?;
+				if item.ident != "Origin" {
+					return Err(syn::Error::new(item.ident.span(), "the origins of a module are declared as `pub enum Origin`"));
+				}
+				match item.vis {
+					Visibility::Public(_) => (),
+					_ => return Err(syn::Error::new(item.ident.span(), "the `Origin` of a module must be `pub`")),
+				}
+				if origin.is_some() {
+					return Err(syn::Error::new(item.ident.span(), "duplicate `Origin`"));
+				}
+				origin = Some(item);
+			} else if content.peek(Token![type]) {
+				let span = content.parse::<Token![type]>()?.span;
+				let name: Ident = content.parse()?;
+				if name != "Error" {
+					return Err(syn::Error::new(name.span(), "unsupported type; modules only declare `type Error = ..;`"));
+				}
+				if error_type.is_some() {
+					return Err(syn::Error::new(span, "duplicate `type Error`"));
+				}
+				content.parse::<Token![=]>()?;
+				error_type = Some(content.parse()?);
+				content.parse::<Token![;]>()?;
+			} else if Hook::peek(&content, "deposit_event") {
+				let def: DepositEvent = content.parse()?;
+				if deposit_event.is_some() {
+					return Err(syn::Error::new(def.name.span(), "duplicate `deposit_event`"));
+				}
+				deposit_event = Some(def);
+			} else if Hook::peek(&content, "on_storage_migration") {
+				let hook: Hook = content.parse()?;
+				if on_storage_migration.is_some() {
+					return Err(syn::Error::new(hook.name.span(), "duplicate `on_storage_migration`"));
+				}
+				if hook.params.len() != 1 || hook.output.is_some() {
+					return Err(syn::Error::new(
+						hook.name.span(),
+						"`on_storage_migration` takes the version to migrate from: `fn on_storage_migration(from: u32);`",
+					));
+				}
+				on_storage_migration = Some(hook);
+			} else if Hook::peek(&content, "on_finalise") {
+				let hook: Hook = content.parse()?;
+				if on_finalise.is_some() {
+					return Err(syn::Error::new(hook.name.span(), "duplicate `on_finalise`"));
+				}
+				if hook.params.len() != 1 || hook.output.is_some() {
+					return Err(syn::Error::new(
+						hook.name.span(),
+						"`on_finalise` takes the number of the block: `fn on_finalise(n: T::BlockNumber);`",
+					));
+				}
+				on_finalise = Some(hook);
+			} else if Hook::peek(&content, "on_runtime_upgrade") {
+				let hook: Hook = content.parse()?;
+				if on_runtime_upgrade.is_some() {
+					return Err(syn::Error::new(hook.name.span(), "duplicate `on_runtime_upgrade`"));
+				}
+				if !hook.params.is_empty() || hook.output.is_none() {
+					return Err(syn::Error::new(
+						hook.name.span(),
+						"`on_runtime_upgrade` takes nothing and returns its weight: `fn on_runtime_upgrade() -> Weight;`",
+					));
+				}
+				on_runtime_upgrade = Some(hook);
+			} else if Hook::peek(&content, "offchain_worker") {
+				let hook: Hook = content.parse()?;
+				if offchain_worker.is_some() {
+					return Err(syn::Error::new(hook.name.span(), "duplicate `offchain_worker`"));
+				}
+				if hook.params.len() != 1 || hook.output.is_some() {
+					return Err(syn::Error::new(
+						hook.name.span(),
+						"`offchain_worker` takes the number of the block: `fn offchain_worker(n: T::BlockNumber);`",
+					));
+				}
+				offchain_worker = Some(hook);
+			} else {
+				let mut function: Dispatchable = content.parse()?;
+				function.index = function.attrs.index.unwrap_or(functions.len() as u8);
+				if let Some(other) = functions.iter().find(|f: &&Dispatchable| f.index == function.index) {
+					return Err(syn::Error::new(
+						function.name.span(),
+						format!("index {} is already the one of `{}`", function.index, other.name),
+					));
+				}
+				functions.push(function);
+			}
+		}
+
+		Ok(ModuleDefinition {
+			scrate,
+			attrs,
+			module_name,
+			generics,
+			call_type,
+			origin_type,
+			system,
+			functions,
+			on_storage_migration,
+			on_finalise,
+			on_runtime_upgrade,
+			offchain_worker,
+			deposit_event,
+			error_type,
+			origin,
+			helpers,
+			constants,
+		})
+	}
+}
+
+/// Whether the next item is an enum, i.e. the `Origin` of the module.
+fn peek_origin(input: ParseStream) -> bool {
+	let fork = input.fork();
+	fork.call(Attribute::parse_outer).is_ok()
+		&& fork.parse::<Visibility>().is_ok()
+		&& fork.peek(Token![enum])
+}
+
+impl Parse for Dispatchable {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let attrs = input.call(Attribute::parse_outer)?;
+		input.parse::<Token![fn]>()?;
+		let name = input.parse()?;
+
+		let content;
+		parenthesized!(content in input);
+		let takes_origin = content.peek(keyword::origin) && !content.peek2(Token![:]);
+		if takes_origin {
+			content.parse::<keyword::origin>()?;
+			if !content.is_empty() {
+				content.parse::<Token![,]>()?;
+			}
+		}
+		let params = content.parse_terminated::<Param, Token![,]>(Param::parse)?.into_iter().collect();
+
+		input.parse::<Token![->]>()?;
+		input.parse::<Type>()?;
+		input.parse::<Token![;]>()?;
+
+		Ok(Dispatchable {
+			attrs: FnAttributes::from_attrs(attrs, takes_origin)?,
+			// Resolved with the other functions of the module.
+			index: 0,
+			name,
+			takes_origin,
+			params,
+		})
+	}
+}
+
+impl Hook {
+	/// Whether the next item is the hook with the given name.
+	fn peek(input: ParseStream, name: &str) -> bool {
+		let fork = input.fork();
+		fork.call(Attribute::parse_outer).is_ok()
+			&& fork.parse::<Token![fn]>().is_ok()
+			&& fork.parse::<Ident>().map_or(false, |ident| ident == name)
+	}
+}
+
+impl Parse for Hook {
+	fn parse(input: ParseStream) -> Result<Self> {
+		for attr in input.call(Attribute::parse_outer)? {
+			if utils::doc_of(&attr).is_none() {
+				return Err(syn::Error::new(attr.span(), "unsupported attribute; hooks only accept doc comments"));
+			}
+		}
+		input.parse::<Token![fn]>()?;
+		let name = input.parse()?;
+
+		let content;
+		parenthesized!(content in input);
+		let params = content.parse_terminated::<Param, Token![,]>(Param::parse)?.into_iter().collect();
+		let output = if input.peek(Token![->]) {
+			input.parse::<Token![->]>()?;
+			Some(input.parse()?)
+		} else {
+			None
+		};
+		input.parse::<Token![;]>()?;
+
+		Ok(Hook { name, params, output })
+	}
+}
+
+impl Constant {
+	/// Whether the next item is a constant.
+	fn peek(input: ParseStream) -> bool {
+		let fork = input.fork();
+		fork.call(Attribute::parse_outer).is_ok() && fork.peek(Token![const])
+	}
+}
+
+impl Parse for Constant {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let mut docs = Vec::new();
+		for attr in input.call(Attribute::parse_outer)? {
+			match utils::doc_of(&attr) {
+				Some(doc) => docs.push(doc?),
+				None => return Err(syn::Error::new(attr.span(), "unsupported attribute; constants only accept doc comments")),
+			}
+		}
+		input.parse::<Token![const]>()?;
+		let name = input.parse()?;
+		input.parse::<Token![:]>()?;
+		let ty = input.parse()?;
+		input.parse::<Token![=]>()?;
+		let value = input.parse()?;
+		input.parse::<Token![;]>()?;
+
+		Ok(Constant { docs, name, ty, value })
+	}
+}
+
+impl Parse for DepositEvent {
+	fn parse(input: ParseStream) -> Result<Self> {
+		for attr in input.call(Attribute::parse_outer)? {
+			if utils::doc_of(&attr).is_none() {
+				return Err(syn::Error::new(attr.span(), "unsupported attribute; `deposit_event` only accepts doc comments"));
+			}
+		}
+		input.parse::<Token![fn]>()?;
+		let name = input.parse()?;
+
+		let mut event_generics = Vec::new();
+		if input.peek(Token![<]) {
+			input.parse::<Token![<]>()?;
+			while !input.peek(Token![>]) {
+				event_generics.push(input.parse()?);
+				if !input.peek(Token![>]) {
+					input.parse::<Token![,]>()?;
+				}
+			}
+			input.parse::<Token![>]>()?;
+		}
+
+		let content;
+		parenthesized!(content in input);
+		if !content.is_empty() {
+			return Err(content.error("`deposit_event` takes no parameters: `fn deposit_event<T>() = default;`"));
+		}
+		input.parse::<Token![=]>()?;
+		input.parse::<keyword::default>()?;
+		input.parse::<Token![;]>()?;
+
+		Ok(DepositEvent { name, event_generics })
+	}
+}
+
+impl FnAttributes {
+	fn from_attrs(attrs: Vec<Attribute>, takes_origin: bool) -> Result<Self> {
+		let mut res = FnAttributes::default();
+		for attr in attrs {
+			if let Some(doc) = utils::doc_of(&attr) {
+				res.docs.push(doc?);
+				continue;
+			}
+			if let Some(index) = utils::codec_index_of(&attr) {
+				if res.index.is_some() {
+					return Err(syn::Error::new(attr.span(), "duplicate `#[codec(index = ..)]` attribute"));
+				}
+				res.index = Some(index?);
+				continue;
+			}
+			if let Some(weight) = utils::weight_of(&attr) {
+				if res.weight.is_some() {
+					return Err(syn::Error::new(attr.span(), "duplicate `#[weight]` attribute"));
+				}
+				res.weight = Some(weight?);
+				continue;
+			}
+			if utils::is_operational(&attr) {
+				if res.operational {
+					return Err(syn::Error::new(attr.span(), "duplicate `#[operational]` attribute"));
+				}
+				res.operational = true;
+				continue;
+			}
+			if let Some(pays_fee) = utils::pays_fee_of(&attr) {
+				if res.pays_fee.is_some() {
+					return Err(syn::Error::new(attr.span(), "duplicate `#[pays_fee]` attribute"));
+				}
+				res.pays_fee = Some(pays_fee?);
+				continue;
+			}
+			if let Some(required_origin) = RequiredOrigin::of(&attr) {
+				if takes_origin {
+					return Err(syn::Error::new(
+						attr.span(),
+						"the origin is checked before dispatch only for functions which don't take it",
+					));
+				}
+				if res.required_origin.is_some() {
+					return Err(syn::Error::new(attr.span(), "the origin may only be required once"));
+				}
+				res.required_origin = Some(required_origin);
+				continue;
+			}
+			return Err(syn::Error::new(
+				attr.span(),
+				"unsupported attribute; dispatchable functions only accept doc comments, `#[codec(index = ..)]`, \
+				`#[weight = ..]`, `#[operational]`, `#[pays_fee = ..]`, `#[require_root]` and `#[require_none]`",
+			));
+		}
+		Ok(res)
+	}
+}
+
+impl Parse for Param {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let attrs = input.call(Attribute::parse_outer)?;
+		let mut compact = false;
+		for attr in attrs {
+			match utils::codec_compact_of(&attr) {
+				Some(Ok(())) if !compact => compact = true,
+				Some(Ok(())) => return Err(syn::Error::new(attr.span(), "duplicate `#[compact]` attribute")),
+				Some(Err(e)) => return Err(e),
+				None => return Err(syn::Error::new(
+					attr.span(),
+					"unsupported attribute; parameters only accept `#[compact]` or `#[codec(compact)]`",
+				)),
+			}
+		}
+
+		let name = input.parse()?;
+		input.parse::<Token![:]>()?;
+		let ty = input.parse()?;
+
+		Ok(Param { compact, name, ty })
+	}
+}
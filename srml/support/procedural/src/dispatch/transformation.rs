@@ -0,0 +1,549 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Code generation of `decl_module!`.
+
+use proc_macro2::{Span, TokenStream};
+use syn::Ident;
+
+use utils::{docs_to_json, lit_str, type_to_string};
+use super::{Dispatchable, Hook, ModuleDefinition, RequiredOrigin};
+
+pub fn expand(def: ModuleDefinition) -> TokenStream {
+	let ModuleDefinition {
+		ref scrate,
+		ref attrs,
+		ref module_name,
+		ref generics,
+		ref call_type,
+		ref origin_type,
+		ref system,
+		ref functions,
+		ref on_storage_migration,
+		ref on_finalise,
+		ref on_runtime_upgrade,
+		ref offchain_worker,
+		ref deposit_event,
+		ref error_type,
+		ref origin,
+		ref helpers,
+		ref constants,
+	} = def;
+	let trait_instance = &generics.trait_instance;
+	let impl_generics = generics.impl_generics();
+	let type_generics = generics.type_generics();
+	let phantom = generics.phantom();
+
+	let mut variants = Vec::new();
+	let mut clone_arms = Vec::new();
+	let mut eq_arms = Vec::new();
+	let mut debug_arms = Vec::new();
+	let mut decode_arms = Vec::new();
+	let mut encode_arms = Vec::new();
+	let mut dispatch_arms = Vec::new();
+	let mut weight_arms = Vec::new();
+	let mut metadata = Vec::new();
+
+	for (position, function) in functions.iter().enumerate() {
+		let name = &function.name;
+		let params = &function.params.iter().map(|p| &p.name).collect::<Vec<_>>();
+		let others = &function.params.iter().map(|p| Ident::new(&format!("_other_{}", p.name), Span::call_site()))
+			.collect::<Vec<_>>();
+		let types = &function.params.iter().map(|p| &p.ty).collect::<Vec<_>>();
+		let index_lit = function.index;
+
+		variants.push(quote! {
+			#[allow(non_camel_case_types)]
+			#name( #(#types),* )
+		});
+
+		clone_arms.push(quote! {
+			#call_type::#name( #(ref #params),* ) => #call_type::#name( #(#params.clone()),* )
+		});
+
+		eq_arms.push(quote! {
+			(&#call_type::#name( #(ref #params),* ), &#call_type::#name( #(ref #others),* )) =>
+				#( #params == #others && )* true
+		});
+
+		debug_arms.push(quote! {
+			#call_type::#name( #(ref #params),* ) =>
+				write!(_f, "{}{:?}", stringify!(#name), ( #(#params.clone(),)* ))
+		});
+
+		let decode_params = function.params.iter().map(|p| {
+			let (name, ty) = (&p.name, &p.ty);
+			if p.compact {
+				quote! {
+					let #name = <#scrate::dispatch::Compact<#ty> as #scrate::dispatch::Decode>::decode(input)?.0;
+				}
+			} else {
+				quote! {
+					let #name = #scrate::dispatch::Decode::decode(input)?;
+				}
+			}
+		});
+		decode_arms.push(quote! {
+			#index_lit => {
+				#( #decode_params )*
+				Some(#call_type::#name( #(#params),* ))
+			}
+		});
+
+		let encode_params = function.params.iter().map(|p| {
+			let name = &p.name;
+			if p.compact {
+				quote! { #scrate::dispatch::Encode::encode_to(&#scrate::dispatch::Compact(*#name), _dest); }
+			} else {
+				quote! { #scrate::dispatch::Encode::encode_to(#name, _dest); }
+			}
+		});
+		encode_arms.push(quote! {
+			#call_type::#name( #(ref #params),* ) => {
+				_dest.push_byte(#index_lit);
+				#( #encode_params )*
+			}
+		});
+
+		// Failures are numbered with the errors of the module; the outer dispatch then sets the
+		// index of the module.
+		let into_dispatch_error = quote! {
+			#scrate::dispatch::IntoDispatchError::into_dispatch_error::<#module_name<#type_generics>>
+		};
+		let call = if function.takes_origin {
+			quote! {
+				<#module_name<#type_generics>>::#name(_origin #(, #params)*).map_err(#into_dispatch_error)
+			}
+		} else {
+			let ensure = match function.attrs.required_origin {
+				Some(RequiredOrigin::None) => quote! { ensure_none },
+				Some(RequiredOrigin::Root) | None => quote! { ensure_root },
+			};
+			quote! {{
+				#system::#ensure(_origin).map_err(#into_dispatch_error)?;
+				<#module_name<#type_generics>>::#name( #(#params),* ).map_err(#into_dispatch_error)
+			}}
+		};
+		dispatch_arms.push(quote! {
+			#call_type::#name( #(#params),* ) => #call
+		});
+
+		let weight = match function.attrs.weight {
+			Some(ref weight) => quote! { #weight },
+			None => quote! { #scrate::dispatch::DEFAULT_WEIGHT },
+		};
+		let class = if function.attrs.operational {
+			quote! { #scrate::dispatch::DispatchClass::Operational }
+		} else {
+			quote! { #scrate::dispatch::DispatchClass::Normal }
+		};
+		let pays_fee = function.attrs.pays_fee.unwrap_or(true);
+		weight_arms.push(quote! {
+			#call_type::#name(..) => #scrate::dispatch::DispatchInfo {
+				weight: #weight,
+				class: #class,
+				pays_fee: #pays_fee,
+			}
+		});
+
+		metadata.push(function_json_metadata(function, position == 0, origin_type));
+	}
+
+	let storage_migration = on_storage_migration.as_ref().map(|hook| {
+		let name = &hook.name;
+		quote! {
+			let from = <Self as #scrate::storage::StorageVersion>::on_chain_storage_version();
+			let to = <Self as #scrate::storage::StorageVersion>::current_storage_version();
+			if from < to {
+				<#module_name<#type_generics>>::#name(from);
+				<Self as #scrate::storage::StorageVersion>::put_on_chain_storage_version(to);
+			}
+		}
+	});
+
+	// The hooks taking the number of a block implement their trait for that type; modules
+	// without the hook implement it for any block number, doing nothing.
+	let block_number_hook = |trait_name: &str, hook: &Option<Hook>| {
+		let trait_name = Ident::new(trait_name, Span::call_site());
+		match *hook {
+			Some(ref hook) => {
+				let name = &hook.name;
+				let ty = &hook.params[0].ty;
+				quote! {
+					impl<#impl_generics> #scrate::runtime_primitives::traits::#trait_name<#ty>
+						for #module_name<#type_generics>
+					{
+						fn #name(n: #ty) {
+							<#module_name<#type_generics>>::#name(n)
+						}
+					}
+				}
+			},
+			None => quote! {
+				impl<#impl_generics, __BlockNumber> #scrate::runtime_primitives::traits::#trait_name<__BlockNumber>
+					for #module_name<#type_generics>
+				{}
+			},
+		}
+	};
+	let on_finalise = block_number_hook("OnFinalise", on_finalise);
+	let offchain_worker = block_number_hook("OffchainWorker", offchain_worker);
+
+	let runtime_upgrade = on_runtime_upgrade.as_ref().map(|hook| {
+		let name = &hook.name;
+		quote! {
+			fn on_runtime_upgrade() -> #scrate::dispatch::Weight {
+				<#module_name<#type_generics>>::#name()
+			}
+		}
+	});
+
+	let deposit_event = deposit_event.as_ref().map(|def| {
+		let name = &def.name;
+		let event_generics = &def.event_generics;
+		let event = if event_generics.is_empty() {
+			quote! { Event }
+		} else {
+			quote! { Event<#(#event_generics),*> }
+		};
+		let trait_type = &generics.trait_type;
+		let module_trait = match generics.instance {
+			Some(ref instance) => {
+				let instance = &instance.name;
+				quote! { #trait_type<#instance> }
+			},
+			None => quote! { #trait_type },
+		};
+		quote! {
+			/// Deposit one of this module's events.
+			fn #name(event: #event) {
+				<#system::Module<#trait_instance>>::deposit_event(<#trait_instance as #module_trait>::Event::from(event).into());
+			}
+		}
+	});
+
+	let module_errors = match *error_type {
+		Some(ref error_type) => quote! {
+			fn error_index(message: &str) -> Option<u8> {
+				<#error_type as #scrate::dispatch::ModuleError>::from_message(message)
+					.map(|error| #scrate::dispatch::ModuleError::index(&error))
+			}
+
+			fn error_json_metadata() -> &'static str {
+				<#error_type as #scrate::dispatch::ModuleError>::error_json_metadata()
+			}
+		},
+		None => quote! {
+			fn error_index(_message: &str) -> Option<u8> {
+				None
+			}
+
+			fn error_json_metadata() -> &'static str {
+				"[ ]"
+			}
+		},
+	};
+
+	let origin = origin.as_ref().map(|origin| quote! {
+		#[derive(Clone, PartialEq, Eq)]
+		#[cfg_attr(feature = "std", derive(Debug))]
+		#origin
+	});
+
+	// The types of the arguments and of the constants, each once, with the way it is written.
+	let mut written_types: Vec<(String, TokenStream)> = Vec::new();
+	let args = functions.iter().flat_map(|function| function.params.iter());
+	for (compact, ty) in args.map(|p| (p.compact, &p.ty)).chain(constants.iter().map(|c| (false, &c.ty))) {
+		let written = if compact { format!("Compact<{}>", type_to_string(ty)) } else { type_to_string(ty) };
+		if !written_types.iter().any(|&(ref w, _)| *w == written) {
+			let ty = if compact { quote! { #scrate::dispatch::Compact<#ty> } } else { quote! { #ty } };
+			written_types.push((written, ty));
+		}
+	}
+	let register_types = written_types.iter().map(|&(ref written, ref ty)| {
+		let written = lit_str(written);
+		quote! { registry.register_written::<#ty>(#written, types); }
+	});
+	let type_bounds = &written_types.iter().map(|&(_, ref ty)| quote! { #ty: #scrate::type_info::TypeInfo })
+		.collect::<Vec<_>>();
+
+	// The calls as they are encoded: the variant of the index of the function, with its arguments.
+	let call_variants = functions.iter().map(|function| {
+		let name = lit_str(&function.name.to_string());
+		let index = function.index;
+		let fields = function.params.iter().map(|p| {
+			let (param_name, ty) = (lit_str(&p.name.to_string()), &p.ty);
+			let ty = if p.compact { quote! { #scrate::dispatch::Compact<#ty> } } else { quote! { #ty } };
+			quote! { #scrate::type_info::field::<#ty>(registry, Some(#param_name)) }
+		});
+		quote! {
+			#scrate::type_info::VariantDef {
+				name: #name.into(),
+				index: #index,
+				fields: <[_]>::into_vec(#scrate::metadata::Box::new([ #(#fields),* ])),
+			}
+		}
+	});
+	let call_name = lit_str(&format!("::{}", call_type));
+
+	let constants = constants.iter().map(|constant| {
+		let (ty, value) = (&constant.ty, &constant.value);
+		let name = lit_str(&constant.name.to_string());
+		let ty_str = lit_str(&type_to_string(ty));
+		let docs = &constant.docs;
+		quote! {
+			#scrate::metadata::ModuleConstantMetadata {
+				name: #name,
+				ty: #ty_str,
+				value: #scrate::dispatch::Encode::encode(&{ let value: #ty = #value; value }),
+				documentation: &[ #(#docs),* ],
+			}
+		}
+	});
+
+	let variants = &variants;
+	let module_name_str = lit_str(&module_name.to_string());
+	let call_type_str = lit_str(&call_type.to_string());
+	let call_metadata = quote! {
+		concat!(
+			r#"{ "name": ""#, #call_type_str, r#"", "functions": {"#,
+			#(#metadata,)*
+			" } }"
+		)
+	};
+
+	quote! {
+		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+		#[derive(Clone, Copy, PartialEq, Eq)]
+		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+		// TODO: switching based on std feature is because of an issue in
+		// serde-derive for when we attempt to derive `Deserialize` on these types,
+		// in a situation where we've imported `srml_support` as another name.
+		#[cfg(feature = "std")]
+		pub struct #module_name<#impl_generics>(::std::marker::PhantomData<#phantom>);
+
+		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+		#[derive(Clone, Copy, PartialEq, Eq)]
+		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+		#[cfg(not(feature = "std"))]
+		pub struct #module_name<#impl_generics>(::core::marker::PhantomData<#phantom>);
+
+		#[cfg(feature = "std")]
+		#(#attrs)*
+		#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+		pub enum #call_type<#impl_generics> {
+			__PhantomItem(::std::marker::PhantomData<#phantom>),
+			__OtherPhantomItem(::std::marker::PhantomData<#phantom>),
+			#(#variants,)*
+		}
+
+		#[cfg(not(feature = "std"))]
+		#(#attrs)*
+		pub enum #call_type<#impl_generics> {
+			__PhantomItem(::core::marker::PhantomData<#phantom>),
+			__OtherPhantomItem(::core::marker::PhantomData<#phantom>),
+			#(#variants,)*
+		}
+
+		// manual implementation of clone/eq/partialeq because using derive erroneously requires
+		// clone/eq/partialeq from T.
+		impl<#impl_generics> #scrate::dispatch::Clone for #call_type<#type_generics> {
+			fn clone(&self) -> Self {
+				match *self {
+					#(#clone_arms,)*
+					_ => unreachable!(),
+				}
+			}
+		}
+
+		impl<#impl_generics> #scrate::dispatch::PartialEq for #call_type<#type_generics> {
+			fn eq(&self, _other: &Self) -> bool {
+				match (self, _other) {
+					#(#eq_arms,)*
+					(&#call_type::__PhantomItem(_), _) | (&#call_type::__OtherPhantomItem(_), _) |
+					(_, &#call_type::__PhantomItem(_)) | (_, &#call_type::__OtherPhantomItem(_)) => unreachable!(),
+					#[allow(unreachable_patterns)]
+					_ => false,
+				}
+			}
+		}
+
+		impl<#impl_generics> #scrate::dispatch::Eq for #call_type<#type_generics> {}
+
+		#[cfg(feature = "std")]
+		impl<#impl_generics> #scrate::dispatch::fmt::Debug for #call_type<#type_generics> {
+			fn fmt(&self, _f: &mut #scrate::dispatch::fmt::Formatter) -> #scrate::dispatch::result::Result<(), #scrate::dispatch::fmt::Error> {
+				match *self {
+					#(#debug_arms,)*
+					_ => unreachable!(),
+				}
+			}
+		}
+
+		impl<#impl_generics> #scrate::dispatch::Decode for #call_type<#type_generics> {
+			fn decode<In: #scrate::dispatch::Input>(input: &mut In) -> Option<Self> {
+				match input.read_byte()? {
+					#(#decode_arms,)*
+					_ => None,
+				}
+			}
+		}
+
+		impl<#impl_generics> #scrate::dispatch::Encode for #call_type<#type_generics> {
+			fn encode_to<W: #scrate::dispatch::Output>(&self, _dest: &mut W) {
+				match *self {
+					#(#encode_arms,)*
+					_ => unreachable!(),
+				}
+			}
+		}
+
+		impl<#impl_generics> #scrate::dispatch::Dispatchable for #call_type<#type_generics> {
+			type Trait = #trait_instance;
+			type Origin = #origin_type;
+			fn dispatch(self, _origin: Self::Origin) -> #scrate::dispatch::DispatchResult {
+				// A failed call leaves the storage as it found it.
+				#scrate::storage::transactional::with_transaction(move || match self {
+					#(#dispatch_arms,)*
+					_ => { panic!("__PhantomItem should never be used.") },
+				})
+			}
+		}
+
+		impl<#impl_generics> #scrate::dispatch::GetDispatchInfo for #call_type<#type_generics> {
+			fn get_dispatch_info(&self) -> #scrate::dispatch::DispatchInfo {
+				match *self {
+					#(#weight_arms,)*
+					_ => unreachable!(),
+				}
+			}
+		}
+
+		#origin
+
+		impl<#impl_generics> #scrate::type_info::TypeInfo for #call_type<#type_generics>
+			where #( #type_bounds ),*
+		{
+			fn type_name() -> #scrate::type_info::String {
+				concat!(module_path!(), #call_name).into()
+			}
+
+			#[allow(unused_variables)]
+			fn type_def(registry: &mut #scrate::type_info::TypeRegistry) -> #scrate::type_info::TypeDef {
+				#scrate::type_info::TypeDef::Enum(
+					<[_]>::into_vec(#scrate::metadata::Box::new([ #(#call_variants),* ]))
+				)
+			}
+		}
+
+		impl<#impl_generics> #scrate::dispatch::Callable for #module_name<#type_generics> {
+			type Call = #call_type<#type_generics>;
+		}
+
+		impl<#impl_generics> #scrate::dispatch::ModuleErrors for #module_name<#type_generics> {
+			#module_errors
+		}
+
+		#on_finalise
+
+		#offchain_worker
+
+		impl<#impl_generics> #scrate::storage::OnStorageMigration for #module_name<#type_generics> {
+			fn on_storage_migration() {
+				#storage_migration
+			}
+		}
+
+		impl<#impl_generics> #scrate::storage::OnRuntimeUpgrade for #module_name<#type_generics> {
+			#runtime_upgrade
+		}
+
+		impl<#impl_generics> #module_name<#type_generics> {
+			pub fn dispatch<D: #scrate::dispatch::Dispatchable<Trait = #trait_instance>>(d: D, origin: D::Origin) -> #scrate::dispatch::DispatchResult {
+				d.dispatch(origin)
+			}
+
+			#deposit_event
+
+			#(#helpers)*
+
+			/// The JSON metadata of the calls of this module: their index, the name, type and
+			/// encoding of their arguments, and their docs.
+			pub fn call_metadata() -> &'static str {
+				#call_metadata
+			}
+
+			/// The constants of this module, with their values in this runtime.
+			pub fn module_constants_metadata() -> #scrate::dispatch::Vec<#scrate::metadata::ModuleConstantMetadata> {
+				#[allow(unused_mut)]
+				let mut constants = #scrate::dispatch::Vec::new();
+				#( constants.push(#constants); )*
+				constants
+			}
+
+			/// Register the types of the arguments of the calls and of the constants of this
+			/// module, noting in `types` the way each of them is written in the metadata.
+			#[allow(unused_variables)]
+			pub fn register_types(
+				registry: &mut #scrate::type_info::TypeRegistry,
+				types: &mut #scrate::dispatch::Vec<(&'static str, #scrate::type_info::String)>,
+			) where #( #type_bounds ),* {
+				#( #register_types )*
+			}
+
+			pub fn json_metadata() -> &'static str {
+				concat!(
+					r#"{ "name": ""#, #module_name_str, r#"", "call": "#,
+					#call_metadata,
+					" }"
+				)
+			}
+		}
+	}
+}
+
+/// The JSON description of a single function, as a list of `concat!` arguments.
+fn function_json_metadata(function: &Dispatchable, first: bool, origin_type: &syn::Type) -> TokenStream {
+	let prefix = lit_str(if first { " " } else { ", " });
+	let index = lit_str(&function.index.to_string());
+	let name = lit_str(&function.name.to_string());
+
+	let mut params = Vec::new();
+	if function.takes_origin {
+		let ty = lit_str(&type_to_string(origin_type));
+		params.push(quote! { r#"{ "name": "origin", "type": ""#, #ty, r#"", "compact": false }"# });
+	}
+	for param in &function.params {
+		let prefix = lit_str(if params.is_empty() { "" } else { ", " });
+		let param_name = lit_str(&param.name.to_string());
+		let ty = lit_str(&if param.compact {
+			format!("Compact<{}>", type_to_string(&param.ty))
+		} else {
+			type_to_string(&param.ty)
+		});
+		let compact = lit_str(if param.compact { "true" } else { "false" });
+		params.push(quote! {
+			#prefix, r#"{ "name": ""#, #param_name, r#"", "type": ""#, #ty, r#"", "compact": "#, #compact, " }"
+		});
+	}
+	let docs = docs_to_json(&function.attrs.docs);
+
+	quote! {
+		#prefix, r#"""#, #index, r#"": { "name": ""#, #name, r#"", "params": [ "#,
+		#(#params,)*
+		r#" ], "description": ["#, #(#docs,)* " ] }"
+	}
+}
@@ -0,0 +1,163 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The `Encode` and `Decode` implementations of the event enums of `decl_event!`, which may give a
+//! variant an index with `#[codec(index = N)]`. As for the `Call` of `decl_module!`, they are
+//! generated here rather than derived, as `parity-codec-derive` only takes the index as a string.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use syn::{Data, DeriveInput, Fields, Ident, Type, WherePredicate};
+use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
+
+use utils;
+
+pub fn impl_event_codec_impl(input: TokenStream) -> TokenStream {
+	let def = parse_macro_input!(input as EventDefinition);
+	match expand(def) {
+		Ok(tokens) => tokens.into(),
+		Err(e) => e.to_compile_error().into(),
+	}
+}
+
+/// The event enum of a `decl_event!`, prefixed with the path to `srml-support`.
+struct EventDefinition {
+	scrate: TokenTree,
+	event: DeriveInput,
+}
+
+impl Parse for EventDefinition {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let scrate = input.parse()?;
+		input.parse::<Token![;]>()?;
+		let event = input.parse()?;
+		Ok(EventDefinition { scrate, event })
+	}
+}
+
+/// The enum without its `#[codec(index = N)]` attributes, and its `Encode` and `Decode`: a
+/// variant is encoded with its index, or else with its position, then with its parameters.
+fn expand(def: EventDefinition) -> Result<TokenStream2> {
+	let EventDefinition { scrate, mut event } = def;
+	let name = event.ident.clone();
+
+	let mut encode_arms = Vec::new();
+	let mut decode_arms = Vec::new();
+	let mut params: Vec<Type> = Vec::new();
+	{
+		let variants = match event.data {
+			Data::Enum(ref mut data) => &mut data.variants,
+			_ => return Err(syn::Error::new(name.span(), "an event is an enum")),
+		};
+		let mut indices: Vec<(u8, Ident)> = Vec::new();
+		for (position, variant) in variants.iter_mut().enumerate() {
+			let mut index = None;
+			let mut attrs = Vec::new();
+			for attr in variant.attrs.drain(..) {
+				match utils::codec_index_of(&attr) {
+					Some(_) if index.is_some() => {
+						return Err(syn::Error::new(attr.span(), "duplicate `#[codec(index = ..)]` attribute"));
+					},
+					Some(res) => index = Some(res?),
+					None => attrs.push(attr),
+				}
+			}
+			variant.attrs = attrs;
+
+			let index = index.unwrap_or(position as u8);
+			if let Some((_, other)) = indices.iter().find(|&&(i, _)| i == index) {
+				return Err(syn::Error::new(
+					variant.ident.span(),
+					format!("index {} is already the one of `{}`", index, other),
+				));
+			}
+			indices.push((index, variant.ident.clone()));
+
+			let variant_name = &variant.ident;
+			match variant.fields {
+				Fields::Unit => {
+					encode_arms.push(quote! { #name::#variant_name => _dest.push_byte(#index) });
+					decode_arms.push(quote! { #index => Some(#name::#variant_name) });
+				},
+				Fields::Unnamed(ref fields) => {
+					let names = (0..fields.unnamed.len())
+						.map(|i| Ident::new(&format!("param{}", i), Span::call_site()))
+						.collect::<Vec<_>>();
+					let encodes = names.iter()
+						.map(|param| quote! { #scrate::dispatch::Encode::encode_to(#param, _dest); })
+						.collect::<Vec<_>>();
+					let decodes = fields.unnamed.iter().map(|_| quote! { #scrate::dispatch::Decode::decode(input)? });
+					encode_arms.push(quote! {
+						#name::#variant_name( #( ref #names ),* ) => {
+							_dest.push_byte(#index);
+							#( #encodes )*
+						}
+					});
+					decode_arms.push(quote! { #index => Some(#name::#variant_name( #( #decodes ),* )) });
+					for field in fields.unnamed.iter() {
+						let ty = &field.ty;
+						if !params.iter().any(|param| quote!(#param).to_string() == quote!(#ty).to_string()) {
+							params.push(field.ty.clone());
+						}
+					}
+				},
+				Fields::Named(ref fields) => {
+					return Err(syn::Error::new(fields.span(), "the parameters of an event are unnamed"));
+				},
+			}
+		}
+	}
+
+	// The parameters, rather than the type parameters, are bound, as the instance of an event is
+	// only ever in `PhantomData`.
+	let bounded = |bound: TokenStream2| {
+		let mut generics = event.generics.clone();
+		if !generics.params.is_empty() {
+			let where_clause = generics.make_where_clause();
+			for param in &params {
+				let predicate: WherePredicate = parse_quote!(#param: #bound);
+				where_clause.predicates.push(predicate);
+			}
+		}
+		generics
+	};
+	let encode_generics = bounded(quote! { #scrate::dispatch::Encode });
+	let decode_generics = bounded(quote! { #scrate::dispatch::Decode });
+	let (encode_impl, ty_generics, encode_where) = encode_generics.split_for_impl();
+	let (decode_impl, _, decode_where) = decode_generics.split_for_impl();
+
+	Ok(quote! {
+		#event
+
+		impl #encode_impl #scrate::dispatch::Encode for #name #ty_generics #encode_where {
+			fn encode_to<W: #scrate::dispatch::Output>(&self, _dest: &mut W) {
+				match *self {
+					#( #encode_arms, )*
+				}
+			}
+		}
+
+		impl #decode_impl #scrate::dispatch::Decode for #name #ty_generics #decode_where {
+			fn decode<In: #scrate::dispatch::Input>(input: &mut In) -> Option<Self> {
+				match input.read_byte()? {
+					#( #decode_arms, )*
+					_ => None,
+				}
+			}
+		}
+	})
+}
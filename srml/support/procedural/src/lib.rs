@@ -0,0 +1,86 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+// tag::description[]
+//! Procedural macros used by the runtime support library.
+// end::description[]
+//!
+//! These are not meant to be used directly; `srml-support` wraps each of them in a `macro_rules`
+//! macro of the same name which, where the expansion refers to `srml-support`, passes its own
+//! `$crate` path along as the first token.
+
+#![recursion_limit = "512"]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+extern crate twox_hash;
+
+mod dispatch;
+mod event;
+mod max_encoded_len;
+mod runtime;
+mod storage;
+mod type_info;
+mod utils;
+
+use proc_macro::TokenStream;
+
+/// Expand a `decl_storage!` declaration. See `srml_support::decl_storage!` for the syntax.
+#[proc_macro]
+pub fn decl_storage(input: TokenStream) -> TokenStream {
+	storage::decl_storage_impl(input)
+}
+
+/// Expand a `decl_module!` declaration. See `srml_support::decl_module!` for the syntax.
+#[proc_macro]
+pub fn decl_module(input: TokenStream) -> TokenStream {
+	dispatch::decl_module_impl(input)
+}
+
+/// Expand the event enum of a `decl_event!` with its `Encode` and `Decode`. See
+/// `srml_support::decl_event!` for the syntax.
+#[proc_macro]
+pub fn impl_event_codec(input: TokenStream) -> TokenStream {
+	event::impl_event_codec_impl(input)
+}
+
+/// Derive `TypeInfo`, describing the type as it is encoded by `#[derive(Encode, Decode)]`. The
+/// type parameters of the type must implement `TypeInfo` too. The crate deriving it must depend on
+/// `srml-support`. Variants are described with the index given with `#[codec(index = N)]`, or
+/// else with their position.
+#[proc_macro_derive(TypeInfo, attributes(codec))]
+pub fn derive_type_info(input: TokenStream) -> TokenStream {
+	type_info::derive_type_info_impl(input)
+}
+
+/// Derive `MaxEncodedLen`, the largest size of the type as it is encoded by
+/// `#[derive(Encode, Decode)]`. The type parameters of the type must implement `MaxEncodedLen`
+/// too. The crate deriving it must depend on `srml-support`.
+#[proc_macro_derive(MaxEncodedLen, attributes(codec))]
+pub fn derive_max_encoded_len(input: TokenStream) -> TokenStream {
+	max_encoded_len::derive_max_encoded_len_impl(input)
+}
+
+/// Expand a `construct_runtime!` declaration. See `srml_support::construct_runtime!` for the
+/// syntax.
+#[proc_macro]
+pub fn construct_runtime(input: TokenStream) -> TokenStream {
+	runtime::construct_runtime_impl(input)
+}
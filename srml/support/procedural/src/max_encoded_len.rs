@@ -0,0 +1,116 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `#[derive(MaxEncodedLen)]`, bounding the size of a struct or an enum as it is encoded by
+//! `#[derive(Encode, Decode)]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use syn::{Data, DeriveInput, Fields, GenericParam, Ident, Type};
+use syn::spanned::Spanned;
+
+use utils;
+
+pub fn derive_max_encoded_len_impl(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match expand(input) {
+		Ok(tokens) => tokens.into(),
+		Err(e) => e.to_compile_error().into(),
+	}
+}
+
+fn expand(mut input: DeriveInput) -> syn::Result<TokenStream2> {
+	let name = &input.ident;
+	let mut compact_types = Vec::new();
+	let len = match input.data {
+		Data::Struct(ref data) => fields_len(&data.fields, &mut compact_types)?,
+		Data::Enum(ref data) => {
+			// The index of the variant, then the fields of the largest one.
+			let mut variants = Vec::new();
+			for variant in data.variants.iter() {
+				variants.push(fields_len(&variant.fields, &mut compact_types)?);
+			}
+			quote! {
+				1 + {
+					let mut max = 0;
+					#(
+						let len = #variants;
+						if len > max {
+							max = len;
+						}
+					)*
+					max
+				}
+			}
+		},
+		Data::Union(ref data) => {
+			return Err(syn::Error::new(data.union_token.span(), "unions aren't encoded"));
+		},
+	};
+
+	for param in input.generics.params.iter_mut() {
+		if let GenericParam::Type(ref mut param) = *param {
+			param.bounds.push(parse_quote!(_srml_support::bounded::MaxEncodedLen));
+		}
+	}
+	// A compact field of a type parameter needs the parameter to have a compact encoding too.
+	for ty in compact_types {
+		input.generics.make_where_clause().predicates.push(
+			parse_quote!(_srml_support::dispatch::Compact<#ty>: _srml_support::bounded::MaxEncodedLen)
+		);
+	}
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let dummy_const = Ident::new(&format!("_IMPL_MAX_ENCODED_LEN_FOR_{}", name), Span::call_site());
+	Ok(quote! {
+		const #dummy_const: () = {
+			#[allow(unknown_lints)]
+			#[allow(rust_2018_idioms)]
+			extern crate srml_support as _srml_support;
+
+			impl #impl_generics _srml_support::bounded::MaxEncodedLen for #name #ty_generics #where_clause {
+				fn max_encoded_len() -> usize {
+					#len
+				}
+			}
+		};
+	})
+}
+
+/// The sum of the maximum encoded lengths of `fields`, the `Compact` one of a field declared
+/// `#[codec(compact)]`, whose type is added to `compact_types`.
+fn fields_len(fields: &Fields, compact_types: &mut Vec<Type>) -> syn::Result<TokenStream2> {
+	let mut lens = Vec::new();
+	for field in fields.iter() {
+		let mut compact = false;
+		for attr in &field.attrs {
+			if let Some(res) = utils::codec_compact_of(attr) {
+				res?;
+				compact = true;
+			}
+		}
+		let ty = &field.ty;
+		if compact {
+			compact_types.push(ty.clone());
+		}
+		lens.push(if compact {
+			quote! { <_srml_support::dispatch::Compact<#ty> as _srml_support::bounded::MaxEncodedLen>::max_encoded_len() }
+		} else {
+			quote! { <#ty as _srml_support::bounded::MaxEncodedLen>::max_encoded_len() }
+		});
+	}
+	Ok(quote! { 0 #( + #lens )* })
+}
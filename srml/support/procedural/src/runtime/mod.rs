@@ -0,0 +1,422 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsing of `construct_runtime!`.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::{Attribute, Ident, LitInt, Type};
+use syn::parse::{Parse, ParseStream, Result};
+
+mod transformation;
+
+mod keyword {
+	custom_keyword!(with);
+	custom_keyword!(DigestItem);
+	custom_keyword!(hooks);
+	custom_keyword!(reversed);
+}
+
+pub fn construct_runtime_impl(input: TokenStream) -> TokenStream {
+	let def = parse_macro_input!(input as RuntimeDefinition);
+	transformation::expand(def).into()
+}
+
+/// The whole `construct_runtime!` invocation.
+pub struct RuntimeDefinition {
+	/// Attributes of the runtime, applied to its struct.
+	pub attrs: Vec<Attribute>,
+	pub name: Ident,
+	/// The outer log, given with `with Log(InternalLog: DigestItem<..>)`.
+	pub log: Option<OuterLog>,
+	pub modules: Vec<ModuleDeclaration>,
+	/// The index of each module of `modules` in the outer `Call` and `Event`.
+	pub indices: Vec<u8>,
+	/// The order of the hooks, given with `with hooks(..)` or `with hooks reversed` after the
+	/// modules; the declaration order otherwise.
+	pub hooks: HooksOrder,
+}
+
+/// The order in which the hooks of the modules, e.g. `on_finalise`, are called.
+pub enum HooksOrder {
+	/// The order the modules are declared in.
+	Declared,
+	/// The reverse of the order the modules are declared in.
+	Reversed,
+	/// The given order, which names every module but `system` with a `Module` once.
+	Explicit(Vec<Ident>),
+}
+
+/// `Log(InternalLog: DigestItem<SessionKey>)`.
+pub struct OuterLog {
+	pub name: Ident,
+	pub internal: Ident,
+	/// The type arguments of `DigestItem`.
+	pub digest_args: Vec<Type>,
+}
+
+/// A module of the runtime, e.g. `Balances: balances::{Module, Call, Storage, Config, Event<T>}`.
+pub struct ModuleDeclaration {
+	/// The name of the module in the runtime, e.g. `Balances`.
+	pub name: Ident,
+	/// The Rust module declaring it, e.g. `balances`.
+	pub module: Ident,
+	pub parts: Vec<ModulePart>,
+	/// The index given with `= 5` after the parts, if any.
+	pub index: Option<LitInt>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PartKind {
+	Module,
+	Call,
+	Storage,
+	Config,
+	Event,
+	Origin,
+	Log,
+}
+
+/// Something a module brings to the runtime, e.g. `Call` or `Event<T>`.
+pub struct ModulePart {
+	pub kind: PartKind,
+	pub name: Ident,
+	/// Whether the part is generic over the runtime, e.g. `Event<T>`.
+	pub generic: bool,
+	/// The system log items of a `Log` part, e.g. `AuthoritiesChange`.
+	pub log_items: Vec<Ident>,
+}
+
+impl ModuleDeclaration {
+	/// The part of the given kind, if the module declares it.
+	pub fn part(&self, kind: PartKind) -> Option<&ModulePart> {
+		self.parts.iter().find(|part| part.kind == kind)
+	}
+
+	/// Whether this is the `system` module, whose `Event` and `Origin` are always part of the
+	/// outer ones.
+	pub fn is_system(&self) -> bool {
+		self.module == "system"
+	}
+
+	/// Whether the hooks of the module are called, that is whether it is in `AllModules`.
+	pub fn has_hooks(&self) -> bool {
+		self.part(PartKind::Module).is_some() && !self.is_system()
+	}
+}
+
+impl RuntimeDefinition {
+	/// The names of the modules whose hooks are called, in the order they are called in.
+	pub fn hooked_modules(&self) -> Vec<&Ident> {
+		let declared = self.modules.iter().filter(|m| m.has_hooks()).map(|m| &m.name);
+		match self.hooks {
+			HooksOrder::Declared => declared.collect(),
+			HooksOrder::Reversed => declared.rev().collect(),
+			HooksOrder::Explicit(ref order) => order.iter().collect(),
+		}
+	}
+}
+
+impl Parse for RuntimeDefinition {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let attrs = input.call(Attribute::parse_outer)?;
+		input.parse::<Token![pub]>()?;
+		input.parse::<Token![enum]>()?;
+		let name = input.parse()?;
+
+		let log = if input.peek(keyword::with) {
+			input.parse::<keyword::with>()?;
+			Some(input.parse()?)
+		} else {
+			None
+		};
+
+		let content;
+		braced!(content in input);
+		let modules: Vec<ModuleDeclaration> = content
+			.parse_terminated::<ModuleDeclaration, Token![,]>(ModuleDeclaration::parse)?
+			.into_iter()
+			.collect();
+
+		let indices = resolve_indices(&modules)?;
+		for module in &modules {
+			if let Some(part) = module.part(PartKind::Log) {
+				if log.is_none() {
+					return Err(syn::Error::new(
+						part.name.span(),
+						"a module with a `Log` needs the outer log: `pub enum Runtime with Log(InternalLog: DigestItem<..>)`",
+					));
+				}
+			}
+			if module.part(PartKind::Module).is_none() {
+				for kind in &[PartKind::Call, PartKind::Storage] {
+					if let Some(part) = module.part(*kind) {
+						return Err(syn::Error::new(
+							part.name.span(),
+							"the `Call` and `Storage` of a module are reached through its `Module`, which must be declared too",
+						));
+					}
+				}
+			}
+		}
+
+		let hooks = if input.peek(keyword::with) {
+			input.parse::<keyword::with>()?;
+			input.parse::<keyword::hooks>()?;
+			if input.peek(keyword::reversed) {
+				input.parse::<keyword::reversed>()?;
+				HooksOrder::Reversed
+			} else {
+				let content;
+				let paren = parenthesized!(content in input);
+				let order: Vec<Ident> = content
+					.parse_terminated::<Ident, Token![,]>(Ident::parse)?
+					.into_iter()
+					.collect();
+				check_hooks_order(&modules, &order, paren.span)?;
+				HooksOrder::Explicit(order)
+			}
+		} else {
+			HooksOrder::Declared
+		};
+
+		Ok(RuntimeDefinition { attrs, name, log, modules, indices, hooks })
+	}
+}
+
+/// The index of each module: the given one, else the one after the index of the module before it,
+/// the first module being at 0. No two modules may have the same index.
+fn resolve_indices(modules: &[ModuleDeclaration]) -> Result<Vec<u8>> {
+	let mut indices: Vec<u8> = Vec::new();
+	let mut next = 0u64;
+	for module in modules {
+		let (index, span) = match module.index {
+			Some(ref index) => (index.value(), index.span()),
+			None => (next, module.name.span()),
+		};
+		if index > u64::from(u8::max_value()) {
+			return Err(syn::Error::new(span, "the index of a module must fit in a byte"));
+		}
+		let index = index as u8;
+		if let Some(i) = indices.iter().position(|i| *i == index) {
+			return Err(syn::Error::new(
+				span,
+				format!("index {} is already the one of `{}`", index, modules[i].name),
+			));
+		}
+		indices.push(index);
+		next = u64::from(index) + 1;
+	}
+	Ok(indices)
+}
+
+/// Check that `order` names every module with hooks exactly once.
+fn check_hooks_order(modules: &[ModuleDeclaration], order: &[Ident], span: Span) -> Result<()> {
+	for (i, name) in order.iter().enumerate() {
+		if !modules.iter().any(|m| m.has_hooks() && m.name == *name) {
+			return Err(syn::Error::new(
+				name.span(),
+				"not a module with hooks; expected a module of this runtime with a `Module`, other than `system`",
+			));
+		}
+		if order[..i].contains(name) {
+			return Err(syn::Error::new(name.span(), format!("duplicate `{}`", name)));
+		}
+	}
+	if let Some(missing) = modules.iter().find(|m| m.has_hooks() && !order.contains(&m.name)) {
+		return Err(syn::Error::new(span, format!("missing `{}`; the order must name every module with hooks", missing.name)));
+	}
+	Ok(())
+}
+
+impl Parse for OuterLog {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let name = input.parse()?;
+		let content;
+		parenthesized!(content in input);
+		let internal = content.parse()?;
+		content.parse::<Token![:]>()?;
+		content.parse::<keyword::DigestItem>()?;
+		content.parse::<Token![<]>()?;
+		let mut digest_args = Vec::new();
+		while !content.peek(Token![>]) {
+			digest_args.push(content.parse()?);
+			if !content.peek(Token![>]) {
+				content.parse::<Token![,]>()?;
+			}
+		}
+		content.parse::<Token![>]>()?;
+
+		Ok(OuterLog { name, internal, digest_args })
+	}
+}
+
+impl Parse for ModuleDeclaration {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let name = input.parse()?;
+		input.parse::<Token![:]>()?;
+		let module = input.parse()?;
+		input.parse::<Token![::]>()?;
+
+		let content;
+		braced!(content in input);
+		let parts: Vec<ModulePart> = content
+			.parse_terminated::<ModulePart, Token![,]>(ModulePart::parse)?
+			.into_iter()
+			.collect();
+
+		for (i, part) in parts.iter().enumerate() {
+			if parts[..i].iter().any(|p| p.kind == part.kind) {
+				return Err(syn::Error::new(part.name.span(), format!("duplicate `{}`", part.name)));
+			}
+		}
+
+		let index = if input.peek(Token![=]) {
+			input.parse::<Token![=]>()?;
+			Some(input.parse()?)
+		} else {
+			None
+		};
+
+		Ok(ModuleDeclaration { name, module, parts, index })
+	}
+}
+
+impl Parse for ModulePart {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let name: Ident = input.parse()?;
+		let kind = match name.to_string().as_str() {
+			"Module" => PartKind::Module,
+			"Call" => PartKind::Call,
+			"Storage" => PartKind::Storage,
+			"Config" => PartKind::Config,
+			"Event" => PartKind::Event,
+			"Origin" => PartKind::Origin,
+			"Log" => PartKind::Log,
+			_ => return Err(syn::Error::new(
+				name.span(),
+				"unknown part; expected `Module`, `Call`, `Storage`, `Config`, `Event`, `Origin` or `Log`",
+			)),
+		};
+
+		let mut generic = false;
+		if (kind == PartKind::Event || kind == PartKind::Origin) && input.peek(Token![<]) {
+			input.parse::<Token![<]>()?;
+			let param: Ident = input.parse()?;
+			if param != "T" {
+				return Err(syn::Error::new(param.span(), "expected `T`, standing for the runtime"));
+			}
+			input.parse::<Token![>]>()?;
+			generic = true;
+		}
+
+		let mut log_items = Vec::new();
+		if kind == PartKind::Log {
+			let content;
+			parenthesized!(content in input);
+			log_items = content.parse_terminated::<Ident, Token![,]>(Ident::parse)?.into_iter().collect();
+		}
+
+		Ok(ModulePart { kind, name, generic, log_items })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn modules_and_parts_are_parsed() {
+		let def: RuntimeDefinition = syn::parse_str(r#"
+			pub enum Runtime with Log(InternalLog: DigestItem<SessionKey>) {
+				System: system::{Module, Storage, Config, Event},
+				Consensus: consensus::{Module, Call, Storage, Config, Log(AuthoritiesChange)},
+				Balances: balances::{Module, Call, Storage, Config, Event<T>},
+			}
+		"#).unwrap();
+
+		assert_eq!(def.name, "Runtime");
+		let log = def.log.unwrap();
+		assert_eq!(log.internal, "InternalLog");
+		assert_eq!(log.digest_args.len(), 1);
+
+		assert_eq!(def.modules.len(), 3);
+		assert!(def.modules[0].is_system());
+		assert!(def.modules[0].part(PartKind::Call).is_none());
+		assert_eq!(def.modules[1].part(PartKind::Log).unwrap().log_items[0], "AuthoritiesChange");
+		assert_eq!(def.modules[2].module, "balances");
+		assert!(def.modules[2].part(PartKind::Event).unwrap().generic);
+		assert_eq!(def.indices, vec![0, 1, 2]);
+	}
+
+	#[test]
+	fn module_indices_are_resolved() {
+		let indices = |modules: &str| -> Result<Vec<u8>> {
+			let def: RuntimeDefinition = syn::parse_str(&format!("pub enum Runtime {{ {} }}", modules))?;
+			Ok(def.indices)
+		};
+
+		assert_eq!(
+			indices("System: system::{Module}, Balances: balances::{Module, Call} = 5, Staking: staking::{Module}").unwrap(),
+			vec![0, 5, 6],
+		);
+		assert_eq!(
+			indices("System: system::{Module} = 3, Balances: balances::{Module} = 1, Staking: staking::{Module}").unwrap(),
+			vec![3, 1, 2],
+		);
+
+		assert!(indices("System: system::{Module} = 1, Balances: balances::{Module} = 1").is_err());
+		assert!(indices("System: system::{Module}, Balances: balances::{Module} = 0").is_err());
+		assert!(indices("System: system::{Module} = 2, Balances: balances::{Module} = 1, Staking: staking::{Module}").is_err());
+		assert!(indices("System: system::{Module} = 256").is_err());
+		assert!(indices("System: system::{Module} = 255, Balances: balances::{Module}").is_err());
+	}
+
+	#[test]
+	fn invalid_declarations_are_rejected() {
+		let parse = |s: &str| syn::parse_str::<RuntimeDefinition>(s).map(|_| ());
+		assert!(parse("pub enum Runtime { Consensus: consensus::{Module, Log(AuthoritiesChange)} }").is_err());
+		assert!(parse("pub enum Runtime { Balances: balances::{Call} }").is_err());
+		assert!(parse("pub enum Runtime { Balances: balances::{Module, Module} }").is_err());
+		assert!(parse("pub enum Runtime { Balances: balances::{Module, Events} }").is_err());
+		assert!(parse("pub enum Runtime { Balances: balances::{Module, Event<Runtime>} }").is_err());
+	}
+
+	#[test]
+	fn hooks_order_is_resolved() {
+		let order = |hooks: &str| -> Result<Vec<String>> {
+			let def: RuntimeDefinition = syn::parse_str(&format!(r#"
+				pub enum Runtime {{
+					System: system::{{Module, Storage}},
+					Consensus: consensus::{{Module, Call}},
+					Balances: balances::{{Module, Call}},
+					Motions: council_motions::{{Origin}},
+					Staking: staking::{{Module, Call}},
+				}} {}
+			"#, hooks))?;
+			Ok(def.hooked_modules().iter().map(|m| m.to_string()).collect())
+		};
+
+		assert_eq!(order("").unwrap(), vec!["Consensus", "Balances", "Staking"]);
+		assert_eq!(order("with hooks reversed").unwrap(), vec!["Staking", "Balances", "Consensus"]);
+		assert_eq!(order("with hooks(Balances, Staking, Consensus)").unwrap(), vec!["Balances", "Staking", "Consensus"]);
+
+		assert!(order("with hooks(Balances, Staking)").is_err());
+		assert!(order("with hooks(Balances, Staking, Consensus, Balances)").is_err());
+		assert!(order("with hooks(System, Balances, Staking, Consensus)").is_err());
+		assert!(order("with hooks(Motions, Balances, Staking, Consensus)").is_err());
+	}
+}
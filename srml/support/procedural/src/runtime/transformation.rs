@@ -0,0 +1,136 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Code generation of `construct_runtime!`, in terms of the `impl_outer_*` macros.
+
+use proc_macro2::{Literal, TokenStream};
+use syn::Ident;
+
+use utils::lit_str;
+use super::{PartKind, RuntimeDefinition};
+
+pub fn expand(def: RuntimeDefinition) -> TokenStream {
+	let all_modules = &def.hooked_modules();
+	let RuntimeDefinition { ref attrs, ref name, ref log, ref modules, ref indices, .. } = def;
+
+	let mut aliases = Vec::new();
+	let mut metadata = Vec::new();
+	let mut events = Vec::new();
+	let mut origins = Vec::new();
+	let mut logs = Vec::new();
+	let mut calls = Vec::new();
+	let mut configs = Vec::new();
+	let mut module_indices = Vec::new();
+	let mut system_event = None;
+
+	for (declaration, index) in modules.iter().zip(indices) {
+		let alias = &declaration.name;
+		let module = &declaration.module;
+		let index = Literal::u8_unsuffixed(*index);
+
+		if declaration.part(PartKind::Module).is_some() {
+			let doc = lit_str(&format!(" The `{}` module of this runtime.", module));
+			aliases.push(quote! {
+				#[doc = #doc]
+				pub type #alias = #module::Module<#name>;
+			});
+			metadata.push(match declaration.part(PartKind::Storage) {
+				Some(_) => quote! { #module::Module with Storage, },
+				None => quote! { #module::Module, },
+			});
+		}
+		module_indices.push(quote! { #alias = #index });
+		if declaration.part(PartKind::Call).is_some() {
+			calls.push(quote! { #alias = #index });
+		}
+		if let Some(part) = declaration.part(PartKind::Config) {
+			let config = Ident::new(&format!("{}Config", alias), part.name.span());
+			configs.push(quote! { #config => #module });
+		}
+		if declaration.is_system() {
+			system_event = Some(quote! { where system = #index });
+		} else {
+			if let Some(part) = declaration.part(PartKind::Event) {
+				events.push(if part.generic { quote! { #module<T> = #index } } else { quote! { #module = #index } });
+			}
+			if let Some(part) = declaration.part(PartKind::Origin) {
+				origins.push(if part.generic { quote! { #module<T> } } else { quote! { #module } });
+			}
+		}
+		if let Some(part) = declaration.part(PartKind::Log) {
+			let items = &part.log_items;
+			logs.push(quote! { #module(#(#items),*) });
+		}
+	}
+
+	let log = log.as_ref().map(|log| {
+		let log_name = &log.name;
+		let internal = &log.internal;
+		let digest_args = &log.digest_args;
+		quote! {
+			impl_outer_log! {
+				pub enum #log_name(#internal: DigestItem<#(#digest_args),*>) for #name {
+					#(#logs),*
+				}
+			}
+			__impl_outer_log_type_info!(#log_name: DigestItem<#(#digest_args),*>);
+		}
+	});
+
+	quote! {
+		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+		#[derive(Clone, Copy, PartialEq, Eq)]
+		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+		#(#attrs)*
+		pub struct #name;
+
+		#(#aliases)*
+
+		/// All modules of this runtime but `system`, in the order their hooks are called in.
+		pub type AllModules = ( #(#all_modules,)* );
+
+		impl_outer_event! {
+			pub enum Event for #name #system_event {
+				#(#events,)*
+			}
+		}
+
+		#log
+
+		impl_outer_origin! {
+			pub enum Origin for #name {
+				#(#origins),*
+			}
+		}
+
+		impl_outer_dispatch! {
+			pub enum Call where origin: Origin {
+				#(#calls,)*
+			}
+		}
+
+		impl_outer_config! {
+			pub struct GenesisConfig for #name {
+				#(#configs,)*
+			}
+		}
+
+		impl_json_metadata!(
+			for #name with indices ( #(#module_indices),* ) and hooks ( #(#all_modules),* ) and modules
+				#(#metadata)*
+		);
+	}
+}
@@ -0,0 +1,546 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsing of `decl_storage!`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
+use syn::{Attribute, Expr, Ident, LitByteStr, LitInt, LitStr, Type, Visibility};
+use syn::parse::{Parse, ParseStream, Result};
+use syn::spanned::Spanned;
+use utils::{self, ModuleGenerics};
+
+mod transformation;
+
+mod keyword {
+	custom_keyword!(get);
+	custom_keyword!(map);
+	custom_keyword!(linked_map);
+	custom_keyword!(counted_map);
+	custom_keyword!(double_map);
+	custom_keyword!(nmap);
+	custom_keyword!(child);
+	custom_keyword!(deque);
+	custom_keyword!(ordered_set);
+	custom_keyword!(blob);
+	custom_keyword!(hasher);
+	custom_keyword!(default);
+	custom_keyword!(required);
+	custom_keyword!(version);
+	custom_keyword!(bounded);
+	custom_keyword!(config);
+	custom_keyword!(build);
+	custom_keyword!(add_extra_genesis);
+}
+
+pub fn decl_storage_impl(input: TokenStream) -> TokenStream {
+	let def = parse_macro_input!(input as StorageDefinition);
+	if let Err(err) = transformation::check_keys(&def) {
+		return err.to_compile_error().into();
+	}
+	if let Err(err) = transformation::check_bounded(&def) {
+		return err.to_compile_error().into();
+	}
+	transformation::expand(def).into()
+}
+
+/// The whole `decl_storage!` invocation, prefixed with the path to `srml-support`.
+pub struct StorageDefinition {
+	pub scrate: TokenTree,
+	pub visibility: Visibility,
+	pub store_trait: Ident,
+	pub module_name: Ident,
+	pub generics: ModuleGenerics,
+	pub crate_name: Ident,
+	/// The version of the storage layout given with `version(..)`, zero if not given.
+	pub version: u32,
+	/// Whether the values of the items must have a `MaxEncodedLen`, given with `bounded` after the
+	/// version.
+	pub bounded: bool,
+	pub items: Vec<StorageItem>,
+	/// The `add_extra_genesis { .. }` section following the items.
+	pub extra_genesis: Option<ExtraGenesis>,
+}
+
+/// Additional genesis configuration of the module, e.g.
+/// `add_extra_genesis { config(authorities): Vec<T::AccountId>; build(|storage, config| { .. }); }`.
+pub struct ExtraGenesis {
+	/// Fields of the genesis config that are not kept in a storage item of their own.
+	pub fields: Vec<ExtraGenesisField>,
+	/// A closure given the genesis storage and the genesis config, run after the items are built.
+	pub build: Option<Expr>,
+}
+
+/// A field of the genesis config declared in `add_extra_genesis` with `config(name): Type;`.
+pub struct ExtraGenesisField {
+	pub docs: Vec<LitStr>,
+	/// The `#[serde(..)]` attributes put on the field of the genesis config.
+	pub serde_attrs: Vec<Attribute>,
+	pub name: Ident,
+	pub ty: Type,
+}
+
+/// A single storage item, e.g. `pub Foo get(foo): default map [u32 => u64];`.
+pub struct StorageItem {
+	pub docs: Vec<LitStr>,
+	pub compact: bool,
+	/// Whether the item is a `blob`: a `Vec<u8>` value kept as is rather than encoded.
+	pub blob: bool,
+	/// The `#[serde(..)]` attributes put on the field of the genesis config given with `config(..)`.
+	pub serde_attrs: Vec<Attribute>,
+	/// Whether the key of the item is listed by `WhitelistedStorageKeys`, given with
+	/// `#[whitelisted]`.
+	pub whitelisted: bool,
+	pub visibility: Visibility,
+	pub name: Ident,
+	pub getter: Option<Ident>,
+	/// The field of the genesis config the item is initialised from, given with `config(name)`,
+	/// or with `config()` to name it after the getter.
+	pub config: Option<Ident>,
+	/// The closure computing the genesis value of the item, given with `build(..)`.
+	pub build: Option<Expr>,
+	pub modifier: Modifier,
+	/// The type providing the value of a missing `default` item, given with
+	/// `default(Provider)`; `T::default()` is used otherwise.
+	pub on_empty: Option<Type>,
+	pub kind: StorageKind,
+}
+
+/// How a missing value is treated when read.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+	/// No modifier: reads return `Option<T>`.
+	Optional,
+	/// `default`: reads return `T::default()` if absent, or the value of the provider given with
+	/// `default(Provider)`.
+	Default,
+	/// `required`: reads panic if absent.
+	Required,
+}
+
+/// How the keys of a map are turned into the suffix of their storage key, chosen with
+/// `hasher(..)`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Hasher {
+	Blake2_256,
+	Twox128,
+	Twox256,
+	Identity,
+}
+
+impl Hasher {
+	/// The name of the hasher, as written in `hasher(..)` and reported in the metadata.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			Hasher::Blake2_256 => "blake2_256",
+			Hasher::Twox128 => "twox_128",
+			Hasher::Twox256 => "twox_256",
+			Hasher::Identity => "identity",
+		}
+	}
+}
+
+impl Parse for Hasher {
+	fn parse(input: ParseStream) -> Result<Self> {
+		input.parse::<keyword::hasher>()?;
+		let content;
+		parenthesized!(content in input);
+		let name: Ident = content.parse()?;
+		match &name.to_string()[..] {
+			"blake2_256" => Ok(Hasher::Blake2_256),
+			"twox_128" => Ok(Hasher::Twox128),
+			"twox_256" => Ok(Hasher::Twox256),
+			"identity" => Ok(Hasher::Identity),
+			_ => Err(syn::Error::new(
+				name.span(),
+				"unknown hasher; expected `blake2_256`, `twox_128`, `twox_256` or `identity`",
+			)),
+		}
+	}
+}
+
+/// The keyword a map is declared with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MapFlavor {
+	/// `map`.
+	Plain,
+	/// `linked_map`: the keys are also kept in a linked list to enumerate the entries.
+	Linked,
+	/// `counted_map`: the number of entries is also kept.
+	Counted,
+}
+
+pub enum StorageKind {
+	Value(Type),
+	/// `map [K => V]`, `linked_map [K => V]` or `counted_map [K => V]`, optionally with
+	/// `hasher(..)` before the brackets.
+	Map { key: Type, value: Type, flavor: MapFlavor, hasher: Option<Hasher> },
+	/// `double_map K1, K2 => V`.
+	DoubleMap { key1: Type, key2: Type, value: Type },
+	/// `nmap K1, K2, .. => V`, with two to four keys.
+	NMap { keys: Vec<Type>, value: Type },
+	/// `child(b":child:name") map [K => V]`, kept in the child storage of the given key.
+	ChildMap { child: LitByteStr, key: Type, value: Type },
+	/// `deque [V; capacity]`, a queue holding at most `capacity` items.
+	Deque { value: Type, capacity: Expr },
+	/// `ordered_set [V]`, a sorted set of items.
+	OrderedSet { value: Type },
+}
+
+impl StorageKind {
+	/// The type of the stored value.
+	pub fn value_type(&self) -> &Type {
+		match *self {
+			StorageKind::Value(ref ty) => ty,
+			StorageKind::Map { ref value, .. }
+				| StorageKind::DoubleMap { ref value, .. }
+				| StorageKind::NMap { ref value, .. }
+				| StorageKind::ChildMap { ref value, .. }
+				| StorageKind::Deque { ref value, .. }
+				| StorageKind::OrderedSet { ref value } => value,
+		}
+	}
+}
+
+impl Parse for StorageDefinition {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let scrate = input.parse()?;
+		input.parse::<Token![;]>()?;
+
+		let visibility = input.parse()?;
+		input.parse::<Token![trait]>()?;
+		let store_trait = input.parse()?;
+		input.parse::<Token![for]>()?;
+		let module_name = input.parse()?;
+		let mut generics: ModuleGenerics = input.parse()?;
+		generics.where_clause = input.parse()?;
+		input.parse::<Token![as]>()?;
+		let crate_name = input.parse()?;
+
+		let version = if input.peek(keyword::version) {
+			input.parse::<keyword::version>()?;
+			let content;
+			parenthesized!(content in input);
+			content.parse::<LitInt>()?.value() as u32
+		} else {
+			0
+		};
+
+		let bounded = input.peek(keyword::bounded);
+		if bounded {
+			input.parse::<keyword::bounded>()?;
+		}
+
+		let content;
+		braced!(content in input);
+		let mut items = Vec::new();
+		while !content.is_empty() {
+			items.push(content.parse()?);
+		}
+
+		let extra_genesis = if input.peek(keyword::add_extra_genesis) {
+			Some(input.parse()?)
+		} else {
+			None
+		};
+
+		Ok(StorageDefinition {
+			scrate,
+			visibility,
+			store_trait,
+			module_name,
+			generics,
+			crate_name,
+			version,
+			bounded,
+			items,
+			extra_genesis,
+		})
+	}
+}
+
+impl Parse for ExtraGenesis {
+	fn parse(input: ParseStream) -> Result<Self> {
+		input.parse::<keyword::add_extra_genesis>()?;
+		let content;
+		braced!(content in input);
+		let mut fields = Vec::new();
+		let mut build = None;
+		while !content.is_empty() {
+			let attrs = content.call(Attribute::parse_outer)?;
+			if content.peek(keyword::build) {
+				let span = content.parse::<keyword::build>()?.span;
+				if build.is_some() {
+					return Err(syn::Error::new(span, "duplicate `build(..)` in `add_extra_genesis`"));
+				}
+				if let Some(attr) = attrs.first() {
+					return Err(syn::Error::new(attr.span(), "`build(..)` does not take attributes"));
+				}
+				let closure;
+				parenthesized!(closure in content);
+				build = Some(closure.parse()?);
+			} else {
+				content.parse::<keyword::config>()?;
+				let name;
+				parenthesized!(name in content);
+				let name = name.parse()?;
+				content.parse::<Token![:]>()?;
+				let ty = content.parse()?;
+				let mut docs = Vec::new();
+				let mut serde_attrs = Vec::new();
+				for attr in attrs {
+					match utils::doc_of(&attr) {
+						Some(doc) => docs.push(doc?),
+						None if utils::is_serde(&attr) => serde_attrs.push(attr),
+						None => return Err(syn::Error::new(
+							attr.span(),
+							"unsupported attribute; genesis fields only accept doc comments and `#[serde(..)]`",
+						)),
+					}
+				}
+				fields.push(ExtraGenesisField { docs, serde_attrs, name, ty });
+			}
+			content.parse::<Token![;]>()?;
+		}
+		Ok(ExtraGenesis { fields, build })
+	}
+}
+
+impl Parse for StorageItem {
+	fn parse(input: ParseStream) -> Result<Self> {
+		let attrs = input.call(Attribute::parse_outer)?;
+		let visibility = input.parse()?;
+		let name: Ident = input.parse()?;
+
+		let getter: Option<Ident> = if input.peek(keyword::get) {
+			input.parse::<keyword::get>()?;
+			let content;
+			parenthesized!(content in input);
+			Some(content.parse()?)
+		} else {
+			None
+		};
+
+		let mut config = None;
+		let mut build = None;
+		loop {
+			if input.peek(keyword::config) {
+				let span = input.parse::<keyword::config>()?.span;
+				let content;
+				parenthesized!(content in input);
+				if config.is_some() {
+					return Err(syn::Error::new(span, "duplicate `config(..)`"));
+				}
+				let field: Option<Ident> = if content.is_empty() { None } else { Some(content.parse()?) };
+				match (field, &getter) {
+					(Some(field), _) => config = Some(field),
+					(None, &Some(ref getter)) => config = Some(getter.clone()),
+					(None, &None) => return Err(syn::Error::new(
+						span,
+						"`config()` needs a `get(..)` to name the field of the genesis config; use `config(name)` otherwise",
+					)),
+				}
+			} else if input.peek(keyword::build) {
+				let span = input.parse::<keyword::build>()?.span;
+				let content;
+				parenthesized!(content in input);
+				if build.is_some() {
+					return Err(syn::Error::new(span, "duplicate `build(..)`"));
+				}
+				build = Some(content.parse()?);
+			} else {
+				break;
+			}
+		}
+		input.parse::<Token![:]>()?;
+
+		let mut on_empty = None;
+		let modifier = if input.peek(keyword::default) {
+			input.parse::<keyword::default>()?;
+			// A parenthesized type followed by `;` is the value type, e.g. `default (u32, u64);`.
+			if input.peek(syn::token::Paren) && !input.peek2(Token![;]) {
+				let content;
+				parenthesized!(content in input);
+				on_empty = Some(content.parse()?);
+			}
+			Modifier::Default
+		} else if input.peek(keyword::required) {
+			input.parse::<keyword::required>()?;
+			Modifier::Required
+		} else {
+			Modifier::Optional
+		};
+
+		let blob = input.peek(keyword::blob) && input.peek2(Token![;]);
+		let map = input.peek(keyword::map) || input.peek(keyword::linked_map) || input.peek(keyword::counted_map);
+		let kind = if blob {
+			input.parse::<keyword::blob>()?;
+			StorageKind::Value(parse_quote!(Vec<u8>))
+		} else if map && (input.peek2(syn::token::Bracket) || input.peek2(keyword::hasher)) {
+			let flavor = if input.peek(keyword::linked_map) {
+				input.parse::<keyword::linked_map>()?;
+				MapFlavor::Linked
+			} else if input.peek(keyword::counted_map) {
+				input.parse::<keyword::counted_map>()?;
+				MapFlavor::Counted
+			} else {
+				input.parse::<keyword::map>()?;
+				MapFlavor::Plain
+			};
+			let hasher = if input.peek(keyword::hasher) {
+				Some(input.parse()?)
+			} else {
+				None
+			};
+			let content;
+			bracketed!(content in input);
+			let key = content.parse()?;
+			content.parse::<Token![=>]>()?;
+			let value = content.parse()?;
+			StorageKind::Map { key, value, flavor, hasher }
+		} else if input.peek(keyword::double_map) {
+			input.parse::<keyword::double_map>()?;
+			let key1 = input.parse()?;
+			input.parse::<Token![,]>()?;
+			let key2 = input.parse()?;
+			input.parse::<Token![=>]>()?;
+			let value = input.parse()?;
+			StorageKind::DoubleMap { key1, key2, value }
+		} else if input.peek(keyword::child) {
+			input.parse::<keyword::child>()?;
+			let content;
+			parenthesized!(content in input);
+			let child = content.parse()?;
+			input.parse::<keyword::map>()?;
+			let content;
+			bracketed!(content in input);
+			let key = content.parse()?;
+			content.parse::<Token![=>]>()?;
+			let value = content.parse()?;
+			StorageKind::ChildMap { child, key, value }
+		} else if input.peek(keyword::nmap) {
+			let span = input.parse::<keyword::nmap>()?.span;
+			let mut keys = vec![input.parse()?];
+			while input.peek(Token![,]) {
+				input.parse::<Token![,]>()?;
+				keys.push(input.parse()?);
+			}
+			if keys.len() < 2 || keys.len() > 4 {
+				return Err(syn::Error::new(span, "`nmap` takes between two and four keys"));
+			}
+			input.parse::<Token![=>]>()?;
+			let value = input.parse()?;
+			StorageKind::NMap { keys, value }
+		} else if input.peek(keyword::deque) && input.peek2(syn::token::Bracket) {
+			input.parse::<keyword::deque>()?;
+			let content;
+			bracketed!(content in input);
+			let value = content.parse()?;
+			content.parse::<Token![;]>()?;
+			let capacity = content.parse()?;
+			StorageKind::Deque { value, capacity }
+		} else if input.peek(keyword::ordered_set) && input.peek2(syn::token::Bracket) {
+			input.parse::<keyword::ordered_set>()?;
+			let content;
+			bracketed!(content in input);
+			StorageKind::OrderedSet { value: content.parse()? }
+		} else {
+			StorageKind::Value(input.parse()?)
+		};
+
+		input.parse::<Token![;]>()?;
+
+		let mut docs = Vec::new();
+		let mut compact = None;
+		let mut serde_attrs = Vec::new();
+		let mut whitelisted = None;
+		for attr in attrs {
+			if let Some(doc) = utils::doc_of(&attr) {
+				docs.push(doc?);
+				continue;
+			}
+			if utils::is_whitelisted(&attr) {
+				if whitelisted.is_some() {
+					return Err(syn::Error::new(attr.span(), "duplicate `#[whitelisted]` attribute"));
+				}
+				whitelisted = Some(attr.span());
+				continue;
+			}
+			if utils::is_serde(&attr) {
+				if config.is_none() {
+					return Err(syn::Error::new(attr.span(), "`#[serde(..)]` is only supported on items with `config(..)`"));
+				}
+				serde_attrs.push(attr);
+				continue;
+			}
+			match utils::codec_compact_of(&attr) {
+				Some(Ok(())) if compact.is_some() =>
+					return Err(syn::Error::new(attr.span(), "duplicate `#[codec(compact)]` attribute")),
+				Some(Ok(())) => compact = Some(attr.span()),
+				Some(Err(e)) => return Err(e),
+				None => return Err(syn::Error::new(
+					attr.span(),
+					"unsupported attribute; storage items only accept doc comments, `#[codec(compact)]`, `#[serde(..)]` and `#[whitelisted]`",
+				)),
+			}
+		}
+
+		match kind {
+			StorageKind::Value(_) | StorageKind::Map { .. } | StorageKind::Deque { .. } | StorageKind::OrderedSet { .. } => {},
+			_ if config.is_some() || build.is_some() => return Err(syn::Error::new(
+				name.span(),
+				"`config(..)` and `build(..)` are only supported on storage values, maps, deques and ordered sets",
+			)),
+			_ => {},
+		}
+
+		match kind {
+			StorageKind::Deque { .. } | StorageKind::OrderedSet { .. } if modifier != Modifier::Optional =>
+				return Err(syn::Error::new(name.span(), "deques and ordered sets do not take `default` or `required`")),
+			_ => {},
+		}
+
+		match (compact, &kind) {
+			(Some(span), _) if blob =>
+				return Err(syn::Error::new(span, "`#[codec(compact)]` is not supported on blobs")),
+			(_, &StorageKind::Value(_)) | (None, _) => {},
+			(Some(span), _) =>
+				return Err(syn::Error::new(span, "`#[codec(compact)]` is only supported on storage values, not maps")),
+		}
+
+		match (whitelisted, &kind) {
+			(_, &StorageKind::Value(_)) | (None, _) => {},
+			(Some(span), _) =>
+				return Err(syn::Error::new(span, "`#[whitelisted]` is only supported on storage values")),
+		}
+
+		Ok(StorageItem {
+			docs,
+			compact: compact.is_some(),
+			blob,
+			serde_attrs,
+			whitelisted: whitelisted.is_some(),
+			visibility,
+			name,
+			getter,
+			config,
+			build,
+			modifier,
+			on_empty,
+			kind,
+		})
+	}
+}
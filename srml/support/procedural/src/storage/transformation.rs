@@ -0,0 +1,1267 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Code generation of `decl_storage!`.
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use syn::{Ident, LitByteStr, Type};
+
+use utils::{docs_to_json, expr_to_string, lit_str, twox_128, type_to_string};
+use super::{Hasher, MapFlavor, Modifier, StorageDefinition, StorageItem, StorageKind};
+
+pub fn expand(def: StorageDefinition) -> TokenStream {
+	let StorageDefinition {
+		ref scrate,
+		ref visibility,
+		ref store_trait,
+		ref module_name,
+		ref generics,
+		ref crate_name,
+		version,
+		ref items,
+		..
+	} = def;
+	let impl_generics = generics.impl_generics();
+	let type_generics = generics.type_generics();
+	let where_clause = generics.where_clause();
+
+	let mut structs = Vec::new();
+	let mut store_items = Vec::new();
+	let mut store_impls = Vec::new();
+	let mut getters = Vec::new();
+	let mut metadata = Vec::new();
+	let mut whitelisted = Vec::new();
+	let mut written_types: Vec<(String, TokenStream)> = Vec::new();
+
+	for (i, item) in items.iter().enumerate() {
+		let name = &item.name;
+		structs.push(expand_item(&def, item));
+		store_items.push(quote! { type #name; });
+		store_impls.push(quote! { type #name = #name<#type_generics>; });
+		if let Some(ref getter) = item.getter {
+			getters.push(expand_getter(&def, item, getter));
+			getters.push(expand_exists(&def, item, getter));
+		}
+		metadata.push(item_json_metadata(item, if i == 0 { "" } else { "," }));
+		for (written, ty) in item_types(scrate, item) {
+			if !written_types.iter().any(|&(ref w, _)| *w == written) {
+				written_types.push((written, ty));
+			}
+		}
+		if let (true, &StorageKind::Value(ref ty)) = (item.whitelisted, &item.kind) {
+			whitelisted.push(quote! {
+				<#name<#type_generics> as #scrate::storage::generator::StorageValue<#ty>>::key()
+			});
+		}
+	}
+
+	let genesis = expand_genesis(&def);
+	let instances = expand_instances(&def);
+	let crate_name_str = lit_str(&crate_name.to_string());
+	let version_key = storage_key(&def, "STORAGE_VERSION_KEY");
+	let register_types = written_types.iter().map(|&(ref written, ref ty)| {
+		let written = lit_str(written);
+		quote! { registry.register_written::<#ty>(#written, types); }
+	});
+	let type_bounds = written_types.iter().map(|&(_, ref ty)| quote! { #ty: #scrate::type_info::TypeInfo });
+	let max_encoded_lens = if def.bounded {
+		expand_max_encoded_lens(&def)
+	} else {
+		quote! {}
+	};
+
+	quote! {
+		#(#structs)*
+
+		#visibility trait #store_trait {
+			#(#store_items)*
+		}
+
+		impl<#impl_generics> #store_trait for #module_name<#type_generics> #where_clause {
+			#(#store_impls)*
+		}
+
+		impl<#impl_generics> #scrate::storage::StorageVersion for #module_name<#type_generics> #where_clause {
+			fn current_storage_version() -> u32 {
+				#version
+			}
+
+			fn storage_version_key() -> &'static [u8] {
+				#version_key
+			}
+		}
+
+		impl<#impl_generics> #scrate::storage::WhitelistedStorageKeys for #module_name<#type_generics> #where_clause {
+			fn whitelisted_storage_keys() -> Vec<Vec<u8>> {
+				#[allow(unused_mut)]
+				let mut keys = Vec::new();
+				#( keys.push(#whitelisted.to_vec()); )*
+				keys
+			}
+		}
+
+		impl<#impl_generics> #module_name<#type_generics> #where_clause {
+			#(#getters)*
+
+			pub fn store_json_metadata() -> &'static str {
+				concat!(
+					r#"{ "prefix": ""#, #crate_name_str, r#"", "prefixHasher": "twox_128", "items": {"#,
+					#(#metadata,)*
+					" } }"
+				)
+			}
+
+			/// Register the key and value types of the storage items of this module, noting in
+			/// `types` the way each of them is written in the metadata.
+			#[allow(unused_variables)]
+			pub fn register_storage_types(
+				registry: &mut #scrate::type_info::TypeRegistry,
+				types: &mut #scrate::dispatch::Vec<(&'static str, #scrate::type_info::String)>,
+			) where #( #type_bounds ),* {
+				#( #register_types )*
+			}
+
+			#max_encoded_lens
+		}
+
+		#genesis
+
+		#instances
+	}
+}
+
+/// The type returned by reads of the given item.
+fn query_type(item: &StorageItem) -> TokenStream {
+	let ty = item.kind.value_type();
+	match item.modifier {
+		Modifier::Optional => quote! { Option<#ty> },
+		Modifier::Default | Modifier::Required => quote! { #ty },
+	}
+}
+
+/// The query kind of the given item, and the provider of the value of a missing item.
+fn query_kind(def: &StorageDefinition, item: &StorageItem) -> (TokenStream, TokenStream) {
+	let scrate = &def.scrate;
+	let generator = quote! { #scrate::storage::generator };
+	match item.modifier {
+		Modifier::Optional => (quote! { #generator::OptionQuery }, quote! { #generator::GetDefault }),
+		Modifier::Default => match item.on_empty {
+			Some(ref on_empty) => (quote! { #generator::ValueQuery }, quote! { #on_empty }),
+			None => (quote! { #generator::ValueQuery }, quote! { #generator::GetDefault }),
+		},
+		Modifier::Required => (quote! { #generator::ValueQuery }, quote! { #generator::PanicOnEmpty }),
+	}
+}
+
+/// The struct of a storage item together with its `StorageValue`/`StorageMap` implementation.
+fn expand_item(def: &StorageDefinition, item: &StorageItem) -> TokenStream {
+	let scrate = &def.scrate;
+	let impl_generics = def.generics.impl_generics();
+	let type_generics = def.generics.type_generics();
+	let where_clause = def.generics.where_clause();
+	let phantom = def.generics.phantom();
+	let visibility = &item.visibility;
+	let name = &item.name;
+	let docs = &item.docs;
+	let query_type = query_type(item);
+	let (query_kind, on_empty) = query_kind(def, item);
+	let key = storage_key(def, &format!("PREFIX_FOR_{}", name));
+	let module_prefix = storage_key(def, "MODULE_PREFIX");
+	let storage_prefix = LitByteStr::new(name.to_string().as_bytes(), name.span());
+	let prefixed_map = quote! {
+		impl<#impl_generics> #scrate::storage::StoragePrefixedMap for #name<#type_generics> #where_clause {
+			fn module_prefix() -> &'static [u8] {
+				#module_prefix
+			}
+
+			fn storage_prefix() -> &'static [u8] {
+				#storage_prefix
+			}
+
+			fn final_prefix() -> &'static [u8] {
+				#key
+			}
+		}
+	};
+
+	let body = match item.kind {
+		StorageKind::Value(ref ty) => {
+			let as_value = quote! { <#name<#type_generics> as #scrate::storage::generator::StorageValue<#ty>> };
+			let compact = if item.compact {
+				let as_query_kind = quote! {
+					<#query_kind as #scrate::storage::generator::QueryKindTrait<#ty, #on_empty>>
+				};
+				quote! {
+					/// Load the value from the provided storage instance.
+					fn get<S: #scrate::GenericStorage>(storage: &S) -> Self::Query {
+						#as_query_kind::from_optional_value_to_query(
+							storage.get::<#scrate::storage::generator::Compact<#ty>>(#as_value::key()).map(|v| v.0)
+						)
+					}
+
+					/// Take a value from storage, removing it afterwards.
+					fn take<S: #scrate::GenericStorage>(storage: &S) -> Self::Query {
+						#as_query_kind::from_optional_value_to_query(
+							storage.take::<#scrate::storage::generator::Compact<#ty>>(#as_value::key()).map(|v| v.0)
+						)
+					}
+
+					/// Store a value under this key into the provided storage instance.
+					fn put<S: #scrate::GenericStorage>(val: &#ty, storage: &S) {
+						storage.put(#as_value::key(), &#scrate::storage::generator::Compact(*val))
+					}
+				}
+			} else if item.blob {
+				let as_query_kind = quote! {
+					<#query_kind as #scrate::storage::generator::QueryKindTrait<#ty, #on_empty>>
+				};
+				quote! {
+					/// Load the bytes from the provided storage instance.
+					fn get<S: #scrate::GenericStorage>(storage: &S) -> Self::Query {
+						#as_query_kind::from_optional_value_to_query(storage.get_raw(#as_value::key()))
+					}
+
+					/// Take the bytes from storage, removing them afterwards.
+					fn take<S: #scrate::GenericStorage>(storage: &S) -> Self::Query {
+						let value = storage.get_raw(#as_value::key());
+						storage.kill(#as_value::key());
+						#as_query_kind::from_optional_value_to_query(value)
+					}
+
+					/// Store the bytes as they are under this key into the provided storage instance.
+					fn put<S: #scrate::GenericStorage>(val: &#ty, storage: &S) {
+						storage.put_raw(#as_value::key(), &val[..])
+					}
+
+					/// The number of bytes, `None` if they are not in storage.
+					fn decode_len<S: #scrate::GenericStorage>(storage: &S) -> Option<u32> {
+						storage.get_raw(#as_value::key()).map(|v| v.len() as u32)
+					}
+				}
+			} else {
+				quote! {}
+			};
+
+			quote! {
+				impl<#impl_generics> #scrate::storage::generator::StorageValue<#ty> for #name<#type_generics> #where_clause {
+					type Query = #query_type;
+					type QueryKind = #query_kind;
+					type OnEmpty = #on_empty;
+
+					/// Get the storage key.
+					fn key() -> &'static [u8] {
+						#key
+					}
+
+					#compact
+				}
+			}
+		},
+		StorageKind::Map { key: ref kty, value: ref ty, flavor, hasher } => {
+			let as_map = quote! { <#name<#type_generics> as #scrate::storage::generator::StorageMap<#kty, #ty>> };
+
+			let hash_key = match hasher {
+				None | Some(Hasher::Identity) => quote! { #scrate::codec::Encode::encode_to(x, &mut key); },
+				Some(Hasher::Blake2_256) => quote! { key.extend(&#scrate::runtime_io::blake2_256(&#scrate::codec::Encode::encode(x))[..]); },
+				Some(Hasher::Twox128) => quote! { key.extend(&#scrate::runtime_io::twox_128(&#scrate::codec::Encode::encode(x))[..]); },
+				Some(Hasher::Twox256) => quote! { key.extend(&#scrate::runtime_io::twox_256(&#scrate::codec::Encode::encode(x))[..]); },
+			};
+
+			// The bookkeeping of linked and counted maps, run before a key is inserted or removed,
+			// and the methods they override to keep it.
+			let (on_insert, on_remove, overrides, flavor_impl) = match flavor {
+				MapFlavor::Plain => (quote! {}, quote! {}, quote! {}, None),
+				MapFlavor::Linked => {
+					let as_linked = quote! { <#name<#type_generics> as #scrate::storage::generator::EnumerableStorageMap<#kty, #ty>> };
+					let head_key = storage_key(def, &format!("HEAD_KEY_FOR_{}", name));
+					let linkage_prefix = storage_key(def, &format!("LINKAGE_PREFIX_FOR_{}", name));
+					let enumerable = quote! {
+						impl<#impl_generics> #scrate::storage::generator::EnumerableStorageMap<#kty, #ty> for #name<#type_generics> #where_clause {
+							/// Get the storage key of the most recently inserted key.
+							fn head_key() -> &'static [u8] {
+								#head_key
+							}
+
+							/// Get the storage key holding the linkage of a specific key.
+							fn linkage_key_for(x: &#kty) -> Vec<u8> {
+								let mut key = #linkage_prefix.to_vec();
+								#scrate::codec::Encode::encode_to(x, &mut key);
+								key
+							}
+						}
+					};
+					let overrides = quote! {
+						/// Exchange the values under two keys, keeping the list of keys in step
+						/// if only one of them is in the map.
+						fn swap<S: #scrate::GenericStorage>(key1: &#kty, key2: &#kty, storage: &S) {
+							let (exists1, exists2) = (#as_map::exists(key1, storage), #as_map::exists(key2, storage));
+							if exists1 != exists2 {
+								let (from, to) = if exists1 { (key1, key2) } else { (key2, key1) };
+								#as_linked::unlink(from, storage);
+								#as_linked::link(to, storage);
+							}
+							storage.swap(&#as_map::key_for(key1)[..], &#as_map::key_for(key2)[..]);
+						}
+
+						/// Remove all entries of the map, walking its list of keys.
+						fn remove_all<S: #scrate::GenericStorage>(storage: &S) {
+							while let Some(key) = #as_linked::head(storage) {
+								#as_map::remove(&key, storage);
+							}
+						}
+					};
+					(
+						quote! { #as_linked::link(key, storage); },
+						quote! { #as_linked::unlink(key, storage); },
+						overrides,
+						Some(enumerable),
+					)
+				},
+				MapFlavor::Counted => {
+					let as_counted = quote! { <#name<#type_generics> as #scrate::storage::generator::CountedStorageMap<#kty, #ty>> };
+					let count_key = storage_key(def, &format!("COUNT_KEY_FOR_{}", name));
+					let counted = quote! {
+						impl<#impl_generics> #scrate::storage::generator::CountedStorageMap<#kty, #ty> for #name<#type_generics> #where_clause {
+							/// Get the storage key holding the number of entries.
+							fn count_key() -> &'static [u8] {
+								#count_key
+							}
+						}
+					};
+					let overrides = quote! {
+						/// Remove all entries of the map, one by one to keep their count.
+						fn remove_all<S: #scrate::GenericStorage>(storage: &S) {
+							let keys = #as_map::iter_prefix(storage).map(|(key, _)| key).collect::<Vec<_>>();
+							for key in keys {
+								#as_map::remove(&key, storage);
+							}
+						}
+					};
+					(
+						quote! { #as_counted::note_insert(key, storage); },
+						quote! { #as_counted::note_remove(key, storage); },
+						overrides,
+						Some(counted),
+					)
+				},
+			};
+			let insert_remove = if flavor == MapFlavor::Plain {
+				quote! {}
+			} else {
+				quote! {
+					/// Store a value to be associated with the given key from the map.
+					fn insert<S: #scrate::GenericStorage>(key: &#kty, val: &#ty, storage: &S) {
+						#on_insert
+						storage.put(&#as_map::key_for(key)[..], val);
+					}
+
+					/// Remove the value under a key.
+					fn remove<S: #scrate::GenericStorage>(key: &#kty, storage: &S) {
+						#on_remove
+						storage.kill(&#as_map::key_for(key)[..]);
+					}
+
+					/// Store an already encoded value under a key.
+					fn put_raw<S: #scrate::GenericStorage>(key: &#kty, value: &[u8], storage: &S) {
+						#on_insert
+						storage.put_raw(&#as_map::key_for(key)[..], value);
+					}
+				}
+			};
+
+			quote! {
+				impl<#impl_generics> #scrate::storage::generator::StorageMap<#kty, #ty> for #name<#type_generics> #where_clause {
+					type Query = #query_type;
+					type QueryKind = #query_kind;
+					type OnEmpty = #on_empty;
+
+					/// Get the prefix key in storage.
+					fn prefix() -> &'static [u8] {
+						#key
+					}
+
+					/// Get the storage key used to fetch a value corresponding to a specific key.
+					fn key_for<KeyArg: #scrate::storage::generator::EncodeLike<#kty> + ?Sized>(x: &KeyArg) -> Vec<u8> {
+						let mut key = #as_map::prefix().to_vec();
+						#hash_key
+						key
+					}
+
+					#insert_remove
+
+					#overrides
+				}
+
+				#flavor_impl
+
+				#prefixed_map
+			}
+		},
+		StorageKind::DoubleMap { ref key1, ref key2, value: ref ty } => {
+			let as_double_map = quote! {
+				<#name<#type_generics> as #scrate::storage::unhashed::generator::StorageDoubleMap<#key1, #key2, #ty>>
+			};
+			let storage_trait = quote! { #scrate::storage::unhashed::generator::UnhashedStorage };
+			let as_query_kind = quote! {
+				<#query_kind as #scrate::storage::generator::QueryKindTrait<#ty, #on_empty>>
+			};
+
+			quote! {
+				impl<#impl_generics> #scrate::storage::unhashed::generator::StorageDoubleMap<#key1, #key2, #ty> for #name<#type_generics> #where_clause {
+					type Query = #query_type;
+
+					/// Get the prefix key in storage.
+					fn prefix() -> &'static [u8] {
+						#key
+					}
+
+					/// Get the storage key shared by all entries with the given first key.
+					fn prefix_for(k1: &#key1) -> Vec<u8> {
+						let mut key = #as_double_map::prefix().to_vec();
+						key.extend(&#scrate::Hashable::twox_128(k1)[..]);
+						key
+					}
+
+					/// Get the storage key used to fetch a value corresponding to specific keys.
+					fn key_for(k1: &#key1, k2: &#key2) -> Vec<u8> {
+						let mut key = #as_double_map::prefix_for(k1);
+						key.extend(&#scrate::Hashable::blake2_256(k2)[..]);
+						key
+					}
+
+					/// Load the value associated with the given keys from the map.
+					fn get<S: #storage_trait>(k1: &#key1, k2: &#key2, storage: &S) -> Self::Query {
+						let key = #as_double_map::key_for(k1, k2);
+						#as_query_kind::from_optional_value_to_query(storage.get(&key[..]))
+					}
+
+					/// Take the value, reading and removing it.
+					fn take<S: #storage_trait>(k1: &#key1, k2: &#key2, storage: &S) -> Self::Query {
+						let key = #as_double_map::key_for(k1, k2);
+						#as_query_kind::from_optional_value_to_query(storage.take(&key[..]))
+					}
+
+					/// Mutate the value under the given keys.
+					fn mutate<F: FnOnce(&mut Self::Query), S: #storage_trait>(k1: &#key1, k2: &#key2, f: F, storage: &S) {
+						let mut val = #as_double_map::get(k1, k2, storage);
+
+						f(&mut val);
+
+						match #as_query_kind::from_query_to_optional_value(val) {
+							Some(val) => #as_double_map::insert(k1, k2, &val, storage),
+							None => #as_double_map::remove(k1, k2, storage),
+						}
+					}
+				}
+
+				#prefixed_map
+			}
+		},
+		StorageKind::ChildMap { key: ref kty, value: ref ty, .. } => {
+			let child = storage_key(def, &format!("CHILD_KEY_FOR_{}", name));
+			let as_child_map = quote! { <#name<#type_generics> as #scrate::storage::generator::StorageChildMap<#kty, #ty>> };
+			let as_query_kind = quote! {
+				<#query_kind as #scrate::storage::generator::QueryKindTrait<#ty, #on_empty>>
+			};
+
+			quote! {
+				impl<#impl_generics> #scrate::storage::generator::StorageChildMap<#kty, #ty> for #name<#type_generics> #where_clause {
+					type Query = #query_type;
+
+					/// Get the key of the child storage.
+					fn child_key() -> &'static [u8] {
+						#child
+					}
+
+					/// Get the key of the value corresponding to a specific key in the child storage.
+					fn key_for(x: &#kty) -> Vec<u8> {
+						#scrate::codec::Encode::encode(x)
+					}
+
+					/// Load the value associated with the given key from the map.
+					fn get<S: #scrate::GenericStorage>(key: &#kty, storage: &S) -> Self::Query {
+						#as_query_kind::from_optional_value_to_query(
+							storage.child_get(#as_child_map::child_key(), &#as_child_map::key_for(key)[..])
+						)
+					}
+
+					/// Take the value, reading and removing it.
+					fn take<S: #scrate::GenericStorage>(key: &#kty, storage: &S) -> Self::Query {
+						let val = #as_child_map::get(key, storage);
+						#as_child_map::remove(key, storage);
+						val
+					}
+
+					/// Mutate the value under a key, returning the result of `f`.
+					fn mutate<R, F: FnOnce(&mut Self::Query) -> R, S: #scrate::GenericStorage>(key: &#kty, f: F, storage: &S) -> R {
+						let mut val = #as_child_map::get(key, storage);
+
+						let result = f(&mut val);
+
+						match #as_query_kind::from_query_to_optional_value(val) {
+							Some(val) => #as_child_map::insert(key, &val, storage),
+							None => #as_child_map::remove(key, storage),
+						}
+						result
+					}
+				}
+			}
+		},
+		StorageKind::NMap { ref keys, value: ref ty } => {
+			let kty = quote! { ( #(#keys,)* ) };
+			let key_tuple = quote! { #scrate::storage::unhashed::generator::KeyTuple };
+			let as_nmap = quote! {
+				<#name<#type_generics> as #scrate::storage::unhashed::generator::StorageNMap<#kty, #ty>>
+			};
+			let storage_trait = quote! { #scrate::storage::unhashed::generator::UnhashedStorage };
+			let as_query_kind = quote! {
+				<#query_kind as #scrate::storage::generator::QueryKindTrait<#ty, #on_empty>>
+			};
+
+			quote! {
+				impl<#impl_generics> #scrate::storage::unhashed::generator::StorageNMap<#kty, #ty> for #name<#type_generics> #where_clause {
+					type Query = #query_type;
+
+					/// Get the prefix key in storage.
+					fn prefix() -> &'static [u8] {
+						#key
+					}
+
+					/// Get the storage key shared by all entries starting with the given keys.
+					fn prefix_for<P: #key_tuple>(partial: &P) -> Vec<u8>
+						where #kty: #scrate::storage::unhashed::generator::HasKeyPrefix<P>
+					{
+						let mut key = #as_nmap::prefix().to_vec();
+						key.extend(#key_tuple::hashed_key(partial));
+						key
+					}
+
+					/// Get the storage key used to fetch a value corresponding to specific keys.
+					fn key_for(x: &#kty) -> Vec<u8> {
+						let mut key = #as_nmap::prefix().to_vec();
+						key.extend(#key_tuple::hashed_key(x));
+						key
+					}
+
+					/// Load the value associated with the given keys from the map.
+					fn get<S: #storage_trait>(key: &#kty, storage: &S) -> Self::Query {
+						let key = #as_nmap::key_for(key);
+						#as_query_kind::from_optional_value_to_query(storage.get(&key[..]))
+					}
+
+					/// Take the value, reading and removing it.
+					fn take<S: #storage_trait>(key: &#kty, storage: &S) -> Self::Query {
+						let key = #as_nmap::key_for(key);
+						#as_query_kind::from_optional_value_to_query(storage.take(&key[..]))
+					}
+
+					/// Mutate the value under the given keys.
+					fn mutate<F: FnOnce(&mut Self::Query), S: #storage_trait>(key: &#kty, f: F, storage: &S) {
+						let mut val = #as_nmap::get(key, storage);
+
+						f(&mut val);
+
+						match #as_query_kind::from_query_to_optional_value(val) {
+							Some(val) => #as_nmap::insert(key, &val, storage),
+							None => #as_nmap::remove(key, storage),
+						}
+					}
+				}
+
+				#prefixed_map
+			}
+		},
+		StorageKind::Deque { value: ref ty, ref capacity } => quote! {
+			impl<#impl_generics> #scrate::storage::generator::StorageDeque<#ty> for #name<#type_generics> #where_clause {
+				/// Get the prefix key in storage.
+				fn prefix() -> &'static [u8] {
+					#key
+				}
+
+				/// Get the storage key used to fetch the item at a given position.
+				fn key_for(position: u32) -> Vec<u8> {
+					let mut key = <#name<#type_generics> as #scrate::storage::generator::StorageDeque<#ty>>::prefix().to_vec();
+					#scrate::codec::Encode::encode_to(&position, &mut key);
+					key
+				}
+
+				/// The maximum number of items.
+				fn capacity() -> u32 {
+					#capacity
+				}
+			}
+
+			#prefixed_map
+		},
+		StorageKind::OrderedSet { value: ref ty } => quote! {
+			impl<#impl_generics> #scrate::storage::generator::StorageOrderedSet<#ty> for #name<#type_generics> #where_clause {
+				/// Get the prefix key in storage.
+				fn prefix() -> &'static [u8] {
+					#key
+				}
+
+				/// Get the storage key used to fetch the item of a given rank.
+				fn key_for(rank: u32) -> Vec<u8> {
+					let mut key = <#name<#type_generics> as #scrate::storage::generator::StorageOrderedSet<#ty>>::prefix().to_vec();
+					#scrate::codec::Encode::encode_to(&rank, &mut key);
+					key
+				}
+			}
+
+			#prefixed_map
+		},
+	};
+
+	quote! {
+		#( #[doc = #docs] )*
+		#visibility struct #name<#impl_generics>(#scrate::storage::generator::PhantomData<#phantom>) #where_clause;
+
+		#body
+	}
+}
+
+/// The public getter function declared with `get(..)`.
+fn expand_getter(def: &StorageDefinition, item: &StorageItem, getter: &Ident) -> TokenStream {
+	let scrate = &def.scrate;
+	let type_generics = def.generics.type_generics();
+	let name = &item.name;
+	let query_type = query_type(item);
+
+	match item.kind {
+		StorageKind::Value(ref ty) => quote! {
+			pub fn #getter() -> #query_type {
+				<#name<#type_generics> as #scrate::storage::generator::StorageValue<#ty>>::get(&#scrate::storage::RuntimeStorage)
+			}
+		},
+		StorageKind::Map { ref key, ref value, .. } => quote! {
+			pub fn #getter<K: #scrate::storage::generator::EncodeLike<#key>>(key: K) -> #query_type {
+				<#name<#type_generics> as #scrate::storage::generator::StorageMap<#key, #value>>::get(&key, &#scrate::storage::RuntimeStorage)
+			}
+		},
+		StorageKind::DoubleMap { ref key1, ref key2, ref value } => quote! {
+			pub fn #getter<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> #query_type where
+				KArg1: #scrate::storage::generator::Borrow<#key1>,
+				KArg2: #scrate::storage::generator::Borrow<#key2>,
+			{
+				<#name<#type_generics> as #scrate::storage::unhashed::generator::StorageDoubleMap<#key1, #key2, #value>>::get(
+					k1.borrow(), k2.borrow(), &#scrate::storage::RuntimeStorage
+				)
+			}
+		},
+		StorageKind::NMap { ref keys, ref value } => quote! {
+			pub fn #getter<KArg: #scrate::storage::generator::Borrow<( #(#keys,)* )>>(key: KArg) -> #query_type {
+				<#name<#type_generics> as #scrate::storage::unhashed::generator::StorageNMap<( #(#keys,)* ), #value>>::get(
+					key.borrow(), &#scrate::storage::RuntimeStorage
+				)
+			}
+		},
+		StorageKind::ChildMap { ref key, ref value, .. } => quote! {
+			pub fn #getter<K: #scrate::storage::generator::Borrow<#key>>(key: K) -> #query_type {
+				<#name<#type_generics> as #scrate::storage::generator::StorageChildMap<#key, #value>>::get(key.borrow(), &#scrate::storage::RuntimeStorage)
+			}
+		},
+		StorageKind::Deque { ref value, .. } => quote! {
+			pub fn #getter() -> Vec<#value> {
+				<#name<#type_generics> as #scrate::storage::generator::StorageDeque<#value>>::items(&#scrate::storage::RuntimeStorage)
+			}
+		},
+		StorageKind::OrderedSet { ref value } => quote! {
+			pub fn #getter() -> Vec<#value> {
+				<#name<#type_generics> as #scrate::storage::generator::StorageOrderedSet<#value>>::items(&#scrate::storage::RuntimeStorage)
+			}
+		},
+	}
+}
+
+/// The `<getter>_exists` function generated along the getter, telling whether the item or an
+/// entry of it is in storage without decoding it. Deques and ordered sets have none.
+fn expand_exists(def: &StorageDefinition, item: &StorageItem, getter: &Ident) -> TokenStream {
+	let scrate = &def.scrate;
+	let type_generics = def.generics.type_generics();
+	let name = &item.name;
+	let exists = Ident::new(&format!("{}_exists", getter), getter.span());
+
+	match item.kind {
+		StorageKind::Value(ref ty) => quote! {
+			pub fn #exists() -> bool {
+				<#name<#type_generics> as #scrate::storage::generator::StorageValue<#ty>>::exists(&#scrate::storage::RuntimeStorage)
+			}
+		},
+		StorageKind::Map { ref key, ref value, .. } => quote! {
+			pub fn #exists<K: #scrate::storage::generator::EncodeLike<#key>>(key: K) -> bool {
+				<#name<#type_generics> as #scrate::storage::generator::StorageMap<#key, #value>>::exists(&key, &#scrate::storage::RuntimeStorage)
+			}
+		},
+		StorageKind::DoubleMap { ref key1, ref key2, ref value } => quote! {
+			pub fn #exists<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> bool where
+				KArg1: #scrate::storage::generator::Borrow<#key1>,
+				KArg2: #scrate::storage::generator::Borrow<#key2>,
+			{
+				<#name<#type_generics> as #scrate::storage::unhashed::generator::StorageDoubleMap<#key1, #key2, #value>>::exists(
+					k1.borrow(), k2.borrow(), &#scrate::storage::RuntimeStorage
+				)
+			}
+		},
+		StorageKind::NMap { ref keys, ref value } => quote! {
+			pub fn #exists<KArg: #scrate::storage::generator::Borrow<( #(#keys,)* )>>(key: KArg) -> bool {
+				<#name<#type_generics> as #scrate::storage::unhashed::generator::StorageNMap<( #(#keys,)* ), #value>>::exists(
+					key.borrow(), &#scrate::storage::RuntimeStorage
+				)
+			}
+		},
+		StorageKind::ChildMap { ref key, ref value, .. } => quote! {
+			pub fn #exists<K: #scrate::storage::generator::Borrow<#key>>(key: K) -> bool {
+				<#name<#type_generics> as #scrate::storage::generator::StorageChildMap<#key, #value>>::exists(key.borrow(), &#scrate::storage::RuntimeStorage)
+			}
+		},
+		StorageKind::Deque { .. } | StorageKind::OrderedSet { .. } => quote! {},
+	}
+}
+
+/// The type an item is initialised from at genesis: the value of a storage value, the list of
+/// entries of a map, the items of a deque from front to back, or the items of an ordered set in
+/// any order.
+fn genesis_type(item: &StorageItem) -> TokenStream {
+	match item.kind {
+		StorageKind::Map { ref key, ref value, .. } => quote! { Vec<(#key, #value)> },
+		StorageKind::Deque { ref value, .. } | StorageKind::OrderedSet { ref value } => quote! { Vec<#value> },
+		ref kind => {
+			let ty = kind.value_type();
+			quote! { #ty }
+		},
+	}
+}
+
+/// The `GenesisConfig` of the module and its `BuildStorage` implementation, generated if any item
+/// is initialised at genesis with `config(..)` or `build(..)`, or if `add_extra_genesis` declares
+/// anything.
+fn expand_genesis(def: &StorageDefinition) -> TokenStream {
+	let scrate = &def.scrate;
+	let module_name = &def.module_name;
+	let impl_generics = def.generics.impl_generics();
+	let type_generics = def.generics.type_generics();
+	let where_clause = def.generics.where_clause();
+	let where_predicates = def.generics.where_predicates();
+	let phantom = def.generics.phantom();
+	let extra_build = def.extra_genesis.as_ref().and_then(|extra| extra.build.as_ref());
+	let extra_fields = def.extra_genesis.as_ref().map_or(&[][..], |extra| &extra.fields[..]);
+
+	let genesis_items: Vec<_> = def.items.iter()
+		.filter(|item| item.config.is_some() || item.build.is_some())
+		.collect();
+	if genesis_items.is_empty() && extra_fields.is_empty() && extra_build.is_none() {
+		return quote! {};
+	}
+
+	let mut fields = Vec::new();
+	let mut field_types = Vec::new();
+	let mut defaults = Vec::new();
+	let mut builders = Vec::new();
+	for item in genesis_items {
+		let name = &item.name;
+		let ty = genesis_type(item);
+
+		if let Some(ref field) = item.config {
+			let docs = &item.docs;
+			let serde_attrs = &item.serde_attrs;
+			fields.push(quote! {
+				#( #[doc = #docs] )*
+				#( #serde_attrs )*
+				pub #field: #ty,
+			});
+			defaults.push(quote! { #field: Default::default(), });
+			field_types.push(ty.clone());
+		}
+
+		let load = match (&item.build, &item.config) {
+			(&Some(ref build), _) => quote! {
+				let build: fn(&#scrate::storage::GenesisStorage, &GenesisConfig<#type_generics>) -> #ty = #build;
+				let value = build(&storage, config);
+				let value = &value;
+			},
+			(&None, &Some(ref field)) => quote! {
+				let value = &config.#field;
+			},
+			(&None, &None) => unreachable!("only items with `config(..)` or `build(..)` are built at genesis; qed"),
+		};
+		let store = match item.kind {
+			StorageKind::Value(ref ty) => quote! {
+				<#name<#type_generics> as #scrate::storage::generator::StorageValue<#ty>>::put(value, &storage);
+			},
+			StorageKind::Map { ref key, ref value, .. } => quote! {
+				for &(ref k, ref v) in value.iter() {
+					<#name<#type_generics> as #scrate::storage::generator::StorageMap<#key, #value>>::insert(k, v, &storage);
+				}
+			},
+			StorageKind::Deque { ref value, .. } => quote! {
+				for v in value.iter() {
+					<#name<#type_generics> as #scrate::storage::generator::StorageDeque<#value>>::push_back(v, &storage)?;
+				}
+			},
+			StorageKind::OrderedSet { ref value } => quote! {
+				for v in value.iter() {
+					<#name<#type_generics> as #scrate::storage::generator::StorageOrderedSet<#value>>::insert(v, &storage);
+				}
+			},
+			_ => unreachable!("`config(..)` and `build(..)` are only parsed on values, maps, deques and ordered sets; qed"),
+		};
+		builders.push(quote! {
+			{
+				#load
+				#store
+			}
+		});
+	}
+
+	for field in extra_fields {
+		let docs = &field.docs;
+		let serde_attrs = &field.serde_attrs;
+		let name = &field.name;
+		let ty = &field.ty;
+		fields.push(quote! {
+			#( #[doc = #docs] )*
+			#( #serde_attrs )*
+			pub #name: #ty,
+		});
+		defaults.push(quote! { #name: Default::default(), });
+		field_types.push(quote! { #ty });
+	}
+
+	if let Some(build) = extra_build {
+		builders.push(quote! {
+			{
+				let build: fn(&#scrate::storage::GenesisStorage, &GenesisConfig<#type_generics>) = #build;
+				build(&storage, config);
+			}
+		});
+	}
+
+	if def.version > 0 {
+		builders.push(quote! {
+			#scrate::GenericStorage::put(
+				&storage,
+				<#module_name<#type_generics> as #scrate::storage::StorageVersion>::storage_version_key(),
+				&<#module_name<#type_generics> as #scrate::storage::StorageVersion>::current_storage_version(),
+			);
+		});
+	}
+
+	quote! {
+		/// The genesis configuration of the module, built into the storage of the genesis block.
+		#[cfg(feature = "std")]
+		#[derive(Serialize, Deserialize)]
+		#[serde(rename_all = "camelCase")]
+		#[serde(deny_unknown_fields)]
+		pub struct GenesisConfig<#impl_generics> #where_clause {
+			#(#fields)*
+			#[serde(skip)]
+			pub _genesis_phantom_data: ::std::marker::PhantomData<#phantom>,
+		}
+
+		#[cfg(feature = "std")]
+		impl<#impl_generics> Default for GenesisConfig<#type_generics> where #where_predicates #( #field_types: Default, )* {
+			fn default() -> Self {
+				GenesisConfig {
+					#(#defaults)*
+					_genesis_phantom_data: Default::default(),
+				}
+			}
+		}
+
+		#[cfg(feature = "std")]
+		impl<#impl_generics> GenesisConfig<#type_generics> #where_clause {
+			/// Build the storage of the module into `storage`, e.g. the genesis storage of the other
+			/// modules of a runtime. Fails, leaving `storage` untouched, if a key already holds
+			/// another value.
+			pub fn assimilate_storage(self, storage: &mut #scrate::runtime_primitives::StorageMap) -> ::std::result::Result<(), ::std::string::String> {
+				#scrate::runtime_primitives::BuildStorage::assimilate_storage(self, storage)
+			}
+
+			/// Test externalities holding the storage of the module, for unit tests.
+			///
+			/// Panics if the storage fails to build.
+			pub fn new_test_ext(self) -> #scrate::runtime_io::TestExternalities<#scrate::runtime_io::Blake2Hasher> {
+				#scrate::runtime_primitives::BuildStorage::build_storage(self)
+					.expect("the genesis config of the module must build")
+					.into()
+			}
+		}
+
+		#[cfg(feature = "std")]
+		impl<#impl_generics> #scrate::runtime_primitives::BuildStorage for GenesisConfig<#type_generics> #where_clause {
+			fn build_storage(self) -> ::std::result::Result<#scrate::runtime_primitives::StorageMap, ::std::string::String> {
+				let storage = #scrate::storage::GenesisStorage::new();
+				{
+					#[allow(unused_variables)]
+					let config = &self;
+					#(#builders)*
+				}
+				Ok(storage.into_inner())
+			}
+		}
+	}
+}
+
+/// A key of the storage of the module, listed by `storage_keys`.
+struct StorageKey<'a> {
+	/// The name of the constant holding the key in the instance trait.
+	name: Ident,
+	/// The item the key belongs to, if any.
+	item: Option<&'a StorageItem>,
+	/// Whether the key is the one of a child storage rather than a key of the main storage.
+	child: bool,
+	key: Vec<u8>,
+}
+
+impl<'a> StorageKey<'a> {
+	/// The key as a byte string literal.
+	fn literal(&self) -> LitByteStr {
+		LitByteStr::new(&self.key, Span::call_site())
+	}
+}
+
+/// The final keys of the storage of the module, each with the name of the constant holding it in
+/// the instance trait, together with the prefix of the module under `MODULE_PREFIX`. A key is
+/// `twox_128(module) ++ twox_128(item)`, where the module is the name it is declared with after
+/// the name of the instance, which is empty without instances.
+fn storage_keys<'a>(def: &'a StorageDefinition, instance_prefix: &str) -> Vec<StorageKey<'a>> {
+	let module_prefix = format!("{}{}", instance_prefix, def.crate_name);
+	let byte_key = |name: String, item, child, key: Vec<u8>| StorageKey {
+		name: Ident::new(&name, Span::call_site()),
+		item,
+		child,
+		key,
+	};
+	let final_key = |name: String, item, item_key: String| {
+		let mut key = twox_128(module_prefix.as_bytes()).to_vec();
+		key.extend(&twox_128(item_key.as_bytes())[..]);
+		byte_key(name, item, false, key)
+	};
+
+	let mut keys = vec![
+		byte_key("MODULE_PREFIX".into(), None, false, module_prefix.as_bytes().to_vec()),
+		final_key("STORAGE_VERSION_KEY".into(), None, ":storage_version:".into()),
+	];
+	for item in &def.items {
+		let name = &item.name;
+		keys.push(final_key(format!("PREFIX_FOR_{}", name), Some(item), name.to_string()));
+		match item.kind {
+			StorageKind::Map { flavor: MapFlavor::Linked, .. } => {
+				keys.push(final_key(format!("HEAD_KEY_FOR_{}", name), Some(item), format!("head of {}", name)));
+				keys.push(final_key(format!("LINKAGE_PREFIX_FOR_{}", name), Some(item), format!("linkage of {}", name)));
+			},
+			StorageKind::Map { flavor: MapFlavor::Counted, .. } => {
+				keys.push(final_key(format!("COUNT_KEY_FOR_{}", name), Some(item), format!("count of {}", name)));
+			},
+			StorageKind::ChildMap { ref child, .. } => {
+				let mut key = instance_prefix.as_bytes().to_vec();
+				key.extend(child.value());
+				keys.push(byte_key(format!("CHILD_KEY_FOR_{}", name), Some(item), true, key));
+			},
+			_ => {},
+		}
+	}
+	keys
+}
+
+/// Refuse items whose keys collide: two keys of the main storage that are equal or of which one
+/// starts with the other, as the entries under the longer key would be read and removed through
+/// the shorter one, or two child storages under the same key.
+pub fn check_keys(def: &StorageDefinition) -> syn::Result<()> {
+	// the prefix of the module is not a key of its own.
+	let keys = storage_keys(def, "").into_iter().skip(1).collect::<Vec<_>>();
+	for (i, key) in keys.iter().enumerate() {
+		for other in &keys[..i] {
+			let collides = match (key.child, other.child) {
+				(false, false) => key.key.starts_with(&other.key) || other.key.starts_with(&key.key),
+				(true, true) => key.key == other.key,
+				_ => false,
+			};
+			if collides {
+				let describe = |key: &StorageKey| key.item
+					.map(|item| format!("`{}`", item.name))
+					.unwrap_or_else(|| "the storage version".into());
+				let span = key.item.map_or_else(Span::call_site, |item| item.name.span());
+				return Err(syn::Error::new(
+					span,
+					format!("the storage key of {} collides with the one of {}", describe(key), describe(other)),
+				));
+			}
+		}
+	}
+	Ok(())
+}
+
+/// The types of which the size can't be bounded, which a `bounded` storage refuses.
+const UNBOUNDED_TYPES: &[&str] = &["Vec", "String", "BTreeMap", "BTreeSet", "VecDeque", "BinaryHeap"];
+
+/// Refuse, in a `bounded` storage, blobs and values of a type containing an unbounded collection,
+/// whose size a `MaxEncodedLen` can't be given for.
+pub fn check_bounded(def: &StorageDefinition) -> syn::Result<()> {
+	if !def.bounded {
+		return Ok(());
+	}
+	for item in &def.items {
+		if item.blob {
+			return Err(syn::Error::new(
+				item.name.span(),
+				"blobs are not bounded; a `bounded` storage takes a `BoundedVec<u8, _>` instead",
+			));
+		}
+		if let Some(ident) = unbounded_collection(item.kind.value_type()) {
+			return Err(syn::Error::new(
+				ident.span(),
+				format!(
+					"`{}` is not bounded; a `bounded` storage takes a `BoundedVec` or a `BoundedBTreeMap` instead",
+					ident,
+				),
+			));
+		}
+	}
+	Ok(())
+}
+
+/// The first collection of `UNBOUNDED_TYPES` that `ty` is or is made of.
+fn unbounded_collection(ty: &Type) -> Option<&Ident> {
+	match *ty {
+		Type::Path(ref path) => {
+			if let Some(ref qself) = path.qself {
+				if let Some(ident) = unbounded_collection(&qself.ty) {
+					return Some(ident);
+				}
+			}
+			for segment in path.path.segments.iter() {
+				if UNBOUNDED_TYPES.iter().any(|name| segment.ident == *name) {
+					return Some(&segment.ident);
+				}
+				if let syn::PathArguments::AngleBracketed(ref args) = segment.arguments {
+					for arg in args.args.iter() {
+						if let syn::GenericArgument::Type(ref ty) = *arg {
+							if let Some(ident) = unbounded_collection(ty) {
+								return Some(ident);
+							}
+						}
+					}
+				}
+			}
+			None
+		},
+		Type::Tuple(ref tuple) => tuple.elems.iter().filter_map(unbounded_collection).next(),
+		Type::Array(ref array) => unbounded_collection(&array.elem),
+		Type::Paren(ref paren) => unbounded_collection(&paren.elem),
+		Type::Group(ref group) => unbounded_collection(&group.elem),
+		_ => None,
+	}
+}
+
+/// `storage_max_encoded_lens`, the largest encoded value of each item of a `bounded` storage.
+fn expand_max_encoded_lens(def: &StorageDefinition) -> TokenStream {
+	let scrate = &def.scrate;
+	let mut lens = Vec::new();
+	let mut bounds = Vec::new();
+	for item in &def.items {
+		let name = lit_str(&item.name.to_string());
+		let ty = item.kind.value_type();
+		let ty = if item.compact {
+			quote! { #scrate::dispatch::Compact<#ty> }
+		} else {
+			quote! { #ty }
+		};
+		lens.push(quote! {
+			(#name, <#ty as #scrate::bounded::MaxEncodedLen>::max_encoded_len())
+		});
+		bounds.push(quote! { #ty: #scrate::bounded::MaxEncodedLen });
+	}
+	quote! {
+		/// The name of each storage item of this module with the largest size of its encoded
+		/// value, in the order the items are declared.
+		pub fn storage_max_encoded_lens() -> #scrate::dispatch::Vec<(&'static str, usize)>
+			where #( #bounds ),*
+		{
+			<[_]>::into_vec(#scrate::metadata::Box::new([ #( #lens ),* ]))
+		}
+	}
+}
+
+/// The key of the storage of the module listed under `name` by `storage_keys`: the literal key, or
+/// with instances the constant of the instance holding it.
+fn storage_key(def: &StorageDefinition, name: &str) -> TokenStream {
+	match def.generics.instance {
+		Some(ref instance) => {
+			let (instance, bound) = (&instance.name, &instance.bound);
+			let name = Ident::new(name, Span::call_site());
+			quote! { <#instance as #bound>::#name }
+		},
+		None => {
+			let key = storage_keys(def, "").into_iter()
+				.find(|key| key.name == name)
+				.map(|key| key.literal())
+				.expect("keys are only looked up under the names `storage_keys` lists them with; qed");
+			quote! { #key }
+		},
+	}
+}
+
+/// The number of instances generated for a module that can be included several times in a
+/// runtime, besides `DefaultInstance`.
+const INSTANCES: usize = 16;
+
+/// The instance trait of a module declared with an instance parameter, together with
+/// `DefaultInstance`, which keeps the keys the module would have without instances, and
+/// `Instance1` to `Instance16`.
+fn expand_instances(def: &StorageDefinition) -> TokenStream {
+	let scrate = &def.scrate;
+	let bound = match def.generics.instance {
+		Some(ref instance) => &instance.bound,
+		None => return quote! {},
+	};
+
+	let consts = storage_keys(def, "").into_iter().map(|key| {
+		let name = key.name;
+		quote! {
+			#[doc(hidden)]
+			const #name: &'static [u8];
+		}
+	});
+
+	let instances = (0..INSTANCES + 1).map(|i| {
+		let (name, doc, prefix) = if i == 0 {
+			(
+				"DefaultInstance".to_owned(),
+				"The instance keeping its storage under the keys the module would have without instances.".to_owned(),
+				String::new(),
+			)
+		} else {
+			(format!("Instance{}", i), format!("Instance number {} of the module.", i), format!("Instance{}", i))
+		};
+		let name = Ident::new(&name, Span::call_site());
+		let prefix_lit = lit_str(&prefix);
+		let consts = storage_keys(def, &prefix).into_iter().map(|key| {
+			let (name, literal) = (&key.name, key.literal());
+			quote! {
+				const #name: &'static [u8] = #literal;
+			}
+		});
+		quote! {
+			#[doc = #doc]
+			#[derive(Clone, Copy, PartialEq, Eq)]
+			#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+			pub struct #name;
+
+			#[allow(non_upper_case_globals)]
+			impl #bound for #name {
+				const PREFIX: &'static str = #prefix_lit;
+				#(#consts)*
+			}
+
+			impl #scrate::codec::Encode for #name {
+				fn encode_to<W: #scrate::codec::Output>(&self, _dest: &mut W) {}
+			}
+
+			impl #scrate::codec::Decode for #name {
+				fn decode<I: #scrate::codec::Input>(_input: &mut I) -> Option<Self> {
+					Some(#name)
+				}
+			}
+		}
+	});
+
+	quote! {
+		/// An instance of the module, keeping its storage apart from the other instances.
+		#[allow(non_upper_case_globals)]
+		pub trait #bound: 'static {
+			/// The prefix of the storage keys of the instance, empty for `DefaultInstance`.
+			const PREFIX: &'static str;
+			#(#consts)*
+		}
+
+		#(#instances)*
+	}
+}
+
+/// The key and value types of a single item, as written in its metadata; none for a blob.
+fn item_types(scrate: &TokenTree, item: &StorageItem) -> Vec<(String, TokenStream)> {
+	let plain = |ty: &Type| (type_to_string(ty), quote! { #ty });
+	match item.kind {
+		StorageKind::Value(ref ty) if item.compact => {
+			vec![(format!("Compact<{}>", type_to_string(ty)), quote! { #scrate::dispatch::Compact<#ty> })]
+		},
+		StorageKind::Value(_) if item.blob => Vec::new(),
+		StorageKind::Value(ref ty) => vec![plain(ty)],
+		StorageKind::Map { ref key, ref value, .. } |
+		StorageKind::ChildMap { ref key, ref value, .. } => vec![plain(key), plain(value)],
+		StorageKind::DoubleMap { ref key1, ref key2, ref value } => vec![plain(key1), plain(key2), plain(value)],
+		StorageKind::NMap { ref keys, ref value } => keys.iter().chain(Some(value)).map(plain).collect(),
+		StorageKind::Deque { ref value, .. } | StorageKind::OrderedSet { ref value } => vec![plain(value)],
+	}
+}
+
+/// The JSON description of a single item, as a list of `concat!` arguments.
+fn item_json_metadata(item: &StorageItem, prefix: &str) -> TokenStream {
+	let prefix = lit_str(prefix);
+	let name = lit_str(&item.name.to_string());
+	let docs = docs_to_json(&item.docs);
+	let modifier = lit_str(match item.modifier {
+		Modifier::Optional => "null",
+		Modifier::Default => r#""default""#,
+		Modifier::Required => r#""required""#,
+	});
+	let ty = lit_str(&match item.kind {
+		StorageKind::Value(ref ty) if item.compact => format!("\"Compact<{}>\"", type_to_string(ty)),
+		StorageKind::Value(_) if item.blob => "\"Blob\"".to_owned(),
+		StorageKind::Value(ref ty) => format!("\"{}\"", type_to_string(ty)),
+		StorageKind::Map { ref key, ref value, flavor, hasher } => format!(
+			r#"{{ "key": "{}", "value": "{}", "hasher": "{}", "kind": "{}" }}"#,
+			type_to_string(key),
+			type_to_string(value),
+			hasher.unwrap_or(Hasher::Identity).name(),
+			match flavor {
+				MapFlavor::Plain => "map",
+				MapFlavor::Linked => "linked_map",
+				MapFlavor::Counted => "counted_map",
+			},
+		),
+		StorageKind::DoubleMap { ref key1, ref key2, ref value } => format!(
+			r#"{{ "key1": "{}", "key2": "{}", "value": "{}", "hashers": [ "{}", "{}" ] }}"#,
+			type_to_string(key1), type_to_string(key2), type_to_string(value),
+			Hasher::Twox128.name(), Hasher::Blake2_256.name(),
+		),
+		StorageKind::NMap { ref keys, ref value } => format!(
+			r#"{{ "keys": [ {} ], "value": "{}", "hashers": [ {} ] }}"#,
+			keys.iter().map(|key| format!("\"{}\"", type_to_string(key))).collect::<Vec<_>>().join(", "),
+			type_to_string(value),
+			keys.iter().map(|_| format!("\"{}\"", Hasher::Blake2_256.name())).collect::<Vec<_>>().join(", "),
+		),
+		StorageKind::ChildMap { ref child, ref key, ref value } => format!(
+			r#"{{ "key": "{}", "value": "{}", "child": {:?} }}"#,
+			type_to_string(key),
+			type_to_string(value),
+			String::from_utf8_lossy(&child.value()),
+		),
+		StorageKind::Deque { ref value, ref capacity } => format!(
+			r#"{{ "value": "{}", "capacity": "{}" }}"#,
+			type_to_string(value),
+			expr_to_string(capacity),
+		),
+		StorageKind::OrderedSet { ref value } => format!(r#"{{ "value": "{}", "sorted": true }}"#, type_to_string(value)),
+	});
+
+	quote! {
+		#prefix, " \"", #name, "\": { ",
+		r#""description": ["#, #(#docs,)* " ], ",
+		r#""modifier": "#, #modifier, r#", "type": "#, #ty, r#" }"#
+	}
+}
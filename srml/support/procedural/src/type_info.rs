@@ -0,0 +1,163 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `#[derive(TypeInfo)]`, describing a struct or an enum as it is encoded by
+//! `#[derive(Encode, Decode)]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use syn::{Data, DeriveInput, Fields, GenericParam, Ident};
+use syn::spanned::Spanned;
+
+use utils::{self, lit_str};
+
+pub fn derive_type_info_impl(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match expand(input) {
+		Ok(tokens) => tokens.into(),
+		Err(e) => e.to_compile_error().into(),
+	}
+}
+
+fn expand(mut input: DeriveInput) -> syn::Result<TokenStream2> {
+	let name = &input.ident;
+	// Named after the path of the type, as types of different modules may have the same name.
+	let name_str = lit_str(&format!("::{}", name));
+
+	let params = input.generics.type_params().map(|param| param.ident.clone()).collect::<Vec<_>>();
+	for param in input.generics.params.iter_mut() {
+		if let GenericParam::Type(ref mut param) = *param {
+			param.bounds.push(parse_quote!(_srml_support::type_info::TypeInfo));
+		}
+	}
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let type_name = if params.is_empty() {
+		quote! { concat!(module_path!(), #name_str).into() }
+	} else {
+		quote! {
+			_srml_support::type_info::generic_name(
+				concat!(module_path!(), #name_str),
+				&[ #( <#params as _srml_support::type_info::TypeInfo>::type_name() ),* ],
+			)
+		}
+	};
+
+	let type_def = match input.data {
+		Data::Struct(ref data) => {
+			let fields = fields(&data.fields)?;
+			quote! { _srml_support::type_info::TypeDef::Struct(#fields) }
+		},
+		Data::Enum(ref data) => {
+			let mut variants = Vec::new();
+			let mut indices = Vec::new();
+			for (position, variant) in data.variants.iter().enumerate() {
+				if let Some((_, ref discriminant)) = variant.discriminant {
+					return Err(syn::Error::new(
+						discriminant.span(),
+						"variants are encoded with their position or with `#[codec(index = N)]`, not their discriminant",
+					));
+				}
+				let mut index = None;
+				for attr in &variant.attrs {
+					if let Some(res) = utils::codec_index_of(attr) {
+						index = Some(res?);
+					}
+				}
+				let index = index.unwrap_or(position as u8);
+				if let Some(i) = indices.iter().position(|i| *i == index) {
+					return Err(syn::Error::new(
+						variant.ident.span(),
+						format!("index {} is already the one of `{}`", index, data.variants[i].ident),
+					));
+				}
+				indices.push(index);
+				let variant_name = lit_str(&variant.ident.to_string());
+				let fields = fields(&variant.fields)?;
+				variants.push(quote! {
+					_srml_support::type_info::VariantDef {
+						name: #variant_name.into(),
+						index: #index,
+						fields: #fields,
+					}
+				});
+			}
+			let variants = list(variants);
+			quote! { _srml_support::type_info::TypeDef::Enum(#variants) }
+		},
+		Data::Union(ref data) => {
+			return Err(syn::Error::new(data.union_token.span(), "unions can't be described"));
+		},
+	};
+	let dummy_const = Ident::new(&format!("_IMPL_TYPE_INFO_FOR_{}", name), Span::call_site());
+	Ok(quote! {
+		const #dummy_const: () = {
+			#[allow(unknown_lints)]
+			#[allow(rust_2018_idioms)]
+			extern crate srml_support as _srml_support;
+
+			impl #impl_generics _srml_support::type_info::TypeInfo for #name #ty_generics #where_clause {
+				fn type_name() -> _srml_support::type_info::String {
+					#type_name
+				}
+
+				fn type_def(
+					registry: &mut _srml_support::type_info::TypeRegistry,
+				) -> _srml_support::type_info::TypeDef {
+					#type_def
+				}
+			}
+		};
+	})
+}
+
+/// The `FieldDef`s of `fields`, the `Compact` type of a field declared `#[codec(compact)]`.
+fn fields(fields: &Fields) -> syn::Result<TokenStream2> {
+	let mut defs = Vec::new();
+	for field in fields.iter() {
+		let mut compact = false;
+		for attr in &field.attrs {
+			if let Some(res) = utils::codec_compact_of(attr) {
+				res?;
+				compact = true;
+			}
+		}
+		let ty = &field.ty;
+		let ty = if compact {
+			quote! { _srml_support::dispatch::Compact<#ty> }
+		} else {
+			quote! { #ty }
+		};
+		let name = match field.ident {
+			Some(ref ident) => {
+				let name = lit_str(&ident.to_string());
+				quote! { Some(#name) }
+			},
+			None => quote! { None },
+		};
+		defs.push(quote! {
+			_srml_support::type_info::field::<#ty>(registry, #name)
+		});
+	}
+	Ok(list(defs))
+}
+
+/// A `Vec` of `items`, built without `vec!`, which isn't there without `std`.
+fn list(items: Vec<TokenStream2>) -> TokenStream2 {
+	quote! {
+		<[_]>::into_vec(_srml_support::metadata::Box::new([ #(#items),* ]))
+	}
+}
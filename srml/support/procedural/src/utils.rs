@@ -0,0 +1,393 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers shared by the macros.
+
+use std::hash::Hasher;
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{Attribute, Expr, Ident, Lit, LitStr, Meta, NestedMeta, Type, TypeParam, TypeParamBound, WhereClause};
+use syn::parse::{Parse, ParseStream, Parser, Result};
+use syn::spanned::Spanned;
+
+/// A string literal with the call-site span.
+pub fn lit_str(value: &str) -> LitStr {
+	LitStr::new(value, Span::call_site())
+}
+
+/// The XX 128-bit hash of `data`, as `runtime_io::twox_128` computes it in the runtime.
+pub fn twox_128(data: &[u8]) -> [u8; 16] {
+	let mut r = [0u8; 16];
+	for (seed, chunk) in r.chunks_mut(8).enumerate() {
+		let mut h = twox_hash::XxHash::with_seed(seed as u64);
+		h.write(data);
+		let hash = h.finish();
+		for (i, byte) in chunk.iter_mut().enumerate() {
+			*byte = (hash >> (8 * i)) as u8;
+		}
+	}
+	r
+}
+
+/// A type as it would be written in the source, e.g. `Vec<T::AccountId>` rather than the
+/// `Vec < T :: AccountId >` of `TokenStream`'s `Display`.
+pub fn type_to_string(ty: &Type) -> String {
+	let mut res = String::new();
+	write_tokens(ty.into_token_stream(), &mut res);
+	res
+}
+
+/// An expression as it would be written in the source, in the way of `type_to_string`.
+pub fn expr_to_string(expr: &Expr) -> String {
+	let mut res = String::new();
+	write_tokens(expr.into_token_stream(), &mut res);
+	res
+}
+
+fn write_tokens(tokens: TokenStream, out: &mut String) {
+	let mut tokens = tokens.into_iter().peekable();
+	while let Some(token) = tokens.next() {
+		match token {
+			TokenTree::Group(group) => {
+				let (open, close) = match group.delimiter() {
+					Delimiter::Parenthesis => ("(", ")"),
+					Delimiter::Bracket => ("[", "]"),
+					Delimiter::Brace => ("{", "}"),
+					Delimiter::None => ("", ""),
+				};
+				if group.delimiter() == Delimiter::Bracket && ends_with_word(out) {
+					out.push(' ');
+				}
+				out.push_str(open);
+				write_tokens(group.stream(), out);
+				out.push_str(close);
+			},
+			TokenTree::Punct(punct) => match punct.as_char() {
+				',' | ';' => {
+					out.push(punct.as_char());
+					out.push(' ');
+				},
+				'-' if tokens.peek().map_or(false, |t| t.to_string() == ">") => {
+					tokens.next();
+					out.push_str(" -> ");
+				},
+				'=' | '+' => {
+					out.push(' ');
+					out.push(punct.as_char());
+					out.push(' ');
+				},
+				c => out.push(c),
+			},
+			other => {
+				if ends_with_word(out) {
+					out.push(' ');
+				}
+				out.push_str(&other.to_string());
+			},
+		}
+	}
+}
+
+fn ends_with_word(out: &str) -> bool {
+	out.ends_with(|c: char| c.is_alphanumeric() || c == '_')
+}
+
+/// The generics of a module: `<T: Trait>`, or `<T: Trait<I>, I: Instance>` for a module that can
+/// be included several times in a runtime, possibly followed by further type parameters and, in
+/// `decl_storage!`, a `where` clause.
+pub struct ModuleGenerics {
+	pub trait_instance: Ident,
+	pub trait_type: Ident,
+	pub instance: Option<InstanceGeneric>,
+	/// The type parameters following the trait and the instance, e.g. `X: Convert<u64>`.
+	pub params: Vec<TypeParam>,
+	pub where_clause: Option<WhereClause>,
+}
+
+/// The instance parameter of a module, e.g. `I: Instance`.
+pub struct InstanceGeneric {
+	pub name: Ident,
+	pub bound: Ident,
+}
+
+impl ModuleGenerics {
+	/// The parameters of an impl block for the module, e.g. `T: Trait<I>, I: Instance`.
+	pub fn impl_generics(&self) -> TokenStream {
+		let (trait_instance, trait_type) = (&self.trait_instance, &self.trait_type);
+		let params = self.params.iter().map(|param| {
+			let (ident, bounds) = (&param.ident, &param.bounds);
+			quote! { , #ident: #bounds }
+		});
+		match self.instance {
+			Some(InstanceGeneric { ref name, ref bound }) =>
+				quote! { #trait_instance: #trait_type<#name>, #name: #bound #(#params)* },
+			None => quote! { #trait_instance: #trait_type #(#params)* },
+		}
+	}
+
+	/// The arguments of the types of the module, e.g. `T, I`.
+	pub fn type_generics(&self) -> TokenStream {
+		let trait_instance = &self.trait_instance;
+		let params = self.params.iter().map(|param| &param.ident);
+		match self.instance {
+			Some(InstanceGeneric { ref name, .. }) => quote! { #trait_instance, #name #(, #params)* },
+			None => quote! { #trait_instance #(, #params)* },
+		}
+	}
+
+	/// The type held by the `PhantomData` of the types of the module, e.g. `(T, I)`.
+	pub fn phantom(&self) -> TokenStream {
+		if self.instance.is_some() || !self.params.is_empty() {
+			let type_generics = self.type_generics();
+			quote! { (#type_generics) }
+		} else {
+			self.type_generics()
+		}
+	}
+
+	/// The `where` clause of the module, empty if none was given.
+	pub fn where_clause(&self) -> TokenStream {
+		let predicates = self.where_predicates();
+		match self.where_clause {
+			Some(_) => quote! { where #predicates },
+			None => quote! {},
+		}
+	}
+
+	/// The predicates of the `where` clause of the module followed by a comma, to add to another
+	/// `where` clause.
+	pub fn where_predicates(&self) -> TokenStream {
+		let predicates = self.where_clause.iter().flat_map(|clause| clause.predicates.iter());
+		quote! { #( #predicates, )* }
+	}
+}
+
+impl Parse for ModuleGenerics {
+	fn parse(input: ParseStream) -> Result<Self> {
+		input.parse::<Token![<]>()?;
+		let trait_instance = input.parse()?;
+		input.parse::<Token![:]>()?;
+		let trait_type = input.parse()?;
+		let trait_param = if input.peek(Token![<]) {
+			input.parse::<Token![<]>()?;
+			let param: Ident = input.parse()?;
+			input.parse::<Token![>]>()?;
+			Some(param)
+		} else {
+			None
+		};
+		let instance = match trait_param {
+			Some(param) => {
+				if !input.peek(Token![,]) {
+					return Err(syn::Error::new(param.span(), format!("expected an instance parameter `{}: Instance`", param)));
+				}
+				input.parse::<Token![,]>()?;
+				let name: Ident = input.parse()?;
+				input.parse::<Token![:]>()?;
+				let bound = input.parse()?;
+				if param != name {
+					return Err(syn::Error::new(
+						name.span(),
+						format!("the trait must be instantiated with the instance: `{}: {}<{}>`", trait_instance, trait_type, name),
+					));
+				}
+				Some(InstanceGeneric { name, bound })
+			},
+			None => None,
+		};
+		let mut params = Vec::new();
+		while input.peek(Token![,]) {
+			input.parse::<Token![,]>()?;
+			let param: TypeParam = input.parse()?;
+			if let Some(ref default) = param.default {
+				return Err(syn::Error::new(default.span(), "default types are not supported on the parameters of a module"));
+			}
+			if instance.is_none() && params.is_empty() && is_instance_bound(&param) {
+				return Err(syn::Error::new(
+					param.ident.span(),
+					format!("the trait must be instantiated with the instance: `{}: {}<{}>`", trait_instance, trait_type, param.ident),
+				));
+			}
+			params.push(param);
+		}
+		input.parse::<Token![>]>()?;
+
+		Ok(ModuleGenerics { trait_instance, trait_type, instance, params, where_clause: None })
+	}
+}
+
+/// Whether `param` is bounded by `Instance` only, as the instance parameter of a module.
+fn is_instance_bound(param: &TypeParam) -> bool {
+	param.bounds.len() == 1 && match param.bounds[0] {
+		TypeParamBound::Trait(ref bound) => bound.path.segments.last().map_or(false, |s| s.value().ident == "Instance"),
+		_ => false,
+	}
+}
+
+/// The string of a `#[doc = ".."]` attribute, `None` if `attr` is not a doc attribute.
+pub fn doc_of(attr: &Attribute) -> Option<syn::Result<LitStr>> {
+	match attr.interpret_meta() {
+		Some(Meta::NameValue(ref meta)) if meta.ident == "doc" => Some(match meta.lit {
+			Lit::Str(ref doc) => Ok(doc.clone()),
+			ref lit => Err(syn::Error::new(lit.span(), "expected a string literal")),
+		}),
+		_ => None,
+	}
+}
+
+/// Check a `#[codec(..)]` attribute, which must be `#[codec(compact)]`, or its short form
+/// `#[compact]`; `None` if `attr` is neither.
+pub fn codec_compact_of(attr: &Attribute) -> Option<syn::Result<()>> {
+	match attr.interpret_meta() {
+		Some(Meta::Word(ref word)) if word == "compact" => Some(Ok(())),
+		Some(Meta::List(ref meta)) if meta.ident == "codec" => {
+			let is_compact = meta.nested.len() == 1 && match meta.nested[0] {
+				NestedMeta::Meta(Meta::Word(ref word)) => word == "compact",
+				_ => false,
+			};
+			Some(if is_compact {
+				Ok(())
+			} else {
+				Err(syn::Error::new(meta.nested.span(), "expected `compact`"))
+			})
+		},
+		_ => None,
+	}
+}
+
+/// The index given by a `#[codec(index = N)]` attribute, which encodes a variant of an enum with
+/// `N` rather than with its position; `None` if `attr` is not such an attribute. The index may be
+/// given as a string too, as `parity-codec-derive` requires it on the types deriving `Encode`.
+pub fn codec_index_of(attr: &Attribute) -> Option<syn::Result<u8>> {
+	match attr.interpret_meta() {
+		Some(Meta::List(ref meta)) if meta.ident == "codec" => match meta.nested.first().map(|n| n.into_value()) {
+			Some(&NestedMeta::Meta(Meta::NameValue(ref index))) if meta.nested.len() == 1 && index.ident == "index" =>
+				Some(match index.lit {
+					Lit::Int(ref lit) if lit.value() <= u64::from(u8::max_value()) => Ok(lit.value() as u8),
+					Lit::Str(ref lit) => lit.value().parse()
+						.map_err(|_| syn::Error::new(lit.span(), "expected an index fitting in a byte")),
+					ref lit => Err(syn::Error::new(lit.span(), "expected an index fitting in a byte")),
+				}),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// The weight given by a `#[weight = ..]` attribute; `None` if `attr` is not a weight attribute.
+pub fn weight_of(attr: &Attribute) -> Option<syn::Result<Expr>> {
+	let is_weight = attr.path.leading_colon.is_none()
+		&& attr.path.segments.len() == 1
+		&& attr.path.segments[0].ident == "weight";
+	if !is_weight {
+		return None;
+	}
+	let parser = |input: ParseStream| {
+		input.parse::<Token![=]>()?;
+		input.parse::<Expr>()
+	};
+	Some(parser.parse2(attr.tts.clone()))
+}
+
+/// Whether the sender pays the fees according to a `#[pays_fee = yes|no]` attribute; `None` if
+/// `attr` is not a `pays_fee` attribute.
+pub fn pays_fee_of(attr: &Attribute) -> Option<syn::Result<bool>> {
+	let is_pays_fee = attr.path.leading_colon.is_none()
+		&& attr.path.segments.len() == 1
+		&& attr.path.segments[0].ident == "pays_fee";
+	if !is_pays_fee {
+		return None;
+	}
+	let parser = |input: ParseStream| {
+		input.parse::<Token![=]>()?;
+		let answer: Ident = input.parse()?;
+		if answer == "yes" {
+			Ok(true)
+		} else if answer == "no" {
+			Ok(false)
+		} else {
+			Err(syn::Error::new(answer.span(), "expected `yes` or `no`"))
+		}
+	};
+	Some(parser.parse2(attr.tts.clone()))
+}
+
+/// Whether `attr` is `#[operational]`.
+pub fn is_operational(attr: &Attribute) -> bool {
+	match attr.interpret_meta() {
+		Some(Meta::Word(ref word)) => word == "operational",
+		_ => false,
+	}
+}
+
+/// Whether `attr` is `#[whitelisted]`.
+pub fn is_whitelisted(attr: &Attribute) -> bool {
+	match attr.interpret_meta() {
+		Some(Meta::Word(ref word)) => word == "whitelisted",
+		_ => false,
+	}
+}
+
+/// Whether `attr` is a `#[serde(..)]` attribute, forwarded as is to a field of the genesis config.
+pub fn is_serde(attr: &Attribute) -> bool {
+	attr.path.leading_colon.is_none() && attr.path.segments.len() == 1 && attr.path.segments[0].ident == "serde"
+}
+
+/// `value` escaped to be put between the quotes of a JSON string.
+pub fn json_escape(value: &str) -> String {
+	let mut escaped = String::with_capacity(value.len());
+	for c in value.chars() {
+		match c {
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\t' => escaped.push_str("\\t"),
+			c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// The JSON array items of a list of doc lines, as `concat!` arguments.
+pub fn docs_to_json(docs: &[LitStr]) -> Vec<TokenStream> {
+	docs.iter().enumerate().map(|(i, doc)| {
+		let item = lit_str(&format!(r#"{} "{}""#, if i == 0 { "" } else { "," }, json_escape(&doc.value())));
+		quote! { #item }
+	}).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn docs_are_escaped() {
+		assert_eq!(json_escape(r#"A "quoted" \ path"#), r#"A \"quoted\" \\ path"#);
+		assert_eq!(json_escape("tab\tand\u{1}"), "tab\\tand\\u0001");
+	}
+
+	#[test]
+	fn types_are_printed_as_written() {
+		let ty = |s: &str| type_to_string(&syn::parse_str::<Type>(s).unwrap());
+		assert_eq!(ty("T :: Origin"), "T::Origin");
+		assert_eq!(ty("Vec<(T::AccountId, u32)>"), "Vec<(T::AccountId, u32)>");
+		assert_eq!(ty("[u8; 32]"), "[u8; 32]");
+		assert_eq!(ty("&'static [u8]"), "&'static [u8]");
+		assert_eq!(ty("<T as Trait>::Balance"), "<T as Trait>::Balance");
+		assert_eq!(ty("Box<Fn(u32) -> u32 + Send>"), "Box<Fn(u32) -> u32 + Send>");
+	}
+}
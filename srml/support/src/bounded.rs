@@ -0,0 +1,421 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Collections that refuse to grow past a bound fixed by their type, so that the size of a
+//! storage value holding them is known in advance.
+//!
+//! The bound is given by a type implementing `Get<u32>`:
+//!
+//! ```rust
+//! # extern crate srml_support;
+//! use srml_support::bounded::{BoundedVec, Get, MaxEncodedLen};
+//!
+//! pub struct MaxValidators;
+//! impl Get<u32> for MaxValidators {
+//! 	fn get() -> u32 { 2 }
+//! }
+//!
+//! # fn main() {
+//! let mut validators = BoundedVec::<u64, MaxValidators>::default();
+//! assert!(validators.try_push(1).is_ok());
+//! assert!(validators.try_push(2).is_ok());
+//! assert!(validators.try_push(3).is_err());
+//! assert_eq!(BoundedVec::<u64, MaxValidators>::max_encoded_len(), 4 + 2 * 8);
+//! # }
+//! ```
+
+use codec::{Decode, Encode, Input, Output};
+use primitives::compact::{Compact, HasCompact};
+use primitives::{H160, H256, H512, AuthorityId};
+use runtime_primitives::Permill;
+use rstd::collections::btree_map::BTreeMap;
+use rstd::marker::PhantomData;
+use rstd::mem;
+use rstd::ops::Deref;
+use rstd::prelude::*;
+
+/// A value fixed by a type.
+pub trait Get<T> {
+	/// The value.
+	fn get() -> T;
+}
+
+impl<T: Default> Get<T> for () {
+	fn get() -> T {
+		T::default()
+	}
+}
+
+/// The largest number of bytes a value of the type can be encoded to.
+///
+/// Implemented for types of bounded size only, which `#[derive(MaxEncodedLen)]` sums up for a
+/// struct or an enum of them; `Vec` and `String` have none, `BoundedVec` has one instead.
+pub trait MaxEncodedLen {
+	/// The maximum encoded length.
+	fn max_encoded_len() -> usize;
+}
+
+macro_rules! impl_max_encoded_len_for_fixed_size {
+	( $( $t:ty ),* ) => { $(
+		impl MaxEncodedLen for $t {
+			fn max_encoded_len() -> usize {
+				mem::size_of::<$t>()
+			}
+		}
+	)* }
+}
+
+impl_max_encoded_len_for_fixed_size!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, bool, (),
+	H160, H256, H512, AuthorityId, Permill);
+
+macro_rules! impl_max_encoded_len_for_array {
+	( $( $n:expr )* ) => { $(
+		impl<T: MaxEncodedLen> MaxEncodedLen for [T; $n] {
+			fn max_encoded_len() -> usize {
+				T::max_encoded_len() * $n
+			}
+		}
+	)* }
+}
+
+impl_max_encoded_len_for_array!(1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28
+	29 30 31 32 40 48 56 64 72 96 128 160 192 224 256);
+
+macro_rules! impl_max_encoded_len_for_tuple {
+	( $( $t:ident ),+ ) => {
+		impl<$( $t: MaxEncodedLen ),+> MaxEncodedLen for ( $( $t, )+ ) {
+			fn max_encoded_len() -> usize {
+				0 $( + $t::max_encoded_len() )+
+			}
+		}
+	}
+}
+
+impl_max_encoded_len_for_tuple!(A);
+impl_max_encoded_len_for_tuple!(A, B);
+impl_max_encoded_len_for_tuple!(A, B, C);
+impl_max_encoded_len_for_tuple!(A, B, C, D);
+impl_max_encoded_len_for_tuple!(A, B, C, D, E);
+impl_max_encoded_len_for_tuple!(A, B, C, D, E, F);
+
+impl<T: MaxEncodedLen> MaxEncodedLen for Option<T> {
+	fn max_encoded_len() -> usize {
+		1 + T::max_encoded_len()
+	}
+}
+
+impl<T: MaxEncodedLen, E: MaxEncodedLen> MaxEncodedLen for Result<T, E> {
+	fn max_encoded_len() -> usize {
+		1 + T::max_encoded_len().max(E::max_encoded_len())
+	}
+}
+
+impl<T: MaxEncodedLen> MaxEncodedLen for Box<T> {
+	fn max_encoded_len() -> usize {
+		T::max_encoded_len()
+	}
+}
+
+impl<T> MaxEncodedLen for PhantomData<T> {
+	fn max_encoded_len() -> usize {
+		0
+	}
+}
+
+/// The largest value of a byte or two is encoded in the two or four byte mode, any wider one in
+/// the big integer mode, after a byte of prefix.
+impl<T: HasCompact + MaxEncodedLen> MaxEncodedLen for Compact<T> {
+	fn max_encoded_len() -> usize {
+		match T::max_encoded_len() {
+			0 | 1 => 2,
+			2 => 4,
+			n => 1 + n,
+		}
+	}
+}
+
+/// The number of bytes the length of a collection is encoded to.
+const LENGTH_PREFIX: usize = 4;
+
+/// A `Vec` of at most `S::get()` items.
+pub struct BoundedVec<T, S>(Vec<T>, PhantomData<S>);
+
+impl<T, S: Get<u32>> BoundedVec<T, S> {
+	/// The largest number of items.
+	pub fn bound() -> usize {
+		S::get() as usize
+	}
+
+	/// Wrap `items`, or fail if there are too many of them.
+	pub fn from_vec(items: Vec<T>) -> Result<Self, &'static str> {
+		if items.len() > Self::bound() {
+			return Err("too many items for the bound");
+		}
+		Ok(BoundedVec(items, PhantomData))
+	}
+
+	/// Append an item, or fail if the bound is reached.
+	pub fn try_push(&mut self, item: T) -> Result<(), &'static str> {
+		if self.0.len() >= Self::bound() {
+			return Err("bounded vector is full");
+		}
+		self.0.push(item);
+		Ok(())
+	}
+
+	/// Insert an item at `index`, shifting the following ones, or fail if the bound is reached.
+	pub fn try_insert(&mut self, index: usize, item: T) -> Result<(), &'static str> {
+		if self.0.len() >= Self::bound() {
+			return Err("bounded vector is full");
+		}
+		self.0.insert(index, item);
+		Ok(())
+	}
+
+	/// Remove and return the item at `index`, shifting the following ones.
+	pub fn remove(&mut self, index: usize) -> T {
+		self.0.remove(index)
+	}
+
+	/// Remove and return the last item, `None` if there is none.
+	pub fn pop(&mut self) -> Option<T> {
+		self.0.pop()
+	}
+
+	/// Keep only the items for which `f` returns true.
+	pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+		self.0.retain(f)
+	}
+
+	/// The items, without the bound.
+	pub fn into_inner(self) -> Vec<T> {
+		self.0
+	}
+}
+
+// Implemented by hand, as deriving would require the bound to implement the traits as well.
+impl<T: Clone, S> Clone for BoundedVec<T, S> {
+	fn clone(&self) -> Self {
+		BoundedVec(self.0.clone(), PhantomData)
+	}
+}
+
+impl<T: PartialEq, S> PartialEq for BoundedVec<T, S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<T: Eq, S> Eq for BoundedVec<T, S> {}
+
+#[cfg(feature = "std")]
+impl<T: ::std::fmt::Debug, S> ::std::fmt::Debug for BoundedVec<T, S> {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl<T, S> Default for BoundedVec<T, S> {
+	fn default() -> Self {
+		BoundedVec(Vec::new(), PhantomData)
+	}
+}
+
+impl<T, S> Deref for BoundedVec<T, S> {
+	type Target = [T];
+
+	fn deref(&self) -> &[T] {
+		&self.0[..]
+	}
+}
+
+impl<T: Encode, S> Encode for BoundedVec<T, S> {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.0.encode_to(dest)
+	}
+}
+
+impl<T: Decode, S: Get<u32>> Decode for BoundedVec<T, S> {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		Self::from_vec(Decode::decode(input)?).ok()
+	}
+}
+
+impl<T: Encode + Decode, S: Get<u32>> ::storage::generator::DecodeLength for BoundedVec<T, S> {}
+
+impl<T: MaxEncodedLen, S: Get<u32>> MaxEncodedLen for BoundedVec<T, S> {
+	fn max_encoded_len() -> usize {
+		LENGTH_PREFIX + Self::bound() * T::max_encoded_len()
+	}
+}
+
+/// A `BTreeMap` of at most `S::get()` entries.
+pub struct BoundedBTreeMap<K: Ord, V, S>(BTreeMap<K, V>, PhantomData<S>);
+
+impl<K: Ord, V, S: Get<u32>> BoundedBTreeMap<K, V, S> {
+	/// The largest number of entries.
+	pub fn bound() -> usize {
+		S::get() as usize
+	}
+
+	/// Wrap `entries`, or fail if there are too many of them.
+	pub fn from_map(entries: BTreeMap<K, V>) -> Result<Self, &'static str> {
+		if entries.len() > Self::bound() {
+			return Err("too many entries for the bound");
+		}
+		Ok(BoundedBTreeMap(entries, PhantomData))
+	}
+
+	/// Insert an entry, returning the value it replaces, or fail if the key is new and the bound
+	/// is reached.
+	pub fn try_insert(&mut self, key: K, value: V) -> Result<Option<V>, &'static str> {
+		if self.0.len() >= Self::bound() && !self.0.contains_key(&key) {
+			return Err("bounded map is full");
+		}
+		Ok(self.0.insert(key, value))
+	}
+
+	/// Remove the entry under `key`, returning its value.
+	pub fn remove(&mut self, key: &K) -> Option<V> {
+		self.0.remove(key)
+	}
+
+	/// The entries, without the bound.
+	pub fn into_inner(self) -> BTreeMap<K, V> {
+		self.0
+	}
+}
+
+impl<K: Ord + Clone, V: Clone, S> Clone for BoundedBTreeMap<K, V, S> {
+	fn clone(&self) -> Self {
+		BoundedBTreeMap(self.0.clone(), PhantomData)
+	}
+}
+
+impl<K: Ord, V: PartialEq, S> PartialEq for BoundedBTreeMap<K, V, S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<K: Ord, V: Eq, S> Eq for BoundedBTreeMap<K, V, S> {}
+
+#[cfg(feature = "std")]
+impl<K: Ord + ::std::fmt::Debug, V: ::std::fmt::Debug, S> ::std::fmt::Debug for BoundedBTreeMap<K, V, S> {
+	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl<K: Ord, V, S> Default for BoundedBTreeMap<K, V, S> {
+	fn default() -> Self {
+		BoundedBTreeMap(BTreeMap::new(), PhantomData)
+	}
+}
+
+impl<K: Ord, V, S> Deref for BoundedBTreeMap<K, V, S> {
+	type Target = BTreeMap<K, V>;
+
+	fn deref(&self) -> &BTreeMap<K, V> {
+		&self.0
+	}
+}
+
+/// Encoded like a `Vec` of its entries in key order.
+impl<K: Ord + Encode, V: Encode, S> Encode for BoundedBTreeMap<K, V, S> {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		(self.0.len() as u32).encode_to(dest);
+		for entry in self.0.iter() {
+			entry.encode_to(dest);
+		}
+	}
+}
+
+impl<K: Ord + Decode, V: Decode, S: Get<u32>> Decode for BoundedBTreeMap<K, V, S> {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		let len = u32::decode(input)? as usize;
+		if len > Self::bound() {
+			return None;
+		}
+		let mut entries = BTreeMap::new();
+		for _ in 0..len {
+			let (key, value) = Decode::decode(input)?;
+			entries.insert(key, value);
+		}
+		Self::from_map(entries).ok()
+	}
+}
+
+impl<K: Ord + MaxEncodedLen, V: MaxEncodedLen, S: Get<u32>> MaxEncodedLen for BoundedBTreeMap<K, V, S> {
+	fn max_encoded_len() -> usize {
+		LENGTH_PREFIX + Self::bound() * (K::max_encoded_len() + V::max_encoded_len())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Three;
+	impl Get<u32> for Three {
+		fn get() -> u32 { 3 }
+	}
+
+	#[test]
+	fn bounded_vec_refuses_to_grow_past_bound() {
+		let mut v = BoundedVec::<u32, Three>::from_vec(vec![1, 2]).unwrap();
+		assert!(v.try_insert(0, 0).is_ok());
+		assert!(v.try_push(3).is_err());
+		assert_eq!(&v[..], &[0, 1, 2]);
+		assert_eq!(v.pop(), Some(2));
+		assert!(v.try_push(3).is_ok());
+		assert!(BoundedVec::<u32, Three>::from_vec(vec![1, 2, 3, 4]).is_err());
+	}
+
+	#[test]
+	fn bounded_vec_decodes_only_within_bound() {
+		let v = BoundedVec::<u32, Three>::from_vec(vec![1, 2, 3]).unwrap();
+		let encoded = v.encode();
+		assert_eq!(encoded, vec![1u32, 2, 3].encode());
+		assert_eq!(BoundedVec::<u32, Three>::decode(&mut &encoded[..]), Some(v));
+		assert_eq!(BoundedVec::<u32, Three>::decode(&mut &vec![1u32, 2, 3, 4].encode()[..]), None);
+		assert_eq!(BoundedVec::<u32, Three>::max_encoded_len(), 4 + 3 * 4);
+	}
+
+	#[test]
+	fn bounded_btree_map_refuses_new_keys_past_bound() {
+		let mut m = BoundedBTreeMap::<u32, u64, Three>::default();
+		for i in 0..3 {
+			assert_eq!(m.try_insert(i, i as u64), Ok(None));
+		}
+		assert!(m.try_insert(3, 3).is_err());
+		assert_eq!(m.try_insert(2, 20), Ok(Some(2)));
+
+		let encoded = m.encode();
+		assert_eq!(encoded, vec![(0u32, 0u64), (1, 1), (2, 20)].encode());
+		assert_eq!(BoundedBTreeMap::<u32, u64, Three>::decode(&mut &encoded[..]), Some(m));
+		assert_eq!(BoundedBTreeMap::<u32, u64, Three>::max_encoded_len(), 4 + 3 * 12);
+	}
+
+	#[test]
+	fn compact_max_encoded_len_is_the_one_of_the_largest_value() {
+		assert_eq!(Compact::<u8>::max_encoded_len(), Compact(u8::max_value()).encode().len());
+		assert_eq!(Compact::<u16>::max_encoded_len(), Compact(u16::max_value()).encode().len());
+		assert_eq!(Compact::<u32>::max_encoded_len(), Compact(u32::max_value()).encode().len());
+		assert_eq!(Compact::<u64>::max_encoded_len(), Compact(u64::max_value()).encode().len());
+		assert_eq!(Compact::<u128>::max_encoded_len(), Compact(u128::max_value()).encode().len());
+	}
+}
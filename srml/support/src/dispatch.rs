@@ -23,13 +23,19 @@ pub use rstd::result;
 #[cfg(feature = "std")]
 use serde;
 pub use codec::{Codec, Decode, Encode, Input, Output};
+pub use primitives::compact::{Compact, HasCompact};
+pub use primitives::decode_limit::{DecodeAll, DecodeLimit, DepthGuard, MAX_DECODE_DEPTH};
+use type_info::{self, TypeDef, TypeInfo, TypeRegistry};
 
 pub type Result = result::Result<(), &'static str>;
 
+/// The result of the dispatch of a call.
+pub type DispatchResult = result::Result<(), DispatchError>;
+
 pub trait Dispatchable {
 	type Origin;
 	type Trait;
-	fn dispatch(self, origin: Self::Origin) -> Result;
+	fn dispatch(self, origin: Self::Origin) -> DispatchResult;
 }
 
 #[cfg(feature = "std")]
@@ -59,335 +65,345 @@ impl<T> Parameter for T where T: Codec + Clone + Eq {}
 
 /// Declare a struct for this module, then implement dispatch logic to create a pairing of several
 /// dispatch traits and enums.
+///
+/// Each dispatchable is declared as
+///
+/// ```rust,ignore
+/// /// Documentation of the call.
+/// fn transfer(origin, dest: T::AccountId, #[compact] value: u64) -> Result;
+/// ```
+///
+/// A dispatchable returns either a `dispatch::Result`, failing with a `&'static str`, or a
+/// `DispatchResult`, failing with a `DispatchError`, e.g. an `Error` declared with `decl_error!`.
+/// The `Call` dispatches to either as a `DispatchResult`, and the outer dispatch sets the index of
+/// the module in the error. Each call is dispatched in a storage transaction: the writes of a call
+/// which fails are discarded, see `storage::transactional`.
+///
+/// Functions which don't take `origin` may only be called by root: the generated dispatch calls
+/// `system::ensure_root` first, or the `ensure_root` of the module given with
+/// `where system = ..` after the origin type. Such a function may be declared `#[require_none]`
+/// to be called by unsigned extrinsics, e.g. inherents, instead: `ensure_none` is called then.
+/// `#[require_root]` states the default explicitly.
+///
+/// A dispatchable may be given a weight with `#[weight = <expr>]`, where the expression is a
+/// `Weight`; `DEFAULT_WEIGHT` is used otherwise. The weights are returned by the `GetDispatchInfo`
+/// implementation of the `Call`, which lets the executive limit the weight of a block.
+///
+/// The `DispatchInfo` also carries the class of the call, `DispatchClass::Operational` for calls
+/// declared with `#[operational]`, which the transaction pool prioritises, and whether the sender
+/// pays the transaction fees, which they don't for calls declared with `#[pays_fee = no]`.
+///
+/// Integer parameters may be prefixed with `#[compact]`, or its long form `#[codec(compact)]`, in
+/// which case they are encoded using the variable-length `Compact` encoding in the call. The metadata reports their type as
+/// `Compact<T>` and flags them with `"compact": true` so that clients know how to encode them.
+///
+/// A call is encoded with the position of its function among the dispatchable ones of the module,
+/// unless the function is given an index of its own with `#[codec(index = N)]`, which keeps the
+/// encoding of the call when functions are removed or reordered. No two calls may have the same
+/// index.
+///
+/// The calls are described by `Module::call_metadata()`, which is part of the JSON metadata of the
+/// module and lists, under the index of each call, its name, the name, type and encoding of its
+/// arguments, and its docs.
+///
+/// A module may also declare
+///
+/// ```rust,ignore
+/// fn on_storage_migration(from: u32);
+/// ```
+///
+/// which is called with the on-chain storage version whenever it is older than the `version(N)`
+/// declared in `decl_storage`, after which the on-chain version is updated. The hook is run
+/// through `OnStorageMigration`, which the executive calls at the start of each block.
+///
+/// Likewise, a module may declare
+///
+/// ```rust,ignore
+/// fn on_finalise(n: T::BlockNumber);
+/// ```
+///
+/// which the executive calls through `OnFinalise` with the number of the block after its last
+/// extrinsic. Modules without it implement `OnFinalise` doing nothing. In the same way,
+///
+/// ```rust,ignore
+/// fn offchain_worker(n: T::BlockNumber);
+/// ```
+///
+/// is called through `OffchainWorker` by the node once it has imported the block `n`, outside of
+/// consensus: it may submit extrinsics, e.g. heartbeats or price feeds, but its changes to the
+/// state are discarded.
+///
+/// Finally, a module may declare
+///
+/// ```rust,ignore
+/// fn on_runtime_upgrade() -> Weight;
+/// ```
+///
+/// which the executive calls through `OnRuntimeUpgrade` at the start of the first block whose
+/// runtime has a `spec_version` different from the one of the previous block, before the storage
+/// migrations. The returned weight is counted against the block.
+///
+/// A module with events, declared with `decl_event!`, may declare
+///
+/// ```rust,ignore
+/// fn deposit_event<T>() = default;
+/// ```
+///
+/// to generate `fn deposit_event(event: Event<T>)`, which converts the event into the `Event` of
+/// the `Trait` of the module and deposits it with `system::Module::deposit_event`, or with the
+/// module given with `where system = ..`. A module whose `Event` is not generic declares
+/// `fn deposit_event() = default;`, and an instantiable one `fn deposit_event<T, I>() = default;`.
+///
+/// A module declaring its errors with `decl_error!` names them with
+///
+/// ```rust,ignore
+/// type Error = Error;
+/// ```
+///
+/// which implements `ModuleErrors` with them, so that the `&'static str` failures of the module are
+/// numbered in its `DispatchError`s; other failures are numbered `UNDECLARED_ERROR`.
+///
+/// The constants of a module, e.g. the values of the `Get` types of its `Trait`, are declared with
+///
+/// ```rust,ignore
+/// /// The largest weight of a block.
+/// const MaximumBlockWeight: Weight = T::MaximumBlockWeight::get();
+/// ```
+///
+/// and returned with their encoded value and docs by `Module::module_constants_metadata()`, which
+/// is part of the runtime metadata.
+///
+/// The types of the arguments and of the constants, and the `Call` itself, must implement
+/// `TypeInfo` for the runtime metadata, which describes them with `Module::register_types()`.
+///
+/// A function given with a body rather than `-> Result;`, e.g.
+///
+/// ```rust,ignore
+/// fn is_councillor(who: &T::AccountId) -> bool {
+/// 	<ActiveCouncil<T>>::get().iter().any(|a| a == who)
+/// }
+/// ```
+///
+/// is not a call: it is emitted as is into the inherent `impl` of the module, keeping its
+/// visibility, so that small helpers can live next to the calls they serve.
+///
+/// A module whose calls may come from origins of its own, e.g. a number of the members of a
+/// council, declares them with
+///
+/// ```rust,ignore
+/// /// Origin for the council module.
+/// pub enum Origin {
+/// 	/// It has been condoned by a given number of council members.
+/// 	Members(u32),
+/// }
+/// ```
+///
+/// which becomes a variant of the outer `Origin` with `impl_outer_origin!`, or with the `Origin`
+/// part of the module in `construct_runtime!`. The outer `Origin` converts from it, and into an
+/// `Option` of it.
+///
+/// A module that can be included several times in a runtime is declared as
+/// `pub struct Module<T: Trait<I>, I: Instance> for enum Call where origin: T::Origin`, with the
+/// `Instance` trait declared by `decl_storage`; its `Call` then also takes the instance.
+///
+/// The declaration is expanded by a procedural macro, so syntax errors point at the offending
+/// function.
 #[macro_export]
 macro_rules! decl_module {
-	(
-		$(#[$attr:meta])*
-		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident>
-		for enum $call_type:ident where origin: $origin_type:ty {
-			$($t:tt)*
-		}
-	) => {
-		decl_module!(@normalize
-			$(#[$attr])*
-			pub struct $mod_type<$trait_instance: $trait_name>
-			for enum $call_type where origin: $origin_type where system = system
-			[]
-			$($t)*
-		);
-	};
-	(
-		$(#[$attr:meta])*
-		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident>
-		for enum $call_type:ident where origin: $origin_type:ty where system = $system:ident {
-			$($t:tt)*
-		}
-	) => {
-		decl_module!(@normalize
-			$(#[$attr])*
-			pub struct $mod_type<$trait_instance: $trait_name>
-			for enum $call_type where origin: $origin_type where system = $system
-			[]
-			$($t)*
-		);
-	};
+	($($t:tt)*) => {
+		$crate::__decl_module_impl! { $crate; $($t)* }
+	}
+}
 
-	(@normalize
-		$(#[$attr:meta])*
-		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident>
-		for enum $call_type:ident where origin: $origin_type:ty where system = $system:ident
-		[ $($t:tt)* ]
-		$(#[doc = $doc_attr:tt])*
-		fn $fn_name:ident(origin $(, $param_name:ident : $param:ty)* ) -> $result:ty ;
-		$($rest:tt)*
-	) => {
-		decl_module!(@normalize
-			$(#[$attr])*
-			pub struct $mod_type<$trait_instance: $trait_name>
-			for enum $call_type where origin: $origin_type where system = $system
-			[ $($t)* $(#[doc = $doc_attr])* fn $fn_name(origin $( , $param_name : $param )* ) -> $result; ]
-			$($rest)*
-		);
-	};
-	(@normalize
-		$(#[$attr:meta])*
-		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident>
-		for enum $call_type:ident where origin: $origin_type:ty where system = $system:ident
-		[ $($t:tt)* ]
-		$(#[doc = $doc_attr:tt])*
-		fn $fn_name:ident($( $param_name:ident : $param:ty),* ) -> $result:ty ;
-		$($rest:tt)*
-	) => {
-		decl_module!(@normalize
-			$(#[$attr])*
-			pub struct $mod_type<$trait_instance: $trait_name>
-			for enum $call_type where origin: $origin_type where system = $system
-			[ $($t)* $(#[doc = $doc_attr])* fn $fn_name(root $( , $param_name : $param )* ) -> $result; ]
-			$($rest)*
-		);
-	};
-	(@normalize
-		$(#[$attr:meta])*
-		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident>
-		for enum $call_type:ident where origin: $origin_type:ty where system = $system:ident
-		[ $($t:tt)* ]
-	) => {
-		decl_module!(@imp
-			$(#[$attr])*
-			pub struct $mod_type<$trait_instance: $trait_name>
-			for enum $call_type where origin: $origin_type where system = $system {
-				$($t)*
-			}
-		);
-	};
+/// A measure of the resources taken by the execution of a dispatchable.
+pub type Weight = u32;
+
+/// The weight of dispatchables declared without `#[weight = ..]`.
+pub const DEFAULT_WEIGHT: Weight = 10_000;
+
+/// The class of a dispatchable, given with `#[operational]` for operational ones.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum DispatchClass {
+	/// A call made by users.
+	Normal,
+	/// A call which keeps the chain running, e.g. a misbehaviour report; the transaction pool
+	/// prioritises these.
+	Operational,
+}
 
-	(@call
-		origin
-		$mod_type:ident $trait_instance:ident $fn_name:ident $origin:ident $system:ident [ $( $param_name:ident),* ]
-	) => {
-		<$mod_type<$trait_instance>>::$fn_name( $origin $(, $param_name )* )
-	};
-	(@call
-		root
-		$mod_type:ident $trait_instance:ident $fn_name:ident $origin:ident $system:ident [ $( $param_name:ident),* ]
-	) => {
-		{
-			$system::ensure_root($origin)?;
-			<$mod_type<$trait_instance>>::$fn_name( $( $param_name ),* )
-		}
-	};
+impl Default for DispatchClass {
+	fn default() -> Self {
+		DispatchClass::Normal
+	}
+}
 
-	(@imp
-		$(#[$attr:meta])*
-		pub struct $mod_type:ident<$trait_instance:ident: $trait_name:ident>
-		for enum $call_type:ident where origin: $origin_type:ty where system = $system:ident {
-		$(
-			$(#[doc = $doc_attr:tt])*
-			fn $fn_name:ident($from:ident $( , $param_name:ident : $param:ty)*) -> $result:ty;
-		)*}
-	) => {
-		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
-		#[derive(Clone, Copy, PartialEq, Eq)]
-		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
-		// TODO: switching based on std feature is because of an issue in
-		// serde-derive for when we attempt to derive `Deserialize` on these types,
-		// in a situation where we've imported `srml_support` as another name.
-		#[cfg(feature = "std")]
-		pub struct $mod_type<$trait_instance: $trait_name>(::std::marker::PhantomData<$trait_instance>);
-
-		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
-		#[derive(Clone, Copy, PartialEq, Eq)]
-		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
-		#[cfg(not(feature = "std"))]
-		pub struct $mod_type<$trait_instance: $trait_name>(::core::marker::PhantomData<$trait_instance>);
+/// What is known about the dispatch of a call before it happens.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DispatchInfo {
+	pub weight: Weight,
+	pub class: DispatchClass,
+	/// Whether the sender is charged the transaction fees, `false` for calls declared with
+	/// `#[pays_fee = no]`.
+	pub pays_fee: bool,
+}
 
-		#[cfg(feature = "std")]
-		$(#[$attr])*
-		#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-		pub enum $call_type<$trait_instance: $trait_name> {
-			__PhantomItem(::std::marker::PhantomData<$trait_instance>),
-			__OtherPhantomItem(::std::marker::PhantomData<$trait_instance>),
-			$(
-				#[allow(non_camel_case_types)]
-				$fn_name ( $( $param ),* ),
-			)*
-		}
+/// Something that knows about its dispatch before it happens, implemented by the `Call` of
+/// `decl_module!` and `impl_outer_dispatch!`.
+pub trait GetDispatchInfo {
+	fn get_dispatch_info(&self) -> DispatchInfo;
+}
 
-		#[cfg(not(feature = "std"))]
-		$(#[$attr])*
-		#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
-		pub enum $call_type<$trait_instance: $trait_name> {
-			__PhantomItem(::core::marker::PhantomData<$trait_instance>),
-			__OtherPhantomItem(::core::marker::PhantomData<$trait_instance>),
-			$(
-				#[allow(non_camel_case_types)]
-				$fn_name ( $( $param ),* ),
-			)*
-		}
+pub trait IsSubType<T: Callable> {
+	fn is_aux_sub_type(&self) -> Option<&<T as Callable>::Call>;
+}
 
-		// manual implementation of clone/eq/partialeq because using derive erroneously requires
-		// clone/eq/partialeq from T.
-		impl<$trait_instance: $trait_name> $crate::dispatch::Clone
-			for $call_type<$trait_instance>
-		{
-			fn clone(&self) -> Self {
-				match *self {
-					$(
-						$call_type::$fn_name( $( ref $param_name ),* ) =>
-							$call_type::$fn_name( $( $param_name.clone() ),* )
-					,)*
-					_ => unreachable!(),
-				}
-			}
-		}
-		impl<$trait_instance: $trait_name> $crate::dispatch::PartialEq
-			for $call_type<$trait_instance>
-		{
-			fn eq(&self, _other: &Self) -> bool {
-				match *self {
-					$(
-						$call_type::$fn_name( $( ref $param_name ),* ) => {
-							let self_params = ( $( $param_name, )* );
-							if let $call_type::$fn_name( $( ref $param_name ),* ) = *_other {
-								self_params == ( $( $param_name, )* )
-							} else {
-								match *_other {
-									$call_type::__PhantomItem(_) => unreachable!(),
-									$call_type::__OtherPhantomItem(_) => unreachable!(),
-									_ => false,
-								}
-							}
-						}
-					)*
-					_ => unreachable!(),
-				}
-			}
-		}
-		impl<$trait_instance: $trait_name> $crate::dispatch::Eq
-			for $call_type<$trait_instance>
-		{}
-
-		#[cfg(feature = "std")]
-		impl<$trait_instance: $trait_name> $crate::dispatch::fmt::Debug
-			for $call_type<$trait_instance>
-		{
-			fn fmt(&self, _f: &mut $crate::dispatch::fmt::Formatter) -> $crate::dispatch::result::Result<(), $crate::dispatch::fmt::Error> {
-				match *self {
-					$(
-						$call_type::$fn_name( $( ref $param_name ),* ) =>
-							write!(_f, "{}{:?}",
-								stringify!($fn_name),
-								( $( $param_name.clone(), )* )
-							)
-					,)*
-					_ => unreachable!(),
-				}
-			}
-		}
+/// The error index of failures which the module does not declare with `decl_error!`.
+pub const UNDECLARED_ERROR: u8 = 255;
+
+/// A failed dispatch: the index of the module in the outer `Call` and the index of the error in
+/// the `Error` of the module, declared with `decl_error!`. Native builds also keep the message of
+/// the failure, which is neither encoded nor part of the wasm runtime.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DispatchError {
+	pub module: u8,
+	pub error: u8,
+	#[cfg(feature = "std")]
+	message: Option<&'static str>,
+}
 
-		impl<$trait_instance: $trait_name> $crate::dispatch::Decode for $call_type<$trait_instance> {
-			fn decode<I: $crate::dispatch::Input>(input: &mut I) -> Option<Self> {
-				let _input_id = input.read_byte()?;
-				__impl_decode!(input; _input_id; 0; $call_type; $( fn $fn_name( $( $param_name ),* ); )*)
-			}
+impl DispatchError {
+	/// The failure `error` of the module at index `module`, without a message.
+	pub fn new(module: u8, error: u8) -> Self {
+		DispatchError {
+			module,
+			error,
+			#[cfg(feature = "std")]
+			message: None,
 		}
+	}
 
-		impl<$trait_instance: $trait_name> $crate::dispatch::Encode for $call_type<$trait_instance> {
-			fn encode_to<W: $crate::dispatch::Output>(&self, _dest: &mut W) {
-				__impl_encode!(_dest; *self; 0; $call_type; $( fn $fn_name( $( $param_name ),* ); )*);
-				if let $call_type::__PhantomItem(_) = *self { unreachable!() }
-				if let $call_type::__OtherPhantomItem(_) = *self { unreachable!() }
-			}
-		}
-		impl<$trait_instance: $trait_name> $crate::dispatch::Dispatchable
-			for $call_type<$trait_instance>
-		{
-			type Trait = $trait_instance;
-			type Origin = $origin_type;
-			fn dispatch(self, _origin: Self::Origin) -> $crate::dispatch::Result {
-				match self {
-					$(
-						$call_type::$fn_name( $( $param_name ),* ) => {
-							decl_module!(@call $from $mod_type $trait_instance $fn_name _origin $system [ $( $param_name ),* ])
-						},
-					)*
-					_ => { panic!("__PhantomItem should never be used.") },
-				}
-			}
-		}
-		impl<$trait_instance: $trait_name> $crate::dispatch::Callable
-			for $mod_type<$trait_instance>
-		{
-			type Call = $call_type<$trait_instance>;
+	/// This failure, described by `message` in native builds.
+	#[cfg_attr(not(feature = "std"), allow(unused_variables))]
+	pub fn with_message(self, message: &'static str) -> Self {
+		DispatchError {
+			#[cfg(feature = "std")]
+			message: Some(message),
+			..self
 		}
+	}
 
-		impl<$trait_instance: $trait_name> $mod_type<$trait_instance> {
-			pub fn dispatch<D: $crate::dispatch::Dispatchable<Trait = $trait_instance>>(d: D, origin: D::Origin) -> $crate::dispatch::Result {
-				d.dispatch(origin)
-			}
-		}
-		__dispatch_impl_json_metadata! {
-			$mod_type $trait_instance $trait_name $call_type $origin_type
-			{$( $(#[doc = $doc_attr])* fn $fn_name($from $(, $param_name : $param )*) -> $result; )*}
-		}
+	/// This failure, raised by the module at index `module` of the outer `Call`.
+	pub fn in_module(self, module: u8) -> Self {
+		DispatchError { module, ..self }
+	}
+
+	/// The message of this failure, if known.
+	#[cfg(feature = "std")]
+	pub fn message(&self) -> Option<&'static str> {
+		self.message
 	}
 }
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __impl_decode {
-	(
-		$input:expr;
-		$input_id:expr;
-		$fn_id:expr;
-		$call_type:ident;
-		fn $fn_name:ident(
-			$( $param_name:ident ),*
-		);
-		$($rest:tt)*
-	) => {
-		{
-			if $input_id == ($fn_id) {
-				$(
-					let $param_name = $crate::dispatch::Decode::decode($input)?;
-				)*
-				return Some($call_type:: $fn_name( $( $param_name ),* ));
-			}
+impl From<&'static str> for DispatchError {
+	fn from(message: &'static str) -> Self {
+		DispatchError::new(0, UNDECLARED_ERROR).with_message(message)
+	}
+}
 
-			__impl_decode!($input; $input_id; $fn_id + 1; $call_type; $($rest)*)
-		}
-	};
-	(
-		$input:expr;
-		$input_id:expr;
-		$fn_id:expr;
-		$call_type:ident;
-	) => {
-		None
+impl Encode for DispatchError {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		dest.push_byte(self.module);
+		dest.push_byte(self.error);
 	}
 }
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __impl_encode {
-	(
-		$dest:expr;
-		$self:expr;
-		$fn_id:expr;
-		$call_type:ident;
-		fn $fn_name:ident(
-			$( $param_name:ident ),*
-		);
-		$($rest:tt)*
-	) => {
-		{
-			if let $call_type::$fn_name(
-				$(
-					ref $param_name
-				),*
-			) = $self {
-				$dest.push_byte(($fn_id) as u8);
-				$(
-					$param_name.encode_to($dest);
-				)*
-			}
+impl Decode for DispatchError {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		Some(DispatchError::new(input.read_byte()?, input.read_byte()?))
+	}
+}
 
-			__impl_encode!($dest; $self; $fn_id + 1; $call_type; $($rest)*)
-		}
-	};
-	(
-		$dest:expr;
-		$self:expr;
-		$fn_id:expr;
-		$call_type:ident;
-	) => {{}}
+impl TypeInfo for DispatchError {
+	fn type_name() -> type_info::String {
+		"DispatchError".into()
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		let mut fields = Vec::new();
+		fields.push(type_info::field::<u8>(registry, Some("module")));
+		fields.push(type_info::field::<u8>(registry, Some("error")));
+		TypeDef::Struct(fields)
+	}
 }
 
-pub trait IsSubType<T: Callable> {
-	fn is_aux_sub_type(&self) -> Option<&<T as Callable>::Call>;
+#[cfg(feature = "std")]
+impl serde::Serialize for DispatchError {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> result::Result<S::Ok, S::Error> {
+		serde::Serialize::serialize(&(self.module, self.error), serializer)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de> serde::Deserialize<'de> for DispatchError {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> result::Result<Self, D::Error> {
+		let (module, error) = serde::Deserialize::deserialize(deserializer)?;
+		Ok(DispatchError::new(module, error))
+	}
+}
+
+/// The failure of a dispatchable of a module: either the `&'static str` of a `dispatch::Result`,
+/// numbered with the errors of the module, or a `DispatchError`.
+pub trait IntoDispatchError {
+	/// The failure as a `DispatchError` of the module `M`, whose index in the outer `Call` is set
+	/// by the outer dispatch.
+	fn into_dispatch_error<M: ModuleErrors>(self) -> DispatchError;
+}
+
+impl IntoDispatchError for &'static str {
+	fn into_dispatch_error<M: ModuleErrors>(self) -> DispatchError {
+		DispatchError::new(0, M::error_index(self).unwrap_or(UNDECLARED_ERROR)).with_message(self)
+	}
+}
+
+impl IntoDispatchError for DispatchError {
+	fn into_dispatch_error<M: ModuleErrors>(self) -> DispatchError {
+		self
+	}
+}
+
+/// The `Error` of a module, declared with `decl_error!`.
+pub trait ModuleError: Sized {
+	/// The index of the error in its declaration.
+	fn index(&self) -> u8;
+
+	/// The message of the error, which dispatchables return on failure.
+	fn message(&self) -> &'static str;
+
+	/// The error whose message is `message`, if any.
+	fn from_message(message: &str) -> Option<Self>;
+
+	/// The JSON metadata of all errors, in the order of their indices.
+	fn error_json_metadata() -> &'static str;
+}
+
+/// The errors of a module, implemented by `decl_module!` with the `Error` given with
+/// `type Error = Error;`. Modules declaring no errors know none of their failures.
+pub trait ModuleErrors {
+	/// The index of the error of the module whose message is `message`, if any.
+	fn error_index(message: &str) -> Option<u8>;
+
+	/// The JSON metadata of the errors of the module.
+	fn error_json_metadata() -> &'static str;
 }
 
 /// Implement a meta-dispatch module to dispatch to other dispatchers.
+///
+/// A module is encoded in a call as its index, the one after the index of the module before it,
+/// starting at 0. The index may be given instead, as in `Balances = 5,`, so that the encoding of
+/// the calls of a module doesn't change when modules are added or removed before it.
 #[macro_export]
 macro_rules! impl_outer_dispatch {
 	() => ();
@@ -395,7 +411,7 @@ macro_rules! impl_outer_dispatch {
 		$(#[$attr:meta])*
 		pub enum $call_type:ident where origin: $origin:ty {
 			$(
-				$camelcase:ident,
+				$camelcase:ident $( = $index:tt )*,
 			)*
 		}
 		$( $rest:tt )*
@@ -408,18 +424,68 @@ macro_rules! impl_outer_dispatch {
 				$camelcase ( $crate::dispatch::CallableCallFor<$camelcase> )
 			,)*
 		}
-		__impl_outer_dispatch_common! { $call_type, $($camelcase,)* }
+		__impl_outer_dispatch_common! { $call_type, $( $camelcase $( = $index )*, )* }
+		impl $crate::type_info::TypeInfo for $call_type {
+			fn type_name() -> $crate::type_info::String {
+				stringify!($call_type).into()
+			}
+
+			#[allow(unused_mut, unused_variables)]
+			fn type_def(registry: &mut $crate::type_info::TypeRegistry) -> $crate::type_info::TypeDef {
+				let mut variants = $crate::dispatch::Vec::new();
+				let mut indices = Self::module_indices().into_iter();
+				$(
+					variants.push($crate::type_info::VariantDef {
+						name: stringify!($camelcase).into(),
+						index: indices.next().map_or(0, |(_, index)| index),
+						fields: <[_]>::into_vec($crate::metadata::Box::new([
+							$crate::type_info::field::<$crate::dispatch::CallableCallFor<$camelcase>>(registry, None),
+						])),
+					});
+				)*
+				$crate::type_info::TypeDef::Enum(variants)
+			}
+		}
 		impl $crate::dispatch::Dispatchable for $call_type {
 			type Origin = $origin;
 			type Trait = $call_type;
-			fn dispatch(self, origin: $origin) -> $crate::dispatch::Result {
+			fn dispatch(self, origin: $origin) -> $crate::dispatch::DispatchResult {
+				let module = self.module_index();
 				match self {
 					$(
 						$call_type::$camelcase(call) => call.dispatch(origin),
 					)*
+				}.map_err(|error| error.in_module(module))
+			}
+		}
+		impl $crate::dispatch::GetDispatchInfo for $call_type {
+			fn get_dispatch_info(&self) -> $crate::dispatch::DispatchInfo {
+				match *self {
+					$(
+						$call_type::$camelcase(ref call) => $crate::dispatch::GetDispatchInfo::get_dispatch_info(call),
+					)*
 				}
 			}
 		}
+		impl $call_type {
+			/// The index of the module of this call, the one it is encoded with.
+			pub fn module_index(&self) -> u8 {
+				__impl_module_index!(self; 0; $call_type; $( $camelcase $( = $index )*, )*)
+			}
+
+			/// The names of the modules of the outer `Call`, with their index.
+			#[allow(unused_mut, unused_assignments)]
+			pub fn module_indices() -> $crate::dispatch::Vec<(&'static str, u8)> {
+				let mut indices = $crate::dispatch::Vec::new();
+				let mut next: u8 = 0;
+				$(
+					let index: u8 = __module_index!(next; $( $index )*);
+					indices.push((stringify!($camelcase), index));
+					next = index.wrapping_add(1);
+				)*
+				indices
+			}
+		}
 		$(
 			impl $crate::dispatch::IsSubType<$camelcase> for $call_type {
 				fn is_aux_sub_type(&self) -> Option<&<$camelcase as $crate::dispatch::Callable>::Call> {
@@ -435,167 +501,95 @@ macro_rules! impl_outer_dispatch {
 	}
 }
 
-/// Implement a meta-dispatch module to dispatch to other dispatchers.
+/// The index of a module: the one given, else `$next`, the one after the module before it.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! __impl_outer_dispatch_common {
-	(
-		$call_type:ident, $( $camelcase:ident, )*
-	) => {
-		impl $crate::dispatch::Decode for $call_type {
-			fn decode<I: $crate::dispatch::Input>(input: &mut I) -> Option<Self> {
-				let input_id = input.read_byte()?;
-				__impl_decode!(input; input_id; 0; $call_type; $( fn $camelcase ( outer_dispatch_param ); )*)
-			}
-		}
-
-		impl $crate::dispatch::Encode for $call_type {
-			fn encode_to<W: $crate::dispatch::Output>(&self, dest: &mut W) {
-				__impl_encode!(dest; *self; 0; $call_type; $( fn $camelcase( outer_dispatch_param ); )*)
-			}
-		}
-
-	}
+macro_rules! __module_index {
+	( $next:expr; ) => { $next };
+	( $next:expr; $index:tt ) => { $index };
 }
 
-/// Implement the `json_metadata` function.
+/// The index of the module of a call of the outer dispatch.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! __dispatch_impl_json_metadata {
+macro_rules! __impl_module_index {
 	(
-		$mod_type:ident $trait_instance:ident $trait_name:ident
-		$($rest:tt)*
+		$self:expr;
+		$next:expr;
+		$call_type:ident;
+		$camelcase:ident $( = $index:tt )*,
+		$( $rest:tt )*
 	) => {
-		impl<$trait_instance: $trait_name> $mod_type<$trait_instance> {
-			pub fn json_metadata() -> &'static str {
-				concat!(r#"{ "name": ""#, stringify!($mod_type), r#"", "call": "#,
-					__call_to_json!($($rest)*), " }")
-			}
+		match *$self {
+			$call_type::$camelcase(_) => (__module_index!($next; $( $index )*)) as u8,
+			#[allow(unreachable_patterns)]
+			_ => __impl_module_index!($self; __module_index!($next; $( $index )*) + 1; $call_type; $( $rest )*),
 		}
-	}
-}
-
-/// Convert the list of calls into their JSON representation, joined by ",".
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __call_to_json {
+	};
 	(
-		$call_type:ident $origin_type:ty
-			{$(
-				$(#[doc = $doc_attr:tt])*
-				fn $fn_name:ident($from:ident
-					$(
-						, $param_name:ident : $param:ty
-					)*
-				) -> $result:ty;
-			)*}
+		$self:expr;
+		$next:expr;
+		$call_type:ident;
 	) => {
-		concat!(
-			r#"{ "name": ""#, stringify!($call_type),
-			r#"", "functions": {"#,
-			__functions_to_json!(""; 0; $origin_type; $(
-				fn $fn_name($from $(, $param_name: $param )* ) -> $result;
-				__function_doc_to_json!(""; $($doc_attr)*);
-			)*), " } }"
-		)
-	};
+		unreachable!()
+	}
 }
 
-/// Convert a list of functions into their JSON representation, joined by ",".
+/// The call of the outer dispatch whose module has the index `$input_id`, decoded from `$input`.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! __functions_to_json {
-	// ROOT
+macro_rules! __impl_outer_decode {
 	(
-		$prefix_str:tt;
-		$fn_id:expr;
-		$origin_type:ty;
-		fn $fn_name:ident(root
-			$(
-				, $param_name:ident : $param:ty
-			)*
-		) -> $result:ty;
-		$fn_doc:expr;
-		$($rest:tt)*
-	) => {
-			concat!($prefix_str, " ",
-				__function_to_json!(
-					fn $fn_name(
-						$( $param_name : $param ),*
-					) -> $result;
-					$fn_doc;
-					$fn_id;
-				), __functions_to_json!(","; $fn_id + 1; $origin_type; $($rest)*)
-			)
-	};
-	// NON ROOT
-	(
-		$prefix_str:tt;
-		$fn_id:expr;
-		$origin_type:ty;
-		fn $fn_name:ident(origin
-			$(
-				, $param_name:ident : $param:ty
-			)*
-		) -> $result:ty;
-		$fn_doc:expr;
-		$($rest:tt)*
-	) => {
-			concat!($prefix_str, " ",
-				__function_to_json!(
-					fn $fn_name(
-						origin: $origin_type
-						$(, $param_name : $param)*
-					) -> $result;
-					$fn_doc;
-					$fn_id;
-				), __functions_to_json!(","; $fn_id + 1; $origin_type; $($rest)*)
-			)
-	};
-	// BASE CASE
+		$input:expr;
+		$input_id:expr;
+		$next:expr;
+		$call_type:ident;
+		$camelcase:ident $( = $index:tt )*,
+		$( $rest:tt )*
+	) => {{
+		if $input_id == (__module_index!($next; $( $index )*)) as u8 {
+			return Some($call_type::$camelcase($crate::dispatch::Decode::decode($input)?));
+		}
+		__impl_outer_decode!($input; $input_id; __module_index!($next; $( $index )*) + 1; $call_type; $( $rest )*)
+	}};
 	(
-		$prefix_str:tt;
-		$fn_id:expr;
-		$($origin_type:ty;)*
+		$input:expr;
+		$input_id:expr;
+		$next:expr;
+		$call_type:ident;
 	) => {
-		""
+		None
 	}
 }
 
-/// Convert a function into its JSON representation.
+/// Implement a meta-dispatch module to dispatch to other dispatchers.
 #[macro_export]
 #[doc(hidden)]
-macro_rules! __function_to_json {
-	(
-		fn $fn_name:ident(
-			$first_param_name:ident : $first_param:ty $(, $param_name:ident : $param:ty)*
-		) -> $result:ty;
-		$fn_doc:tt;
-		$fn_id:expr;
-	) => {
-			concat!(
-				r#"""#, stringify!($fn_id), r#"""#,
-				r#": { "name": ""#, stringify!($fn_name),
-				r#"", "params": [ "#,
-				concat!(r#"{ "name": ""#, stringify!($first_param_name), r#"", "type": ""#, stringify!($first_param), r#"" }"# ),
-				$(
-					concat!(r#", { "name": ""#, stringify!($param_name), r#"", "type": ""#, stringify!($param), r#"" }"# ),
-				)*
-				r#" ], "description": ["#, $fn_doc, " ] }"
-			)
-	};
+macro_rules! __impl_outer_dispatch_common {
 	(
-		fn $fn_name:ident() -> $result:ty;
-		$fn_doc:tt;
-		$fn_id:expr;
+		$call_type:ident, $( $camelcase:ident $( = $index:tt )*, )*
 	) => {
-			concat!(
-				r#"""#, stringify!($fn_id), r#"""#,
-				r#": { "name": ""#, stringify!($fn_name),
-				r#"", "params": [ "#,
-				r#" ], "description": ["#, $fn_doc, " ] }"
-			)
-	};
+		// A call may hold others, e.g. a proposal, so its decoding counts against the depth limit.
+		impl $crate::dispatch::Decode for $call_type {
+			fn decode<I: $crate::dispatch::Input>(input: &mut I) -> Option<Self> {
+				let _guard = $crate::dispatch::DepthGuard::enter()?;
+				let input_id = input.read_byte()?;
+				__impl_outer_decode!(input; input_id; 0; $call_type; $( $camelcase $( = $index )*, )*)
+			}
+		}
+
+		impl $crate::dispatch::Encode for $call_type {
+			fn encode_to<W: $crate::dispatch::Output>(&self, dest: &mut W) {
+				dest.push_byte(self.module_index());
+				match *self {
+					$(
+						$call_type::$camelcase(ref call) => $crate::dispatch::Encode::encode_to(call, dest),
+					)*
+				}
+			}
+		}
+
+	}
 }
 
 /// Convert a function documentation attribute into its JSON representation.
@@ -648,33 +642,66 @@ mod tests {
 			fn aux_1(origin, data: i32) -> Result;
 			fn aux_2(origin, data: i32, data2: String) -> Result;
 			fn aux_3() -> Result;
+			#[weight = 5 * 1_000]
+			#[operational]
+			#[pays_fee = no]
 			fn aux_4(data: i32) -> Result;
+			fn aux_5(origin, data: i32, #[codec(compact)] data2: u32) -> Result;
+			fn aux_6(#[compact] data: u64) -> Result;
+			#[codec(index = 10)]
+			fn aux_7(data: u8) -> Result;
+
+			fn on_finalise(n: u64);
+			fn on_runtime_upgrade() -> Weight;
+			fn offchain_worker(n: u64);
+
+			/// Not a call.
+			fn double(data: i32) -> i32 {
+				data * 2
+			}
 		}
 	}
 
+	thread_local!(static FINALISED: ::std::cell::Cell<Option<u64>> = ::std::cell::Cell::new(None));
+	thread_local!(static OFFCHAIN_RUNS: ::std::cell::Cell<Option<u64>> = ::std::cell::Cell::new(None));
+
 	const EXPECTED_METADATA: &str = concat!(
 		r#"{ "name": "Module", "call": "#,
 			r#"{ "name": "Call", "functions": { "#,
 				r#""0": { "name": "aux_0", "params": [ "#,
-					r#"{ "name": "origin", "type": "T::Origin" }"#,
+					r#"{ "name": "origin", "type": "T::Origin", "compact": false }"#,
 				r#" ], "description": [ " Hi, this is a comment." ] }, "#,
 
-				r#""0 + 1": { "name": "aux_1", "params": [ "#,
-					r#"{ "name": "origin", "type": "T::Origin" }, "#,
-					r#"{ "name": "data", "type": "i32" }"#,
+				r#""1": { "name": "aux_1", "params": [ "#,
+					r#"{ "name": "origin", "type": "T::Origin", "compact": false }, "#,
+					r#"{ "name": "data", "type": "i32", "compact": false }"#,
+				r#" ], "description": [ ] }, "#,
+
+				r#""2": { "name": "aux_2", "params": [ "#,
+					r#"{ "name": "origin", "type": "T::Origin", "compact": false }, "#,
+					r#"{ "name": "data", "type": "i32", "compact": false }, "#,
+					r#"{ "name": "data2", "type": "String", "compact": false }"#,
+				r#" ], "description": [ ] }, "#,
+
+				r#""3": { "name": "aux_3", "params": [ "#,
+				r#" ], "description": [ ] }, "#,
+
+				r#""4": { "name": "aux_4", "params": [ "#,
+					r#"{ "name": "data", "type": "i32", "compact": false }"#,
 				r#" ], "description": [ ] }, "#,
 
-				r#""0 + 1 + 1": { "name": "aux_2", "params": [ "#,
-					r#"{ "name": "origin", "type": "T::Origin" }, "#,
-					r#"{ "name": "data", "type": "i32" }, "#,
-					r#"{ "name": "data2", "type": "String" }"#,
+				r#""5": { "name": "aux_5", "params": [ "#,
+					r#"{ "name": "origin", "type": "T::Origin", "compact": false }, "#,
+					r#"{ "name": "data", "type": "i32", "compact": false }, "#,
+					r#"{ "name": "data2", "type": "Compact<u32>", "compact": true }"#,
 				r#" ], "description": [ ] }, "#,
 
-				r#""0 + 1 + 1 + 1": { "name": "aux_3", "params": [ "#,
+				r#""6": { "name": "aux_6", "params": [ "#,
+					r#"{ "name": "data", "type": "Compact<u64>", "compact": true }"#,
 				r#" ], "description": [ ] }, "#,
 
-				r#""0 + 1 + 1 + 1 + 1": { "name": "aux_4", "params": [ "#,
-					r#"{ "name": "data", "type": "i32" }"#,
+				r#""10": { "name": "aux_7", "params": [ "#,
+					r#"{ "name": "data", "type": "u8", "compact": false }"#,
 				r#" ], "description": [ ] }"#,
 			r#" } }"#,
 		r#" }"#,
@@ -700,9 +727,33 @@ mod tests {
 		fn aux_4(_: i32) -> Result {
 			unreachable!()
 		}
+
+		fn aux_5(_: T::Origin, _: i32, _: u32) -> Result {
+			unreachable!()
+		}
+
+		fn aux_6(_: u64) -> Result {
+			unreachable!()
+		}
+
+		fn aux_7(_: u8) -> Result {
+			unreachable!()
+		}
+
+		fn on_finalise(n: u64) {
+			FINALISED.with(|f| f.set(Some(n)));
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			7
+		}
+
+		fn offchain_worker(n: u64) {
+			OFFCHAIN_RUNS.with(|r| r.set(Some(n)));
+		}
 	}
 
-	struct TraitImpl {}
+	pub struct TraitImpl {}
 
 	impl Trait for TraitImpl {
 		type Origin = u32;
@@ -715,4 +766,130 @@ mod tests {
 		let _: serde::de::IgnoredAny =
 			serde_json::from_str(metadata).expect("Is valid json syntax");
 	}
+
+	#[test]
+	fn call_metadata_is_part_of_the_module_metadata() {
+		let calls = Module::<TraitImpl>::call_metadata();
+		assert!(calls.starts_with(r#"{ "name": "Call", "functions": { "0": { "name": "aux_0""#));
+		assert!(EXPECTED_METADATA.contains(calls));
+		let _: serde::de::IgnoredAny = serde_json::from_str(calls).expect("Is valid json syntax");
+	}
+
+	#[test]
+	fn weights_are_given_by_attributes() {
+		assert_eq!(
+			Call::<TraitImpl>::aux_3().get_dispatch_info(),
+			DispatchInfo { weight: DEFAULT_WEIGHT, class: DispatchClass::Normal, pays_fee: true },
+		);
+		assert_eq!(
+			Call::<TraitImpl>::aux_4(1).get_dispatch_info(),
+			DispatchInfo { weight: 5_000, class: DispatchClass::Operational, pays_fee: false },
+		);
+	}
+
+	#[test]
+	fn on_finalise_is_forwarded_to_the_module() {
+		use runtime_primitives::traits::OnFinalise;
+
+		<Module<TraitImpl> as OnFinalise<u64>>::on_finalise(42);
+		assert_eq!(FINALISED.with(|f| f.get()), Some(42));
+	}
+
+	#[test]
+	fn offchain_worker_is_forwarded_to_the_module() {
+		use runtime_primitives::traits::OffchainWorker;
+
+		<Module<TraitImpl> as OffchainWorker<u64>>::offchain_worker(3);
+		assert_eq!(OFFCHAIN_RUNS.with(|r| r.get()), Some(3));
+	}
+
+	#[test]
+	fn runtime_upgrade_weights_are_summed() {
+		use storage::OnRuntimeUpgrade;
+
+		assert_eq!(<Module<TraitImpl> as OnRuntimeUpgrade>::on_runtime_upgrade(), 7);
+		assert_eq!(<(Module<TraitImpl>, (), Module<TraitImpl>) as OnRuntimeUpgrade>::on_runtime_upgrade(), 14);
+	}
+
+	#[test]
+	fn helpers_are_inherent_functions() {
+		assert_eq!(Module::<TraitImpl>::double(21), 42);
+		// The first free index of the `Call` is still the one after `aux_6`.
+		assert_eq!(Call::<TraitImpl>::decode(&mut &[7u8][..]), None);
+	}
+
+	#[test]
+	fn compact_params_are_encoded_compactly() {
+		let call = Call::<TraitImpl>::aux_5(-1, 1);
+		let encoded = call.encode();
+		assert_eq!(encoded, vec![5, 0xff, 0xff, 0xff, 0xff, 0b0000_0100]);
+		assert_eq!(Call::<TraitImpl>::decode(&mut &encoded[..]), Some(call));
+
+		let call = Call::<TraitImpl>::aux_6(1 << 14);
+		let encoded = call.encode();
+		assert_eq!(encoded, vec![6, 0b0000_0010, 0b0000_0000, 0b0000_0001, 0b0000_0000]);
+		assert_eq!(Call::<TraitImpl>::decode(&mut &encoded[..]), Some(call));
+	}
+
+	#[test]
+	fn calls_given_an_index_are_encoded_with_it() {
+		let call = Call::<TraitImpl>::aux_7(3);
+		let encoded = call.encode();
+		assert_eq!(encoded, vec![10, 3]);
+		assert_eq!(Call::<TraitImpl>::decode(&mut &encoded[..]), Some(call));
+		assert_eq!(Call::<TraitImpl>::decode(&mut &[7u8, 3][..]), None);
+
+		let mut registry = ::type_info::TypeRegistry::new();
+		match <Call<TraitImpl> as ::type_info::TypeInfo>::type_def(&mut registry) {
+			::type_info::TypeDef::Enum(variants) => {
+				assert_eq!(variants.last().map(|v| (v.name.as_str(), v.index)), Some(("aux_7", 10)));
+			},
+			_ => panic!("calls are described as an enum"),
+		}
+	}
+
+	pub struct SecondImpl;
+	impl Trait for SecondImpl {
+		type Origin = u32;
+	}
+
+	pub struct ThirdImpl;
+	impl Trait for ThirdImpl {
+		type Origin = u32;
+	}
+
+	type First = Module<TraitImpl>;
+	type Second = Module<SecondImpl>;
+	type Third = Module<ThirdImpl>;
+
+	impl_outer_dispatch! {
+		pub enum OuterCall where origin: u32 {
+			First,
+			Second = 5,
+			Third,
+		}
+	}
+
+	#[test]
+	fn outer_calls_are_encoded_with_the_index_of_their_module() {
+		assert_eq!(OuterCall::module_indices(), vec![("First", 0), ("Second", 5), ("Third", 6)]);
+
+		let call = OuterCall::Second(Call::aux_3());
+		assert_eq!(call.module_index(), 5);
+		let encoded = call.encode();
+		assert_eq!(encoded, vec![5, 3]);
+		assert_eq!(OuterCall::decode(&mut &encoded[..]), Some(call));
+
+		let call = OuterCall::Third(Call::aux_3());
+		assert_eq!(call.encode(), vec![6, 3]);
+		assert_eq!(OuterCall::decode(&mut &[1u8, 3][..]), None);
+	}
+
+	#[test]
+	fn outer_calls_count_against_the_decode_depth_limit() {
+		let encoded = OuterCall::Second(Call::aux_3()).encode();
+		assert_eq!(OuterCall::decode_with_depth_limit(1, &mut &encoded[..]), Some(OuterCall::Second(Call::aux_3())));
+		assert_eq!(OuterCall::decode_with_depth_limit(0, &mut &encoded[..]), None);
+		assert_eq!(OuterCall::decode_all(&[5u8, 3, 0][..]), None);
+	}
 }
@@ -0,0 +1,254 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+/// Declare the `Error` of a module.
+///
+/// ```rust
+/// #[macro_use]
+/// extern crate srml_support;
+///
+/// decl_error! {
+///     pub enum Error {
+///         /// The balance of the sender is too low.
+///         InsufficientBalance,
+///         DestinationUnknown,
+///     }
+/// }
+///# fn main() {}
+/// ```
+///
+/// The errors are numbered in the order of their declaration. Each converts into a
+/// `DispatchError` for dispatchables returning `dispatch::DispatchResult`, e.g. with
+/// `fail!(Error::InsufficientBalance)`, and into its name as a `&'static str` for those returning
+/// `dispatch::Result`. A module naming its errors in `decl_module!` with `type Error = Error;` has
+/// the latter translated back into a `DispatchError` by its dispatch, and lists them with their
+/// docs in `ModuleErrors::error_json_metadata`.
+#[macro_export]
+macro_rules! decl_error {
+	(
+		$(#[$attr:meta])*
+		pub enum Error {
+			$(
+				$( #[doc = $doc_attr:tt] )*
+				$name:ident
+			),+
+			$(,)*
+		}
+	) => {
+		#[derive(Clone, Copy, PartialEq, Eq)]
+		#[cfg_attr(feature = "std", derive(Debug))]
+		$(#[$attr])*
+		pub enum Error {
+			$(
+				$( #[doc = $doc_attr] )*
+				$name,
+			)+
+		}
+
+		impl $crate::dispatch::ModuleError for Error {
+			fn index(&self) -> u8 {
+				*self as u8
+			}
+
+			fn message(&self) -> &'static str {
+				match *self {
+					$( Error::$name => stringify!($name), )+
+				}
+			}
+
+			fn from_message(message: &str) -> Option<Self> {
+				$(
+					if message == stringify!($name) {
+						return Some(Error::$name);
+					}
+				)+
+				None
+			}
+
+			fn error_json_metadata() -> &'static str {
+				concat!("[", __errors_to_json!(""; $( $( #[doc = $doc_attr] )* $name, )+ ), " ]")
+			}
+		}
+
+		impl From<Error> for &'static str {
+			fn from(error: Error) -> &'static str {
+				$crate::dispatch::ModuleError::message(&error)
+			}
+		}
+
+		impl From<Error> for $crate::dispatch::DispatchError {
+			fn from(error: Error) -> $crate::dispatch::DispatchError {
+				$crate::dispatch::DispatchError::new(0, $crate::dispatch::ModuleError::index(&error))
+					.with_message($crate::dispatch::ModuleError::message(&error))
+			}
+		}
+	}
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __errors_to_json {
+	(
+		$prefix_str:expr;
+		$( #[doc = $doc_attr:tt] )*
+		$name:ident,
+		$( $rest:tt )*
+	) => {
+		concat!($prefix_str, r#" { "name": ""#, stringify!($name), r#"", "description": ["#,
+				__function_doc_to_json!(""; $( $doc_attr )*), " ] }",
+				__errors_to_json!(","; $( $rest )*)
+		)
+	};
+	(
+		$prefix_str:expr;
+	) => {
+		""
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde;
+	use serde_json;
+	use dispatch::{DispatchError, DispatchResult, ModuleError, ModuleErrors, Result, UNDECLARED_ERROR};
+
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_error! {
+		pub enum Error {
+			/// The balance of the sender is too low.
+			InsufficientBalance,
+			DestinationUnknown,
+		}
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+			type Error = Error;
+
+			fn transfer(origin, to: u32) -> Result;
+			fn burn(origin, amount: u32) -> DispatchResult;
+		}
+	}
+
+	impl<T: Trait> Module<T> {
+		fn transfer(_: T::Origin, to: u32) -> Result {
+			if to == 0 {
+				return Err(Error::DestinationUnknown.into());
+			}
+			Err(Error::InsufficientBalance.into())
+		}
+
+		fn burn(_: T::Origin, amount: u32) -> DispatchResult {
+			if amount > 0 {
+				return Err(Error::InsufficientBalance.into());
+			}
+			Ok(())
+		}
+	}
+
+	mod other {
+		use dispatch::Result;
+
+		pub trait Trait {
+			type Origin;
+		}
+
+		decl_module! {
+			pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+				fn fail(origin) -> Result;
+			}
+		}
+
+		impl<T: Trait> Module<T> {
+			fn fail(_: T::Origin) -> Result {
+				Err("failed")
+			}
+		}
+	}
+
+	pub struct Runtime;
+
+	impl Trait for Runtime {
+		type Origin = u32;
+	}
+
+	impl other::Trait for Runtime {
+		type Origin = u32;
+	}
+
+	type Other = other::Module<Runtime>;
+	type Errors = Module<Runtime>;
+
+	impl_outer_dispatch! {
+		pub enum OuterCall where origin: u32 {
+			Other,
+			Errors,
+		}
+	}
+
+	#[test]
+	fn errors_are_numbered_in_order() {
+		assert_eq!(Error::InsufficientBalance.index(), 0);
+		assert_eq!(Error::DestinationUnknown.index(), 1);
+		let message: &'static str = Error::DestinationUnknown.into();
+		assert_eq!(Error::from_message(message), Some(Error::DestinationUnknown));
+		assert_eq!(Error::from_message("failed"), None);
+	}
+
+	#[test]
+	fn outer_dispatch_translates_failures() {
+		use dispatch::Dispatchable;
+
+		let error = OuterCall::Errors(Call::transfer(0)).dispatch(1).unwrap_err();
+		assert_eq!(error, DispatchError::new(1, 1).with_message("DestinationUnknown"));
+		assert_eq!(error.message(), Some("DestinationUnknown"));
+
+		let error = OuterCall::Errors(Call::burn(5)).dispatch(1).unwrap_err();
+		assert_eq!(error, DispatchError::new(1, 0).with_message("InsufficientBalance"));
+		assert_eq!(OuterCall::Errors(Call::burn(0)).dispatch(1), Ok(()));
+
+		let error = OuterCall::Other(other::Call::fail()).dispatch(1).unwrap_err();
+		assert_eq!(error, DispatchError::new(0, UNDECLARED_ERROR).with_message("failed"));
+	}
+
+	#[test]
+	fn only_numbers_of_dispatch_errors_are_encoded() {
+		use codec::{Decode, Encode};
+
+		let error = DispatchError::new(1, 0).with_message("InsufficientBalance");
+		let encoded = error.encode();
+		assert_eq!(encoded, vec![1, 0]);
+		let decoded = DispatchError::decode(&mut &encoded[..]).unwrap();
+		assert_eq!(decoded, DispatchError::new(1, 0));
+		assert_eq!(decoded.message(), None);
+	}
+
+	#[test]
+	fn error_json_metadata() {
+		let metadata = <Errors as ModuleErrors>::error_json_metadata();
+		assert_eq!(metadata, concat!(
+			"[",
+			r#" { "name": "InsufficientBalance", "description": [ " The balance of the sender is too low." ] },"#,
+			r#" { "name": "DestinationUnknown", "description": [ ] }"#,
+			" ]"
+		));
+		assert_eq!(<Other as ModuleErrors>::error_json_metadata(), "[ ]");
+		let _: serde::de::IgnoredAny = serde_json::from_str(metadata).expect("Is valid json syntax");
+	}
+}
@@ -84,11 +84,30 @@
 /// ```
 ///
 /// The syntax for generic events requires the `where`.
+///
+/// Events are encoded with their position in the declaration, by an `Encode` and `Decode` the
+/// macro implements rather than derives. An event may be given an index of its own with
+/// `#[codec(index = N)]` after its doc comments, e.g. to remove or reorder events without changing
+/// the encoding of the others:
+///
+/// ```rust,ignore
+/// decl_event!(
+/// 	pub enum Event {
+/// 		/// Kept at the index it had before the event before it was removed.
+/// 		#[codec(index = 2)]
+/// 		Slashed(u64),
+/// 	}
+/// );
+/// ```
+///
+/// The event of a module with an instance parameter also takes the instance, as in
+/// `pub enum Event<T, I> where <T as Trait<I>>::Balance { .. }`, so that each instance of the
+/// module has an event type of its own.
 #[macro_export]
 macro_rules! decl_event {
 	(
 		$(#[$attr:meta])*
-		pub enum Event<$evt_generic_param:ident> where
+		pub enum Event<$evt_generic_param:ident $(, $instance:ident )*> where
 			$( $( $generic_rename:ident = )* <$generic:ident as $trait:path>::$trait_type:ident ),*
 		{
 			$(
@@ -98,7 +117,7 @@ macro_rules! decl_event {
 	) => {
 		__decl_generic_event!(
 			$( #[ $attr ] )*;
-			$evt_generic_param;
+			$evt_generic_param $(, $instance )*;
 			$( $( $generic_rename = )* <$generic as $trait>::$trait_type ),*;
 			Events { $( $events )* };
 		);
@@ -112,13 +131,16 @@ macro_rules! decl_event {
 		}
 	) => {
 		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
-		#[derive(Clone, PartialEq, Eq, Encode, Decode)]
-		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
-		$(#[$attr])*
-		pub enum Event {
-			$(
-				$events
-			)*
+		$crate::__impl_event_codec! {
+			$crate;
+			#[derive(Clone, PartialEq, Eq)]
+			#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+			$(#[$attr])*
+			pub enum Event {
+				$(
+					$events
+				)*
+			}
 		}
 		impl From<Event> for () {
 			fn from(_: Event) -> () { () }
@@ -129,6 +151,18 @@ macro_rules! decl_event {
 				concat!("{", __events_to_json!(""; $( $events )* ), " }")
 			}
 		}
+		impl $crate::type_info::TypeInfo for Event {
+			fn type_name() -> $crate::type_info::String {
+				concat!(module_path!(), "::Event").into()
+			}
+
+			#[allow(unused_mut, unused_variables)]
+			fn type_def(registry: &mut $crate::type_info::TypeRegistry) -> $crate::type_info::TypeDef {
+				let mut variants = $crate::metadata::Vec::new();
+				__events_to_variants!(variants; registry; $( $events )*);
+				$crate::type_info::TypeDef::Enum(variants)
+			}
+		}
 	}
 }
 
@@ -137,14 +171,14 @@ macro_rules! decl_event {
 macro_rules! __decl_generic_event {
 	(
 		$(#[$attr:meta])*;
-		$event_generic_param:ident;
+		$event_generic_param:ident $(, $instance:ident )*;
 		$generic_rename:ident = <$generic:ident as $trait:path>::$trait_type:ident
 			$(, $( $rest_gen_rename:ident = )* <$rest_gen:ident as $rest_trait:path>::$rest_trait_type:ident )*;
 		Events { $( $events:tt )* };
 	) => {
 		__decl_generic_event!(
 			$( #[ $attr ] )*;
-			$event_generic_param;
+			$event_generic_param $(, $instance )*;
 			$( $( $rest_gen_rename = )* <$rest_gen as $rest_trait>::$rest_trait_type ),*;
 			Events { $( $events )* };
 			$generic_rename;
@@ -153,7 +187,7 @@ macro_rules! __decl_generic_event {
 	};
 	(
 		$(#[$attr:meta])*;
-		$event_generic_param:ident;
+		$event_generic_param:ident $(, $instance:ident )*;
 		$generic_rename:ident = <$generic:ident as $trait:path>::$trait_type:ident
 			$(, $( $rest_gen_rename:ident = )* <$rest_gen:ident as $rest_trait:path>::$rest_trait_type:ident )*;
 		Events { $( $events:tt )* };
@@ -162,7 +196,7 @@ macro_rules! __decl_generic_event {
 	) => {
 		__decl_generic_event!(
 			$( #[ $attr ] )*;
-			$event_generic_param;
+			$event_generic_param $(, $instance )*;
 			$( $( $rest_gen_rename = )* <$rest_gen as $rest_trait>::$rest_trait_type ),*;
 			Events { $( $events )* };
 			$( $parsed_generic_params ),*, $generic_rename;
@@ -171,14 +205,14 @@ macro_rules! __decl_generic_event {
 	};
 	(
 		$(#[$attr:meta])*;
-		$event_generic_param:ident;
+		$event_generic_param:ident $(, $instance:ident )*;
 		<$generic:ident as $trait:path>::$trait_type:ident
 			$(, $( $rest_gen_rename:ident = )* <$rest_gen:ident as $rest_trait:path>::$rest_trait_type:ident )*;
 		Events { $( $events:tt )* };
 	) => {
 		__decl_generic_event!(
 			$( #[ $attr ] )*;
-			$event_generic_param;
+			$event_generic_param $(, $instance )*;
 			$( $( $rest_gen_rename = )* <$rest_gen as $rest_trait>::$rest_trait_type ),*;
 			Events { $( $events )* };
 			$trait_type;
@@ -187,7 +221,7 @@ macro_rules! __decl_generic_event {
 	};
 	(
 		$(#[$attr:meta])*;
-		$event_generic_param:ident;
+		$event_generic_param:ident $(, $instance:ident )*;
 		<$generic:ident as $trait:path>::$trait_type:ident
 			$(, $( $rest_gen_rename:ident = )* <$rest_gen:ident as $rest_trait:path>::$rest_trait_type:ident )*;
 		Events { $( $events:tt )* };
@@ -196,7 +230,7 @@ macro_rules! __decl_generic_event {
 	) => {
 		__decl_generic_event!(
 			$( #[ $attr ] )*;
-			$event_generic_param;
+			$event_generic_param $(, $instance )*;
 			$( $( $rest_gen_rename = )* <$rest_gen as $rest_trait>::$rest_trait_type ),*;
 			Events { $( $events )* };
 			$( $parsed_generic_params ),*, $trait_type;
@@ -205,31 +239,56 @@ macro_rules! __decl_generic_event {
 	};
 	(
 		$(#[$attr:meta])*;
-		$event_generic_param:ident;
+		$event_generic_param:ident $(, $instance:ident )*;
 		;
 		Events { $( $events:tt )* };
 		$( $generic_param:ident ),*;
 		$( <$generic:ident as $trait:path>::$trait_type:ident ),*;
 	) => {
-		pub type Event<$event_generic_param> = RawEvent<$( <$generic as $trait>::$trait_type ),*>;
+		pub type Event<$event_generic_param $(, $instance )*> = RawEvent<$( <$generic as $trait>::$trait_type ),* $(, $instance )*>;
 		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
-		#[derive(Clone, PartialEq, Eq, Encode, Decode)]
-		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
-		$(#[$attr])*
-		pub enum RawEvent<$( $generic_param ),*> {
-			$(
-				$events
-			)*
+		$crate::__impl_event_codec! {
+			$crate;
+			#[derive(Clone, PartialEq, Eq)]
+			#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+			$(#[$attr])*
+			pub enum RawEvent<$( $generic_param ),* $(, $instance )*> {
+				$(
+					$events
+				)*
+				$(
+					#[doc(hidden)]
+					__PhantomInstance($crate::storage::generator::PhantomData<$instance>),
+				)*
+			}
 		}
-		impl<$( $generic_param ),*> From<RawEvent<$( $generic_param ),*>> for () {
-			fn from(_: RawEvent<$( $generic_param ),*>) -> () { () }
+		impl<$( $generic_param ),* $(, $instance )*> From<RawEvent<$( $generic_param ),* $(, $instance )*>> for () {
+			fn from(_: RawEvent<$( $generic_param ),* $(, $instance )*>) -> () { () }
 		}
-		impl<$( $generic_param ),*> RawEvent<$( $generic_param ),*> {
+		impl<$( $generic_param ),* $(, $instance )*> RawEvent<$( $generic_param ),* $(, $instance )*> {
 			#[allow(dead_code)]
 			pub fn event_json_metadata() -> &'static str {
 				concat!("{", __events_to_json!(""; $( $events )* ), " }")
 			}
 		}
+		// The hidden variant of an instance is never encoded, and isn't described.
+		impl<$( $generic_param: $crate::type_info::TypeInfo ),* $(, $instance )*> $crate::type_info::TypeInfo
+			for RawEvent<$( $generic_param ),* $(, $instance )*>
+		{
+			fn type_name() -> $crate::type_info::String {
+				$crate::type_info::generic_name(
+					concat!(module_path!(), "::RawEvent"),
+					&[ $( <$generic_param as $crate::type_info::TypeInfo>::type_name() ),* ],
+				)
+			}
+
+			#[allow(unused_mut, unused_variables)]
+			fn type_def(registry: &mut $crate::type_info::TypeRegistry) -> $crate::type_info::TypeDef {
+				let mut variants = $crate::metadata::Vec::new();
+				__events_to_variants!(variants; registry; $( $events )*);
+				$crate::type_info::TypeDef::Enum(variants)
+			}
+		}
 	}
 }
 
@@ -239,6 +298,7 @@ macro_rules! __events_to_json {
 	(
 		$prefix_str:expr;
 		$( #[doc = $doc_attr:tt] )*
+		$( #[codec(index = $index:tt)] )*
 		$event:ident( $first_param:path $(, $param:path )* ),
 		$( $rest:tt )*
 	) => {
@@ -252,6 +312,7 @@ macro_rules! __events_to_json {
 	(
 		$prefix_str:expr;
 		$( #[doc = $doc_attr:tt] )*
+		$( #[codec(index = $index:tt)] )*
 		$event:ident,
 		$( $rest:tt )*
 	) => {
@@ -269,89 +330,85 @@ macro_rules! __events_to_json {
 }
 
 #[macro_export]
-macro_rules! impl_outer_event {
-	(
-		$(#[$attr:meta])*
-		pub enum $name:ident for $runtime:ident {
-			$module:ident<T>,
-			$( $rest:tt $( <$t:ident> )*, )*
-		}
-	) => {
-		impl_outer_event!(
-			$( #[$attr] )*;
-			$name;
-			$runtime;
-			Modules { $( $rest $(<$t>)*, )* };
-			$module::Event<$runtime>,;
-		);
-	};
+#[doc(hidden)]
+macro_rules! __events_to_variants {
 	(
-		$(#[$attr:meta])*
-		pub enum $name:ident for $runtime:ident {
-			$module:ident,
-			$( $rest:tt $( <$t:ident> )*, )*
-		}
+		$variants:ident;
+		$registry:ident;
+		$( #[doc = $doc_attr:tt] )*
+		$( #[codec(index = $index:tt)] )*
+		$event:ident( $( $param:path ),* ),
+		$( $rest:tt )*
 	) => {
-		impl_outer_event!(
-			$( #[$attr] )*;
-			$name;
-			$runtime;
-			Modules { $( $rest $(<$t>)*, )* };
-			$module::Event,;
-		);
+		// The position of the event, unless it is given an index of its own.
+		let index = $variants.len() as u8;
+		$( let index: u8 = $index; )*
+		$variants.push($crate::type_info::VariantDef {
+			name: stringify!($event).into(),
+			index,
+			fields: {
+				let mut fields = $crate::metadata::Vec::new();
+				$( fields.push($crate::type_info::field::<$param>($registry, None)); )*
+				fields
+			},
+		});
+		__events_to_variants!($variants; $registry; $( $rest )*);
 	};
 	(
-		$(#[$attr:meta])*;
-		$name:ident;
-		$runtime:ident;
-		Modules {
-			$module:ident<T>,
-			$( $rest:tt $( <$t:ident> )*, )*
-		};
-		$( $module_name:ident::Event $( <$generic_param:ident> )*, )*;
+		$variants:ident;
+		$registry:ident;
+		$( #[doc = $doc_attr:tt] )*
+		$( #[codec(index = $index:tt)] )*
+		$event:ident,
+		$( $rest:tt )*
 	) => {
-		impl_outer_event!(
-			$( #[$attr] )*;
-			$name;
-			$runtime;
-			Modules { $( $rest $(<$t>)*, )* };
-			$( $module_name::Event $( <$generic_param> )*, )* $module::Event<$runtime>,;
-		);
+		let index = $variants.len() as u8;
+		$( let index: u8 = $index; )*
+		$variants.push($crate::type_info::VariantDef {
+			name: stringify!($event).into(),
+			index,
+			fields: $crate::metadata::Vec::new(),
+		});
+		__events_to_variants!($variants; $registry; $( $rest )*);
 	};
 	(
-		$(#[$attr:meta])*;
-		$name:ident;
-		$runtime:ident;
-		Modules {
-			$module:ident,
-			$( $rest:tt, )*
-		};
-		$( $module_name:ident::Event $( <$generic_param:ident> )*, )*;
-	) => {
-		impl_outer_event!(
-			$( #[$attr] )*;
-			$name;
-			$runtime;
-			Modules { $( $rest, )* };
-			$( $module_name::Event $( <$generic_param> )*, )* $module::Event,;
-		);
-	};
+		$variants:ident;
+		$registry:ident;
+	) => {};
+}
+
+/// Implement the outer event of a runtime, made of the `Event` of `system` and of the listed
+/// modules, `<T>` marking the ones whose event is generic over the runtime.
+///
+/// ```rust,ignore
+/// impl_outer_event! {
+/// 	pub enum Event for Runtime where system = 0 {
+/// 		balances<T> = 5,
+/// 		session<T>,
+/// 	}
+/// }
+/// ```
+///
+/// A module is encoded in an event as its index, the one after the index of the module before
+/// it, `system` being first at 0. The index may be given instead, as for the outer `Call`, so that
+/// the encoding of the events of a module doesn't change when modules are added or removed.
+#[macro_export]
+macro_rules! impl_outer_event {
 	(
-		$(#[$attr:meta])*;
-		$name:ident;
-		$runtime:ident;
-		Modules {};
-		$( $module_name:ident::Event $( <$generic_param:ident> )*, )*;
+		$(#[$attr:meta])*
+		pub enum $name:ident for $runtime:ident $( where system = $system_index:tt )* {
+			$( $module:ident $( <$t:ident> )* $( = $index:tt )*, )*
+		}
 	) => {
 		// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
-		#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+		#[derive(Clone, PartialEq, Eq)]
 		#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 		$(#[$attr])*
 		#[allow(non_camel_case_types)]
 		pub enum $name {
 			system(system::Event),
 			$(
-				$module_name( $module_name::Event $( <$generic_param> )* ),
+				$module( __outer_event_module_type!($module; $runtime; $( $t )*) ),
 			)*
 		}
 		impl From<system::Event> for $name {
@@ -360,27 +417,126 @@ macro_rules! impl_outer_event {
 			}
 		}
 		$(
-			impl From<$module_name::Event $( <$generic_param> )*> for $name {
-				fn from(x: $module_name::Event $( <$generic_param> )*) -> Self {
-					$name::$module_name(x)
+			impl From<__outer_event_module_type!($module; $runtime; $( $t )*)> for $name {
+				fn from(x: __outer_event_module_type!($module; $runtime; $( $t )*)) -> Self {
+					$name::$module(x)
 				}
 			}
 		)*
+		impl $name {
+			/// The names of the modules of the outer event, `system` first, with their index.
+			#[allow(unused_mut, unused_variables, unused_assignments)]
+			pub fn module_indices() -> $crate::dispatch::Vec<(&'static str, u8)> {
+				let mut indices = $crate::dispatch::Vec::new();
+				let system: u8 = __module_index!(0; $( $system_index )*);
+				indices.push(("system", system));
+				let mut next = system.wrapping_add(1);
+				$(
+					let index: u8 = __module_index!(next; $( $index )*);
+					indices.push((stringify!($module), index));
+					next = index.wrapping_add(1);
+				)*
+				indices
+			}
+
+			/// The index of the module of the event.
+			#[allow(unused_mut, unused_variables, unused_assignments)]
+			pub fn module_index(&self) -> u8 {
+				let system: u8 = __module_index!(0; $( $system_index )*);
+				let mut next = system.wrapping_add(1);
+				$(
+					let index: u8 = __module_index!(next; $( $index )*);
+					if let $name::$module(_) = *self {
+						return index;
+					}
+					next = index.wrapping_add(1);
+				)*
+				system
+			}
+		}
+		impl $crate::dispatch::Encode for $name {
+			fn encode_to<W: $crate::dispatch::Output>(&self, dest: &mut W) {
+				dest.push_byte(self.module_index());
+				match *self {
+					$name::system(ref event) => $crate::dispatch::Encode::encode_to(event, dest),
+					$(
+						$name::$module(ref event) => $crate::dispatch::Encode::encode_to(event, dest),
+					)*
+				}
+			}
+		}
+		impl $crate::dispatch::Decode for $name {
+			#[allow(unused_mut, unused_variables, unused_assignments)]
+			fn decode<I: $crate::dispatch::Input>(input: &mut I) -> Option<Self> {
+				let input_id = input.read_byte()?;
+				let system: u8 = __module_index!(0; $( $system_index )*);
+				if input_id == system {
+					return Some($name::system($crate::dispatch::Decode::decode(input)?));
+				}
+				let mut next = system.wrapping_add(1);
+				$(
+					let index: u8 = __module_index!(next; $( $index )*);
+					if input_id == index {
+						return Some($name::$module($crate::dispatch::Decode::decode(input)?));
+					}
+					next = index.wrapping_add(1);
+				)*
+				None
+			}
+		}
+		impl $crate::type_info::TypeInfo for $name {
+			fn type_name() -> $crate::type_info::String {
+				stringify!($name).into()
+			}
+
+			fn type_def(registry: &mut $crate::type_info::TypeRegistry) -> $crate::type_info::TypeDef {
+				let mut indices = Self::module_indices().into_iter().map(|(_, index)| index);
+				let mut variants = $crate::metadata::Vec::new();
+				variants.push($crate::type_info::VariantDef {
+					name: "system".into(),
+					index: indices.next().unwrap_or(0),
+					fields: <[_]>::into_vec($crate::metadata::Box::new([
+						$crate::type_info::field::<system::Event>(registry, None),
+					])),
+				});
+				$(
+					variants.push($crate::type_info::VariantDef {
+						name: stringify!($module).into(),
+						index: indices.next().unwrap_or(0),
+						fields: <[_]>::into_vec($crate::metadata::Box::new([
+							$crate::type_info::field::<__outer_event_module_type!($module; $runtime; $( $t )*)>(
+								registry,
+								None,
+							),
+						])),
+					});
+				)*
+				$crate::type_info::TypeDef::Enum(variants)
+			}
+		}
 		__impl_outer_event_json_metadata!(
 			$runtime;
 			$name;
-			$( $module_name::Event $( <$generic_param> )*, )*;
+			$( $module $( <$t> )*, )*;
 		);
 	}
 }
 
+/// The `Event` of a module in the outer event: `module::Event<Runtime>` if it is generic.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __outer_event_module_type {
+	( $module:ident; $runtime:ident; ) => { $module::Event };
+	( $module:ident; $runtime:ident; $t:ident ) => { $module::Event<$runtime> };
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! __impl_outer_event_json_metadata {
 	(
 		$runtime:ident;
 		$event_name:ident;
-		$( $module_name:ident::Event $( <$generic_param:ident> )*, )*;
+		$( $module:ident $( <$t:ident> )*, )*;
 	) => {
 		impl $runtime {
 			#[allow(dead_code)]
@@ -389,8 +545,8 @@ macro_rules! __impl_outer_event_json_metadata {
 					("system", system::Event::event_json_metadata)
 					$(
 						, (
-							stringify!($module_name),
-							$module_name::Event $( ::<$generic_param> )*::event_json_metadata
+							stringify!($module),
+							<__outer_event_module_type!($module; $runtime; $( $t )*)>::event_json_metadata
 						)
 					)*
 				];
@@ -399,6 +555,12 @@ macro_rules! __impl_outer_event_json_metadata {
 					METADATA
 				)
 			}
+
+			/// Register the outer event, and the events of the modules it is made of.
+			#[allow(dead_code)]
+			pub fn register_outer_event_type(registry: &mut $crate::type_info::TypeRegistry) {
+				registry.register::<$event_name>();
+			}
 		}
 	}
 }
@@ -477,6 +639,94 @@ mod tests {
 		);
 	}
 
+	mod indexed_event_module {
+		pub trait Trait {
+			type Balance;
+		}
+
+		decl_event!(
+			pub enum Event<T> where <T as Trait>::Balance {
+				Deposited(Balance),
+				/// Kept at its index after the event before it was removed.
+				#[codec(index = 2)]
+				Slashed(Balance),
+				#[codec(index = 7)]
+				Reaped,
+			}
+		);
+	}
+
+	mod instance_event_module {
+		pub trait Trait<I: Instance> {
+			type Origin;
+			type Balance;
+		}
+
+		decl_module! {
+			pub struct Module<T: Trait<I>, I: Instance> for enum Call where origin: T::Origin {}
+		}
+
+		decl_storage! {
+			trait Store for Module<T: Trait<I>, I: Instance> as InstanceEventModule {}
+		}
+
+		decl_event!(
+			pub enum Event<T, I> where <T as Trait<I>>::Balance {
+				/// Hi, I am a comment.
+				TestEvent(Balance),
+			}
+		);
+	}
+
+	mod depositing_module {
+		pub mod system {
+			use std::cell::RefCell;
+			use std::marker::PhantomData;
+			use dispatch::Result;
+
+			thread_local!(pub static EVENTS: RefCell<Vec<Vec<u8>>> = RefCell::new(Vec::new()));
+
+			pub trait Trait {
+				type Origin;
+				type Event: ::codec::Encode;
+			}
+
+			pub struct Module<T>(PhantomData<T>);
+
+			impl<T: Trait> Module<T> {
+				pub fn deposit_event(event: T::Event) {
+					EVENTS.with(|events| events.borrow_mut().push(::codec::Encode::encode(&event)));
+				}
+			}
+
+			pub fn ensure_root<R>(_: R) -> Result {
+				Ok(())
+			}
+		}
+
+		pub trait Trait: system::Trait {
+			type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+		}
+
+		decl_module! {
+			pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+				fn deposit_event<T>() = default;
+			}
+		}
+
+		decl_event!(
+			pub enum Event<T> where <T as system::Trait>::Origin {
+				Deposited(Origin),
+			}
+		);
+
+		impl<T: Trait> Module<T> {
+			pub fn deposit(who: T::Origin) {
+				Self::deposit_event(RawEvent::Deposited(who));
+			}
+		}
+	}
+
 	#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Deserialize, Serialize)]
 	pub struct TestRuntime;
 
@@ -502,6 +752,20 @@ mod tests {
 		type Origin = u32;
 	}
 
+	impl depositing_module::system::Trait for TestRuntime {
+		type Origin = u32;
+		type Event = depositing_module::Event<TestRuntime>;
+	}
+
+	impl depositing_module::Trait for TestRuntime {
+		type Event = depositing_module::Event<TestRuntime>;
+	}
+
+	impl instance_event_module::Trait<instance_event_module::Instance1> for TestRuntime {
+		type Origin = u32;
+		type Balance = u32;
+	}
+
 	const EXPECTED_METADATA: (&str, &[(&str, &str)]) = (
 		"TestEvent", &[
 			("system", r#"{ "SystemEvent": { "params": null, "description": [ ] } }"#),
@@ -544,4 +808,66 @@ mod tests {
 				serde_json::from_str(got.1()).expect(&format!("Is valid json syntax: {}", got.1()));
 		}
 	}
+
+	#[test]
+	fn events_given_an_index_are_encoded_with_it() {
+		use self::indexed_event_module::RawEvent;
+
+		assert_eq!(codec::Encode::encode(&RawEvent::Deposited(1u8)), vec![0, 1]);
+		assert_eq!(codec::Encode::encode(&RawEvent::Slashed(1u8)), vec![2, 1]);
+		assert_eq!(codec::Encode::encode(&RawEvent::<u8>::Reaped), vec![7]);
+		assert_eq!(codec::Decode::decode(&mut &[2u8, 1][..]), Some(RawEvent::Slashed(1u8)));
+		assert_eq!(<RawEvent<u8> as codec::Decode>::decode(&mut &[1u8, 1][..]), None);
+
+		let mut registry = ::type_info::TypeRegistry::new();
+		match <RawEvent<u8> as ::type_info::TypeInfo>::type_def(&mut registry) {
+			::type_info::TypeDef::Enum(variants) => assert_eq!(
+				variants.iter().map(|v| (v.name.as_str(), v.index)).collect::<Vec<_>>(),
+				vec![("Deposited", 0), ("Slashed", 2), ("Reaped", 7)],
+			),
+			_ => panic!("events are described as an enum"),
+		}
+	}
+
+	#[test]
+	fn instance_event_takes_the_instance() {
+		use self::instance_event_module::{Event, Instance1, RawEvent};
+
+		let event: Event<TestRuntime, Instance1> = RawEvent::TestEvent(5);
+		assert_eq!(event.clone(), RawEvent::TestEvent(5));
+		assert_eq!(codec::Encode::encode(&event), vec![0, 5, 0, 0, 0]);
+		assert_eq!(
+			RawEvent::<u32, Instance1>::event_json_metadata(),
+			r#"{ "TestEvent": { "params": [ "Balance" ], "description": [ " Hi, I am a comment." ] } }"#
+		);
+	}
+	pub struct IndexedRuntime;
+
+	impl_outer_event! {
+		pub enum IndexedEvent for IndexedRuntime where system = 2 {
+			event_module3 = 7,
+		}
+	}
+
+	#[test]
+	fn outer_events_are_encoded_with_the_index_of_their_module() {
+		use codec::{Decode, Encode};
+
+		assert_eq!(IndexedEvent::module_indices(), vec![("system", 2), ("event_module3", 7)]);
+		assert_eq!(TestEvent::module_indices()[3], ("event_module3", 3));
+
+		let event = IndexedEvent::from(event_module3::Event::HiEvent);
+		assert_eq!(event.encode(), vec![7, 0]);
+		assert_eq!(IndexedEvent::decode(&mut &[7u8, 0][..]), Some(event));
+		assert_eq!(IndexedEvent::from(system::Event::SystemEvent).encode(), vec![2, 0]);
+		assert_eq!(IndexedEvent::decode(&mut &[0u8, 0][..]), None);
+	}
+
+	#[test]
+	fn deposit_event_is_generated() {
+		depositing_module::Module::<TestRuntime>::deposit(7);
+		depositing_module::system::EVENTS.with(|events| {
+			assert_eq!(*events.borrow(), vec![codec::Encode::encode(&depositing_module::RawEvent::Deposited(7u32))]);
+		});
+	}
 }
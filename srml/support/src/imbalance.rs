@@ -0,0 +1,122 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Imbalances: funds created or destroyed without the total issuance being updated yet.
+//!
+//! A function minting funds into an account gives a `PositiveImbalance`, one burning funds of an
+//! account a `NegativeImbalance`. The caller must hand it to an `OnUnbalanced` handler, which
+//! decides where the funds go, e.g. the balances module updating the total issuance, or the
+//! treasury taking slashed funds into its pot. An imbalance can't be cloned, and opposite ones
+//! can offset each other first.
+
+use rstd::ops::{Add, Sub};
+
+/// Funds created, which the total issuance doesn't account for yet.
+#[must_use = "the imbalance must be handed to an `OnUnbalanced` handler"]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct PositiveImbalance<Balance>(Balance);
+
+/// Funds destroyed, which the total issuance doesn't account for yet.
+#[must_use = "the imbalance must be handed to an `OnUnbalanced` handler"]
+#[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct NegativeImbalance<Balance>(Balance);
+
+macro_rules! impl_imbalance {
+	($name:ident, $opposite:ident) => {
+		impl<Balance> $name<Balance> where
+			Balance: Copy + Ord + Default + Add<Output=Balance> + Sub<Output=Balance>
+		{
+			/// An imbalance of `amount`.
+			pub fn new(amount: Balance) -> Self {
+				$name(amount)
+			}
+
+			/// An imbalance of nothing.
+			pub fn zero() -> Self {
+				$name(Balance::default())
+			}
+
+			/// The amount of the imbalance.
+			pub fn peek(&self) -> Balance {
+				self.0
+			}
+
+			/// Drop the imbalance if it is of nothing, else give it back.
+			pub fn drop_zero(self) -> Result<(), Self> {
+				if self.0 == Balance::default() { Ok(()) } else { Err(self) }
+			}
+
+			/// Split the imbalance in one of up to `amount` and one of the rest.
+			pub fn split(self, amount: Balance) -> (Self, Self) {
+				let first = self.0.min(amount);
+				($name(first), $name(self.0 - first))
+			}
+
+			/// The imbalance of both amounts.
+			pub fn merge(self, other: Self) -> Self {
+				$name(self.0 + other.0)
+			}
+
+			/// Add the amount of `other` to this imbalance.
+			pub fn subsume(&mut self, other: Self) {
+				self.0 = self.0 + other.0;
+			}
+
+			/// Offset the imbalance by an opposite one, giving what is left of either.
+			pub fn offset(self, other: $opposite<Balance>) -> Result<Self, $opposite<Balance>> {
+				if self.0 >= other.0 {
+					Ok($name(self.0 - other.0))
+				} else {
+					Err($opposite(other.0 - self.0))
+				}
+			}
+		}
+	}
+}
+
+impl_imbalance!(PositiveImbalance, NegativeImbalance);
+impl_imbalance!(NegativeImbalance, PositiveImbalance);
+
+/// A handler of the imbalances of some operation, e.g. the slashes of the staking module.
+pub trait OnUnbalanced<Imbalance> {
+	/// Take the imbalance, putting the funds somewhere and the total issuance in line with them.
+	fn on_unbalanced(amount: Imbalance);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn imbalances_split_merge_and_offset() {
+		let (first, rest) = PositiveImbalance::new(10u64).split(4);
+		assert_eq!((first.peek(), rest.peek()), (4, 6));
+		let (all, none) = NegativeImbalance::new(3u64).split(5);
+		assert_eq!((all.peek(), none.peek()), (3, 0));
+		assert_eq!(none.drop_zero(), Ok(()));
+
+		let mut merged = first.merge(PositiveImbalance::zero());
+		merged.subsume(rest);
+		assert_eq!(merged.peek(), 10);
+		assert_eq!(merged.drop_zero(), Err(PositiveImbalance::new(10)));
+
+		assert_eq!(PositiveImbalance::new(10u64).offset(all), Ok(PositiveImbalance::new(7)));
+		assert_eq!(PositiveImbalance::new(2u64).offset(NegativeImbalance::new(3)), Err(NegativeImbalance::new(1)));
+		assert_eq!(NegativeImbalance::new(3u64).offset(PositiveImbalance::new(3)), Ok(NegativeImbalance::zero()));
+	}
+}
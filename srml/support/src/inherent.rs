@@ -0,0 +1,223 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Inherent extrinsics: the unsigned extrinsics the author of a block puts in it, e.g. the
+//! timestamp.
+//!
+//! The client gathers the `InherentData` of a block, each piece under the identifier of the
+//! module it is for. A module implementing `ProvideInherent` creates its inherent call from its
+//! piece when the block is built, and checks the call of an imported block against the local
+//! data. The runtime lists these modules with `impl_outer_inherent!`.
+
+use rstd::prelude::*;
+use rstd::collections::btree_map::BTreeMap;
+use codec::{Decode, Encode, Input, Output};
+
+/// The identifier of a piece of inherent data, e.g. `*b"timstap0"`.
+pub type InherentIdentifier = [u8; 8];
+
+/// The data the inherent extrinsics of a block are created from, and checked against.
+#[derive(Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct InherentData {
+	data: BTreeMap<InherentIdentifier, Vec<u8>>,
+}
+
+impl InherentData {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Put the piece of data of `identifier`; fails if there is one already.
+	pub fn put_data<I: Encode>(&mut self, identifier: InherentIdentifier, data: &I) -> Result<(), &'static str> {
+		if self.data.contains_key(&identifier) {
+			return Err("inherent data of this identifier is already put");
+		}
+		self.data.insert(identifier, data.encode());
+		Ok(())
+	}
+
+	/// The piece of data of `identifier`, `None` if there is none; fails if it doesn't decode.
+	pub fn get_data<I: Decode>(&self, identifier: &InherentIdentifier) -> Result<Option<I>, &'static str> {
+		match self.data.get(identifier) {
+			Some(data) => I::decode(&mut &data[..]).map(Some).ok_or("inherent data does not decode"),
+			None => Ok(None),
+		}
+	}
+}
+
+// Encoded as the list of pieces of data with their identifiers.
+impl Encode for InherentData {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		(self.data.len() as u32).encode_to(dest);
+		for (identifier, data) in &self.data {
+			identifier.encode_to(dest);
+			data.encode_to(dest);
+		}
+	}
+}
+
+impl Decode for InherentData {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		let len = u32::decode(input)?;
+		let mut data = BTreeMap::new();
+		for _ in 0..len {
+			let identifier = InherentIdentifier::decode(input)?;
+			data.insert(identifier, Vec::<u8>::decode(input)?);
+		}
+		Some(InherentData { data })
+	}
+}
+
+/// A module with an inherent extrinsic.
+pub trait ProvideInherent {
+	/// The `Call` of the module.
+	type Call;
+
+	/// The identifier of the piece of inherent data of the module.
+	const INHERENT_IDENTIFIER: InherentIdentifier;
+
+	/// The inherent call to put in the block being built, `None` if there is none.
+	fn create_inherent(data: &InherentData) -> Option<Self::Call>;
+
+	/// Check a call of the module among the inherent extrinsics of an imported block against the
+	/// local data.
+	fn check_inherent(call: &Self::Call, data: &InherentData) -> Result<(), &'static str>;
+}
+
+/// Implement the creation and the checks of the inherent extrinsics of a runtime, whose outer
+/// `Call` is given, for the listed modules.
+///
+/// ```rust,ignore
+/// impl_outer_inherent! {
+/// 	for Runtime where Call = Call {
+/// 		Timestamp,
+/// 		Consensus,
+/// 	}
+/// }
+/// ```
+///
+/// The runtime gets `create_inherents`, giving the inherent calls of a block in the order the
+/// modules are listed in, and `check_inherents`, which checks the calls of the unsigned extrinsics
+/// of an imported block.
+#[macro_export]
+macro_rules! impl_outer_inherent {
+	(
+		for $runtime:ident where Call = $call:ident {
+			$( $module:ident, )*
+		}
+	) => {
+		impl $runtime {
+			/// The inherent calls of a block, created from `data`.
+			pub fn create_inherents(
+				data: &$crate::inherent::InherentData,
+			) -> $crate::dispatch::Vec<$call> {
+				let mut calls = $crate::dispatch::Vec::new();
+				$(
+					if let Some(call) = <$module as $crate::inherent::ProvideInherent>::create_inherent(data) {
+						calls.push($call::$module(call));
+					}
+				)*
+				calls
+			}
+
+			/// Check the inherent calls of an imported block against `data`.
+			pub fn check_inherents<'a, I: IntoIterator<Item=&'a $call>>(
+				calls: I,
+				data: &$crate::inherent::InherentData,
+			) -> $crate::dispatch::Result {
+				for call in calls {
+					#[allow(unreachable_patterns)]
+					match *call {
+						$(
+							$call::$module(ref call) =>
+								<$module as $crate::inherent::ProvideInherent>::check_inherent(call, data)?,
+						)*
+						_ => {},
+					}
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Clone, PartialEq, Eq, Debug)]
+	pub enum TimestampCall {
+		Set(u64),
+	}
+
+	pub struct Timestamp;
+	impl ProvideInherent for Timestamp {
+		type Call = TimestampCall;
+		const INHERENT_IDENTIFIER: InherentIdentifier = *b"timstap0";
+
+		fn create_inherent(data: &InherentData) -> Option<TimestampCall> {
+			data.get_data(&Self::INHERENT_IDENTIFIER).unwrap().map(TimestampCall::Set)
+		}
+
+		fn check_inherent(call: &TimestampCall, data: &InherentData) -> Result<(), &'static str> {
+			let now: u64 = data.get_data(&Self::INHERENT_IDENTIFIER)?.ok_or("no timestamp")?;
+			match *call {
+				TimestampCall::Set(t) if t > now + 60 => Err("timestamp too far in the future"),
+				_ => Ok(()),
+			}
+		}
+	}
+
+	#[derive(Clone, PartialEq, Eq, Debug)]
+	pub enum Call {
+		Timestamp(TimestampCall),
+		Balances(u32),
+	}
+
+	pub struct Runtime;
+	impl_outer_inherent! {
+		for Runtime where Call = Call {
+			Timestamp,
+		}
+	}
+
+	#[test]
+	fn inherent_data_is_encoded() {
+		let mut data = InherentData::new();
+		data.put_data(*b"timstap0", &42u64).unwrap();
+		assert!(data.put_data(*b"timstap0", &43u64).is_err());
+		assert_eq!(data.get_data::<u64>(b"timstap0"), Ok(Some(42)));
+		assert_eq!(data.get_data::<u64>(b"offline0"), Ok(None));
+		assert!(data.get_data::<[u8; 16]>(b"timstap0").is_err());
+
+		let decoded = InherentData::decode(&mut &data.encode()[..]).unwrap();
+		assert_eq!(decoded, data);
+	}
+
+	#[test]
+	fn inherents_are_created_and_checked() {
+		let mut data = InherentData::new();
+		data.put_data(Timestamp::INHERENT_IDENTIFIER, &1_000u64).unwrap();
+		assert_eq!(Runtime::create_inherents(&data), vec![Call::Timestamp(TimestampCall::Set(1_000))]);
+		assert_eq!(Runtime::create_inherents(&InherentData::new()), vec![]);
+
+		let block = vec![Call::Timestamp(TimestampCall::Set(1_050)), Call::Balances(1)];
+		assert_eq!(Runtime::check_inherents(&block, &data), Ok(()));
+		let block = vec![Call::Timestamp(TimestampCall::Set(1_100))];
+		assert_eq!(Runtime::check_inherents(&block, &data), Err("timestamp too far in the future"));
+	}
+}
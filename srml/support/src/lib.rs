@@ -28,9 +28,9 @@ extern crate alloc;
 extern crate serde;
 
 extern crate sr_std as rstd;
-extern crate sr_io as runtime_io;
 extern crate substrate_primitives as primitives;
 extern crate substrate_metadata;
+extern crate srml_support_procedural;
 
 #[cfg(test)]
 #[macro_use]
@@ -46,12 +46,18 @@ extern crate parity_codec_derive;
 
 #[doc(hidden)]
 pub extern crate parity_codec as codec;
+#[doc(hidden)]
+pub extern crate sr_primitives as runtime_primitives;
+#[doc(hidden)]
+pub extern crate sr_io as runtime_io;
 pub use self::storage::generator::Storage as GenericStorage;
+pub use self::storage::generator::StorageBatch;
 
 #[cfg(feature = "std")]
 pub mod alloc {
 	pub use std::boxed;
 	pub use std::vec;
+	pub use std::string;
 }
 
 #[macro_use]
@@ -59,26 +65,53 @@ pub mod dispatch;
 #[macro_use]
 pub mod storage;
 mod hashable;
+pub mod bounded;
 #[macro_use]
 mod event;
 #[macro_use]
+mod error;
+#[macro_use]
 pub mod metadata;
 #[macro_use]
-mod origin;
+pub mod origin;
+#[macro_use]
+pub mod inherent;
+pub mod imbalance;
+pub mod type_info;
+#[macro_use]
+mod runtime;
 
-pub use self::storage::{StorageVec, StorageList, StorageValue, StorageMap};
+pub use self::storage::{StorageVec, StorageDeque, StorageOrderedSet, StorageValue, StorageMap, EnumerableStorageMap, CountedStorageMap,
+	StorageChildMap, StoragePrefixedMap};
+pub use self::storage::unhashed::{StorageDoubleMap, StorageNMap};
 pub use self::hashable::Hashable;
-pub use self::dispatch::{Parameter, Dispatchable, Callable, IsSubType};
+pub use self::bounded::{BoundedVec, BoundedBTreeMap, Get, MaxEncodedLen};
+pub use self::imbalance::{PositiveImbalance, NegativeImbalance, OnUnbalanced};
+pub use self::type_info::{TypeInfo, TypeRegistry};
+pub use self::dispatch::{Parameter, Dispatchable, Callable, IsSubType, DispatchError, DispatchResult};
 pub use runtime_io::print;
+#[doc(hidden)]
+pub use srml_support_procedural::decl_storage as __decl_storage_impl;
+#[doc(hidden)]
+pub use srml_support_procedural::decl_module as __decl_module_impl;
+#[doc(hidden)]
+pub use srml_support_procedural::impl_event_codec as __impl_event_codec;
+#[doc(hidden)]
+pub use srml_support_procedural::construct_runtime as __construct_runtime_impl;
+pub use srml_support_procedural::TypeInfo;
+pub use srml_support_procedural::MaxEncodedLen;
 
 
+/// Return early with the error `$y`, converted into the error of the function, e.g. an `Error`
+/// declared with `decl_error!` into a `DispatchError`.
 #[macro_export]
 macro_rules! fail {
 	( $y:expr ) => {{
-		return Err($y);
+		return Err($y.into());
 	}}
 }
 
+/// Return early with the error `$y` if `$x` doesn't hold.
 #[macro_export]
 macro_rules! ensure {
 	( $x:expr, $y:expr ) => {{
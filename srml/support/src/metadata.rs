@@ -15,12 +15,14 @@
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
 use alloc;
-pub use substrate_metadata::JsonMetadata;
+pub use substrate_metadata::{JsonMetadata, ModuleConstantMetadata, RuntimeMetadata};
 
 /// Make Box available on `std` and `no_std`.
 pub type Box<T> = alloc::boxed::Box<T>;
 /// Make Vec available on `std` and `no_std`.
 pub type Vec<T> = alloc::vec::Vec<T>;
+/// Make String available on `std` and `no_std`.
+pub type String = alloc::string::String;
 
 /// Implements the json metadata support for the given runtime and all its modules.
 ///
@@ -30,11 +32,66 @@ pub type Vec<T> = alloc::vec::Vec<T>;
 /// ```
 ///
 /// In this example, just `MODULE3` implements the `Storage` trait.
+///
+/// The order in which the hooks of the modules are called may be given before the modules, as
+/// the names of the modules in the runtime:
+/// ```compile_fail
+/// impl_json_metadata!(for RUNTIME_NAME with hooks (Module3, Module0) and modules MODULE0, MODULE3);
+/// ```
+///
+/// The indices the modules are encoded with in the outer `Call` and `Event` may be given first,
+/// as `with indices (System = 0, Balances = 5) and ..`; they are part of the metadata as
+/// `ModuleIndices`.
+///
+/// Besides `json_metadata`, the runtime gets a `metadata` function returning all of its metadata
+/// tagged with the current version of the metadata format: the events, the order of the hooks,
+/// for each module a `FullModule` with its calls, storage, constants and errors, and last the
+/// `Types` these are made of, as registered with `TypeInfo`. This is what the runtime serves
+/// through its `Metadata_metadata` API.
 #[macro_export]
 macro_rules! impl_json_metadata {
 	(
 		for $runtime:ident with modules
 		$( $rest:tt )*
+	) => {
+		impl_json_metadata!(@impl $runtime; ; $( $rest )*);
+	};
+	(
+		for $runtime:ident with hooks ( $( $hook:ident ),* ) and modules
+		$( $rest:tt )*
+	) => {
+		impl_json_metadata!(
+			@impl $runtime;
+			$crate::metadata::JsonMetadata::HooksOrder { modules: &[ $( stringify!($hook) ),* ] };
+			$( $rest )*
+		);
+	};
+	(
+		for $runtime:ident with indices ( $( $name:ident = $index:expr ),* ) and modules
+		$( $rest:tt )*
+	) => {
+		impl_json_metadata!(
+			@impl $runtime;
+			$crate::metadata::JsonMetadata::ModuleIndices { modules: &[ $( (stringify!($name), $index) ),* ] };
+			$( $rest )*
+		);
+	};
+	(
+		for $runtime:ident with indices ( $( $name:ident = $index:expr ),* )
+		and hooks ( $( $hook:ident ),* ) and modules
+		$( $rest:tt )*
+	) => {
+		impl_json_metadata!(
+			@impl $runtime;
+			$crate::metadata::JsonMetadata::ModuleIndices { modules: &[ $( (stringify!($name), $index) ),* ] },
+			$crate::metadata::JsonMetadata::HooksOrder { modules: &[ $( stringify!($hook) ),* ] };
+			$( $rest )*
+		);
+	};
+	(
+		@impl $runtime:ident;
+		$( $hooks:expr ),*;
+		$( $rest:tt )*
 	) => {
 		impl $runtime {
 			pub fn json_metadata() -> $crate::metadata::Vec<$crate::metadata::JsonMetadata> {
@@ -43,10 +100,27 @@ macro_rules! impl_json_metadata {
 					$crate::metadata::JsonMetadata::Events {
 						name: events.0,
 						events: events.1,
-					};
+					}
+					$( , $hooks )*;
 					$( $rest )*
 				)
 			}
+
+			pub fn metadata() -> $crate::metadata::RuntimeMetadata {
+				let events = Self::outer_event_json_metadata();
+				let mut registry = $crate::type_info::TypeRegistry::new();
+				Self::register_outer_event_type(&mut registry);
+				let mut metadata: $crate::metadata::Vec<_> = __impl_runtime_metadata!($runtime; registry;
+					$crate::metadata::JsonMetadata::Events {
+						name: events.0,
+						events: events.1,
+					}
+					$( , $hooks )*;
+					$( $rest )*
+				);
+				metadata.push($crate::metadata::JsonMetadata::Types { types: registry.into_types() });
+				$crate::metadata::RuntimeMetadata::V2(metadata)
+			}
 		}
 	}
 }
@@ -116,6 +190,98 @@ macro_rules! __impl_json_metadata {
 	};
 }
 
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __impl_runtime_metadata {
+	(
+		$runtime: ident;
+		$registry: ident;
+		$( $metadata:expr ),*;
+		$mod:ident::$module:ident,
+		$( $rest:tt )*
+	) => {
+		__impl_runtime_metadata!(
+			$runtime;
+			$registry;
+			$( $metadata, )* __runtime_module_metadata!($runtime; $registry; $mod::$module;);
+			$( $rest )*
+		)
+	};
+	(
+		$runtime: ident;
+		$registry: ident;
+		$( $metadata:expr ),*;
+		$mod:ident::$module:ident
+	) => {
+		__impl_runtime_metadata!(
+			$runtime;
+			$registry;
+			$( $metadata, )* __runtime_module_metadata!($runtime; $registry; $mod::$module;);
+		)
+	};
+	(
+		$runtime: ident;
+		$registry: ident;
+		$( $metadata:expr ),*;
+		$mod:ident::$module:ident with Storage,
+		$( $rest:tt )*
+	) => {
+		__impl_runtime_metadata!(
+			$runtime;
+			$registry;
+			$( $metadata, )* __runtime_module_metadata!($runtime; $registry; $mod::$module; Storage);
+			$( $rest )*
+		)
+	};
+	(
+		$runtime: ident;
+		$registry: ident;
+		$( $metadata:expr ),*;
+		$mod:ident::$module:ident with Storage
+	) => {
+		__impl_runtime_metadata!(
+			$runtime;
+			$registry;
+			$( $metadata, )* __runtime_module_metadata!($runtime; $registry; $mod::$module; Storage);
+		)
+	};
+	(
+		$runtime:ident;
+		$registry: ident;
+		$( $metadata:expr ),*;
+	) => {
+		<[_]>::into_vec($crate::metadata::Box::new([ $( $metadata ),* ]))
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_module_metadata {
+	( $runtime:ident; $registry:ident; $mod:ident::$module:ident; ) => {{
+		let mut types = $crate::metadata::Vec::new();
+		$mod::$module::<$runtime>::register_types(&mut $registry, &mut types);
+		__runtime_module_metadata!(@module $runtime; $mod::$module; None; types)
+	}};
+	( $runtime:ident; $registry:ident; $mod:ident::$module:ident; Storage ) => {{
+		let mut types = $crate::metadata::Vec::new();
+		$mod::$module::<$runtime>::register_types(&mut $registry, &mut types);
+		$mod::$module::<$runtime>::register_storage_types(&mut $registry, &mut types);
+		__runtime_module_metadata!(
+			@module $runtime; $mod::$module; Some($mod::$module::<$runtime>::store_json_metadata()); types
+		)
+	}};
+	( @module $runtime:ident; $mod:ident::$module:ident; $storage:expr; $types:ident ) => {
+		$crate::metadata::JsonMetadata::FullModule {
+			module: $mod::$module::<$runtime>::json_metadata(),
+			prefix: stringify!($mod),
+			storage: $storage,
+			constants: $mod::$module::<$runtime>::module_constants_metadata(),
+			errors: <$mod::$module<$runtime> as $crate::dispatch::ModuleErrors>::error_json_metadata(),
+			types: $types,
+		}
+	};
+}
+
 #[cfg(test)]
 // Do not complain about unused `dispatch` and `dispatch_aux`.
 #[allow(dead_code)]
@@ -123,7 +289,8 @@ mod tests {
 	use super::*;
 	use serde;
 	use serde_json;
-	use substrate_metadata::JsonMetadataDecodable;
+	use substrate_metadata::{JsonMetadataDecodable, RuntimeMetadataDecodable};
+	use type_info::{FieldDef, TypeDef, VariantDef};
 	use codec::{Decode, Encode};
 
 	mod system {
@@ -160,6 +327,9 @@ mod tests {
 
 		decl_module! {
 			pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+				/// The answer.
+				const Answer: u32 = 42;
+
 				fn aux_0(origin) -> Result;
 			}
 		}
@@ -232,11 +402,19 @@ mod tests {
 	}
 
 	impl_json_metadata!(
-		for TestRuntime with modules
+		for TestRuntime with hooks (EventModule2, EventModule) and modules
 			event_module::Module,
 			event_module2::ModuleWithStorage with Storage
 	);
 
+	const EXPECTED_METADATA_MODULE: &str = concat!(
+		r#"{ "name": "Module", "call": "#,
+			r#"{ "name": "Call", "functions": "#,
+				r#"{ "0": { "name": "aux_0", "params": [ "#,
+					r#"{ "name": "origin", "type": "T::Origin", "compact": false } ], "#,
+					r#""description": [ ] } } } }"#
+	);
+
 	const EXPECTED_METADATA: &[JsonMetadata] = &[
 		JsonMetadata::Events {
 			name: "TestEvent",
@@ -246,21 +424,16 @@ mod tests {
 				("event_module2", event_module2_event_json),
 			]
 		},
+		JsonMetadata::HooksOrder { modules: &["EventModule2", "EventModule"] },
 		JsonMetadata::Module {
-			module: concat!(
-				r#"{ "name": "Module", "call": "#,
-					r#"{ "name": "Call", "functions": "#,
-						r#"{ "0": { "name": "aux_0", "params": [ "#,
-							r#"{ "name": "origin", "type": "T::Origin" } ], "#,
-							r#""description": [ ] } } } }"#
-			),
+			module: EXPECTED_METADATA_MODULE,
 			prefix: "event_module"
 		},
 		JsonMetadata::ModuleWithStorage {
 			module: r#"{ "name": "ModuleWithStorage", "call": { "name": "Call", "functions": { } } }"#,
 			prefix: "event_module2",
 			storage: concat!(
-				r#"{ "prefix": "TestStorage", "items": { "#,
+				r#"{ "prefix": "TestStorage", "prefixHasher": "twox_128", "items": { "#,
 					r#""StorageMethod": { "description": [ ], "modifier": null, "type": "u32" }"#,
 				r#" } }"#
 			)
@@ -282,6 +455,84 @@ mod tests {
 		assert_eq!(&metadata_decoded.unwrap()[..], &metadata[..]);
 	}
 
+	#[test]
+	fn versioned_metadata_encode_and_decode() {
+		let metadata = TestRuntime::metadata();
+		let metadata_decoded = RuntimeMetadataDecodable::decode(&mut &metadata.encode()[..]).unwrap();
+
+		assert_eq!(metadata_decoded.version(), 2);
+		assert_eq!(metadata_decoded, metadata);
+	}
+
+	#[test]
+	fn runtime_metadata_has_full_modules() {
+		let metadata = match TestRuntime::metadata() {
+			RuntimeMetadata::V2(metadata) => metadata,
+			_ => panic!("the metadata is of the current version"),
+		};
+
+		assert_eq!(&metadata[..2], &EXPECTED_METADATA[..2]);
+		assert_eq!(metadata[2], JsonMetadata::FullModule {
+			module: EXPECTED_METADATA_MODULE,
+			prefix: "event_module",
+			storage: None,
+			constants: vec![ModuleConstantMetadata {
+				name: "Answer",
+				ty: "u32",
+				value: vec![42, 0, 0, 0],
+				documentation: &[" The answer."],
+			}],
+			errors: "[ ]",
+			types: vec![("u32", "u32".to_owned())],
+		});
+		match metadata[3] {
+			JsonMetadata::FullModule { ref storage, ref constants, ref types, .. } => {
+				assert!(storage.is_some());
+				assert!(constants.is_empty());
+				assert_eq!(types, &vec![("u32", "u32".to_owned())]);
+			},
+			_ => panic!("the second module has a full module metadata"),
+		}
+	}
+
+	#[test]
+	fn runtime_metadata_ends_with_the_registered_types() {
+		let metadata = match TestRuntime::metadata() {
+			RuntimeMetadata::V2(metadata) => metadata,
+			_ => panic!("the metadata is of the current version"),
+		};
+
+		let types = match metadata.last() {
+			Some(JsonMetadata::Types { types }) => types.clone(),
+			_ => panic!("the types are the last metadata"),
+		};
+		let names = types.iter().map(|(name, _)| &name[..]).collect::<Vec<_>>();
+		assert_eq!(names, vec![
+			"TestEvent",
+			"srml_support::metadata::tests::system::Event",
+			"srml_support::metadata::tests::event_module::RawEvent<u32>",
+			"u32",
+			"srml_support::metadata::tests::event_module2::RawEvent<u32>",
+		]);
+		assert_eq!(types[1].1, TypeDef::Enum(vec![
+			VariantDef { name: "SystemEvent".into(), index: 0, fields: vec![] },
+		]));
+		assert_eq!(types[2].1, TypeDef::Enum(vec![
+			VariantDef { name: "TestEvent".into(), index: 0, fields: vec![FieldDef { name: None, ty: "u32".into() }] },
+		]));
+	}
+
+	#[test]
+	fn full_modules_are_valid_json() {
+		let metadata_decoded = RuntimeMetadataDecodable::decode(&mut &TestRuntime::metadata().encode()[..]);
+
+		for mdata in metadata_decoded.unwrap().into_json_metadata() {
+			let json = mdata.into_json_string();
+			let _: serde::de::IgnoredAny =
+				serde_json::from_str(&json.1).expect(&format!("Is valid json syntax: {}", json.1));
+		}
+	}
+
 	#[test]
 	fn into_json_string_is_valid_json() {
 		let metadata = TestRuntime::json_metadata();
@@ -14,6 +14,124 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
+//! The origin of calls: the `RawOrigin` of the system module, the checks of it which
+//! dispatchables start with, and the outer `Origin` of a runtime.
+
+use dispatch::{DispatchError, IntoDispatchError, ModuleErrors};
+pub use runtime_primitives::traits::EnsureOrigin;
+
+/// The origin of a call as known to the system module.
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum RawOrigin<AccountId> {
+	/// The system itself ordained this dispatch to happen: this is the highest privilege level.
+	Root,
+	/// It is signed by some public key and we provide the AccountId.
+	Signed(AccountId),
+	/// It is signed by nobody but included and agreed upon by the validators anyway: it's "inherently" true.
+	Inherent,
+}
+
+impl<AccountId> From<Option<AccountId>> for RawOrigin<AccountId> {
+	fn from(s: Option<AccountId>) -> RawOrigin<AccountId> {
+		match s {
+			Some(who) => RawOrigin::Signed(who),
+			None => RawOrigin::Inherent,
+		}
+	}
+}
+
+/// The origin of a call is not the one expected by `ensure_signed`, `ensure_root` or
+/// `ensure_none`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum BadOrigin {
+	ExpectedSigned,
+	ExpectedRoot,
+	ExpectedNone,
+}
+
+impl From<BadOrigin> for &'static str {
+	fn from(error: BadOrigin) -> &'static str {
+		match error {
+			BadOrigin::ExpectedSigned => "bad origin: expected to be a signed origin",
+			BadOrigin::ExpectedRoot => "bad origin: expected to be a root origin",
+			BadOrigin::ExpectedNone => "bad origin: expected to be an inherent origin",
+		}
+	}
+}
+
+impl From<BadOrigin> for DispatchError {
+	fn from(error: BadOrigin) -> DispatchError {
+		<&'static str>::from(error).into()
+	}
+}
+
+impl IntoDispatchError for BadOrigin {
+	fn into_dispatch_error<M: ModuleErrors>(self) -> DispatchError {
+		self.into()
+	}
+}
+
+/// Ensure that the origin `o` represents a signed extrinsic (i.e. transaction).
+/// Returns `Ok` with the account that signed the extrinsic or an `Err` otherwise.
+pub fn ensure_signed<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<AccountId, BadOrigin>
+	where OuterOrigin: Into<Option<RawOrigin<AccountId>>>
+{
+	match o.into() {
+		Some(RawOrigin::Signed(t)) => Ok(t),
+		_ => Err(BadOrigin::ExpectedSigned),
+	}
+}
+
+/// Ensure that the origin `o` represents the root. Returns `Ok` or an `Err` otherwise.
+pub fn ensure_root<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<(), BadOrigin>
+	where OuterOrigin: Into<Option<RawOrigin<AccountId>>>
+{
+	match o.into() {
+		Some(RawOrigin::Root) => Ok(()),
+		_ => Err(BadOrigin::ExpectedRoot),
+	}
+}
+
+/// Ensure that the origin `o` represents an unsigned extrinsic, i.e. an inherent. Returns `Ok` or
+/// an `Err` otherwise.
+pub fn ensure_none<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<(), BadOrigin>
+	where OuterOrigin: Into<Option<RawOrigin<AccountId>>>
+{
+	match o.into() {
+		Some(RawOrigin::Inherent) => Ok(()),
+		_ => Err(BadOrigin::ExpectedNone),
+	}
+}
+
+/// Accepts the root origin only, e.g. as the `ApproveOrigin` of a module.
+pub struct EnsureRoot<AccountId>(::rstd::marker::PhantomData<AccountId>);
+impl<O: Into<Option<RawOrigin<AccountId>>>, AccountId> EnsureOrigin<O> for EnsureRoot<AccountId> {
+	type Success = ();
+	fn ensure_origin(o: O) -> Result<Self::Success, &'static str> {
+		ensure_root(o).map_err(Into::into)
+	}
+}
+
+/// Accepts signed origins, succeeding with the signing account.
+pub struct EnsureSigned<AccountId>(::rstd::marker::PhantomData<AccountId>);
+impl<O: Into<Option<RawOrigin<AccountId>>>, AccountId> EnsureOrigin<O> for EnsureSigned<AccountId> {
+	type Success = AccountId;
+	fn ensure_origin(o: O) -> Result<Self::Success, &'static str> {
+		ensure_signed(o).map_err(Into::into)
+	}
+}
+
+/// Accepts the origin of inherents only.
+pub struct EnsureNone<AccountId>(::rstd::marker::PhantomData<AccountId>);
+impl<O: Into<Option<RawOrigin<AccountId>>>, AccountId> EnsureOrigin<O> for EnsureNone<AccountId> {
+	type Success = ();
+	fn ensure_origin(o: O) -> Result<Self::Success, &'static str> {
+		ensure_none(o).map_err(Into::into)
+	}
+}
+
 #[macro_export]
 macro_rules! impl_outer_origin {
 	(
@@ -219,9 +337,57 @@ mod tests {
 		}
 	}
 
+	mod collective {
+		pub trait Trait {
+			type Origin;
+		}
+
+		decl_module! {
+			pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+				/// The origins of the collective.
+				pub enum Origin {
+					/// Approved by the given number of members out of the given total.
+					Members(u32, u32),
+				}
+			}
+		}
+	}
+
+	mod checked {
+		use origin::{self, RawOrigin};
+		use dispatch::Result;
+
+		pub trait Trait {
+			type Origin: Into<Option<RawOrigin<u32>>>;
+		}
+
+		decl_module! {
+			pub struct Module<T: Trait> for enum Call where origin: T::Origin where system = origin {
+				#[require_none]
+				fn note(data: u32) -> Result;
+				#[require_root]
+				fn set(data: u32) -> Result;
+			}
+		}
+
+		impl<T: Trait> Module<T> {
+			fn note(_: u32) -> Result {
+				Ok(())
+			}
+
+			fn set(_: u32) -> Result {
+				Ok(())
+			}
+		}
+	}
+
 	#[derive(Clone, PartialEq, Eq, Debug)]
 	pub struct TestRuntime;
 
+	impl checked::Trait for TestRuntime {
+		type Origin = ::origin::RawOrigin<u32>;
+	}
+
 	impl system::Trait for TestRuntime {
 		type AccountId = u32;
 	}
@@ -257,4 +423,54 @@ mod tests {
 	impl_outer_origin!(
 		pub enum OriginEmpty for TestRuntime where system = system {}
 	);
+
+	impl_outer_origin!(
+		pub enum OriginWithCollective for TestRuntime where system = system {
+			collective,
+		}
+	);
+
+	#[test]
+	fn module_origins_convert_both_ways() {
+		let origin: OriginWithCollective = collective::Origin::Members(2, 3).into();
+		let module_origin: Option<collective::Origin> = origin.clone().into();
+		assert_eq!(module_origin, Some(collective::Origin::Members(2, 3)));
+		assert_eq!(Into::<Option<system::Origin<TestRuntime>>>::into(origin), None);
+
+		let origin: Option<collective::Origin> = OriginWithCollective::ROOT.into();
+		assert_eq!(origin, None);
+	}
+
+	#[test]
+	fn required_origins_are_checked_before_dispatch() {
+		use dispatch::Dispatchable;
+		use origin::RawOrigin;
+
+		let note = || checked::Call::<TestRuntime>::note(1);
+		assert!(note().dispatch(RawOrigin::Inherent).is_ok());
+		assert!(note().dispatch(RawOrigin::Root).is_err());
+		assert!(note().dispatch(RawOrigin::Signed(1)).is_err());
+
+		let set = || checked::Call::<TestRuntime>::set(1);
+		assert!(set().dispatch(RawOrigin::Root).is_ok());
+		assert!(set().dispatch(RawOrigin::Inherent).is_err());
+	}
+
+	#[test]
+	fn origins_are_checked() {
+		use origin::{self, BadOrigin, EnsureOrigin, EnsureSigned, RawOrigin};
+
+		assert_eq!(origin::ensure_signed(RawOrigin::Signed(5u32)), Ok(5));
+		assert_eq!(origin::ensure_signed(RawOrigin::<u32>::Root), Err(BadOrigin::ExpectedSigned));
+		assert_eq!(origin::ensure_root(RawOrigin::<u32>::Root), Ok(()));
+		assert_eq!(origin::ensure_root(RawOrigin::Signed(5u32)), Err(BadOrigin::ExpectedRoot));
+		assert_eq!(origin::ensure_none(RawOrigin::<u32>::from(None)), Ok(()));
+		assert_eq!(origin::ensure_none(RawOrigin::<u32>::Root), Err(BadOrigin::ExpectedNone));
+
+		assert_eq!(EnsureSigned::<u32>::ensure_origin(RawOrigin::Signed(5u32)), Ok(5));
+		assert_eq!(
+			EnsureSigned::<u32>::ensure_origin(RawOrigin::Inherent),
+			Err("bad origin: expected to be a signed origin")
+		);
+	}
 }
@@ -0,0 +1,92 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Composition of a runtime out of its modules.
+
+/// Declare a runtime and the modules it is made of, generating the glue otherwise written with the
+/// `impl_outer_*` macros.
+///
+/// ```rust,ignore
+/// construct_runtime!(
+/// 	pub enum Runtime with Log(InternalLog: DigestItem<SessionKey>) {
+/// 		System: system::{Module, Storage, Config, Event},
+/// 		Consensus: consensus::{Module, Call, Storage, Config, Log(AuthoritiesChange)},
+/// 		Balances: balances::{Module, Call, Storage, Config, Event<T>},
+/// 		CouncilMotions: council_motions::{Module, Call, Storage, Event<T>, Origin},
+/// 	}
+/// );
+/// ```
+///
+/// Each module is given its name in the runtime, the Rust module declaring it, and the parts it
+/// brings to the runtime:
+///
+/// - `Module`: the type alias `Name = module::Module<Runtime>`, and the module in `AllModules`,
+///   which lists every module but `system` for the `Executive` to call their hooks, and in the
+///   metadata.
+/// - `Call`: a variant of the outer `Call`.
+/// - `Storage`: the storage of the module in the metadata.
+/// - `Config`: a field of the `GenesisConfig`, of type `NameConfig`.
+/// - `Event` or `Event<T>`: a variant of the outer `Event`.
+/// - `Origin` or `Origin<T>`: a variant of the outer `Origin`.
+/// - `Log(..)`: a variant of the outer log declared with `with Log(..)`, the system log items of
+///   the module given in parentheses.
+///
+/// The hooks of the modules in `AllModules`, e.g. `on_finalise`, are called in the order the
+/// modules are declared in. Another order may be given after the modules, either the reverse one
+/// or an explicit one naming each of these modules once:
+///
+/// ```rust,ignore
+/// construct_runtime!(
+/// 	pub enum Runtime {
+/// 		..
+/// 	} with hooks(Staking, Session, Balances, Consensus)
+/// );
+/// ```
+///
+/// or `} with hooks reversed`. The resolved order is part of the metadata, as `HooksOrder`.
+///
+/// Each module has an index, which its calls and events are encoded with, and which identifies it
+/// in a `DispatchError`: the one after the index of the module before it, the first module being
+/// at 0. The index may be given after the parts, e.g. `Balances: balances::{Module, Call} = 5`, to
+/// keep the encoding of the calls and events of a module when modules are added or reordered. No
+/// two modules may have the same index. The indices are part of the metadata, as `ModuleIndices`.
+///
+/// The `Event` and `Origin` of `system` are always part of the outer ones. The runtime must
+/// `#[macro_use]` both `srml-support` and `sr-primitives`, which declare the `impl_outer_*`
+/// macros, and `serde_derive`.
+#[macro_export]
+macro_rules! construct_runtime {
+	($($t:tt)*) => {
+		$crate::__construct_runtime_impl! { $($t)* }
+	}
+}
+
+/// `TypeInfo` of the outer log of a runtime, which is encoded as the `DigestItem` it wraps.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __impl_outer_log_type_info {
+	( $log:ident: DigestItem<$( $arg:ty ),*> ) => {
+		impl $crate::type_info::TypeInfo for $log {
+			fn type_name() -> $crate::type_info::String {
+				<$crate::runtime_primitives::generic::DigestItem<$( $arg ),*> as $crate::type_info::TypeInfo>::type_name()
+			}
+
+			fn type_def(registry: &mut $crate::type_info::TypeRegistry) -> $crate::type_info::TypeDef {
+				<$crate::runtime_primitives::generic::DigestItem<$( $arg ),*> as $crate::type_info::TypeInfo>::type_def(registry)
+			}
+		}
+	}
+}
@@ -0,0 +1,183 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An opt-in cache of the values read from the state, e.g. for the execution of a block.
+//!
+//! While the closure given to `with_read_cache` runs, the first read of a key fetches its encoded
+//! value from the state and later reads are served from memory, saving the calls into the
+//! externalities. Writes through this module (`RuntimeStorage`, the `unhashed` functions and the
+//! hashed functions) go to the state and update the cache; writes made directly with
+//! `runtime_io` are not seen by the cache, so keys written that way must not be read while it is
+//! enabled.
+
+use rstd::prelude::*;
+use rstd::collections::btree_map::BTreeMap;
+
+/// The encoded values of the keys read or written while the cache is enabled, `None` for keys
+/// known to have no value.
+type Cache = BTreeMap<Vec<u8>, Option<Vec<u8>>>;
+
+#[cfg(feature = "std")]
+thread_local!(static CACHE: ::std::cell::RefCell<Option<Cache>> = ::std::cell::RefCell::new(None));
+
+// The runtime is single-threaded.
+#[cfg(not(feature = "std"))]
+static mut CACHE: Option<Cache> = None;
+
+#[cfg(feature = "std")]
+fn with_cache<R, F: FnOnce(&mut Option<Cache>) -> R>(f: F) -> R {
+	CACHE.with(|cache| f(&mut cache.borrow_mut()))
+}
+
+#[cfg(not(feature = "std"))]
+fn with_cache<R, F: FnOnce(&mut Option<Cache>) -> R>(f: F) -> R {
+	unsafe { f(&mut CACHE) }
+}
+
+/// Drops the cache when the closure given to `with_read_cache` returns.
+struct Disable;
+
+impl Drop for Disable {
+	fn drop(&mut self) {
+		with_cache(|cache| *cache = None);
+	}
+}
+
+/// Execute `f` with the cache enabled, dropping it afterwards. A nested call reuses the cache of
+/// the outer one.
+pub fn with_read_cache<R, F: FnOnce() -> R>(f: F) -> R {
+	let enabled_here = with_cache(|cache| match *cache {
+		Some(_) => false,
+		None => {
+			*cache = Some(Cache::new());
+			true
+		},
+	});
+	let _disable = if enabled_here { Some(Disable) } else { None };
+	f()
+}
+
+/// Whether the cache is enabled.
+pub fn is_enabled() -> bool {
+	with_cache(|cache| cache.is_some())
+}
+
+/// The cached value of `key`: `None` if the cache is disabled or has not seen the key, `Some(None)`
+/// if the key is known to have no value.
+pub(super) fn cached(key: &[u8]) -> Option<Option<Vec<u8>>> {
+	with_cache(|cache| cache.as_ref().and_then(|cache| cache.get(key).cloned()))
+}
+
+/// The encoded value of `key`, from the cache if it holds it, or from `read` otherwise, in which
+/// case it is cached if the cache is enabled.
+pub(super) fn get_or_read<F: FnOnce() -> Option<Vec<u8>>>(key: &[u8], read: F) -> Option<Vec<u8>> {
+	if let Some(value) = cached(key) {
+		return value;
+	}
+	let value = read();
+	with_cache(|cache| if let Some(ref mut cache) = *cache {
+		cache.insert(key.to_vec(), value.clone());
+	});
+	value
+}
+
+/// Record that `key` now holds `value`, if the cache is enabled.
+pub(super) fn write(key: &[u8], value: Option<&[u8]>) {
+	with_cache(|cache| if let Some(ref mut cache) = *cache {
+		cache.insert(key.to_vec(), value.map(|v| v.to_vec()));
+	});
+}
+
+/// Record that the keys starting with `prefix` hold no value any more, if the cache is enabled.
+pub(super) fn kill_prefix(prefix: &[u8]) {
+	with_cache(|cache| if let Some(ref mut cache) = *cache {
+		for (_, value) in cache.iter_mut().filter(|&(ref key, _)| key.starts_with(prefix)) {
+			*value = None;
+		}
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use runtime_io::{self, with_externalities, TestExternalities};
+	use storage::{self, unhashed};
+
+	#[test]
+	fn reads_are_served_from_the_cache() {
+		with_externalities(&mut TestExternalities::new(), || {
+			unhashed::put(b":validators", &vec![1u64, 2, 3]);
+
+			with_read_cache(|| {
+				assert!(is_enabled());
+				assert_eq!(unhashed::get(b":validators"), Some(vec![1u64, 2, 3]));
+
+				// Not seen by the cache, which keeps the value read first.
+				runtime_io::set_storage(b":validators", &vec![4u64].encode());
+				assert_eq!(unhashed::get(b":validators"), Some(vec![1u64, 2, 3]));
+
+				assert_eq!(unhashed::get::<u64>(b":missing"), None);
+				runtime_io::set_storage(b":missing", &5u64.encode());
+				assert!(!unhashed::exists(b":missing"));
+			});
+
+			assert!(!is_enabled());
+			assert_eq!(unhashed::get(b":validators"), Some(vec![4u64]));
+			assert_eq!(unhashed::get(b":missing"), Some(5u64));
+		});
+	}
+
+	#[test]
+	fn writes_update_the_cache() {
+		with_externalities(&mut TestExternalities::new(), || {
+			with_read_cache(|| {
+				assert_eq!(unhashed::get::<u32>(b":a:1"), None);
+				unhashed::put(b":a:1", &1u32);
+				unhashed::put(b":a:2", &2u32);
+				assert_eq!(unhashed::get(b":a:1"), Some(1u32));
+
+				unhashed::kill(b":a:1");
+				assert_eq!(unhashed::get::<u32>(b":a:1"), None);
+
+				unhashed::put(b":a:1", &1u32);
+				unhashed::kill_prefix(b":a:");
+				assert!(!unhashed::exists(b":a:1"));
+				assert_eq!(unhashed::take::<u32>(b":a:2"), None);
+
+				storage::put(b"hashed", &3u32);
+				assert_eq!(storage::get(b"hashed"), Some(3u32));
+				storage::kill(b"hashed");
+				assert_eq!(storage::get::<u32>(b"hashed"), None);
+			});
+
+			assert_eq!(unhashed::get::<u32>(b":a:1"), None);
+			assert_eq!(storage::get::<u32>(b"hashed"), None);
+		});
+	}
+
+	#[test]
+	fn nested_calls_share_the_cache() {
+		with_externalities(&mut TestExternalities::new(), || {
+			with_read_cache(|| {
+				with_read_cache(|| unhashed::put(b":n", &1u32));
+				assert!(is_enabled());
+				runtime_io::set_storage(b":n", &2u32.encode());
+				assert_eq!(unhashed::get(b":n"), Some(1u32));
+			});
+		});
+	}
+}
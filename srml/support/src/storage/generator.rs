@@ -19,10 +19,11 @@
 //! This crate exports a macro `storage_items!` and traits describing behavior of generated
 //! structs.
 //!
-//! Three kinds of data types are currently supported:
+//! Four kinds of data types are currently supported:
 //!   - values
 //!   - maps
-//!   - lists
+//!   - lists, kept as a `StorageVec`
+//!   - deques of bounded capacity, kept as a `StorageDeque`
 //!
 //! # Examples:
 //!
@@ -41,17 +42,23 @@
 //!     Balances: b"private_map:" => map [AuthorityId => Balance];
 //!     // private list.
 //!     Authorities: b"auth:" => list [AuthorityId];
+//!     // private deque of at most 16 items.
+//!     Pending: b"pending:" => deque [Balance; 16];
 //! }
 //!
 //!# fn main() { }
 //! ```
 
+use bounded::Get;
 use codec;
+use rstd::cmp::Ordering;
 use rstd::vec::Vec;
 #[doc(hidden)]
 pub use rstd::borrow::Borrow;
 #[doc(hidden)]
 pub use rstd::marker::PhantomData;
+#[doc(hidden)]
+pub use primitives::compact::Compact;
 
 /// Abstraction around storage.
 pub trait Storage {
@@ -69,12 +76,64 @@ pub trait Storage {
 	/// default is returned if it's not there.
 	fn get_or_default<T: codec::Codec + Default>(&self, key: &[u8]) -> T { self.get(key).unwrap_or_default() }
 
+	/// Load the bytes of a key from storage. Can panic if the type is incorrect. The value built by
+	/// `default_value`, which is only called then, is returned if it's not there.
+	fn get_or_else<T: codec::Codec, F: FnOnce() -> T>(&self, key: &[u8], default_value: F) -> T {
+		self.get(key).unwrap_or_else(default_value)
+	}
+
+	/// Load the first bytes of the value of a key into `buf`, returning the length of the whole
+	/// value, or `None` if it's not there.
+	fn read(&self, key: &[u8], buf: &mut [u8]) -> Option<usize>;
+
+	/// Load the encoded bytes of a key from storage, without decoding them.
+	fn get_raw(&self, key: &[u8]) -> Option<Vec<u8>> {
+		let len = self.read(key, &mut [])?;
+		let mut value = Vec::new();
+		value.resize(len, 0);
+		self.read(key, &mut value[..]);
+		Some(value)
+	}
+
 	/// Put a value in under a key.
 	fn put<T: codec::Codec>(&self, key: &[u8], val: &T);
 
+	/// Put encoded bytes in under a key.
+	fn put_raw(&self, key: &[u8], value: &[u8]);
+
 	/// Remove the bytes of a key from storage.
 	fn kill(&self, key: &[u8]);
 
+	/// Exchange the bytes under two keys. If only one of them is in storage, it is moved under
+	/// the other key.
+	fn swap(&self, key1: &[u8], key2: &[u8]) {
+		let value1 = self.get_raw(key1);
+		let value2 = self.get_raw(key2);
+		match value2 {
+			Some(value) => self.put_raw(key1, &value[..]),
+			None => self.kill(key1),
+		}
+		match value1 {
+			Some(value) => self.put_raw(key2, &value[..]),
+			None => self.kill(key2),
+		}
+	}
+
+	/// Remove the bytes of all keys starting with `prefix` from storage. Keys are found with
+	/// `next_storage_key`, so storages that cannot walk their keys in order only remove `prefix`
+	/// itself.
+	fn kill_prefix(&self, prefix: &[u8]) {
+		self.kill(prefix);
+		let mut previous = prefix.to_vec();
+		while let Some(key) = self.next_storage_key(&previous) {
+			if !key.starts_with(prefix) {
+				break;
+			}
+			self.kill(&key[..]);
+			previous = key;
+		}
+	}
+
 	/// Take a value from storage, deleting it after reading.
 	fn take<T: codec::Codec>(&self, key: &[u8]) -> Option<T> {
 		let value = self.get(key);
@@ -87,6 +146,214 @@ pub trait Storage {
 
 	/// Take a value from storage, deleting it after reading.
 	fn take_or_default<T: codec::Codec + Default>(&self, key: &[u8]) -> T { self.take(key).unwrap_or_default() }
+
+	/// Take a value from storage, deleting it after reading. The value built by `default_value`,
+	/// which is only called then, is returned if it's not there.
+	fn take_or_else<T: codec::Codec, F: FnOnce() -> T>(&self, key: &[u8], default_value: F) -> T {
+		self.take(key).unwrap_or_else(default_value)
+	}
+
+	/// The smallest key in storage that is greater than `key`. Storages that cannot walk their
+	/// keys in order return `None`.
+	fn next_storage_key(&self, _key: &[u8]) -> Option<Vec<u8>> { None }
+
+	/// Apply many writes at once, in order: `Some` puts the encoded bytes under the key and `None`
+	/// removes it. Usually built with a `StorageBatch`. Storages which can apply the writes in one
+	/// operation override this; the others apply them one by one.
+	fn put_batch(&self, changes: &[(Vec<u8>, Option<Vec<u8>>)]) {
+		for &(ref key, ref value) in changes {
+			match *value {
+				Some(ref value) => self.put_raw(&key[..], &value[..]),
+				None => self.kill(&key[..]),
+			}
+		}
+	}
+
+	/// true if the key exists in the child storage `storage_key`.
+	fn child_exists(&self, storage_key: &[u8], key: &[u8]) -> bool {
+		self.exists(&child_key(storage_key, key)[..])
+	}
+
+	/// Load the bytes of a key from the child storage `storage_key`. Can panic if the type is
+	/// incorrect.
+	fn child_get<T: codec::Codec>(&self, storage_key: &[u8], key: &[u8]) -> Option<T> {
+		self.get(&child_key(storage_key, key)[..])
+	}
+
+	/// Put a value in under a key of the child storage `storage_key`.
+	fn child_put<T: codec::Codec>(&self, storage_key: &[u8], key: &[u8], val: &T) {
+		self.put(&child_key(storage_key, key)[..], val)
+	}
+
+	/// Remove the bytes of a key from the child storage `storage_key`.
+	fn child_kill(&self, storage_key: &[u8], key: &[u8]) {
+		self.kill(&child_key(storage_key, key)[..])
+	}
+
+	/// Remove the whole child storage `storage_key`.
+	fn child_kill_all(&self, storage_key: &[u8]) {
+		self.kill_prefix(&child_prefix(storage_key)[..])
+	}
+}
+
+/// Writes collected to be applied to a storage at once with `Storage::put_batch`.
+///
+/// ```rust,ignore
+/// let mut batch = StorageBatch::new();
+/// batch.put(b":a", &1u32).kill(b":b");
+/// batch.commit(&RuntimeStorage);
+/// ```
+#[derive(Default)]
+pub struct StorageBatch {
+	changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+impl StorageBatch {
+	/// An empty batch.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Put a value in under a key.
+	pub fn put<T: codec::Codec>(&mut self, key: &[u8], val: &T) -> &mut Self {
+		self.changes.push((key.to_vec(), Some(codec::Encode::encode(val))));
+		self
+	}
+
+	/// Put encoded bytes in under a key.
+	pub fn put_raw(&mut self, key: &[u8], value: &[u8]) -> &mut Self {
+		self.changes.push((key.to_vec(), Some(value.to_vec())));
+		self
+	}
+
+	/// Remove the bytes of a key.
+	pub fn kill(&mut self, key: &[u8]) -> &mut Self {
+		self.changes.push((key.to_vec(), None));
+		self
+	}
+
+	/// The number of writes in the batch.
+	pub fn len(&self) -> usize {
+		self.changes.len()
+	}
+
+	/// true if the batch holds no write.
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Apply the writes to `storage`.
+	pub fn commit<S: Storage + ?Sized>(self, storage: &S) {
+		if !self.changes.is_empty() {
+			storage.put_batch(&self.changes[..]);
+		}
+	}
+}
+
+/// The prefix of the keys under which the entries of all child storages are kept.
+pub const CHILD_STORAGE_PREFIX: &[u8] = b":child_storage:";
+
+/// The prefix of the keys under which the entries of the child storage `storage_key` are kept.
+pub fn child_prefix(storage_key: &[u8]) -> Vec<u8> {
+	let mut prefix = CHILD_STORAGE_PREFIX.to_vec();
+	codec::Encode::encode_to(storage_key, &mut prefix);
+	prefix
+}
+
+/// The key under which `key` of the child storage `storage_key` is kept.
+pub fn child_key(storage_key: &[u8], key: &[u8]) -> Vec<u8> {
+	let mut child_key = child_prefix(storage_key);
+	child_key.extend_from_slice(key);
+	child_key
+}
+
+/// A value whose encoding starts with its length, which can be read without decoding the rest.
+pub trait DecodeLength: codec::Codec {}
+
+impl<T: codec::Codec> DecodeLength for Vec<T> {}
+
+/// A type which encodes exactly like `T`, so that it can be used to look up a map keyed by `T`
+/// without building a `T` first, e.g. `&str` for a `String` key or `&[u8]` for a `Vec<u8>` key.
+pub trait EncodeLike<T: codec::Encode>: codec::Encode {}
+
+impl<T: codec::Encode> EncodeLike<T> for T {}
+impl<'a, T: codec::Encode> EncodeLike<T> for &'a T {}
+impl<T: codec::Encode> EncodeLike<Vec<T>> for [T] {}
+impl<'a, T: codec::Encode> EncodeLike<Vec<T>> for &'a [T] {}
+#[cfg(feature = "std")]
+impl EncodeLike<String> for str {}
+#[cfg(feature = "std")]
+impl<'a> EncodeLike<String> for &'a str {}
+
+/// Read the length of the value under `key` from the prefix of its encoding.
+fn decode_len<S: Storage>(key: &[u8], storage: &S) -> Option<u32> {
+	let mut prefix = [0u8; 4];
+	match storage.read(key, &mut prefix[..]) {
+		Some(len) if len >= prefix.len() => codec::Decode::decode(&mut &prefix[..]),
+		Some(_) => panic!("storage is not null, therefore must be a valid type"),
+		None => None,
+	}
+}
+
+/// Provides the default value of `T`.
+pub struct GetDefault;
+
+impl<T: Default> Get<T> for GetDefault {
+	fn get() -> T {
+		T::default()
+	}
+}
+
+/// Panics when asked for a value, for items that must always be in storage.
+pub struct PanicOnEmpty;
+
+impl<T> Get<T> for PanicOnEmpty {
+	fn get() -> T {
+		panic!("Required values must be in storage")
+	}
+}
+
+/// How the reads of an item turn the value in storage, which may be missing, into the type they
+/// return. `OnEmpty` provides the value of missing items to the kinds which need one.
+pub trait QueryKindTrait<T, OnEmpty> {
+	/// The type reads return.
+	type Query;
+
+	/// The result of reading `v` from storage.
+	fn from_optional_value_to_query(v: Option<T>) -> Self::Query;
+
+	/// The value to keep in storage for `query`; `None` removes the item.
+	fn from_query_to_optional_value(query: Self::Query) -> Option<T>;
+}
+
+/// Reads return `Option<T>`, `None` if the item is missing. Writing `None` removes the item.
+pub struct OptionQuery;
+
+impl<T, OnEmpty> QueryKindTrait<T, OnEmpty> for OptionQuery {
+	type Query = Option<T>;
+
+	fn from_optional_value_to_query(v: Option<T>) -> Self::Query {
+		v
+	}
+
+	fn from_query_to_optional_value(query: Self::Query) -> Option<T> {
+		query
+	}
+}
+
+/// Reads return `T`, the value given by `OnEmpty` if the item is missing.
+pub struct ValueQuery;
+
+impl<T, OnEmpty: Get<T>> QueryKindTrait<T, OnEmpty> for ValueQuery {
+	type Query = T;
+
+	fn from_optional_value_to_query(v: Option<T>) -> Self::Query {
+		v.unwrap_or_else(OnEmpty::get)
+	}
+
+	fn from_query_to_optional_value(query: Self::Query) -> Option<T> {
+		Some(query)
+	}
 }
 
 /// A strongly-typed value kept in storage.
@@ -94,6 +361,12 @@ pub trait StorageValue<T: codec::Codec> {
 	/// The type that get/take returns.
 	type Query;
 
+	/// How a missing value is turned into `Query`.
+	type QueryKind: QueryKindTrait<T, Self::OnEmpty, Query = Self::Query>;
+
+	/// The value of a missing item, for query kinds that need one.
+	type OnEmpty;
+
 	/// Get the storage key.
 	fn key() -> &'static [u8];
 
@@ -103,27 +376,91 @@ pub trait StorageValue<T: codec::Codec> {
 	}
 
 	/// Load the value from the provided storage instance.
-	fn get<S: Storage>(storage: &S) -> Self::Query;
+	fn get<S: Storage>(storage: &S) -> Self::Query {
+		Self::QueryKind::from_optional_value_to_query(storage.get(Self::key()))
+	}
 
 	/// Take a value from storage, removing it afterwards.
-	fn take<S: Storage>(storage: &S) -> Self::Query;
+	fn take<S: Storage>(storage: &S) -> Self::Query {
+		Self::QueryKind::from_optional_value_to_query(storage.take(Self::key()))
+	}
 
 	/// Store a value under this key into the provided storage instance.
 	fn put<S: Storage>(val: &T, storage: &S) {
 		storage.put(Self::key(), val)
 	}
 
-	/// Mutate this value
-	fn mutate<F: FnOnce(&mut Self::Query), S: Storage>(f: F, storage: &S);
+	/// Load the encoded value without decoding it.
+	fn get_raw<S: Storage>(storage: &S) -> Option<Vec<u8>> {
+		storage.get_raw(Self::key())
+	}
+
+	/// Store an already encoded value.
+	fn put_raw<S: Storage>(value: &[u8], storage: &S) {
+		storage.put_raw(Self::key(), value)
+	}
+
+	/// Mutate this value, returning the result of `f`.
+	fn mutate<R, F: FnOnce(&mut Self::Query) -> R, S: Storage>(f: F, storage: &S) -> R {
+		let mut val = Self::get(storage);
+
+		let result = f(&mut val);
+
+		match Self::QueryKind::from_query_to_optional_value(val) {
+			Some(val) => Self::put(&val, storage),
+			None => Self::kill(storage),
+		}
+		result
+	}
+
+	/// Mutate this value, storing the result only if `f` returns `Ok`. The value is left
+	/// untouched if it returns an error, which is passed on.
+	fn try_mutate<R, E, F: FnOnce(&mut Self::Query) -> Result<R, E>, S: Storage>(f: F, storage: &S) -> Result<R, E> {
+		let mut val = Self::get(storage);
+
+		let result = f(&mut val)?;
+
+		match Self::QueryKind::from_query_to_optional_value(val) {
+			Some(val) => Self::put(&val, storage),
+			None => Self::kill(storage),
+		}
+		Ok(result)
+	}
 
 	/// Clear the storage value.
 	fn kill<S: Storage>(storage: &S) {
 		storage.kill(Self::key())
 	}
+
+	/// Read the length of the value without decoding it, `None` if it's not in storage.
+	fn decode_len<S: Storage>(storage: &S) -> Option<u32> where T: DecodeLength {
+		decode_len(Self::key(), storage)
+	}
+
+	/// Replace the value, kept in the encoding of `O`, with the one `f` maps it to. `f` is given
+	/// `None` if there is no value and the value is removed if it returns `None`.
+	///
+	/// Fails, leaving the storage untouched, if the value cannot be decoded as an `O`.
+	fn translate<O: codec::Decode, F: FnOnce(Option<O>) -> Option<T>, S: Storage>(f: F, storage: &S)
+		-> Result<Option<T>, &'static str>
+	{
+		let old = match storage.get_raw(Self::key()) {
+			Some(value) => Some(O::decode(&mut &value[..]).ok_or("old value could not be decoded")?),
+			None => None,
+		};
+		let new = f(old);
+		match new {
+			Some(ref value) => storage.put(Self::key(), value),
+			None => storage.kill(Self::key()),
+		}
+		Ok(new)
+	}
 }
 
-/// A strongly-typed list in storage.
-pub trait StorageList<T: codec::Codec> {
+/// A strongly-typed vector in storage, keeping its items under the prefix followed by their index
+/// and its length under the prefix followed by `len`. Pushing, popping and removing items only
+/// touch the affected indices and the length.
+pub trait StorageVec<T: codec::Codec> {
 	/// Get the prefix key in storage.
 	fn prefix() -> &'static [u8];
 
@@ -134,22 +471,330 @@ pub trait StorageList<T: codec::Codec> {
 	fn key_for(index: u32) -> Vec<u8>;
 
 	/// Read out all the items.
-	fn items<S: Storage>(storage: &S) -> Vec<T>;
+	fn items<S: Storage>(storage: &S) -> Vec<T> {
+		(0..Self::len(storage))
+			.map(|i| Self::get(i, storage).expect("all items within length are set; qed"))
+			.collect()
+	}
 
 	/// Set the current set of items.
-	fn set_items<S: Storage>(items: &[T], storage: &S);
+	fn set_items<S: Storage>(items: &[T], storage: &S) {
+		let len = Self::len(storage);
+		let mut batch = StorageBatch::new();
+		for (i, item) in items.iter().enumerate() {
+			batch.put(&Self::key_for(i as u32)[..], item);
+		}
+		let new_len = items.len() as u32;
+		(new_len..len).for_each(|i| { batch.kill(&Self::key_for(i)[..]); });
+		batch.put(&Self::len_key()[..], &new_len);
+		batch.commit(storage);
+	}
 
-	/// Set the item at the given index.
-	fn set_item<S: Storage>(index: u32, item: &T, storage: &S);
+	/// Set the item at the given index. Does nothing if the index is out-of-bounds.
+	fn set_item<S: Storage>(index: u32, item: &T, storage: &S) {
+		if index < Self::len(storage) {
+			storage.put(&Self::key_for(index)[..], item);
+		}
+	}
 
 	/// Load the value at given index. Returns `None` if the index is out-of-bounds.
-	fn get<S: Storage>(index: u32, storage: &S) -> Option<T>;
+	fn get<S: Storage>(index: u32, storage: &S) -> Option<T> {
+		storage.get(&Self::key_for(index)[..])
+	}
+
+	/// Load the length of the vector.
+	fn len<S: Storage>(storage: &S) -> u32 {
+		storage.get_or_default(&Self::len_key()[..])
+	}
+
+	/// Append an item.
+	fn push<S: Storage>(item: &T, storage: &S) {
+		let len = Self::len(storage);
+		storage.put(&Self::key_for(len)[..], item);
+		storage.put(&Self::len_key()[..], &len.checked_add(1).expect("exceeded runtime storage capacity"));
+	}
+
+	/// Remove the last item and return it, or `None` if the vector is empty.
+	fn pop<S: Storage>(storage: &S) -> Option<T> {
+		let len = Self::len(storage).checked_sub(1)?;
+		let item = storage.take(&Self::key_for(len)[..]);
+		storage.put(&Self::len_key()[..], &len);
+		item
+	}
+
+	/// Remove the items from the given length on. Does nothing if the vector is not longer.
+	fn truncate<S: Storage>(len: u32, storage: &S) {
+		let old_len = Self::len(storage);
+		if len < old_len {
+			let mut batch = StorageBatch::new();
+			(len..old_len).for_each(|i| { batch.kill(&Self::key_for(i)[..]); });
+			batch.put(&Self::len_key()[..], &len);
+			batch.commit(storage);
+		}
+	}
+
+	/// Keep only the items for which `f` returns true, in order. The items before the first one
+	/// removed are left untouched.
+	fn retain<F: FnMut(&T) -> bool, S: Storage>(mut f: F, storage: &S) {
+		let len = Self::len(storage);
+		let mut kept = 0;
+		for i in 0..len {
+			let item = Self::get(i, storage).expect("all items within length are set; qed");
+			if f(&item) {
+				if kept != i {
+					storage.put(&Self::key_for(kept)[..], &item);
+				}
+				kept += 1;
+			}
+		}
+		Self::truncate(kept, storage);
+	}
+
+	/// Remove the item at the given index and return it, replacing it with the last item. Returns
+	/// `None` if the index is out-of-bounds.
+	fn swap_remove<S: Storage>(index: u32, storage: &S) -> Option<T> {
+		let len = Self::len(storage);
+		if index >= len {
+			return None;
+		}
+		let last = len - 1;
+		let item = Self::get(index, storage);
+		if index != last {
+			storage.swap(&Self::key_for(index)[..], &Self::key_for(last)[..]);
+		}
+		storage.kill(&Self::key_for(last)[..]);
+		storage.put(&Self::len_key()[..], &last);
+		item
+	}
+
+	/// Clear the vector.
+	fn clear<S: Storage>(storage: &S) {
+		let mut batch = StorageBatch::new();
+		(0..Self::len(storage)).for_each(|i| { batch.kill(&Self::key_for(i)[..]); });
+		batch.kill(&Self::len_key()[..]);
+		batch.commit(storage);
+	}
+}
+
+/// A strongly-typed double-ended queue in storage, holding at most `capacity()` items. It is kept
+/// as a ring: the items are stored under the prefix followed by their position, and the positions
+/// of the front item and of the one after the back item under the prefix itself. Positions wrap
+/// around, so pushing and popping only touch the affected item and the positions.
+pub trait StorageDeque<T: codec::Codec> {
+	/// Get the prefix key in storage.
+	fn prefix() -> &'static [u8];
+
+	/// Get the storage key used to fetch the item at a given position.
+	fn key_for(position: u32) -> Vec<u8>;
+
+	/// The maximum number of items.
+	fn capacity() -> u32;
+
+	/// The positions of the front item and of the one after the back item.
+	fn bounds<S: Storage>(storage: &S) -> (u32, u32) {
+		storage.get_or_default(Self::prefix())
+	}
+
+	/// Load the number of items.
+	fn len<S: Storage>(storage: &S) -> u32 {
+		let (front, back) = Self::bounds(storage);
+		back.wrapping_sub(front)
+	}
+
+	/// Read out all the items, from front to back.
+	fn items<S: Storage>(storage: &S) -> Vec<T> {
+		let (front, back) = Self::bounds(storage);
+		(0..back.wrapping_sub(front))
+			.map(|i| storage.require(&Self::key_for(front.wrapping_add(i))[..]))
+			.collect()
+	}
+
+	/// Load the front item, `None` if the queue is empty.
+	fn front<S: Storage>(storage: &S) -> Option<T> {
+		let (front, back) = Self::bounds(storage);
+		if front == back {
+			return None;
+		}
+		storage.get(&Self::key_for(front)[..])
+	}
+
+	/// Load the back item, `None` if the queue is empty.
+	fn back<S: Storage>(storage: &S) -> Option<T> {
+		let (front, back) = Self::bounds(storage);
+		if front == back {
+			return None;
+		}
+		storage.get(&Self::key_for(back.wrapping_sub(1))[..])
+	}
+
+	/// Append an item at the back. Fails if the queue is full.
+	fn push_back<S: Storage>(item: &T, storage: &S) -> Result<(), &'static str> {
+		let (front, back) = Self::bounds(storage);
+		if back.wrapping_sub(front) >= Self::capacity() {
+			return Err("the queue is full");
+		}
+		storage.put(&Self::key_for(back)[..], item);
+		storage.put(Self::prefix(), &(front, back.wrapping_add(1)));
+		Ok(())
+	}
+
+	/// Prepend an item at the front. Fails if the queue is full.
+	fn push_front<S: Storage>(item: &T, storage: &S) -> Result<(), &'static str> {
+		let (front, back) = Self::bounds(storage);
+		if back.wrapping_sub(front) >= Self::capacity() {
+			return Err("the queue is full");
+		}
+		let front = front.wrapping_sub(1);
+		storage.put(&Self::key_for(front)[..], item);
+		storage.put(Self::prefix(), &(front, back));
+		Ok(())
+	}
+
+	/// Remove the front item and return it, `None` if the queue is empty.
+	fn pop_front<S: Storage>(storage: &S) -> Option<T> {
+		let (front, back) = Self::bounds(storage);
+		if front == back {
+			return None;
+		}
+		let item = storage.take(&Self::key_for(front)[..]);
+		put_deque_bounds::<Self, T, S>(front.wrapping_add(1), back, storage);
+		item
+	}
 
-	/// Load the length of the list
-	fn len<S: Storage>(storage: &S) -> u32;
+	/// Remove the back item and return it, `None` if the queue is empty.
+	fn pop_back<S: Storage>(storage: &S) -> Option<T> {
+		let (front, back) = Self::bounds(storage);
+		if front == back {
+			return None;
+		}
+		let back = back.wrapping_sub(1);
+		let item = storage.take(&Self::key_for(back)[..]);
+		put_deque_bounds::<Self, T, S>(front, back, storage);
+		item
+	}
+
+	/// Remove all the items.
+	fn clear<S: Storage>(storage: &S) {
+		let (front, back) = Self::bounds(storage);
+		(0..back.wrapping_sub(front)).for_each(|i| storage.kill(&Self::key_for(front.wrapping_add(i))[..]));
+		storage.kill(Self::prefix());
+	}
+}
+
+/// Store the positions of a deque, removing them once it is empty so that it starts over from
+/// position zero.
+fn put_deque_bounds<D: StorageDeque<T> + ?Sized, T: codec::Codec, S: Storage>(front: u32, back: u32, storage: &S) {
+	if front == back {
+		storage.kill(D::prefix());
+	} else {
+		storage.put(D::prefix(), &(front, back));
+	}
+}
+
+/// A strongly-typed set in storage, keeping its items sorted and deduplicated. The items are
+/// stored in order under the prefix followed by their rank, and their number under the prefix
+/// itself, so checking membership only reads the few items of a binary search.
+pub trait StorageOrderedSet<T: codec::Codec + Ord> {
+	/// Get the prefix key in storage.
+	fn prefix() -> &'static [u8];
+
+	/// Get the storage key used to fetch the item of a given rank.
+	fn key_for(rank: u32) -> Vec<u8>;
+
+	/// Load the number of items.
+	fn len<S: Storage>(storage: &S) -> u32 {
+		storage.get_or_default(Self::prefix())
+	}
+
+	/// Load the item of the given rank, the smallest item having rank zero. Returns `None` if the
+	/// rank is out-of-bounds.
+	fn get<S: Storage>(rank: u32, storage: &S) -> Option<T> {
+		storage.get(&Self::key_for(rank)[..])
+	}
+
+	/// Read out all the items, in order.
+	fn items<S: Storage>(storage: &S) -> Vec<T> {
+		(0..Self::len(storage))
+			.map(|i| storage.require(&Self::key_for(i)[..]))
+			.collect()
+	}
+
+	/// Search the set for an item: `Ok` with its rank if it is in the set, `Err` with the rank it
+	/// would be inserted at otherwise.
+	fn search<S: Storage>(item: &T, storage: &S) -> Result<u32, u32> {
+		let (mut low, mut high) = (0, Self::len(storage));
+		while low < high {
+			let mid = low + (high - low) / 2;
+			let other: T = storage.require(&Self::key_for(mid)[..]);
+			match other.cmp(item) {
+				Ordering::Less => low = mid + 1,
+				Ordering::Greater => high = mid,
+				Ordering::Equal => return Ok(mid),
+			}
+		}
+		Err(low)
+	}
+
+	/// true if the item is in the set.
+	fn contains<S: Storage>(item: &T, storage: &S) -> bool {
+		Self::search(item, storage).is_ok()
+	}
+
+	/// The rank of the item, `None` if it is not in the set.
+	fn rank_of<S: Storage>(item: &T, storage: &S) -> Option<u32> {
+		Self::search(item, storage).ok()
+	}
+
+	/// Add an item, moving the greater items up by one rank. Returns false if it was already in
+	/// the set.
+	fn insert<S: Storage>(item: &T, storage: &S) -> bool {
+		let rank = match Self::search(item, storage) {
+			Ok(_) => return false,
+			Err(rank) => rank,
+		};
+		let len = Self::len(storage);
+		let mut batch = StorageBatch::new();
+		for i in (rank..len).rev() {
+			if let Some(value) = storage.get_raw(&Self::key_for(i)[..]) {
+				batch.put_raw(&Self::key_for(i + 1)[..], &value[..]);
+			}
+		}
+		batch.put(&Self::key_for(rank)[..], item);
+		batch.put(Self::prefix(), &len.checked_add(1).expect("exceeded runtime storage capacity"));
+		batch.commit(storage);
+		true
+	}
+
+	/// Remove an item, moving the greater items down by one rank. Returns false if it was not in
+	/// the set.
+	fn remove<S: Storage>(item: &T, storage: &S) -> bool {
+		let rank = match Self::search(item, storage) {
+			Ok(rank) => rank,
+			Err(_) => return false,
+		};
+		let len = Self::len(storage);
+		let mut batch = StorageBatch::new();
+		for i in rank + 1..len {
+			if let Some(value) = storage.get_raw(&Self::key_for(i)[..]) {
+				batch.put_raw(&Self::key_for(i - 1)[..], &value[..]);
+			}
+		}
+		batch.kill(&Self::key_for(len - 1)[..]);
+		match len - 1 {
+			0 => batch.kill(Self::prefix()),
+			len => batch.put(Self::prefix(), &len),
+		};
+		batch.commit(storage);
+		true
+	}
 
-	/// Clear the list.
-	fn clear<S: Storage>(storage: &S);
+	/// Remove all the items.
+	fn clear<S: Storage>(storage: &S) {
+		let mut batch = StorageBatch::new();
+		(0..Self::len(storage)).for_each(|i| { batch.kill(&Self::key_for(i)[..]); });
+		batch.kill(Self::prefix());
+		batch.commit(storage);
+	}
 }
 
 /// A strongly-typed map in storage.
@@ -157,22 +802,34 @@ pub trait StorageMap<K: codec::Codec, V: codec::Codec> {
 	/// The type that get/take returns.
 	type Query;
 
+	/// How a missing value is turned into `Query`.
+	type QueryKind: QueryKindTrait<V, Self::OnEmpty, Query = Self::Query>;
+
+	/// The value of a missing entry, for query kinds that need one.
+	type OnEmpty;
+
 	/// Get the prefix key in storage.
 	fn prefix() -> &'static [u8];
 
 	/// Get the storage key used to fetch a value corresponding to a specific key.
-	fn key_for(x: &K) -> Vec<u8>;
+	fn key_for<KeyArg: EncodeLike<K> + ?Sized>(x: &KeyArg) -> Vec<u8>;
 
 	/// true if the value is defined in storage.
-	fn exists<S: Storage>(key: &K, storage: &S) -> bool {
+	fn exists<KeyArg: EncodeLike<K> + ?Sized, S: Storage>(key: &KeyArg, storage: &S) -> bool {
 		storage.exists(&Self::key_for(key)[..])
 	}
 
 	/// Load the value associated with the given key from the map.
-	fn get<S: Storage>(key: &K, storage: &S) -> Self::Query;
+	fn get<KeyArg: EncodeLike<K> + ?Sized, S: Storage>(key: &KeyArg, storage: &S) -> Self::Query {
+		Self::QueryKind::from_optional_value_to_query(storage.get(&Self::key_for(key)[..]))
+	}
 
 	/// Take the value under a key.
-	fn take<S: Storage>(key: &K, storage: &S) -> Self::Query;
+	fn take<S: Storage>(key: &K, storage: &S) -> Self::Query {
+		let val = Self::get(key, storage);
+		Self::remove(key, storage);
+		val
+	}
 
 	/// Store a value to be associated with the given key from the map.
 	fn insert<S: Storage>(key: &K, val: &V, storage: &S) {
@@ -184,1286 +841,883 @@ pub trait StorageMap<K: codec::Codec, V: codec::Codec> {
 		storage.kill(&Self::key_for(key)[..]);
 	}
 
-	/// Mutate the value under a key.
-	fn mutate<F: FnOnce(&mut Self::Query), S: Storage>(key: &K, f: F, storage: &S);
-}
+	/// Load the encoded value under a key without decoding it.
+	fn get_raw<KeyArg: EncodeLike<K> + ?Sized, S: Storage>(key: &KeyArg, storage: &S) -> Option<Vec<u8>> {
+		storage.get_raw(&Self::key_for(key)[..])
+	}
 
-// TODO: Remove this in favour of `decl_storage` macro.
-/// Declares strongly-typed wrappers around codec-compatible types in storage.
-#[macro_export]
-macro_rules! storage_items {
-	// simple values
-	($name:ident : $key:expr => $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!(() () (OPTION_TYPE Option<$ty>) (get) (take) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident : $key:expr => $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!((pub) () (OPTION_TYPE Option<$ty>) (get) (take) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	($name:ident : $key:expr => default $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!(() () (RAW_TYPE $ty) (get_or_default) (take_or_default) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident : $key:expr => default $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!((pub) () (RAW_TYPE $ty) (get_or_default) (take_or_default) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	($name:ident : $key:expr => required $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!(() () (RAW_TYPE $ty) (require) (take_or_panic) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident : $key:expr => required $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!((pub) () (RAW_TYPE $ty) (require) (take_or_panic) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
+	/// Store an already encoded value under a key.
+	fn put_raw<S: Storage>(key: &K, value: &[u8], storage: &S) {
+		storage.put_raw(&Self::key_for(key)[..], value)
+	}
 
-	($name:ident get($getfn:ident) : $key:expr => $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!(() ($getfn) (OPTION_TYPE Option<$ty>) (get) (take) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident get($getfn:ident) : $key:expr => $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!((pub) ($getfn) (OPTION_TYPE Option<$ty>) (get) (take) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	($name:ident get($getfn:ident) : $key:expr => default $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!(() ($getfn) (RAW_TYPE $ty) (get_or_default) (take_or_default) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident get($getfn:ident) : $key:expr => default $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!((pub) ($getfn) (RAW_TYPE $ty) (get_or_default) (take_or_default) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	($name:ident get($getfn:ident) : $key:expr => required $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!(() ($getfn) (RAW_TYPE $ty) (require) (take_or_panic) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident get($getfn:ident) : $key:expr => required $ty:ty; $($t:tt)*) => {
-		__storage_items_internal!((pub) ($getfn) (RAW_TYPE $ty) (require) (take_or_panic) $name: $key => $ty);
-		storage_items!($($t)*);
-	};
+	/// Mutate the value under a key, returning the result of `f`.
+	fn mutate<R, F: FnOnce(&mut Self::Query) -> R, S: Storage>(key: &K, f: F, storage: &S) -> R {
+		let mut val = Self::get(key, storage);
 
-	// maps
-	($name:ident : $prefix:expr => map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!(() () (OPTION_TYPE Option<$ty>) (get) (take) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident : $prefix:expr => map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!((pub) () (OPTION_TYPE Option<$ty>) (get) (take) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	($name:ident : $prefix:expr => default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!(() () (RAW_TYPE $ty) (get_or_default) (take_or_default) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident : $prefix:expr => default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!((pub) () (RAW_TYPE $ty) (get_or_default) (take_or_default) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	($name:ident : $prefix:expr => required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!(() () (RAW_TYPE $ty) (require) (take_or_panic) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident : $prefix:expr => required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!((pub) () (RAW_TYPE $ty) (require) (take_or_panic) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
+		let result = f(&mut val);
 
-	($name:ident get($getfn:ident) : $prefix:expr => map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!(() ($getfn) (OPTION_TYPE Option<$ty>) (get) (take) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident get($getfn:ident) : $prefix:expr => map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!((pub) ($getfn) (OPTION_TYPE Option<$ty>) (get) (take) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	($name:ident get($getfn:ident) : $prefix:expr => default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!(() ($getfn) (RAW_TYPE $ty) (get_or_default) (take_or_default) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident get($getfn:ident) : $prefix:expr => default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!((pub) ($getfn) (RAW_TYPE $ty) (get_or_default) (take_or_default) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	($name:ident get($getfn:ident) : $prefix:expr => required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!(() ($getfn) (RAW_TYPE $ty) (require) (take_or_panic) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident get($getfn:ident) : $prefix:expr => required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!((pub) ($getfn) (RAW_TYPE $ty) (require) (take_or_panic) $name: $prefix => map [$kty => $ty]);
-		storage_items!($($t)*);
-	};
+		match Self::QueryKind::from_query_to_optional_value(val) {
+			Some(val) => Self::insert(key, &val, storage),
+			None => Self::remove(key, storage),
+		}
+		result
+	}
 
+	/// Mutate the value under a key, storing the result only if `f` returns `Ok`. The value is
+	/// left untouched if it returns an error, which is passed on.
+	fn try_mutate<R, E, F: FnOnce(&mut Self::Query) -> Result<R, E>, S: Storage>(key: &K, f: F, storage: &S) -> Result<R, E> {
+		let mut val = Self::get(key, storage);
 
-	// lists
-	($name:ident : $prefix:expr => list [$ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!(() $name: $prefix => list [$ty]);
-		storage_items!($($t)*);
-	};
-	(pub $name:ident : $prefix:expr => list [$ty:ty]; $($t:tt)*) => {
-		__storage_items_internal!((pub) $name: $prefix => list [$ty]);
-		storage_items!($($t)*);
-	};
-	() => ()
-}
+		let result = f(&mut val)?;
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __handle_wrap_internal {
-	(RAW_TYPE { $($raw:tt)* } { $($option:tt)* }) => {
-		$($raw)*;
-	};
-	(OPTION_TYPE { $($raw:tt)* } { $($option:tt)* }) => {
-		$($option)*;
-	};
-}
+		match Self::QueryKind::from_query_to_optional_value(val) {
+			Some(val) => Self::insert(key, &val, storage),
+			None => Self::remove(key, storage),
+		}
+		Ok(result)
+	}
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __storage_items_internal {
-	// generator for values.
-	(($($vis:tt)*) ($get_fn:ident) ($wraptype:ident $gettype:ty) ($getter:ident) ($taker:ident) $name:ident : $key:expr => $ty:ty) => {
-		__storage_items_internal!{ ($($vis)*) () ($wraptype $gettype) ($getter) ($taker) $name : $key => $ty }
-		pub fn $get_fn() -> $gettype { <$name as $crate::storage::generator::StorageValue<$ty>> :: get(&$crate::storage::RuntimeStorage) }
-	};
-	(($($vis:tt)*) () ($wraptype:ident $gettype:ty) ($getter:ident) ($taker:ident) $name:ident : $key:expr => $ty:ty) => {
-		$($vis)* struct $name;
+	/// Read the length of the value under a key without decoding it, `None` if it's not in
+	/// storage.
+	fn decode_len<KeyArg: EncodeLike<K> + ?Sized, S: Storage>(key: &KeyArg, storage: &S) -> Option<u32>
+		where V: DecodeLength
+	{
+		decode_len(&Self::key_for(key)[..], storage)
+	}
 
-		impl $crate::storage::generator::StorageValue<$ty> for $name {
-			type Query = $gettype;
+	/// Iterate over all `(key, value)` pairs of the map in the order of their storage keys, by
+	/// walking the keys under `prefix()`. Only maps whose keys are not hashed can be iterated.
+	fn iter_prefix<'a, S: Storage>(storage: &'a S) -> PrefixIterator<'a, S, K, V> {
+		PrefixIterator {
+			storage,
+			prefix: Self::prefix(),
+			previous: Self::prefix().to_vec(),
+			_marker: PhantomData,
+		}
+	}
 
-			/// Get the storage key.
-			fn key() -> &'static [u8] {
-				$key
-			}
+	/// Exchange the values under two keys. If only one of them is in the map, its value is
+	/// moved under the other key.
+	fn swap<S: Storage>(key1: &K, key2: &K, storage: &S) {
+		storage.swap(&Self::key_for(key1)[..], &Self::key_for(key2)[..]);
+	}
 
-			/// Load the value from the provided storage instance.
-			fn get<S: $crate::GenericStorage>(storage: &S) -> Self::Query {
-				storage.$getter($key)
-			}
+	/// Remove all entries of the map. They are found like `iter_prefix` finds them, except for
+	/// linked maps which walk their list of keys.
+	fn remove_all<S: Storage>(storage: &S) {
+		storage.kill_prefix(Self::prefix());
+	}
 
-			/// Take a value from storage, removing it afterwards.
-			fn take<S: $crate::GenericStorage>(storage: &S) -> Self::Query {
-				storage.$taker($key)
+	/// Replace every value of the map, kept in the encoding of `OldV`, with the one `f` maps it
+	/// to, removing the entry if `f` returns `None`. Entries are visited like `iter_prefix` does,
+	/// so only maps whose keys are not hashed can be translated.
+	///
+	/// Returns the number of entries translated. Values which cannot be decoded as an `OldV`
+	/// are left untouched.
+	fn translate_values<OldV: codec::Decode, F: FnMut(OldV) -> Option<V>, S: Storage>(mut f: F, storage: &S) -> u32 {
+		let prefix = Self::prefix();
+		let mut previous = prefix.to_vec();
+		let mut translated = 0;
+		while let Some((key, _)) = next_map_key::<_, K>(storage, prefix, &previous) {
+			let old = storage.get_raw(&key[..]).and_then(|value| OldV::decode(&mut &value[..]));
+			if let Some(old) = old {
+				match f(old) {
+					Some(value) => storage.put(&key[..], &value),
+					None => storage.kill(&key[..]),
+				}
+				translated += 1;
 			}
+			previous = key;
+		}
+		translated
+	}
+}
+
+/// Iterator over the `(key, value)` pairs of a `StorageMap`, in the order of their storage keys.
+pub struct PrefixIterator<'a, S: Storage + 'a, K, V> {
+	storage: &'a S,
+	prefix: &'static [u8],
+	previous: Vec<u8>,
+	_marker: PhantomData<(K, V)>,
+}
+
+impl<'a, S: Storage + 'a, K: codec::Codec, V: codec::Codec> Iterator for PrefixIterator<'a, S, K, V> {
+	type Item = (K, V);
 
-			/// Mutate this value.
-			fn mutate<F: FnOnce(&mut Self::Query), S: $crate::GenericStorage>(f: F, storage: &S) {
-				let mut val = <Self as $crate::storage::generator::StorageValue<$ty>>::get(storage);
-
-				f(&mut val);
-
-				__handle_wrap_internal!($wraptype {
-					// raw type case
-					<Self as $crate::storage::generator::StorageValue<$ty>>::put(&val, storage)
-				} {
-					// Option<> type case
-					match val {
-						Some(val) => <Self as $crate::storage::generator::StorageValue<$ty>>::put(&val, storage),
-						None => <Self as $crate::storage::generator::StorageValue<$ty>>::kill(storage),
-					}
-				});
+	fn next(&mut self) -> Option<(K, V)> {
+		loop {
+			let (key, k) = next_map_key::<_, K>(self.storage, self.prefix, &self.previous)?;
+			self.previous = key;
+			if let Some(v) = self.storage.get(&self.previous[..]) {
+				return Some((k, v));
 			}
 		}
-	};
-	// generator for maps.
-	(($($vis:tt)*) ($get_fn:ident) ($wraptype:ident $gettype:ty) ($getter:ident) ($taker:ident) $name:ident : $prefix:expr => map [$kty:ty => $ty:ty]) => {
-		__storage_items_internal!{ ($($vis)*) () ($wraptype $gettype) ($getter) ($taker) $name : $prefix => map [$kty => $ty] }
-		pub fn $get_fn<K: $crate::storage::generator::Borrow<$kty>>(key: K) -> $gettype {
-			<$name as $crate::storage::generator::StorageMap<$kty, $ty>> :: get(key.borrow(), &$crate::storage::RuntimeStorage)
-		}
-	};
-	(($($vis:tt)*) () ($wraptype:ident $gettype:ty) ($getter:ident) ($taker:ident) $name:ident : $prefix:expr => map [$kty:ty => $ty:ty]) => {
-		$($vis)* struct $name;
+	}
+}
 
-		impl $crate::storage::generator::StorageMap<$kty, $ty> for $name {
-			type Query = $gettype;
+/// The first storage key of the map under `prefix` that is greater than `previous`, along with
+/// the map key it stands for.
+fn next_map_key<S: Storage, K: codec::Codec>(storage: &S, prefix: &[u8], previous: &[u8]) -> Option<(Vec<u8>, K)> {
+	let mut previous = previous.to_vec();
+	loop {
+		let key = storage.next_storage_key(&previous)?;
+		if !key.starts_with(prefix) {
+			return None;
+		}
 
-			/// Get the prefix key in storage.
-			fn prefix() -> &'static [u8] {
-				$prefix
+		// keys of other items may share the prefix; only keys which are exactly the prefix
+		// followed by an encoded `K` belong to the map.
+		let k = {
+			let mut suffix = &key[prefix.len()..];
+			match <K as codec::Decode>::decode(&mut suffix) {
+				Some(k) if suffix.is_empty() => Some(k),
+				_ => None,
 			}
+		};
+		match k {
+			Some(k) => return Some((key, k)),
+			None => previous = key,
+		}
+	}
+}
 
-			/// Get the storage key used to fetch a value corresponding to a specific key.
-			fn key_for(x: &$kty) -> Vec<u8> {
-				let mut key = $prefix.to_vec();
-				$crate::codec::Encode::encode_to(x, &mut key);
-				key
-			}
+/// A strongly-typed map kept in a child storage of its own, so that all of its entries can be
+/// removed at once.
+pub trait StorageChildMap<K: codec::Codec, V: codec::Codec> {
+	/// The type that get/take returns.
+	type Query;
 
-			/// Load the value associated with the given key from the map.
-			fn get<S: $crate::GenericStorage>(key: &$kty, storage: &S) -> Self::Query {
-				let key = <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::key_for(key);
-				storage.$getter(&key[..])
-			}
+	/// Get the key of the child storage.
+	fn child_key() -> &'static [u8];
 
-			/// Take the value, reading and removing it.
-			fn take<S: $crate::GenericStorage>(key: &$kty, storage: &S) -> Self::Query {
-				let key = <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::key_for(key);
-				storage.$taker(&key[..])
-			}
+	/// Get the key of the value corresponding to a specific key in the child storage.
+	fn key_for(x: &K) -> Vec<u8>;
 
-			/// Mutate the value under a key.
-			fn mutate<F: FnOnce(&mut Self::Query), S: $crate::GenericStorage>(key: &$kty, f: F, storage: &S) {
-				let mut val = <Self as $crate::storage::generator::StorageMap<$kty, $ty>>::take(key, storage);
-
-				f(&mut val);
-
-				__handle_wrap_internal!($wraptype {
-					// raw type case
-					<Self as $crate::storage::generator::StorageMap<$kty, $ty>>::insert(key, &val, storage)
-				} {
-					// Option<> type case
-					match val {
-						Some(val) => <Self as $crate::storage::generator::StorageMap<$kty, $ty>>::insert(key, &val, storage),
-						None => <Self as $crate::storage::generator::StorageMap<$kty, $ty>>::remove(key, storage),
-					}
-				});
-			}
-		}
-	};
-	// generator for lists.
-	(($($vis:tt)*) $name:ident : $prefix:expr => list [$ty:ty]) => {
-		$($vis)* struct $name;
+	/// true if the value is defined in storage.
+	fn exists<S: Storage>(key: &K, storage: &S) -> bool {
+		storage.child_exists(Self::child_key(), &Self::key_for(key)[..])
+	}
 
-		impl $name {
-			fn clear_item<S: $crate::GenericStorage>(index: u32, storage: &S) {
-				if index < <$name as $crate::storage::generator::StorageList<$ty>>::len(storage) {
-					storage.kill(&<$name as $crate::storage::generator::StorageList<$ty>>::key_for(index));
-				}
-			}
+	/// Load the value associated with the given key from the map.
+	fn get<S: Storage>(key: &K, storage: &S) -> Self::Query;
 
-			fn set_len<S: $crate::GenericStorage>(count: u32, storage: &S) {
-				(count..<$name as $crate::storage::generator::StorageList<$ty>>::len(storage)).for_each(|i| $name::clear_item(i, storage));
-				storage.put(&<$name as $crate::storage::generator::StorageList<$ty>>::len_key(), &count);
-			}
-		}
+	/// Take the value under a key.
+	fn take<S: Storage>(key: &K, storage: &S) -> Self::Query;
 
-		impl $crate::storage::generator::StorageList<$ty> for $name {
-			/// Get the prefix key in storage.
-			fn prefix() -> &'static [u8] {
-				$prefix
-			}
+	/// Store a value to be associated with the given key from the map.
+	fn insert<S: Storage>(key: &K, val: &V, storage: &S) {
+		storage.child_put(Self::child_key(), &Self::key_for(key)[..], val);
+	}
 
-			/// Get the key used to put the length field.
-			// TODO: concat macro should accept byte literals.
-			fn len_key() -> Vec<u8> {
-				let mut key = $prefix.to_vec();
-				key.extend(b"len");
-				key
-			}
+	/// Remove the value under a key.
+	fn remove<S: Storage>(key: &K, storage: &S) {
+		storage.child_kill(Self::child_key(), &Self::key_for(key)[..]);
+	}
 
-			/// Get the storage key used to fetch a value at a given index.
-			fn key_for(index: u32) -> Vec<u8> {
-				let mut key = $prefix.to_vec();
-				$crate::codec::Encode::encode_to(&index, &mut key);
-				key
-			}
+	/// Mutate the value under a key, returning the result of `f`.
+	fn mutate<R, F: FnOnce(&mut Self::Query) -> R, S: Storage>(key: &K, f: F, storage: &S) -> R;
 
-			/// Read out all the items.
-			fn items<S: $crate::GenericStorage>(storage: &S) -> Vec<$ty> {
-				(0..<$name as $crate::storage::generator::StorageList<$ty>>::len(storage))
-					.map(|i| <$name as $crate::storage::generator::StorageList<$ty>>::get(i, storage).expect("all items within length are set; qed"))
-					.collect()
-			}
+	/// Remove all entries of the map, killing its child storage.
+	fn remove_all<S: Storage>(storage: &S) {
+		storage.child_kill_all(Self::child_key());
+	}
+}
 
-			/// Set the current set of items.
-			fn set_items<S: $crate::GenericStorage>(items: &[$ty], storage: &S) {
-				$name::set_len(items.len() as u32, storage);
-				items.iter()
-					.enumerate()
-					.for_each(|(i, item)| <$name as $crate::storage::generator::StorageList<$ty>>::set_item(i as u32, item, storage));
-			}
+/// The neighbours of a key in the list threading the keys of an `EnumerableStorageMap`.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Linkage<K> {
+	/// The key inserted after this one, `None` if this is the head of the list.
+	pub previous: Option<K>,
+	/// The key inserted before this one, `None` if this is the tail of the list.
+	pub next: Option<K>,
+}
 
-			fn set_item<S: $crate::GenericStorage>(index: u32, item: &$ty, storage: &S) {
-				if index < <$name as $crate::storage::generator::StorageList<$ty>>::len(storage) {
-					storage.put(&<$name as $crate::storage::generator::StorageList<$ty>>::key_for(index)[..], item);
-				}
-			}
+impl<K: codec::Encode> codec::Encode for Linkage<K> {
+	fn encode_to<W: codec::Output>(&self, dest: &mut W) {
+		codec::Encode::encode_to(&self.previous, dest);
+		codec::Encode::encode_to(&self.next, dest);
+	}
+}
 
-			/// Load the value at given index. Returns `None` if the index is out-of-bounds.
-			fn get<S: $crate::GenericStorage>(index: u32, storage: &S) -> Option<$ty> {
-				storage.get(&<$name as $crate::storage::generator::StorageList<$ty>>::key_for(index)[..])
-			}
+impl<K: codec::Decode> codec::Decode for Linkage<K> {
+	fn decode<I: codec::Input>(input: &mut I) -> Option<Self> {
+		Some(Linkage {
+			previous: codec::Decode::decode(input)?,
+			next: codec::Decode::decode(input)?,
+		})
+	}
+}
 
-			/// Load the length of the list.
-			fn len<S: $crate::GenericStorage>(storage: &S) -> u32 {
-				storage.get(&<$name as $crate::storage::generator::StorageList<$ty>>::len_key()).unwrap_or_default()
-			}
+/// A strongly-typed map in storage whose keys are threaded into a doubly-linked list, so that its
+/// entries can be enumerated.
+pub trait EnumerableStorageMap<K: codec::Codec + Clone, V: codec::Codec>: StorageMap<K, V> {
+	/// Get the storage key of the most recently inserted key.
+	fn head_key() -> &'static [u8];
 
-			/// Clear the list.
-			fn clear<S: $crate::GenericStorage>(storage: &S) {
-				for i in 0..<$name as $crate::storage::generator::StorageList<$ty>>::len(storage) {
-					$name::clear_item(i, storage);
-				}
+	/// Get the storage key holding the linkage of a specific key.
+	fn linkage_key_for(x: &K) -> Vec<u8>;
 
-				storage.kill(&<$name as $crate::storage::generator::StorageList<$ty>>::len_key()[..])
-			}
+	/// The most recently inserted key, `None` if the map is empty.
+	fn head<S: Storage>(storage: &S) -> Option<K> {
+		storage.get(Self::head_key())
+	}
+
+	/// Iterate over all `(key, value)` pairs of the map, most recently inserted first.
+	fn enumerate<'a, S: Storage>(storage: &'a S) -> Enumerator<'a, S, K, V, Self> where Self: Sized {
+		Enumerator {
+			storage,
+			next: Self::head(storage),
+			_marker: PhantomData,
 		}
-	};
-}
+	}
 
-// TODO: revisit this idiom once we get `type`s in `impl`s.
-/*impl<T: Trait> Module<T> {
-	type Now = super::Now<T>;
-}*/
+	/// Iterate over all keys of the map, most recently inserted first, without reading the values.
+	fn iter_keys<'a, S: Storage>(storage: &'a S) -> KeyEnumerator<'a, S, K, V, Self> where Self: Sized {
+		KeyEnumerator {
+			storage,
+			next: Self::head(storage),
+			_marker: PhantomData,
+		}
+	}
 
-/// Declares strongly-typed wrappers around codec-compatible types in storage.
-///
-/// For now we implement a convenience trait with pre-specialised associated types, one for each
-/// storage item. This allows you to gain access to publicly visisible storage items from a
-/// module type. Currently you must disambiguate by using `<Module as Store>::Item` rather than
-/// the simpler `Module::Item`. Hopefully the rust guys with fix this soon.
-#[macro_export]
-macro_rules! decl_storage {
-	(
-		trait $storetype:ident for $modulename:ident<$traitinstance:ident: $traittype:ident> as $cratename:ident {
-			$($t:tt)*
+	/// Iterate over all `(key, value)` pairs of the map, most recently inserted first, removing
+	/// each of them as it is visited. Entries not visited are left in the map.
+	fn drain<'a, S: Storage>(storage: &'a S) -> Drainer<'a, S, K, V, Self> where Self: Sized {
+		Drainer {
+			storage,
+			_marker: PhantomData,
 		}
-	) => {
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
-		trait $storetype {
-			__decl_store_items!($($t)*);
+	}
+
+	/// Add a key to the head of the list, if it is not in the list already.
+	fn link<S: Storage>(key: &K, storage: &S) {
+		let linkage_key = Self::linkage_key_for(key);
+		if storage.exists(&linkage_key[..]) {
+			return;
 		}
-		impl<$traitinstance: $traittype> $storetype for $modulename<$traitinstance> {
-			__impl_store_items!($traitinstance $($t)*);
+
+		let head = Self::head(storage);
+		if let Some(ref head) = head {
+			let head_linkage_key = Self::linkage_key_for(head);
+			let mut head_linkage: Linkage<K> = storage.require(&head_linkage_key[..]);
+			head_linkage.previous = Some(key.clone());
+			storage.put(&head_linkage_key[..], &head_linkage);
 		}
-		impl<$traitinstance: $traittype> $modulename<$traitinstance> {
-			__impl_store_fns!($traitinstance $($t)*);
-			__impl_store_json_metadata!($cratename; $($t)*);
+		storage.put(&linkage_key[..], &Linkage { previous: None, next: head });
+		storage.put(Self::head_key(), key);
+	}
+
+	/// Remove a key from the list, joining its neighbours.
+	fn unlink<S: Storage>(key: &K, storage: &S) {
+		let linkage: Linkage<K> = match storage.take(&Self::linkage_key_for(key)[..]) {
+			Some(linkage) => linkage,
+			None => return,
+		};
+
+		match linkage.previous {
+			Some(ref previous) => {
+				let previous_linkage_key = Self::linkage_key_for(previous);
+				let mut previous_linkage: Linkage<K> = storage.require(&previous_linkage_key[..]);
+				previous_linkage.next = linkage.next.clone();
+				storage.put(&previous_linkage_key[..], &previous_linkage);
+			},
+			None => match linkage.next {
+				Some(ref next) => storage.put(Self::head_key(), next),
+				None => storage.kill(Self::head_key()),
+			},
 		}
-	};
-	(
-		pub trait $storetype:ident for $modulename:ident<$traitinstance:ident: $traittype:ident> as $cratename:ident {
-			$($t:tt)*
+		if let Some(ref next) = linkage.next {
+			let next_linkage_key = Self::linkage_key_for(next);
+			let mut next_linkage: Linkage<K> = storage.require(&next_linkage_key[..]);
+			next_linkage.previous = linkage.previous;
+			storage.put(&next_linkage_key[..], &next_linkage);
 		}
-	) => {
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
-		pub trait $storetype {
-			__decl_store_items!($($t)*);
+	}
+}
+
+/// Iterator over the `(key, value)` pairs of an `EnumerableStorageMap`.
+pub struct Enumerator<'a, S: Storage + 'a, K, V, M> {
+	storage: &'a S,
+	next: Option<K>,
+	_marker: PhantomData<(V, M)>,
+}
+
+impl<'a, S, K, V, M> Iterator for Enumerator<'a, S, K, V, M> where
+	S: Storage + 'a,
+	K: codec::Codec + Clone,
+	V: codec::Codec,
+	M: EnumerableStorageMap<K, V>,
+{
+	type Item = (K, V);
+
+	fn next(&mut self) -> Option<(K, V)> {
+		let key = self.next.take()?;
+		let linkage: Linkage<K> = self.storage.require(&M::linkage_key_for(&key)[..]);
+		let value = self.storage.require(&M::key_for(&key)[..]);
+		self.next = linkage.next;
+		Some((key, value))
+	}
+}
+
+/// Iterator over the keys of an `EnumerableStorageMap`.
+pub struct KeyEnumerator<'a, S: Storage + 'a, K, V, M> {
+	storage: &'a S,
+	next: Option<K>,
+	_marker: PhantomData<(V, M)>,
+}
+
+impl<'a, S, K, V, M> Iterator for KeyEnumerator<'a, S, K, V, M> where
+	S: Storage + 'a,
+	K: codec::Codec + Clone,
+	V: codec::Codec,
+	M: EnumerableStorageMap<K, V>,
+{
+	type Item = K;
+
+	fn next(&mut self) -> Option<K> {
+		let key = self.next.take()?;
+		let linkage: Linkage<K> = self.storage.require(&M::linkage_key_for(&key)[..]);
+		self.next = linkage.next;
+		Some(key)
+	}
+}
+
+/// Iterator over the `(key, value)` pairs of an `EnumerableStorageMap` removing each of them,
+/// returned by `EnumerableStorageMap::drain`.
+pub struct Drainer<'a, S: Storage + 'a, K, V, M> {
+	storage: &'a S,
+	_marker: PhantomData<(K, V, M)>,
+}
+
+impl<'a, S, K, V, M> Iterator for Drainer<'a, S, K, V, M> where
+	S: Storage + 'a,
+	K: codec::Codec + Clone,
+	V: codec::Codec,
+	M: EnumerableStorageMap<K, V>,
+{
+	type Item = (K, V);
+
+	fn next(&mut self) -> Option<(K, V)> {
+		// The visited entries are removed, so the next one is always at the head of the list.
+		let key = M::head(self.storage)?;
+		let value = self.storage.require(&M::key_for(&key)[..]);
+		M::remove(&key, self.storage);
+		Some((key, value))
+	}
+}
+
+/// A strongly-typed map in storage which keeps count of its entries.
+pub trait CountedStorageMap<K: codec::Codec, V: codec::Codec>: StorageMap<K, V> {
+	/// Get the storage key holding the number of entries.
+	fn count_key() -> &'static [u8];
+
+	/// The number of entries in the map.
+	fn count<S: Storage>(storage: &S) -> u32 {
+		storage.get_or_default(Self::count_key())
+	}
+
+	/// Count a key about to be inserted, unless it is in the map already.
+	fn note_insert<S: Storage>(key: &K, storage: &S) {
+		if !Self::exists(key, storage) {
+			let count = Self::count(storage).checked_add(1).expect("exceeded runtime storage capacity");
+			storage.put(Self::count_key(), &count);
 		}
-		impl<$traitinstance: $traittype> $storetype for $modulename<$traitinstance> {
-			__impl_store_items!($traitinstance $($t)*);
+	}
+
+	/// Stop counting a key about to be removed, if it is in the map.
+	fn note_remove<S: Storage>(key: &K, storage: &S) {
+		if Self::exists(key, storage) {
+			match Self::count(storage).saturating_sub(1) {
+				0 => storage.kill(Self::count_key()),
+				count => storage.put(Self::count_key(), &count),
+			}
 		}
-		impl<$traitinstance: $traittype> $modulename<$traitinstance> {
-			__impl_store_fns!($traitinstance $($t)*);
+	}
+}
+
+/// Panics if two of the named storage keys collide: if they are equal, or if one starts with the
+/// other, as the entries under the longer key would then be read and removed through the shorter
+/// one.
+#[doc(hidden)]
+pub fn assert_distinct_keys(keys: &[(&str, &[u8])]) {
+	for (i, &(name, key)) in keys.iter().enumerate() {
+		for &(other_name, other_key) in &keys[..i] {
+			if key.starts_with(other_key) || other_key.starts_with(key) {
+				panic!("the storage key of `{}` collides with the one of `{}`", name, other_name);
+			}
 		}
 	}
 }
 
+/// Moves the value of the item `Old` to the key of the item `New`, e.g. from an item of
+/// `storage_items!` to its counterpart in `decl_storage!`. The value is moved as it is stored,
+/// without being decoded. Returns whether there was a value to move.
+pub fn translate_value<T: codec::Codec, Old: StorageValue<T>, New: StorageValue<T>, S: Storage>(storage: &S) -> bool {
+	match storage.get_raw(Old::key()) {
+		Some(value) => {
+			storage.kill(Old::key());
+			storage.put_raw(New::key(), &value);
+			true
+		},
+		None => false,
+	}
+}
+
+/// Moves the value under `key` of the map `Old` to the same key of the map `New`, e.g. from an
+/// item of `storage_items!` to its counterpart in `decl_storage!`. The value is moved as it is
+/// stored, without being decoded. Returns whether there was a value to move.
+///
+/// This does not maintain the bookkeeping of linked or counted maps, which must be moved with
+/// their own `insert`.
+pub fn translate_map_entry<K: codec::Codec, V: codec::Codec, Old: StorageMap<K, V>, New: StorageMap<K, V>, S: Storage>(
+	key: &K,
+	storage: &S,
+) -> bool {
+	let old_key = Old::key_for(key);
+	match storage.get_raw(&old_key) {
+		Some(value) => {
+			storage.kill(&old_key);
+			storage.put_raw(&New::key_for(key), &value);
+			true
+		},
+		None => false,
+	}
+}
+
+/// Declares strongly-typed wrappers around codec-compatible types in storage.
+///
+/// Items are kept under the keys they are given, which are not hashed. Each block generates a
+/// `storage_items_keys_are_distinct` test failing if a key or prefix of an item starts with the
+/// one of another, so a module holds at most one block.
+///
+/// Each block also generates a `storage_items_json_metadata` function describing its items like
+/// `store_json_metadata` of `decl_storage!` does, with an empty prefix as the keys are given
+/// explicitly, so the module can be listed in the runtime metadata before it is moved to
+/// `decl_storage!`.
+///
+/// This macro is deprecated in favour of `decl_storage!`. A module can move one item at a time:
+/// declare the item in `decl_storage!` and move the stored values over from the old key with
+/// `translate_value` or, entry by entry, `translate_map_entry`.
 #[macro_export]
-#[doc(hidden)]
-macro_rules! __decl_storage_items {
+macro_rules! storage_items {
 	// simple values
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident : $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) () (OPTION_TYPE Option<$ty>) (get) (take) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident : $key:expr => $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!(() () (OptionQuery GetDefault Option<$ty>) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident : $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) () (OPTION_TYPE Option<$ty>) (get) (take) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident : $key:expr => $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!((pub) () (OptionQuery GetDefault Option<$ty>) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident : default $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) () (RAW_TYPE $ty) (get_or_default) (take_or_default) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident : $key:expr => default $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!(() () (ValueQuery GetDefault $ty) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": "default", "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident : default $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) () (RAW_TYPE $ty) (get_or_default) (take_or_default) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident : $key:expr => default $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!((pub) () (ValueQuery GetDefault $ty) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": "default", "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident : required $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) () (RAW_TYPE $ty) (require) (take_or_panic) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident : $key:expr => required $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!(() () (ValueQuery PanicOnEmpty $ty) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": "required", "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident : required $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) () (RAW_TYPE $ty) (require) (take_or_panic) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident : $key:expr => required $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!((pub) () (ValueQuery PanicOnEmpty $ty) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": "required", "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
 
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) ($getfn) (OPTION_TYPE Option<$ty>) (get) (take) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident get($getfn:ident) : $key:expr => $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!(() ($getfn) (OptionQuery GetDefault Option<$ty>) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) ($getfn) (OPTION_TYPE Option<$ty>) (get) (take) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident get($getfn:ident) : $key:expr => $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!((pub) ($getfn) (OptionQuery GetDefault Option<$ty>) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : default $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) ($getfn) (RAW_TYPE $ty) (get_or_default) (take_or_default) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident get($getfn:ident) : $key:expr => default $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!(() ($getfn) (ValueQuery GetDefault $ty) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": "default", "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : default $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) ($getfn) (RAW_TYPE $ty) (get_or_default) (take_or_default) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident get($getfn:ident) : $key:expr => default $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!((pub) ($getfn) (ValueQuery GetDefault $ty) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": "default", "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : required $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) ($getfn) (RAW_TYPE $ty) (require) (take_or_panic) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident get($getfn:ident) : $key:expr => required $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!(() ($getfn) (ValueQuery PanicOnEmpty $ty) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": "required", "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : required $ty:ty; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) ($getfn) (RAW_TYPE $ty) (require) (take_or_panic) $cratename $name: $ty);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident get($getfn:ident) : $key:expr => required $ty:ty; $($t:tt)*) => {
+		__storage_items_internal!((pub) ($getfn) (ValueQuery PanicOnEmpty $ty) $name: $key => $ty);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageValue<$ty>>::key(), concat!(r#"{ "description": [ ], "modifier": "required", "type": "#, concat!(r#"""#, stringify!($ty), r#"""#), " }"))] $($t)*);
 	};
 
 	// maps
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) () (OPTION_TYPE Option<$ty>) (get) (take) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident : $prefix:expr => map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!(() () (OptionQuery GetDefault Option<$ty>) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) () (OPTION_TYPE Option<$ty>) (get) (take) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident : $prefix:expr => map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!((pub) () (OptionQuery GetDefault Option<$ty>) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) () (RAW_TYPE $ty) (get_or_default) (take_or_default) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident : $prefix:expr => default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!(() () (ValueQuery GetDefault $ty) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": "default", "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) () (RAW_TYPE $ty) (get_or_default) (take_or_default) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident : $prefix:expr => default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!((pub) () (ValueQuery GetDefault $ty) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": "default", "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) () (RAW_TYPE $ty) (require) (take_or_panic) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident : $prefix:expr => required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!(() () (ValueQuery PanicOnEmpty $ty) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": "required", "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) () (RAW_TYPE $ty) (require) (take_or_panic) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident : $prefix:expr => required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!((pub) () (ValueQuery PanicOnEmpty $ty) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": "required", "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
 	};
 
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) ($getfn) (OPTION_TYPE Option<$ty>) (get) (take) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] $name:ident get($getfn:ident) : $prefix:expr => map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!(() ($getfn) (OptionQuery GetDefault Option<$ty>) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) ($getfn) (OPTION_TYPE Option<$ty>) (get) (take) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident get($getfn:ident) : $prefix:expr => map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!((pub) ($getfn) (OptionQuery GetDefault Option<$ty>) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
+	};
+	(@keys [$($keys:tt)*] $name:ident get($getfn:ident) : $prefix:expr => default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!(() ($getfn) (ValueQuery GetDefault $ty) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": "default", "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
+	};
+	(@keys [$($keys:tt)*] pub $name:ident get($getfn:ident) : $prefix:expr => default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!((pub) ($getfn) (ValueQuery GetDefault $ty) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": "default", "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
+	};
+	(@keys [$($keys:tt)*] $name:ident get($getfn:ident) : $prefix:expr => required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!(() ($getfn) (ValueQuery PanicOnEmpty $ty) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": "required", "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
+	};
+	(@keys [$($keys:tt)*] pub $name:ident get($getfn:ident) : $prefix:expr => required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!((pub) ($getfn) (ValueQuery PanicOnEmpty $ty) $name: $prefix => map [$kty => $ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": "required", "type": "#, concat!(r#"{ "key": ""#, stringify!($kty), r#"", "value": ""#, stringify!($ty), r#"", "hasher": "identity", "kind": "map" }"#), " }"))] $($t)*);
+	};
+
+
+	// lists
+	(@keys [$($keys:tt)*] $name:ident : $prefix:expr => list [$ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!(() $name: $prefix => list [$ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageVec<$ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"{ "value": ""#, stringify!($ty), r#"", "kind": "list" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) ($getfn) (RAW_TYPE $ty) (get_or_default) (take_or_default) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident : $prefix:expr => list [$ty:ty]; $($t:tt)*) => {
+		__storage_items_internal!((pub) $name: $prefix => list [$ty]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageVec<$ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"{ "value": ""#, stringify!($ty), r#"", "kind": "list" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) ($getfn) (RAW_TYPE $ty) (get_or_default) (take_or_default) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+
+	// deques
+	(@keys [$($keys:tt)*] $name:ident : $prefix:expr => deque [$ty:ty; $capacity:expr]; $($t:tt)*) => {
+		__storage_items_internal!(() $name: $prefix => deque [$ty; $capacity]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageDeque<$ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"{ "value": ""#, stringify!($ty), r#"", "kind": "deque" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!(() ($traittype as $traitinstance) ($getfn) (RAW_TYPE $ty) (require) (take_or_panic) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys [$($keys:tt)*] pub $name:ident : $prefix:expr => deque [$ty:ty; $capacity:expr]; $($t:tt)*) => {
+		__storage_items_internal!((pub) $name: $prefix => deque [$ty; $capacity]);
+		storage_items!(@keys [$($keys)* ($name, <$name as $crate::storage::generator::StorageDeque<$ty>>::prefix(), concat!(r#"{ "description": [ ], "modifier": null, "type": "#, concat!(r#"{ "value": ""#, stringify!($ty), r#"", "kind": "deque" }"#), " }"))] $($t)*);
 	};
-	($cratename:ident $traittype:ident $traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_storage_item!((pub) ($traittype as $traitinstance) ($getfn) (RAW_TYPE $ty) (require) (take_or_panic) $cratename $name: map [$kty => $ty]);
-		__decl_storage_items!($cratename $traittype $traitinstance $($t)*);
+	(@keys []) => {
+		/// The JSON metadata of the items declared by `storage_items!`.
+		#[allow(dead_code)]
+		pub fn storage_items_json_metadata() -> &'static str {
+			r#"{ "prefix": "", "prefixHasher": "identity", "items": { } }"#
+		}
 	};
+	(@keys [($first:ident, $first_key:expr, $first_meta:expr) $(($name:ident, $key:expr, $meta:expr))*]) => {
+		/// The JSON metadata of the items declared by `storage_items!`.
+		#[allow(dead_code)]
+		pub fn storage_items_json_metadata() -> &'static str {
+			concat!(
+				r#"{ "prefix": "", "prefixHasher": "identity", "items": { ""#, stringify!($first), r#"": "#, $first_meta,
+				$( r#", ""#, stringify!($name), r#"": "#, $meta, )*
+				" } }"
+			)
+		}
 
-	// exit
-	($cratename:ident $traittype:ident $traitinstance:ident) => ()
+		#[cfg(test)]
+		#[test]
+		fn storage_items_keys_are_distinct() {
+			$crate::storage::generator::assert_distinct_keys(&[
+				(stringify!($first), $first_key),
+				$((stringify!($name), $key),)*
+			]);
+		}
+	};
+	($($t:tt)*) => {
+		storage_items!(@keys [] $($t)*);
+	};
 }
 
 #[macro_export]
 #[doc(hidden)]
-macro_rules! __decl_storage_item {
+macro_rules! __storage_items_internal {
 	// generator for values.
-	(($($vis:tt)*) ($traittype:ident as $traitinstance:ident) ($get_fn:ident) ($wraptype:ident $gettype:ty) ($getter:ident) ($taker:ident) $cratename:ident $name:ident : $ty:ty) => {
-		__decl_storage_item!{ ($($vis)*) ($traittype as $traitinstance) () ($wraptype $gettype) ($getter) ($taker) $cratename $name : $ty }
+	(($($vis:tt)*) ($get_fn:ident) ($kind:ident $on_empty:ident $gettype:ty) $name:ident : $key:expr => $ty:ty) => {
+		__storage_items_internal!{ ($($vis)*) () ($kind $on_empty $gettype) $name : $key => $ty }
+		pub fn $get_fn() -> $gettype { <$name as $crate::storage::generator::StorageValue<$ty>> :: get(&$crate::storage::RuntimeStorage) }
 	};
-	(($($vis:tt)*) ($traittype:ident as $traitinstance:ident) () ($wraptype:ident $gettype:ty) ($getter:ident) ($taker:ident) $cratename:ident $name:ident : $ty:ty) => {
-		$($vis)* struct $name<$traitinstance: $traittype>($crate::storage::generator::PhantomData<$traitinstance>);
+	(($($vis:tt)*) () ($kind:ident $on_empty:ident $gettype:ty) $name:ident : $key:expr => $ty:ty) => {
+		$($vis)* struct $name;
 
-		impl<$traitinstance: $traittype> $crate::storage::generator::StorageValue<$ty> for $name<$traitinstance> {
+		impl $crate::storage::generator::StorageValue<$ty> for $name {
 			type Query = $gettype;
+			type QueryKind = $crate::storage::generator::$kind;
+			type OnEmpty = $crate::storage::generator::$on_empty;
 
 			/// Get the storage key.
 			fn key() -> &'static [u8] {
-				stringify!($cratename $name).as_bytes()
-			}
-
-			/// Load the value from the provided storage instance.
-			fn get<S: $crate::GenericStorage>(storage: &S) -> Self::Query {
-				storage.$getter(<$name<$traitinstance> as $crate::storage::generator::StorageValue<$ty>>::key())
-			}
-
-			/// Take a value from storage, removing it afterwards.
-			fn take<S: $crate::GenericStorage>(storage: &S) -> Self::Query {
-				storage.$taker(<$name<$traitinstance> as $crate::storage::generator::StorageValue<$ty>>::key())
-			}
-
-			/// Mutate the value under a key.
-			fn mutate<F: FnOnce(&mut Self::Query), S: $crate::GenericStorage>(f: F, storage: &S) {
-				let mut val = <Self as $crate::storage::generator::StorageValue<$ty>>::get(storage);
-
-				f(&mut val);
-
-				__handle_wrap_internal!($wraptype {
-					// raw type case
-					<Self as $crate::storage::generator::StorageValue<$ty>>::put(&val, storage)
-				} {
-					// Option<> type case
-					match val {
-						Some(val) => <Self as $crate::storage::generator::StorageValue<$ty>>::put(&val, storage),
-						None => <Self as $crate::storage::generator::StorageValue<$ty>>::kill(storage),
-					}
-				})
+				$key
 			}
 		}
 	};
 	// generator for maps.
-	(($($vis:tt)*) ($traittype:ident as $traitinstance:ident) ($get_fn:ident) ($wraptype:ident $gettype:ty) ($getter:ident) ($taker:ident) $cratename:ident $name:ident : map [$kty:ty => $ty:ty]) => {
-		__decl_storage_item!{ ($($vis)*) ($traittype as $traitinstance) () ($wraptype $gettype) ($getter) ($taker) $cratename $name : map [$kty => $ty] }
+	(($($vis:tt)*) ($get_fn:ident) ($kind:ident $on_empty:ident $gettype:ty) $name:ident : $prefix:expr => map [$kty:ty => $ty:ty]) => {
+		__storage_items_internal!{ ($($vis)*) () ($kind $on_empty $gettype) $name : $prefix => map [$kty => $ty] }
+		pub fn $get_fn<K: $crate::storage::generator::EncodeLike<$kty>>(key: K) -> $gettype {
+			<$name as $crate::storage::generator::StorageMap<$kty, $ty>> :: get(&key, &$crate::storage::RuntimeStorage)
+		}
 	};
-	(($($vis:tt)*) ($traittype:ident as $traitinstance:ident) () ($wraptype:ident $gettype:ty) ($getter:ident) ($taker:ident) $cratename:ident $name:ident : map [$kty:ty => $ty:ty]) => {
-		$($vis)* struct $name<$traitinstance: $traittype>($crate::storage::generator::PhantomData<$traitinstance>);
+	(($($vis:tt)*) () ($kind:ident $on_empty:ident $gettype:ty) $name:ident : $prefix:expr => map [$kty:ty => $ty:ty]) => {
+		$($vis)* struct $name;
 
-		impl<$traitinstance: $traittype> $crate::storage::generator::StorageMap<$kty, $ty> for $name<$traitinstance> {
+		impl $crate::storage::generator::StorageMap<$kty, $ty> for $name {
 			type Query = $gettype;
+			type QueryKind = $crate::storage::generator::$kind;
+			type OnEmpty = $crate::storage::generator::$on_empty;
 
 			/// Get the prefix key in storage.
 			fn prefix() -> &'static [u8] {
-				stringify!($cratename $name).as_bytes()
+				$prefix
 			}
 
 			/// Get the storage key used to fetch a value corresponding to a specific key.
-			fn key_for(x: &$kty) -> Vec<u8> {
-				let mut key = <$name<$traitinstance> as $crate::storage::generator::StorageMap<$kty, $ty>>::prefix().to_vec();
+			fn key_for<KeyArg: $crate::storage::generator::EncodeLike<$kty> + ?Sized>(x: &KeyArg) -> Vec<u8> {
+				let mut key = $prefix.to_vec();
 				$crate::codec::Encode::encode_to(x, &mut key);
 				key
 			}
+		}
+	};
+	// generator for lists.
+	(($($vis:tt)*) $name:ident : $prefix:expr => list [$ty:ty]) => {
+		$($vis)* struct $name;
 
-			/// Load the value associated with the given key from the map.
-			fn get<S: $crate::GenericStorage>(key: &$kty, storage: &S) -> Self::Query {
-				let key = <$name<$traitinstance> as $crate::storage::generator::StorageMap<$kty, $ty>>::key_for(key);
-				storage.$getter(&key[..])
+		impl $crate::storage::generator::StorageVec<$ty> for $name {
+			/// Get the prefix key in storage.
+			fn prefix() -> &'static [u8] {
+				$prefix
 			}
 
-			/// Take the value, reading and removing it.
-			fn take<S: $crate::GenericStorage>(key: &$kty, storage: &S) -> Self::Query {
-				let key = <$name<$traitinstance> as $crate::storage::generator::StorageMap<$kty, $ty>>::key_for(key);
-				storage.$taker(&key[..])
+			/// Get the key used to put the length field.
+			// TODO: concat macro should accept byte literals.
+			fn len_key() -> Vec<u8> {
+				let mut key = $prefix.to_vec();
+				key.extend(b"len");
+				key
 			}
 
-			/// Mutate the value under a key
-			fn mutate<F: FnOnce(&mut Self::Query), S: $crate::GenericStorage>(key: &$kty, f: F, storage: &S) {
-				let mut val = <Self as $crate::storage::generator::StorageMap<$kty, $ty>>::take(key, storage);
-
-				f(&mut val);
-
-				__handle_wrap_internal!($wraptype {
-					<Self as $crate::storage::generator::StorageMap<$kty, $ty>>::insert(key, &val, storage);
-				} {
-					match val {
-						Some(val) => <Self as $crate::storage::generator::StorageMap<$kty, $ty>>::insert(key, &val, storage),
-						None => <Self as $crate::storage::generator::StorageMap<$kty, $ty>>::remove(key, storage),
-					}
-				});
+			/// Get the storage key used to fetch a value at a given index.
+			fn key_for(index: u32) -> Vec<u8> {
+				let mut key = $prefix.to_vec();
+				$crate::codec::Encode::encode_to(&index, &mut key);
+				key
 			}
 		}
 	};
-}
+	// generator for deques.
+	(($($vis:tt)*) $name:ident : $prefix:expr => deque [$ty:ty; $capacity:expr]) => {
+		$($vis)* struct $name;
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __decl_store_items {
-	// simple values
-	($(#[$doc:meta])* $name:ident : default $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident : default $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* $name:ident : required $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident : required $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* $name:ident : $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident : $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
+		impl $crate::storage::generator::StorageDeque<$ty> for $name {
+			/// Get the prefix key in storage.
+			fn prefix() -> &'static [u8] {
+				$prefix
+			}
 
-	($(#[$doc:meta])* $name:ident get($getfn:ident) : default $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident get($getfn:ident) : default $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* $name:ident get($getfn:ident) : required $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident get($getfn:ident) : required $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* $name:ident get($getfn:ident) : $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident get($getfn:ident) : $ty:ty; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
+			/// Get the storage key used to fetch the item at a given position.
+			fn key_for(position: u32) -> Vec<u8> {
+				let mut key = $prefix.to_vec();
+				$crate::codec::Encode::encode_to(&position, &mut key);
+				key
+			}
 
-	// maps
-	($(#[$doc:meta])* $name:ident : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* $name:ident : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* $name:ident : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
+			/// The maximum number of items.
+			fn capacity() -> u32 {
+				$capacity
+			}
+		}
 	};
+}
 
-	($(#[$doc:meta])* $name:ident get($getfn:ident) : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident get($getfn:ident) : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* $name:ident get($getfn:ident) : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident get($getfn:ident) : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* $name:ident get($getfn:ident) : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-	($(#[$doc:meta])* pub $name:ident get($getfn:ident) : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__decl_store_item!($name); __decl_store_items!($($t)*);
-	};
-
-	// exit
-	() => ()
-}
-
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __decl_store_item {
-	($name:ident) => { type $name; }
-}
-
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __impl_store_fns {
-	// simple values
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : default $ty:ty; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : default $ty:ty; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : required $ty:ty; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : required $ty:ty; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : $ty:ty; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : $ty:ty; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : default $ty:ty; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn ($ty) $ty);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : default $ty:ty; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn ($ty) $ty);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : required $ty:ty; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn ($ty) $ty);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : required $ty:ty; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn ($ty) $ty);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : $ty:ty; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn (Option<$ty>) $ty);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : $ty:ty; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn (Option<$ty>) $ty);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-
-	// maps
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn ($ty) map [$kty => $ty]);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn ($ty) map [$kty => $ty]);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn ($ty) map [$kty => $ty]);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn ($ty) map [$kty => $ty]);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn (Option<$ty>) map [$kty => $ty]);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_fn!($traitinstance $name $getfn (Option<$ty>) map [$kty => $ty]);
-		__impl_store_fns!($traitinstance $($t)*);
-	};
-
-	// exit
-	($traitinstance:ident) => ()
-}
-
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __impl_store_fn {
-	($traitinstance:ident $name:ident $get_fn:ident ($gettype:ty) $ty:ty) => {
-		pub fn $get_fn() -> $gettype {
-			<$name<$traitinstance> as $crate::storage::generator::StorageValue<$ty>> :: get(&$crate::storage::RuntimeStorage)
-		}
-	};
-	($traitinstance:ident $name:ident $get_fn:ident ($gettype:ty) map [$kty:ty => $ty:ty]) => {
-		pub fn $get_fn<K: $crate::storage::generator::Borrow<$kty>>(key: K) -> $gettype {
-			<$name<$traitinstance> as $crate::storage::generator::StorageMap<$kty, $ty>> :: get(key.borrow(), &$crate::storage::RuntimeStorage)
-		}
-	}
-}
-
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __impl_store_items {
-	// simple values
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : default $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : default $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : required $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : required $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : default $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : default $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : required $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : required $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : $ty:ty; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-
-	// maps
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : default map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : required map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* $name:ident get($getfn:ident) : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-	($traitinstance:ident $(#[$doc:meta])* pub $name:ident get($getfn:ident) : map [$kty:ty => $ty:ty]; $($t:tt)*) => {
-		__impl_store_item!($name $traitinstance);
-		__impl_store_items!($traitinstance $($t)*);
-	};
-
-	// exit
-	($traitinstance:ident) => ()
-}
-
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __impl_store_item {
-	($name:ident $traitinstance:ident) => { type $name = $name<$traitinstance>; }
-}
-
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __impl_store_json_metadata {
-	(
-		$cratename:ident;
-		$($rest:tt)*
-	) => {
-		pub fn store_json_metadata() -> &'static str {
-			concat!(r#"{ "prefix": ""#, stringify!($cratename), r#"", "items": {"#,
-				__store_functions_to_json!(""; $($rest)*), " } }")
-		}
-	}
-}
-
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __store_functions_to_json {
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident :
-			default $ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty), default
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident :
-			default $ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty), default
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident :
-			required $ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty), required
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident :
-			required $ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty), required
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	// simple values
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident :
-			$ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty)
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident :
-			$ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty)
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident get($getfn:ident) :
-			default $ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty), default
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident get($getfn:ident) :
-			default $ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty), default
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident get($getfn:ident) :
-			required $ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty), required
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident get($getfn:ident) :
-			required $ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty), required
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident get($getfn:ident) :
-			$ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty)
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident get($getfn:ident) :
-			$ty:ty; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($ty)
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-
-	// maps
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident :
-			default map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty), default
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident :
-			default map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty), default
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident :
-			required map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty), required
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident :
-			required map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty), required
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident :
-			map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty)
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident :
-			map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty)
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])* $name:ident get($getfn:ident) :
-			default map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty), default
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident get($getfn:ident) :
-			default map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty), default
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])* $name:ident get($getfn:ident) :
-			required map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty), required
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident get($getfn:ident) :
-			required map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty), required
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		$name:ident get($getfn:ident) :
-			map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty)
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	(
-		$prefix_str:tt;
-		$(#[doc = $doc_attr:tt])*
-		pub $name:ident get($getfn:ident) :
-		map [$kty:ty => $ty:ty]; $($t:tt)*
-	) => {
-		concat!(
-			__store_function_to_json!($prefix_str,
-				__function_doc_to_json!(""; $($doc_attr)*),
-				$name, __store_type_to_json!($kty, $ty)
-			),
-			__store_functions_to_json!(","; $($t)*)
-		)
-	};
-	($prefix_str:tt;) => { "" }
-}
-
-#[macro_export]
-#[doc(hidden)]
-macro_rules! __store_function_to_json {
-	($prefix_str:tt, $fn_doc:expr, $name:ident, $type:expr, $modifier:ident) => {
-		__store_function_to_json!($prefix_str; $fn_doc; $name; $type; 
-			concat!("\"", stringify!($modifier), "\""))
-	};
-	($prefix_str:tt, $fn_doc:expr, $name:ident, $type:expr) => {
-		__store_function_to_json!($prefix_str; $fn_doc; $name; $type; "null")
-	};
-	($prefix_str:tt; $fn_doc:expr; $name:ident; $type:expr; $modifier:expr) => {
-		concat!($prefix_str, " \"", stringify!($name), "\": { ",
-			r#""description": ["#, $fn_doc, " ], ",
-			r#""modifier": "#, $modifier, r#", "type": "#, $type, r#" }"#
-		)
-	}
-}
+// TODO: revisit this idiom once we get `type`s in `impl`s.
+/*impl<T: Trait> Module<T> {
+	type Now = super::Now<T>;
+}*/
 
+/// Declares strongly-typed wrappers around codec-compatible types in storage.
+///
+/// For now we implement a convenience trait with pre-specialised associated types, one for each
+/// storage item. This allows you to gain access to publicly visisible storage items from a
+/// module type. Currently you must disambiguate by using `<Module as Store>::Item` rather than
+/// the simpler `Module::Item`. Hopefully the rust guys with fix this soon.
+///
+/// Each item is declared as
+///
+/// ```rust,ignore
+/// /// Documentation of the item.
+/// pub Name get(getter_fn): default map [KeyType => ValueType];
+/// ```
+///
+/// where the visibility, the `get(..)` getter, the `default`/`required` modifier and the map
+/// are all optional. Without a modifier a read of a missing value yields `None`; with `default`
+/// it yields `Default::default()` and with `required` it panics.
+///
+/// Along the getter of a value or a map comes a `getter_fn_exists` function, taking the same keys
+/// and telling whether the value is in storage without decoding it.
+///
+/// The value of a missing `default` item may instead come from a type implementing `Get`, e.g.
+/// a constant of the module configuration, with `default(T::ExistentialDeposit) T::Balance`.
+/// The modifier of an item is implemented by the `QueryKind` (`OptionQuery` or `ValueQuery`) and
+/// `OnEmpty` associated types of its `StorageValue` or `StorageMap` implementation.
+///
+/// Integer values may be marked `#[codec(compact)]`, or its short form `#[compact]`, (in any
+/// position among the doc comments) to be stored in the variable-length `Compact` encoding, which
+/// takes their type to implement `HasCompact`; their metadata type is then reported as
+/// `Compact<T>`.
+///
+/// A value declared as `blob`, as in `Code get(code): blob;`, is a `Vec<u8>` kept as it is rather
+/// than encoded with a length prefix, like the runtime code under `:code`. Its metadata type is
+/// reported as `Blob`.
+///
+/// The key and value types of the items must implement `TypeInfo` for the runtime metadata,
+/// which describes them with `Module::register_storage_types()`.
+///
+/// Values read in most blocks, such as the block number, may be marked `#[whitelisted]`. The
+/// module then lists their keys through `WhitelistedStorageKeys`, for cost accounting to leave
+/// their reads out.
+///
+/// An item is kept under `twox_128(module) ++ twox_128(item)`, the module being the crate name
+/// given after `as`, e.g. `twox_128(b"Balances") ++ twox_128(b"FreeBalance")`. The metadata
+/// records the scheme as its `prefixHasher`. Maps expose their prefix through
+/// `StoragePrefixedMap`.
+///
+/// The key of a map entry is the prefix of the map followed by the encoded key. The key may be
+/// hashed instead by declaring the map as `map hasher(blake2_256) [KeyType => ValueType]`, which
+/// keeps keys chosen by untrusted parties from unbalancing the trie; `twox_128`, `twox_256` and
+/// `identity` (the default) are also available. The metadata of a map records its hasher and
+/// its `kind` (`map`, `linked_map` or `counted_map`); the one of a double map or an nmap records
+/// the hashers of its keys, so that clients can compute the keys of entries.
+///
+/// A map declared as `linked_map [KeyType => ValueType]` also keeps its keys in a linked list,
+/// and implements `EnumerableStorageMap` to iterate over its entries with `enumerate()`, over its
+/// keys alone with `iter_keys()`, or over its entries while removing them with `drain()`.
+///
+/// A map declared as `counted_map [KeyType => ValueType]` also keeps the number of its entries,
+/// and implements `CountedStorageMap` to read it with `count()`.
+///
+/// A map declared as `double_map Key1Type, Key2Type => ValueType` is keyed by two values and
+/// implements `StorageDoubleMap`; all entries sharing the first key can be removed at once with
+/// `remove_prefix`. The second key is hashed with blake2, so it may be chosen by untrusted parties.
+///
+/// A map declared as `child(b":child:name") map [KeyType => ValueType]` keeps its entries in the
+/// child storage of the given key, and implements `StorageChildMap`; all of its entries can be
+/// removed at once with `remove_all`. Two child maps of a module may not share their key, which
+/// fails the expansion as would any other two keys of the module starting with one another.
+///
+/// A deque declared as `deque [ValueType; capacity]` holds at most `capacity` items, an expression
+/// of type `u32`, and implements `StorageDeque` to push and pop items at both ends. Its getter
+/// returns the items from front to back, and `config(..)` takes them as a `Vec`.
+///
+/// A set declared as `ordered_set [ValueType]` keeps its items sorted and deduplicated, and
+/// implements `StorageOrderedSet` to check membership with a binary search and to access items
+/// by rank. Its getter returns the items in order, and `config(..)` takes them as a `Vec`.
+///
+/// A map declared as `nmap Key1Type, Key2Type, .. => ValueType` takes a tuple of two to four keys,
+/// each hashed on its own with blake2, and implements `StorageNMap`. All entries sharing leading
+/// keys can be removed with `remove_prefix` or visited with `iter_prefix_values`.
+///
+/// The layout of the storage may be versioned by following the crate name with `version(N)`, as
+/// in `as Balances version(2)`. The version defaults to 0 and is implemented through
+/// `StorageVersion`; the version kept on chain is bumped by the `on_storage_migration` hook of
+/// the module. The genesis of a new chain should record the current version with
+/// `put_on_chain_storage_version`, or the hook would migrate storage that is already up to date;
+/// the generated `GenesisConfig` below does so.
+///
+/// Following the crate name, and the version if any, with `bounded`, as in `as Staking bounded`,
+/// gives every value of the storage a size known in advance, so that proofs of it are of a
+/// predictable size. Blobs and values of a type made of `Vec`, `String`, `BTreeMap`, `BTreeSet`,
+/// `VecDeque` or `BinaryHeap` are then refused, in favour of `BoundedVec` and `BoundedBTreeMap`,
+/// and the module gets `storage_max_encoded_lens`, the largest encoded value of each item, as
+/// given by `MaxEncodedLen`.
+///
+/// Storage values and maps may be initialised at genesis by following the getter with
+/// `config(..)` or `build(..)`:
+///
+/// ```rust,ignore
+/// Initial get(initial) config(): u32;
+/// Limit get(limit) config(initial_limit): u32;
+/// Balances get(balance_of) config(): map [T::AccountId => T::Balance];
+/// Total get(total) build(|storage, config| config.balance_of.iter().map(|&(_, b)| b).sum()): T::Balance;
+/// ```
+///
+/// `config()` adds a field named after the getter to the `GenesisConfig<T>` of the module,
+/// holding the value of a storage value or the list of `(key, value)` entries of a map;
+/// `config(name)` names the field `name` instead, and needs no getter. Fields are serialized in
+/// camel case, so `initial_limit` is `initialLimit` in a chain spec.
+/// `#[serde(..)]` attributes on such items, e.g. `#[serde(with = "bytes")]` to write a list of
+/// bytes as a hex string, are put on their field as they are.
+/// `build(|storage, config| ..)` computes that value instead, from the `GenesisConfig` and from
+/// the `GenesisStorage` built so far; items are built in the order they are declared.
+///
+/// Genesis configuration that is not kept in a storage item of its own goes in a trailing
+/// section:
+///
+/// ```rust,ignore
+/// add_extra_genesis {
+/// 	config(authorities): Vec<T::AccountId>;
+/// 	build(|storage, config| { .. });
+/// }
+/// ```
+///
+/// Each `config(name): Type;` adds a field to the `GenesisConfig`, which may also be given
+/// `#[serde(..)]` attributes, and the `build` closure runs after all items, writing anything
+/// through `storage`. The `GenesisConfig` is only generated, together with its `BuildStorage`
+/// implementation, when at least one of these is used.
+/// Besides `build_storage`, it offers `assimilate_storage` to build into an existing map, which
+/// fails if a key there already holds another value, and `new_test_ext` to get
+/// `TestExternalities` holding the storage of the module in unit tests:
+///
+/// ```rust,ignore
+/// with_externalities(&mut GenesisConfig::<Test> { initial: 5, ..Default::default() }.new_test_ext(), || {
+/// 	assert_eq!(Module::<Test>::initial(), Some(5));
+/// });
+/// ```
+///
+/// A module that can be included several times in a runtime takes an instance parameter, as in
+/// `trait Store for Module<T: Trait<I>, I: Instance> as Balances`. The macro then also declares
+/// the trait `Instance`, the instance `DefaultInstance`, whose storage keys are those the module
+/// would have without instances, and `Instance1` to `Instance16`, whose module name is prefixed
+/// with their name before it is hashed. Every item, and the `GenesisConfig`, takes the instance after `T`.
+///
+/// Further type parameters may follow `T` and the instance, and bounds may be put in a `where`
+/// clause before `as`, as in `trait Store for Module<T: Trait, S: Scale> where T::Balance: From<u64>
+/// as Example`. They must match the ones of the `Module` declared by `decl_module!`; every item,
+/// the `GenesisConfig` and all their implementations carry them.
+///
+/// The declaration is expanded by a procedural macro, so syntax errors point at the offending
+/// item.
 #[macro_export]
-#[doc(hidden)]
-macro_rules! __store_type_to_json {
-	($name:ty) => {
-		concat!("\"", stringify!($name), "\"")
-	};
-	($key: ty, $value:ty) => {
-		concat!(r#"{ "key": ""#, stringify!($key), r#"", "value": ""#,
-			stringify!($value), "\" }")
+macro_rules! decl_storage {
+	($($t:tt)*) => {
+		$crate::__decl_storage_impl! { $crate; $($t)* }
 	}
 }
 
@@ -1475,9 +1729,17 @@ mod tests {
 	use std::cell::RefCell;
 	use codec::Codec;
 	use super::*;
+	use storage::unhashed::generator::{UnhashedStorage, StorageDoubleMap, StorageNMap};
 	use serde;
 	use serde_json;
 
+	/// The key `decl_storage!` gives to `item` of `module`.
+	fn final_key(module: &[u8], item: &[u8]) -> Vec<u8> {
+		let mut key = ::runtime_io::twox_128(module).to_vec();
+		key.extend(&::runtime_io::twox_128(item)[..]);
+		key
+	}
+
 	impl Storage for RefCell<HashMap<Vec<u8>, Vec<u8>>> {
 		fn exists(&self, key: &[u8]) -> bool {
 			self.borrow_mut().get(key).is_some()
@@ -1487,19 +1749,79 @@ mod tests {
 			self.borrow_mut().get(key).map(|v| T::decode(&mut &v[..]).unwrap())
 		}
 
+		fn read(&self, key: &[u8], buf: &mut [u8]) -> Option<usize> {
+			self.borrow().get(key).map(|v| {
+				let len = ::std::cmp::min(v.len(), buf.len());
+				buf[..len].copy_from_slice(&v[..len]);
+				v.len()
+			})
+		}
+
 		fn put<T: Codec>(&self, key: &[u8], val: &T) {
 			self.borrow_mut().insert(key.to_owned(), val.encode());
 		}
 
+		fn put_raw(&self, key: &[u8], value: &[u8]) {
+			self.borrow_mut().insert(key.to_owned(), value.to_owned());
+		}
+
 		fn kill(&self, key: &[u8]) {
 			self.borrow_mut().remove(key);
 		}
+
+		fn kill_prefix(&self, prefix: &[u8]) {
+			self.borrow_mut().retain(|key, _| !key.starts_with(prefix));
+		}
+
+		fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+			self.borrow().keys().filter(|k| &k[..] > key).min().cloned()
+		}
+	}
+
+	impl UnhashedStorage for RefCell<HashMap<Vec<u8>, Vec<u8>>> {
+		fn exists(&self, key: &[u8]) -> bool {
+			Storage::exists(self, key)
+		}
+
+		fn get<T: Codec>(&self, key: &[u8]) -> Option<T> {
+			Storage::get(self, key)
+		}
+
+		fn put<T: Codec>(&self, key: &[u8], val: &T) {
+			Storage::put(self, key, val)
+		}
+
+		fn kill(&self, key: &[u8]) {
+			Storage::kill(self, key)
+		}
+
+		fn kill_prefix(&self, prefix: &[u8]) {
+			Storage::kill_prefix(self, prefix)
+		}
+
+		fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+			Storage::next_storage_key(self, key)
+		}
 	}
 
 	storage_items! {
 		Value: b"a" => u32;
 		List: b"b:" => list [u64];
 		Map: b"c:" => map [u32 => [u8; 32]];
+		Balances: b"d:" => map [u32 => u64];
+		Queue: b"e:" => deque [u32; 3];
+	}
+
+	#[test]
+	#[should_panic(expected = "the storage key of `Other` collides with the one of `Value`")]
+	fn equal_keys_collide() {
+		assert_distinct_keys(&[("Value", b"a"), ("Map", b"c:"), ("Other", b"a")]);
+	}
+
+	#[test]
+	#[should_panic(expected = "the storage key of `Map` collides with the one of `Value`")]
+	fn keys_starting_with_another_collide() {
+		assert_distinct_keys(&[("Value", b"abc"), ("Map", b"abc:")]);
 	}
 
 	#[test]
@@ -1508,10 +1830,45 @@ mod tests {
 		assert!(Value::get(&storage).is_none());
 		Value::put(&100_000, &storage);
 		assert_eq!(Value::get(&storage), Some(100_000));
+		assert_eq!(Value::mutate(|v| v.take(), &storage), Some(100_000));
+		assert!(Value::get(&storage).is_none());
+		Value::put(&100_000, &storage);
 		Value::kill(&storage);
 		assert!(Value::get(&storage).is_none());
 	}
 
+	#[test]
+	fn try_mutate() {
+		let storage = RefCell::new(HashMap::new());
+		assert_eq!(Value::try_mutate(|v| { *v = Some(1); Err::<(), _>("no") }, &storage), Err("no"));
+		assert!(Value::get(&storage).is_none());
+		assert_eq!(Value::try_mutate(|v| { *v = Some(1); Ok::<_, ()>(2) }, &storage), Ok(2));
+		assert_eq!(Value::get(&storage), Some(1));
+
+		Map::insert(&1, &[1; 32], &storage);
+		assert_eq!(Map::try_mutate(&1, |v| { *v = None; Err::<(), _>("no") }, &storage), Err("no"));
+		assert_eq!(Map::get(&1, &storage), Some([1; 32]));
+		assert_eq!(Map::try_mutate(&1, |v| { *v = None; Ok::<_, ()>(()) }, &storage), Ok(()));
+		assert!(Map::get(&1, &storage).is_none());
+	}
+
+	#[test]
+	fn translate() {
+		let storage = RefCell::new(HashMap::new());
+		assert_eq!(Value::translate(|old: Option<u16>| old.map(u32::from), &storage), Ok(None));
+		assert!(storage.borrow().is_empty());
+
+		storage.borrow_mut().insert(b"a".to_vec(), vec![7, 1]);
+		assert_eq!(Value::translate(|old: Option<u16>| old.map(|v| u32::from(v) * 2), &storage), Ok(Some(2 * 263)));
+		assert_eq!(Value::get(&storage), Some(2 * 263));
+
+		assert!(Value::translate(|old: Option<u64>| old.map(|v| v as u32), &storage).is_err());
+		assert_eq!(Value::get(&storage), Some(2 * 263));
+
+		assert_eq!(Value::translate(|_: Option<u32>| None, &storage), Ok(None));
+		assert!(storage.borrow().is_empty());
+	}
+
 	#[test]
 	fn list() {
 		let storage = RefCell::new(HashMap::new());
@@ -1531,6 +1888,77 @@ mod tests {
 		assert!(List::items(&storage).is_empty());
 	}
 
+	#[test]
+	fn list_push_pop() {
+		let storage = RefCell::new(HashMap::new());
+		assert_eq!(List::pop(&storage), None);
+
+		List::push(&1, &storage);
+		List::push(&2, &storage);
+		List::push(&3, &storage);
+		assert_eq!(List::items(&storage), &[1, 2, 3]);
+
+		assert_eq!(List::pop(&storage), Some(3));
+		assert_eq!(List::items(&storage), &[1, 2]);
+		assert!(List::get(2, &storage).is_none());
+
+		List::set_items(&[4], &storage);
+		assert_eq!(List::items(&storage), &[4]);
+		assert!(List::get(1, &storage).is_none());
+	}
+
+	#[test]
+	fn list_remove_items() {
+		let storage = RefCell::new(HashMap::new());
+		List::set_items(&[0, 1, 2, 3, 4, 5, 6], &storage);
+
+		List::truncate(10, &storage);
+		assert_eq!(List::len(&storage), 7);
+		List::truncate(6, &storage);
+		assert_eq!(List::items(&storage), &[0, 1, 2, 3, 4, 5]);
+		assert!(List::get(6, &storage).is_none());
+
+		List::retain(|i| i % 2 == 0, &storage);
+		assert_eq!(List::items(&storage), &[0, 2, 4]);
+		assert!(List::get(3, &storage).is_none());
+
+		assert_eq!(List::swap_remove(0, &storage), Some(0));
+		assert_eq!(List::items(&storage), &[4, 2]);
+		assert_eq!(List::swap_remove(1, &storage), Some(2));
+		assert_eq!(List::items(&storage), &[4]);
+		assert_eq!(List::swap_remove(1, &storage), None);
+		assert_eq!(List::swap_remove(0, &storage), Some(4));
+		assert_eq!(List::len(&storage), 0);
+		assert!(storage.borrow().keys().all(|k| !k.starts_with(b"b:") || k == &List::len_key()));
+	}
+
+	#[test]
+	fn deque() {
+		let storage = RefCell::new(HashMap::new());
+		assert_eq!(Queue::len(&storage), 0);
+		assert_eq!(Queue::pop_front(&storage), None);
+		assert_eq!(Queue::back(&storage), None);
+
+		Queue::push_back(&1, &storage).unwrap();
+		Queue::push_back(&2, &storage).unwrap();
+		Queue::push_front(&0, &storage).unwrap();
+		assert_eq!(Queue::push_back(&3, &storage), Err("the queue is full"));
+		assert_eq!(Queue::items(&storage), &[0, 1, 2]);
+		assert_eq!((Queue::front(&storage), Queue::back(&storage)), (Some(0), Some(2)));
+		// the front was pushed before position zero.
+		assert_eq!(Queue::bounds(&storage), (u32::max_value(), 2));
+
+		assert_eq!(Queue::pop_front(&storage), Some(0));
+		assert_eq!(Queue::pop_back(&storage), Some(2));
+		Queue::push_back(&3, &storage).unwrap();
+		assert_eq!(Queue::items(&storage), &[1, 3]);
+		assert!(storage.borrow().get(&Queue::key_for(u32::max_value())).is_none());
+
+		Queue::clear(&storage);
+		assert_eq!(Queue::len(&storage), 0);
+		assert!(storage.borrow().keys().all(|k| !k.starts_with(b"e:")));
+	}
+
 	#[test]
 	fn map() {
 		let storage = RefCell::new(HashMap::new());
@@ -1542,6 +1970,47 @@ mod tests {
 		assert!(Map::get(&999, &storage).is_none());
 	}
 
+	#[test]
+	fn map_iter_prefix() {
+		let storage = RefCell::new(HashMap::new());
+		Value::put(&7, &storage);
+		List::set_items(&[1, 2], &storage);
+		for i in &[3u32, 1, 256, 2] {
+			Map::insert(i, &[*i as u8; 32], &storage);
+		}
+		// shares the prefix of the map but is not one of its keys.
+		storage.borrow_mut().insert(b"c:x".to_vec(), vec![0]);
+
+		assert_eq!(
+			Map::iter_prefix(&storage).map(|(k, _)| k).collect::<Vec<_>>(),
+			vec![256, 1, 2, 3],
+		);
+		assert_eq!(Map::iter_prefix(&storage).next(), Some((256, [0; 32])));
+	}
+
+	#[test]
+	fn map_translate_values() {
+		let storage = RefCell::new(HashMap::new());
+		for i in 1..4u32 {
+			storage.borrow_mut().insert(Balances::key_for(&i), codec::Encode::encode(&(i as u16)));
+		}
+		storage.borrow_mut().insert(b"d:x".to_vec(), vec![0]);
+		storage.borrow_mut().insert(Balances::key_for(&4), vec![0]);
+
+		let translated = Balances::translate_values(|old: u16| if old == 2 {
+			None
+		} else {
+			Some(u64::from(old) * 10)
+		}, &storage);
+
+		assert_eq!(translated, 3);
+		assert_eq!(Balances::get(&1, &storage), Some(10));
+		assert_eq!(Balances::get(&2, &storage), None);
+		assert_eq!(Balances::get(&3, &storage), Some(30));
+		assert_eq!(storage.borrow().get(&Balances::key_for(&4)), Some(&vec![0]));
+		assert_eq!(storage.borrow().get(&b"d:x"[..]), Some(&vec![0]));
+	}
+
 	pub trait Trait {
 		 type Origin;
 	}
@@ -1565,7 +2034,12 @@ mod tests {
 			GETU32Required get(get_u32_required): required u32;
 			pub PUBU32Required : required u32;
 			pub GETPUBU32Required get(pub_get_u32_required): required u32;
+			/// Hello, this is compact doc!
+			#[codec(compact)]
+			COMPACTU64 get(compact_u64_getter): default u64;
+			VECU32 get(vec_u32_getter): Vec<u32>;
 
+			/// Hello, this is map doc!
 			MAPU32 : map [ u32 => String ];
 			/// Hello, this is doc!
 			/// Hello, this is doc 2!
@@ -1581,6 +2055,16 @@ mod tests {
 			pub PUBMAPU32Required : required map [ u32 => String ];
 			pub GETPUBMAPU32Required get(map_pub_get_u32_required): required map [ u32 => String ];
 
+			LINKEDMAPU32 get(linked_map_u32_getter): linked_map [ u32 => String ];
+
+			/// Hello, this is "double map" doc!
+			DOUBLEMAPU32 get(double_map_u32_getter): default double_map u32, Vec<u8> => u64;
+			/// Hello, this is nmap doc!
+			NMAPU32 get(nmap_u32_getter): nmap u32, u32, u32 => u64;
+			HASHEDMAPU32 get(hashed_map_u32_getter): map hasher(blake2_256) [u32 => String];
+			COUNTEDMAPU32 get(counted_map_u32_getter): counted_map [u32 => String];
+			/// Hello, this is child map doc!
+			CHILDMAPU32 get(child_map_u32_getter): default child(b":child:test") map [u32 => u64];
 		}
 	}
 
@@ -1591,7 +2075,7 @@ mod tests {
 	}
 
 	const EXPECTED_METADATA: &str = concat!(
-		r#"{ "prefix": "TestStorage", "items": { "#,
+		r#"{ "prefix": "TestStorage", "prefixHasher": "twox_128", "items": { "#,
 			r#""U32": { "description": [ " Hello, this is doc!" ], "modifier": null, "type": "u32" }, "#,
 			r#""GETU32": { "description": [ ], "modifier": null, "type": "u32" }, "#,
 			r#""PUBU32": { "description": [ ], "modifier": null, "type": "u32" }, "#,
@@ -1604,18 +2088,26 @@ mod tests {
 			r#""GETU32Required": { "description": [ ], "modifier": "required", "type": "u32" }, "#,
 			r#""PUBU32Required": { "description": [ ], "modifier": "required", "type": "u32" }, "#,
 			r#""GETPUBU32Required": { "description": [ ], "modifier": "required", "type": "u32" }, "#,
-			r#""MAPU32": { "description": [ ], "modifier": null, "type": { "key": "u32", "value": "String" } }, "#,
-			r#""GETMAPU32": { "description": [ " Hello, this is doc!", " Hello, this is doc 2!" ], "modifier": null, "type": { "key": "u32", "value": "String" } }, "#,
-			r#""PUBMAPU32": { "description": [ ], "modifier": null, "type": { "key": "u32", "value": "String" } }, "#,
-			r#""GETPUBMAPU32": { "description": [ ], "modifier": null, "type": { "key": "u32", "value": "String" } }, "#,
-			r#""MAPU32Default": { "description": [ ], "modifier": "default", "type": { "key": "u32", "value": "String" } }, "#,
-			r#""GETMAPU32Default": { "description": [ ], "modifier": "default", "type": { "key": "u32", "value": "String" } }, "#,
-			r#""PUBMAPU32Default": { "description": [ ], "modifier": "default", "type": { "key": "u32", "value": "String" } }, "#,
-			r#""GETPUBMAPU32Default": { "description": [ ], "modifier": "default", "type": { "key": "u32", "value": "String" } }, "#,
-			r#""MAPU32Required": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "String" } }, "#,
-			r#""GETMAPU32Required": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "String" } }, "#,
-			r#""PUBMAPU32Required": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "String" } }, "#,
-			r#""GETPUBMAPU32Required": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "String" } }"#,
+			r#""COMPACTU64": { "description": [ " Hello, this is compact doc!" ], "modifier": "default", "type": "Compact<u64>" }, "#,
+			r#""VECU32": { "description": [ ], "modifier": null, "type": "Vec<u32>" }, "#,
+			r#""MAPU32": { "description": [ " Hello, this is map doc!" ], "modifier": null, "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""GETMAPU32": { "description": [ " Hello, this is doc!", " Hello, this is doc 2!" ], "modifier": null, "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""PUBMAPU32": { "description": [ ], "modifier": null, "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""GETPUBMAPU32": { "description": [ ], "modifier": null, "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""MAPU32Default": { "description": [ ], "modifier": "default", "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""GETMAPU32Default": { "description": [ ], "modifier": "default", "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""PUBMAPU32Default": { "description": [ ], "modifier": "default", "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""GETPUBMAPU32Default": { "description": [ ], "modifier": "default", "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""MAPU32Required": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""GETMAPU32Required": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""PUBMAPU32Required": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""GETPUBMAPU32Required": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "map" } }, "#,
+			r#""LINKEDMAPU32": { "description": [ ], "modifier": null, "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "linked_map" } }, "#,
+			r#""DOUBLEMAPU32": { "description": [ " Hello, this is \"double map\" doc!" ], "modifier": "default", "type": { "key1": "u32", "key2": "Vec<u8>", "value": "u64", "hashers": [ "twox_128", "blake2_256" ] } }, "#,
+			r#""NMAPU32": { "description": [ " Hello, this is nmap doc!" ], "modifier": null, "type": { "keys": [ "u32", "u32", "u32" ], "value": "u64", "hashers": [ "blake2_256", "blake2_256", "blake2_256" ] } }, "#,
+			r#""HASHEDMAPU32": { "description": [ ], "modifier": null, "type": { "key": "u32", "value": "String", "hasher": "blake2_256", "kind": "map" } }, "#,
+			r#""COUNTEDMAPU32": { "description": [ ], "modifier": null, "type": { "key": "u32", "value": "String", "hasher": "identity", "kind": "counted_map" } }, "#,
+			r#""CHILDMAPU32": { "description": [ " Hello, this is child map doc!" ], "modifier": "default", "type": { "key": "u32", "value": "u64", "child": ":child:test" } }"#,
 		" } }"
 	);
 
@@ -1626,10 +2118,275 @@ mod tests {
 		let _: serde::de::IgnoredAny =
 			serde_json::from_str(metadata).expect("Is valid json syntax");
 	}
-}
 
-#[cfg(test)]
-// Do not complain about unused `dispatch` and `dispatch_aux`.
+	#[test]
+	fn compact_value() {
+		let storage = RefCell::new(HashMap::new());
+		assert_eq!(<COMPACTU64<TraitImpl> as StorageValue<u64>>::get(&storage), 0);
+		<COMPACTU64<TraitImpl> as StorageValue<u64>>::put(&42, &storage);
+		assert_eq!(storage.borrow().get(&final_key(b"TestStorage", b"COMPACTU64")), Some(&vec![42 << 2]));
+		<COMPACTU64<TraitImpl> as StorageValue<u64>>::mutate(|v| *v += 1 << 20, &storage);
+		assert_eq!(<COMPACTU64<TraitImpl> as StorageValue<u64>>::take(&storage), 42 + (1 << 20));
+		assert!(storage.borrow().is_empty());
+	}
+
+	#[test]
+	fn decode_len() {
+		type Vector = VECU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		assert_eq!(<Vector as StorageValue<Vec<u32>>>::decode_len(&storage), None);
+		<Vector as StorageValue<Vec<u32>>>::put(&vec![1, 2, 3], &storage);
+		assert_eq!(<Vector as StorageValue<Vec<u32>>>::decode_len(&storage), Some(3));
+		<Vector as StorageValue<Vec<u32>>>::put(&vec![], &storage);
+		assert_eq!(<Vector as StorageValue<Vec<u32>>>::decode_len(&storage), Some(0));
+	}
+
+	#[test]
+	fn linked_map() {
+		type Linked = LINKEDMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		let entries = |storage| Linked::enumerate(storage).collect::<Vec<_>>();
+		assert!(entries(&storage).is_empty());
+
+		for i in 1..5 {
+			Linked::insert(&i, &i.to_string(), &storage);
+		}
+		Linked::insert(&2, &"two".to_string(), &storage);
+		assert_eq!(entries(&storage), vec![
+			(4, "4".to_string()), (3, "3".to_string()), (2, "two".to_string()), (1, "1".to_string()),
+		]);
+
+		Linked::remove(&3, &storage);
+		assert_eq!(Linked::take(&4, &storage), Some("4".to_string()));
+		Linked::mutate(&1, |v| *v = None, &storage);
+		Linked::mutate(&5, |v| *v = Some("5".to_string()), &storage);
+		assert_eq!(entries(&storage), vec![(5, "5".to_string()), (2, "two".to_string())]);
+		assert_eq!(Linked::head(&storage), Some(5));
+
+		Linked::remove(&5, &storage);
+		Linked::remove(&2, &storage);
+		assert!(entries(&storage).is_empty());
+		assert!(storage.borrow().is_empty());
+	}
+
+	#[test]
+	fn batches_apply_in_order() {
+		let storage = RefCell::new(HashMap::new());
+		Storage::put(&storage, b":old", &1u32);
+
+		let mut batch = StorageBatch::new();
+		batch.put(b":a", &1u32).put_raw(b":b", &codec::Encode::encode(&2u32)[..]).kill(b":old").put(b":a", &3u32);
+		assert_eq!(batch.len(), 4);
+		assert_eq!(Storage::get::<u32>(&storage, b":a"), None);
+
+		batch.commit(&storage);
+		assert_eq!(Storage::get(&storage, b":a"), Some(3u32));
+		assert_eq!(Storage::get(&storage, b":b"), Some(2u32));
+		assert!(!Storage::exists(&storage, b":old"));
+	}
+
+	#[test]
+	fn linked_map_keys_and_drain() {
+		type Linked = LINKEDMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		for i in 1..5 {
+			Linked::insert(&i, &i.to_string(), &storage);
+		}
+		assert_eq!(Linked::iter_keys(&storage).collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+
+		let drained = Linked::drain(&storage).take(2).collect::<Vec<_>>();
+		assert_eq!(drained, vec![(4, "4".to_string()), (3, "3".to_string())]);
+		assert_eq!(Linked::enumerate(&storage).collect::<Vec<_>>(), vec![(2, "2".to_string()), (1, "1".to_string())]);
+		assert_eq!(Linked::head(&storage), Some(2));
+
+		assert_eq!(Linked::drain(&storage).map(|(k, _)| k).collect::<Vec<_>>(), vec![2, 1]);
+		assert!(Linked::iter_keys(&storage).next().is_none());
+		assert!(storage.borrow().is_empty());
+	}
+
+	#[test]
+	fn double_map() {
+		type Double = DOUBLEMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		Double::insert(&1, &b"foo".to_vec(), &1, &storage);
+		Double::insert(&1, &b"bar".to_vec(), &2, &storage);
+		Double::insert(&2, &b"foo".to_vec(), &3, &storage);
+		Double::mutate(&2, &b"bar".to_vec(), |v| *v += 4, &storage);
+		assert_eq!(Double::get(&1, &b"foo".to_vec(), &storage), 1);
+		assert_eq!(Double::get(&2, &b"bar".to_vec(), &storage), 4);
+		assert!(Double::key_for(&1, &b"foo".to_vec()).starts_with(&Double::prefix_for(&1)));
+
+		Double::remove_prefix(&1, &storage);
+		assert!(!Double::exists(&1, &b"foo".to_vec(), &storage));
+		assert!(!Double::exists(&1, &b"bar".to_vec(), &storage));
+		assert_eq!(Double::take(&2, &b"foo".to_vec(), &storage), 3);
+		Double::remove(&2, &b"bar".to_vec(), &storage);
+		assert!(storage.borrow().is_empty());
+	}
+
+	#[test]
+	fn nmap() {
+		type NMap = NMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		NMap::insert(&(1, 1, 1), &111, &storage);
+		NMap::insert(&(1, 1, 2), &112, &storage);
+		NMap::insert(&(1, 2, 1), &121, &storage);
+		NMap::insert(&(2, 1, 1), &211, &storage);
+		NMap::mutate(&(2, 2, 2), |v| *v = Some(222), &storage);
+		assert_eq!(NMap::get(&(1, 1, 2), &storage), Some(112));
+		assert_eq!(NMap::get(&(2, 2, 2), &storage), Some(222));
+		assert!(NMap::key_for(&(1, 2, 1)).starts_with(&NMap::prefix_for(&(1, 2))));
+		assert!(NMap::prefix_for(&(1, 2)).starts_with(&NMap::prefix_for(&(1,))));
+
+		let values = |partial, storage| {
+			let mut values = NMap::iter_prefix_values::<(u32, u32), _>(partial, storage).collect::<Vec<_>>();
+			values.sort();
+			values
+		};
+		assert_eq!(values(&(1, 1), &storage), vec![111, 112]);
+		let mut first = NMap::iter_prefix_values(&(1,), &storage).collect::<Vec<_>>();
+		first.sort();
+		assert_eq!(first, vec![111, 112, 121]);
+
+		NMap::remove_prefix(&(1, 1), &storage);
+		assert_eq!(values(&(1, 1), &storage), Vec::<u64>::new());
+		assert_eq!(NMap::get(&(1, 2, 1), &storage), Some(121));
+		NMap::remove_prefix(&(1,), &storage);
+		assert!(!NMap::exists(&(1, 2, 1), &storage));
+		assert_eq!(NMap::take(&(2, 1, 1), &storage), Some(211));
+		NMap::mutate(&(2, 2, 2), |v| *v = None, &storage);
+		assert!(storage.borrow().is_empty());
+	}
+
+	#[test]
+	fn hashed_map() {
+		type Hashed = HASHEDMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		Hashed::insert(&3, &"three".to_owned(), &storage);
+		let mut key = final_key(b"TestStorage", b"HASHEDMAPU32");
+		key.extend(&::Hashable::blake2_256(&3u32)[..]);
+		assert_eq!(Hashed::key_for(&3), key);
+		assert!(storage.borrow().contains_key(&key));
+		assert_eq!(Hashed::get(&3, &storage), Some("three".to_owned()));
+	}
+
+	#[test]
+	fn counted_map() {
+		type Counted = COUNTEDMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		assert_eq!(Counted::count(&storage), 0);
+
+		Counted::insert(&1, &"one".to_owned(), &storage);
+		Counted::insert(&2, &"two".to_owned(), &storage);
+		Counted::insert(&2, &"deux".to_owned(), &storage);
+		Counted::mutate(&3, |v| *v = Some("three".to_owned()), &storage);
+		assert_eq!(Counted::count(&storage), 3);
+
+		Counted::remove(&1, &storage);
+		Counted::remove(&1, &storage);
+		assert_eq!(Counted::count(&storage), 2);
+		assert_eq!(Counted::take(&2, &storage), Some("deux".to_owned()));
+		assert_eq!(Counted::take(&2, &storage), None);
+		assert_eq!(Counted::count(&storage), 1);
+		Counted::mutate(&3, |v| *v = None, &storage);
+		assert_eq!(Counted::count(&storage), 0);
+		assert!(storage.borrow().is_empty());
+	}
+
+	#[test]
+	fn lazy_defaults() {
+		let storage = RefCell::new(HashMap::new());
+		assert_eq!(Storage::get_or_else(&storage, b"lazy", || 3u32), 3);
+		Storage::put(&storage, b"lazy", &5u32);
+		assert_eq!(Storage::get_or_else(&storage, b"lazy", || -> u32 { panic!("the value is in storage") }), 5);
+		assert_eq!(Storage::take_or_else(&storage, b"lazy", || -> u32 { panic!("the value is in storage") }), 5);
+		assert_eq!(Storage::take_or_else(&storage, b"lazy", || 7u32), 7);
+		assert!(storage.borrow().is_empty());
+	}
+
+	#[test]
+	fn raw_values() {
+		type Linked = LINKEDMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		Value::put_raw(&codec::Encode::encode(&7u32), &storage);
+		assert_eq!(Value::get(&storage), Some(7));
+		assert_eq!(Value::get_raw(&storage), Some(codec::Encode::encode(&7u32)));
+
+		Map::put_raw(&1, &[3; 32], &storage);
+		assert_eq!(Map::get(&1, &storage), Some([3; 32]));
+		assert_eq!(Map::get_raw(&1, &storage), Some(vec![3; 32]));
+		assert_eq!(Map::get_raw(&2, &storage), None);
+
+		// raw values are still linked into the list of keys.
+		Linked::put_raw(&1, &codec::Encode::encode("one"), &storage);
+		assert_eq!(Linked::enumerate(&storage).collect::<Vec<_>>(), vec![(1, "one".to_owned())]);
+		assert_eq!(Linked::get_raw(&1, &storage), storage.borrow().get(&Linked::key_for(&1)).cloned());
+	}
+
+	#[test]
+	fn map_swap() {
+		type Linked = LINKEDMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		Map::insert(&1, &[1; 32], &storage);
+		Map::insert(&2, &[2; 32], &storage);
+		Map::swap(&1, &2, &storage);
+		assert_eq!(Map::get(&1, &storage), Some([2; 32]));
+		assert_eq!(Map::get(&2, &storage), Some([1; 32]));
+		Map::swap(&3, &1, &storage);
+		assert_eq!(Map::get(&3, &storage), Some([2; 32]));
+		assert!(Map::get(&1, &storage).is_none());
+
+		Linked::insert(&1, &"one".to_owned(), &storage);
+		Linked::insert(&2, &"two".to_owned(), &storage);
+		Linked::swap(&1, &3, &storage);
+		Linked::swap(&2, &3, &storage);
+		assert_eq!(Linked::enumerate(&storage).collect::<Vec<_>>(), vec![
+			(3, "two".to_owned()), (2, "one".to_owned()),
+		]);
+	}
+
+	#[test]
+	fn child_map() {
+		type Child = CHILDMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		Map::insert(&1, &[1; 32], &storage);
+		Child::insert(&1, &10, &storage);
+		Child::insert(&2, &20, &storage);
+		assert!(Child::exists(&1, &storage));
+		assert_eq!(Child::mutate(&2, |v| { *v += 1; *v }, &storage), 21);
+		assert_eq!(Child::take(&1, &storage), 10);
+		assert_eq!(Child::get(&1, &storage), 0);
+		assert!(storage.borrow().contains_key(&child_key(b":child:test", &codec::Encode::encode(&2u32))));
+
+		Child::remove_all(&storage);
+		assert_eq!(Child::get(&2, &storage), 0);
+		assert_eq!(storage.borrow().len(), 1);
+	}
+
+	#[test]
+	fn map_remove_all() {
+		type Linked = LINKEDMAPU32<TraitImpl>;
+		type Counted = COUNTEDMAPU32<TraitImpl>;
+		let storage = RefCell::new(HashMap::new());
+		Value::put(&7, &storage);
+		for i in 1..4u32 {
+			Map::insert(&i, &[i as u8; 32], &storage);
+			Linked::insert(&i, &i.to_string(), &storage);
+			Counted::insert(&i, &i.to_string(), &storage);
+		}
+
+		Map::remove_all(&storage);
+		Linked::remove_all(&storage);
+		Counted::remove_all(&storage);
+		assert!(Linked::head(&storage).is_none());
+		assert_eq!(Counted::count(&storage), 0);
+		assert_eq!(storage.borrow().len(), 1);
+		assert_eq!(Value::get(&storage), Some(7));
+	}
+}
+
+#[cfg(test)]
+// Do not complain about unused `dispatch` and `dispatch_aux`.
 #[allow(dead_code)]
 mod test2 {
 	pub trait Trait {
@@ -1648,6 +2405,11 @@ mod test2 {
 			PairDef : default PairOf<u32>;
 			Single : u32;
 			Pair : (u32, u32);
+			#[codec(compact)]
+			/// Attributes may come in any order.
+			CompactFirst : default u64;
+			#[compact]
+			CompactBalance : default u128;
 		}
 	}
 
@@ -1656,4 +2418,755 @@ mod test2 {
 	impl Trait for TraitImpl {
 		type Origin = u32;
 	}
+
+	#[test]
+	fn compact_u128_value() {
+		use std::cell::RefCell;
+		use std::collections::HashMap;
+		use storage::generator::StorageValue;
+
+		let storage = RefCell::new(HashMap::new());
+		<CompactBalance<TraitImpl> as StorageValue<u128>>::put(&(1 << 64), &storage);
+		let key = <CompactBalance<TraitImpl> as StorageValue<u128>>::key();
+		assert_eq!(storage.borrow().get(key), Some(&vec![0b0001_0111, 0, 0, 0, 0, 0, 0, 0, 0, 1]));
+		assert_eq!(<CompactBalance<TraitImpl> as StorageValue<u128>>::get(&storage), 1 << 64);
+	}
+}
+
+#[cfg(test)]
+mod test_migration {
+	use runtime_io::{with_externalities, TestExternalities};
+	use storage::{OnStorageMigration, StorageValue, StorageVersion};
+
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+			fn on_storage_migration(from: u32);
+		}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Migrating version(2) {
+			Migrations get(migrations): default Vec<u32>;
+		}
+	}
+
+	impl<T: Trait> Module<T> {
+		fn on_storage_migration(from: u32) {
+			<Migrations<T>>::mutate(|m| m.push(from));
+		}
+	}
+
+	struct TraitImpl;
+
+	impl Trait for TraitImpl {
+		type Origin = u32;
+	}
+
+	type Migrating = Module<TraitImpl>;
+
+	#[test]
+	fn storage_migration_runs_once_per_version() {
+		with_externalities(&mut TestExternalities::new(), || {
+			assert_eq!(Migrating::on_chain_storage_version(), 0);
+			<Migrating as OnStorageMigration>::on_storage_migration();
+			<Migrating as OnStorageMigration>::on_storage_migration();
+			assert_eq!(Migrating::migrations(), vec![0]);
+			assert_eq!(Migrating::on_chain_storage_version(), 2);
+
+			Migrating::put_on_chain_storage_version(1);
+			<(Migrating, ()) as OnStorageMigration>::on_storage_migration();
+			assert_eq!(Migrating::migrations(), vec![0, 1]);
+			assert_eq!(Migrating::on_chain_storage_version(), 2);
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_genesis {
+	use runtime_io::{with_externalities, Blake2Hasher, TestExternalities};
+	use runtime_primitives::BuildStorage;
+	use storage::{RuntimeStorage, StorageMap, StorageOrderedSet, StorageVersion};
+	use storage::generator::StorageValue as GenericStorageValue;
+
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Genesis version(1) {
+			Initial get(initial) config(): u32;
+			Balances get(balance_of) config(): map [u64 => u64];
+			Limit get(limit) config(initial_limit): default u32;
+			Threshold config(threshold_at_genesis): u32;
+			Total get(total) build(|_, config| config.balance_of.iter().map(|&(_, b)| b).sum()): default u64;
+			Doubled get(doubled) build(|storage, _| <Initial<T> as GenericStorageValue<u32>>::get(storage).unwrap_or_default() * 2): u32;
+			Extra get(extra): u32;
+			FirstAuthority get(first_authority): u64;
+			AuthorityCount get(authority_count): default u32;
+			Pending get(pending) config(): deque [u64; 4];
+			Members get(members) config(): ordered_set [u64];
+		}
+		add_extra_genesis {
+			/// The initial authorities, kept as the first one and their count.
+			config(authorities): Vec<u64>;
+			build(|storage, config| {
+				<Extra<T> as GenericStorageValue<u32>>::put(&(config.initial + 1), storage);
+				<AuthorityCount<T> as GenericStorageValue<u32>>::put(&(config.authorities.len() as u32), storage);
+				if let Some(first) = config.authorities.first() {
+					<FirstAuthority<T> as GenericStorageValue<u64>>::put(first, storage);
+				}
+			});
+		}
+	}
+
+	struct TraitImpl;
+
+	impl Trait for TraitImpl {
+		type Origin = u32;
+	}
+
+	type Genesis = Module<TraitImpl>;
+
+	#[test]
+	fn genesis_config_builds_items() {
+		let mut ext = GenesisConfig::<TraitImpl> {
+			initial: 5,
+			balance_of: vec![(1, 10), (2, 20)],
+			authorities: vec![7, 8, 9],
+			initial_limit: 100,
+			threshold_at_genesis: 3,
+			pending: vec![4, 2],
+			members: vec![5, 1, 3, 1],
+			..Default::default()
+		}.new_test_ext();
+
+		with_externalities(&mut ext, || {
+			assert_eq!(Genesis::initial(), Some(5));
+			assert_eq!(Genesis::balance_of(1), Some(10));
+			assert_eq!(Genesis::balance_of(2), Some(20));
+			assert_eq!(Genesis::total(), 30);
+			assert_eq!(Genesis::doubled(), Some(10));
+			assert_eq!(Genesis::extra(), Some(6));
+			assert_eq!(Genesis::first_authority(), Some(7));
+			assert_eq!(Genesis::limit(), 100);
+			assert_eq!(<Threshold<TraitImpl> as GenericStorageValue<u32>>::get(&RuntimeStorage), Some(3));
+			assert_eq!(Genesis::authority_count(), 3);
+			assert_eq!(Genesis::on_chain_storage_version(), 1);
+			assert!(<Balances<TraitImpl>>::exists(2));
+			assert_eq!(Genesis::pending(), vec![4, 2]);
+			assert_eq!(Genesis::members(), vec![1, 3, 5]);
+
+			assert!(Genesis::initial_exists());
+			assert!(Genesis::balance_of_exists(1));
+			assert!(!Genesis::balance_of_exists(3));
+		});
+	}
+
+	#[test]
+	fn exists_does_not_fall_back_to_the_default() {
+		with_externalities(&mut TestExternalities::<Blake2Hasher>::default(), || {
+			assert_eq!(Genesis::limit(), 0);
+			assert!(!Genesis::limit_exists());
+			<Limit<TraitImpl> as GenericStorageValue<u32>>::put(&0, &RuntimeStorage);
+			assert!(Genesis::limit_exists());
+		});
+	}
+
+	#[test]
+	fn ordered_set_keeps_items_sorted() {
+		with_externalities(&mut TestExternalities::<Blake2Hasher>::default(), || {
+			assert!(<Members<TraitImpl>>::insert(4));
+			assert!(<Members<TraitImpl>>::insert(2));
+			assert!(<Members<TraitImpl>>::insert(8));
+			assert!(<Members<TraitImpl>>::insert(6));
+			assert!(!<Members<TraitImpl>>::insert(4));
+			assert_eq!(Genesis::members(), vec![2, 4, 6, 8]);
+
+			assert!(<Members<TraitImpl>>::contains(6));
+			assert!(!<Members<TraitImpl>>::contains(5));
+			assert_eq!(<Members<TraitImpl>>::rank_of(8), Some(3));
+			assert_eq!(<Members<TraitImpl>>::get(0), Some(2));
+
+			assert!(<Members<TraitImpl>>::remove(4));
+			assert!(!<Members<TraitImpl>>::remove(4));
+			assert_eq!(Genesis::members(), vec![2, 6, 8]);
+			assert_eq!(<Members<TraitImpl>>::get(3), None);
+			assert_eq!(<Members<TraitImpl>>::len(), 3);
+
+			<Members<TraitImpl>>::clear();
+			assert!(Genesis::members().is_empty());
+		});
+	}
+
+	#[test]
+	fn genesis_config_assimilates_storage() {
+		let config = || GenesisConfig::<TraitImpl> { initial: 1, balance_of: vec![(3, 30)], ..Default::default() };
+		let mut storage = ::std::collections::HashMap::new();
+		storage.insert(b"other".to_vec(), vec![1]);
+		config().assimilate_storage(&mut storage).unwrap();
+		// Values equal to those already there are no conflict.
+		config().assimilate_storage(&mut storage).unwrap();
+
+		let before = storage.clone();
+		let error = GenesisConfig::<TraitImpl> { initial: 2, balance_of: vec![(4, 40)], ..Default::default() }
+			.assimilate_storage(&mut storage)
+			.unwrap_err();
+		assert!(error.starts_with("conflicting genesis values for storage key 0x"));
+		assert_eq!(storage, before);
+
+		assert_eq!(storage.get(&b"other"[..]), Some(&vec![1]));
+		with_externalities(&mut TestExternalities::<Blake2Hasher>::from(storage), || {
+			assert_eq!(Genesis::initial(), Some(1));
+			assert_eq!(Genesis::balance_of(3), Some(30));
+			assert_eq!(Genesis::balance_of(4), None);
+		});
+	}
+
+	#[test]
+	fn genesis_config_refuses_more_items_than_a_deque_holds() {
+		let config = GenesisConfig::<TraitImpl> {
+			pending: vec![0; 5],
+			..Default::default()
+		};
+		assert!(config.build_storage().is_err());
+	}
+
+	#[test]
+	fn genesis_config_serializes_camel_case() {
+		let config: GenesisConfig<TraitImpl> = ::serde_json::from_str(r#"{ "initial": 1, "balanceOf": [[3, 4]], "initialLimit": 5, "thresholdAtGenesis": 2, "pending": [], "members": [], "authorities": [] }"#).unwrap();
+		assert_eq!(config.initial, 1);
+		assert_eq!(config.balance_of, vec![(3, 4)]);
+		assert!(config.authorities.is_empty());
+		assert_eq!(config.initial_limit, 5);
+		assert_eq!(config.threshold_at_genesis, 2);
+		assert!(::serde_json::from_str::<GenesisConfig<TraitImpl>>(r#"{ "unknown": 1 }"#).is_err());
+	}
+}
+
+#[cfg(test)]
+mod test_instance {
+	use runtime_io::{twox_128, with_externalities};
+	use storage::{EnumerableStorageMap, StorageMap, StoragePrefixedMap};
+	use storage::generator::{StorageChildMap, StorageValue};
+
+	pub trait Trait<I: Instance> {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait<I>, I: Instance> for enum Call where origin: T::Origin {}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait<I>, I: Instance> as Instanced {
+			Total get(total) config(): u64;
+			Balances get(balance_of): linked_map [u64 => u64];
+			Children get(child): child(b":child:instanced") map [u32 => u32];
+		}
+	}
+
+	struct Runtime;
+
+	impl Trait<DefaultInstance> for Runtime {
+		type Origin = u32;
+	}
+
+	impl Trait<Instance1> for Runtime {
+		type Origin = u32;
+	}
+
+	impl Trait<Instance2> for Runtime {
+		type Origin = u32;
+	}
+
+	type First = Module<Runtime, Instance1>;
+	type Second = Module<Runtime, Instance2>;
+
+	#[test]
+	fn instances_have_their_own_keys() {
+		let final_key = |module: &[u8], item: &[u8]| [twox_128(module), twox_128(item)].concat();
+		assert_eq!(<Total<Runtime, DefaultInstance> as StorageValue<u64>>::key(), &final_key(b"Instanced", b"Total")[..]);
+		assert_eq!(<Total<Runtime, Instance1> as StorageValue<u64>>::key(), &final_key(b"Instance1Instanced", b"Total")[..]);
+		assert_eq!(<Balances<Runtime, Instance1> as StoragePrefixedMap>::module_prefix(), b"Instance1Instanced");
+		assert_eq!(<Balances<Runtime, Instance1> as StoragePrefixedMap>::storage_prefix(), b"Balances");
+		assert_eq!(<Balances<Runtime, Instance1> as StoragePrefixedMap>::final_prefix(), &final_key(b"Instance1Instanced", b"Balances")[..]);
+		assert_eq!(<Children<Runtime, Instance2> as StorageChildMap<u32, u32>>::child_key(), b"Instance2:child:instanced");
+	}
+
+	#[test]
+	fn instances_keep_storage_apart() {
+		let mut ext = GenesisConfig::<Runtime, Instance2> { total: 5, ..Default::default() }.new_test_ext();
+		with_externalities(&mut ext, || {
+			assert_eq!(First::total(), None);
+			assert_eq!(Second::total(), Some(5));
+
+			<Balances<Runtime, Instance1>>::insert(1, 10);
+			<Balances<Runtime, Instance2>>::insert(2, 20);
+			assert_eq!(First::balance_of(1), Some(10));
+			assert_eq!(Second::balance_of(1), None);
+			assert_eq!(<Balances<Runtime, Instance2>>::enumerate().collect::<Vec<_>>(), vec![(2, 20)]);
+			assert_eq!(<Balances<Runtime, Instance1>>::iter_keys().collect::<Vec<_>>(), vec![1]);
+			assert_eq!(<Balances<Runtime, Instance2>>::drain().collect::<Vec<_>>(), vec![(2, 20)]);
+			assert_eq!(Second::balance_of(2), None);
+			assert_eq!(First::balance_of(1), Some(10));
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_query_kind {
+	use runtime_io::{with_externalities, TestExternalities};
+	use storage::{StorageMap, StorageValue};
+	use Get;
+
+	pub trait Trait {
+		type Origin;
+		type ExistentialDeposit: Get<u64>;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as QueryKinds {
+			Deposit get(deposit): default(T::ExistentialDeposit) u64;
+			Deposits get(deposit_of): default(T::ExistentialDeposit) map [u32 => u64];
+			Pair get(pair): default (u32, u64);
+		}
+	}
+
+	pub struct ExistentialDeposit;
+
+	impl Get<u64> for ExistentialDeposit {
+		fn get() -> u64 {
+			500
+		}
+	}
+
+	struct Runtime;
+
+	impl Trait for Runtime {
+		type Origin = u32;
+		type ExistentialDeposit = ExistentialDeposit;
+	}
+
+	type QueryKinds = Module<Runtime>;
+
+	storage_items! {
+		Total: b"query:total" => required u64;
+	}
+
+	#[test]
+	fn missing_values_come_from_the_provider() {
+		with_externalities(&mut TestExternalities::new(), || {
+			assert_eq!(QueryKinds::deposit(), 500);
+			assert_eq!(QueryKinds::deposit_of(1), 500);
+			assert_eq!(QueryKinds::pair(), (0, 0));
+
+			<Deposit<Runtime>>::mutate(|d| *d += 1);
+			<Deposits<Runtime>>::insert(1, 10);
+			assert_eq!(QueryKinds::deposit(), 501);
+			assert_eq!(QueryKinds::deposit_of(1), 10);
+
+			assert_eq!(<Deposits<Runtime>>::take(1), 10);
+			assert_eq!(QueryKinds::deposit_of(1), 500);
+		});
+	}
+
+	#[test]
+	#[should_panic(expected = "Required values must be in storage")]
+	fn missing_required_values_panic() {
+		with_externalities(&mut TestExternalities::new(), || {
+			<Total as StorageValue<u64>>::get();
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_bounds {
+	use codec::Codec;
+	use runtime_io::with_externalities;
+	use storage::{StorageMap, StorageValue};
+
+	pub trait Trait {
+		type Origin;
+		type Balance: Codec + Default;
+	}
+
+	pub trait Scale {
+		fn scale(value: u64) -> u64;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait, S: Scale> for enum Call where origin: T::Origin {}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait, S: Scale> where T::Balance: From<u64> as Bounds {
+			Total get(total) config(): T::Balance;
+			Scaled get(scaled) build(|_, config: &GenesisConfig<T, S>| config.deposits.iter().map(|&(k, v)| (k, S::scale(v).into())).collect()): map [u32 => T::Balance];
+		}
+		add_extra_genesis {
+			config(deposits): Vec<(u32, u64)>;
+		}
+	}
+
+	impl<T: Trait, S: Scale> Module<T, S> where T::Balance: From<u64> {
+		fn deposit(who: u32, amount: u64) {
+			<Scaled<T, S>>::insert(who, T::Balance::from(S::scale(amount)));
+		}
+	}
+
+	pub struct Double;
+
+	impl Scale for Double {
+		fn scale(value: u64) -> u64 {
+			value * 2
+		}
+	}
+
+	struct Runtime;
+
+	impl Trait for Runtime {
+		type Origin = u32;
+		type Balance = u128;
+	}
+
+	type Bounds = Module<Runtime, Double>;
+
+	#[test]
+	fn bounded_modules_build_their_genesis() {
+		let mut ext = GenesisConfig::<Runtime, Double> {
+			total: 7,
+			deposits: vec![(1, 10)],
+			..Default::default()
+		}.new_test_ext();
+		with_externalities(&mut ext, || {
+			assert_eq!(Bounds::total(), Some(7));
+			assert_eq!(Bounds::scaled(1), Some(20));
+
+			Bounds::deposit(2, 3);
+			assert_eq!(Bounds::scaled(2), Some(6));
+			<Total<Runtime, Double>>::put(&1);
+			assert_eq!(Bounds::total(), Some(1));
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_bounded {
+	use bounded::{BoundedVec, Get};
+
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	pub struct MaxItems;
+
+	impl Get<u32> for MaxItems {
+		fn get() -> u32 {
+			4
+		}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Bounded version(1) bounded {
+			#[compact] Total get(total): default u64;
+			Items get(items): default BoundedVec<(u32, Option<u16>), MaxItems>;
+			Owners get(owner): map [u32 => [u8; 32]];
+		}
+	}
+
+	struct Runtime;
+
+	impl Trait for Runtime {
+		type Origin = u32;
+	}
+
+	#[test]
+	fn bounded_storage_knows_the_largest_value_of_each_item() {
+		assert_eq!(Module::<Runtime>::storage_max_encoded_lens(), vec![
+			("Total", 9),
+			("Items", 4 + 4 * (4 + 3)),
+			("Owners", 32),
+		]);
+	}
+}
+
+#[cfg(test)]
+mod test_encode_like {
+	use runtime_io::{with_externalities, TestExternalities};
+	use storage::StorageMap;
+
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as EncodeLike {
+			Names get(name): map [String => u32];
+			Blobs get(blob): map hasher(twox_128) [Vec<u8> => u32];
+			LinkedBlobs: linked_map [Vec<u8> => u32];
+		}
+	}
+
+	struct Runtime;
+
+	impl Trait for Runtime {
+		type Origin = u32;
+	}
+
+	type EncodeLike = Module<Runtime>;
+
+	#[test]
+	fn maps_are_read_with_keys_encoding_alike() {
+		with_externalities(&mut TestExternalities::new(), || {
+			<Names<Runtime>>::insert("alice".to_owned(), 1);
+			<Blobs<Runtime>>::insert(b"blob".to_vec(), 2);
+			<LinkedBlobs<Runtime>>::insert(b"linked".to_vec(), 3);
+
+			assert_eq!(EncodeLike::name("alice"), Some(1));
+			assert_eq!(EncodeLike::name(&"alice".to_owned()), Some(1));
+			assert_eq!(EncodeLike::name("bob"), None);
+			assert_eq!(EncodeLike::blob(&b"blob"[..]), Some(2));
+			assert_eq!(EncodeLike::blob(b"blob".to_vec()), Some(2));
+			assert!(<Blobs<Runtime>>::exists(&b"blob"[..]));
+			assert_eq!(<LinkedBlobs<Runtime>>::get(&b"linked"[..]), Some(3));
+			assert_eq!(<Names<Runtime>>::key_for("alice"), <Names<Runtime>>::key_for("alice".to_owned()));
+		});
+	}
+}
+
+#[cfg(test)]
+mod test_serde_attrs {
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	mod hex {
+		use serde::{Deserialize, Deserializer, Serializer};
+
+		pub fn serialize<S: Serializer>(bytes: &Vec<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+			serializer.serialize_str(&bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+		}
+
+		pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+			let hex = String::deserialize(deserializer)?;
+			(0..hex.len()).step_by(2)
+				.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(::serde::de::Error::custom))
+				.collect()
+		}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as SerdeAttrs {
+			#[serde(with = "hex")]
+			Key get(key) config(): Vec<u8>;
+			/// Doc comments and serde attributes mix.
+			#[serde(rename = "limit", default)]
+			Limit get(limit) config(max_limit): default u32;
+		}
+		add_extra_genesis {
+			#[serde(skip)]
+			config(local_only): Vec<u64>;
+		}
+	}
+
+	struct TraitImpl;
+
+	impl Trait for TraitImpl {
+		type Origin = u32;
+	}
+
+	#[test]
+	fn serde_attributes_are_forwarded_to_the_genesis_config() {
+		let config = GenesisConfig::<TraitImpl> {
+			key: vec![0xde, 0xad, 0xbe, 0xef],
+			max_limit: 3,
+			local_only: vec![1],
+			..Default::default()
+		};
+		assert_eq!(::serde_json::to_string(&config).unwrap(), r#"{"key":"deadbeef","limit":3}"#);
+
+		let config: GenesisConfig<TraitImpl> = ::serde_json::from_str(r#"{ "key": "0102" }"#).unwrap();
+		assert_eq!(config.key, vec![1, 2]);
+		assert_eq!(config.max_limit, 0);
+		assert!(config.local_only.is_empty());
+	}
+}
+
+#[cfg(test)]
+mod test_whitelisted {
+	use storage::{StorageValue, WhitelistedStorageKeys};
+
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Whitelisted {
+			#[whitelisted]
+			/// The current block number.
+			Number get(number): default u64;
+			Other get(other): u32;
+			#[whitelisted]
+			EventCount get(event_count): default u32;
+		}
+	}
+
+	struct TraitImpl;
+
+	impl Trait for TraitImpl {
+		type Origin = u32;
+	}
+
+	#[test]
+	fn whitelisted_values_list_their_keys() {
+		assert_eq!(
+			Module::<TraitImpl>::whitelisted_storage_keys(),
+			vec![<Number<TraitImpl>>::key().to_vec(), <EventCount<TraitImpl>>::key().to_vec()],
+		);
+		assert_eq!(
+			<(Module<TraitImpl>, ())>::whitelisted_storage_keys(),
+			Module::<TraitImpl>::whitelisted_storage_keys(),
+		);
+	}
+}
+
+#[cfg(test)]
+mod test_blob {
+	use runtime_io::{self, with_externalities};
+	use storage::StorageValue;
+
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Blobs {
+			Wasm get(wasm) config(): blob;
+			Preimage get(preimage): default blob;
+		}
+	}
+
+	struct TraitImpl;
+
+	impl Trait for TraitImpl {
+		type Origin = u32;
+	}
+
+	type Blobs = Module<TraitImpl>;
+
+	#[test]
+	fn blobs_are_kept_unencoded() {
+		let mut ext = GenesisConfig::<TraitImpl> { wasm: b"\0asm".to_vec(), ..Default::default() }.new_test_ext();
+		with_externalities(&mut ext, || {
+			assert_eq!(runtime_io::storage(<Wasm<TraitImpl>>::key()), Some(b"\0asm".to_vec()));
+			assert_eq!(Blobs::wasm(), Some(b"\0asm".to_vec()));
+			assert_eq!(<Wasm<TraitImpl>>::decode_len(), Some(4));
+
+			assert_eq!(Blobs::preimage(), Vec::<u8>::new());
+			<Preimage<TraitImpl>>::mutate(|p| p.extend_from_slice(b"hash"));
+			assert_eq!(runtime_io::storage(<Preimage<TraitImpl>>::key()), Some(b"hash".to_vec()));
+			assert_eq!(<Preimage<TraitImpl>>::take(), b"hash".to_vec());
+			assert!(!<Preimage<TraitImpl>>::exists());
+		});
+		assert!(Blobs::store_json_metadata().contains(r#""Wasm": { "description": [ ], "modifier": null, "type": "Blob" }"#));
+	}
+}
+
+#[cfg(test)]
+mod test_storage_items_compat {
+	use serde;
+	use serde_json;
+	use runtime_io::{with_externalities, TestExternalities};
+	use storage::{RuntimeStorage, StorageMap, StorageValue};
+	use super::{translate_map_entry, translate_value};
+
+	pub trait Trait {
+		type Origin;
+	}
+
+	decl_module! {
+		pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	}
+
+	decl_storage! {
+		trait Store for Module<T: Trait> as Compat {
+			NewTotal: default u64;
+			NewBalances: map [u32 => u64];
+		}
+	}
+
+	struct TraitImpl;
+
+	impl Trait for TraitImpl {
+		type Origin = u32;
+	}
+
+	storage_items! {
+		Total: b"compat:total" => default u64;
+		pub Balances get(balance_of): b"compat:balances:" => required map [u32 => u64];
+		Pending: b"compat:pending:" => list [Vec<u8>];
+	}
+
+	#[test]
+	fn storage_items_emit_json_metadata() {
+		let metadata = storage_items_json_metadata();
+		assert_eq!(metadata, concat!(
+			r#"{ "prefix": "", "prefixHasher": "identity", "items": { "#,
+				r#""Total": { "description": [ ], "modifier": "default", "type": "u64" }, "#,
+				r#""Balances": { "description": [ ], "modifier": "required", "type": { "key": "u32", "value": "u64", "hasher": "identity", "kind": "map" } }, "#,
+				r#""Pending": { "description": [ ], "modifier": null, "type": { "value": "Vec<u8>", "kind": "list" } }"#,
+			" } }"
+		));
+		let _: serde::de::IgnoredAny = serde_json::from_str(metadata).expect("Is valid json syntax");
+	}
+
+	#[test]
+	fn values_move_to_decl_storage() {
+		with_externalities(&mut TestExternalities::new(), || {
+			Total::put(10);
+			Balances::insert(1, 100);
+			assert_eq!(balance_of(1), 100);
+
+			assert!(translate_value::<u64, Total, NewTotal<TraitImpl>, _>(&RuntimeStorage));
+			assert!(translate_map_entry::<u32, u64, Balances, NewBalances<TraitImpl>, _>(&1, &RuntimeStorage));
+			assert!(!translate_map_entry::<u32, u64, Balances, NewBalances<TraitImpl>, _>(&2, &RuntimeStorage));
+
+			assert!(!Total::exists());
+			assert!(!Balances::exists(1));
+			assert_eq!(<NewTotal<TraitImpl>>::get(), 10);
+			assert_eq!(<NewBalances<TraitImpl>>::get(1), Some(100));
+		});
+	}
 }
@@ -20,9 +20,13 @@ use rstd::prelude::*;
 use rstd::borrow::Borrow;
 use runtime_io::{self, twox_128};
 use codec::{Codec, Decode, KeyedVec, Input};
+use dispatch::{DecodeLimit, Weight, MAX_DECODE_DEPTH};
 
 #[macro_use]
 pub mod generator;
+pub mod cache;
+pub mod transactional;
+pub mod well_known_keys;
 
 // TODO: consider using blake256 to avoid possible preimage attack.
 
@@ -31,6 +35,32 @@ struct IncrementalInput<'a> {
 	pos: usize,
 }
 
+/// Compute the key in the state under which the hashed storage API keeps the entry `key`.
+pub fn hashed_key(key: &[u8]) -> [u8; 16] {
+	twox_128(key)
+}
+
+/// Compute the key in the state of a storage item declared with `decl_storage!`, which is
+/// `twox_128(module) ++ twox_128(item)` followed, for map entries, by the key as the hasher of the
+/// map gives it.
+///
+/// - `storage_key!(Item)` gives the key of a storage value;
+/// - `storage_key!(Item => key)` gives the key of the entry under `key` of a storage map.
+///
+/// ```rust,ignore
+/// let free_balance = storage_key!(balances::FreeBalance<Runtime> => alice());
+/// let block_number = storage_key!(system::Number<Runtime>);
+/// ```
+#[macro_export]
+macro_rules! storage_key {
+	($item:ty => $key:expr) => {
+		<$item as $crate::storage::StorageMap<_, _>>::key_for($key)
+	};
+	($item:ty) => {
+		<$item as $crate::storage::StorageValue<_>>::key().to_vec()
+	};
+}
+
 impl<'a> Input for IncrementalInput<'a> {
 	fn read(&mut self, into: &mut [u8]) -> usize {
 		let len = runtime_io::read_storage(self.key, into, self.pos).unwrap_or(0);
@@ -42,14 +72,7 @@ impl<'a> Input for IncrementalInput<'a> {
 
  /// Return the value of the item in storage under `key`, or `None` if there is no explicit entry.
 pub fn get<T: Codec + Sized>(key: &[u8]) -> Option<T> {
-	let key = twox_128(key);
-	runtime_io::read_storage(&key[..], &mut [0; 0][..], 0).map(|_| {
-		let mut input = IncrementalInput {
-			key: &key[..],
-			pos: 0,
-		};
-		Decode::decode(&mut input).expect("storage is not null, therefore must be a valid type")
-	})
+	unhashed::get(&twox_128(key)[..])
 }
 
 /// Return the value of the item in storage under `key`, or the type's default if there is no
@@ -72,7 +95,7 @@ pub fn get_or_else<T: Codec + Sized, F: FnOnce() -> T>(key: &[u8], default_value
 
 /// Put `value` in storage under `key`.
 pub fn put<T: Codec>(key: &[u8], value: &T) {
-	value.using_encoded(|slice| runtime_io::set_storage(&twox_128(key)[..], slice));
+	unhashed::put(&twox_128(key)[..], value)
 }
 
 /// Remove `key` from storage, returning its value if it had an explicit entry or `None` otherwise.
@@ -104,50 +127,156 @@ pub fn take_or_else<T: Codec + Sized, F: FnOnce() -> T>(key: &[u8], default_valu
 
 /// Check to see if `key` has an explicit entry in storage.
 pub fn exists(key: &[u8]) -> bool {
-	runtime_io::exists_storage(&twox_128(key)[..])
+	unhashed::exists(&twox_128(key)[..])
 }
 
 /// Ensure `key` has no explicit entry in storage.
 pub fn kill(key: &[u8]) {
-	runtime_io::clear_storage(&twox_128(key)[..]);
+	unhashed::kill(&twox_128(key)[..])
 }
 
 /// Get a Vec of bytes from storage.
 pub fn get_raw(key: &[u8]) -> Option<Vec<u8>> {
-	runtime_io::storage(&twox_128(key)[..])
+	unhashed::get_raw(&twox_128(key)[..])
 }
 
 /// Put a raw byte slice into storage.
 pub fn put_raw(key: &[u8], value: &[u8]) {
-	runtime_io::set_storage(&twox_128(key)[..], value)
+	unhashed::put_raw(&twox_128(key)[..], value)
 }
 
-/// The underlying runtime storage.
+/// The underlying runtime storage, as seen by the storage items generated by `decl_storage!` and
+/// `storage_items!`. Keys are used as they are: the items hash the names they are declared with
+/// into their keys themselves.
 pub struct RuntimeStorage;
 
 impl ::GenericStorage for RuntimeStorage {
 	fn exists(&self, key: &[u8]) -> bool {
-		super::storage::exists(key)
+		unhashed::exists(key)
 	}
 
 	/// Load the bytes of a key from storage. Can panic if the type is incorrect.
 	fn get<T: Codec>(&self, key: &[u8]) -> Option<T> {
-		super::storage::get(key)
+		unhashed::get(key)
+	}
+
+	/// Load the first bytes of the value of a key into `buf`.
+	fn read(&self, key: &[u8], buf: &mut [u8]) -> Option<usize> {
+		if !cache::is_enabled() {
+			return runtime_io::read_storage(key, buf, 0);
+		}
+		unhashed::get_raw(key).map(|v| {
+			let len = ::rstd::cmp::min(v.len(), buf.len());
+			buf[..len].copy_from_slice(&v[..len]);
+			v.len()
+		})
+	}
+
+	/// Load the encoded bytes of a key from storage.
+	fn get_raw(&self, key: &[u8]) -> Option<Vec<u8>> {
+		unhashed::get_raw(key)
 	}
 
 	/// Put a value in under a key.
 	fn put<T: Codec>(&self, key: &[u8], val: &T) {
-		super::storage::put(key, val)
+		unhashed::put(key, val)
+	}
+
+	/// Put encoded bytes in under a key.
+	fn put_raw(&self, key: &[u8], value: &[u8]) {
+		unhashed::put_raw(key, value)
 	}
 
 	/// Remove the bytes of a key from storage.
 	fn kill(&self, key: &[u8]) {
-		super::storage::kill(key)
+		unhashed::kill(key)
+	}
+
+	/// Remove the bytes of all keys starting with `prefix` from storage.
+	fn kill_prefix(&self, prefix: &[u8]) {
+		unhashed::kill_prefix(prefix)
 	}
 
 	/// Take a value from storage, deleting it after reading.
 	fn take<T: Codec>(&self, key: &[u8]) -> Option<T> {
-		super::storage::take(key)
+		unhashed::take(key)
+	}
+
+	/// The smallest key in storage that is greater than `key`.
+	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+		unhashed::next_key(key)
+	}
+}
+
+/// The storage of a genesis block being built, laid out as `RuntimeStorage` lays out the state.
+/// The `GenesisConfig` generated by `decl_storage!` writes its items through it.
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub struct GenesisStorage(::std::cell::RefCell<::runtime_primitives::StorageMap>);
+
+#[cfg(feature = "std")]
+impl GenesisStorage {
+	/// An empty genesis storage.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// The entries written so far, under their keys in the state.
+	pub fn into_inner(self) -> ::runtime_primitives::StorageMap {
+		self.0.into_inner()
+	}
+}
+
+#[cfg(feature = "std")]
+impl ::GenericStorage for GenesisStorage {
+	fn exists(&self, key: &[u8]) -> bool {
+		self.0.borrow().contains_key(key)
+	}
+
+	fn get<T: Codec>(&self, key: &[u8]) -> Option<T> {
+		self.get_raw(key).map(|v| Decode::decode(&mut &v[..]).expect("storage is not null, therefore must be a valid type"))
+	}
+
+	fn read(&self, key: &[u8], buf: &mut [u8]) -> Option<usize> {
+		self.0.borrow().get(key).map(|v| {
+			let len = ::rstd::cmp::min(v.len(), buf.len());
+			buf[..len].copy_from_slice(&v[..len]);
+			v.len()
+		})
+	}
+
+	fn get_raw(&self, key: &[u8]) -> Option<Vec<u8>> {
+		self.0.borrow().get(key).cloned()
+	}
+
+	fn put<T: Codec>(&self, key: &[u8], val: &T) {
+		self.put_raw(key, &val.encode()[..])
+	}
+
+	fn put_raw(&self, key: &[u8], value: &[u8]) {
+		self.0.borrow_mut().insert(key.to_vec(), value.to_vec());
+	}
+
+	fn kill(&self, key: &[u8]) {
+		self.0.borrow_mut().remove(key);
+	}
+
+	fn kill_prefix(&self, prefix: &[u8]) {
+		unhashed::generator::UnhashedStorage::kill_prefix(self, prefix)
+	}
+
+	fn put_batch(&self, changes: &[(Vec<u8>, Option<Vec<u8>>)]) {
+		let mut storage = self.0.borrow_mut();
+		for &(ref key, ref value) in changes {
+			match *value {
+				Some(ref value) => storage.insert(key.clone(), value.clone()),
+				None => storage.remove(key),
+			};
+		}
+	}
+
+	fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+		unhashed::generator::UnhashedStorage::next_storage_key(self, key)
 	}
 }
 
@@ -156,7 +285,7 @@ pub trait StorageValue<T: Codec> {
 	/// The type that get/take return.
 	type Query;
 
-	/// Get the storage key.
+	/// Get the storage key, under which the value is found in the state.
 	fn key() -> &'static [u8];
 
 	/// Does the value (explicitly) exist in storage?
@@ -168,14 +297,34 @@ pub trait StorageValue<T: Codec> {
 	/// Store a value under this key into the provided storage instance.
 	fn put<Arg: Borrow<T>>(val: Arg);
 
-	/// Mutate the value
-	fn mutate<F: FnOnce(&mut Self::Query)>(f: F);
+	/// Mutate the value, returning the result of `f`.
+	fn mutate<R, F: FnOnce(&mut Self::Query) -> R>(f: F) -> R;
+
+	/// Mutate the value, storing the result only if `f` returns `Ok`. The value is left
+	/// untouched if it returns an error, which is passed on.
+	fn try_mutate<R, E, F: FnOnce(&mut Self::Query) -> Result<R, E>>(f: F) -> Result<R, E>;
 
 	/// Clear the storage value.
 	fn kill();
 
 	/// Take a value from storage, removing it afterwards.
 	fn take() -> Self::Query;
+
+	/// Load the encoded value without decoding it.
+	fn get_raw() -> Option<Vec<u8>>;
+
+	/// Store an already encoded value.
+	fn put_raw(value: &[u8]);
+
+	/// Read the length of the value without decoding it, `None` if it's not in storage.
+	fn decode_len() -> Option<u32> where T: generator::DecodeLength;
+
+	/// Replace the value, kept in the encoding of `O`, with the one `f` maps it to. `f` is given
+	/// `None` if there is no value and the value is removed if it returns `None`.
+	///
+	/// Fails, leaving the storage untouched, if the value cannot be decoded as an `O`. This is
+	/// meant for migrating a value whose type changed across a runtime upgrade.
+	fn translate<O: Decode, F: FnOnce(Option<O>) -> Option<T>>(f: F) -> Result<Option<T>, &'static str>;
 }
 
 impl<T: Codec, U> StorageValue<T> for U where U: generator::StorageValue<T> {
@@ -193,19 +342,35 @@ impl<T: Codec, U> StorageValue<T> for U where U: generator::StorageValue<T> {
 	fn put<Arg: Borrow<T>>(val: Arg) {
 		U::put(val.borrow(), &RuntimeStorage)
 	}
-	fn mutate<F: FnOnce(&mut Self::Query)>(f: F) {
+	fn mutate<R, F: FnOnce(&mut Self::Query) -> R>(f: F) -> R {
 		U::mutate(f, &RuntimeStorage)
 	}
+	fn try_mutate<R, E, F: FnOnce(&mut Self::Query) -> Result<R, E>>(f: F) -> Result<R, E> {
+		U::try_mutate(f, &RuntimeStorage)
+	}
 	fn kill() {
 		U::kill(&RuntimeStorage)
 	}
 	fn take() -> Self::Query {
 		U::take(&RuntimeStorage)
 	}
+	fn get_raw() -> Option<Vec<u8>> {
+		U::get_raw(&RuntimeStorage)
+	}
+	fn put_raw(value: &[u8]) {
+		U::put_raw(value, &RuntimeStorage)
+	}
+	fn decode_len() -> Option<u32> where T: generator::DecodeLength {
+		U::decode_len(&RuntimeStorage)
+	}
+	fn translate<O: Decode, F: FnOnce(Option<O>) -> Option<T>>(f: F) -> Result<Option<T>, &'static str> {
+		U::translate(f, &RuntimeStorage)
+	}
 }
 
-/// A strongly-typed list in storage.
-pub trait StorageList<T: Codec> {
+/// A strongly-typed vector in storage. Pushing, popping and removing items only touch the
+/// affected indices and the length.
+pub trait StorageVec<T: Codec> {
 	/// Get the prefix key in storage.
 	fn prefix() -> &'static [u8];
 
@@ -227,24 +392,39 @@ pub trait StorageList<T: Codec> {
 	/// Load the value at given index. Returns `None` if the index is out-of-bounds.
 	fn get(index: u32) -> Option<T>;
 
-	/// Load the length of the list
+	/// Load the length of the vector.
 	fn len() -> u32;
 
-	/// Clear the list.
+	/// Append an item.
+	fn push<Arg: Borrow<T>>(val: Arg);
+
+	/// Remove the last item and return it, or `None` if the vector is empty.
+	fn pop() -> Option<T>;
+
+	/// Remove the items from the given length on.
+	fn truncate(len: u32);
+
+	/// Keep only the items for which `f` returns true, in order.
+	fn retain<F: FnMut(&T) -> bool>(f: F);
+
+	/// Remove the item at the given index and return it, replacing it with the last item.
+	fn swap_remove(index: u32) -> Option<T>;
+
+	/// Clear the vector.
 	fn clear();
 }
 
-impl<T: Codec, U> StorageList<T> for U where U: generator::StorageList<T> {
+impl<T: Codec, U> StorageVec<T> for U where U: generator::StorageVec<T> {
 	fn prefix() -> &'static [u8] {
-		<U as generator::StorageList<T>>::prefix()
+		<U as generator::StorageVec<T>>::prefix()
 	}
 
 	fn len_key() -> Vec<u8> {
-		<U as generator::StorageList<T>>::len_key()
+		<U as generator::StorageVec<T>>::len_key()
 	}
 
 	fn key_for(index: u32) -> Vec<u8> {
-		<U as generator::StorageList<T>>::key_for(index)
+		<U as generator::StorageVec<T>>::key_for(index)
 	}
 
 	fn items() -> Vec<T> {
@@ -267,12 +447,201 @@ impl<T: Codec, U> StorageList<T> for U where U: generator::StorageList<T> {
 		U::len(&RuntimeStorage)
 	}
 
+	fn push<Arg: Borrow<T>>(val: Arg) {
+		U::push(val.borrow(), &RuntimeStorage)
+	}
+
+	fn pop() -> Option<T> {
+		U::pop(&RuntimeStorage)
+	}
+
+	fn truncate(len: u32) {
+		U::truncate(len, &RuntimeStorage)
+	}
+
+	fn retain<F: FnMut(&T) -> bool>(f: F) {
+		U::retain(f, &RuntimeStorage)
+	}
+
+	fn swap_remove(index: u32) -> Option<T> {
+		U::swap_remove(index, &RuntimeStorage)
+	}
+
+	fn clear() {
+		U::clear(&RuntimeStorage)
+	}
+}
+
+/// A strongly-typed double-ended queue in storage, holding a bounded number of items. Pushing and
+/// popping only touch the affected item and the positions of the ends.
+pub trait StorageDeque<T: Codec> {
+	/// Get the prefix key in storage.
+	fn prefix() -> &'static [u8];
+
+	/// Get the storage key used to fetch the item at a given position.
+	fn key_for(position: u32) -> Vec<u8>;
+
+	/// The maximum number of items.
+	fn capacity() -> u32;
+
+	/// Load the number of items.
+	fn len() -> u32;
+
+	/// Read out all the items, from front to back.
+	fn items() -> Vec<T>;
+
+	/// Load the front item, `None` if the queue is empty.
+	fn front() -> Option<T>;
+
+	/// Load the back item, `None` if the queue is empty.
+	fn back() -> Option<T>;
+
+	/// Append an item at the back. Fails if the queue is full.
+	fn push_back<Arg: Borrow<T>>(val: Arg) -> Result<(), &'static str>;
+
+	/// Prepend an item at the front. Fails if the queue is full.
+	fn push_front<Arg: Borrow<T>>(val: Arg) -> Result<(), &'static str>;
+
+	/// Remove the front item and return it, `None` if the queue is empty.
+	fn pop_front() -> Option<T>;
+
+	/// Remove the back item and return it, `None` if the queue is empty.
+	fn pop_back() -> Option<T>;
+
+	/// Remove all the items.
+	fn clear();
+}
+
+impl<T: Codec, U> StorageDeque<T> for U where U: generator::StorageDeque<T> {
+	fn prefix() -> &'static [u8] {
+		<U as generator::StorageDeque<T>>::prefix()
+	}
+
+	fn key_for(position: u32) -> Vec<u8> {
+		<U as generator::StorageDeque<T>>::key_for(position)
+	}
+
+	fn capacity() -> u32 {
+		<U as generator::StorageDeque<T>>::capacity()
+	}
+
+	fn len() -> u32 {
+		U::len(&RuntimeStorage)
+	}
+
+	fn items() -> Vec<T> {
+		U::items(&RuntimeStorage)
+	}
+
+	fn front() -> Option<T> {
+		U::front(&RuntimeStorage)
+	}
+
+	fn back() -> Option<T> {
+		U::back(&RuntimeStorage)
+	}
+
+	fn push_back<Arg: Borrow<T>>(val: Arg) -> Result<(), &'static str> {
+		U::push_back(val.borrow(), &RuntimeStorage)
+	}
+
+	fn push_front<Arg: Borrow<T>>(val: Arg) -> Result<(), &'static str> {
+		U::push_front(val.borrow(), &RuntimeStorage)
+	}
+
+	fn pop_front() -> Option<T> {
+		U::pop_front(&RuntimeStorage)
+	}
+
+	fn pop_back() -> Option<T> {
+		U::pop_back(&RuntimeStorage)
+	}
+
+	fn clear() {
+		U::clear(&RuntimeStorage)
+	}
+}
+
+/// A strongly-typed set in storage, keeping its items sorted and deduplicated. Checking membership
+/// only reads the few items of a binary search.
+pub trait StorageOrderedSet<T: Codec + Ord> {
+	/// Get the prefix key in storage.
+	fn prefix() -> &'static [u8];
+
+	/// Get the storage key used to fetch the item of a given rank.
+	fn key_for(rank: u32) -> Vec<u8>;
+
+	/// Load the number of items.
+	fn len() -> u32;
+
+	/// Load the item of the given rank, the smallest item having rank zero.
+	fn get(rank: u32) -> Option<T>;
+
+	/// Read out all the items, in order.
+	fn items() -> Vec<T>;
+
+	/// true if the item is in the set.
+	fn contains<Arg: Borrow<T>>(item: Arg) -> bool;
+
+	/// The rank of the item, `None` if it is not in the set.
+	fn rank_of<Arg: Borrow<T>>(item: Arg) -> Option<u32>;
+
+	/// Add an item. Returns false if it was already in the set.
+	fn insert<Arg: Borrow<T>>(item: Arg) -> bool;
+
+	/// Remove an item. Returns false if it was not in the set.
+	fn remove<Arg: Borrow<T>>(item: Arg) -> bool;
+
+	/// Remove all the items.
+	fn clear();
+}
+
+impl<T: Codec + Ord, U> StorageOrderedSet<T> for U where U: generator::StorageOrderedSet<T> {
+	fn prefix() -> &'static [u8] {
+		<U as generator::StorageOrderedSet<T>>::prefix()
+	}
+
+	fn key_for(rank: u32) -> Vec<u8> {
+		<U as generator::StorageOrderedSet<T>>::key_for(rank)
+	}
+
+	fn len() -> u32 {
+		U::len(&RuntimeStorage)
+	}
+
+	fn get(rank: u32) -> Option<T> {
+		U::get(rank, &RuntimeStorage)
+	}
+
+	fn items() -> Vec<T> {
+		U::items(&RuntimeStorage)
+	}
+
+	fn contains<Arg: Borrow<T>>(item: Arg) -> bool {
+		U::contains(item.borrow(), &RuntimeStorage)
+	}
+
+	fn rank_of<Arg: Borrow<T>>(item: Arg) -> Option<u32> {
+		U::rank_of(item.borrow(), &RuntimeStorage)
+	}
+
+	fn insert<Arg: Borrow<T>>(item: Arg) -> bool {
+		U::insert(item.borrow(), &RuntimeStorage)
+	}
+
+	fn remove<Arg: Borrow<T>>(item: Arg) -> bool {
+		U::remove(item.borrow(), &RuntimeStorage)
+	}
+
 	fn clear() {
 		U::clear(&RuntimeStorage)
 	}
 }
 
 /// A strongly-typed map in storage.
+///
+/// Lookups take any key which encodes like `K`, see `EncodeLike`, so that e.g. a map keyed by
+/// `Vec<u8>` can be read with a `&[u8]`. Writes take the key itself.
 pub trait StorageMap<K: Codec, V: Codec> {
 	/// The type that get/take return.
 	type Query;
@@ -280,14 +649,15 @@ pub trait StorageMap<K: Codec, V: Codec> {
 	/// Get the prefix key in storage.
 	fn prefix() -> &'static [u8];
 
-	/// Get the storage key used to fetch a value corresponding to a specific key.
-	fn key_for<KeyArg: Borrow<K>>(key: KeyArg) -> Vec<u8>;
+	/// Get the storage key used to fetch a value corresponding to a specific key, under which the
+	/// value is found in the state.
+	fn key_for<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> Vec<u8>;
 
 	/// Does the value (explicitly) exist in storage?
-	fn exists<KeyArg: Borrow<K>>(key: KeyArg) -> bool;
+	fn exists<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> bool;
 
 	/// Load the value associated with the given key from the map.
-	fn get<KeyArg: Borrow<K>>(key: KeyArg) -> Self::Query;
+	fn get<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> Self::Query;
 
 	/// Store a value to be associated with the given key from the map.
 	fn insert<KeyArg: Borrow<K>, ValArg: Borrow<V>>(key: KeyArg, val: ValArg);
@@ -295,11 +665,34 @@ pub trait StorageMap<K: Codec, V: Codec> {
 	/// Remove the value under a key.
 	fn remove<KeyArg: Borrow<K>>(key: KeyArg);
 
-	/// Mutate the value under a key.
-	fn mutate<KeyArg: Borrow<K>, F: FnOnce(&mut Self::Query)>(key: KeyArg, f: F);
+	/// Mutate the value under a key, returning the result of `f`.
+	fn mutate<KeyArg: Borrow<K>, R, F: FnOnce(&mut Self::Query) -> R>(key: KeyArg, f: F) -> R;
+
+	/// Mutate the value under a key, storing the result only if `f` returns `Ok`. The value is
+	/// left untouched if it returns an error, which is passed on.
+	fn try_mutate<KeyArg: Borrow<K>, R, E, F: FnOnce(&mut Self::Query) -> Result<R, E>>(key: KeyArg, f: F) -> Result<R, E>;
+
+	/// Exchange the values under two keys. If only one of them is in the map, its value is
+	/// moved under the other key.
+	fn swap<KeyArg1: Borrow<K>, KeyArg2: Borrow<K>>(key1: KeyArg1, key2: KeyArg2);
 
 	/// Take the value under a key.
 	fn take<KeyArg: Borrow<K>>(key: KeyArg) -> Self::Query;
+
+	/// Load the encoded value under a key without decoding it.
+	fn get_raw<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> Option<Vec<u8>>;
+
+	/// Store an already encoded value under a key.
+	fn put_raw<KeyArg: Borrow<K>>(key: KeyArg, value: &[u8]);
+
+	/// Read the length of the value under a key without decoding it, `None` if it's not in
+	/// storage.
+	fn decode_len<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> Option<u32> where V: generator::DecodeLength;
+
+	/// Remove all entries of the map. Linked maps walk their list of keys; other maps rely on
+	/// `kill_prefix`, which cannot find the entries of the runtime storage while they are kept
+	/// under the hash of their key.
+	fn remove_all();
 }
 
 impl<K: Codec, V: Codec, U> StorageMap<K, V> for U where U: generator::StorageMap<K, V> {
@@ -309,8 +702,128 @@ impl<K: Codec, V: Codec, U> StorageMap<K, V> for U where U: generator::StorageMa
 		<U as generator::StorageMap<K, V>>::prefix()
 	}
 
-	fn key_for<KeyArg: Borrow<K>>(key: KeyArg) -> Vec<u8> {
-		<U as generator::StorageMap<K, V>>::key_for(key.borrow())
+	fn key_for<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> Vec<u8> {
+		<U as generator::StorageMap<K, V>>::key_for(&key)
+	}
+
+	fn exists<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> bool {
+		U::exists(&key, &RuntimeStorage)
+	}
+
+	fn get<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> Self::Query {
+		U::get(&key, &RuntimeStorage)
+	}
+
+	fn insert<KeyArg: Borrow<K>, ValArg: Borrow<V>>(key: KeyArg, val: ValArg) {
+		U::insert(key.borrow(), val.borrow(), &RuntimeStorage)
+	}
+
+	fn remove<KeyArg: Borrow<K>>(key: KeyArg) {
+		U::remove(key.borrow(), &RuntimeStorage)
+	}
+
+	fn mutate<KeyArg: Borrow<K>, R, F: FnOnce(&mut Self::Query) -> R>(key: KeyArg, f: F) -> R {
+		U::mutate(key.borrow(), f, &RuntimeStorage)
+	}
+
+	fn try_mutate<KeyArg: Borrow<K>, R, E, F: FnOnce(&mut Self::Query) -> Result<R, E>>(key: KeyArg, f: F) -> Result<R, E> {
+		U::try_mutate(key.borrow(), f, &RuntimeStorage)
+	}
+
+	fn swap<KeyArg1: Borrow<K>, KeyArg2: Borrow<K>>(key1: KeyArg1, key2: KeyArg2) {
+		U::swap(key1.borrow(), key2.borrow(), &RuntimeStorage)
+	}
+
+	fn take<KeyArg: Borrow<K>>(key: KeyArg) -> Self::Query {
+		U::take(key.borrow(), &RuntimeStorage)
+	}
+
+	fn get_raw<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> Option<Vec<u8>> {
+		U::get_raw(&key, &RuntimeStorage)
+	}
+
+	fn put_raw<KeyArg: Borrow<K>>(key: KeyArg, value: &[u8]) {
+		U::put_raw(key.borrow(), value, &RuntimeStorage)
+	}
+
+	fn decode_len<KeyArg: generator::EncodeLike<K>>(key: KeyArg) -> Option<u32> where V: generator::DecodeLength {
+		U::decode_len(&key, &RuntimeStorage)
+	}
+
+	fn remove_all() {
+		U::remove_all(&RuntimeStorage)
+	}
+}
+
+/// A strongly-typed map in storage whose entries can be enumerated.
+pub trait EnumerableStorageMap<K: Codec + Clone, V: Codec>: StorageMap<K, V> + Sized {
+	/// The most recently inserted key, `None` if the map is empty.
+	fn head() -> Option<K>;
+
+	/// Iterate over all `(key, value)` pairs of the map, most recently inserted first.
+	fn enumerate() -> generator::Enumerator<'static, RuntimeStorage, K, V, Self>;
+
+	/// Iterate over all keys of the map, most recently inserted first, without reading the values.
+	fn iter_keys() -> generator::KeyEnumerator<'static, RuntimeStorage, K, V, Self>;
+
+	/// Iterate over all `(key, value)` pairs of the map, most recently inserted first, removing
+	/// each of them as it is visited.
+	fn drain() -> generator::Drainer<'static, RuntimeStorage, K, V, Self>;
+}
+
+impl<K: Codec + Clone, V: Codec, U> EnumerableStorageMap<K, V> for U where U: generator::EnumerableStorageMap<K, V> {
+	fn head() -> Option<K> {
+		<U as generator::EnumerableStorageMap<K, V>>::head(&RuntimeStorage)
+	}
+
+	fn enumerate() -> generator::Enumerator<'static, RuntimeStorage, K, V, Self> {
+		<U as generator::EnumerableStorageMap<K, V>>::enumerate(&RuntimeStorage)
+	}
+
+	fn iter_keys() -> generator::KeyEnumerator<'static, RuntimeStorage, K, V, Self> {
+		<U as generator::EnumerableStorageMap<K, V>>::iter_keys(&RuntimeStorage)
+	}
+
+	fn drain() -> generator::Drainer<'static, RuntimeStorage, K, V, Self> {
+		<U as generator::EnumerableStorageMap<K, V>>::drain(&RuntimeStorage)
+	}
+}
+
+/// A strongly-typed map kept in a child storage of its own.
+pub trait StorageChildMap<K: Codec, V: Codec> {
+	/// The type that get/take return.
+	type Query;
+
+	/// Get the key of the child storage.
+	fn child_key() -> &'static [u8];
+
+	/// Does the value (explicitly) exist in storage?
+	fn exists<KeyArg: Borrow<K>>(key: KeyArg) -> bool;
+
+	/// Load the value associated with the given key from the map.
+	fn get<KeyArg: Borrow<K>>(key: KeyArg) -> Self::Query;
+
+	/// Store a value to be associated with the given key from the map.
+	fn insert<KeyArg: Borrow<K>, ValArg: Borrow<V>>(key: KeyArg, val: ValArg);
+
+	/// Remove the value under a key.
+	fn remove<KeyArg: Borrow<K>>(key: KeyArg);
+
+	/// Mutate the value under a key, returning the result of `f`.
+	fn mutate<KeyArg: Borrow<K>, R, F: FnOnce(&mut Self::Query) -> R>(key: KeyArg, f: F) -> R;
+
+	/// Take the value under a key.
+	fn take<KeyArg: Borrow<K>>(key: KeyArg) -> Self::Query;
+
+	/// Remove all entries of the map, killing its child storage.
+	fn remove_all();
+}
+
+impl<K: Codec, V: Codec, U> StorageChildMap<K, V> for U where U: generator::StorageChildMap<K, V> {
+	type Query = U::Query;
+
+	fn child_key() -> &'static [u8] {
+		<U as generator::StorageChildMap<K, V>>::child_key()
 	}
 
 	fn exists<KeyArg: Borrow<K>>(key: KeyArg) -> bool {
@@ -329,86 +842,186 @@ impl<K: Codec, V: Codec, U> StorageMap<K, V> for U where U: generator::StorageMa
 		U::remove(key.borrow(), &RuntimeStorage)
 	}
 
-	fn mutate<KeyArg: Borrow<K>, F: FnOnce(&mut Self::Query)>(key: KeyArg, f: F) {
+	fn mutate<KeyArg: Borrow<K>, R, F: FnOnce(&mut Self::Query) -> R>(key: KeyArg, f: F) -> R {
 		U::mutate(key.borrow(), f, &RuntimeStorage)
 	}
 
 	fn take<KeyArg: Borrow<K>>(key: KeyArg) -> Self::Query {
 		U::take(key.borrow(), &RuntimeStorage)
 	}
+
+	fn remove_all() {
+		U::remove_all(&RuntimeStorage)
+	}
 }
 
-/// A trait to conveniently store a vector of storable data.
-pub trait StorageVec {
-	type Item: Default + Sized + Codec;
-	const PREFIX: &'static [u8];
+/// A strongly-typed map in storage which keeps count of its entries.
+pub trait CountedStorageMap<K: Codec, V: Codec>: StorageMap<K, V> {
+	/// The number of entries in the map.
+	fn count() -> u32;
+}
 
-	/// Get the current set of items.
-	fn items() -> Vec<Self::Item> {
-		(0..Self::count()).into_iter().map(Self::item).collect()
+impl<K: Codec, V: Codec, U> CountedStorageMap<K, V> for U where U: generator::CountedStorageMap<K, V> {
+	fn count() -> u32 {
+		<U as generator::CountedStorageMap<K, V>>::count(&RuntimeStorage)
 	}
+}
 
-	/// Set the current set of items.
-	fn set_items<I, T>(items: I)
-		where
-			I: IntoIterator<Item=T>,
-			T: Borrow<Self::Item>,
-	{
-		let mut count: u32 = 0;
+/// A storage map declared with `decl_storage!`, whose entries are all kept under
+/// `twox_128(module_prefix) ++ twox_128(storage_prefix)`, followed by their own keys.
+pub trait StoragePrefixedMap {
+	/// The name the module is declared with, after the name of its instance if it has one.
+	fn module_prefix() -> &'static [u8];
 
-		for i in items.into_iter() {
-			put(&count.to_keyed_vec(Self::PREFIX), i.borrow());
-			count = count.checked_add(1).expect("exceeded runtime storage capacity");
-		}
+	/// The name of the map.
+	fn storage_prefix() -> &'static [u8];
+
+	/// The prefix of the keys of all entries, `twox_128(module_prefix) ++ twox_128(storage_prefix)`.
+	fn final_prefix() -> &'static [u8];
+}
 
-		Self::set_count(count);
+/// The version of the storage layout of a module, declared with `version(..)` in
+/// `decl_storage!`. The version of the layout the state is in is kept in storage, so that a
+/// migration can tell which layout it finds.
+pub trait StorageVersion {
+	/// The version of the layout declared by the code.
+	fn current_storage_version() -> u32;
+
+	/// The key under which the version of the layout of the state is kept.
+	fn storage_version_key() -> &'static [u8];
+
+	/// The version of the layout the state is in, zero if it was never recorded.
+	fn on_chain_storage_version() -> u32 {
+		unhashed::get_or_default(Self::storage_version_key())
 	}
 
-	/// Push an item.
-	fn push(item: &Self::Item) {
-		let len = Self::count();
-		put(&len.to_keyed_vec(Self::PREFIX), item);
-		Self::set_count(len + 1);
+	/// Record the version of the layout the state is in.
+	fn put_on_chain_storage_version(version: u32) {
+		unhashed::put(Self::storage_version_key(), &version)
 	}
+}
+
+/// Migration of the storage of a module to the layout declared by its code, implemented by
+/// `decl_module!`.
+pub trait OnStorageMigration {
+	/// Migrate the storage if the layout of the state is older than the declared one.
+	fn on_storage_migration() {}
+}
+
+impl OnStorageMigration for () {}
 
-	fn set_item(index: u32, item: &Self::Item) {
-		if index < Self::count() {
-			put(&index.to_keyed_vec(Self::PREFIX), item);
+macro_rules! impl_on_storage_migration_for_tuple {
+	($one:ident,) => {
+		impl<$one: OnStorageMigration> OnStorageMigration for ($one,) {
+			fn on_storage_migration() {
+				$one::on_storage_migration();
+			}
+		}
+	};
+	($first:ident, $($rest:ident,)+) => {
+		impl<$first: OnStorageMigration, $($rest: OnStorageMigration),+> OnStorageMigration for ($first, $($rest),+) {
+			fn on_storage_migration() {
+				$first::on_storage_migration();
+				$($rest::on_storage_migration();)+
+			}
 		}
+		impl_on_storage_migration_for_tuple!($($rest,)+);
 	}
+}
+
+impl_on_storage_migration_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,);
+
+/// Work to be done once, at the start of the first block executed by a new runtime, implemented
+/// by `decl_module!`.
+pub trait OnRuntimeUpgrade {
+	/// Run the upgrade, returning its weight.
+	fn on_runtime_upgrade() -> Weight { 0 }
+}
+
+impl OnRuntimeUpgrade for () {}
 
-	fn clear_item(index: u32) {
-		if index < Self::count() {
-			kill(&index.to_keyed_vec(Self::PREFIX));
+macro_rules! impl_on_runtime_upgrade_for_tuple {
+	($one:ident,) => {
+		impl<$one: OnRuntimeUpgrade> OnRuntimeUpgrade for ($one,) {
+			fn on_runtime_upgrade() -> Weight {
+				$one::on_runtime_upgrade()
+			}
 		}
+	};
+	($first:ident, $($rest:ident,)+) => {
+		impl<$first: OnRuntimeUpgrade, $($rest: OnRuntimeUpgrade),+> OnRuntimeUpgrade for ($first, $($rest),+) {
+			fn on_runtime_upgrade() -> Weight {
+				let weight = $first::on_runtime_upgrade();
+				$( let weight = weight.saturating_add($rest::on_runtime_upgrade()); )+
+				weight
+			}
+		}
+		impl_on_runtime_upgrade_for_tuple!($($rest,)+);
 	}
+}
 
-	fn item(index: u32) -> Self::Item {
-		get_or_default(&index.to_keyed_vec(Self::PREFIX))
-	}
+impl_on_runtime_upgrade_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,);
+
+/// The keys in the state of the storage items of a module marked `#[whitelisted]` in
+/// `decl_storage!`, such as the block number, which are read so often that they are not meant to
+/// be charged for per read. Tuples of modules list the keys of all of them.
+pub trait WhitelistedStorageKeys {
+	/// The whitelisted keys, as found in the state.
+	fn whitelisted_storage_keys() -> Vec<Vec<u8>>;
+}
 
-	fn set_count(count: u32) {
-		(count..Self::count()).for_each(Self::clear_item);
-		put(&b"len".to_keyed_vec(Self::PREFIX), &count);
+impl WhitelistedStorageKeys for () {
+	fn whitelisted_storage_keys() -> Vec<Vec<u8>> {
+		Vec::new()
 	}
+}
 
-	fn count() -> u32 {
-		get_or_default(&b"len".to_keyed_vec(Self::PREFIX))
+macro_rules! impl_whitelisted_storage_keys_for_tuple {
+	($one:ident,) => {
+		impl<$one: WhitelistedStorageKeys> WhitelistedStorageKeys for ($one,) {
+			fn whitelisted_storage_keys() -> Vec<Vec<u8>> {
+				$one::whitelisted_storage_keys()
+			}
+		}
+	};
+	($first:ident, $($rest:ident,)+) => {
+		impl<$first: WhitelistedStorageKeys, $($rest: WhitelistedStorageKeys),+> WhitelistedStorageKeys for ($first, $($rest),+) {
+			fn whitelisted_storage_keys() -> Vec<Vec<u8>> {
+				let mut keys = $first::whitelisted_storage_keys();
+				$(keys.extend($rest::whitelisted_storage_keys());)+
+				keys
+			}
+		}
+		impl_whitelisted_storage_keys_for_tuple!($($rest,)+);
 	}
 }
 
+impl_whitelisted_storage_keys_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,);
+
 pub mod unhashed {
 	use rstd::borrow::Borrow;
-	use super::{runtime_io, Codec, Decode, KeyedVec, Vec, IncrementalInput};
+	use super::{cache, transactional, runtime_io, Codec, Decode, DecodeLimit, KeyedVec, Vec, IncrementalInput, RuntimeStorage};
+	use super::MAX_DECODE_DEPTH;
+	#[cfg(feature = "std")]
+	use super::GenesisStorage;
+
+	pub mod generator;
 
 	/// Return the value of the item in storage under `key`, or `None` if there is no explicit entry.
+	///
+	/// The value is decoded with a limited depth, as it may have been written raw, e.g. with
+	/// `set_storage`, rather than encoded from a value of its type.
 	pub fn get<T: Codec + Sized>(key: &[u8]) -> Option<T> {
+		if cache::is_enabled() {
+			return get_raw(key).map(|v| T::decode_with_depth_limit(MAX_DECODE_DEPTH, &mut &v[..])
+				.expect("storage is not null, therefore must be a valid type"));
+		}
 		runtime_io::read_storage(key, &mut [0; 0][..], 0).map(|_| {
 			let mut input = IncrementalInput {
 				key,
 				pos: 0,
 			};
-			Decode::decode(&mut input).expect("storage is not null, therefore must be a valid type")
+			T::decode_with_depth_limit(MAX_DECODE_DEPTH, &mut input).expect("storage is not null, therefore must be a valid type")
 		})
 	}
 
@@ -432,7 +1045,7 @@ pub mod unhashed {
 
 	/// Put `value` in storage under `key`.
 	pub fn put<T: Codec>(key: &[u8], value: &T) {
-		value.using_encoded(|slice| runtime_io::set_storage(key, slice));
+		value.using_encoded(|slice| put_raw(key, slice));
 	}
 
 	/// Remove `key` from storage, returning its value if it had an explicit entry or `None` otherwise.
@@ -464,27 +1077,274 @@ pub mod unhashed {
 
 	/// Check to see if `key` has an explicit entry in storage.
 	pub fn exists(key: &[u8]) -> bool {
-		runtime_io::read_storage(key, &mut [0;0][..], 0).is_some()
+		match cache::cached(key) {
+			Some(value) => value.is_some(),
+			None => runtime_io::read_storage(key, &mut [0;0][..], 0).is_some(),
+		}
 	}
 
 	/// Ensure `key` has no explicit entry in storage.
 	pub fn kill(key: &[u8]) {
+		transactional::write(key);
 		runtime_io::clear_storage(key);
+		cache::write(key, None);
 	}
 
 	/// Ensure keys with the given `prefix` have no entries in storage.
 	pub fn kill_prefix(prefix: &[u8]) {
+		transactional::kill_prefix(prefix);
 		runtime_io::clear_prefix(prefix);
+		cache::kill_prefix(prefix);
+	}
+
+	/// Return the smallest key in storage that is greater than `key`, or `None` if there is none.
+	pub fn next_key(key: &[u8]) -> Option<Vec<u8>> {
+		runtime_io::next_storage_key(key)
+	}
+
+	impl generator::UnhashedStorage for RuntimeStorage {
+		fn exists(&self, key: &[u8]) -> bool {
+			exists(key)
+		}
+
+		/// Load the bytes of a key from storage. Can panic if the type is incorrect.
+		fn get<T: Codec>(&self, key: &[u8]) -> Option<T> {
+			get(key)
+		}
+
+		/// Put a value in under a key.
+		fn put<T: Codec>(&self, key: &[u8], val: &T) {
+			put(key, val)
+		}
+
+		/// Remove the bytes of a key from storage.
+		fn kill(&self, key: &[u8]) {
+			kill(key)
+		}
+
+		/// Remove the bytes of all keys starting with `prefix` from storage.
+		fn kill_prefix(&self, prefix: &[u8]) {
+			kill_prefix(prefix)
+		}
+
+		/// The smallest key in storage that is greater than `key`.
+		fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+			next_key(key)
+		}
+
+		/// Take a value from storage, deleting it after reading.
+		fn take<T: Codec>(&self, key: &[u8]) -> Option<T> {
+			take(key)
+		}
+	}
+
+	#[cfg(feature = "std")]
+	impl generator::UnhashedStorage for GenesisStorage {
+		fn exists(&self, key: &[u8]) -> bool {
+			self.0.borrow().contains_key(key)
+		}
+
+		fn get<T: Codec>(&self, key: &[u8]) -> Option<T> {
+			self.0.borrow().get(key).map(|v| Decode::decode(&mut &v[..]).expect("storage is not null, therefore must be a valid type"))
+		}
+
+		fn put<T: Codec>(&self, key: &[u8], val: &T) {
+			self.0.borrow_mut().insert(key.to_vec(), val.encode());
+		}
+
+		fn kill(&self, key: &[u8]) {
+			self.0.borrow_mut().remove(key);
+		}
+
+		fn kill_prefix(&self, prefix: &[u8]) {
+			self.0.borrow_mut().retain(|key, _| !key.starts_with(prefix));
+		}
+
+		fn next_storage_key(&self, key: &[u8]) -> Option<Vec<u8>> {
+			self.0.borrow().keys().filter(|k| &k[..] > key).min().cloned()
+		}
+	}
+
+	/// A strongly-typed map with two keys in storage, kept under unhashed storage keys.
+	pub trait StorageDoubleMap<K1: Codec, K2: Codec, V: Codec> {
+		/// The type that get/take return.
+		type Query;
+
+		/// Get the prefix key in storage.
+		fn prefix() -> &'static [u8];
+
+		/// Get the storage key shared by all entries with the given first key.
+		fn prefix_for<KArg1: Borrow<K1>>(k1: KArg1) -> Vec<u8>;
+
+		/// Get the storage key used to fetch a value corresponding to specific keys.
+		fn key_for<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) -> Vec<u8>;
+
+		/// Does the value (explicitly) exist in storage?
+		fn exists<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) -> bool;
+
+		/// Load the value associated with the given keys from the map.
+		fn get<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) -> Self::Query;
+
+		/// Take the value under the given keys.
+		fn take<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) -> Self::Query;
+
+		/// Store a value to be associated with the given keys from the map.
+		fn insert<KArg1: Borrow<K1>, KArg2: Borrow<K2>, VArg: Borrow<V>>(k1: KArg1, k2: KArg2, val: VArg);
+
+		/// Remove the value under the given keys.
+		fn remove<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2);
+
+		/// Remove all values under the first key.
+		fn remove_prefix<KArg1: Borrow<K1>>(k1: KArg1);
+
+		/// Mutate the value under the given keys.
+		fn mutate<KArg1: Borrow<K1>, KArg2: Borrow<K2>, F: FnOnce(&mut Self::Query)>(k1: KArg1, k2: KArg2, f: F);
+	}
+
+	impl<K1: Codec, K2: Codec, V: Codec, U> StorageDoubleMap<K1, K2, V> for U where U: generator::StorageDoubleMap<K1, K2, V> {
+		type Query = U::Query;
+
+		fn prefix() -> &'static [u8] {
+			<U as generator::StorageDoubleMap<K1, K2, V>>::prefix()
+		}
+
+		fn prefix_for<KArg1: Borrow<K1>>(k1: KArg1) -> Vec<u8> {
+			<U as generator::StorageDoubleMap<K1, K2, V>>::prefix_for(k1.borrow())
+		}
+
+		fn key_for<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) -> Vec<u8> {
+			<U as generator::StorageDoubleMap<K1, K2, V>>::key_for(k1.borrow(), k2.borrow())
+		}
+
+		fn exists<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) -> bool {
+			U::exists(k1.borrow(), k2.borrow(), &RuntimeStorage)
+		}
+
+		fn get<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) -> Self::Query {
+			U::get(k1.borrow(), k2.borrow(), &RuntimeStorage)
+		}
+
+		fn take<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) -> Self::Query {
+			U::take(k1.borrow(), k2.borrow(), &RuntimeStorage)
+		}
+
+		fn insert<KArg1: Borrow<K1>, KArg2: Borrow<K2>, VArg: Borrow<V>>(k1: KArg1, k2: KArg2, val: VArg) {
+			U::insert(k1.borrow(), k2.borrow(), val.borrow(), &RuntimeStorage)
+		}
+
+		fn remove<KArg1: Borrow<K1>, KArg2: Borrow<K2>>(k1: KArg1, k2: KArg2) {
+			U::remove(k1.borrow(), k2.borrow(), &RuntimeStorage)
+		}
+
+		fn remove_prefix<KArg1: Borrow<K1>>(k1: KArg1) {
+			U::remove_prefix(k1.borrow(), &RuntimeStorage)
+		}
+
+		fn mutate<KArg1: Borrow<K1>, KArg2: Borrow<K2>, F: FnOnce(&mut Self::Query)>(k1: KArg1, k2: KArg2, f: F) {
+			U::mutate(k1.borrow(), k2.borrow(), f, &RuntimeStorage)
+		}
+	}
+
+	/// A strongly-typed map in storage keyed by a tuple of keys, kept under unhashed storage keys.
+	pub trait StorageNMap<K: generator::KeyTuple, V: Codec> {
+		/// The type that get/take return.
+		type Query;
+
+		/// Get the prefix key in storage.
+		fn prefix() -> &'static [u8];
+
+		/// Get the storage key shared by all entries starting with the given keys.
+		fn prefix_for<P: generator::KeyTuple>(partial: &P) -> Vec<u8> where K: generator::HasKeyPrefix<P>;
+
+		/// Get the storage key used to fetch a value corresponding to specific keys.
+		fn key_for<KArg: Borrow<K>>(key: KArg) -> Vec<u8>;
+
+		/// Does the value (explicitly) exist in storage?
+		fn exists<KArg: Borrow<K>>(key: KArg) -> bool;
+
+		/// Load the value associated with the given keys from the map.
+		fn get<KArg: Borrow<K>>(key: KArg) -> Self::Query;
+
+		/// Take the value under the given keys.
+		fn take<KArg: Borrow<K>>(key: KArg) -> Self::Query;
+
+		/// Store a value to be associated with the given keys from the map.
+		fn insert<KArg: Borrow<K>, VArg: Borrow<V>>(key: KArg, val: VArg);
+
+		/// Remove the value under the given keys.
+		fn remove<KArg: Borrow<K>>(key: KArg);
+
+		/// Remove all values under the given leading keys.
+		fn remove_prefix<P: generator::KeyTuple>(partial: &P) where K: generator::HasKeyPrefix<P>;
+
+		/// Iterate over the values under the given leading keys, in storage key order.
+		fn iter_prefix_values<P: generator::KeyTuple>(partial: &P) -> generator::PrefixValues<'static, RuntimeStorage, V>
+			where K: generator::HasKeyPrefix<P>;
+
+		/// Mutate the value under the given keys.
+		fn mutate<KArg: Borrow<K>, F: FnOnce(&mut Self::Query)>(key: KArg, f: F);
+	}
+
+	impl<K: generator::KeyTuple, V: Codec, U> StorageNMap<K, V> for U where U: generator::StorageNMap<K, V> {
+		type Query = U::Query;
+
+		fn prefix() -> &'static [u8] {
+			<U as generator::StorageNMap<K, V>>::prefix()
+		}
+
+		fn prefix_for<P: generator::KeyTuple>(partial: &P) -> Vec<u8> where K: generator::HasKeyPrefix<P> {
+			<U as generator::StorageNMap<K, V>>::prefix_for(partial)
+		}
+
+		fn key_for<KArg: Borrow<K>>(key: KArg) -> Vec<u8> {
+			<U as generator::StorageNMap<K, V>>::key_for(key.borrow())
+		}
+
+		fn exists<KArg: Borrow<K>>(key: KArg) -> bool {
+			U::exists(key.borrow(), &RuntimeStorage)
+		}
+
+		fn get<KArg: Borrow<K>>(key: KArg) -> Self::Query {
+			U::get(key.borrow(), &RuntimeStorage)
+		}
+
+		fn take<KArg: Borrow<K>>(key: KArg) -> Self::Query {
+			U::take(key.borrow(), &RuntimeStorage)
+		}
+
+		fn insert<KArg: Borrow<K>, VArg: Borrow<V>>(key: KArg, val: VArg) {
+			U::insert(key.borrow(), val.borrow(), &RuntimeStorage)
+		}
+
+		fn remove<KArg: Borrow<K>>(key: KArg) {
+			U::remove(key.borrow(), &RuntimeStorage)
+		}
+
+		fn remove_prefix<P: generator::KeyTuple>(partial: &P) where K: generator::HasKeyPrefix<P> {
+			U::remove_prefix(partial, &RuntimeStorage)
+		}
+
+		fn iter_prefix_values<P: generator::KeyTuple>(partial: &P) -> generator::PrefixValues<'static, RuntimeStorage, V>
+			where K: generator::HasKeyPrefix<P>
+		{
+			U::iter_prefix_values(partial, &RuntimeStorage)
+		}
+
+		fn mutate<KArg: Borrow<K>, F: FnOnce(&mut Self::Query)>(key: KArg, f: F) {
+			U::mutate(key.borrow(), f, &RuntimeStorage)
+		}
 	}
 
 	/// Get a Vec of bytes from storage.
 	pub fn get_raw(key: &[u8]) -> Option<Vec<u8>> {
-		runtime_io::storage(key)
+		cache::get_or_read(key, || runtime_io::storage(key))
 	}
 
 	/// Put a raw byte slice into storage.
 	pub fn put_raw(key: &[u8], value: &[u8]) {
-		runtime_io::set_storage(key, value)
+		transactional::write(key);
+		runtime_io::set_storage(key, value);
+		cache::write(key, Some(value));
 	}
 
 	/// A trait to conveniently store a vector of storable data.
@@ -543,6 +1403,7 @@ pub mod unhashed {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use codec::Encode;
 	use runtime_io::{twox_128, TestExternalities, with_externalities};
 
 	#[test]
@@ -606,4 +1467,23 @@ mod tests {
 			assert_eq!(x, y);
 		});
 	}
+
+	storage_items! {
+		Value: b"a" => u32;
+		Map: b"c:" => map [u32 => u64];
+	}
+
+	#[test]
+	fn storage_key_matches_generated_items() {
+		let mut t = TestExternalities::new();
+		with_externalities(&mut t, || {
+			<Value as StorageValue<_>>::put(42u32);
+			<Map as StorageMap<_, _>>::insert(7u32, 69u64);
+
+			assert_eq!(storage_key!(Value), b"a".to_vec());
+			assert_eq!(runtime_io::storage(&storage_key!(Value)), Some(42u32.encode()));
+			assert_eq!(runtime_io::storage(&storage_key!(Map => 7u32)), Some(69u64.encode()));
+			assert_eq!(runtime_io::storage(&storage_key!(Map => 8u32)), None);
+		});
+	}
 }
@@ -0,0 +1,174 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage transactions, which `decl_module!` wraps the dispatch of each call in.
+//!
+//! While the closure given to `with_transaction` runs, the first write of a key through this
+//! module (`RuntimeStorage`, the `unhashed` functions and the hashed functions) notes the value it
+//! held before. If the closure returns `Err`, the noted values are put back, discarding all of its
+//! writes. Transactions nest: a failed inner one only discards its own writes. As for the cache,
+//! writes made directly with `runtime_io` are not seen, and so are not discarded either.
+
+use rstd::prelude::*;
+use rstd::collections::btree_map::BTreeMap;
+use runtime_io;
+use super::cache;
+
+/// The values held before the transaction by the keys it wrote, `None` for keys which had none.
+type Layer = BTreeMap<Vec<u8>, Option<Vec<u8>>>;
+
+#[cfg(feature = "std")]
+thread_local!(static LAYERS: ::std::cell::RefCell<Vec<Layer>> = ::std::cell::RefCell::new(Vec::new()));
+
+// The runtime is single-threaded.
+#[cfg(not(feature = "std"))]
+static mut LAYERS: Option<Vec<Layer>> = None;
+
+#[cfg(feature = "std")]
+fn with_layers<R, F: FnOnce(&mut Vec<Layer>) -> R>(f: F) -> R {
+	LAYERS.with(|layers| f(&mut layers.borrow_mut()))
+}
+
+#[cfg(not(feature = "std"))]
+fn with_layers<R, F: FnOnce(&mut Vec<Layer>) -> R>(f: F) -> R {
+	unsafe { f(LAYERS.get_or_insert_with(Vec::new)) }
+}
+
+/// Execute `f` in a transaction, keeping its writes if it returns `Ok` and discarding them if it
+/// returns `Err`.
+pub fn with_transaction<R, E, F: FnOnce() -> Result<R, E>>(f: F) -> Result<R, E> {
+	with_layers(|layers| layers.push(Layer::new()));
+	let result = f();
+	let layer = with_layers(|layers| layers.pop()).expect("the layer of this transaction was pushed above; qed");
+
+	if result.is_ok() {
+		// The parent transaction must be able to discard these writes too.
+		with_layers(|layers| if let Some(parent) = layers.last_mut() {
+			for (key, value) in layer {
+				parent.entry(key).or_insert(value);
+			}
+		});
+	} else {
+		for (key, value) in layer {
+			match value {
+				Some(ref value) => runtime_io::set_storage(&key, value),
+				None => runtime_io::clear_storage(&key),
+			}
+			cache::write(&key, value.as_ref().map(|v| &v[..]));
+		}
+	}
+	result
+}
+
+/// Whether a transaction is open.
+pub fn is_open() -> bool {
+	with_layers(|layers| !layers.is_empty())
+}
+
+/// Note that `key` is about to be written, if a transaction is open.
+pub(super) fn write(key: &[u8]) {
+	with_layers(|layers| if let Some(layer) = layers.last_mut() {
+		if !layer.contains_key(key) {
+			layer.insert(key.to_vec(), runtime_io::storage(key));
+		}
+	});
+}
+
+/// Note that the keys starting with `prefix` are about to be killed, if a transaction is open.
+pub(super) fn kill_prefix(prefix: &[u8]) {
+	if !is_open() {
+		return;
+	}
+	write(prefix);
+	let mut key = prefix.to_vec();
+	while let Some(next) = runtime_io::next_storage_key(&key) {
+		if !next.starts_with(prefix) {
+			break;
+		}
+		write(&next);
+		key = next;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime_io::{with_externalities, TestExternalities};
+	use storage::unhashed;
+
+	#[test]
+	fn failed_transactions_discard_their_writes() {
+		with_externalities(&mut TestExternalities::new(), || {
+			unhashed::put(b":a:1", &1u32);
+			unhashed::put(b":a:2", &2u32);
+
+			let result: Result<(), &str> = with_transaction(|| {
+				assert!(is_open());
+				unhashed::put(b":a:1", &10u32);
+				unhashed::put(b":b", &3u32);
+				unhashed::kill_prefix(b":a:");
+				Err("failed")
+			});
+			assert_eq!(result, Err("failed"));
+
+			assert!(!is_open());
+			assert_eq!(unhashed::get(b":a:1"), Some(1u32));
+			assert_eq!(unhashed::get(b":a:2"), Some(2u32));
+			assert!(!unhashed::exists(b":b"));
+		});
+	}
+
+	#[test]
+	fn successful_transactions_keep_their_writes() {
+		with_externalities(&mut TestExternalities::new(), || {
+			let result: Result<u32, ()> = with_transaction(|| {
+				unhashed::put(b":a", &1u32);
+				unhashed::kill(b":missing");
+				Ok(5)
+			});
+			assert_eq!(result, Ok(5));
+			assert_eq!(unhashed::get(b":a"), Some(1u32));
+		});
+	}
+
+	#[test]
+	fn nested_transactions_are_discarded_with_their_parent() {
+		with_externalities(&mut TestExternalities::new(), || {
+			unhashed::put(b":a", &1u32);
+
+			let _: Result<(), ()> = with_transaction(|| {
+				unhashed::put(b":a", &2u32);
+				let _: Result<(), ()> = with_transaction(|| {
+					unhashed::put(b":b", &3u32);
+					Err(())
+				});
+				assert!(!unhashed::exists(b":b"));
+				assert_eq!(unhashed::get(b":a"), Some(2u32));
+
+				let _: Result<(), ()> = with_transaction(|| {
+					unhashed::put(b":a", &4u32);
+					unhashed::put(b":c", &5u32);
+					Ok(())
+				});
+				assert_eq!(unhashed::get(b":c"), Some(5u32));
+				Err(())
+			});
+
+			assert_eq!(unhashed::get(b":a"), Some(1u32));
+			assert!(!unhashed::exists(b":c"));
+		});
+	}
+}
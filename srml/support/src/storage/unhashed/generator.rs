@@ -0,0 +1,255 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Strongly typed wrappers around values kept in storage under the keys they are given, without
+//! further hashing.
+
+use codec;
+use rstd::marker::PhantomData;
+use rstd::vec::Vec;
+use Hashable;
+
+/// Abstraction around storage with unhashed access.
+pub trait UnhashedStorage {
+	/// true if the key exists in storage.
+	fn exists(&self, key: &[u8]) -> bool;
+
+	/// Load the bytes of a key from storage. Can panic if the type is incorrect.
+	fn get<T: codec::Codec>(&self, key: &[u8]) -> Option<T>;
+
+	/// Load the bytes of a key from storage. Can panic if the type is incorrect. Will panic if
+	/// it's not there.
+	fn require<T: codec::Codec>(&self, key: &[u8]) -> T { self.get(key).expect("Required values must be in storage") }
+
+	/// Load the bytes of a key from storage. Can panic if the type is incorrect. The type's
+	/// default is returned if it's not there.
+	fn get_or_default<T: codec::Codec + Default>(&self, key: &[u8]) -> T { self.get(key).unwrap_or_default() }
+
+	/// Load the bytes of a key from storage. Can panic if the type is incorrect. The value built by
+	/// `default_value`, which is only called then, is returned if it's not there.
+	fn get_or_else<T: codec::Codec, F: FnOnce() -> T>(&self, key: &[u8], default_value: F) -> T {
+		self.get(key).unwrap_or_else(default_value)
+	}
+
+	/// Put a value in under a key.
+	fn put<T: codec::Codec>(&self, key: &[u8], val: &T);
+
+	/// Remove the bytes of a key from storage.
+	fn kill(&self, key: &[u8]);
+
+	/// Remove the bytes of all keys starting with `prefix` from storage.
+	fn kill_prefix(&self, prefix: &[u8]);
+
+	/// The smallest key in storage that is greater than `key`. Storages that cannot walk their
+	/// keys in order return `None`.
+	fn next_storage_key(&self, _key: &[u8]) -> Option<Vec<u8>> { None }
+
+	/// Take a value from storage, deleting it after reading.
+	fn take<T: codec::Codec>(&self, key: &[u8]) -> Option<T> {
+		let value = self.get(key);
+		self.kill(key);
+		value
+	}
+
+	/// Take a value from storage, deleting it after reading.
+	fn take_or_panic<T: codec::Codec>(&self, key: &[u8]) -> T { self.take(key).expect("Required values must be in storage") }
+
+	/// Take a value from storage, deleting it after reading.
+	fn take_or_default<T: codec::Codec + Default>(&self, key: &[u8]) -> T { self.take(key).unwrap_or_default() }
+
+	/// Take a value from storage, deleting it after reading. The value built by `default_value`,
+	/// which is only called then, is returned if it's not there.
+	fn take_or_else<T: codec::Codec, F: FnOnce() -> T>(&self, key: &[u8], default_value: F) -> T {
+		self.take(key).unwrap_or_else(default_value)
+	}
+}
+
+/// A strongly-typed map with two keys in storage. All entries sharing the first key can be
+/// removed at once.
+///
+/// The storage key of an entry is the prefix followed by the XX hash of `k1` and the blake2 hash
+/// of `k2`; the latter is meant for keys that may be chosen by untrusted parties.
+pub trait StorageDoubleMap<K1: codec::Codec, K2: codec::Codec, V: codec::Codec> {
+	/// The type that get/take returns.
+	type Query;
+
+	/// Get the prefix key in storage.
+	fn prefix() -> &'static [u8];
+
+	/// Get the storage key shared by all entries with the given first key.
+	fn prefix_for(k1: &K1) -> Vec<u8>;
+
+	/// Get the storage key used to fetch a value corresponding to specific keys.
+	fn key_for(k1: &K1, k2: &K2) -> Vec<u8>;
+
+	/// true if the value is defined in storage.
+	fn exists<S: UnhashedStorage>(k1: &K1, k2: &K2, storage: &S) -> bool {
+		storage.exists(&Self::key_for(k1, k2)[..])
+	}
+
+	/// Load the value associated with the given keys from the map.
+	fn get<S: UnhashedStorage>(k1: &K1, k2: &K2, storage: &S) -> Self::Query;
+
+	/// Take the value under the given keys.
+	fn take<S: UnhashedStorage>(k1: &K1, k2: &K2, storage: &S) -> Self::Query;
+
+	/// Store a value to be associated with the given keys from the map.
+	fn insert<S: UnhashedStorage>(k1: &K1, k2: &K2, val: &V, storage: &S) {
+		storage.put(&Self::key_for(k1, k2)[..], val);
+	}
+
+	/// Remove the value under the given keys.
+	fn remove<S: UnhashedStorage>(k1: &K1, k2: &K2, storage: &S) {
+		storage.kill(&Self::key_for(k1, k2)[..]);
+	}
+
+	/// Remove all values under the first key.
+	fn remove_prefix<S: UnhashedStorage>(k1: &K1, storage: &S) {
+		storage.kill_prefix(&Self::prefix_for(k1)[..]);
+	}
+
+	/// Mutate the value under the given keys.
+	fn mutate<F: FnOnce(&mut Self::Query), S: UnhashedStorage>(k1: &K1, k2: &K2, f: F, storage: &S);
+}
+
+/// A tuple of keys of a `StorageNMap`. Each key is hashed on its own with blake2 and the hashes
+/// are concatenated, so the hashed keys of a leading part of the tuple prefix those of the whole.
+pub trait KeyTuple {
+	/// The concatenated hashes of the keys.
+	fn hashed_key(&self) -> Vec<u8>;
+}
+
+/// Marks `P` as a leading part of the key tuple, which can be used to remove or iterate over all
+/// entries sharing it.
+pub trait HasKeyPrefix<P: KeyTuple>: KeyTuple {}
+
+macro_rules! impl_key_tuple {
+	( $( $k:ident ),+ ) => {
+		impl<$( $k: codec::Codec ),+> KeyTuple for ( $( $k, )+ ) {
+			#[allow(non_snake_case)]
+			fn hashed_key(&self) -> Vec<u8> {
+				let ( $( ref $k, )+ ) = *self;
+				let mut key = Vec::new();
+				$( key.extend(&Hashable::blake2_256($k)[..]); )+
+				key
+			}
+		}
+	}
+}
+
+macro_rules! impl_key_prefix {
+	( ( $( $p:ident ),+ ) => ( $( $k:ident ),+ ) ) => {
+		impl<$( $k: codec::Codec ),+> HasKeyPrefix<( $( $p, )+ )> for ( $( $k, )+ ) {}
+	}
+}
+
+impl_key_tuple!(A);
+impl_key_tuple!(A, B);
+impl_key_tuple!(A, B, C);
+impl_key_tuple!(A, B, C, D);
+
+impl_key_prefix!((A) => (A, B));
+impl_key_prefix!((A) => (A, B, C));
+impl_key_prefix!((A, B) => (A, B, C));
+impl_key_prefix!((A) => (A, B, C, D));
+impl_key_prefix!((A, B) => (A, B, C, D));
+impl_key_prefix!((A, B, C) => (A, B, C, D));
+
+/// A strongly-typed map in storage whose key is a tuple of up to four keys. All entries sharing
+/// a leading part of the key can be removed or iterated over at once.
+///
+/// The storage key of an entry is the prefix followed by the blake2 hash of every key.
+pub trait StorageNMap<K: KeyTuple, V: codec::Codec> {
+	/// The type that get/take returns.
+	type Query;
+
+	/// Get the prefix key in storage.
+	fn prefix() -> &'static [u8];
+
+	/// Get the storage key shared by all entries starting with the given keys.
+	fn prefix_for<P: KeyTuple>(partial: &P) -> Vec<u8> where K: HasKeyPrefix<P>;
+
+	/// Get the storage key used to fetch a value corresponding to specific keys.
+	fn key_for(key: &K) -> Vec<u8>;
+
+	/// true if the value is defined in storage.
+	fn exists<S: UnhashedStorage>(key: &K, storage: &S) -> bool {
+		storage.exists(&Self::key_for(key)[..])
+	}
+
+	/// Load the value associated with the given keys from the map.
+	fn get<S: UnhashedStorage>(key: &K, storage: &S) -> Self::Query;
+
+	/// Take the value under the given keys.
+	fn take<S: UnhashedStorage>(key: &K, storage: &S) -> Self::Query;
+
+	/// Store a value to be associated with the given keys from the map.
+	fn insert<S: UnhashedStorage>(key: &K, val: &V, storage: &S) {
+		storage.put(&Self::key_for(key)[..], val);
+	}
+
+	/// Remove the value under the given keys.
+	fn remove<S: UnhashedStorage>(key: &K, storage: &S) {
+		storage.kill(&Self::key_for(key)[..]);
+	}
+
+	/// Remove all values under the given leading keys.
+	fn remove_prefix<P: KeyTuple, S: UnhashedStorage>(partial: &P, storage: &S) where K: HasKeyPrefix<P> {
+		storage.kill_prefix(&Self::prefix_for(partial)[..]);
+	}
+
+	/// Iterate over the values under the given leading keys, in storage key order.
+	fn iter_prefix_values<'a, P: KeyTuple, S: UnhashedStorage>(partial: &P, storage: &'a S) -> PrefixValues<'a, S, V>
+		where K: HasKeyPrefix<P>
+	{
+		let prefix = Self::prefix_for(partial);
+		PrefixValues {
+			storage,
+			last: prefix.clone(),
+			prefix,
+			_value: PhantomData,
+		}
+	}
+
+	/// Mutate the value under the given keys.
+	fn mutate<F: FnOnce(&mut Self::Query), S: UnhashedStorage>(key: &K, f: F, storage: &S);
+}
+
+/// Iterator over the values stored under a prefix, returned by `StorageNMap::iter_prefix_values`.
+pub struct PrefixValues<'a, S: 'a, V> {
+	storage: &'a S,
+	prefix: Vec<u8>,
+	last: Vec<u8>,
+	_value: PhantomData<V>,
+}
+
+impl<'a, S: UnhashedStorage, V: codec::Codec> Iterator for PrefixValues<'a, S, V> {
+	type Item = V;
+
+	fn next(&mut self) -> Option<V> {
+		loop {
+			let key = self.storage.next_storage_key(&self.last[..])?;
+			if !key.starts_with(&self.prefix[..]) {
+				return None;
+			}
+			let value = self.storage.get(&key[..]);
+			self.last = key;
+			if value.is_some() {
+				return value;
+			}
+		}
+	}
+}
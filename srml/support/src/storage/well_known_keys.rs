@@ -0,0 +1,102 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Typed access to the storage entries which have a meaning outside of the runtime, kept under
+//! the keys of `substrate_primitives::storage::well_known_keys` as they are, without hashing.
+//!
+//! `HeapPages` and `ExtrinsicIndex` implement `StorageValue`; the code is kept as is rather than
+//! encoded, so `Code` only offers raw access.
+
+use rstd::prelude::*;
+use super::generator::{self, GetDefault, OptionQuery};
+use super::unhashed;
+
+pub use primitives::storage::well_known_keys::{CODE, EXTRINSIC_INDEX, HEAP_PAGES};
+
+/// The wasm code of the runtime.
+pub struct Code;
+
+impl Code {
+	/// Load the code.
+	pub fn get() -> Option<Vec<u8>> {
+		unhashed::get_raw(CODE)
+	}
+
+	/// Replace the code, which takes effect from the next block.
+	pub fn put(code: &[u8]) {
+		unhashed::put_raw(CODE, code)
+	}
+
+	/// Whether the code is in storage.
+	pub fn exists() -> bool {
+		unhashed::exists(CODE)
+	}
+}
+
+/// The number of wasm linear memory pages the runtime needs to be executed.
+pub struct HeapPages;
+
+impl generator::StorageValue<u64> for HeapPages {
+	type Query = Option<u64>;
+	type QueryKind = OptionQuery;
+	type OnEmpty = GetDefault;
+
+	fn key() -> &'static [u8] {
+		HEAP_PAGES
+	}
+}
+
+/// The index of the extrinsic being applied, set while the extrinsics of a block are applied.
+///
+/// It used to be the `ExtrinsicIndex` item of the storage of the system module. As it is killed
+/// at the end of every block, nothing is left under the old key to migrate.
+pub struct ExtrinsicIndex;
+
+impl generator::StorageValue<u32> for ExtrinsicIndex {
+	type Query = Option<u32>;
+	type QueryKind = OptionQuery;
+	type OnEmpty = GetDefault;
+
+	fn key() -> &'static [u8] {
+		EXTRINSIC_INDEX
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime_io::{self, with_externalities, TestExternalities};
+	use storage::StorageValue;
+
+	#[test]
+	fn well_known_keys_are_kept_as_they_are() {
+		with_externalities(&mut TestExternalities::new(), || {
+			assert_eq!(Code::get(), None);
+			Code::put(b"\0asm");
+			assert_eq!(runtime_io::storage(b":code"), Some(b"\0asm".to_vec()));
+			assert!(Code::exists());
+
+			HeapPages::put(16);
+			assert_eq!(runtime_io::storage(b":heappages"), Some(vec![16, 0, 0, 0, 0, 0, 0, 0]));
+			assert_eq!(HeapPages::get(), Some(16));
+
+			ExtrinsicIndex::put(2);
+			assert_eq!(ExtrinsicIndex::get(), Some(2));
+			ExtrinsicIndex::kill();
+			assert!(!ExtrinsicIndex::exists());
+		});
+	}
+}
@@ -0,0 +1,385 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The structure of the types the metadata refers to.
+//!
+//! The metadata names the types of calls, storage items, events and constants as they are written
+//! in the modules, e.g. `T::Balance`, which a client can't decode anything with. Each of these
+//! types implements `TypeInfo`, mostly with `#[derive(TypeInfo)]`, and the runtime registers them
+//! all in a `TypeRegistry`, which becomes the `Types` of its metadata: the name of each type, e.g.
+//! `u128` for `T::Balance`, with its structure down to primitive types.
+
+use rstd::prelude::*;
+use rstd::marker::PhantomData;
+use primitives::compact::Compact;
+use primitives::{H160, H256, H512, AuthorityId};
+use runtime_primitives::{Permill, generic};
+use bounded::{BoundedVec, BoundedBTreeMap};
+pub use substrate_metadata::{TypeDef, FieldDef, VariantDef};
+pub use metadata::String;
+
+// `vec!` without `std`.
+macro_rules! list {
+	( $( $item:expr ),* $(,)* ) => {
+		<[_]>::into_vec(Box::new([ $( $item ),* ]))
+	}
+}
+
+/// A type whose structure is known to the metadata.
+pub trait TypeInfo {
+	/// The name of the type in the registry, e.g. `u64`, `Vec<u8>` or `Exposure<H256, u128>`.
+	fn type_name() -> String;
+
+	/// The structure of the type, registering the types it is made of in `registry`.
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef;
+}
+
+/// The types registered for the metadata, in the order they were registered in.
+#[derive(Default)]
+pub struct TypeRegistry {
+	types: Vec<(String, TypeDef)>,
+}
+
+impl TypeRegistry {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register `T` and the types it is made of, returning the name of `T`.
+	pub fn register<T: TypeInfo + ?Sized>(&mut self) -> String {
+		let name = T::type_name();
+		if !self.types.iter().any(|(registered, _)| *registered == name) {
+			// Noted before its structure, which may refer to the type itself.
+			let index = self.types.len();
+			self.types.push((name.clone(), TypeDef::Primitive));
+			self.types[index].1 = T::type_def(self);
+		}
+		name
+	}
+
+	/// Register `T`, written `written` in the metadata of a module, and note it in `written_types`
+	/// unless it is already there.
+	pub fn register_written<T: TypeInfo + ?Sized>(
+		&mut self,
+		written: &'static str,
+		written_types: &mut Vec<(&'static str, String)>,
+	) {
+		if !written_types.iter().any(|(w, _)| *w == written) {
+			let name = self.register::<T>();
+			written_types.push((written, name));
+		}
+	}
+
+	/// The registered types with their structure.
+	pub fn into_types(self) -> Vec<(String, TypeDef)> {
+		self.types
+	}
+}
+
+/// `name<params>`, the name of a generic type.
+pub fn generic_name(name: &str, params: &[String]) -> String {
+	let mut res = String::from(name);
+	res.push('<');
+	join_names(&mut res, params);
+	res.push('>');
+	res
+}
+
+fn join_names(res: &mut String, names: &[String]) {
+	for (i, name) in names.iter().enumerate() {
+		if i > 0 {
+			res.push_str(", ");
+		}
+		res.push_str(name);
+	}
+}
+
+/// The field `name` of type `T`, registering `T`.
+pub fn field<T: TypeInfo + ?Sized>(registry: &mut TypeRegistry, name: Option<&str>) -> FieldDef {
+	FieldDef { name: name.map(Into::into), ty: registry.register::<T>() }
+}
+
+macro_rules! impl_primitive {
+	( $( $t:ty ),* ) => { $(
+		impl TypeInfo for $t {
+			fn type_name() -> String {
+				stringify!($t).into()
+			}
+
+			fn type_def(_: &mut TypeRegistry) -> TypeDef {
+				TypeDef::Primitive
+			}
+		}
+	)* }
+}
+
+impl_primitive!(bool, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl<T: TypeInfo> TypeInfo for Vec<T> {
+	fn type_name() -> String {
+		generic_name("Vec", &[T::type_name()])
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Sequence(registry.register::<T>())
+	}
+}
+
+impl<T: TypeInfo> TypeInfo for Option<T> {
+	fn type_name() -> String {
+		generic_name("Option", &[T::type_name()])
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Enum(list![
+			VariantDef { name: "None".into(), index: 0, fields: list![] },
+			VariantDef { name: "Some".into(), index: 1, fields: list![field::<T>(registry, None)] },
+		])
+	}
+}
+
+impl<T: TypeInfo, E: TypeInfo> TypeInfo for Result<T, E> {
+	fn type_name() -> String {
+		generic_name("Result", &[T::type_name(), E::type_name()])
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Enum(list![
+			VariantDef { name: "Ok".into(), index: 0, fields: list![field::<T>(registry, None)] },
+			VariantDef { name: "Err".into(), index: 1, fields: list![field::<E>(registry, None)] },
+		])
+	}
+}
+
+// Encoded as its UTF-8 bytes.
+impl TypeInfo for String {
+	fn type_name() -> String {
+		"String".into()
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Sequence(registry.register::<u8>())
+	}
+}
+
+// Encoded as the boxed value.
+impl<T: TypeInfo> TypeInfo for Box<T> {
+	fn type_name() -> String {
+		T::type_name()
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		T::type_def(registry)
+	}
+}
+
+// Encoded as the `Vec` of its items; the bound is only checked when decoding.
+impl<T: TypeInfo, S> TypeInfo for BoundedVec<T, S> {
+	fn type_name() -> String {
+		<Vec<T>>::type_name()
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		<Vec<T>>::type_def(registry)
+	}
+}
+
+// Encoded as the `Vec` of its entries in key order.
+impl<K: Ord + TypeInfo, V: TypeInfo, S> TypeInfo for BoundedBTreeMap<K, V, S> {
+	fn type_name() -> String {
+		<Vec<(K, V)>>::type_name()
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		<Vec<(K, V)>>::type_def(registry)
+	}
+}
+
+impl<T: TypeInfo> TypeInfo for Compact<T> {
+	fn type_name() -> String {
+		generic_name("Compact", &[T::type_name()])
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Compact(registry.register::<T>())
+	}
+}
+
+// Encoded as nothing.
+impl<T> TypeInfo for PhantomData<T> {
+	fn type_name() -> String {
+		"()".into()
+	}
+
+	fn type_def(_: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Tuple(list![])
+	}
+}
+
+impl TypeInfo for () {
+	fn type_name() -> String {
+		"()".into()
+	}
+
+	fn type_def(_: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Tuple(list![])
+	}
+}
+
+macro_rules! impl_tuple {
+	( $first:ident, $( $rest:ident, )* ) => {
+		impl<$first: TypeInfo, $( $rest: TypeInfo ),*> TypeInfo for ($first, $( $rest, )*) {
+			fn type_name() -> String {
+				let mut res = String::from("(");
+				join_names(&mut res, &[$first::type_name() $( , $rest::type_name() )*]);
+				res.push(')');
+				res
+			}
+
+			fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+				TypeDef::Tuple(list![registry.register::<$first>() $( , registry.register::<$rest>() )*])
+			}
+		}
+
+		impl_tuple!($( $rest, )*);
+	};
+	() => {};
+}
+
+impl_tuple!(A, B, C, D, E, F, G, H,);
+
+macro_rules! impl_array {
+	( $( $n:expr )* ) => { $(
+		impl<T: TypeInfo> TypeInfo for [T; $n] {
+			fn type_name() -> String {
+				let mut res = String::from("[");
+				res.push_str(&T::type_name());
+				res.push_str(concat!("; ", stringify!($n), "]"));
+				res
+			}
+
+			fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+				TypeDef::Array($n, registry.register::<T>())
+			}
+		}
+	)* }
+}
+
+impl_array!(1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32 64);
+
+macro_rules! impl_fixed_bytes {
+	( $( $t:ident: $n:expr ),* ) => { $(
+		impl TypeInfo for $t {
+			fn type_name() -> String {
+				stringify!($t).into()
+			}
+
+			fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+				TypeDef::Array($n, registry.register::<u8>())
+			}
+		}
+	)* }
+}
+
+impl_fixed_bytes!(H160: 20, H256: 32, H512: 64, AuthorityId: 32);
+
+impl TypeInfo for Permill {
+	fn type_name() -> String {
+		"Permill".into()
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Struct(list![field::<u32>(registry, None)])
+	}
+}
+
+impl<Item: TypeInfo> TypeInfo for generic::Digest<Item> {
+	fn type_name() -> String {
+		generic_name("Digest", &[Item::type_name()])
+	}
+
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Struct(list![field::<Vec<Item>>(registry, Some("logs"))])
+	}
+}
+
+impl<AuthorityId: TypeInfo> TypeInfo for generic::DigestItem<AuthorityId> {
+	fn type_name() -> String {
+		generic_name("DigestItem", &[AuthorityId::type_name()])
+	}
+
+	// Its variants are encoded with indices of their own, not the ones of their declaration.
+	fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+		TypeDef::Enum(list![
+			VariantDef { name: "Other".into(), index: 0, fields: list![field::<Vec<u8>>(registry, None)] },
+			VariantDef {
+				name: "AuthoritiesChange".into(),
+				index: 1,
+				fields: list![field::<Vec<AuthorityId>>(registry, None)],
+			},
+		])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Ledger;
+
+	impl TypeInfo for Ledger {
+		fn type_name() -> String {
+			"Ledger".into()
+		}
+
+		fn type_def(registry: &mut TypeRegistry) -> TypeDef {
+			TypeDef::Struct(vec![
+				field::<Compact<u64>>(registry, Some("total")),
+				field::<Vec<(H256, u64)>>(registry, Some("unlocking")),
+				field::<Option<Box<Ledger>>>(registry, Some("next")),
+			])
+		}
+	}
+
+	#[test]
+	fn types_are_registered_once_with_what_they_are_made_of() {
+		let mut registry = TypeRegistry::new();
+		let mut written = Vec::new();
+		registry.register_written::<Ledger>("StakingLedger<T::Balance>", &mut written);
+		registry.register_written::<u64>("T::Balance", &mut written);
+		registry.register_written::<u64>("T::Balance", &mut written);
+		assert_eq!(written, vec![
+			("StakingLedger<T::Balance>", "Ledger".to_owned()),
+			("T::Balance", "u64".to_owned()),
+		]);
+
+		let names = registry.into_types().into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+		assert_eq!(names, vec![
+			"Ledger", "Compact<u64>", "u64", "Vec<(H256, u64)>", "(H256, u64)", "H256", "u8", "Option<Ledger>",
+		]);
+	}
+
+	#[test]
+	fn containers_are_described() {
+		let mut registry = TypeRegistry::new();
+		assert_eq!(<[u8; 4]>::type_def(&mut registry), TypeDef::Array(4, "u8".into()));
+		assert_eq!(<(u32, bool)>::type_name(), "(u32, bool)");
+		assert_eq!(<Result<u32, ()>>::type_def(&mut registry), TypeDef::Enum(vec![
+			VariantDef { name: "Ok".into(), index: 0, fields: vec![FieldDef { name: None, ty: "u32".into() }] },
+			VariantDef { name: "Err".into(), index: 1, fields: vec![FieldDef { name: None, ty: "()".into() }] },
+		]));
+	}
+}
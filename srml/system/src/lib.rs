@@ -45,17 +45,20 @@ extern crate safe_mix;
 
 use rstd::prelude::*;
 use primitives::traits::{self, CheckEqual, SimpleArithmetic, SimpleBitOps, Zero, One, Bounded,
-	Hash, Member, MaybeDisplay, EnsureOrigin};
-use runtime_support::{StorageValue, StorageMap, Parameter};
+	Hash, Member, MaybeDisplay, SignedExtension};
+use primitives::ApplyError;
+use runtime_support::{StorageValue, StorageMap, StorageDoubleMap, Parameter, Get, TypeInfo};
+use runtime_support::dispatch::{Weight, DispatchResult, DispatchError, GetDispatchInfo, Compact, HasCompact};
+pub use runtime_support::storage::well_known_keys::ExtrinsicIndex;
+pub use runtime_support::origin::{RawOrigin, BadOrigin, ensure_signed, ensure_root, ensure_none, EnsureRoot,
+	EnsureSigned, EnsureNone};
 use safe_mix::TripletMix;
 
-#[cfg(any(feature = "std", test))]
 use rstd::marker::PhantomData;
-#[cfg(any(feature = "std", test))]
-use codec::Encode;
+use codec::{Encode, Decode, Input, Output};
 
 #[cfg(any(feature = "std", test))]
-use runtime_io::{twox_128, TestExternalities, Blake2Hasher};
+use runtime_io::{TestExternalities, Blake2Hasher};
 
 /// Compute the extrinsics root of a list of extrinsics.
 pub fn extrinsics_root<H: Hash, E: codec::Encode>(extrinsics: &[E]) -> H::Output {
@@ -70,8 +73,9 @@ pub fn extrinsics_data_root<H: Hash>(xts: Vec<Vec<u8>>) -> H::Output {
 
 pub trait Trait: Eq + Clone {
 	type Origin: Into<Option<RawOrigin<Self::AccountId>>> + From<RawOrigin<Self::AccountId>>;
-	type Index: Parameter + Member + Default + MaybeDisplay + SimpleArithmetic + Copy;
-	type BlockNumber: Parameter + Member + MaybeDisplay + SimpleArithmetic + Default + Bounded + Copy + rstd::hash::Hash;
+	type Index: Parameter + Member + Default + MaybeDisplay + SimpleArithmetic + Copy + HasCompact;
+	type BlockNumber: Parameter + Member + MaybeDisplay + SimpleArithmetic + Default + Bounded + Copy + rstd::hash::Hash
+		+ HasCompact;
 	type Hash: Parameter + Member + MaybeDisplay + SimpleBitOps + Default + Copy + CheckEqual + rstd::hash::Hash + AsRef<[u8]>;
 	type Hashing: Hash<Output = Self::Hash>;
 	type Digest: Parameter + Member + Default + traits::Digest;
@@ -82,16 +86,33 @@ pub trait Trait: Eq + Clone {
 		Digest = Self::Digest
 	>;
 	type Event: Parameter + Member + From<Event>;
+	/// The largest total weight of the extrinsics of a block.
+	type MaximumBlockWeight: Get<Weight>;
+	/// The `spec_version` of the runtime. The first block executed after it changes runs the
+	/// `OnRuntimeUpgrade` hooks.
+	type SpecVersion: Get<u32>;
+}
+
+/// A `MaximumBlockWeight` fitting a few hundred extrinsics of the default weight.
+pub struct DefaultMaximumBlockWeight;
+
+impl Get<Weight> for DefaultMaximumBlockWeight {
+	fn get() -> Weight {
+		4 * 1024 * 1024
+	}
 }
 
 pub type DigestItemOf<T> = <<T as Trait>::Digest as traits::Digest>::Item;
 
 decl_module! {
-	pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		/// The largest total weight of the extrinsics of a block.
+		const MaximumBlockWeight: Weight = T::MaximumBlockWeight::get();
+	}
 }
 
 /// A phase of a block's execution.
-#[derive(Encode, Decode)]
+#[derive(Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, PartialEq, Eq, Clone, Debug))]
 pub enum Phase {
 	/// Applying an extrinsic.
@@ -100,14 +121,19 @@ pub enum Phase {
 	Finalization,
 }
 
+/// The index of an event in the events of a block.
+pub type EventIndex = u32;
+
 /// Record of an event happening.
-#[derive(Encode, Decode)]
+#[derive(Encode, Decode, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, PartialEq, Eq, Clone, Debug))]
-pub struct EventRecord<E: Parameter + Member> {
+pub struct EventRecord<E: Parameter + Member, T> {
 	/// The phase of the block it happened in.
 	pub phase: Phase,
 	/// The event itself.
 	pub event: E,
+	/// The topics the event is indexed under in `EventTopics`.
+	pub topics: Vec<T>,
 }
 
 /// Event for the system module.
@@ -115,32 +141,11 @@ decl_event!(
 	pub enum Event {
 		/// An extrinsic completed successfully.
 		ExtrinsicSuccess,
-		/// An extrinsic failed.
-		ExtrinsicFailed,
+		/// An extrinsic failed, with the module and error of the failure.
+		ExtrinsicFailed(DispatchError),
 	}
 );
 
-/// Origin for the system module.
-#[derive(PartialEq, Eq, Clone)]
-#[cfg_attr(feature = "std", derive(Debug))]
-pub enum RawOrigin<AccountId> {
-	/// The system itself ordained this dispatch to happen: this is the highest privilege level.
-	Root,
-	/// It is signed by some public key and we provide the AccountId.
-	Signed(AccountId),
-	/// It is signed by nobody but included and agreed upon by the validators anyway: it's "inherently" true.
-	Inherent,
-}
-
-impl<AccountId> From<Option<AccountId>> for RawOrigin<AccountId> {
-	fn from(s: Option<AccountId>) -> RawOrigin<AccountId> {
-		match s {
-			Some(who) => RawOrigin::Signed(who),
-			None => RawOrigin::Inherent,
-		}
-	}
-}
-
 /// Exposed trait-generic origin type.
 pub type Origin<T> = RawOrigin<<T as Trait>::AccountId>;
 
@@ -150,8 +155,12 @@ decl_storage! {
 		pub AccountNonce get(account_nonce): default map [ T::AccountId => T::Index ];
 
 		ExtrinsicCount: u32;
+		/// The total weight of the extrinsics applied so far in the current block.
+		AllExtrinsicsWeight get(all_extrinsics_weight): default Weight;
+		/// The `spec_version` of the runtime which executed the last block, 0 until it is first
+		/// noted.
+		LastSpecVersion get(last_spec_version): default u32;
 		pub BlockHash get(block_hash): required map [ T::BlockNumber => T::Hash ];
-		pub ExtrinsicIndex get(extrinsic_index): u32;
 		ExtrinsicData get(extrinsic_data): required map [ u32 => Vec<u8> ];
 		RandomSeed get(random_seed): required T::Hash;
 		/// The current block number being processed. Set by `execute_block`.
@@ -160,50 +169,27 @@ decl_storage! {
 		ExtrinsicsRoot get(extrinsics_root): required T::Hash;
 		Digest get(digest): default T::Digest;
 
-		Events get(events): default Vec<EventRecord<T::Event>>;
+		Events get(events): default Vec<EventRecord<T::Event, T::Hash>>;
+		/// The number of events in `Events`.
+		EventCount get(event_count): default EventIndex;
+		/// The events of the current block deposited under each topic, as the number of the block
+		/// and the index of the event in `Events`, so that the events of a topic can be found
+		/// without decoding all of them. The first key is always `()`, for all entries to be removed
+		/// at once when a block starts.
+		///
+		/// The block number is kept for clients looking for a topic in the changes of storage over
+		/// several blocks, which tell the blocks where its entry changed but not their numbers.
+		EventTopics get(event_topics): default double_map (), T::Hash => Vec<(T::BlockNumber, EventIndex)>;
 	}
 }
 
-pub struct EnsureRoot<AccountId>(::rstd::marker::PhantomData<AccountId>);
-impl<O: Into<Option<RawOrigin<AccountId>>>, AccountId> EnsureOrigin<O> for EnsureRoot<AccountId> {
-	type Success = ();
-	fn ensure_origin(o: O) -> Result<Self::Success, &'static str> {
-		ensure_root(o)
-	}
-}
-
-/// Ensure that the origin `o` represents a signed extrinsic (i.e. transaction).
-/// Returns `Ok` with the account that signed the extrinsic or an `Err` otherwise.
-pub fn ensure_signed<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<AccountId, &'static str>
-	where OuterOrigin: Into<Option<RawOrigin<AccountId>>>
-{
-	match o.into() {
-		Some(RawOrigin::Signed(t)) => Ok(t),
-		_ => Err("bad origin: expected to be a signed origin"),
-	}
-}
-
-/// Ensure that the origin `o` represents the root. Returns `Ok` or an `Err` otherwise.
-pub fn ensure_root<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<(), &'static str>
-	where OuterOrigin: Into<Option<RawOrigin<AccountId>>>
-{
-	match o.into() {
-		Some(RawOrigin::Root) => Ok(()),
-		_ => Err("bad origin: expected to be a root origin"),
-	}
-}
-
-/// Ensure that the origin `o` represents an unsigned extrinsic. Returns `Ok` or an `Err` otherwise.
-pub fn ensure_inherent<OuterOrigin, AccountId>(o: OuterOrigin) -> Result<(), &'static str>
-	where OuterOrigin: Into<Option<RawOrigin<AccountId>>>
-{
-	match o.into() {
-		Some(RawOrigin::Inherent) => Ok(()),
-		_ => Err("bad origin: expected to be an inherent origin"),
+impl<T: Trait> Module<T> {
+	/// The index of the extrinsic being applied, kept under the well-known key so that it can be
+	/// read from outside of the runtime; `None` outside of the extrinsics of a block.
+	pub fn extrinsic_index() -> Option<u32> {
+		ExtrinsicIndex::get()
 	}
-}
 
-impl<T: Trait> Module<T> {
 	/// Start the execution of a particular block.
 	pub fn initialise(number: &T::BlockNumber, parent_hash: &T::Hash, txs_root: &T::Hash) {
 		// populate environment.
@@ -212,14 +198,17 @@ impl<T: Trait> Module<T> {
 		<BlockHash<T>>::insert(*number - One::one(), parent_hash);
 		<ExtrinsicsRoot<T>>::put(txs_root);
 		<RandomSeed<T>>::put(Self::calculate_random());
-		<ExtrinsicIndex<T>>::put(0u32);
+		ExtrinsicIndex::put(0u32);
 		<Events<T>>::kill();
+		<EventCount<T>>::kill();
+		<EventTopics<T>>::remove_prefix(&());
 	}
 
 	/// Remove temporary "environment" entries in storage.
 	pub fn finalise() -> T::Header {
 		<RandomSeed<T>>::kill();
 		<ExtrinsicCount<T>>::kill();
+		<AllExtrinsicsWeight<T>>::kill();
 
 		let number = <Number<T>>::take();
 		let parent_hash = <ParentHash<T>>::take();
@@ -227,7 +216,7 @@ impl<T: Trait> Module<T> {
 		let extrinsics_root = <ExtrinsicsRoot<T>>::take();
 		let storage_root = T::Hashing::storage_root();
 
-		// <Events<T>> stays to be inspected by the client.
+		// <Events<T>> and <EventTopics<T>> stay to be inspected by the client.
 
 		<T::Header as traits::Header>::new(number, extrinsics_root, storage_root, parent_hash, digest)
 	}
@@ -241,10 +230,28 @@ impl<T: Trait> Module<T> {
 
 	/// Deposits an event onto this block's event record.
 	pub fn deposit_event(event: T::Event) {
-		let phase = <ExtrinsicIndex<T>>::get().map_or(Phase::Finalization, |c| Phase::ApplyExtrinsic(c));
+		Self::deposit_event_indexed(&[], event);
+	}
+
+	/// Deposits an event onto this block's event record, indexed under each of `topics` in
+	/// `EventTopics`. The event is dropped if the block has as many events as an `EventIndex` can
+	/// count.
+	pub fn deposit_event_indexed(topics: &[T::Hash], event: T::Event) {
+		let index = Self::event_count();
+		let count = match index.checked_add(1) {
+			Some(count) => count,
+			None => return,
+		};
+		<EventCount<T>>::put(count);
+
+		let phase = Self::extrinsic_index().map_or(Phase::Finalization, |c| Phase::ApplyExtrinsic(c));
 		let mut events = Self::events();
-		events.push(EventRecord { phase, event });
+		events.push(EventRecord { phase, event, topics: topics.to_vec() });
 		<Events<T>>::put(events);
+
+		for topic in topics {
+			<EventTopics<T>>::mutate(&(), topic, |indices| indices.push((Self::block_number(), index)));
+		}
 	}
 
 	/// Calculate the current block's random seed.
@@ -263,10 +270,10 @@ impl<T: Trait> Module<T> {
 	#[cfg(any(feature = "std", test))]
 	pub fn externalities() -> TestExternalities<Blake2Hasher> {
 		map![
-			twox_128(&<BlockHash<T>>::key_for(T::BlockNumber::zero())).to_vec() => [69u8; 32].encode(),	// TODO: replace with Hash::default().encode
-			twox_128(<Number<T>>::key()).to_vec() => T::BlockNumber::one().encode(),
-			twox_128(<ParentHash<T>>::key()).to_vec() => [69u8; 32].encode(),	// TODO: replace with Hash::default().encode
-			twox_128(<RandomSeed<T>>::key()).to_vec() => T::Hash::default().encode()
+			<BlockHash<T>>::key_for(T::BlockNumber::zero()) => [69u8; 32].encode(),	// TODO: replace with Hash::default().encode
+			<Number<T>>::key().to_vec() => T::BlockNumber::one().encode(),
+			<ParentHash<T>>::key().to_vec() => [69u8; 32].encode(),	// TODO: replace with Hash::default().encode
+			<RandomSeed<T>>::key().to_vec() => T::Hash::default().encode()
 		]
 	}
 
@@ -299,23 +306,52 @@ impl<T: Trait> Module<T> {
 	/// Note what the extrinsic data of the current extrinsic index is. If this is called, then
 	/// ensure `derive_extrinsics` is also called before block-building is completed.
 	pub fn note_extrinsic(encoded_xt: Vec<u8>) {
-		<ExtrinsicData<T>>::insert(<ExtrinsicIndex<T>>::get().unwrap_or_default(), encoded_xt);
+		<ExtrinsicData<T>>::insert(Self::extrinsic_index().unwrap_or_default(), encoded_xt);
 	}
 
 	/// To be called immediately after an extrinsic has been applied.
-	pub fn note_applied_extrinsic(r: &Result<(), &'static str>) {
-		Self::deposit_event(match r {
+	pub fn note_applied_extrinsic(r: &DispatchResult) {
+		Self::deposit_event(match *r {
 			Ok(_) => Event::ExtrinsicSuccess,
-			Err(_) => Event::ExtrinsicFailed,
+			Err(error) => Event::ExtrinsicFailed(error),
 		}.into());
-		<ExtrinsicIndex<T>>::put(<ExtrinsicIndex<T>>::get().unwrap_or_default() + 1u32);
+		ExtrinsicIndex::put(Self::extrinsic_index().unwrap_or_default() + 1u32);
+	}
+
+	/// Whether an extrinsic of the given weight still fits in the current block.
+	pub fn can_apply_weight(weight: Weight) -> bool {
+		Self::all_extrinsics_weight().checked_add(weight).map_or(false, |all| all <= T::MaximumBlockWeight::get())
+	}
+
+	/// To be called before the dispatch of an extrinsic of the given weight, which must fit in the
+	/// block.
+	pub fn note_extrinsic_weight(weight: Weight) {
+		<AllExtrinsicsWeight<T>>::mutate(|all| *all += weight);
+	}
+
+	/// Record the `spec_version` of the runtime executing the current block, returning whether it
+	/// differs from the one which executed the previous block, i.e. whether the runtime has been
+	/// upgraded.
+	pub fn note_spec_version() -> bool {
+		let current = T::SpecVersion::get();
+		if Self::last_spec_version() == current {
+			return false;
+		}
+		<LastSpecVersion<T>>::put(current);
+		true
+	}
+
+	/// To be called with the weight of the work done at the start of the block, before any
+	/// extrinsic, which leaves that much less weight to the extrinsics.
+	pub fn note_initialisation_weight(weight: Weight) {
+		<AllExtrinsicsWeight<T>>::mutate(|all| *all = all.saturating_add(weight));
 	}
 
 	/// To be called immediately after `note_applied_extrinsic` of the last extrinsic of the block
 	/// has been called.
 	pub fn note_finished_extrinsics() {
-		<ExtrinsicCount<T>>::put(<ExtrinsicIndex<T>>::get().unwrap_or_default());
-		<ExtrinsicIndex<T>>::kill();
+		<ExtrinsicCount<T>>::put(Self::extrinsic_index().unwrap_or_default());
+		ExtrinsicIndex::kill();
 	}
 
 	/// Remove all extrinsics data and save the extrinsics trie root.
@@ -326,6 +362,132 @@ impl<T: Trait> Module<T> {
 	}
 }
 
+/// Signed extension checking that the extrinsic is applied no later than the given block, if
+/// it is mortal. The transaction pool doesn't know which block the extrinsic will be in, so only
+/// the check before dispatch is done.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct CheckEra<T: Trait>(pub Option<T::BlockNumber>);
+
+impl<T: Trait> CheckEra<T> {
+	/// An extrinsic valid in any block.
+	pub fn immortal() -> Self {
+		CheckEra(None)
+	}
+
+	/// An extrinsic valid up to block `last`.
+	pub fn mortal(last: T::BlockNumber) -> Self {
+		CheckEra(Some(last))
+	}
+}
+
+impl<T: Trait> Encode for CheckEra<T> {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		self.0.encode_to(dest)
+	}
+}
+
+impl<T: Trait> Decode for CheckEra<T> {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		Decode::decode(input).map(CheckEra)
+	}
+}
+
+impl<T: Trait, Call> SignedExtension<T::AccountId, Call> for CheckEra<T> {
+	fn pre_dispatch(&self, _who: &T::AccountId, _call: &Call, _len: usize) -> Result<(), ApplyError> {
+		match self.0 {
+			Some(last) if <Module<T>>::block_number() > last => Err(ApplyError::Stale),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Signed extension checking that the extrinsic is signed with the current nonce of its sender,
+/// which is incremented before dispatch. The nonce is compact-encoded.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct CheckNonce<T: Trait>(pub T::Index);
+
+impl<T: Trait> Encode for CheckNonce<T> {
+	fn encode_to<W: Output>(&self, dest: &mut W) {
+		Compact(self.0).encode_to(dest)
+	}
+}
+
+impl<T: Trait> Decode for CheckNonce<T> {
+	fn decode<I: Input>(input: &mut I) -> Option<Self> {
+		<Compact<T::Index>>::decode(input).map(|nonce| CheckNonce(nonce.0))
+	}
+}
+
+impl<T: Trait, Call> SignedExtension<T::AccountId, Call> for CheckNonce<T> {
+	fn validate(&self, who: &T::AccountId, _call: &Call, _len: usize) -> Result<(), ApplyError> {
+		let expected = <Module<T>>::account_nonce(who);
+		if self.0 < expected {
+			Err(ApplyError::Stale)
+		} else if self.0 > expected {
+			Err(ApplyError::Future)
+		} else {
+			Ok(())
+		}
+	}
+
+	fn pre_dispatch(&self, who: &T::AccountId, call: &Call, len: usize) -> Result<(), ApplyError> {
+		self.validate(who, call, len)?;
+		<Module<T>>::inc_account_nonce(who);
+		Ok(())
+	}
+}
+
+/// Signed extension checking that the weight of the call still fits in the block, which is then
+/// noted. Unsigned extrinsics are checked too.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+pub struct CheckWeight<T: Trait>(PhantomData<T>);
+
+impl<T: Trait> CheckWeight<T> {
+	pub fn new() -> Self {
+		CheckWeight(PhantomData)
+	}
+}
+
+impl<T: Trait> Encode for CheckWeight<T> {
+	fn encode_to<W: Output>(&self, _dest: &mut W) {}
+}
+
+impl<T: Trait> Decode for CheckWeight<T> {
+	fn decode<I: Input>(_input: &mut I) -> Option<Self> {
+		Some(CheckWeight::new())
+	}
+}
+
+impl<T: Trait, Call: GetDispatchInfo> SignedExtension<T::AccountId, Call> for CheckWeight<T> {
+	fn validate(&self, _who: &T::AccountId, call: &Call, len: usize) -> Result<(), ApplyError> {
+		Self::validate_unsigned(call, len)
+	}
+
+	fn pre_dispatch(&self, _who: &T::AccountId, call: &Call, len: usize) -> Result<(), ApplyError> {
+		Self::pre_dispatch_unsigned(call, len)
+	}
+
+	fn validate_unsigned(call: &Call, _len: usize) -> Result<(), ApplyError> {
+		if <Module<T>>::can_apply_weight(call.get_dispatch_info().weight) {
+			Ok(())
+		} else {
+			Err(ApplyError::FullBlock)
+		}
+	}
+
+	fn pre_dispatch_unsigned(call: &Call, _len: usize) -> Result<(), ApplyError> {
+		let weight = call.get_dispatch_info().weight;
+		if !<Module<T>>::can_apply_weight(weight) {
+			return Err(ApplyError::FullBlock);
+		}
+		<Module<T>>::note_extrinsic_weight(weight);
+		Ok(())
+	}
+}
+
 #[cfg(any(feature = "std", test))]
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -346,11 +508,11 @@ impl<T: Trait> primitives::BuildStorage for GenesisConfig<T>
 		use codec::Encode;
 
 		Ok(map![
-			Self::hash(&<BlockHash<T>>::key_for(T::BlockNumber::zero())).to_vec() => [69u8; 32].encode(),
-			Self::hash(<Number<T>>::key()).to_vec() => 1u64.encode(),
-			Self::hash(<ParentHash<T>>::key()).to_vec() => [69u8; 32].encode(),
-			Self::hash(<RandomSeed<T>>::key()).to_vec() => [0u8; 32].encode(),
-			Self::hash(<ExtrinsicIndex<T>>::key()).to_vec() => [0u8; 4].encode()
+			<BlockHash<T>>::key_for(T::BlockNumber::zero()) => [69u8; 32].encode(),
+			<Number<T>>::key().to_vec() => 1u64.encode(),
+			<ParentHash<T>>::key().to_vec() => [69u8; 32].encode(),
+			<RandomSeed<T>>::key().to_vec() => [0u8; 32].encode(),
+			ExtrinsicIndex::key().to_vec() => [0u8; 4].encode()
 		])
 	}
 }
@@ -380,13 +542,23 @@ mod tests {
 		type AccountId = u64;
 		type Header = Header;
 		type Event = u16;
+		type MaximumBlockWeight = MaximumBlockWeight;
+		type SpecVersion = ();
+	}
+
+	pub struct MaximumBlockWeight;
+
+	impl Get<Weight> for MaximumBlockWeight {
+		fn get() -> Weight {
+			100
+		}
 	}
 
 	impl From<Event> for u16 {
 		fn from(e: Event) -> u16 {
 			match e {
 				Event::ExtrinsicSuccess => 100,
-				Event::ExtrinsicFailed => 101,
+				Event::ExtrinsicFailed(_) => 101,
 			}
 		}
 	}
@@ -406,21 +578,77 @@ mod tests {
 			System::note_finished_extrinsics();
 			System::deposit_event(1u16);
 			System::finalise();
-			assert_eq!(System::events(), vec![EventRecord { phase: Phase::Finalization, event: 1u16 }]);
+			assert_eq!(System::events(), vec![EventRecord { phase: Phase::Finalization, event: 1u16, topics: vec![] }]);
 
 			System::initialise(&2, &[0u8; 32].into(), &[0u8; 32].into());
 			System::deposit_event(42u16);
 			System::note_applied_extrinsic(&Ok(()));
-			System::note_applied_extrinsic(&Err(""));
+			System::note_applied_extrinsic(&Err(DispatchError::new(1, 0)));
 			System::note_finished_extrinsics();
 			System::deposit_event(3u16);
 			System::finalise();
 			assert_eq!(System::events(), vec![
-				EventRecord { phase: Phase::ApplyExtrinsic(0), event: 42u16 },
-				EventRecord { phase: Phase::ApplyExtrinsic(0), event: 100u16 },
-				EventRecord { phase: Phase::ApplyExtrinsic(1), event: 101u16 },
-				EventRecord { phase: Phase::Finalization, event: 3u16 }
+				EventRecord { phase: Phase::ApplyExtrinsic(0), event: 42u16, topics: vec![] },
+				EventRecord { phase: Phase::ApplyExtrinsic(0), event: 100u16, topics: vec![] },
+				EventRecord { phase: Phase::ApplyExtrinsic(1), event: 101u16, topics: vec![] },
+				EventRecord { phase: Phase::Finalization, event: 3u16, topics: vec![] }
+			]);
+		});
+	}
+
+	#[test]
+	fn deposit_event_indexes_topics() {
+		with_externalities(&mut new_test_ext(), || {
+			let (topic1, topic2, topic3): (H256, H256, H256) = ([1u8; 32].into(), [2u8; 32].into(), [3u8; 32].into());
+			System::initialise(&1, &[0u8; 32].into(), &[0u8; 32].into());
+			System::deposit_event_indexed(&[topic3], 7u16);
+			System::finalise();
+
+			System::initialise(&2, &[0u8; 32].into(), &[0u8; 32].into());
+			System::deposit_event(1u16);
+			System::deposit_event_indexed(&[topic1, topic2], 2u16);
+			System::note_finished_extrinsics();
+			System::deposit_event_indexed(&[topic2], 3u16);
+			System::finalise();
+
+			assert_eq!(System::events(), vec![
+				EventRecord { phase: Phase::ApplyExtrinsic(0), event: 1u16, topics: vec![] },
+				EventRecord { phase: Phase::ApplyExtrinsic(0), event: 2u16, topics: vec![topic1, topic2] },
+				EventRecord { phase: Phase::Finalization, event: 3u16, topics: vec![topic2] },
 			]);
+			assert_eq!(System::event_count(), 3);
+			assert_eq!(System::event_topics(&(), &topic1), vec![(2, 1)]);
+			assert_eq!(System::event_topics(&(), &topic2), vec![(2, 1), (2, 2)]);
+			// The topics of the previous block are gone.
+			assert_eq!(System::event_topics(&(), &topic3), vec![]);
+		});
+	}
+	#[test]
+	fn block_weight_is_limited() {
+		with_externalities(&mut new_test_ext(), || {
+			System::initialise(&1, &[0u8; 32].into(), &[0u8; 32].into());
+			assert!(System::can_apply_weight(60));
+			System::note_extrinsic_weight(60);
+			assert!(System::can_apply_weight(40));
+			assert!(!System::can_apply_weight(41));
+			assert!(!System::can_apply_weight(Weight::max_value()));
+			System::note_extrinsic_weight(40);
+			assert_eq!(System::all_extrinsics_weight(), 100);
+
+			System::finalise();
+			assert_eq!(System::all_extrinsics_weight(), 0);
+		});
+	}
+
+	#[test]
+	fn spec_version_changes_are_noted_once() {
+		with_externalities(&mut new_test_ext(), || {
+			assert!(!System::note_spec_version());
+
+			<LastSpecVersion<Test>>::put(7);
+			assert!(System::note_spec_version());
+			assert_eq!(System::last_spec_version(), 0);
+			assert!(!System::note_spec_version());
 		});
 	}
 }
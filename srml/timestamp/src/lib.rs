@@ -24,6 +24,10 @@
 //! before setting the new timestamp, specified by the `tim:block_period`
 //! storage entry.
 //!
+//! The timestamp is an inherent: the extrinsic setting it is created from the time given in the
+//! inherent data under `INHERENT_IDENTIFIER`, and imported blocks are refused if their timestamp is
+//! too far ahead of the local time.
+//!
 //! # Interaction with the system
 //!
 //! ## Finalization
@@ -54,21 +58,30 @@ extern crate srml_consensus as consensus;
 extern crate parity_codec as codec;
 
 use runtime_support::{StorageValue, Parameter};
-use runtime_support::dispatch::Result;
-use runtime_primitives::traits::{OnFinalise, SimpleArithmetic, As, Zero};
-use system::ensure_inherent;
+use runtime_support::dispatch::{Result, HasCompact};
+use runtime_support::inherent::{InherentData, InherentIdentifier, ProvideInherent};
+use runtime_primitives::traits::{SimpleArithmetic, As, Zero};
+
+/// The identifier of the inherent data of this module, the current time.
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"timstap0";
+
+/// How far ahead of the local time the timestamp of an imported block may be.
+const MAX_TIMESTAMP_DRIFT: u64 = 60;
 
 pub trait Trait: consensus::Trait + system::Trait {
 	/// The position of the required timestamp-set extrinsic.
 	const TIMESTAMP_SET_POSITION: u32;
 
 	/// Type used for expressing timestamp.
-	type Moment: Parameter + Default + SimpleArithmetic + As<Self::BlockNumber>;
+	type Moment: Parameter + Default + SimpleArithmetic + As<Self::BlockNumber> + HasCompact;
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-		fn set(origin, now: T::Moment) -> Result;
+		#[require_none]
+		fn set(#[compact] now: T::Moment) -> Result;
+
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
@@ -102,8 +115,7 @@ impl<T: Trait> Module<T> {
 	/// if this call hasn't been invoked by that time.
 	///
 	/// The timestamp should be greater than the previous one by the amount specified by `block_period`.
-	fn set(origin: T::Origin, now: T::Moment) -> Result {
-		ensure_inherent(origin)?;
+	fn set(now: T::Moment) -> Result {
 		assert!(!<Self as Store>::DidUpdate::exists(), "Timestamp must be updated only once in the block");
 		assert!(
 			<system::Module<T>>::extrinsic_index() == Some(T::TIMESTAMP_SET_POSITION),
@@ -126,12 +138,34 @@ impl<T: Trait> Module<T> {
 	}
 }
 
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+impl<T: Trait> Module<T> {
 	fn on_finalise(_n: T::BlockNumber) {
 		assert!(<Self as Store>::DidUpdate::take(), "Timestamp must be updated once in the block");
 	}
 }
 
+impl<T: Trait> ProvideInherent for Module<T> {
+	type Call = Call<T>;
+
+	const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+	fn create_inherent(data: &InherentData) -> Option<Call<T>> {
+		data.get_data(&INHERENT_IDENTIFIER).ok().and_then(|now| now).map(Call::set)
+	}
+
+	fn check_inherent(call: &Call<T>, data: &InherentData) -> Result {
+		let timestamp = match *call {
+			Call::set(ref timestamp) => timestamp.clone(),
+			_ => return Ok(()),
+		};
+		let now: T::Moment = data.get_data(&INHERENT_IDENTIFIER)?.ok_or("no timestamp in the inherent data")?;
+		if timestamp > now + T::Moment::sa(MAX_TIMESTAMP_DRIFT) {
+			return Err("timestamp too far in the future");
+		}
+		Ok(())
+	}
+}
+
 /// Configuration of a genesis block for the timestamp module.
 #[cfg(any(feature = "std", test))]
 #[derive(Serialize, Deserialize)]
@@ -157,8 +191,8 @@ impl<T: Trait> runtime_primitives::BuildStorage for GenesisConfig<T>
 	fn build_storage(self) -> ::std::result::Result<runtime_primitives::StorageMap, String> {
 		use codec::Encode;
 		Ok(map![
-			Self::hash(<BlockPeriod<T>>::key()).to_vec() => self.period.encode(),
-			Self::hash(<Now<T>>::key()).to_vec() => T::Moment::sa(0).encode()
+			<BlockPeriod<T>>::key().to_vec() => self.period.encode(),
+			<Now<T>>::key().to_vec() => T::Moment::sa(0).encode()
 		])
 	}
 }
@@ -189,6 +223,8 @@ mod tests {
 		type AccountId = u64;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+		type SpecVersion = ();
 	}
 	impl consensus::Trait for Test {
 		const NOTE_OFFLINE_POSITION: u32 = 1;
@@ -214,6 +250,18 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn timestamp_is_an_inherent() {
+		let mut data = InherentData::new();
+		assert_eq!(Timestamp::create_inherent(&data), None);
+		data.put_data(INHERENT_IDENTIFIER, &100u64).unwrap();
+		assert_eq!(Timestamp::create_inherent(&data), Some(Call::set(100)));
+
+		assert_ok!(Timestamp::check_inherent(&Call::set(160), &data));
+		assert_eq!(Timestamp::check_inherent(&Call::set(161), &data), Err("timestamp too far in the future"));
+		assert!(Timestamp::check_inherent(&Call::set(100), &InherentData::new()).is_err());
+	}
+
 	#[test]
 	#[should_panic(expected = "Timestamp must be updated only once in the block")]
 	fn double_timestamp_should_fail() {
@@ -42,9 +42,10 @@ extern crate srml_system as system;
 extern crate srml_balances as balances;
 
 use rstd::prelude::*;
-use runtime_support::{StorageValue, StorageMap};
+use runtime_support::{StorageValue, StorageMap, TypeInfo};
 use runtime_support::dispatch::Result;
-use runtime_primitives::{Permill, traits::{OnFinalise, Zero, EnsureOrigin}};
+use runtime_support::imbalance::{NegativeImbalance, OnUnbalanced};
+use runtime_primitives::{Permill, traits::{Zero, EnsureOrigin}};
 use balances::OnDilution;
 use system::ensure_signed;
 
@@ -71,29 +72,38 @@ type ProposalIndex = u32;
 decl_module! {
 	// Simple declaration of the `Module` type. Lets the macro know what its working on.
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event<T>() = default;
+
 		// Put forward a suggestion for spending. A deposit proportional to the value
 		// is reserved and slashed if the proposal is rejected. It is returned once the
 		// proposal is awarded.
-		fn propose_spend(origin, value: T::Balance, beneficiary: T::AccountId) -> Result;
+		fn propose_spend(origin, #[compact] value: T::Balance, beneficiary: T::AccountId) -> Result;
 
 		// Set the balance of funds available to spend.
-		fn set_pot(new_pot: T::Balance) -> Result;
+		fn set_pot(#[compact] new_pot: T::Balance) -> Result;
 
 		// (Re-)configure this module.
-		fn configure(proposal_bond: Permill, proposal_bond_minimum: T::Balance, spend_period: T::BlockNumber, burn: Permill) -> Result;
+		fn configure(
+			proposal_bond: Permill,
+			#[compact] proposal_bond_minimum: T::Balance,
+			#[compact] spend_period: T::BlockNumber,
+			burn: Permill
+		) -> Result;
 
 		// Reject a proposed spend. The original deposit will be slashed.
-		fn reject_proposal(origin, roposal_id: ProposalIndex) -> Result;
+		fn reject_proposal(origin, #[compact] roposal_id: ProposalIndex) -> Result;
 
 		// Approve a proposal. At a later time, the proposal will be allocated to the beneficiary
 		// and the original deposit will be returned.
-		fn approve_proposal(origin, proposal_id: ProposalIndex) -> Result;
+		fn approve_proposal(origin, #[compact] proposal_id: ProposalIndex) -> Result;
+
+		fn on_finalise(n: T::BlockNumber);
 	}
 }
 
 /// A spending proposal.
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
-#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
 pub struct Proposal<AccountId, Balance> {
 	proposer: AccountId,
 	value: Balance,
@@ -151,11 +161,6 @@ decl_event!(
 );
 
 impl<T: Trait> Module<T> {
-	/// Deposit one of this module's events.
-	fn deposit_event(event: Event<T>) {
-		<system::Module<T>>::deposit_event(<T as Trait>::Event::from(event).into());
-	}
-
 	// Implement Calls and add public immutables and private mutables.
 
 	fn propose_spend(origin: T::Origin, value: T::Balance, beneficiary: T::AccountId) -> Result {
@@ -180,7 +185,8 @@ impl<T: Trait> Module<T> {
 		let proposal = <Proposals<T>>::take(proposal_id).ok_or("No proposal at that index")?;
 
 		let value = proposal.bond;
-		let _ = <balances::Module<T>>::slash_reserved(&proposal.proposer, value);
+		let (imbalance, _) = <balances::Module<T>>::slash_reserved(&proposal.proposer, value);
+		<balances::Module<T>>::on_unbalanced(imbalance);
 
 		Ok(())
 	}
@@ -277,7 +283,15 @@ impl<T: Trait> OnDilution<T::Balance> for Module<T> {
 	}
 }
 
-impl<T: Trait> OnFinalise<T::BlockNumber> for Module<T> {
+/// Funds slashed elsewhere, e.g. from offline validators, go to the pot. They are spent like the
+/// rest of it, so the total issuance keeps accounting for them.
+impl<T: Trait> OnUnbalanced<NegativeImbalance<T::Balance>> for Module<T> {
+	fn on_unbalanced(amount: NegativeImbalance<T::Balance>) {
+		<Pot<T>>::mutate(|pot| *pot += amount.peek());
+	}
+}
+
+impl<T: Trait> Module<T> {
 	fn on_finalise(n: T::BlockNumber) {
 		// Check to see if we should spend some funds!
 		if (n % Self::spend_period()).is_zero() {
@@ -317,10 +331,10 @@ impl<T: Trait> runtime_primitives::BuildStorage for GenesisConfig<T>
 	fn build_storage(self) -> ::std::result::Result<runtime_primitives::StorageMap, String> {
 		use codec::Encode;
 		Ok(map![
-			Self::hash(<ProposalBond<T>>::key()).to_vec() => self.proposal_bond.encode(),
-			Self::hash(<ProposalBondMinimum<T>>::key()).to_vec() => self.proposal_bond_minimum.encode(),
-			Self::hash(<SpendPeriod<T>>::key()).to_vec() => self.spend_period.encode(),
-			Self::hash(<Burn<T>>::key()).to_vec() => self.burn.encode()
+			<ProposalBond<T>>::key().to_vec() => self.proposal_bond.encode(),
+			<ProposalBondMinimum<T>>::key().to_vec() => self.proposal_bond_minimum.encode(),
+			<SpendPeriod<T>>::key().to_vec() => self.spend_period.encode(),
+			<Burn<T>>::key().to_vec() => self.burn.encode()
 		])
 	}
 }
@@ -332,7 +346,7 @@ mod tests {
 	use runtime_io::with_externalities;
 	use substrate_primitives::{H256, Blake2Hasher};
 	use runtime_primitives::BuildStorage;
-	use runtime_primitives::traits::{BlakeTwo256};
+	use runtime_primitives::traits::{BlakeTwo256, OnFinalise};
 	use runtime_primitives::testing::{Digest, Header};
 
 	impl_outer_origin! {
@@ -351,6 +365,8 @@ mod tests {
 		type AccountId = u64;
 		type Header = Header;
 		type Event = ();
+		type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+		type SpecVersion = ();
 	}
 	impl balances::Trait for Test {
 		type Balance = u64;
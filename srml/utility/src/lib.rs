@@ -0,0 +1,230 @@
+// Copyright 2018 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Utility module: dispatches several calls with a single extrinsic, and calls from pseudonymous
+//! accounts derived from the sender.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "std")]
+extern crate serde;
+
+#[cfg(feature = "std")]
+#[macro_use]
+extern crate serde_derive;
+
+#[macro_use]
+extern crate parity_codec_derive;
+
+#[cfg_attr(feature = "std", macro_use)]
+extern crate sr_std as rstd;
+
+#[macro_use]
+extern crate srml_support;
+
+#[cfg(test)]
+extern crate substrate_primitives;
+#[cfg(test)]
+extern crate sr_io as runtime_io;
+#[cfg(test)]
+extern crate srml_balances as balances;
+
+extern crate parity_codec as codec;
+extern crate sr_primitives as primitives;
+extern crate srml_system as system;
+
+use rstd::prelude::*;
+use codec::{Decode, Encode};
+use primitives::traits::{Hash, MaybeSerializeDebug};
+use srml_support::{Parameter, Dispatchable};
+use srml_support::dispatch::{Result, DispatchError};
+use system::{ensure_signed, RawOrigin};
+
+/// The prefix of the data hashed into the derivative accounts.
+const DERIVATIVE_PREFIX: &[u8] = b"utility/derivative";
+
+pub trait Trait: system::Trait + Sized {
+	/// The outer call dispatch type.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin> + MaybeSerializeDebug;
+
+	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Dispatch the given calls in order, each from the origin of this call. A failed call
+		/// does not stop the following ones.
+		fn batch(origin, calls: Vec<<T as Trait>::Call>) -> Result;
+		/// Dispatch the given call from the account derived from the sender with the given index.
+		fn as_derivative(origin, index: u16, call: Box<<T as Trait>::Call>) -> Result;
+	}
+}
+
+/// Event for the utility module.
+decl_event!(
+	pub enum Event {
+		/// The call at the given index of a batch was dispatched successfully.
+		BatchItemCompleted(u32),
+		/// The call at the given index of a batch failed with the given error.
+		BatchItemFailed(u32, DispatchError),
+		/// A batch was dispatched; the numbers of its calls which completed and which failed.
+		BatchCompleted(u32, u32),
+		/// A call was dispatched successfully from the derivative account of the given index.
+		DerivativeCompleted(u16),
+		/// A call from the derivative account of the given index failed with the given error.
+		DerivativeFailed(u16, DispatchError),
+	}
+);
+
+impl<T: Trait> Module<T> {
+	/// The pseudonymous account derived from `who` with the given index.
+	pub fn derivative_account_id(who: &T::AccountId, index: u16) -> T::AccountId {
+		let mut data = DERIVATIVE_PREFIX.to_vec();
+		who.encode_to(&mut data);
+		index.encode_to(&mut data);
+		let hash = T::Hashing::hash(&data);
+		T::AccountId::decode(&mut hash.as_ref()).unwrap_or_default()
+	}
+
+	// Dispatch
+
+	fn batch(origin: T::Origin, calls: Vec<<T as Trait>::Call>) -> Result {
+		let origin: Option<RawOrigin<T::AccountId>> = origin.into();
+		let origin = origin.ok_or("bad origin: expected to be a system origin")?;
+
+		let mut failed = 0;
+		for (index, call) in calls.iter().cloned().enumerate() {
+			let event = match call.dispatch(origin.clone().into()) {
+				Ok(()) => Event::BatchItemCompleted(index as u32),
+				Err(error) => {
+					failed += 1;
+					Event::BatchItemFailed(index as u32, error)
+				},
+			};
+			Self::deposit_event(event);
+		}
+		Self::deposit_event(Event::BatchCompleted(calls.len() as u32 - failed, failed));
+		Ok(())
+	}
+
+	fn as_derivative(origin: T::Origin, index: u16, call: Box<<T as Trait>::Call>) -> Result {
+		let who = ensure_signed(origin)?;
+		let pseudonym = Self::derivative_account_id(&who, index);
+		Self::deposit_event(match call.dispatch(RawOrigin::Signed(pseudonym).into()) {
+			Ok(()) => Event::DerivativeCompleted(index),
+			Err(error) => Event::DerivativeFailed(index, error),
+		});
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use runtime_io::with_externalities;
+	use substrate_primitives::{H256, Blake2Hasher};
+	use primitives::BuildStorage;
+	use primitives::traits::BlakeTwo256;
+	use primitives::testing::{Digest, Header};
+	use balances::address::Address;
+
+	impl_outer_origin! {
+		pub enum Origin for Test {}
+	}
+
+	impl_outer_dispatch! {
+		pub enum Call where origin: Origin {
+			Balances,
+			Utility,
+		}
+	}
+
+	// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+	#[derive(Clone, Eq, PartialEq, Debug, Serialize, Deserialize)]
+	pub struct Test;
+	impl system::Trait for Test {
+		type Origin = Origin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type Digest = Digest;
+		type AccountId = u64;
+		type Header = Header;
+		type Event = ();
+		type MaximumBlockWeight = system::DefaultMaximumBlockWeight;
+		type SpecVersion = ();
+	}
+	impl balances::Trait for Test {
+		type Balance = u64;
+		type AccountIndex = u64;
+		type OnFreeBalanceZero = ();
+		type EnsureAccountLiquid = ();
+		type Event = ();
+	}
+	impl Trait for Test {
+		type Call = Call;
+		type Event = ();
+	}
+
+	type Balances = balances::Module<Test>;
+	type Utility = Module<Test>;
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap();
+		t.extend(balances::GenesisConfig::<Test>{
+			balances: vec![(1, 10), (2, 20)],
+			transaction_base_fee: 0,
+			transaction_byte_fee: 0,
+			transfer_fee: 0,
+			creation_fee: 0,
+			existential_deposit: 0,
+			reclaim_rebate: 0,
+		}.build_storage().unwrap());
+		t.into()
+	}
+
+	fn transfer(dest: u64, value: u64) -> Call {
+		Call::Balances(balances::Call::transfer(Address::Id(dest), value))
+	}
+
+	#[test]
+	fn batch_dispatches_every_call() {
+		with_externalities(&mut new_test_ext(), || {
+			assert_ok!(Utility::batch(Origin::signed(1), vec![transfer(2, 3), transfer(3, 100), transfer(3, 4)]));
+			assert_eq!(Balances::free_balance(&1), 3);
+			assert_eq!(Balances::free_balance(&2), 23);
+			assert_eq!(Balances::free_balance(&3), 4);
+		});
+	}
+
+	#[test]
+	fn derivative_accounts_call_for_their_owner() {
+		with_externalities(&mut new_test_ext(), || {
+			let pseudonym = Utility::derivative_account_id(&1, 0);
+			assert!(pseudonym != 1);
+			assert!(pseudonym != Utility::derivative_account_id(&1, 1));
+			assert!(pseudonym != Utility::derivative_account_id(&2, 0));
+
+			assert_ok!(Balances::transfer(Origin::signed(1), Address::Id(pseudonym), 5));
+			assert_ok!(Utility::as_derivative(Origin::signed(1), 0, Box::new(transfer(2, 5))));
+			assert_eq!(Balances::free_balance(&pseudonym), 0);
+			assert_eq!(Balances::free_balance(&2), 25);
+		});
+	}
+}